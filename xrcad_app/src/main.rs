@@ -1,6 +1,5 @@
 
 use bevy::prelude::*;
-use bevy::prelude::{DirectionalLight};
 
 // Camera UI state resource
 #[derive(Resource)]
@@ -10,6 +9,7 @@ struct CameraUiState {
     pub zoom_sensitivity: f32,
     pub is_xr: bool,
     pub is_stereo: bool,
+    pub pivot_mode: PivotMode,
 }
 
 impl Default for CameraUiState {
@@ -20,22 +20,53 @@ impl Default for CameraUiState {
             zoom_sensitivity: 0.5,
             is_xr: false,
             is_stereo: false,
+            pivot_mode: PivotMode::default(),
         }
     }
 }
 
-use xrcad_lib::viewport::camera_control::{CustomCameraController, camera_control_system};
+use xrcad_lib::input::keyboard::{KeyBindings, ShortcutKeys};
+use xrcad_lib::interaction::dimension_edit::DimensionEditSession;
+use xrcad_lib::interaction::rename::RenameSession;
+use xrcad_lib::interaction::selection::Selection;
+use xrcad_lib::interaction::state::{ActiveBody, UiLayout, UiPanel, apply_ui_layout};
+use xrcad_lib::interaction::transform_gizmo::TransformGizmo;
+use xrcad_lib::io::dxf::{ExportDxf, ImportDxf};
+use xrcad_lib::io::measurement_export::ExportMeasurements;
+use xrcad_lib::io::mesh_import::{ImportMesh, apply_mesh_imports};
+use xrcad_lib::io::project::{OpenProject, ProjectFile, SaveProject, handle_project_requests, with_project_extension};
+use xrcad_lib::model::jobs::{Job, StartJob};
+use xrcad_lib::model::metadata::DocumentMetadata;
+use xrcad_lib::model::properties::BodyPropertiesCollection;
+use xrcad_lib::model::units::UnitSystem;
+use xrcad_lib::net::collab::SessionRequest;
+use xrcad_lib::plugin::{DocumentSettings, XrCadPlugin, XrCadSettings};
+use xrcad_lib::render::display_mode::DisplaySettings;
+use xrcad_lib::render::ui_style::PANEL_COLOR;
+use xrcad_lib::sketch::dimension::DimensionKind;
+use xrcad_lib::sketch::sketch::{Sketch, Sketches};
+use xrcad_lib::telemetry::crash::{app_data_dir, install_panic_hook, pending_recovery, PendingRecovery};
+use xrcad_lib::telemetry::usage::UsageStats;
+use xrcad_lib::viewport::camera::{SetViewRig, ViewRig, XrSession};
+use xrcad_lib::viewport::camera_animation::{CameraAnimation, CameraAnimationButton, CameraAnimationLabel};
+use xrcad_lib::viewport::camera_control::{CustomCameraController, PivotMode, ProjectionButton, ProjectionLabel};
 
 use xrcad_lib::model::brep::topology::plane::{Plane, PlaneRenderMode};
 use nalgebra::Point3;
 
 
-use nalgebra::{Vector3};
+use nalgebra::{Vector2, Vector3};
 
 
-use xrcad_lib::{BrepModel, Vertex, Edge, Face, EdgeLoop, Workspace};
+use xrcad_lib::{BrepModel, BodyId, Vertex, Edge, Face, EdgeLoop, Workspace, Tolerance};
 
 fn main() {
+    // Write a recovery file and crash report if anything panics; a recovery
+    // file left by the last run waits for Ctrl+Shift+R (restore) or
+    // Ctrl+Shift+Delete (discard)
+    let crash_dir = app_data_dir();
+    let recovery = PendingRecovery { path: pending_recovery(&crash_dir) };
+    install_panic_hook(crash_dir);
     // Insert default camera UI state
     let camera_ui_state = CameraUiState::default();
     // --- Plane test cases ---
@@ -75,7 +106,41 @@ fn main() {
         Edge { id: 3, vertices: (3, 0) },
     ];
     let edgeloops = vec![EdgeLoop::new(1, vec![edges.iter().map(|e| e.id).collect()])];
-    let faces = edgeloops.iter().enumerate().map(|(i, l)| Face { id: i as usize, edge_loops: vec![l.id] }).collect::<Vec<Face>>();
+    let faces = edgeloops.iter().enumerate().map(|(i, l)| Face::new(i, vec![l.id])).collect::<Vec<Face>>();
+    // Demo sketch: a dimensioned right triangle on the XY plane
+    let mut sketch = Sketch::new("Sketch.001", Plane::xy());
+    let s0 = sketch.add_fixed_point(Vector2::new(150.0, 0.0));
+    let s1 = sketch.add_point(Vector2::new(250.0, 0.0));
+    let s2 = sketch.add_point(Vector2::new(150.0, 80.0));
+    let base = sketch.add_line(s0, s1);
+    let side = sketch.add_line(s0, s2);
+    sketch.add_line(s1, s2);
+    sketch.add_dimension(DimensionKind::Linear { a: s0, b: s1 }, None);
+    sketch.add_dimension(DimensionKind::Angular { a: base, b: side }, None);
+    let hole = sketch.add_circle(s0, 20.0);
+    sketch.add_dimension(DimensionKind::Radial { entity: hole }, None);
+    let mut sketches = Sketches::default();
+    sketches.active = Some(sketches.add(sketch));
+    // Usage statistics stay local and are only recorded after opting in (F4)
+    let mut usage_stats = UsageStats::default();
+    if let Err(err) = usage_stats.load() {
+        warn!("Could not load usage statistics: {}", err);
+    }
+    // Shortcuts edited in the key bindings panel (Ctrl+K) are kept next to the statistics
+    let mut key_bindings = KeyBindings::default();
+    if let Err(err) = key_bindings.load() {
+        warn!("Could not load key bindings: {}", err);
+    }
+    // Scripts given on the command line run once the app has started
+    let startup_scripts = std::env::args_os()
+        .skip(1)
+        .map(std::path::PathBuf::from)
+        .filter(|p| p.extension().is_some_and(|e| e == "rhai"))
+        .collect();
+    // --host <port> or --join <address:port> opens a shared session, as --name <name>
+    let session = session_request(&std::env::args().skip(1).collect::<Vec<_>>());
+    let mut body_properties = BodyPropertiesCollection::new();
+    body_properties.register(BodyId(0), "Body");
     App::new()
         .insert_resource(BrepModel {
             vertices,
@@ -83,102 +148,272 @@ fn main() {
             edgeloops,
             faces,
             selected_vertex: None,
+            tolerance: Tolerance::default(),
+            ..default()
         })
         .insert_resource(workspace)
-        .add_plugins(DefaultPlugins)
+        .insert_resource(body_properties)
+        .insert_resource(ActiveBody(Some(BodyId(0))))
+        .insert_resource(sketches)
+        .insert_resource(usage_stats)
+        .insert_resource(key_bindings)
+        .insert_resource(recovery)
         .insert_resource(camera_ui_state)
-        .add_systems(Update, camera_control_system)
-        .add_systems(Startup, (setup, setup_ui))
+        .add_plugins(DefaultPlugins)
+        .add_plugins(XrCadPlugin { settings: XrCadSettings { document: DocumentSettings { startup_scripts, session, ..default() }, ..default() } })
+        .add_systems(Startup, setup_ui)
         .add_systems(Update, update_ui_panel)
-        .add_systems(Update, camera_ui_panel)
-        .add_systems(Update, BrepModel::render)
-        .add_systems(Update, BrepModel::vertex_drag)
-        .add_systems(Update, Workspace::workspace_render_system)
+        .add_systems(Update, panel_keys.in_set(ShortcutKeys).before(apply_ui_layout))
+        .add_systems(Update, camera_ui_panel.in_set(ShortcutKeys))
+        .add_systems(Update, project_file_keys.in_set(ShortcutKeys).before(handle_project_requests))
+        .add_systems(Update, exchange_file_keys.in_set(ShortcutKeys).before(apply_mesh_imports))
         .run();
 }
 
+// Shared session asked for on the command line
+fn session_request(args: &[String]) -> Option<SessionRequest> {
+    let value = |flag: &str| args.iter().position(|a| a == flag).and_then(|i| args.get(i + 1)).cloned();
+    let name = value("--name").unwrap_or_else(|| std::env::var("USER").unwrap_or_else(|_| "Guest".to_string()));
+    if let Some(port) = value("--host") {
+        match port.parse() {
+            Ok(port) => return Some(SessionRequest::Host { port, name }),
+            Err(_) => warn!("Not a port: {}", port),
+        }
+    }
+    value("--join").map(|address| SessionRequest::Join { address, name })
+}
+
+// The bound keys show or hide the lighting panel (F9), the outliner (F10), saved views (F12) and analysis (Shift+F12)
+fn panel_keys(keyboard: Res<ButtonInput<KeyCode>>, bindings: Res<KeyBindings>, mut layout: ResMut<UiLayout>) {
+    let panels = [("lighting_panel", "lighting"), ("outliner_panel", "outliner"), ("views_panel", "views"), ("analysis_panel", "analysis")];
+    for (action, panel) in panels {
+        if bindings.just_pressed(action, &keyboard) {
+            layout.toggle_panel(panel);
+        }
+    }
+}
+
+// Save (Ctrl+S, asking for a file the first time), save as (Ctrl+Shift+S) and open (Ctrl+O)
+fn project_file_keys(
+    (keyboard, bindings): (Res<ButtonInput<KeyCode>>, Res<KeyBindings>),
+    project: Res<ProjectFile>,
+    metadata: Res<DocumentMetadata>,
+    mut saves: EventWriter<SaveProject>,
+    mut opens: EventWriter<OpenProject>,
+) {
+    let save_as = bindings.just_pressed("save_as", &keyboard);
+    if save_as || bindings.just_pressed("save", &keyboard) {
+        let path = match &project.path {
+            Some(path) if !save_as => Some(path.clone()),
+            _ => prompt_save_path(&metadata.title),
+        };
+        if let Some(path) = path {
+            saves.write(SaveProject { path: with_project_extension(path) });
+        }
+    }
+    if bindings.just_pressed("open", &keyboard)
+        && let Some(path) = prompt_open_path()
+    {
+        opens.write(OpenProject { path });
+    }
+}
+
+// Import (Ctrl+I) an STL or OBJ mesh, a DXF drawing or a STEP file, export (Ctrl+E) the bodies as
+// STEP, glTF or 3MF, export the active sketch as DXF (Ctrl+D) and measurements and dimensions as
+// CSV or JSON (Ctrl+R). STEP files and body exports run as background jobs.
+fn exchange_file_keys(
+    (keyboard, bindings): (Res<ButtonInput<KeyCode>>, Res<KeyBindings>),
+    metadata: Res<DocumentMetadata>,
+    mut imports: EventWriter<ImportMesh>,
+    (mut dxf_imports, mut dxf_exports): (EventWriter<ImportDxf>, EventWriter<ExportDxf>),
+    mut measurement_exports: EventWriter<ExportMeasurements>,
+    mut jobs: EventWriter<StartJob>,
+) {
+    if bindings.just_pressed("import", &keyboard)
+        && let Some(path) = prompt_import_path()
+    {
+        let ext = path.extension().map(|e| e.to_string_lossy().to_ascii_lowercase()).unwrap_or_default();
+        match ext.as_str() {
+            "dxf" => {
+                dxf_imports.write(ImportDxf { path });
+            }
+            "step" | "stp" => {
+                jobs.write(StartJob(Job::ImportStep(path)));
+            }
+            _ => {
+                imports.write(ImportMesh { path });
+            }
+        }
+    }
+    if bindings.just_pressed("export", &keyboard)
+        && let Some(path) = prompt_export_path(&metadata.title)
+    {
+        jobs.write(StartJob(Job::Export(path)));
+    }
+    if bindings.just_pressed("export_dxf", &keyboard)
+        && let Some(path) = prompt_dxf_path(&metadata.title)
+    {
+        dxf_exports.write(ExportDxf { path: path.with_extension("dxf") });
+    }
+    if bindings.just_pressed("export_measurements", &keyboard)
+        && let Some(path) = prompt_measurements_path(&metadata.title)
+    {
+        measurement_exports.write(ExportMeasurements { path });
+    }
+}
+
+#[cfg(feature = "file-dialog")]
+fn prompt_save_path(title: &str) -> Option<std::path::PathBuf> {
+    rfd::FileDialog::new()
+        .add_filter("xrcad project", &["xrcad"])
+        .set_file_name(format!("{}.xrcad", title))
+        .save_file()
+}
+
+#[cfg(feature = "file-dialog")]
+fn prompt_open_path() -> Option<std::path::PathBuf> {
+    rfd::FileDialog::new().add_filter("xrcad project", &["xrcad"]).pick_file()
+}
+
+#[cfg(feature = "file-dialog")]
+fn prompt_import_path() -> Option<std::path::PathBuf> {
+    rfd::FileDialog::new().add_filter("Mesh, drawing or STEP", &["stl", "obj", "dxf", "step", "stp"]).pick_file()
+}
+
+#[cfg(feature = "file-dialog")]
+fn prompt_export_path(title: &str) -> Option<std::path::PathBuf> {
+    rfd::FileDialog::new()
+        .add_filter("STEP", &["step", "stp"])
+        .add_filter("glTF", &["glb", "gltf"])
+        .add_filter("3MF", &["3mf"])
+        .set_file_name(format!("{}.step", title))
+        .save_file()
+}
+
+#[cfg(feature = "file-dialog")]
+fn prompt_dxf_path(title: &str) -> Option<std::path::PathBuf> {
+    rfd::FileDialog::new().add_filter("DXF drawing", &["dxf"]).set_file_name(format!("{}.dxf", title)).save_file()
+}
+
+#[cfg(feature = "file-dialog")]
+fn prompt_measurements_path(title: &str) -> Option<std::path::PathBuf> {
+    rfd::FileDialog::new()
+        .add_filter("CSV", &["csv"])
+        .add_filter("JSON", &["json"])
+        .set_file_name(format!("{} measurements.csv", title))
+        .save_file()
+}
+
+// Without native dialogs, save next to the working directory under the document title
+#[cfg(not(feature = "file-dialog"))]
+fn prompt_save_path(title: &str) -> Option<std::path::PathBuf> {
+    Some(std::path::PathBuf::from(title))
+}
+
+#[cfg(not(feature = "file-dialog"))]
+fn prompt_open_path() -> Option<std::path::PathBuf> {
+    warn!("Opening projects needs the file-dialog feature");
+    None
+}
+
+#[cfg(not(feature = "file-dialog"))]
+fn prompt_import_path() -> Option<std::path::PathBuf> {
+    warn!("Importing meshes, drawings and STEP files needs the file-dialog feature");
+    None
+}
+
+#[cfg(not(feature = "file-dialog"))]
+fn prompt_export_path(title: &str) -> Option<std::path::PathBuf> {
+    Some(std::path::PathBuf::from(format!("{}.step", title)))
+}
+
+#[cfg(not(feature = "file-dialog"))]
+fn prompt_dxf_path(title: &str) -> Option<std::path::PathBuf> {
+    Some(std::path::PathBuf::from(title))
+}
+
+#[cfg(not(feature = "file-dialog"))]
+fn prompt_measurements_path(title: &str) -> Option<std::path::PathBuf> {
+    Some(std::path::PathBuf::from(format!("{} measurements.csv", title)))
+}
+
 // Camera UI panel system (Bevy UI only)
 fn camera_ui_panel(
     mut ui_state: ResMut<CameraUiState>,
     mut text_query: Query<&mut Text, With<CameraPanelText>>,
     mut camera_query: Query<&mut CustomCameraController>,
-    keyboard: Res<ButtonInput<KeyCode>>,
+    (keyboard, bindings): (Res<ButtonInput<KeyCode>>, Res<KeyBindings>),
+    (animation, display, session): (Res<CameraAnimation>, Res<DisplaySettings>, Res<XrSession>),
+    mut rigs: EventWriter<SetViewRig>,
 ) {
-    // Adjust camera parameters with keys (Bevy 0.13+ KeyCode)
-    if keyboard.just_pressed(KeyCode::KeyP) {
+    // Adjust camera parameters with the bound keys
+    let pressed = |action| bindings.just_pressed(action, &keyboard);
+    if pressed("pan_sensitivity_up") {
         ui_state.pan_sensitivity += 0.1;
     }
-    if keyboard.just_pressed(KeyCode::KeyO) {
+    if pressed("pan_sensitivity_down") {
         ui_state.pan_sensitivity -= 0.1;
     }
-    if keyboard.just_pressed(KeyCode::KeyT) {
+    if pressed("rotate_sensitivity_up") {
         ui_state.rotate_sensitivity += 0.1;
     }
-    if keyboard.just_pressed(KeyCode::KeyY) {
+    if pressed("rotate_sensitivity_down") {
         ui_state.rotate_sensitivity -= 0.1;
     }
-    if keyboard.just_pressed(KeyCode::KeyZ) {
+    if pressed("zoom_sensitivity_up") {
         ui_state.zoom_sensitivity += 0.1;
     }
-    if keyboard.just_pressed(KeyCode::KeyX) {
+    if pressed("zoom_sensitivity_down") {
         ui_state.zoom_sensitivity -= 0.1;
     }
-    if keyboard.just_pressed(KeyCode::F1) {
+    // The XR rig needs a running XR session; it falls back to the desktop when the session ends
+    let (mut toggle_xr, toggle_stereo) = (pressed("toggle_xr"), pressed("toggle_stereo"));
+    if toggle_xr && !ui_state.is_xr && !session.running {
+        info!("No XR session is running");
+        toggle_xr = false;
+    }
+    if ui_state.is_xr && !session.running {
+        toggle_xr = true;
+    }
+    if toggle_xr {
         ui_state.is_xr = !ui_state.is_xr;
     }
-    if keyboard.just_pressed(KeyCode::F2) {
+    if toggle_stereo {
         ui_state.is_stereo = !ui_state.is_stereo;
     }
+    if toggle_xr || toggle_stereo {
+        let rig = if ui_state.is_xr { ViewRig::Xr { stereo: ui_state.is_stereo } } else { ViewRig::Desktop };
+        rigs.write(SetViewRig(rig));
+    }
+    if pressed("cycle_pivot") {
+        ui_state.pivot_mode = ui_state.pivot_mode.next();
+    }
     // Update camera controller with new sensitivities
     for mut cam in camera_query.iter_mut() {
         cam.pan_sensitivity = ui_state.pan_sensitivity;
         cam.rotate_sensitivity = ui_state.rotate_sensitivity;
         cam.zoom_sensitivity = ui_state.zoom_sensitivity;
-        cam.is_xr = ui_state.is_xr;
-        cam.is_stereo = ui_state.is_stereo;
+        cam.pivot_mode = ui_state.pivot_mode;
     }
     // Update UI text panel with camera info
     if let Some(mut text) = text_query.iter_mut().next() {
         let mut content = String::from("Camera Controls:\n");
-        content.push_str(&format!("Pan Sensitivity: {:.2} (P/O)\n", ui_state.pan_sensitivity));
-        content.push_str(&format!("Rotate Sensitivity: {:.2} (T/Y)\n", ui_state.rotate_sensitivity));
-        content.push_str(&format!("Zoom Sensitivity: {:.2} (Z/X)\n", ui_state.zoom_sensitivity));
-        content.push_str(&format!("XR Enabled: {} (F1)\n", ui_state.is_xr));
-        content.push_str(&format!("Stereo Enabled: {} (F2)\n", ui_state.is_stereo));
+        let keys = |up, down| format!("{}/{}", bindings.label(up), bindings.label(down));
+        content.push_str(&format!("Pan Sensitivity: {:.2} ({})\n", ui_state.pan_sensitivity, keys("pan_sensitivity_up", "pan_sensitivity_down")));
+        content.push_str(&format!("Rotate Sensitivity: {:.2} ({})\n", ui_state.rotate_sensitivity, keys("rotate_sensitivity_up", "rotate_sensitivity_down")));
+        content.push_str(&format!("Zoom Sensitivity: {:.2} ({})\n", ui_state.zoom_sensitivity, keys("zoom_sensitivity_up", "zoom_sensitivity_down")));
+        content.push_str(&format!("XR Enabled: {} ({})\n", ui_state.is_xr, bindings.label("toggle_xr")));
+        content.push_str(&format!("Stereo Enabled: {} ({})\n", ui_state.is_stereo, bindings.label("toggle_stereo")));
+        content.push_str(&format!("Orbit Pivot: {} ({})\n", ui_state.pivot_mode.label(), bindings.label("cycle_pivot")));
+        if let Some(cam) = camera_query.iter().next() {
+            content.push_str(&format!("Projection: {} ({})\n", cam.projection.label(), bindings.label("toggle_projection")));
+        }
+        content.push_str(&format!("Presentation: {} ({} play, {} mode)\n", animation.mode.label(), bindings.label("play_animation"), bindings.label("animation_mode")));
+        content.push_str(&format!("Display: {} ({} all, {} selection)\n", display.mode.label(), bindings.label("display_mode"), bindings.label("body_display_mode")));
         text.0 = content;
     }
 }
 
-fn setup(
-    mut commands: Commands,
-    // mut meshes: ResMut<Assets<Mesh>>,
-    // mut materials: ResMut<Assets<StandardMaterial>>,
-) {
-    // Spawn camera
-    commands.spawn((
-        // Camera3d::default(),
-        Camera3d {
-            screen_space_specular_transmission_steps: 0,
-            ..Default::default()
-        },
-            
-        Transform::from_xyz(-500.0, 500.0, 500.0).looking_at(Vec3::ZERO, Vec3::Y),
-        GlobalTransform::default(),
-        CustomCameraController::default(),
-    ));
-
-    // Spawn a directional light so objects are visible
-    commands.spawn((
-        DirectionalLight {
-            illuminance: 10000.0,
-            shadows_enabled: true,
-            ..default()
-        },
-        Transform::from_xyz(0.0, 1000.0, 1000.0).looking_at(Vec3::ZERO, Vec3::Y),
-    ));
-
-}
-
 #[derive(Component)]
 struct ControlsPanel;
 
@@ -192,8 +427,9 @@ fn setup_ui(mut commands: Commands) {
     // BREP panel (top left)
     commands.spawn((
         Node::default(),
-        BackgroundColor(Color::srgb(0.1, 0.1, 0.15)),
+        BackgroundColor(PANEL_COLOR),
         ControlsPanel,
+        UiPanel("controls"),
     ))
     .with_children(|parent| {
         parent.spawn((
@@ -207,22 +443,73 @@ fn setup_ui(mut commands: Commands) {
         Node::default(),
         BackgroundColor(Color::srgb(0.15, 0.1, 0.1)),
         ControlsPanel,
+        UiPanel("camera"),
     ))
     .with_children(|parent| {
         parent.spawn((
             Text::new("Camera Controls\n"),
             CameraPanelText,
         ));
+        parent.spawn((
+            Button,
+            Node { padding: UiRect::all(Val::Px(4.0)), ..default() },
+            BackgroundColor(Color::srgb(0.25, 0.15, 0.15)),
+            ProjectionButton,
+        ))
+        .with_child((Text::new("Perspective"), ProjectionLabel));
+        parent.spawn((
+            Button,
+            Node { padding: UiRect::all(Val::Px(4.0)), ..default() },
+            BackgroundColor(Color::srgb(0.25, 0.15, 0.15)),
+            CameraAnimationButton,
+        ))
+        .with_child((Text::new("Play Turntable"), CameraAnimationLabel));
     });
 }
 
 
 fn update_ui_panel(
     brep: Res<BrepModel>,
+    properties: Res<BodyPropertiesCollection>,
+    active: Res<ActiveBody>,
+    (rename, dimension_edit): (Res<RenameSession>, Res<DimensionEditSession>),
+    (sketches, units, selection): (Res<Sketches>, Res<UnitSystem>, Res<Selection>),
+    (usage, gizmo, bindings): (Res<UsageStats>, Res<TransformGizmo>, Res<KeyBindings>),
     mut query: Query<&mut Text, With<BrepPanelText>>,
 ) {
     if let Ok(mut text) = query.single_mut() {
-        let mut content = String::from("BREP Controls\n\nVertices:\n");
+        let mut content = String::from("BREP Controls\n\n");
+        if rename.is_active() {
+            content.push_str(&format!("Rename: {}_ (Enter/Esc)\n", rename.buffer));
+        } else if let Some(props) = active.0.and_then(|id| properties.get(id)) {
+            content.push_str(&format!("Body: {} ({} to rename)\n", props.name, bindings.label("rename_body")));
+        }
+        if let Some(sketch) = sketches.active() {
+            content.push_str(&format!("\nSketch: {} ({} select, {} edit)\n", sketch.name, bindings.label("next_dimension"), bindings.label("confirm")));
+            for dim in &sketch.dimensions {
+                let marker = if dimension_edit.selected == Some(dim.id) { ">" } else { " " };
+                if dimension_edit.is_active() && dimension_edit.selected == Some(dim.id) {
+                    content.push_str(&format!("{} {}: {}_ (Enter/Esc)\n", marker, dim.id, dimension_edit.buffer));
+                } else {
+                    content.push_str(&format!("{} {}: {}\n", marker, dim.id, dim.label_in(&units)));
+                }
+            }
+        }
+        content.push_str(&format!("\nUnits: {} ({})\n", units.length.symbol(), bindings.label("cycle_units")));
+        content.push_str(&format!("Select: {} ({}), {} selected\n", selection.filter.label(), bindings.label("selection_filter"), selection.items.len()));
+        content.push_str(&format!("Gizmo: {} ({})", gizmo.mode.label(), bindings.label("gizmo_mode")));
+        match gizmo.constraint {
+            Some(constraint) if gizmo.is_entering() => content.push_str(&format!(" {}: {}_ (Enter/Esc)\n", constraint.label(), gizmo.buffer)),
+            Some(constraint) => content.push_str(&format!(" {} (type a value)\n", constraint.label())),
+            None => content.push('\n'),
+        }
+        content.push_str(&format!("\nUsage stats: {} ({})\n", if usage.enabled { "on" } else { "off" }, bindings.label("usage_stats")));
+        if usage.enabled {
+            for (name, stats) in usage.most_used(5) {
+                content.push_str(&format!("  {}: {}x, avg {:.1} ms\n", name, stats.count, stats.mean().as_secs_f64() * 1000.0));
+            }
+        }
+        content.push_str("\nVertices:\n");
         for v in &brep.vertices {
             content.push_str(&format!("{}: ({:.1}, {:.1}, {:.1})\n", v.id, v.position.x, v.position.y, v.position.z));
         }
@@ -0,0 +1,347 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: batch
+//!
+//! One document worked on without a window: opened from a project or imported
+//! from a STEP, STL or OBJ file, changed by script operations and exported by
+//! file extension. Scripts hold one operation per line; `#` starts a comment.
+//!
+//! ```text
+//! box <x> <y> <z> [at <x> <y> <z>]
+//! cylinder <radius> <height> [at <x> <y> <z>]
+//! union | subtract | intersect <body> <body>
+//! rename <body> <name>
+//! title <text>
+//! units mm | cm | m | in
+//! export <path>
+//! ```
+//!
+//! Lengths are in the document unit unless they carry their own (`2in`).
+//! Bodies are given by name or by number, counting from 1 in shell order.
+//! Rhai scripts (`.rhai`) run with the library's scripting API instead.
+
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+use nalgebra::{Point3, Vector3};
+use xrcad_lib::io::gltf::save_gltf;
+use xrcad_lib::io::mesh_import::{load_mesh, MeshImportError};
+use xrcad_lib::io::project::{ProjectDocument, ProjectError};
+use xrcad_lib::io::step::{save_step, StepError};
+use xrcad_lib::io::step_import::load_step;
+use xrcad_lib::io::svg::save_projection_svg;
+use xrcad_lib::io::three_mf::save_3mf;
+use xrcad_lib::model::brep::operations::boolean::BooleanOp;
+use xrcad_lib::model::brep::placement::PlacementFrame;
+use xrcad_lib::model::brep::topology::plane::Plane;
+use xrcad_lib::model::command::{execute, ModelCommand};
+use xrcad_lib::model::feature_tree::FeatureKind;
+use xrcad_lib::model::material::Material;
+use xrcad_lib::model::metadata::DocumentMetadata;
+use xrcad_lib::model::units::LengthUnit;
+use xrcad_lib::scripting::engine::{run_script, ScriptDocument, ScriptError};
+use xrcad_lib::{BodyId, BrepModel};
+
+/// Facets of a scripted cylinder
+const CYLINDER_SEGMENTS: usize = 32;
+
+/// Why a batch step failed
+#[derive(Debug)]
+pub enum BatchError {
+    Io(io::Error),
+    Project(ProjectError),
+    Step(StepError),
+    Mesh(MeshImportError),
+    /// No reader or writer for this file extension
+    UnsupportedFormat(String),
+    /// A script operation failed, at this line (from 1)
+    Script { line: usize, message: String },
+    Rhai(ScriptError),
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BatchError::Io(err) => write!(f, "{}", err),
+            BatchError::Project(err) => write!(f, "{}", err),
+            BatchError::Step(err) => write!(f, "{}", err),
+            BatchError::Mesh(err) => write!(f, "{}", err),
+            BatchError::UnsupportedFormat(ext) => write!(f, "unsupported file format: .{}", ext),
+            BatchError::Script { line, message } => write!(f, "line {}: {}", line, message),
+            BatchError::Rhai(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for BatchError {}
+
+impl From<io::Error> for BatchError {
+    fn from(err: io::Error) -> Self {
+        BatchError::Io(err)
+    }
+}
+
+impl From<ProjectError> for BatchError {
+    fn from(err: ProjectError) -> Self {
+        BatchError::Project(err)
+    }
+}
+
+impl From<StepError> for BatchError {
+    fn from(err: StepError) -> Self {
+        BatchError::Step(err)
+    }
+}
+
+impl From<MeshImportError> for BatchError {
+    fn from(err: MeshImportError) -> Self {
+        BatchError::Mesh(err)
+    }
+}
+
+/// Lowercase extension of a path
+pub fn extension(path: &Path) -> String {
+    path.extension().map(|e| e.to_string_lossy().to_ascii_lowercase()).unwrap_or_default()
+}
+
+/// The document being processed, with a log of what was done to it
+#[derive(Debug, Default)]
+pub struct Batch {
+    pub doc: ProjectDocument,
+    pub log: Vec<String>,
+}
+
+impl Batch {
+    /// Empty document
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open a project, or import a STEP, STL or OBJ file into a new document
+    /// titled after the file
+    pub fn open(path: &Path) -> Result<Self, BatchError> {
+        let ext = extension(path);
+        if ext == "xrcad" {
+            let doc = ProjectDocument::load(path)?;
+            let log = vec![format!("Opened {}", path.display())];
+            return Ok(Self { doc, log });
+        }
+        let title = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        let mut batch = Self { doc: ProjectDocument { metadata: DocumentMetadata::new(title), ..Default::default() }, log: Vec::new() };
+        match ext.as_str() {
+            "step" | "stp" => {
+                let import = load_step(path)?;
+                for (name, solid) in &import.solids {
+                    batch.add_body(solid, name);
+                }
+                batch.log.push(format!("Imported {}: {}", path.display(), import.report));
+            }
+            "stl" | "obj" => {
+                let mesh = load_mesh(path, batch.doc.units.length)?;
+                let shells = mesh.reconstruct_shells();
+                for shell in &shells {
+                    batch.add_body(shell, &mesh.name);
+                }
+                batch.log.push(format!("Imported {}: {} closed shell(s)", path.display(), shells.len()));
+                if !mesh.is_closed() {
+                    batch.log.push(format!("{} is not closed; kept as a reference mesh", mesh.name));
+                    batch.doc.meshes.add(mesh);
+                }
+            }
+            _ => return Err(BatchError::UnsupportedFormat(ext)),
+        }
+        Ok(batch)
+    }
+
+    /// Add a body to the model under a generated name based on `base_name`
+    fn add_body(&mut self, brep: &BrepModel, base_name: &str) {
+        let command = ModelCommand::AddBody { name: base_name.into(), body: brep.clone() };
+        if let Err(err) = self.command(command) {
+            self.log.push(format!("Could not add {}: {}", base_name, err));
+        }
+    }
+
+    /// Every body as its name, topology and material
+    pub fn bodies(&self) -> Vec<(String, BrepModel, Material)> {
+        let model = &self.doc.model;
+        model
+            .bodies()
+            .into_iter()
+            .map(|(id, shell)| {
+                let props = self.doc.properties.get(id);
+                let name = props.map_or_else(|| format!("Body {}", id.0 + 1), |p| p.name.clone());
+                (name, model.extract_faces(&shell), props.map(|p| p.material.clone()).unwrap_or_default())
+            })
+            .collect()
+    }
+
+    /// Write the document in the format of the path's extension: a project,
+    /// STEP, glTF, 3MF, or a top view SVG
+    pub fn export(&mut self, path: &Path) -> Result<(), BatchError> {
+        let bodies = self.bodies();
+        let doc = &self.doc;
+        match extension(path).as_str() {
+            "xrcad" => doc.save(path)?,
+            "step" | "stp" => save_step(path, &doc.metadata, &bodies.iter().map(|(n, b, _)| (n.as_str(), b)).collect::<Vec<_>>())?,
+            "gltf" | "glb" => save_gltf(path, &doc.metadata, &bodies.iter().map(|(n, b, m)| (n.as_str(), b, m)).collect::<Vec<_>>())?,
+            "3mf" => save_3mf(path, &doc.metadata, &bodies.iter().map(|(n, b, m)| (n.as_str(), b, m)).collect::<Vec<_>>())?,
+            "svg" => save_projection_svg(path, &doc.model, &Plane::xy())?,
+            ext => return Err(BatchError::UnsupportedFormat(ext.to_string())),
+        }
+        self.log.push(format!("Exported {} body(ies) to {}", bodies.len(), path.display()));
+        Ok(())
+    }
+
+    /// Run every operation of a script, stopping at the first failure
+    pub fn run(&mut self, script: &str) -> Result<(), BatchError> {
+        for (index, line) in script.lines().enumerate() {
+            let op = line.split('#').next().unwrap_or_default().trim();
+            if op.is_empty() {
+                continue;
+            }
+            self.apply(op).map_err(|message| BatchError::Script { line: index + 1, message })?;
+        }
+        Ok(())
+    }
+
+    /// Run a Rhai script; the document is only changed if all of it succeeds
+    pub fn run_rhai(&mut self, source: &str) -> Result<(), BatchError> {
+        let doc = &mut self.doc;
+        let mut script = ScriptDocument { model: doc.model.clone(), features: doc.features.clone(), properties: doc.properties.clone() };
+        let outcome = run_script(source, &mut script).map_err(BatchError::Rhai)?;
+        (doc.model, doc.features, doc.properties) = (script.model, script.features, script.properties);
+        self.log.extend(outcome.output);
+        self.log.push(format!("Ran {} command(s)", outcome.commands.len()));
+        Ok(())
+    }
+
+    /// Run one operation
+    pub fn apply(&mut self, op: &str) -> Result<(), String> {
+        let words: Vec<&str> = op.split_whitespace().collect();
+        let Some((&command, args)) = words.split_first() else { return Ok(()) };
+        match command {
+            "box" => {
+                let (size, placement) = self.primitive_args(args, 3)?;
+                self.command(ModelCommand::CreatePrimitive(FeatureKind::Box { size: Vector3::new(size[0], size[1], size[2]), placement }))
+            }
+            "cylinder" => {
+                let (size, placement) = self.primitive_args(args, 2)?;
+                let (radius, height) = (size[0], size[1]);
+                self.command(ModelCommand::CreatePrimitive(FeatureKind::Cylinder {
+                    bottom_radius: radius,
+                    top_radius: radius,
+                    height,
+                    segments: CYLINDER_SEGMENTS,
+                    placement,
+                }))
+            }
+            "union" | "subtract" | "intersect" => {
+                let op = match command {
+                    "union" => BooleanOp::Union,
+                    "subtract" => BooleanOp::Subtract,
+                    _ => BooleanOp::Intersect,
+                };
+                let [target, tool] = args else { return Err(format!("usage: {} <body> <body>", command)) };
+                let (target, tool) = (self.body(target)?, self.body(tool)?);
+                self.command(ModelCommand::Boolean { op, target, tool })?;
+                self.log.push(format!("{} {} {}", op.label(), args[0], args[1]));
+                Ok(())
+            }
+            "rename" => {
+                let [body, name @ ..] = args else { return Err("usage: rename <body> <name>".into()) };
+                let body = self.body(body)?;
+                self.command(ModelCommand::RenameBody { body, name: name.join(" ") })
+            }
+            "title" => {
+                self.doc.metadata.title = args.join(" ");
+                Ok(())
+            }
+            "units" => {
+                let [symbol] = args else { return Err("usage: units mm | cm | m | in".into()) };
+                let unit = LengthUnit::ALL.into_iter().find(|u| u.symbol() == *symbol).ok_or_else(|| format!("unknown unit {}", symbol))?;
+                self.doc.units.length = unit;
+                Ok(())
+            }
+            "export" => {
+                let path = args.join(" ");
+                self.export(Path::new(&path)).map_err(|e| e.to_string())
+            }
+            _ => Err(format!("unknown operation {}", command)),
+        }
+    }
+
+    /// `count` lengths, optionally followed by `at <x> <y> <z>` for the base point
+    fn primitive_args(&self, args: &[&str], count: usize) -> Result<(Vec<f64>, Option<PlacementFrame>), String> {
+        let (sizes, at) = args.split_at(count.min(args.len()));
+        let lengths = |words: &[&str]| -> Result<Vec<f64>, String> {
+            words.iter().map(|w| self.doc.units.parse_length(w).ok_or_else(|| format!("invalid length {}", w))).collect()
+        };
+        let size = lengths(sizes)?;
+        if size.len() != count {
+            return Err(format!("expected {} lengths", count));
+        }
+        let placement = match at {
+            [] => None,
+            ["at", x, y, z] => {
+                let p = lengths(&[*x, *y, *z])?;
+                Some(PlacementFrame::new(Point3::new(p[0], p[1], p[2]), Vector3::z()))
+            }
+            _ => return Err("expected `at <x> <y> <z>`".into()),
+        };
+        Ok((size, placement))
+    }
+
+    /// Apply a model command to the document
+    fn command(&mut self, command: ModelCommand) -> Result<(), String> {
+        let doc = &mut self.doc;
+        execute(&command, &mut doc.model, &mut doc.features, &mut doc.properties).map_err(|e| e.to_string())
+    }
+
+    /// Body given by name or by its place in the model, counting from 1
+    fn body(&self, reference: &str) -> Result<BodyId, String> {
+        let ids = self.doc.model.body_ids();
+        if let Ok(n) = reference.parse::<usize>() {
+            return n.checked_sub(1).and_then(|i| ids.get(i).copied()).ok_or_else(|| format!("no body {} (of {})", n, ids.len()));
+        }
+        self.doc.properties.iter().find(|(_, p)| p.name == reference).map(|(id, _)| *id).ok_or_else(|| format!("no body named {}", reference))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script_builds_and_combines_bodies() {
+        let mut batch = Batch::new();
+        let script = "units cm\n# plate with a post\nbox 4 4 1\ncylinder 0.5 3 at 0 0 0\nunion Box.001 2\nrename 1 Plate\n";
+        batch.run(script).unwrap();
+        let bodies = batch.bodies();
+        assert_eq!(bodies.len(), 1);
+        assert_eq!(bodies[0].0, "Plate");
+        assert_eq!(batch.doc.features.features.len(), 3);
+
+        let err = batch.run("box 1 1\n").unwrap_err();
+        assert!(matches!(err, BatchError::Script { line: 1, .. }));
+        assert!(batch.apply("subtract 1 7").is_err());
+        assert!(batch.apply("extrude 1").is_err());
+    }
+
+    #[test]
+    fn test_rhai_scripts_change_the_document_only_on_success() {
+        let mut batch = Batch::new();
+        batch.run_rhai("box(10, 10, 10);\nprint(body_count());").unwrap();
+        assert_eq!(batch.log, ["1", "Ran 1 command(s)"]);
+        assert!(matches!(batch.run_rhai("box(1, 1, 1);\ndelete(5);"), Err(BatchError::Rhai(_))));
+        assert_eq!(batch.bodies().len(), 1);
+    }
+
+    #[test]
+    fn test_unknown_formats_are_rejected() {
+        assert!(matches!(Batch::open(Path::new("part.iges")), Err(BatchError::UnsupportedFormat(ext)) if ext == "iges"));
+        let mut batch = Batch::new();
+        assert!(matches!(batch.export(Path::new("part.dwg")), Err(BatchError::UnsupportedFormat(_))));
+    }
+}
@@ -0,0 +1,108 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! xrcad command-line batch converter. Opens or imports one document, runs
+//! script operations on it and exports it, without a window or GPU:
+//!
+//! ```text
+//! xrcad_cli part.step -o part.glb -o part.3mf
+//! xrcad_cli -e "box 40 40 10" -e "cylinder 5 30" -e "subtract 1 2" -o plate.xrcad
+//! xrcad_cli bracket.xrcad -s finish.txt -o bracket.step
+//! xrcad_cli -s gear.rhai -o gear.3mf
+//! ```
+
+mod batch;
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use batch::{Batch, BatchError};
+
+const USAGE: &str = "usage: xrcad_cli [INPUT] [-e OPERATION]... [-s SCRIPT]... [-o OUTPUT]... [-q]
+
+  INPUT         .xrcad project, or .step/.stp, .stl or .obj file to import
+  -e OPERATION  run one script operation
+  -s SCRIPT     run the operations of a script file, one per line, or a .rhai script
+  -o OUTPUT     export to .xrcad, .step/.stp, .gltf/.glb, .3mf or .svg
+  -q            only report errors
+
+Operations and scripts run in the order given; outputs are written after them.";
+
+/// A script step from the command line
+enum Step {
+    Operation(String),
+    Script(PathBuf),
+}
+
+/// Parsed command line
+#[derive(Default)]
+struct Args {
+    input: Option<PathBuf>,
+    steps: Vec<Step>,
+    outputs: Vec<PathBuf>,
+    quiet: bool,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut parsed = Args::default();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+        match arg.as_str() {
+            "-e" => parsed.steps.push(Step::Operation(value()?)),
+            "-s" => parsed.steps.push(Step::Script(value()?.into())),
+            "-o" => parsed.outputs.push(value()?.into()),
+            "-q" => parsed.quiet = true,
+            "-h" | "--help" => return Err(String::new()),
+            flag if flag.starts_with('-') => return Err(format!("unknown option {}", flag)),
+            _ if parsed.input.is_none() => parsed.input = Some(PathBuf::from(&arg)),
+            _ => return Err(format!("more than one input: {}", arg)),
+        }
+    }
+    Ok(parsed)
+}
+
+fn run(args: &Args) -> Result<Batch, BatchError> {
+    let mut batch = match &args.input {
+        Some(path) => Batch::open(path)?,
+        None => Batch::new(),
+    };
+    for step in &args.steps {
+        match step {
+            Step::Operation(op) => batch.run(op)?,
+            Step::Script(path) if batch::extension(path) == "rhai" => batch.run_rhai(&fs::read_to_string(path)?)?,
+            Step::Script(path) => batch.run(&fs::read_to_string(path)?)?,
+        }
+    }
+    for output in &args.outputs {
+        batch.export(output)?;
+    }
+    Ok(batch)
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(message) => {
+            if !message.is_empty() {
+                eprintln!("xrcad_cli: {}", message);
+            }
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    match run(&args) {
+        Ok(batch) => {
+            if !args.quiet {
+                for line in &batch.log {
+                    println!("{}", line);
+                }
+            }
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("xrcad_cli: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
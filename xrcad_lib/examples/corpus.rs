@@ -0,0 +1,32 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Run the regression corpus over a fixture directory:
+//!
+//! ```text
+//! cargo run -p xrcad_lib --example corpus -- [DIR]
+//! ```
+//!
+//! Defaults to the bundled `fixtures` directory; exits non-zero if any fixture fails.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use xrcad_lib::testing::corpus::CorpusRunner;
+
+fn main() -> ExitCode {
+    let dir = std::env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures"));
+    match CorpusRunner::new().run_dir(&dir) {
+        Ok(report) => {
+            print!("{}", report.summary());
+            if report.failed() == 0 { ExitCode::SUCCESS } else { ExitCode::FAILURE }
+        }
+        Err(err) => {
+            eprintln!("cannot read {}: {}", dir.display(), err);
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,109 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: drawing::sheet
+//!
+//! Drafting sheets: a paper size, a border and a title block filled from document metadata.
+
+use crate::drawing::title_block::TitleBlockTemplate;
+use crate::model::metadata::DocumentMetadata;
+
+/// Margin between the paper edge and the drawing border (mm)
+pub const SHEET_MARGIN: f64 = 10.0;
+
+/// Paper size of a sheet
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SheetSize {
+    A4,
+    A3,
+    A2,
+    A1,
+    A0,
+    Custom { width: f64, height: f64 },
+}
+
+impl SheetSize {
+    /// Landscape width and height in mm
+    pub fn dimensions(&self) -> (f64, f64) {
+        match self {
+            SheetSize::A4 => (297.0, 210.0),
+            SheetSize::A3 => (420.0, 297.0),
+            SheetSize::A2 => (594.0, 420.0),
+            SheetSize::A1 => (841.0, 594.0),
+            SheetSize::A0 => (1189.0, 841.0),
+            SheetSize::Custom { width, height } => (*width, *height),
+        }
+    }
+}
+
+/// A drafting sheet with its title block already filled in
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sheet {
+    pub name: String,
+    pub size: SheetSize,
+    pub template: TitleBlockTemplate,
+    /// Title block SVG with all fields substituted
+    pub title_block: String,
+}
+
+impl Sheet {
+    /// Create a sheet, filling the title block from `metadata`
+    pub fn new(name: impl Into<String>, size: SheetSize, template: TitleBlockTemplate, metadata: &DocumentMetadata) -> Self {
+        let title_block = template.fill(metadata);
+        Self { name: name.into(), size, template, title_block }
+    }
+
+    /// Re-fill the title block after the document metadata changed
+    pub fn refresh(&mut self, metadata: &DocumentMetadata) {
+        self.title_block = self.template.fill(metadata);
+    }
+
+    /// Top-left corner of the title block: bottom-right of the border
+    pub fn title_block_origin(&self) -> (f64, f64) {
+        let (w, h) = self.size.dimensions();
+        (w - SHEET_MARGIN - self.template.width, h - SHEET_MARGIN - self.template.height)
+    }
+
+    /// Complete SVG document of the sheet (units in mm)
+    pub fn to_svg(&self) -> String {
+        let (w, h) = self.size.dimensions();
+        let (tx, ty) = self.title_block_origin();
+        format!(
+            concat!(
+                "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}mm\" height=\"{h}mm\" viewBox=\"0 0 {w} {h}\">\n",
+                "<rect x=\"{m}\" y=\"{m}\" width=\"{bw}\" height=\"{bh}\" fill=\"none\" stroke=\"black\" stroke-width=\"0.7\"/>\n",
+                "<g transform=\"translate({tx} {ty})\">\n{tb}\n</g>\n",
+                "</svg>\n"
+            ),
+            w = w,
+            h = h,
+            m = SHEET_MARGIN,
+            bw = w - 2.0 * SHEET_MARGIN,
+            bh = h - 2.0 * SHEET_MARGIN,
+            tx = tx,
+            ty = ty,
+            tb = self.title_block,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sheet_fills_title_block() {
+        let mut meta = DocumentMetadata::new("Bracket");
+        meta.author = "A. Scarlett".to_string();
+        meta.scale = "1:2".to_string();
+        let mut sheet = Sheet::new("Sheet 1", SheetSize::A3, TitleBlockTemplate::default(), &meta);
+        assert!(sheet.title_block.contains(">Bracket<"));
+        assert!(sheet.title_block.contains("1:2"));
+        assert!(!sheet.title_block.contains("{{"));
+        assert_eq!(sheet.title_block_origin(), (230.0, 247.0));
+
+        meta.revision = "C".to_string();
+        sheet.refresh(&meta);
+        assert!(sheet.to_svg().contains(">C<"));
+    }
+}
@@ -0,0 +1,115 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: drawing::title_block
+//!
+//! SVG title-block templates. Text placeholders of the form `{{field}}` are bound
+//! to document metadata and filled in when a sheet is created.
+
+use crate::model::metadata::DocumentMetadata;
+
+/// Built-in title block, 180 x 40 mm, origin at its top-left corner
+pub const DEFAULT_TITLE_BLOCK_SVG: &str = r#"<g class="title-block" font-family="sans-serif" font-size="3.5">
+  <rect x="0" y="0" width="180" height="40" fill="none" stroke="black" stroke-width="0.5"/>
+  <line x1="0" y1="20" x2="180" y2="20" stroke="black" stroke-width="0.35"/>
+  <line x1="120" y1="20" x2="120" y2="40" stroke="black" stroke-width="0.35"/>
+  <line x1="150" y1="20" x2="150" y2="40" stroke="black" stroke-width="0.35"/>
+  <text x="4" y="13" font-size="7">{{title}}</text>
+  <text x="4" y="28">Drawn: {{author}}</text>
+  <text x="4" y="35">Date: {{date}}</text>
+  <text x="124" y="28">Scale</text>
+  <text x="124" y="35">{{scale}}</text>
+  <text x="154" y="28">Rev</text>
+  <text x="154" y="35">{{revision}}</text>
+</g>"#;
+
+/// A title-block template: an SVG fragment with `{{field}}` placeholders
+#[derive(Debug, Clone, PartialEq)]
+pub struct TitleBlockTemplate {
+    pub name: String,
+    pub svg: String,
+    /// Size of the block in sheet units (mm)
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Default for TitleBlockTemplate {
+    fn default() -> Self {
+        Self::new("Default", DEFAULT_TITLE_BLOCK_SVG, 180.0, 40.0)
+    }
+}
+
+impl TitleBlockTemplate {
+    pub fn new(name: impl Into<String>, svg: impl Into<String>, width: f64, height: f64) -> Self {
+        Self { name: name.into(), svg: svg.into(), width, height }
+    }
+
+    /// Names of all placeholders in the template, in order of first appearance
+    pub fn fields(&self) -> Vec<String> {
+        let mut fields: Vec<String> = Vec::new();
+        let mut rest = self.svg.as_str();
+        while let Some((name, tail)) = next_placeholder(rest) {
+            if !fields.iter().any(|f| f == name) {
+                fields.push(name.to_string());
+            }
+            rest = tail;
+        }
+        fields
+    }
+
+    /// Substitute every placeholder with its (XML-escaped) metadata value.
+    /// Fields missing from the metadata are left blank.
+    pub fn fill(&self, metadata: &DocumentMetadata) -> String {
+        let mut out = String::with_capacity(self.svg.len());
+        let mut rest = self.svg.as_str();
+        while let Some(start) = rest.find("{{") {
+            let Some((name, tail)) = next_placeholder(&rest[start..]) else { break; };
+            out.push_str(&rest[..start]);
+            out.push_str(&escape_xml(metadata.field(name).unwrap_or("")));
+            rest = tail;
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+/// Find the next `{{name}}` placeholder, returning its trimmed name and the text after it
+fn next_placeholder(text: &str) -> Option<(&str, &str)> {
+    let start = text.find("{{")?;
+    let end = text[start..].find("}}")? + start;
+    Some((text[start + 2..end].trim(), &text[end + 2..]))
+}
+
+/// Escape text for use in SVG character data and attribute values
+pub fn escape_xml(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_fields() {
+        let fields = TitleBlockTemplate::default().fields();
+        assert_eq!(fields, vec!["title", "author", "date", "scale", "revision"]);
+    }
+
+    #[test]
+    fn test_fill_escapes_and_blanks_missing() {
+        let t = TitleBlockTemplate::new("T", "<text>{{ title }}</text><text>{{part_no}}</text>", 10.0, 5.0);
+        let meta = DocumentMetadata::new("Nut & Bolt");
+        assert_eq!(t.fill(&meta), "<text>Nut &amp; Bolt</text><text></text>");
+    }
+}
@@ -0,0 +1,316 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: input::actions
+//!
+//! Device-independent input actions. Every frame the mouse, keyboard,
+//! gamepads, touchscreen, styluses and XR controllers are mapped onto a small
+//! set of actions (select, confirm, cancel, value steps) and axes (pan, orbit,
+//! zoom) in `ActionState`, so a tool reads "select was pressed" instead of
+//! checking each device. Keys and gamepad buttons come from `KeyBindings`;
+//! the pointer devices have fixed gestures. An action is held while any
+//! device holds it. The camera controller reads the axes, picking reads
+//! select, and tools read the buttons and value steps.
+
+use std::collections::HashSet;
+
+use bevy::input::mouse::{MouseMotion, MouseWheel};
+use bevy::input::touch::Touches;
+use bevy::prelude::*;
+
+use crate::input::gamepad::{deadzoned, GamepadSettings};
+use crate::input::keyboard::KeyBindings;
+use crate::input::stylus::Stylus;
+use crate::input::touchscreen::{touch_gesture, TouchGesture, TouchSettings};
+use crate::interaction::xr_controller::XrController;
+use crate::viewport::camera_control::ORBIT_RADIANS_PER_PIXEL;
+
+/// What the user asks for, whatever the device
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputAction {
+    /// Pick or press what is under the pointer
+    Select,
+    /// Drag the view sideways
+    Pan,
+    /// Turn the view about the pivot
+    Orbit,
+    /// Move the view in or out
+    Zoom,
+    /// Accept the current operation
+    Confirm,
+    /// Abandon the current operation
+    Cancel,
+    /// Step the value being edited up
+    ValueUp,
+    /// Step the value being edited down
+    ValueDown,
+}
+
+impl InputAction {
+    pub const ALL: [InputAction; 8] = [
+        InputAction::Select,
+        InputAction::Pan,
+        InputAction::Orbit,
+        InputAction::Zoom,
+        InputAction::Confirm,
+        InputAction::Cancel,
+        InputAction::ValueUp,
+        InputAction::ValueDown,
+    ];
+}
+
+/// Button actions and their names in `KeyBindings`
+const BOUND_ACTIONS: [(InputAction, &str); 5] = [
+    (InputAction::Select, "select"),
+    (InputAction::Confirm, "confirm"),
+    (InputAction::Cancel, "cancel"),
+    (InputAction::ValueUp, "value_up"),
+    (InputAction::ValueDown, "value_down"),
+];
+
+/// Actions held this frame and how far the axes moved
+#[derive(Resource, Debug, Default, Clone)]
+pub struct ActionState {
+    buttons: ButtonInput<InputAction>,
+    /// Held by some device this frame, applied to `buttons` once all have reported
+    held: HashSet<InputAction>,
+    /// Screen pixels, y down
+    pan: Vec2,
+    /// Screen pixels, y down
+    orbit: Vec2,
+    /// Wheel notches, positive in
+    zoom: f32,
+}
+
+impl ActionState {
+    pub fn pressed(&self, action: InputAction) -> bool {
+        self.buttons.pressed(action)
+    }
+
+    /// No device held the action last frame and one does now
+    pub fn just_pressed(&self, action: InputAction) -> bool {
+        self.buttons.just_pressed(action)
+    }
+
+    /// The last device holding the action let go this frame
+    pub fn just_released(&self, action: InputAction) -> bool {
+        self.buttons.just_released(action)
+    }
+
+    /// Movement of an axis this frame; zoom is in `y`, button actions are zero
+    pub fn axis(&self, action: InputAction) -> Vec2 {
+        match action {
+            InputAction::Pan => self.pan,
+            InputAction::Orbit => self.orbit,
+            InputAction::Zoom => Vec2::new(0.0, self.zoom),
+            _ => Vec2::ZERO,
+        }
+    }
+
+    /// Value step asked for this frame: 1 up, -1 down or 0
+    pub fn step(&self) -> i32 {
+        self.just_pressed(InputAction::ValueUp) as i32 - self.just_pressed(InputAction::ValueDown) as i32
+    }
+
+    /// A device holds an action this frame
+    pub fn hold(&mut self, action: InputAction) {
+        self.held.insert(action);
+    }
+
+    /// A device moves an axis this frame, which also holds it; zoom takes `y`
+    pub fn move_axis(&mut self, action: InputAction, amount: Vec2) {
+        if amount == Vec2::ZERO {
+            return;
+        }
+        match action {
+            InputAction::Pan => self.pan += amount,
+            InputAction::Orbit => self.orbit += amount,
+            InputAction::Zoom if amount.y != 0.0 => self.zoom += amount.y,
+            _ => return,
+        }
+        self.hold(action);
+    }
+}
+
+/// Forget last frame's presses and motion before the devices report
+pub fn begin_actions(mut actions: ResMut<ActionState>) {
+    let actions = &mut *actions;
+    actions.buttons.clear();
+    actions.held.clear();
+    actions.pan = Vec2::ZERO;
+    actions.orbit = Vec2::ZERO;
+    actions.zoom = 0.0;
+}
+
+/// Left button selects and drags to orbit; the middle button, or the left
+/// with Shift, drags to pan; the wheel zooms. Orbit and pan are held while
+/// their button is, so an orbit starts when the button goes down.
+pub fn mouse_actions(
+    mouse: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut motion: EventReader<MouseMotion>,
+    mut wheel: EventReader<MouseWheel>,
+    mut actions: ResMut<ActionState>,
+) {
+    let delta: Vec2 = motion.read().map(|m| m.delta).sum();
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if mouse.pressed(MouseButton::Left) {
+        actions.hold(InputAction::Select);
+    }
+    if mouse.pressed(MouseButton::Middle) || (mouse.pressed(MouseButton::Left) && shift) {
+        actions.hold(InputAction::Pan);
+        actions.move_axis(InputAction::Pan, delta);
+    } else if mouse.pressed(MouseButton::Left) {
+        actions.hold(InputAction::Orbit);
+        actions.move_axis(InputAction::Orbit, delta);
+    }
+    let scroll: f32 = wheel.read().map(|w| w.y).sum();
+    actions.move_axis(InputAction::Zoom, Vec2::new(0.0, scroll));
+}
+
+/// Keys and gamepad buttons from the bindings; the orbit stick orbits, or
+/// pans with the pan modifier held, and the zoom stick zooms (up and down) and
+/// turns (left and right), at the rates in `GamepadSettings`
+pub fn binding_actions(
+    time: Res<Time>,
+    bindings: Res<KeyBindings>,
+    settings: Option<Res<GamepadSettings>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    mut actions: ResMut<ActionState>,
+) {
+    for (action, name) in BOUND_ACTIONS {
+        if bindings.pressed(name, &keys) || bindings.gamepad.pressed(name, &gamepads) {
+            actions.hold(action);
+        }
+    }
+    let dt = time.delta_secs();
+    let settings = settings.as_deref().cloned().unwrap_or_default();
+    let panning = bindings.gamepad.pressed("pan_modifier", &gamepads);
+    let orbit_pixels = settings.orbit_speed.to_radians() / ORBIT_RADIANS_PER_PIXEL * dt;
+    for gamepad in gamepads.iter() {
+        let mut stick = deadzoned(bindings.gamepad.axis("orbit", gamepad), settings.deadzone);
+        if settings.invert_y {
+            stick.y = -stick.y;
+        }
+        // Sticks point up with +y, the screen counts down. Panning moves the
+        // view the way the stick points, so the model drags the other way.
+        if panning {
+            actions.move_axis(InputAction::Pan, Vec2::new(-stick.x, stick.y) * settings.pan_speed * dt);
+        } else {
+            actions.move_axis(InputAction::Orbit, Vec2::new(stick.x, -stick.y) * orbit_pixels);
+        }
+        let zoom_turn = deadzoned(bindings.gamepad.axis("zoom_turn", gamepad), settings.deadzone);
+        actions.move_axis(InputAction::Orbit, Vec2::new(zoom_turn.x * orbit_pixels, 0.0));
+        actions.move_axis(InputAction::Zoom, Vec2::new(0.0, zoom_turn.y * settings.zoom_speed * dt));
+    }
+}
+
+/// One finger orbits, two pan and pinch to zoom (doubling the spread is one
+/// notch), and a tap selects
+pub fn touch_actions(touches: Res<Touches>, settings: Option<Res<TouchSettings>>, mut actions: ResMut<ActionState>) {
+    let fingers: Vec<(Vec2, Vec2)> = touches.iter().map(|t| (t.position(), t.previous_position())).collect();
+    match touch_gesture(&fingers) {
+        Some(TouchGesture::Orbit(delta)) => actions.move_axis(InputAction::Orbit, delta),
+        Some(TouchGesture::PanPinch { pan, pinch }) => {
+            actions.move_axis(InputAction::Pan, pan);
+            if pinch > 0.0 {
+                actions.move_axis(InputAction::Zoom, Vec2::new(0.0, pinch.log2()));
+            }
+        }
+        None => {}
+    }
+    let slop = settings.map_or(TouchSettings::default().slop, |s| s.slop);
+    if touches.iter_just_released().any(|t| t.distance().length() <= slop) {
+        actions.hold(InputAction::Select);
+    }
+}
+
+/// Stylus buttons and XR triggers select
+pub fn pointer_device_actions(styluses: Query<&Stylus>, controllers: Query<&XrController>, mut actions: ResMut<ActionState>) {
+    if styluses.iter().any(|s| s.button) || controllers.iter().any(|c| c.trigger) {
+        actions.hold(InputAction::Select);
+    }
+}
+
+/// Turn what the devices held into presses and releases
+pub fn finish_actions(mut actions: ResMut<ActionState>) {
+    let actions = &mut *actions;
+    for action in InputAction::ALL {
+        if actions.held.contains(&action) {
+            actions.buttons.press(action);
+        } else {
+            actions.buttons.release(action);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interaction::xr_controller::XrHand;
+
+    fn actions_app() -> App {
+        let mut app = App::new();
+        app.init_resource::<ActionState>()
+            .init_resource::<KeyBindings>()
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<ButtonInput<MouseButton>>()
+            .init_resource::<Time>()
+            .add_event::<MouseMotion>()
+            .add_event::<MouseWheel>()
+            .add_systems(Update, (begin_actions, (mouse_actions, binding_actions, pointer_device_actions), finish_actions).chain());
+        app
+    }
+
+    fn actions(app: &App) -> &ActionState {
+        app.world().resource::<ActionState>()
+    }
+
+    #[test]
+    fn test_devices_share_actions() {
+        let mut app = actions_app();
+        app.world_mut().resource_mut::<ButtonInput<KeyCode>>().press(KeyCode::Escape);
+        app.world_mut().resource_mut::<ButtonInput<MouseButton>>().press(MouseButton::Left);
+        app.update();
+        assert!(actions(&app).just_pressed(InputAction::Cancel));
+        assert!(actions(&app).just_pressed(InputAction::Select));
+
+        // An XR trigger keeps select held after the mouse lets go
+        let controller = app.world_mut().spawn(XrController { trigger: true, ..XrController::new(XrHand::Right) }).id();
+        app.world_mut().resource_mut::<ButtonInput<MouseButton>>().release(MouseButton::Left);
+        app.update();
+        assert!(actions(&app).pressed(InputAction::Select));
+        assert!(!actions(&app).just_pressed(InputAction::Select));
+        app.world_mut().get_mut::<XrController>(controller).unwrap().trigger = false;
+        app.update();
+        assert!(actions(&app).just_released(InputAction::Select));
+
+        let mut gamepad = Gamepad::default();
+        gamepad.digital_mut().press(GamepadButton::RightTrigger2);
+        app.world_mut().spawn(gamepad);
+        app.update();
+        assert_eq!(actions(&app).step(), 1);
+    }
+
+    #[test]
+    fn test_mouse_axes() {
+        let mut app = actions_app();
+        app.world_mut().resource_mut::<ButtonInput<MouseButton>>().press(MouseButton::Middle);
+        app.world_mut().send_event(MouseMotion { delta: Vec2::new(4.0, -2.0) });
+        app.world_mut().send_event(MouseWheel { unit: bevy::input::mouse::MouseScrollUnit::Line, x: 0.0, y: 2.0, window: Entity::PLACEHOLDER });
+        app.update();
+        assert_eq!(actions(&app).axis(InputAction::Pan), Vec2::new(4.0, -2.0));
+        assert_eq!(actions(&app).axis(InputAction::Orbit), Vec2::ZERO);
+        assert_eq!(actions(&app).axis(InputAction::Zoom), Vec2::new(0.0, 2.0));
+        assert!(actions(&app).just_pressed(InputAction::Zoom));
+
+        // Motion only counts for the frame it happened in; the drag lasts while the button is down
+        app.update();
+        assert_eq!(actions(&app).axis(InputAction::Pan), Vec2::ZERO);
+        assert!(actions(&app).pressed(InputAction::Pan));
+        app.world_mut().resource_mut::<ButtonInput<MouseButton>>().release(MouseButton::Middle);
+        app.update();
+        assert!(actions(&app).just_released(InputAction::Pan));
+    }
+}
@@ -0,0 +1,132 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: input::bindings
+//!
+//! Gamepad buttons and sticks of named actions. The table is part of
+//! `KeyBindings`, next to the key chords, so an action such as `select_next`
+//! fires from either device under one name, and rebinding it changes it for
+//! everyone reading the table.
+
+use bevy::prelude::*;
+
+/// Gamepad thumbstick
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadStick {
+    Left,
+    Right,
+}
+
+impl GamepadStick {
+    /// Deflection of this stick on a gamepad (-1..1 on each axis, +Y up)
+    pub fn value(&self, gamepad: &Gamepad) -> Vec2 {
+        match self {
+            GamepadStick::Left => gamepad.left_stick(),
+            GamepadStick::Right => gamepad.right_stick(),
+        }
+    }
+}
+
+/// One gamepad input that can trigger an action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Binding {
+    Button(GamepadButton),
+    Stick(GamepadStick),
+}
+
+impl Binding {
+    pub fn label(&self) -> String {
+        match self {
+            Binding::Button(button) => format!("Pad {:?}", button),
+            Binding::Stick(GamepadStick::Left) => "Left stick".to_string(),
+            Binding::Stick(GamepadStick::Right) => "Right stick".to_string(),
+        }
+    }
+}
+
+/// Named actions and the gamepad inputs that trigger each, in display order
+#[derive(Debug, Clone, PartialEq)]
+pub struct GamepadBindings {
+    pub actions: Vec<(String, Vec<Binding>)>,
+}
+
+impl Default for GamepadBindings {
+    fn default() -> Self {
+        let actions = [
+            ("orbit", vec![Binding::Stick(GamepadStick::Left)]),
+            ("pan_modifier", vec![Binding::Button(GamepadButton::LeftTrigger)]),
+            ("zoom_turn", vec![Binding::Stick(GamepadStick::Right)]),
+            ("select_next", vec![Binding::Button(GamepadButton::DPadRight), Binding::Button(GamepadButton::DPadDown)]),
+            ("select_previous", vec![Binding::Button(GamepadButton::DPadLeft), Binding::Button(GamepadButton::DPadUp)]),
+            ("select", vec![Binding::Button(GamepadButton::South)]),
+            ("confirm", vec![Binding::Button(GamepadButton::Start)]),
+            ("cancel", vec![Binding::Button(GamepadButton::East)]),
+            ("value_up", vec![Binding::Button(GamepadButton::RightTrigger2)]),
+            ("value_down", vec![Binding::Button(GamepadButton::LeftTrigger2)]),
+        ];
+        Self { actions: actions.into_iter().map(|(name, bindings)| (name.to_string(), bindings)).collect() }
+    }
+}
+
+impl GamepadBindings {
+    /// What triggers an action; nothing for unknown actions
+    pub fn get(&self, action: &str) -> &[Binding] {
+        self.actions.iter().find(|(name, _)| name == action).map_or(&[], |(_, bindings)| bindings.as_slice())
+    }
+
+    /// Replace what triggers an action, adding the action if it is new
+    pub fn rebind(&mut self, action: &str, bindings: Vec<Binding>) {
+        match self.actions.iter_mut().find(|(name, _)| name == action) {
+            Some((_, existing)) => *existing = bindings,
+            None => self.actions.push((action.to_string(), bindings)),
+        }
+    }
+
+    /// A button of the action went down this frame
+    pub fn just_pressed<'a>(&self, action: &str, gamepads: impl IntoIterator<Item = &'a Gamepad> + Clone) -> bool {
+        self.get(action).iter().any(|b| match b {
+            Binding::Button(button) => gamepads.clone().into_iter().any(|g| g.just_pressed(*button)),
+            Binding::Stick(_) => false,
+        })
+    }
+
+    /// A button of the action is held
+    pub fn pressed<'a>(&self, action: &str, gamepads: impl IntoIterator<Item = &'a Gamepad> + Clone) -> bool {
+        self.get(action).iter().any(|b| match b {
+            Binding::Button(button) => gamepads.clone().into_iter().any(|g| g.pressed(*button)),
+            Binding::Stick(_) => false,
+        })
+    }
+
+    /// Summed deflection of the action's sticks on a gamepad
+    pub fn axis(&self, action: &str, gamepad: &Gamepad) -> Vec2 {
+        self.get(action).iter().filter_map(|b| if let Binding::Stick(stick) = b { Some(stick.value(gamepad)) } else { None }).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rebind() {
+        let mut bindings = GamepadBindings::default();
+        assert!(bindings.get("select_next").contains(&Binding::Button(GamepadButton::DPadRight)));
+        bindings.rebind("select_next", vec![Binding::Button(GamepadButton::RightTrigger)]);
+        assert_eq!(bindings.get("select_next"), &[Binding::Button(GamepadButton::RightTrigger)]);
+        bindings.rebind("frame", vec![Binding::Button(GamepadButton::North)]);
+        assert_eq!(bindings.actions.last().unwrap().0, "frame");
+        assert!(bindings.get("unknown").is_empty());
+    }
+
+    #[test]
+    fn test_buttons_press_actions() {
+        let bindings = GamepadBindings::default();
+        let mut gamepad = Gamepad::default();
+        assert!(!bindings.just_pressed("select_next", [&gamepad]));
+        gamepad.digital_mut().press(GamepadButton::DPadRight);
+        assert!(bindings.just_pressed("select_next", [&gamepad]));
+        assert!(!bindings.just_pressed("select_previous", [&gamepad]));
+        assert!(!bindings.pressed("pan_modifier", [&gamepad]));
+    }
+}
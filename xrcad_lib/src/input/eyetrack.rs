@@ -2,22 +2,219 @@
 // Copyright (c) 2025 Adrian Scarlett
 
 //! Module: input::eyetrack
+//!
+//! Eye-tracking assisted selection. When the headset tracks the eyes, the XR
+//! backend sets the gaze ray of `EyeGaze` every frame; the target looked at
+//! is pre-highlighted so the user sees what a trigger press would pick. A
+//! controller ray pointing close to that target snaps to it, which makes
+//! small edges and vertices easier to hit at arm's length. As an
+//! accessibility option, resting the gaze on a target for a moment selects
+//! it without any button.
 
-/// Represents an eye tracking device.
-pub struct EyeTracker;
+use std::time::Duration;
 
-impl EyeTracker {
-    pub fn new() -> Self {
-        EyeTracker
+use bevy::prelude::*;
+use nalgebra::Vector3;
+
+use crate::color::YELLOW;
+use crate::interaction::picking::{is_body_pickable, pick_where_in, PickHit, PickTarget};
+use crate::interaction::selection::{Selection, SelectionFilter, SelectionItem};
+use crate::interaction::xr_controller::XrController;
+use crate::model::brep_model::{na_vec3_to_bevy, BrepModel};
+use crate::model::bvh::SceneBvh;
+use crate::model::groups::BodyGroups;
+use crate::model::layers::LayerManager;
+use crate::model::properties::BodyPropertiesCollection;
+use crate::render::hilighting::item_edges;
+use crate::telemetry::crash::journal;
+
+/// Pick radius around the gaze per unit of distance (radians); gaze is less
+/// precise than a controller ray
+const GAZE_PICK_ANGLE: f64 = 0.02;
+const GAZE_COLOR: Color = YELLOW;
+
+/// How eye tracking helps selection
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct EyeTrackSettings {
+    /// Highlight the target being looked at
+    pub pre_highlight: bool,
+    /// Controller rays within this angle of the gazed target pick it (radians, 0 turns it off)
+    pub bias_angle: f32,
+    /// Select a target by looking at it for `dwell`
+    pub dwell_select: bool,
+    pub dwell: Duration,
+}
+
+impl Default for EyeTrackSettings {
+    fn default() -> Self {
+        Self { pre_highlight: true, bias_angle: 5f32.to_radians(), dwell_select: false, dwell: Duration::from_millis(1000) }
+    }
+}
+
+/// Where the eyes look, set by the XR backend
+#[derive(Resource, Debug, Default, Clone)]
+pub struct EyeGaze {
+    /// The headset tracks the eyes
+    pub available: bool,
+    /// Gaze origin and unit direction (model space)
+    pub ray: Option<(Vector3<f64>, Vector3<f64>)>,
+    /// Target looked at
+    pub hover: Option<PickHit>,
+    /// How long the gaze has rested on the hovered target
+    pub dwell: Duration,
+    /// The dwell on the hovered target already selected it
+    dwelt: bool,
+}
+
+impl EyeGaze {
+    /// Target looked at, as the filter would select it
+    pub fn item(&self, filter: SelectionFilter) -> Option<SelectionItem> {
+        self.hover.as_ref().and_then(|h| h.selection_item(filter))
+    }
+}
+
+/// Angle between a ray and the direction from its origin to `point` (radians)
+pub fn angle_to(origin: &Vector3<f64>, dir: &Vector3<f64>, point: &Vector3<f64>) -> f64 {
+    let to = point - origin;
+    if to.norm() <= f64::EPSILON {
+        return 0.0;
+    }
+    dir.angle(&to)
+}
+
+/// Cast the gaze into the model and time how long it rests on one target
+pub fn update_gaze(
+    time: Res<Time>,
+    mut gaze: ResMut<EyeGaze>,
+    (model, bvh): (Res<BrepModel>, Option<Res<SceneBvh>>),
+    selection: Option<Res<Selection>>,
+    (properties, groups, layers): (Option<Res<BodyPropertiesCollection>>, Option<Res<BodyGroups>>, Option<Res<LayerManager>>),
+) {
+    let ray = gaze.ray.filter(|_| gaze.available);
+    let Some((origin, dir)) = ray else {
+        if gaze.hover.is_some() {
+            gaze.hover = None;
+        }
+        return;
+    };
+    let built;
+    let bvh = match bvh.as_deref() {
+        Some(bvh) => bvh,
+        None => {
+            built = SceneBvh::build(&model);
+            &built
+        }
+    };
+    let filter = selection.map_or(SelectionFilter::Any, |s| s.filter);
+    let (no_groups, no_layers, no_properties) = (BodyGroups::default(), LayerManager::default(), BodyPropertiesCollection::default());
+    let groups = groups.as_deref().unwrap_or(&no_groups);
+    let layers = layers.as_deref().unwrap_or(&no_layers);
+    let properties = properties.as_deref().unwrap_or(&no_properties);
+    let radius = |_: PickTarget, p: &Vector3<f64>| (p - origin).norm() * GAZE_PICK_ANGLE;
+    let hover = pick_where_in(&model, bvh, &origin, &dir, filter, radius, |body| is_body_pickable(body, groups, layers, properties));
+    let same = gaze.hover.as_ref().map(|h| h.target) == hover.as_ref().map(|h| h.target) && hover.is_some();
+    if same {
+        gaze.dwell += time.delta();
+    } else {
+        gaze.dwell = Duration::ZERO;
+        gaze.dwelt = false;
+    }
+    gaze.hover = hover;
+}
+
+/// Controller rays pointing near the gazed target pick it instead of what they hit
+pub fn bias_xr_pointers(gaze: Res<EyeGaze>, settings: Res<EyeTrackSettings>, mut controllers: Query<&mut XrController>) {
+    let Some(target) = gaze.hover.as_ref().filter(|_| gaze.available && settings.bias_angle > 0.0) else { return };
+    for mut controller in controllers.iter_mut().filter(|c| c.grab.is_none()) {
+        let Some((origin, dir)) = controller.ray else { continue };
+        if controller.hover.as_ref().is_some_and(|h| h.target == target.target) {
+            continue;
+        }
+        if angle_to(&origin, &dir, &target.point) <= settings.bias_angle as f64 {
+            controller.hover = Some(PickHit { depth: (target.point - origin).norm(), ..target.clone() });
+        }
+    }
+}
+
+/// With dwell selection on, a target looked at long enough becomes the selection
+pub fn gaze_dwell_select(settings: Res<EyeTrackSettings>, mut gaze: ResMut<EyeGaze>, mut selection: ResMut<Selection>) {
+    if !settings.dwell_select || gaze.dwelt || gaze.dwell < settings.dwell {
+        return;
+    }
+    let Some(item) = gaze.item(selection.filter) else { return };
+    gaze.dwelt = true;
+    selection.clear();
+    selection.add(item);
+    journal(format!("gaze_select {:?}", item));
+}
+
+/// Outline the gazed target, with a ring filling up while a dwell selection builds
+pub fn render_gaze_highlight(mut gizmos: Gizmos, gaze: Res<EyeGaze>, settings: Res<EyeTrackSettings>, model: Res<BrepModel>, selection: Res<Selection>) {
+    let Some(hit) = gaze.hover.as_ref().filter(|_| gaze.available && settings.pre_highlight) else { return };
+    let Some(item) = hit.selection_item(selection.filter).filter(|i| !selection.contains(*i)) else { return };
+    for edge in item_edges(&model, &item) {
+        let ends = model.edge(edge).and_then(|e| Some((model.vertex_position(e.vertices.0)?, model.vertex_position(e.vertices.1)?)));
+        if let Some((a, b)) = ends {
+            gizmos.line(na_vec3_to_bevy(&a), na_vec3_to_bevy(&b), GAZE_COLOR.with_alpha(0.6));
+        }
+    }
+    if settings.dwell_select && !gaze.dwelt {
+        let point = na_vec3_to_bevy(&hit.point);
+        let radius = (hit.depth * GAZE_PICK_ANGLE) as f32;
+        let progress = (gaze.dwell.as_secs_f32() / settings.dwell.as_secs_f32().max(1e-3)).min(1.0);
+        if let Some((origin, _)) = gaze.ray {
+            let facing = Transform::from_translation(point).looking_at(na_vec3_to_bevy(&origin), Vec3::Y).rotation;
+            gizmos.arc_3d(progress * std::f32::consts::TAU, radius, Isometry3d::new(point, facing * Quat::from_rotation_x(std::f32::consts::FRAC_PI_2)), GAZE_COLOR);
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::interaction::xr_controller::{update_xr_pointers, XrHand};
+    use crate::model::brep::primitives::cube;
+
+    fn gaze_app() -> App {
+        let mut app = App::new();
+        app.insert_resource(cube(10.0))
+            .init_resource::<Selection>()
+            .init_resource::<EyeTrackSettings>()
+            .insert_resource(EyeGaze { available: true, ray: Some((Vector3::new(1.0, 2.0, 20.0), -Vector3::z())), ..default() })
+            .init_resource::<Time>();
+        app
+    }
+
     #[test]
-    fn test_eyetracker_new() {
-        let tracker = EyeTracker::new();
-        let _ = tracker;
+    fn test_controller_near_gaze_picks_it() {
+        let mut app = gaze_app();
+        app.add_systems(Update, (update_gaze, update_xr_pointers, bias_xr_pointers).chain());
+        // Skimming just over the top face the gaze rests on, from the side: the ray misses the face
+        let pose = Transform::from_xyz(30.0, 2.0, 5.5).looking_at(Vec3::new(1.0, 2.0, 5.3), Vec3::Y);
+        let controller = app.world_mut().spawn((XrController::new(XrHand::Right), GlobalTransform::from(pose))).id();
+        app.update();
+        let top = app.world().resource::<BrepModel>().faces[1].id;
+        let gazed = app.world().resource::<EyeGaze>().hover.clone().unwrap();
+        assert_eq!(gazed.target, PickTarget::Face(top));
+        let hover = app.world().get::<XrController>(controller).unwrap().hover.clone();
+        assert_eq!(hover.map(|h| h.target), Some(gazed.target));
+    }
+
+    #[test]
+    fn test_dwell_selects_once() {
+        let mut app = gaze_app();
+        app.insert_resource(EyeTrackSettings { dwell_select: true, ..default() }).add_systems(Update, (update_gaze, gaze_dwell_select).chain());
+        app.update();
+        assert!(app.world().resource::<Selection>().is_empty());
+        app.world_mut().resource_mut::<Time>().advance_by(Duration::from_millis(1100));
+        app.update();
+        let gazed = app.world().resource::<EyeGaze>().item(SelectionFilter::Any).unwrap();
+        assert_eq!(app.world().resource::<Selection>().items, vec![gazed]);
+
+        // Selected once: clearing it while still looking does not select it again
+        app.world_mut().resource_mut::<Selection>().clear();
+        app.world_mut().resource_mut::<Time>().advance_by(Duration::from_millis(1100));
+        app.update();
+        assert!(app.world().resource::<Selection>().is_empty());
     }
 }
@@ -1,23 +1,125 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 // Copyright (c) 2025 Adrian Scarlett
 
-//! Module: input::keyboard
+//! Module: input::gamepad
+//!
+//! Gamepad settings and selection cycling. With the default bindings the left
+//! stick orbits about the pivot, or pans while the left trigger is held; the
+//! right stick zooms (up and down) and turns the view about the pivot (left
+//! and right); the d-pad steps the selection through the bodies. The sticks
+//! reach the camera as the axes of `ActionState`, like the mouse does. What
+//! each stick and button does comes from `KeyBindings`, the table the keyboard
+//! shortcuts are in.
 
-/// Represents a gamepad input device.
-pub struct Gamepad;
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
 
-impl Gamepad {
-    pub fn new() -> Self {
-        Gamepad
+use crate::input::keyboard::KeyBindings;
+use crate::interaction::picking::is_body_pickable;
+use crate::interaction::selection::{Selection, SelectionFilter, SelectionItem};
+use crate::model::body::BodyId;
+use crate::model::brep_model::BrepModel;
+use crate::model::groups::BodyGroups;
+use crate::model::layers::LayerManager;
+use crate::model::properties::BodyPropertiesCollection;
+use crate::telemetry::crash::journal;
+use crate::telemetry::usage::UsageStats;
+
+/// Gamepad navigation speeds
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct GamepadSettings {
+    /// Orbit and turn rate at full deflection (degrees per second)
+    pub orbit_speed: f32,
+    /// Pan at full deflection, as mouse travel (pixels per second)
+    pub pan_speed: f32,
+    /// Zoom at full deflection (wheel notches per second)
+    pub zoom_speed: f32,
+    /// Stick deflection ignored as drift (0..1)
+    pub deadzone: f32,
+    /// Flip the orbit's vertical axis
+    pub invert_y: bool,
+}
+
+impl Default for GamepadSettings {
+    fn default() -> Self {
+        Self { orbit_speed: 90.0, pan_speed: 600.0, zoom_speed: 10.0, deadzone: 0.15, invert_y: false }
+    }
+}
+
+/// Stick deflection with the dead zone cut out
+pub fn deadzoned(stick: Vec2, deadzone: f32) -> Vec2 {
+    if stick.length() <= deadzone {
+        Vec2::ZERO
+    } else {
+        stick.clamp_length_max(1.0)
+    }
+}
+
+/// Body `step` places after `current` among `bodies`, wrapping round and
+/// skipping those `selectable` rejects; the first or last one without a
+/// current body
+pub fn cycle_body(current: Option<BodyId>, bodies: &[BodyId], step: isize, selectable: impl Fn(BodyId) -> bool) -> Option<BodyId> {
+    if bodies.is_empty() {
+        return None;
+    }
+    let n = bodies.len() as isize;
+    let start = match current.and_then(|body| bodies.iter().position(|b| *b == body)) {
+        Some(index) => index as isize,
+        None if step > 0 => -1,
+        None => n,
+    };
+    (1..=n).map(|i| bodies[(start + step.signum() * i).rem_euclid(n) as usize]).find(|b| selectable(*b))
+}
+
+/// D-pad (or its keys) steps the selection to the next or previous body
+pub fn gamepad_select_cycle(
+    bindings: Res<KeyBindings>,
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    model: Res<BrepModel>,
+    mut selection: ResMut<Selection>,
+    (properties, groups, layers): (Option<Res<BodyPropertiesCollection>>, Option<Res<BodyGroups>>, Option<Res<LayerManager>>),
+    mut usage: Option<ResMut<UsageStats>>,
+) {
+    let pressed = |action| bindings.just_pressed(action, &keys) || bindings.gamepad.just_pressed(action, &gamepads);
+    let step = if pressed("select_next") {
+        1
+    } else if pressed("select_previous") {
+        -1
+    } else {
+        return;
+    };
+    let start = Instant::now();
+    let current = selection.bodies().last().copied();
+    // Hidden and ghosted bodies and bodies on locked layers are stepped over, as the cursor skips them
+    let (no_groups, no_layers, no_properties) = (BodyGroups::default(), LayerManager::default(), BodyPropertiesCollection::default());
+    let groups = groups.as_deref().unwrap_or(&no_groups);
+    let layers = layers.as_deref().unwrap_or(&no_layers);
+    let properties = properties.as_deref().unwrap_or(&no_properties);
+    let pickable = |body| is_body_pickable(body, groups, layers, properties);
+    let Some(body) = cycle_body(current, &model.body_ids(), step, pickable) else { return };
+    selection.set_filter(SelectionFilter::Bodies);
+    selection.clear();
+    selection.add(SelectionItem::Body(body));
+    journal(format!("select_cycle body {}", body.0));
+    if let Some(usage) = usage.as_mut() {
+        usage.record("select_cycle", start.elapsed());
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+
     #[test]
-    fn test_gamepad_new() {
-        let g = Gamepad::new();
-        let _ = g;
+    fn test_cycle_body() {
+        let bodies = [BodyId(0), BodyId(3), BodyId(4)];
+        assert_eq!(cycle_body(None, &bodies, 1, |_| true), Some(BodyId(0)));
+        assert_eq!(cycle_body(None, &bodies, -1, |_| true), Some(BodyId(4)));
+        assert_eq!(cycle_body(Some(BodyId(4)), &bodies, 1, |_| true), Some(BodyId(0)));
+        // Locked or hidden bodies are stepped over
+        assert_eq!(cycle_body(Some(BodyId(0)), &bodies, 1, |b| b != BodyId(3)), Some(BodyId(4)));
+        assert_eq!(cycle_body(Some(BodyId(0)), &bodies, 1, |_| false), None);
+        assert_eq!(cycle_body(None, &[], 1, |_| true), None);
     }
 }
@@ -2,22 +2,640 @@
 // Copyright (c) 2025 Adrian Scarlett
 
 //! Module: input::keyboard
+//!
+//! Keyboard shortcuts as data. `KeyBindings` maps named actions to key chords
+//! (a key plus the Ctrl, Shift and Alt it needs), and systems ask it whether
+//! their action was pressed instead of testing keys themselves; it also holds
+//! the gamepad buttons and sticks of the same actions. The chords are kept in
+//! a small text file, one `action = chord` line each, so users can edit it by
+//! hand; the key bindings panel (Ctrl+K) rebinds an action by clicking it and
+//! pressing the new chord, and saves the file. A chord triggers one action
+//! only: binding a chord another action has is refused. Systems that act on
+//! shortcuts go in the `ShortcutKeys` set, which `ShortcutKeysPlugin` keeps
+//! from running while a text field has the keyboard.
 
-/// Represents a keyboard input device.
-pub struct Keyboard;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
 
-impl Keyboard {
-    pub fn new() -> Self {
-        Keyboard
+use bevy::prelude::*;
+
+use crate::input::bindings::GamepadBindings;
+use crate::interaction::dimension_edit::{not_editing_dimension, DimensionEditSession};
+use crate::interaction::rename::{not_renaming, RenameSession};
+#[cfg(feature = "render")]
+use crate::interaction::state::UiPanel;
+#[cfg(feature = "render")]
+use crate::render::ui_style::{BUTTON_IDLE, PANEL_COLOR_TRANSLUCENT};
+use crate::scripting::console::{not_typing_script, ScriptConsole};
+use crate::telemetry::crash::journal;
+
+/// Default key bindings file, relative to the working directory
+pub const DEFAULT_KEY_BINDINGS_FILE: &str = "xrcad_keys.txt";
+
+#[cfg(feature = "render")]
+const BUTTON_CAPTURING: Color = Color::srgb(0.6, 0.45, 0.15);
+
+/// Keys a chord can be bound to
+const BINDABLE_KEYS: &[KeyCode] = &[
+    KeyCode::KeyA, KeyCode::KeyB, KeyCode::KeyC, KeyCode::KeyD, KeyCode::KeyE, KeyCode::KeyF, KeyCode::KeyG, KeyCode::KeyH, KeyCode::KeyI,
+    KeyCode::KeyJ, KeyCode::KeyK, KeyCode::KeyL, KeyCode::KeyM, KeyCode::KeyN, KeyCode::KeyO, KeyCode::KeyP, KeyCode::KeyQ, KeyCode::KeyR,
+    KeyCode::KeyS, KeyCode::KeyT, KeyCode::KeyU, KeyCode::KeyV, KeyCode::KeyW, KeyCode::KeyX, KeyCode::KeyY, KeyCode::KeyZ,
+    KeyCode::Digit0, KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3, KeyCode::Digit4, KeyCode::Digit5, KeyCode::Digit6, KeyCode::Digit7,
+    KeyCode::Digit8, KeyCode::Digit9,
+    KeyCode::F1, KeyCode::F2, KeyCode::F3, KeyCode::F4, KeyCode::F5, KeyCode::F6, KeyCode::F7, KeyCode::F8, KeyCode::F9, KeyCode::F10,
+    KeyCode::F11, KeyCode::F12,
+    KeyCode::Numpad0, KeyCode::Numpad1, KeyCode::Numpad2, KeyCode::Numpad3, KeyCode::Numpad4, KeyCode::Numpad5, KeyCode::Numpad6,
+    KeyCode::Numpad7, KeyCode::Numpad8, KeyCode::Numpad9,
+    KeyCode::ArrowLeft, KeyCode::ArrowRight, KeyCode::ArrowUp, KeyCode::ArrowDown,
+    KeyCode::Home, KeyCode::End, KeyCode::PageUp, KeyCode::PageDown, KeyCode::Insert, KeyCode::Delete,
+    KeyCode::NumpadEnter, KeyCode::NumpadAdd, KeyCode::NumpadSubtract,
+    KeyCode::Space, KeyCode::Tab, KeyCode::Enter, KeyCode::Backspace, KeyCode::Escape,
+    KeyCode::BracketLeft, KeyCode::BracketRight, KeyCode::Backslash, KeyCode::Slash, KeyCode::Semicolon, KeyCode::Quote,
+    KeyCode::Comma, KeyCode::Period, KeyCode::Minus, KeyCode::Equal, KeyCode::Backquote,
+];
+
+const CTRL: [KeyCode; 2] = [KeyCode::ControlLeft, KeyCode::ControlRight];
+const SHIFT: [KeyCode; 2] = [KeyCode::ShiftLeft, KeyCode::ShiftRight];
+const ALT: [KeyCode; 2] = [KeyCode::AltLeft, KeyCode::AltRight];
+
+/// Name of a key in chords: letters and digits bare, others by their key code
+fn key_name(key: KeyCode) -> String {
+    let name = format!("{:?}", key);
+    name.strip_prefix("Key").or_else(|| name.strip_prefix("Digit")).unwrap_or(&name).to_string()
+}
+
+/// A key with the modifiers that must be held with it, and no others
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub key: KeyCode,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+impl KeyChord {
+    pub const fn key(key: KeyCode) -> Self {
+        Self { key, ctrl: false, shift: false, alt: false }
+    }
+
+    pub const fn ctrl(key: KeyCode) -> Self {
+        Self { key, ctrl: true, shift: false, alt: false }
+    }
+
+    pub const fn shift(key: KeyCode) -> Self {
+        Self { key, ctrl: false, shift: true, alt: false }
+    }
+
+    pub const fn ctrl_shift(key: KeyCode) -> Self {
+        Self { key, ctrl: true, shift: true, alt: false }
+    }
+
+    fn modifiers_match(&self, keys: &ButtonInput<KeyCode>) -> bool {
+        keys.any_pressed(CTRL) == self.ctrl && keys.any_pressed(SHIFT) == self.shift && keys.any_pressed(ALT) == self.alt
+    }
+
+    /// The key went down this frame with exactly the chord's modifiers held
+    pub fn just_pressed(&self, keys: &ButtonInput<KeyCode>) -> bool {
+        keys.just_pressed(self.key) && self.modifiers_match(keys)
+    }
+
+    /// The key is held with exactly the chord's modifiers
+    pub fn pressed(&self, keys: &ButtonInput<KeyCode>) -> bool {
+        keys.pressed(self.key) && self.modifiers_match(keys)
+    }
+
+    /// e.g. "Ctrl+Shift+S"
+    pub fn label(&self) -> String {
+        let mut label = String::new();
+        for (held, name) in [(self.ctrl, "Ctrl+"), (self.shift, "Shift+"), (self.alt, "Alt+")] {
+            if held {
+                label.push_str(name);
+            }
+        }
+        label.push_str(&key_name(self.key));
+        label
+    }
+
+    /// Parse a chord written like its label; none for unknown keys
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parts: Vec<&str> = text.split('+').map(str::trim).collect();
+        let key = parts.pop()?;
+        let key = BINDABLE_KEYS.iter().copied().find(|k| key_name(*k).eq_ignore_ascii_case(key) || format!("{:?}", k).eq_ignore_ascii_case(key))?;
+        let mut chord = KeyChord::key(key);
+        for modifier in parts {
+            match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" => chord.ctrl = true,
+                "shift" => chord.shift = true,
+                "alt" => chord.alt = true,
+                _ => return None,
+            }
+        }
+        Some(chord)
+    }
+}
+
+/// Why a chord could not be bound
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyBindingError {
+    /// Another action already has the chord
+    Taken { chord: KeyChord, action: String },
+}
+
+impl fmt::Display for KeyBindingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyBindingError::Taken { chord, action } => write!(f, "{} is already bound to {}", chord.label(), action),
+        }
+    }
+}
+
+impl std::error::Error for KeyBindingError {}
+
+/// Named actions and the chords that trigger each, in display order, with
+/// the gamepad inputs of the same actions
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct KeyBindings {
+    /// An action may be listed more than once, with one chord each
+    pub actions: Vec<(String, KeyChord)>,
+    pub gamepad: GamepadBindings,
+    pub path: PathBuf,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let actions = [
+            ("pan_sensitivity_up", KeyChord::key(KeyCode::KeyP)),
+            ("pan_sensitivity_down", KeyChord::key(KeyCode::KeyO)),
+            ("rotate_sensitivity_up", KeyChord::key(KeyCode::KeyT)),
+            ("rotate_sensitivity_down", KeyChord::key(KeyCode::KeyY)),
+            ("zoom_sensitivity_up", KeyChord::key(KeyCode::KeyZ)),
+            ("zoom_sensitivity_down", KeyChord::key(KeyCode::KeyX)),
+            ("toggle_xr", KeyChord::key(KeyCode::F1)),
+            ("toggle_stereo", KeyChord::key(KeyCode::F3)),
+            ("cycle_pivot", KeyChord::key(KeyCode::Backslash)),
+            ("rename_body", KeyChord::key(KeyCode::F2)),
+            ("snap_turn_left", KeyChord::key(KeyCode::ArrowLeft)),
+            ("snap_turn_right", KeyChord::key(KeyCode::ArrowRight)),
+            ("lighting_preset", KeyChord::key(KeyCode::KeyL)),
+            ("raking_lower", KeyChord::key(KeyCode::BracketLeft)),
+            ("raking_raise", KeyChord::key(KeyCode::BracketRight)),
+            ("key_bindings_panel", KeyChord::ctrl(KeyCode::KeyK)),
+            ("check_clashes", KeyChord::ctrl_shift(KeyCode::KeyI)),
+            ("save", KeyChord::ctrl(KeyCode::KeyS)),
+            ("save_as", KeyChord::ctrl_shift(KeyCode::KeyS)),
+            ("open", KeyChord::ctrl(KeyCode::KeyO)),
+            ("import", KeyChord::ctrl(KeyCode::KeyI)),
+            ("export", KeyChord::ctrl(KeyCode::KeyE)),
+            ("export_dxf", KeyChord::ctrl(KeyCode::KeyD)),
+            ("export_measurements", KeyChord::ctrl(KeyCode::KeyR)),
+            ("restore_recovery", KeyChord::ctrl_shift(KeyCode::KeyR)),
+            ("discard_recovery", KeyChord::ctrl_shift(KeyCode::Delete)),
+            ("lighting_panel", KeyChord::key(KeyCode::F9)),
+            ("outliner_panel", KeyChord::key(KeyCode::F10)),
+            ("views_panel", KeyChord::key(KeyCode::F12)),
+            ("analysis_panel", KeyChord::shift(KeyCode::F12)),
+            ("culling_stats", KeyChord::ctrl(KeyCode::F9)),
+            ("occlusion_culling", KeyChord::ctrl_shift(KeyCode::F9)),
+            ("xr_panel_anchor", KeyChord::ctrl(KeyCode::F10)),
+            ("recenter_xr_panels", KeyChord::ctrl_shift(KeyCode::F10)),
+            ("confirm", KeyChord::key(KeyCode::Enter)),
+            ("confirm", KeyChord::key(KeyCode::NumpadEnter)),
+            ("cancel", KeyChord::key(KeyCode::Escape)),
+            ("select_next", KeyChord::key(KeyCode::End)),
+            ("select_previous", KeyChord::key(KeyCode::Home)),
+            ("value_up", KeyChord::key(KeyCode::PageUp)),
+            ("value_up", KeyChord::key(KeyCode::NumpadAdd)),
+            ("value_down", KeyChord::key(KeyCode::PageDown)),
+            ("value_down", KeyChord::key(KeyCode::NumpadSubtract)),
+            ("selection_filter", KeyChord::key(KeyCode::KeyQ)),
+            ("delete_selection", KeyChord::key(KeyCode::Delete)),
+            ("gizmo_mode", KeyChord::key(KeyCode::KeyW)),
+            ("planarity_mode", KeyChord::key(KeyCode::KeyK)),
+            ("place_box", KeyChord::key(KeyCode::KeyB)),
+            ("place_cylinder", KeyChord::key(KeyCode::KeyC)),
+            ("union", KeyChord::ctrl(KeyCode::KeyJ)),
+            ("subtract", KeyChord::ctrl(KeyCode::KeyM)),
+            ("intersect", KeyChord::ctrl(KeyCode::KeyG)),
+            ("measure_tool", KeyChord::key(KeyCode::KeyM)),
+            ("suggest_plane", KeyChord::key(KeyCode::KeyN)),
+            ("flip_plane", KeyChord::shift(KeyCode::KeyN)),
+            ("next_dimension", KeyChord::key(KeyCode::Tab)),
+            ("new_layer", KeyChord::key(KeyCode::F11)),
+            ("cycle_units", KeyChord::key(KeyCode::KeyU)),
+            ("usage_stats", KeyChord::key(KeyCode::F4)),
+            ("toggle_projection", KeyChord::key(KeyCode::Numpad5)),
+            ("fit_all", KeyChord::key(KeyCode::KeyF)),
+            ("fit_selection", KeyChord::shift(KeyCode::KeyF)),
+            ("view_front", KeyChord::key(KeyCode::Numpad1)),
+            ("view_right", KeyChord::key(KeyCode::Numpad3)),
+            ("view_top", KeyChord::key(KeyCode::Numpad7)),
+            ("view_iso", KeyChord::key(KeyCode::Numpad0)),
+            ("save_view", KeyChord::key(KeyCode::Insert)),
+            ("recall_view_1", KeyChord::key(KeyCode::Digit1)),
+            ("recall_view_2", KeyChord::key(KeyCode::Digit2)),
+            ("recall_view_3", KeyChord::key(KeyCode::Digit3)),
+            ("recall_view_4", KeyChord::key(KeyCode::Digit4)),
+            ("recall_view_5", KeyChord::key(KeyCode::Digit5)),
+            ("recall_view_6", KeyChord::key(KeyCode::Digit6)),
+            ("recall_view_7", KeyChord::key(KeyCode::Digit7)),
+            ("recall_view_8", KeyChord::key(KeyCode::Digit8)),
+            ("recall_view_9", KeyChord::key(KeyCode::Digit9)),
+            ("play_animation", KeyChord::key(KeyCode::Space)),
+            ("animation_mode", KeyChord::shift(KeyCode::Space)),
+            ("xr_scale_life_size", KeyChord::key(KeyCode::F5)),
+            ("xr_scale_tabletop", KeyChord::key(KeyCode::F6)),
+            ("xr_scale_room", KeyChord::key(KeyCode::F7)),
+            ("scale_world_up", KeyChord::key(KeyCode::Equal)),
+            ("scale_world_down", KeyChord::key(KeyCode::Minus)),
+            ("passthrough", KeyChord::key(KeyCode::KeyA)),
+            ("toggle_section", KeyChord::key(KeyCode::KeyH)),
+            ("section_plane", KeyChord::shift(KeyCode::KeyH)),
+            ("section_caps", KeyChord::ctrl(KeyCode::KeyH)),
+            ("section_forward", KeyChord::shift(KeyCode::PageUp)),
+            ("section_back", KeyChord::shift(KeyCode::PageDown)),
+            ("display_mode", KeyChord::key(KeyCode::Backquote)),
+            ("body_display_mode", KeyChord::shift(KeyCode::Backquote)),
+            ("script_console", KeyChord::ctrl(KeyCode::Backquote)),
+            ("edge_display", KeyChord::key(KeyCode::KeyE)),
+            ("render_profile", KeyChord::key(KeyCode::F8)),
+            ("exploded_view", KeyChord::key(KeyCode::KeyV)),
+            ("explode_less", KeyChord::key(KeyCode::Comma)),
+            ("explode_more", KeyChord::key(KeyCode::Period)),
+        ];
+        Self {
+            actions: actions.into_iter().map(|(name, chord)| (name.to_string(), chord)).collect(),
+            gamepad: GamepadBindings::default(),
+            path: PathBuf::from(DEFAULT_KEY_BINDINGS_FILE),
+        }
+    }
+}
+
+impl KeyBindings {
+    /// The action's first chord
+    pub fn chord(&self, action: &str) -> Option<KeyChord> {
+        self.chords(action).next()
+    }
+
+    pub fn chords<'a>(&'a self, action: &'a str) -> impl Iterator<Item = KeyChord> + 'a {
+        self.actions.iter().filter(move |(name, _)| name == action).map(|(_, chord)| *chord)
+    }
+
+    /// Action names, each once, in display order
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        for (name, _) in &self.actions {
+            if !names.contains(&name.as_str()) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    /// A chord of the action was pressed this frame; unknown actions never are
+    pub fn just_pressed(&self, action: &str, keys: &ButtonInput<KeyCode>) -> bool {
+        self.chords(action).any(|c| c.just_pressed(keys))
+    }
+
+    /// A chord of the action is held
+    pub fn pressed(&self, action: &str, keys: &ButtonInput<KeyCode>) -> bool {
+        self.chords(action).any(|c| c.pressed(keys))
+    }
+
+    /// Chord labels for hints such as "(F2 to rename)"; "-" for unbound actions
+    pub fn label(&self, action: &str) -> String {
+        let labels: Vec<String> = self.chords(action).map(|c| c.label()).collect();
+        if labels.is_empty() { "-".to_string() } else { labels.join("/") }
+    }
+
+    /// Bind an action to one chord in place of its others, adding the action
+    /// if it is new; refused if another action has the chord
+    pub fn bind(&mut self, action: &str, chord: KeyChord) -> Result<(), KeyBindingError> {
+        if let Some(other) = self.conflicts(action, chord).first() {
+            return Err(KeyBindingError::Taken { chord, action: other.to_string() });
+        }
+        self.set(action, vec![chord]);
+        Ok(())
+    }
+
+    /// Replace an action's chords, keeping its place in the list
+    fn set(&mut self, action: &str, chords: Vec<KeyChord>) {
+        let at = self.actions.iter().position(|(name, _)| name == action).unwrap_or(self.actions.len());
+        self.actions.retain(|(name, _)| name != action);
+        let at = at.min(self.actions.len());
+        self.actions.splice(at..at, chords.into_iter().map(|chord| (action.to_string(), chord)));
+    }
+
+    /// Other actions already on a chord
+    pub fn conflicts(&self, action: &str, chord: KeyChord) -> Vec<&str> {
+        self.actions.iter().filter(|(name, c)| name != action && *c == chord).map(|(name, _)| name.as_str()).collect()
+    }
+
+    pub fn to_text(&self) -> String {
+        self.actions.iter().map(|(name, chord)| format!("{} = {}\n", name, chord.label())).collect()
+    }
+
+    /// Apply `action = chord` lines over the current bindings; an action's
+    /// lines replace all its chords. Blank lines and `#` comments are skipped;
+    /// malformed lines, and actions whose chord another action keeps, are
+    /// reported and skipped. The lines are applied together, so a file that
+    /// swaps two chords loads.
+    pub fn merge_text(&mut self, text: &str) {
+        let mut lines: Vec<(String, Vec<KeyChord>)> = Vec::new();
+        for line in text.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+            match line.split_once('=').and_then(|(name, chord)| Some((name.trim(), KeyChord::parse(chord)?))) {
+                Some((name, chord)) => match lines.iter_mut().find(|(n, _)| n == name) {
+                    Some((_, chords)) => chords.push(chord),
+                    None => lines.push((name.to_string(), vec![chord])),
+                },
+                None => warn!("Ignoring key binding line {:?}", line),
+            }
+        }
+        let before = self.clone();
+        for (name, chords) in &lines {
+            self.set(name, chords.clone());
+        }
+        for (name, chords) in &lines {
+            let taken = chords.iter().find_map(|c| Some(KeyBindingError::Taken { chord: *c, action: self.conflicts(name, *c).first()?.to_string() }));
+            if let Some(err) = taken {
+                warn!("Ignoring key bindings of {}: {}", name, err);
+                self.set(name, before.chords(name).collect());
+            }
+        }
+    }
+    /// Load bindings saved earlier; a missing file keeps the defaults
+    pub fn load(&mut self) -> io::Result<()> {
+        match fs::read_to_string(&self.path) {
+            Ok(text) => {
+                self.merge_text(&text);
+                Ok(())
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        fs::write(&self.path, self.to_text())
+    }
+}
+
+/// Systems that act on shortcut keys
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ShortcutKeys;
+
+/// Run condition: no text field (a body name, dimension value or the script
+/// console) has the keyboard
+pub fn keyboard_free(
+    rename: Option<Res<RenameSession>>,
+    dimension: Option<Res<DimensionEditSession>>,
+    console: Option<Res<ScriptConsole>>,
+) -> bool {
+    not_renaming(rename) && not_editing_dimension(dimension) && not_typing_script(console)
+}
+
+/// Runs the `ShortcutKeys` set only while `keyboard_free`. Plugins with
+/// shortcut systems add it unless it already is.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ShortcutKeysPlugin;
+
+impl Plugin for ShortcutKeysPlugin {
+    fn build(&self, app: &mut App) {
+        app.configure_sets(Update, ShortcutKeys.run_if(keyboard_free));
+    }
+}
+
+/// The key bindings panel and the action being rebound
+#[derive(Resource, Debug, Default, Clone, PartialEq)]
+pub struct KeyBindingsSession {
+    pub open: bool,
+    /// Action waiting for its new chord
+    pub capturing: Option<String>,
+}
+
+/// Button of one action in the key bindings panel
+#[cfg(feature = "render")]
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct KeyBindingButton(pub String);
+
+/// Chord shown on a key binding button
+#[cfg(feature = "render")]
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct KeyBindingLabel(pub String);
+
+/// List the actions of the key bindings panel live in
+#[cfg(feature = "render")]
+#[derive(Component, Debug)]
+pub struct KeyBindingList;
+
+/// One action's row in the key bindings panel
+#[cfg(feature = "render")]
+#[derive(Component, Debug)]
+pub struct KeyBindingRow;
+
+/// The bound chord opens and closes the key bindings panel
+pub fn key_bindings_panel_keys(keys: Res<ButtonInput<KeyCode>>, bindings: Res<KeyBindings>, mut session: ResMut<KeyBindingsSession>) {
+    if bindings.just_pressed("key_bindings_panel", &keys) {
+        session.open = !session.open;
+        session.capturing = None;
+    }
+}
+
+/// While an action is being rebound, the next key pressed (with its
+/// modifiers) becomes its chord and no other system sees that key; Escape
+/// cancels. Runs straight after input is read, before any shortcut.
+pub fn capture_key_binding(mut keys: ResMut<ButtonInput<KeyCode>>, mut session: ResMut<KeyBindingsSession>, mut bindings: ResMut<KeyBindings>) {
+    let Some(action) = session.capturing.clone() else { return };
+    if keys.just_pressed(KeyCode::Escape) {
+        keys.clear_just_pressed(KeyCode::Escape);
+        session.capturing = None;
+        return;
+    }
+    let Some(key) = keys.get_just_pressed().copied().find(|k| BINDABLE_KEYS.contains(k)) else { return };
+    keys.clear_just_pressed(key);
+    let chord = KeyChord { key, ctrl: keys.any_pressed(CTRL), shift: keys.any_pressed(SHIFT), alt: keys.any_pressed(ALT) };
+    // A taken chord is refused and the panel waits for another key
+    if let Err(err) = bindings.bind(&action, chord) {
+        warn!("Could not bind {}: {}", action, err);
+        return;
+    }
+    session.capturing = None;
+    journal(format!("bind_key {} {}", action, chord.label()));
+    if let Err(err) = bindings.save() {
+        warn!("Could not save key bindings: {}", err);
+    }
+}
+
+/// Spawn the key bindings panel (centre, hidden until opened)
+#[cfg(feature = "render")]
+pub fn spawn_key_bindings_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Percent(35.0),
+                top: Val::Px(40.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                padding: UiRect::all(Val::Px(6.0)),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(PANEL_COLOR_TRANSLUCENT),
+            UiPanel("keys"),
+        ))
+        .with_children(|panel| {
+            panel.spawn(Text::new("Key bindings (click, then press a key; Esc cancels)"));
+            panel.spawn((Node { flex_direction: FlexDirection::Column, row_gap: Val::Px(2.0), ..default() }, KeyBindingList));
+        });
+}
+
+/// Fill the panel with one row per action, start capturing for clicked
+/// actions and keep the chord labels current
+#[cfg(feature = "render")]
+pub fn key_bindings_panel_system(
+    mut commands: Commands,
+    bindings: Res<KeyBindings>,
+    mut session: ResMut<KeyBindingsSession>,
+    pressed: Query<(&Interaction, &KeyBindingButton), Changed<Interaction>>,
+    mut panels: Query<(&UiPanel, &mut Node)>,
+    (list, old_rows): (Query<Entity, With<KeyBindingList>>, Query<Entity, With<KeyBindingRow>>),
+    (mut labels, mut buttons): (Query<(&KeyBindingLabel, &mut Text)>, Query<(&KeyBindingButton, &mut BackgroundColor)>),
+) {
+    for (_, button) in pressed.iter().filter(|(i, _)| **i == Interaction::Pressed) {
+        session.capturing = Some(button.0.clone());
+    }
+    if session.is_changed() {
+        let display = if session.open { Display::Flex } else { Display::None };
+        for (_, mut node) in panels.iter_mut().filter(|(p, _)| p.0 == "keys") {
+            node.display = display;
+        }
+    }
+    if bindings.is_changed() {
+        if let Ok(list) = list.single() {
+            for entity in old_rows.iter() {
+                commands.entity(entity).despawn();
+            }
+            commands.entity(list).with_children(|list| {
+                for name in bindings.names() {
+                    let row = Node { column_gap: Val::Px(8.0), justify_content: JustifyContent::SpaceBetween, ..default() };
+                    list.spawn((row, KeyBindingRow)).with_children(|row| {
+                        row.spawn(Text::new(name.replace('_', " ")));
+                        row.spawn((
+                            Button,
+                            Node { padding: UiRect::axes(Val::Px(6.0), Val::Px(1.0)), ..default() },
+                            BackgroundColor(BUTTON_IDLE),
+                            KeyBindingButton(name.to_string()),
+                        ))
+                        .with_child((Text::new(bindings.label(name)), KeyBindingLabel(name.to_string())));
+                    });
+                }
+            });
+        }
+    }
+    if session.is_changed() || bindings.is_changed() {
+        for (label, mut text) in labels.iter_mut() {
+            text.0 = if session.capturing.as_ref() == Some(&label.0) { "press a key".to_string() } else { bindings.label(&label.0) };
+        }
+        for (button, mut color) in buttons.iter_mut() {
+            color.0 = if session.capturing.as_ref() == Some(&button.0) { BUTTON_CAPTURING } else { BUTTON_IDLE };
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+
     #[test]
-    fn test_keyboard_new() {
-        let k = Keyboard::new();
-        let _ = k;
+    fn test_chord_text_round_trip() {
+        let chord = KeyChord { key: KeyCode::KeyS, ctrl: true, shift: true, alt: false };
+        assert_eq!(chord.label(), "Ctrl+Shift+S");
+        assert_eq!(KeyChord::parse("ctrl + shift + S"), Some(chord));
+        assert_eq!(KeyChord::parse("ArrowLeft"), Some(KeyChord::key(KeyCode::ArrowLeft)));
+        assert_eq!(KeyChord::parse("Hyper+S"), None);
+        assert_eq!(KeyChord::parse("Nope"), None);
+
+        let mut bindings = KeyBindings::default();
+        bindings.merge_text("# mine\ntoggle_xr = Ctrl+X\nbroken line\n");
+        assert_eq!(bindings.chord("toggle_xr"), Some(KeyChord::ctrl(KeyCode::KeyX)));
+        let mut reloaded = KeyBindings { actions: Vec::new(), ..KeyBindings::default() };
+        reloaded.merge_text(&bindings.to_text());
+        assert_eq!(reloaded.actions, bindings.actions);
+    }
+
+    #[test]
+    fn test_shortcuts_wait_for_text_fields() {
+        #[derive(Resource, Default)]
+        struct Runs(u32);
+        let mut app = App::new();
+        app.init_resource::<Runs>()
+            .init_resource::<RenameSession>()
+            .add_plugins(ShortcutKeysPlugin)
+            .add_systems(Update, (|mut runs: ResMut<Runs>| runs.0 += 1).in_set(ShortcutKeys));
+        app.update();
+        app.world_mut().resource_mut::<RenameSession>().begin(crate::model::body::BodyId(0), "Body");
+        app.update();
+        app.world_mut().insert_resource(RenameSession::default());
+        app.world_mut().insert_resource(ScriptConsole { open: true, ..default() });
+        app.update();
+        assert_eq!(app.world().resource::<Runs>().0, 1);
+    }
+
+    #[test]
+    fn test_chords_are_not_shared() {
+        let mut bindings = KeyBindings::default();
+        for (name, chord) in &bindings.actions {
+            assert_eq!(bindings.conflicts(name, *chord), Vec::<&str>::new(), "{}", name);
+        }
+        let taken = KeyBindingError::Taken { chord: KeyChord::ctrl(KeyCode::KeyI), action: "import".to_string() };
+        assert_eq!(bindings.bind("check_clashes", KeyChord::ctrl(KeyCode::KeyI)), Err(taken));
+        assert_eq!(bindings.bind("check_clashes", KeyChord::ctrl(KeyCode::KeyQ)), Ok(()));
+
+        // A saved file swapping two chords loads; a line taking a kept chord does not
+        bindings.merge_text("toggle_xr = F3\ntoggle_stereo = F1\nrename_body = Ctrl+S\n");
+        assert_eq!(bindings.chord("toggle_xr"), Some(KeyChord::key(KeyCode::F3)));
+        assert_eq!(bindings.chord("toggle_stereo"), Some(KeyChord::key(KeyCode::F1)));
+        assert_eq!(bindings.chord("rename_body"), Some(KeyChord::key(KeyCode::F2)));
+
+        // An action's lines replace all its chords
+        assert_eq!(bindings.label("value_up"), "PageUp/NumpadAdd");
+        bindings.merge_text("value_up = Slash\nvalue_up = Ctrl+Slash\n");
+        assert_eq!(bindings.label("value_up"), "Slash/Ctrl+Slash");
+    }
+
+    #[test]
+    fn test_modifiers_are_exact() {
+        let bindings = KeyBindings::default();
+        let mut keys = ButtonInput::<KeyCode>::default();
+        keys.press(KeyCode::ControlLeft);
+        keys.press(KeyCode::Backquote);
+        // Opening the script console does not also cycle the display mode
+        assert!(bindings.just_pressed("script_console", &keys));
+        assert!(!bindings.just_pressed("display_mode", &keys));
+        assert!(!bindings.just_pressed("body_display_mode", &keys));
+    }
+
+    #[test]
+    fn test_capture_rebinds() {
+        let mut app = App::new();
+        let path = std::env::temp_dir().join(format!("xrcad_keys_{}.txt", std::process::id()));
+        app.insert_resource(KeyBindings { path: path.clone(), ..default() })
+            .insert_resource(KeyBindingsSession { open: true, capturing: Some("rename_body".to_string()) })
+            .init_resource::<ButtonInput<KeyCode>>()
+            .add_systems(Update, capture_key_binding);
+        let mut keys = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        keys.press(KeyCode::ShiftLeft);
+        keys.press(KeyCode::KeyR);
+        app.update();
+
+        let bindings = app.world().resource::<KeyBindings>();
+        assert_eq!(bindings.chord("rename_body"), Some(KeyChord::shift(KeyCode::KeyR)));
+        // The chord went to the panel, not to the shortcuts
+        assert!(!app.world().resource::<ButtonInput<KeyCode>>().just_pressed(KeyCode::KeyR));
+        assert!(app.world().resource::<KeyBindingsSession>().capturing.is_none());
+        assert!(std::fs::read_to_string(&path).unwrap().contains("rename_body = Shift+R"));
+        let _ = std::fs::remove_file(path);
     }
 }
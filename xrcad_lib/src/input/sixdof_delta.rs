@@ -3,6 +3,10 @@
 
 //! Module: input::sixdof_delta
 
+use bevy::math::{Quat, Vec3};
+
+use crate::input::sixdof_pose::SixDofPose;
+
 /// Represents a delta (change) in 6DoF pose.
 pub struct SixDofDelta {
     pub translation: [f32; 3],
@@ -13,6 +17,13 @@ impl SixDofDelta {
     pub fn new(translation: [f32; 3], rotation: [f32; 4]) -> Self {
         Self { translation, rotation }
     }
+
+    /// Pose after this change: moved by `translation`, then turned by `rotation` in place
+    pub fn apply_to(&self, pose: &SixDofPose) -> SixDofPose {
+        let position = pose.translation() + Vec3::from_array(self.translation);
+        let orientation = (Quat::from_array(self.rotation).normalize() * pose.rotation()).normalize();
+        SixDofPose::new(position.to_array(), orientation.to_array())
+    }
 }
 
 #[cfg(test)]
@@ -26,4 +37,13 @@ mod tests {
         assert_eq!(delta.translation, t);
         assert_eq!(delta.rotation, r);
     }
+
+    #[test]
+    fn test_apply_delta_to_pose() {
+        let pose = SixDofPose::new([1.0, 0.0, 0.0], [0.0, 0.0, 0.0, 1.0]);
+        let quarter = Quat::from_rotation_y(std::f32::consts::FRAC_PI_2);
+        let moved = SixDofDelta::new([0.0, 2.0, 0.0], quarter.to_array()).apply_to(&pose);
+        assert_eq!(moved.translation(), Vec3::new(1.0, 2.0, 0.0));
+        assert!(moved.rotation().angle_between(quarter) < 1e-6);
+    }
 }
@@ -3,6 +3,8 @@
 
 //! Module: input::sixdof_pose
 
+use bevy::math::{Quat, Vec3};
+
 /// Represents the absolute pose of a 6DoF device (position + orientation).
 #[derive(Debug, Clone, PartialEq)]
 pub struct SixDofPose {
@@ -15,6 +17,14 @@ impl SixDofPose {
     pub fn new(position: [f32; 3], orientation: [f32; 4]) -> Self {
         Self { position, orientation }
     }
+
+    pub fn translation(&self) -> Vec3 {
+        Vec3::from_array(self.position)
+    }
+
+    pub fn rotation(&self) -> Quat {
+        Quat::from_array(self.orientation).normalize()
+    }
 }
 
 #[cfg(test)]
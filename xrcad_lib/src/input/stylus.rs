@@ -2,22 +2,251 @@
 // Copyright (c) 2025 Adrian Scarlett
 
 //! Module: input::stylus
+//!
+//! Spatial stylus: a tracked 6DoF pen that sketches in the air onto the
+//! active sketch. The device backend keeps each `Stylus` pose current, either
+//! absolutely or by applying `SixDofDelta`s, and sets `button` while the pen
+//! button is held. The tip is projected onto the active sketch's plane. In
+//! polyline mode holding the button traces a stroke, smoothed and thinned out
+//! as it goes, that becomes connected sketch lines on release; in points mode
+//! each press places one sketch point. Ends and points snap to existing sketch
+//! points nearby, or else to the grid increment.
 
-/// Represents a stylus input device.
-pub struct Stylus;
+use bevy::prelude::*;
+use nalgebra::{Point3, Vector2};
+
+use crate::input::sixdof_delta::SixDofDelta;
+use crate::input::sixdof_pose::SixDofPose;
+use crate::interaction::grid_snap::GridSnap;
+use crate::model::brep_model::na_vec3_to_bevy;
+use crate::sketch::sketch::{Sketch, Sketches};
+use crate::telemetry::crash::journal;
+
+const STROKE_COLOR: Color = Color::srgb(1.0, 0.6, 0.2);
+
+/// A tracked stylus (pose in model space)
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct Stylus {
+    pub pose: SixDofPose,
+    /// Pen button held
+    pub button: bool,
+    was_pressed: bool,
+}
 
 impl Stylus {
     pub fn new() -> Self {
-        Stylus
+        Stylus { pose: SixDofPose::new([0.0; 3], [0.0, 0.0, 0.0, 1.0]), button: false, was_pressed: false }
+    }
+
+    /// Move the stylus by a relative motion from the device
+    pub fn apply_delta(&mut self, delta: &SixDofDelta) {
+        self.pose = delta.apply_to(&self.pose);
+    }
+
+    /// Pen tip in model space
+    pub fn tip(&self) -> Point3<f64> {
+        let t = self.pose.translation();
+        Point3::new(t.x as f64, t.y as f64, t.z as f64)
+    }
+
+    /// Button went down since the last frame
+    pub fn pressed(&self) -> bool {
+        self.button && !self.was_pressed
+    }
+
+    /// Button came up since the last frame
+    pub fn released(&self) -> bool {
+        !self.button && self.was_pressed
+    }
+}
+
+impl Default for Stylus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What the stylus button draws
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum StylusMode {
+    /// Hold to trace connected lines
+    #[default]
+    Polyline,
+    /// Press to place a point
+    Points,
+}
+
+/// How the stylus sketches
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct StylusSettings {
+    pub mode: StylusMode,
+    /// Weight of the previous smoothed position (0 follows the tip exactly, towards 1 smooths more)
+    pub smoothing: f64,
+    /// Shortest segment kept while tracing (mm)
+    pub min_spacing: f64,
+    /// Ends closer than this to a sketch point join it (mm)
+    pub snap_radius: f64,
+}
+
+impl Default for StylusSettings {
+    fn default() -> Self {
+        Self { mode: StylusMode::default(), smoothing: 0.6, min_spacing: 2.0, snap_radius: 3.0 }
     }
 }
 
+/// Stroke being traced (coordinates on the active sketch plane)
+#[derive(Resource, Debug, Default, Clone, PartialEq)]
+pub struct StylusStroke {
+    pub points: Vec<Vector2<f64>>,
+    smoothed: Option<Vector2<f64>>,
+}
+
+/// Where a stylus point lands in the sketch: an existing point within
+/// `radius`, or else a new point at the grid-snapped position
+pub fn stylus_snap(sketch: &Sketch, uv: Vector2<f64>, radius: f64, grid: Option<&GridSnap>) -> Result<usize, Vector2<f64>> {
+    let nearest = sketch.points.iter().map(|p| (p.id, (p.position - uv).norm())).filter(|(_, d)| *d <= radius).min_by(|a, b| a.1.total_cmp(&b.1));
+    match (nearest, grid) {
+        (Some((id, _)), _) => Ok(id),
+        (None, Some(grid)) if grid.enabled && grid.increment > 0.0 => Err(uv.map(|c| (c / grid.increment).round() * grid.increment)),
+        (None, _) => Err(uv),
+    }
+}
+
+fn snapped_point(sketch: &mut Sketch, uv: Vector2<f64>, settings: &StylusSettings, grid: Option<&GridSnap>) -> usize {
+    stylus_snap(sketch, uv, settings.snap_radius, grid).unwrap_or_else(|uv| sketch.add_point(uv))
+}
+
+/// Trace strokes and place points on the active sketch with the stylus
+pub fn stylus_draw(
+    mut styluses: Query<&mut Stylus>,
+    settings: Res<StylusSettings>,
+    grid: Option<Res<GridSnap>>,
+    mut sketches: ResMut<Sketches>,
+    mut stroke: ResMut<StylusStroke>,
+) {
+    let grid = grid.as_deref();
+    for mut stylus in styluses.iter_mut() {
+        let (pressed, released) = (stylus.pressed(), stylus.released());
+        stylus.was_pressed = stylus.button;
+        if !pressed && !released && !stylus.button {
+            continue;
+        }
+        let Some(sketch) = sketches.active_mut() else {
+            if pressed {
+                warn!("stylus: no active sketch to draw on");
+            }
+            stroke.points.clear();
+            continue;
+        };
+        let uv = sketch.plane.project_2d(&stylus.tip());
+        match settings.mode {
+            StylusMode::Points => {
+                if pressed {
+                    let id = snapped_point(sketch, uv, &settings, grid);
+                    journal(format!("stylus_point {} {}", sketch.name, id));
+                }
+            }
+            StylusMode::Polyline if stylus.button => {
+                let smoothed = stroke.smoothed.map_or(uv, |s| s * settings.smoothing + uv * (1.0 - settings.smoothing));
+                stroke.smoothed = Some(smoothed);
+                if stroke.points.last().is_none_or(|last| (smoothed - last).norm() >= settings.min_spacing) {
+                    stroke.points.push(smoothed);
+                }
+            }
+            StylusMode::Polyline => {
+                let points = std::mem::take(&mut stroke.points);
+                stroke.smoothed = None;
+                if points.len() < 2 {
+                    continue;
+                }
+                // Only the ends snap, and only to what was there before the stroke;
+                // the traced shape in between stays as drawn
+                let (first, last) = (points[0], points[points.len() - 1]);
+                let start = snapped_point(sketch, first, &settings, grid);
+                let end = snapped_point(sketch, last, &settings, grid);
+                let mut ids = vec![start];
+                ids.extend(points[1..points.len() - 1].iter().map(|uv| sketch.add_point(*uv)));
+                ids.push(end);
+                ids.dedup();
+                for pair in ids.windows(2) {
+                    sketch.add_line(pair[0], pair[1]);
+                }
+                journal(format!("stylus_polyline {} {}", sketch.name, ids.len().saturating_sub(1)));
+            }
+        }
+    }
+}
+
+/// Draw the stroke being traced on the active sketch plane
+pub fn render_stylus_stroke(mut gizmos: Gizmos, stroke: Res<StylusStroke>, sketches: Res<Sketches>) {
+    let Some(sketch) = sketches.active().filter(|_| stroke.points.len() >= 2) else { return };
+    let points = stroke.points.iter().map(|uv| na_vec3_to_bevy(&sketch.plane.point_at_2d(uv).coords));
+    gizmos.linestrip(points, STROKE_COLOR);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model::brep::topology::plane::Plane;
+    use crate::sketch::sketch::SketchEntity;
+
+    fn app(mode: StylusMode) -> (App, Entity) {
+        let mut app = App::new();
+        let mut sketches = Sketches::default();
+        sketches.active = Some(sketches.add(Sketch::new("Sketch.001", Plane::xy())));
+        app.insert_resource(sketches)
+            .insert_resource(StylusSettings { mode, smoothing: 0.0, ..default() })
+            .insert_resource(GridSnap { enabled: true, increment: 5.0 })
+            .init_resource::<StylusStroke>()
+            .add_systems(Update, stylus_draw);
+        let stylus = app.world_mut().spawn(Stylus::new()).id();
+        (app, stylus)
+    }
+
+    fn move_to(app: &mut App, stylus: Entity, at: [f32; 3], button: bool) {
+        let mut s = app.world_mut().get_mut::<Stylus>(stylus).unwrap();
+        s.pose.position = at;
+        s.button = button;
+        app.update();
+    }
+
     #[test]
-    fn test_stylus_new() {
-        let s = Stylus::new();
-        let _ = s;
+    fn test_points_snap_to_grid_and_existing_points() {
+        let (mut app, stylus) = app(StylusMode::Points);
+        let plane = Plane::xy();
+        // Above the plane: the point is projected down onto it, then onto the grid
+        move_to(&mut app, stylus, [11.0, 19.0, 40.0], true);
+        move_to(&mut app, stylus, [11.0, 19.0, 40.0], false);
+        let expected = plane.project_2d(&Point3::new(11.0, 19.0, 0.0)).map(|c| (c / 5.0).round() * 5.0);
+        // Within the snap radius of that point: no second point
+        let near = plane.point_at_2d(&(expected + Vector2::new(1.0, 0.0)));
+        move_to(&mut app, stylus, [near.x as f32, near.y as f32, near.z as f32], true);
+        let sketches = app.world().resource::<Sketches>();
+        let points = &sketches.active().unwrap().points;
+        assert_eq!(points.len(), 1);
+        assert!((points[0].position - expected).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_polyline_stroke() {
+        let (mut app, stylus) = app(StylusMode::Polyline);
+        let delta = SixDofDelta::new([1.0, 0.0, 0.0], [0.0, 0.0, 0.0, 1.0]);
+        move_to(&mut app, stylus, [0.0, 0.0, 0.0], true);
+        // Sub-spacing jitter is dropped; the stroke runs along +X to 21
+        for _ in 0..21 {
+            app.world_mut().get_mut::<Stylus>(stylus).unwrap().apply_delta(&delta);
+            app.update();
+        }
+        assert_eq!(app.world().resource::<StylusStroke>().points.len(), 11);
+        move_to(&mut app, stylus, [21.0, 0.0, 0.0], false);
+
+        assert!(app.world().resource::<StylusStroke>().points.is_empty());
+        let sketches = app.world().resource::<Sketches>();
+        let sketch = sketches.active().unwrap();
+        assert_eq!(sketch.points.len(), 11);
+        assert_eq!(sketch.entities.iter().filter(|e| matches!(e, SketchEntity::Line { .. })).count(), 10);
+        // The far end lands on the grid
+        let ends: Vec<f64> = sketch.points.iter().map(|p| p.position.norm()).collect();
+        assert!(ends.iter().any(|d| (d - 20.0).abs() < 1e-9), "{ends:?}");
     }
 }
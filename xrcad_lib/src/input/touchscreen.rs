@@ -2,22 +2,166 @@
 // Copyright (c) 2025 Adrian Scarlett
 
 //! Module: input::touchscreen
+//!
+//! Touch navigation of the desktop camera, for tablets and Android builds:
+//! one finger orbits about the pivot, two fingers pan with their midpoint
+//! and pinch to zoom, and a finger held still for a moment selects the body
+//! under it. The gestures reach the camera as the axes of `ActionState`, so
+//! they drive it at the mouse's rates and zooming eases in the same way.
 
-/// Represents a touchscreen input device.
-pub struct Touchscreen;
+use bevy::input::touch::Touches;
+use bevy::prelude::*;
+use nalgebra::Vector3;
 
-impl Touchscreen {
-    pub fn new() -> Self {
-        Touchscreen
+use crate::interaction::picking::{is_body_pickable, pick_where, PickTarget};
+use crate::interaction::selection::{Selection, SelectionFilter, SelectionItem};
+use crate::model::brep_model::{bevy_vec3_to_na, na_vec3_to_bevy, BrepModel};
+use crate::model::groups::BodyGroups;
+use crate::model::layers::LayerManager;
+use crate::model::properties::BodyPropertiesCollection;
+use crate::render::gizmo_scale::GizmoScale;
+use crate::telemetry::crash::journal;
+
+/// Fingers are wider than a cursor: targets this close on screen are hit (pixels)
+const TOUCH_PICK_PIXELS: f32 = 24.0;
+
+/// Touch gesture tuning
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct TouchSettings {
+    /// Hold this long without moving to select (seconds)
+    pub long_press: f32,
+    /// A held finger may drift this far and still count as still (pixels)
+    pub slop: f32,
+}
+
+impl Default for TouchSettings {
+    fn default() -> Self {
+        Self { long_press: 0.6, slop: 12.0 }
+    }
+}
+
+/// The finger being held for a long press
+#[derive(Resource, Debug, Default, Clone, PartialEq)]
+pub struct TouchState {
+    /// Finger id and when it went down (seconds since startup)
+    hold: Option<(u64, f32)>,
+    /// The long press of the held finger already selected
+    fired: bool,
+}
+
+/// What the fingers on the screen are doing this frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TouchGesture {
+    /// One finger moved by this many pixels
+    Orbit(Vec2),
+    /// Two fingers: their midpoint moved by `pan` pixels and their spread
+    /// changed by the factor `pinch` (above 1 when they move apart)
+    PanPinch { pan: Vec2, pinch: f32 },
+}
+
+/// Gesture of the fingers, each given as (position, position last frame);
+/// none for no fingers or more than two
+pub fn touch_gesture(fingers: &[(Vec2, Vec2)]) -> Option<TouchGesture> {
+    match *fingers {
+        [(now, before)] => Some(TouchGesture::Orbit(now - before)),
+        [(a, a_before), (b, b_before)] => {
+            let pan = (a + b) / 2.0 - (a_before + b_before) / 2.0;
+            let spread_before = a_before.distance(b_before);
+            let pinch = if spread_before > f32::EPSILON { a.distance(b) / spread_before } else { 1.0 };
+            Some(TouchGesture::PanPinch { pan, pinch })
+        }
+        _ => None,
+    }
+}
+
+/// A finger held still selects the body under it; on empty space it clears the selection
+pub fn touch_long_press_select(
+    touches: Res<Touches>,
+    (time, settings): (Res<Time>, Res<TouchSettings>),
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    (model, scale): (Res<BrepModel>, Option<Res<GizmoScale>>),
+    (properties, groups, layers): (Option<Res<BodyPropertiesCollection>>, Option<Res<BodyGroups>>, Option<Res<LayerManager>>),
+    mut state: ResMut<TouchState>,
+    mut selection: ResMut<Selection>,
+) {
+    let now = time.elapsed_secs();
+    let mut fingers = touches.iter();
+    let held = match (fingers.next(), fingers.next()) {
+        (Some(touch), None) if touch.distance().length() <= settings.slop => Some(touch),
+        _ => None,
+    };
+    let Some(touch) = held else {
+        state.hold = None;
+        state.fired = false;
+        return;
+    };
+    match state.hold {
+        Some((id, _)) if id == touch.id() => {}
+        _ => {
+            state.hold = Some((touch.id(), now));
+            state.fired = false;
+        }
+    }
+    if state.fired || !state.hold.is_some_and(|(_, since)| now - since >= settings.long_press) {
+        return;
+    }
+    state.fired = true;
+    let Some(ray) = cameras.iter().find_map(|(camera, transform)| camera.viewport_to_world(transform, touch.position()).ok()) else { return };
+    let scale = scale.as_deref().copied().unwrap_or_default();
+    let (origin, dir) = (bevy_vec3_to_na(&ray.origin), bevy_vec3_to_na(&ray.direction.as_vec3()));
+    let radius = |_: PickTarget, p: &Vector3<f64>| scale.world_size(na_vec3_to_bevy(p), TOUCH_PICK_PIXELS) as f64;
+    let (no_groups, no_layers, no_properties) = (BodyGroups::default(), LayerManager::default(), BodyPropertiesCollection::default());
+    let groups = groups.as_deref().unwrap_or(&no_groups);
+    let layers = layers.as_deref().unwrap_or(&no_layers);
+    let properties = properties.as_deref().unwrap_or(&no_properties);
+    let hit = pick_where(&model, &origin, &dir, SelectionFilter::Bodies, radius, |body| is_body_pickable(body, groups, layers, properties));
+    selection.set_filter(SelectionFilter::Bodies);
+    selection.clear();
+    if let Some(body) = hit.and_then(|h| h.body) {
+        selection.add(SelectionItem::Body(body));
+        journal(format!("touch_select body {}", body.0));
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::input::actions::{begin_actions, finish_actions, touch_actions, ActionState};
+    use crate::viewport::camera_control::{camera_control_system, CustomCameraController};
+    use bevy::input::touch::{touch_screen_input_system, TouchInput, TouchPhase};
+
     #[test]
-    fn test_touchscreen_new() {
-        let t = Touchscreen::new();
-        let _ = t;
+    fn test_gestures() {
+        assert_eq!(touch_gesture(&[]), None);
+        assert_eq!(touch_gesture(&[(Vec2::new(5.0, 2.0), Vec2::ZERO)]), Some(TouchGesture::Orbit(Vec2::new(5.0, 2.0))));
+        // Fingers moving apart from 100 to 200 pixels, both shifted 10 pixels right
+        let fingers = [(Vec2::new(10.0, 0.0), Vec2::new(50.0, 0.0)), (Vec2::new(210.0, 0.0), Vec2::new(150.0, 0.0))];
+        assert_eq!(touch_gesture(&fingers), Some(TouchGesture::PanPinch { pan: Vec2::new(10.0, 0.0), pinch: 2.0 }));
+        assert_eq!(touch_gesture(&[fingers[0], fingers[1], fingers[0]]), None);
+    }
+
+    #[test]
+    fn test_pinch_zooms() {
+        let mut app = App::new();
+        app.init_resource::<Touches>()
+            .init_resource::<ActionState>()
+            .init_resource::<Time>()
+            .add_event::<TouchInput>()
+            .add_systems(Update, (touch_screen_input_system, begin_actions, touch_actions, finish_actions, camera_control_system).chain());
+        let start = Transform::from_xyz(0.0, 0.0, 100.0).looking_at(Vec3::ZERO, Vec3::Y);
+        let camera = app.world_mut().spawn((start, CustomCameraController::default(), Camera::default(), GlobalTransform::from(start))).id();
+        let window = Entity::PLACEHOLDER;
+        let touch = |id, phase, x: f32| TouchInput { phase, position: Vec2::new(x, 300.0), window, force: None, id };
+        app.world_mut().send_event(touch(0, TouchPhase::Started, 100.0));
+        app.world_mut().send_event(touch(1, TouchPhase::Started, 200.0));
+        app.update();
+        app.world_mut().send_event(touch(0, TouchPhase::Moved, 50.0));
+        app.world_mut().send_event(touch(1, TouchPhase::Moved, 250.0));
+        app.update();
+
+        // Spread doubled about a fixed midpoint: a pure zoom in by one notch, eased in over the next frames
+        let controller = app.world().get::<CustomCameraController>(camera).unwrap();
+        assert!((controller.zoom_pending - 0.15).abs() < 1e-5, "{}", controller.zoom_pending);
+        assert_eq!(controller.pivot, Vec3::ZERO);
     }
 }
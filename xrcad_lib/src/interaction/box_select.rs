@@ -0,0 +1,168 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: interaction::box_select
+//!
+//! Dragging with the right mouse button draws a selection rectangle. On
+//! release, every element the selection filter accepts that lies wholly inside
+//! the rectangle is selected, hidden or not: vertices by position, and edges,
+//! faces and bodies when all their vertices are inside. Bodies on hidden or
+//! locked layers are skipped. Ctrl or Shift adds to the selection instead of
+//! replacing it.
+
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use nalgebra::Vector3;
+
+use crate::color::WHITE;
+use crate::interaction::selection::{Selection, SelectionFilter, SelectionItem};
+use crate::model::brep_model::{na_vec3_to_bevy, BrepModel};
+use crate::model::layers::{item_body, LayerManager};
+use crate::model::properties::BodyPropertiesCollection;
+
+/// Shortest drag (pixels) treated as a box rather than a click
+pub const MIN_BOX_PIXELS: f32 = 4.0;
+/// Distance in front of the camera the rectangle is drawn at
+const OVERLAY_DEPTH: f32 = 1.0;
+
+/// Vertex ids of a face, across all its loops
+fn face_vertex_ids(model: &BrepModel, face_id: usize) -> Vec<usize> {
+    model.face(face_id).map_or(Vec::new(), |f| model.face_loops(f).iter().flat_map(|l| model.loop_vertex_ids(l)).collect())
+}
+
+/// Elements accepted by `filter` whose vertices all project inside the screen
+/// rectangle `min`..`max`. `project` maps model positions to screen positions.
+pub fn items_in_box(
+    model: &BrepModel,
+    filter: SelectionFilter,
+    min: Vec2,
+    max: Vec2,
+    project: impl Fn(&Vector3<f64>) -> Option<Vec2>,
+) -> Vec<SelectionItem> {
+    let inside = |id: &usize| {
+        model.vertex_position(*id).and_then(|p| project(&p)).is_some_and(|s| s.cmpge(min).all() && s.cmple(max).all())
+    };
+    let mut items = Vec::new();
+    if filter == SelectionFilter::Bodies {
+        for (id, shell) in model.bodies() {
+            if shell.iter().all(|f| face_vertex_ids(model, *f).iter().all(inside)) {
+                items.push(SelectionItem::Body(id));
+            }
+        }
+        return items;
+    }
+    for v in &model.vertices {
+        let item = SelectionItem::Vertex(v.id);
+        if filter.accepts(&item) && inside(&v.id) {
+            items.push(item);
+        }
+    }
+    for e in &model.edges {
+        let item = SelectionItem::Edge(e.id);
+        if filter.accepts(&item) && inside(&e.vertices.0) && inside(&e.vertices.1) {
+            items.push(item);
+        }
+    }
+    for f in &model.faces {
+        let item = SelectionItem::Face(f.id);
+        if filter.accepts(&item) && face_vertex_ids(model, f.id).iter().all(inside) {
+            items.push(item);
+        }
+    }
+    items
+}
+
+/// Rectangle being dragged, in window coordinates
+#[derive(Resource, Debug, Default, Clone, PartialEq)]
+pub struct BoxSelect {
+    pub start: Option<Vec2>,
+    pub current: Option<Vec2>,
+}
+
+impl BoxSelect {
+    /// Corners (min, max) once the drag is long enough to count as a box
+    pub fn rect(&self) -> Option<(Vec2, Vec2)> {
+        let (a, b) = (self.start?, self.current?);
+        (a.distance(b) >= MIN_BOX_PIXELS).then_some((a.min(b), a.max(b)))
+    }
+}
+
+/// Track right-button drags and select what the finished box encloses
+pub fn box_select(
+    mouse: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    (model, properties, layers): (Res<BrepModel>, Option<Res<BodyPropertiesCollection>>, Option<Res<LayerManager>>),
+    mut state: ResMut<BoxSelect>,
+    mut selection: ResMut<Selection>,
+) {
+    let cursor = windows.single().ok().and_then(|w| w.cursor_position());
+    if mouse.just_pressed(MouseButton::Right) {
+        state.start = cursor;
+    }
+    if mouse.pressed(MouseButton::Right) && cursor.is_some() {
+        state.current = cursor;
+    }
+    if !mouse.just_released(MouseButton::Right) {
+        return;
+    }
+    let rect = state.rect();
+    *state = BoxSelect::default();
+    let (Some((min, max)), Ok((camera, transform))) = (rect, cameras.single()) else { return };
+    let project = |p: &Vector3<f64>| camera.world_to_viewport(transform, na_vec3_to_bevy(p)).ok();
+    let mut items = items_in_box(&model, selection.filter, min, max, project);
+    // Nothing on a hidden or locked layer is selected
+    if let (Some(properties), Some(layers)) = (properties, layers.filter(|l| !l.is_empty())) {
+        let bodies = model.vertex_bodies();
+        items.retain(|item| item_body(&model, &bodies, item).is_none_or(|b| layers.is_body_selectable(b, &properties)));
+    }
+    if !keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight, KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        selection.clear();
+    }
+    for item in items {
+        selection.add(item);
+    }
+}
+
+/// Outline the rectangle being dragged just in front of the camera
+pub fn render_box_select(mut gizmos: Gizmos, state: Res<BoxSelect>, cameras: Query<(&Camera, &GlobalTransform)>) {
+    let (Some((min, max)), Ok((camera, transform))) = (state.rect(), cameras.single()) else { return };
+    let corners: Vec<Vec3> = [min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y), min]
+        .iter()
+        .filter_map(|c| camera.viewport_to_world(transform, *c).ok())
+        .map(|ray| ray.origin + ray.direction * OVERLAY_DEPTH)
+        .collect();
+    if corners.len() == 5 {
+        gizmos.linestrip(corners, WHITE);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::body::BodyId;
+    use crate::model::brep::primitives::cube;
+
+    /// Top view: x and y straight to the screen
+    fn top_view(p: &Vector3<f64>) -> Option<Vec2> {
+        Some(Vec2::new(p.x as f32, p.y as f32))
+    }
+
+    #[test]
+    fn test_items_in_box() {
+        let model = cube(10.0);
+        // The right half of the cube from above: 4 vertices, the 4 edges between them, the +X face
+        let (min, max) = (Vec2::new(0.0, -6.0), Vec2::new(6.0, 6.0));
+        let items = items_in_box(&model, SelectionFilter::Any, min, max, top_view);
+        let count = |f: fn(&SelectionItem) -> bool| items.iter().filter(|i| f(i)).count();
+        assert_eq!(count(|i| matches!(i, SelectionItem::Vertex(_))), 4);
+        assert_eq!(count(|i| matches!(i, SelectionItem::Edge(_))), 4);
+        assert_eq!(count(|i| matches!(i, SelectionItem::Face(_))), 1);
+
+        assert!(items_in_box(&model, SelectionFilter::Bodies, min, max, top_view).is_empty());
+        let all = items_in_box(&model, SelectionFilter::Bodies, Vec2::splat(-6.0), Vec2::splat(6.0), top_view);
+        assert_eq!(all, vec![SelectionItem::Body(BodyId(0))]);
+        assert_eq!(items_in_box(&model, SelectionFilter::Edges, Vec2::splat(-6.0), Vec2::splat(6.0), top_view).len(), 12);
+    }
+}
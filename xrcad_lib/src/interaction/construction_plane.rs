@@ -0,0 +1,269 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: interaction::construction_plane
+//!
+//! Construction planes added to the workspace as plane helpers. A plane can lie
+//! on a planar face, be offset from a face or another helper plane, or sit
+//! midway between two parallel faces. The helpers panel buttons read their
+//! inputs from the selection. Each new plane gets the next free `Plane.NNN` id.
+
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+use nalgebra::Point3;
+use std::fmt;
+
+use crate::interaction::selection::Selection;
+use crate::interaction::state::UiPanel;
+use crate::model::brep::topology::plane::Plane;
+use crate::model::brep_model::BrepModel;
+use crate::render::ui_style::{BUTTON_IDLE, PANEL_COLOR};
+use crate::telemetry::crash::journal;
+use crate::telemetry::usage::UsageStats;
+use crate::workspace::workspace::{HelperKind, Workspace};
+
+/// Offset used by the panel's offset button (mm)
+pub const DEFAULT_PLANE_OFFSET: f64 = 10.0;
+
+/// A plane a construction plane is derived from
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlaneReference {
+    /// Supporting plane of a planar face
+    Face(usize),
+    /// Workspace helper plane, by id
+    Helper(String),
+}
+
+/// Why a construction plane could not be made
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstructionPlaneError {
+    UnknownFace(usize),
+    NonPlanarFace(usize),
+    UnknownPlane(String),
+    NotParallel(usize, usize),
+}
+
+impl fmt::Display for ConstructionPlaneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstructionPlaneError::UnknownFace(id) => write!(f, "no face with id {}", id),
+            ConstructionPlaneError::NonPlanarFace(id) => write!(f, "face {} is not planar", id),
+            ConstructionPlaneError::UnknownPlane(id) => write!(f, "no helper plane with id {}", id),
+            ConstructionPlaneError::NotParallel(a, b) => write!(f, "faces {} and {} are not parallel", a, b),
+        }
+    }
+}
+
+impl std::error::Error for ConstructionPlaneError {}
+
+/// Supporting plane of a face, centered on its outer loop; fails unless every
+/// vertex of the face lies on it
+pub fn planar_face_plane(model: &BrepModel, face_id: usize) -> Result<Plane, ConstructionPlaneError> {
+    let face = model.face(face_id).ok_or(ConstructionPlaneError::UnknownFace(face_id))?;
+    let plane = model.face_plane(face).ok_or(ConstructionPlaneError::NonPlanarFace(face_id))?;
+    let planar = model
+        .face_loops(face)
+        .into_iter()
+        .flat_map(|l| model.loop_positions(l))
+        .all(|p| plane.contains_point(&Point3::from(p), &model.tolerance));
+    if !planar {
+        return Err(ConstructionPlaneError::NonPlanarFace(face_id));
+    }
+    Ok(plane)
+}
+
+/// The plane a reference names
+pub fn reference_plane(model: &BrepModel, workspace: &Workspace, reference: &PlaneReference) -> Result<Plane, ConstructionPlaneError> {
+    match reference {
+        PlaneReference::Face(id) => planar_face_plane(model, *id),
+        PlaneReference::Helper(id) => workspace.plane(id).cloned().ok_or_else(|| ConstructionPlaneError::UnknownPlane(id.clone())),
+    }
+}
+
+/// Plane midway between two parallel planar faces, facing like the first
+pub fn mid_plane(model: &BrepModel, a: usize, b: usize) -> Result<Plane, ConstructionPlaneError> {
+    let (first, second) = (planar_face_plane(model, a)?, planar_face_plane(model, b)?);
+    if !model.tolerance.parallel(&first.normal, &second.normal) {
+        return Err(ConstructionPlaneError::NotParallel(a, b));
+    }
+    // Signed distance from the first face to any point of the second
+    let on_second = Point3::origin() - second.normal * second.d / second.normal.norm_squared();
+    let gap = first.distance(&on_second) / first.normal.norm();
+    Ok(first.offset(gap / 2.0))
+}
+
+/// Request to add a construction plane on a planar face
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct PlaneOnFace {
+    pub face: usize,
+}
+
+/// Request to add a construction plane `distance` along the normal of another plane
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct OffsetPlane {
+    pub from: PlaneReference,
+    pub distance: f64,
+}
+
+/// Request to add a construction plane midway between two parallel faces
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct MidPlane {
+    pub a: usize,
+    pub b: usize,
+}
+
+/// Add the requested construction planes to the workspace
+pub fn apply_construction_plane_requests(
+    mut on_face: EventReader<PlaneOnFace>,
+    mut offsets: EventReader<OffsetPlane>,
+    mut mid_planes: EventReader<MidPlane>,
+    model: Res<BrepModel>,
+    mut workspace: ResMut<Workspace>,
+    mut usage: Option<ResMut<UsageStats>>,
+) {
+    let requests = on_face
+        .read()
+        .map(|ev| ("plane_on_face", format!("face {}", ev.face), planar_face_plane(&model, ev.face)))
+        .chain(offsets.read().map(|ev| {
+            let plane = reference_plane(&model, &workspace, &ev.from).map(|p| p.offset(ev.distance));
+            ("offset_plane", format!("{:?} by {}", ev.from, ev.distance), plane)
+        }))
+        .chain(mid_planes.read().map(|ev| ("mid_plane", format!("faces {} {}", ev.a, ev.b), mid_plane(&model, ev.a, ev.b))))
+        .collect::<Vec<_>>();
+    for (command, source, plane) in requests {
+        let start = Instant::now();
+        match plane {
+            Ok(plane) => {
+                let id = workspace.next_helper_id("Plane");
+                journal(format!("{} {} from {}", command, id, source));
+                workspace.add_helper(id, HelperKind::Plane(plane));
+            }
+            Err(err) => warn!("Could not create construction plane: {}", err),
+        }
+        if let Some(usage) = usage.as_mut() {
+            usage.record(command, start.elapsed());
+        }
+    }
+}
+
+/// Construction plane buttons of the helpers panel
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstructionPlaneButton {
+    OnFace,
+    Offset,
+    MidPlane,
+}
+
+/// Helpers panel (left, below the controls) with the construction plane buttons
+pub fn spawn_helpers_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(8.0),
+                top: Val::Percent(40.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                padding: UiRect::all(Val::Px(6.0)),
+                ..default()
+            },
+            BackgroundColor(PANEL_COLOR),
+            UiPanel("helpers"),
+        ))
+        .with_children(|panel| {
+            panel.spawn(Text::new("Helpers"));
+            let button = |action| (Button, Node { padding: UiRect::axes(Val::Px(4.0), Val::Px(1.0)), ..default() }, BackgroundColor(BUTTON_IDLE), action);
+            panel.spawn(button(ConstructionPlaneButton::OnFace)).with_child(Text::new("+ Plane on face"));
+            panel
+                .spawn(button(ConstructionPlaneButton::Offset))
+                .with_child(Text::new(format!("+ Offset plane ({} mm)", DEFAULT_PLANE_OFFSET)));
+            panel.spawn(button(ConstructionPlaneButton::MidPlane)).with_child(Text::new("+ Mid-plane of two faces"));
+        });
+}
+
+/// Send construction plane requests for clicked buttons. Offsets start from
+/// the selected face, or else from the newest helper plane.
+pub fn construction_plane_button_system(
+    pressed: Query<(&Interaction, &ConstructionPlaneButton), Changed<Interaction>>,
+    selection: Res<Selection>,
+    workspace: Res<Workspace>,
+    (mut on_face, mut offsets, mut mid_planes): (EventWriter<PlaneOnFace>, EventWriter<OffsetPlane>, EventWriter<MidPlane>),
+) {
+    for (interaction, button) in pressed.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let faces = selection.faces();
+        match (button, faces.as_slice()) {
+            (ConstructionPlaneButton::OnFace, [face]) => {
+                on_face.write(PlaneOnFace { face: *face });
+            }
+            (ConstructionPlaneButton::MidPlane, [a, b]) => {
+                mid_planes.write(MidPlane { a: *a, b: *b });
+            }
+            (ConstructionPlaneButton::Offset, [face]) => {
+                offsets.write(OffsetPlane { from: PlaneReference::Face(*face), distance: DEFAULT_PLANE_OFFSET });
+            }
+            (ConstructionPlaneButton::Offset, []) => {
+                let newest = workspace.helpers.iter().rev().find(|h| matches!(h.kind, HelperKind::Plane(_)));
+                match newest {
+                    Some(helper) => {
+                        offsets.write(OffsetPlane { from: PlaneReference::Helper(helper.id.clone()), distance: DEFAULT_PLANE_OFFSET });
+                    }
+                    None => info!("Select a face, or add a plane, to offset from"),
+                }
+            }
+            (ConstructionPlaneButton::OnFace | ConstructionPlaneButton::Offset, _) => info!("Select one face for the construction plane"),
+            (ConstructionPlaneButton::MidPlane, _) => info!("Select two parallel faces for a mid-plane"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::cube;
+    use nalgebra::Vector3;
+
+    fn face_facing(model: &BrepModel, direction: Vector3<f64>) -> usize {
+        model.faces.iter().find(|f| model.face_normal(f).is_some_and(|n| (n - direction).norm() < 1e-9)).unwrap().id
+    }
+
+    #[test]
+    fn test_planes_from_faces() {
+        let model = cube(2.0);
+        let (top, bottom, side) = (face_facing(&model, Vector3::z()), face_facing(&model, -Vector3::z()), face_facing(&model, Vector3::x()));
+        let plane = planar_face_plane(&model, top).unwrap();
+        assert!(plane.distance(&Point3::new(0.0, 0.0, 1.0)).abs() < 1e-9);
+
+        let mid = mid_plane(&model, top, bottom).unwrap();
+        assert!((mid.normal - Vector3::z()).norm() < 1e-9);
+        assert!(mid.contains_point(&Point3::origin(), &model.tolerance));
+        assert_eq!(mid_plane(&model, top, side), Err(ConstructionPlaneError::NotParallel(top, side)));
+        assert_eq!(planar_face_plane(&model, 99), Err(ConstructionPlaneError::UnknownFace(99)));
+    }
+
+    #[test]
+    fn test_requests_register_generated_helpers() {
+        let model = cube(2.0);
+        let top = face_facing(&model, Vector3::z());
+        let mut app = App::new();
+        app.insert_resource(model)
+            .insert_resource(Workspace::new())
+            .add_event::<PlaneOnFace>()
+            .add_event::<OffsetPlane>()
+            .add_event::<MidPlane>()
+            .add_systems(Update, apply_construction_plane_requests);
+        app.world_mut().send_event(PlaneOnFace { face: top });
+        app.update();
+        app.world_mut().send_event(OffsetPlane { from: PlaneReference::Helper("Plane.001".into()), distance: 5.0 });
+        app.world_mut().send_event(OffsetPlane { from: PlaneReference::Helper("missing".into()), distance: 5.0 });
+        app.update();
+
+        let workspace = app.world().resource::<Workspace>();
+        let ids: Vec<&str> = workspace.helpers.iter().map(|h| h.id.as_str()).collect();
+        assert_eq!(ids, ["Plane.001", "Plane.002"]);
+        let offset = workspace.plane("Plane.002").unwrap();
+        assert!(offset.contains_point(&Point3::new(0.0, 0.0, 6.0), &Default::default()));
+    }
+}
@@ -0,0 +1,218 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: interaction::dimension_edit
+//!
+//! Editing driving dimensions of the active sketch: Tab (the next_dimension
+//! binding) cycles the selected dimension, confirm (Enter) starts editing its value, typed digits edit it, Enter commits
+//! (re-solving the sketch) and Escape cancels. Lengths are shown in the
+//! document unit and may be typed with a unit ("2 in"); angles are in degrees.
+
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::ButtonState;
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+
+use crate::input::keyboard::KeyBindings;
+use crate::model::units::{Length, UnitSystem};
+use crate::sketch::dimension::DimensionKind;
+use crate::sketch::sketch::Sketches;
+use crate::sketch::solver::set_dimension_value;
+use crate::telemetry::crash::journal;
+use crate::telemetry::usage::UsageStats;
+
+/// Request to change the driving value of a sketch dimension (model units, radians for angles)
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct SetDimensionValue {
+    pub sketch: usize,
+    pub dimension: usize,
+    pub value: f64,
+}
+
+/// Selected dimension of the active sketch and the in-progress value edit, if any
+#[derive(Resource, Debug, Default, Clone)]
+pub struct DimensionEditSession {
+    pub selected: Option<usize>,
+    pub editing: bool,
+    pub buffer: String,
+}
+
+impl DimensionEditSession {
+    pub fn is_active(&self) -> bool {
+        self.editing
+    }
+
+    /// Select the dimension after the current one (wrapping)
+    pub fn select_next(&mut self, ids: &[usize]) {
+        self.selected = match self.selected.and_then(|s| ids.iter().position(|id| *id == s)) {
+            Some(i) => ids.get((i + 1) % ids.len()).copied(),
+            None => ids.first().copied(),
+        };
+    }
+
+    pub fn begin(&mut self, display_value: f64) {
+        if self.selected.is_some() {
+            self.editing = true;
+            self.buffer = format!("{}", display_value);
+        }
+    }
+
+    pub fn cancel(&mut self) {
+        self.editing = false;
+        self.buffer.clear();
+    }
+
+    /// Finish editing; returns the typed text
+    pub fn commit(&mut self) -> String {
+        self.editing = false;
+        std::mem::take(&mut self.buffer)
+    }
+}
+
+/// Run condition: true unless a dimension value is capturing the keyboard
+pub fn not_editing_dimension(session: Option<Res<DimensionEditSession>>) -> bool {
+    !session.is_some_and(|s| s.is_active())
+}
+
+/// Value as shown to the user: degrees for angles, the document unit for lengths
+fn to_display(kind: &DimensionKind, value: f64, units: &UnitSystem) -> f64 {
+    match kind {
+        DimensionKind::Angular { .. } => value.to_degrees(),
+        _ => Length::mm(value).value_in(units.length),
+    }
+}
+
+/// Typed text as a model value (millimetres or radians)
+fn from_display(kind: &DimensionKind, text: &str, units: &UnitSystem) -> Option<f64> {
+    match kind {
+        DimensionKind::Angular { .. } => units.parse_angle(text),
+        _ => units.parse_length(text),
+    }
+}
+
+/// Keyboard-driven selection and editing of dimensions in the active sketch
+pub fn dimension_edit_input_system(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut key_events: EventReader<KeyboardInput>,
+    sketches: Res<Sketches>,
+    units: Option<Res<UnitSystem>>,
+    mut session: ResMut<DimensionEditSession>,
+    mut requests: EventWriter<SetDimensionValue>,
+) {
+    let units = units.as_deref().cloned().unwrap_or_default();
+    let (Some(index), Some(sketch)) = (sketches.active, sketches.active()) else {
+        key_events.clear();
+        return;
+    };
+    let selected = session.selected.and_then(|id| sketch.dimension(id));
+    if !session.is_active() {
+        key_events.clear();
+        if bindings.just_pressed("next_dimension", &keys) {
+            let ids: Vec<usize> = sketch.dimensions.iter().map(|d| d.id).collect();
+            session.select_next(&ids);
+        } else if bindings.just_pressed("confirm", &keys) {
+            if let Some(dim) = selected {
+                session.begin(to_display(&dim.kind, dim.value, &units));
+            }
+        }
+        return;
+    }
+    let Some(dim) = selected else {
+        session.cancel();
+        return;
+    };
+    for ev in key_events.read() {
+        if ev.state != ButtonState::Pressed {
+            continue;
+        }
+        match &ev.logical_key {
+            Key::Enter => {
+                match from_display(&dim.kind, &session.commit(), &units) {
+                    Some(value) => {
+                        requests.write(SetDimensionValue { sketch: index, dimension: dim.id, value });
+                    }
+                    None => warn!("Not a valid value for this dimension"),
+                }
+                return;
+            }
+            Key::Escape => {
+                session.cancel();
+                return;
+            }
+            Key::Backspace => {
+                session.buffer.pop();
+            }
+            Key::Character(text) => session
+                .buffer
+                .extend(text.chars().filter(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ' ' | '"'))),
+            _ => {}
+        }
+    }
+}
+
+/// Apply dimension changes, re-solving the affected sketch
+pub fn apply_dimension_values(
+    mut events: EventReader<SetDimensionValue>,
+    mut sketches: ResMut<Sketches>,
+    mut usage: Option<ResMut<UsageStats>>,
+) {
+    for ev in events.read() {
+        let Some(sketch) = sketches.sketches.get_mut(ev.sketch) else { continue; };
+        journal(format!("set_dimension {} {} {}", ev.sketch, ev.dimension, ev.value));
+        let start = Instant::now();
+        if let Err(err) = set_dimension_value(sketch, ev.dimension, ev.value) {
+            warn!("Dimension change rejected: {}", err);
+        }
+        if let Some(usage) = usage.as_mut() {
+            usage.record("set_dimension", start.elapsed());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::topology::plane::Plane;
+    use crate::sketch::sketch::Sketch;
+    use nalgebra::Vector2;
+
+    #[test]
+    fn test_session_select_and_commit() {
+        let mut s = DimensionEditSession::default();
+        s.begin(1.0);
+        assert!(!s.is_active());
+        s.select_next(&[4, 7]);
+        assert_eq!(s.selected, Some(4));
+        s.select_next(&[4, 7]);
+        s.select_next(&[4, 7]);
+        assert_eq!(s.selected, Some(4));
+        s.begin(12.5);
+        assert_eq!(s.buffer, "12.5");
+        s.buffer.push_str("5 cm");
+        assert_eq!(s.commit(), "12.55 cm");
+        assert!(!s.is_active() && s.buffer.is_empty());
+        let kind = DimensionKind::Linear { a: 0, b: 1 };
+        assert_eq!(from_display(&kind, "12.55 cm", &UnitSystem::default()), Some(125.5));
+        assert_eq!(to_display(&kind, 50.8, &UnitSystem::new(crate::model::units::LengthUnit::Inch)), 2.0);
+    }
+
+    #[test]
+    fn test_apply_dimension_values() {
+        let mut sketch = Sketch::new("S", Plane::xy());
+        let a = sketch.add_fixed_point(Vector2::new(0.0, 0.0));
+        let b = sketch.add_point(Vector2::new(5.0, 0.0));
+        let d = sketch.add_dimension(DimensionKind::Linear { a, b }, None).unwrap();
+        let mut sketches = Sketches::default();
+        let index = sketches.add(sketch);
+
+        let mut app = App::new();
+        app.insert_resource(sketches)
+            .add_event::<SetDimensionValue>()
+            .add_systems(Update, apply_dimension_values);
+        app.world_mut().send_event(SetDimensionValue { sketch: index, dimension: d, value: 8.0 });
+        app.update();
+        let sketch = &app.world().resource::<Sketches>().sketches[index];
+        assert!((sketch.point_position(b).unwrap().norm() - 8.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,146 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: interaction::grid_snap
+//!
+//! Model-space snapping of dragged vertices to a grid increment, and the live
+//! coordinate readout shown next to the cursor while dragging. An object snap
+//! under the cursor (see `interaction::snapping`) takes precedence over the
+//! grid. Holding Alt drags freely, without snapping or planarity constraints.
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use nalgebra::Vector3;
+
+use crate::interaction::snapping::{SnapKind, SnapSettings, SnapState};
+use crate::model::brep::constraints::planarity::{PlanarEdit, PlanarityMode};
+use crate::model::brep_model::BrepModel;
+use crate::model::units::UnitSystem;
+
+/// Offset of the readout from the cursor (pixels)
+const READOUT_OFFSET: Vec2 = Vec2::new(16.0, 16.0);
+
+/// Grid snapping of dragged geometry
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct GridSnap {
+    pub enabled: bool,
+    /// Grid spacing in model units (mm); follows the document unit
+    pub increment: f64,
+}
+
+impl Default for GridSnap {
+    fn default() -> Self {
+        Self { enabled: true, increment: 1.0 }
+    }
+}
+
+impl GridSnap {
+    /// Nearest grid point, or `p` itself when snapping is off
+    pub fn snap(&self, p: &Vector3<f64>) -> Vector3<f64> {
+        if !self.enabled || self.increment <= 0.0 {
+            return *p;
+        }
+        p.map(|c| (c / self.increment).round() * self.increment)
+    }
+}
+
+/// True while a modifier asks to drag without snapping
+pub fn snap_suppressed(keys: &ButtonInput<KeyCode>) -> bool {
+    keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight])
+}
+
+/// Everything that shapes a vertex drag: object or grid snapping, then face planarity
+#[derive(SystemParam)]
+pub struct DragConstraints<'w> {
+    keys: Res<'w, ButtonInput<KeyCode>>,
+    snap: Option<Res<'w, GridSnap>>,
+    settings: Option<Res<'w, SnapSettings>>,
+    object_snap: Option<Res<'w, SnapState>>,
+    planar: Option<Res<'w, PlanarEdit>>,
+}
+
+impl DragConstraints<'_> {
+    /// Vertex moves for dragging `vertex_id` towards `target`
+    pub fn moves(&self, model: &BrepModel, vertex_id: usize, target: &Vector3<f64>) -> Vec<(usize, Vector3<f64>)> {
+        if snap_suppressed(&self.keys) {
+            return vec![(vertex_id, *target)];
+        }
+        let object_snap = self.object_snap.as_deref().and_then(|s| s.current).filter(|s| s.kind != SnapKind::Grid);
+        let grid = self.snap.as_deref().filter(|_| self.settings.as_deref().is_none_or(|s| s.allows(SnapKind::Grid)));
+        let target = match (object_snap, grid) {
+            (Some(snap), _) => snap.position,
+            (None, Some(grid)) => grid.snap(target),
+            (None, None) => *target,
+        };
+        match self.planar.as_deref() {
+            Some(planar) => planar.moves(model, vertex_id, &target),
+            None => vec![(vertex_id, target)],
+        }
+    }
+}
+
+/// Readout text showing the dragged vertex position
+#[derive(Component, Debug)]
+pub struct DragReadout;
+
+pub fn spawn_drag_readout(mut commands: Commands) {
+    commands.spawn((
+        Text::new(""),
+        Node { position_type: PositionType::Absolute, padding: UiRect::all(Val::Px(3.0)), ..default() },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        Visibility::Hidden,
+        DragReadout,
+    ));
+}
+
+/// Readout text for a position in the document unit
+pub fn format_coordinates(p: &Vector3<f64>, units: &UnitSystem) -> String {
+    format!("X {}  Y {}  Z {}", units.format_length(p.x), units.format_length(p.y), units.format_length(p.z))
+}
+
+/// Follow the cursor with the dragged vertex's coordinates
+pub fn update_drag_readout(
+    model: Res<BrepModel>,
+    keys: Res<ButtonInput<KeyCode>>,
+    planar: Option<Res<PlanarEdit>>,
+    units: Option<Res<UnitSystem>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut readouts: Query<(&mut Text, &mut Node, &mut Visibility), With<DragReadout>>,
+) {
+    let dragged = model.selected_vertex.and_then(|id| model.vertex_position(id));
+    let cursor = windows.single().ok().and_then(|w| w.cursor_position());
+    for (mut text, mut node, mut visibility) in readouts.iter_mut() {
+        let (Some(p), Some(cursor)) = (dragged, cursor) else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+        let mode = planar.as_deref().map_or(PlanarityMode::Off, |p| p.mode);
+        let note = if snap_suppressed(&keys) {
+            "  (free)".to_string()
+        } else if mode != PlanarityMode::Off {
+            format!("  ({})", mode.label())
+        } else {
+            String::new()
+        };
+        let units = units.as_deref().cloned().unwrap_or_default();
+        text.0 = format!("{}{}", format_coordinates(&p, &units), note);
+        node.left = Val::Px(cursor.x + READOUT_OFFSET.x);
+        node.top = Val::Px(cursor.y + READOUT_OFFSET.y);
+        *visibility = Visibility::Inherited;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snap_to_increment() {
+        let snap = GridSnap { enabled: true, increment: 5.0 };
+        assert_eq!(snap.snap(&Vector3::new(7.4, -2.4, 13.0)), Vector3::new(5.0, -0.0, 15.0));
+        let off = GridSnap { enabled: false, ..snap };
+        assert_eq!(off.snap(&Vector3::new(7.4, -2.4, 13.0)), Vector3::new(7.4, -2.4, 13.0));
+        assert_eq!(format_coordinates(&Vector3::new(1.0, 2.5, -3.126), &UnitSystem::default()), "X 1.00 mm  Y 2.50 mm  Z -3.13 mm");
+    }
+}
@@ -0,0 +1,381 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: interaction::measure_tool
+//!
+//! M toggles measuring. While it is on, left clicks pick elements instead of
+//! selecting them. One face gives its area and perimeter; one edge its length,
+//! plus radius and diameter when it lies on a circle; two elements give the
+//! centre distance, the closest approach and, for edges and faces, the angle
+//! between them. Results are drawn in the model, labelled at the cursor side of
+//! the annotation and listed in the measurements panel. Enter keeps them in the
+//! document's measurements; Escape drops the picks, or leaves the tool.
+
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+use nalgebra::{Point3, Vector3};
+
+use crate::color::{CYAN, WHITE, YELLOW};
+use crate::input::keyboard::KeyBindings;
+use crate::interaction::picking::PickState;
+use crate::interaction::selection::SelectionItem;
+use crate::interaction::state::UiPanel;
+use crate::measure::circular::circular_edges;
+use crate::measure::distance::{edge_length, face_area, face_perimeter, item_angle, midpoint, min_distance, reference_point};
+use crate::measure::measurements::{MeasurementKind, Measurements, Quantity};
+use crate::model::brep_model::{na_vec3_to_bevy, BrepModel};
+use crate::model::units::UnitSystem;
+use crate::render::gizmo_scale::{GizmoScale, VERTEX_HANDLE_PIXELS};
+use crate::render::hilighting::item_edges;
+use crate::telemetry::crash::journal;
+use crate::telemetry::usage::UsageStats;
+
+/// Offset of the floating label from its anchor (pixels)
+const LABEL_OFFSET: Vec2 = Vec2::new(12.0, -12.0);
+
+/// One value measured from the current picks
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeasureResult {
+    pub kind: MeasurementKind,
+    /// Value in model units
+    pub value: f64,
+    pub anchor: Point3<f64>,
+    /// Points a dimension line is drawn between, if any
+    pub span: Option<(Vector3<f64>, Vector3<f64>)>,
+}
+
+impl MeasureResult {
+    /// "Area: 2.50 cm²"
+    pub fn label(&self, units: &UnitSystem) -> String {
+        format!("{}: {}", self.kind.label(), format_value(self.kind, self.value, units))
+    }
+}
+
+/// A model-unit value in the document units
+pub fn format_value(kind: MeasurementKind, value: f64, units: &UnitSystem) -> String {
+    match kind.quantity() {
+        Quantity::Length => units.format_length(value),
+        Quantity::Angle => units.format_angle(value),
+        Quantity::Area => units.format_area(value),
+    }
+}
+
+/// Everything measurable from one or two picked elements
+pub fn measure_items(model: &BrepModel, picks: &[SelectionItem]) -> Vec<MeasureResult> {
+    let mut results = Vec::new();
+    match picks {
+        [SelectionItem::Face(face)] => {
+            let Some(anchor) = reference_point(model, &picks[0]).map(Point3::from) else { return results };
+            if let Some(area) = face_area(model, *face) {
+                results.push(MeasureResult { kind: MeasurementKind::Area, value: area, anchor, span: None });
+            }
+            if let Some(perimeter) = face_perimeter(model, *face) {
+                results.push(MeasureResult { kind: MeasurementKind::Perimeter, value: perimeter, anchor, span: None });
+            }
+        }
+        [SelectionItem::Edge(edge)] => {
+            let Some(e) = model.edge(*edge) else { return results };
+            let (Some(a), Some(b)) = (model.vertex_position(e.vertices.0), model.vertex_position(e.vertices.1)) else { return results };
+            if let Some(length) = edge_length(model, *edge) {
+                results.push(MeasureResult { kind: MeasurementKind::Distance, value: length, anchor: midpoint(&a, &b), span: Some((a, b)) });
+            }
+            let on_loop = |l: usize| model.edge_loop(l).is_some_and(|l| l.edges.iter().flatten().any(|id| id == edge));
+            if let Some(circle) = circular_edges(model).into_iter().find(|c| on_loop(c.edge_loop)) {
+                let rim = midpoint(&a, &b).coords;
+                let span = Some((circle.center.coords, rim));
+                results.push(MeasureResult { kind: MeasurementKind::Radius, value: circle.radius, anchor: midpoint(&circle.center.coords, &rim), span });
+                results.push(MeasureResult { kind: MeasurementKind::Diameter, value: circle.diameter(), anchor: circle.center, span: None });
+            }
+        }
+        [a, b] => {
+            if let (Some(p), Some(q)) = (reference_point(model, a), reference_point(model, b)) {
+                results.push(MeasureResult { kind: MeasurementKind::Distance, value: (q - p).norm(), anchor: midpoint(&p, &q), span: Some((p, q)) });
+            }
+            if let Some((d, p, q)) = min_distance(model, a, b) {
+                results.push(MeasureResult { kind: MeasurementKind::MinDistance, value: d, anchor: midpoint(&p, &q), span: Some((p, q)) });
+            }
+            if let Some(angle) = item_angle(model, a, b) {
+                let anchor = [a, b].iter().filter_map(|i| reference_point(model, i)).sum::<Vector3<f64>>() / 2.0;
+                results.push(MeasureResult { kind: MeasurementKind::Angle, value: angle, anchor: Point3::from(anchor), span: None });
+            }
+        }
+        _ => {}
+    }
+    results
+}
+
+/// Measuring mode, the elements picked so far and what they measure
+#[derive(Resource, Debug, Default, Clone)]
+pub struct MeasureTool {
+    pub active: bool,
+    pub picks: Vec<SelectionItem>,
+    pub results: Vec<MeasureResult>,
+}
+
+impl MeasureTool {
+    /// Add a pick; a third pick starts a new measurement
+    pub fn pick(&mut self, item: SelectionItem) {
+        if self.picks.len() >= 2 {
+            self.picks.clear();
+        }
+        if !self.picks.contains(&item) {
+            self.picks.push(item);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.picks.clear();
+        self.results.clear();
+    }
+}
+
+/// Run condition: true unless the measure tool has the mouse and Enter
+pub fn not_measuring(tool: Option<Res<MeasureTool>>) -> bool {
+    !tool.is_some_and(|t| t.active)
+}
+
+/// Request to keep the current results in the document's measurements
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeepMeasurements;
+
+/// The measure_tool binding (M) toggles the tool, confirm keeps the results, cancel clears picks or leaves
+pub fn measure_tool_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut tool: ResMut<MeasureTool>,
+    mut requests: EventWriter<KeepMeasurements>,
+) {
+    if bindings.just_pressed("measure_tool", &keys) {
+        tool.active = !tool.active;
+        tool.clear();
+    }
+    if !tool.active {
+        return;
+    }
+    if bindings.just_pressed("confirm", &keys) && !tool.results.is_empty() {
+        requests.write(KeepMeasurements);
+    } else if bindings.just_pressed("cancel", &keys) {
+        if tool.picks.is_empty() {
+            tool.active = false;
+        }
+        tool.clear();
+    }
+}
+
+/// Pick the element under the cursor and keep the results up to date
+pub fn measure_pick(mouse: Res<ButtonInput<MouseButton>>, pick: Res<PickState>, model: Res<BrepModel>, mut tool: ResMut<MeasureTool>) {
+    if !tool.active {
+        return;
+    }
+    let mut changed = model.is_changed();
+    if mouse.just_pressed(MouseButton::Left) {
+        if let Some(hit) = &pick.hover {
+            tool.pick(hit.target.selection_item());
+            changed = true;
+        }
+    }
+    if changed {
+        tool.results = measure_items(&model, &tool.picks);
+    }
+}
+
+pub fn apply_keep_measurements(
+    mut requests: EventReader<KeepMeasurements>,
+    tool: Res<MeasureTool>,
+    mut measurements: ResMut<Measurements>,
+    mut usage: Option<ResMut<UsageStats>>,
+) {
+    for _ in requests.read() {
+        let start = Instant::now();
+        for r in &tool.results {
+            let id = measurements.add(r.kind, r.value, tool.picks.clone(), r.anchor);
+            journal(format!("keep_measurement {} {} {}", id, r.kind.label(), r.value));
+        }
+        if let Some(usage) = usage.as_mut() {
+            usage.record("keep_measurement", start.elapsed());
+        }
+    }
+}
+
+/// Draw the picks and dimension lines of the current results, and mark kept measurements
+pub fn render_measure_annotations(
+    mut gizmos: Gizmos,
+    model: Res<BrepModel>,
+    tool: Res<MeasureTool>,
+    measurements: Res<Measurements>,
+    scale: Option<Res<GizmoScale>>,
+) {
+    let scale = scale.as_deref().copied().unwrap_or_default();
+    for record in &measurements.records {
+        let p = na_vec3_to_bevy(&record.anchor.coords);
+        let size = scale.world_size(p, VERTEX_HANDLE_PIXELS);
+        gizmos.line(p - Vec3::X * size, p + Vec3::X * size, CYAN);
+        gizmos.line(p - Vec3::Y * size, p + Vec3::Y * size, CYAN);
+        gizmos.line(p - Vec3::Z * size, p + Vec3::Z * size, CYAN);
+    }
+    if !tool.active {
+        return;
+    }
+    for item in &tool.picks {
+        if let SelectionItem::Vertex(id) = item {
+            if let Some(p) = model.vertex_position(*id).map(|p| na_vec3_to_bevy(&p)) {
+                gizmos.circle(p, scale.world_size(p, VERTEX_HANDLE_PIXELS), YELLOW);
+            }
+        }
+        for edge in item_edges(&model, item).into_iter().filter_map(|e| model.edge(e)) {
+            if let (Some(a), Some(b)) = (model.vertex_position(edge.vertices.0), model.vertex_position(edge.vertices.1)) {
+                gizmos.line(na_vec3_to_bevy(&a), na_vec3_to_bevy(&b), YELLOW);
+            }
+        }
+    }
+    for (a, b) in tool.results.iter().filter_map(|r| r.span) {
+        let (a, b) = (na_vec3_to_bevy(&a), na_vec3_to_bevy(&b));
+        gizmos.line(a, b, WHITE);
+        for end in [a, b] {
+            gizmos.sphere(end, scale.world_size(end, VERTEX_HANDLE_PIXELS * 0.5), WHITE);
+        }
+    }
+}
+
+/// Text of the measurements panel
+#[derive(Component, Debug)]
+pub struct MeasurePanelText;
+
+/// Label floating next to the current results
+#[derive(Component, Debug)]
+pub struct MeasureLabel;
+
+pub fn spawn_measure_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(8.0),
+                bottom: Val::Px(8.0),
+                padding: UiRect::all(Val::Px(6.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.1, 0.15, 0.1)),
+            UiPanel("measurements"),
+        ))
+        .with_child((Text::new(""), MeasurePanelText));
+    commands.spawn((
+        Text::new(""),
+        Node { position_type: PositionType::Absolute, padding: UiRect::all(Val::Px(3.0)), ..default() },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        Visibility::Hidden,
+        MeasureLabel,
+    ));
+}
+
+/// Panel listing the current results and kept measurements, rewritten when they change
+pub fn measure_panel_system(
+    tool: Res<MeasureTool>,
+    measurements: Res<Measurements>,
+    units: Option<Res<UnitSystem>>,
+    mut texts: Query<&mut Text, With<MeasurePanelText>>,
+) {
+    if !tool.is_changed() && !measurements.is_changed() && !units.as_ref().is_some_and(|u| u.is_changed()) {
+        return;
+    }
+    let units = units.as_deref().cloned().unwrap_or_default();
+    let Ok(mut text) = texts.single_mut() else { return };
+    let mut content = format!("Measure (M): {}\n", if tool.active { "on" } else { "off" });
+    if tool.active {
+        match tool.picks.len() {
+            0 => content.push_str("Pick a face, an edge or two elements\n"),
+            _ => {
+                for r in &tool.results {
+                    content.push_str(&format!("  {}\n", r.label(&units)));
+                }
+                if !tool.results.is_empty() {
+                    content.push_str("Enter keeps, Esc clears\n");
+                }
+            }
+        }
+    }
+    if !measurements.records.is_empty() {
+        content.push_str("Kept:\n");
+        for r in &measurements.records {
+            content.push_str(&format!("  {} {}: {}\n", r.id, r.kind.label(), format_value(r.kind, r.value, &units)));
+        }
+    }
+    if text.0 != content {
+        text.0 = content;
+    }
+}
+
+/// Keep the floating label beside the first result's anchor
+pub fn update_measure_label(
+    tool: Res<MeasureTool>,
+    units: Option<Res<UnitSystem>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    mut labels: Query<(&mut Text, &mut Node, &mut Visibility), With<MeasureLabel>>,
+) {
+    let units = units.as_deref().cloned().unwrap_or_default();
+    let screen = tool.results.first().filter(|_| tool.active).and_then(|r| {
+        let (camera, transform) = cameras.single().ok()?;
+        camera.world_to_viewport(transform, na_vec3_to_bevy(&r.anchor.coords)).ok()
+    });
+    for (mut text, mut node, mut visibility) in labels.iter_mut() {
+        let Some(screen) = screen else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+        text.0 = tool.results.iter().map(|r| r.label(&units)).collect::<Vec<_>>().join("\n");
+        node.left = Val::Px(screen.x + LABEL_OFFSET.x);
+        node.top = Val::Px(screen.y + LABEL_OFFSET.y);
+        *visibility = Visibility::Inherited;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::{cube, cylinder};
+
+    #[test]
+    fn test_measure_items() {
+        let model = cube(10.0);
+        let top = model.faces.iter().find(|f| model.face_normal(f).is_some_and(|n| n.z > 0.9)).unwrap().id;
+        let bottom = model.faces.iter().find(|f| model.face_normal(f).is_some_and(|n| n.z < -0.9)).unwrap().id;
+        let kinds = |results: &[MeasureResult]| results.iter().map(|r| (r.kind, r.value)).collect::<Vec<_>>();
+        assert_eq!(kinds(&measure_items(&model, &[SelectionItem::Face(top)])), vec![(MeasurementKind::Area, 100.0), (MeasurementKind::Perimeter, 40.0)]);
+        let pair = measure_items(&model, &[SelectionItem::Face(top), SelectionItem::Face(bottom)]);
+        assert_eq!(pair.iter().map(|r| r.kind).collect::<Vec<_>>(), vec![MeasurementKind::Distance, MeasurementKind::MinDistance, MeasurementKind::Angle]);
+        assert!((pair[1].value - 10.0).abs() < 1e-9);
+        assert_eq!(pair[2].label(&UnitSystem::default()), "Angle: 0.00°");
+
+        let c = cylinder(5.0, 10.0, 24);
+        let rim = c.edges.iter().find(|e| {
+            let (a, b) = (c.vertex_position(e.vertices.0).unwrap(), c.vertex_position(e.vertices.1).unwrap());
+            (a.z - b.z).abs() < 1e-9
+        });
+        let results = measure_items(&c, &[SelectionItem::Edge(rim.unwrap().id)]);
+        let diameter = results.iter().find(|r| r.kind == MeasurementKind::Diameter).unwrap();
+        assert!((diameter.value - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_picks_and_keep() {
+        let mut tool = MeasureTool::default();
+        tool.pick(SelectionItem::Vertex(0));
+        tool.pick(SelectionItem::Vertex(0));
+        tool.pick(SelectionItem::Vertex(6));
+        tool.pick(SelectionItem::Edge(1));
+        assert_eq!(tool.picks, vec![SelectionItem::Edge(1)]);
+
+        let mut app = App::new();
+        app.add_event::<KeepMeasurements>().init_resource::<Measurements>().add_systems(Update, apply_keep_measurements);
+        let model = cube(10.0);
+        tool.picks = vec![SelectionItem::Vertex(model.vertices[0].id), SelectionItem::Vertex(model.vertices[6].id)];
+        tool.results = measure_items(&model, &tool.picks);
+        app.insert_resource(tool);
+        app.world_mut().send_event(KeepMeasurements);
+        app.update();
+        let kept = &app.world().resource::<Measurements>().records;
+        assert_eq!(kept.len(), 2);
+        assert_eq!(kept[0].references.len(), 2);
+        assert!((kept[0].value - kept[1].value).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,659 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: interaction::outliner
+//!
+//! Scene tree panel listing the document's bodies (nested in their groups),
+//! sketches, workspace helpers, layers and assembly instances. Each row has a
+//! visibility toggle (plus a lock toggle for layers, a ghost toggle for bodies,
+//! and color and size buttons for helpers), its name (click to select; click
+//! the active body again to rename it) and a delete button; bodies are deleted
+//! through the model command bus. Body names, visibility and ghosting come from the
+//! `BodyPropertiesCollection`, which gains an entry for every new shell. F10
+//! hides the panel.
+
+use bevy::ecs::system::SystemParam;
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+
+use crate::interaction::rename::RenameSession;
+use crate::interaction::selection::{Selection, SelectionFilter, SelectionItem};
+use crate::interaction::state::{ActiveBody, UiPanel};
+use crate::model::assembly::{Assembly, InstanceId};
+use crate::model::body::BodyId;
+use crate::model::brep_model::BrepModel;
+use crate::model::command::ModelCommand;
+use crate::model::groups::{BodyGroups, GroupId};
+use crate::model::layers::{LayerManager, LAYER_COLORS};
+use crate::model::properties::BodyPropertiesCollection;
+use crate::render::ui_style::{BUTTON_ACTIVE, BUTTON_IDLE, PANEL_COLOR};
+use crate::sketch::sketch::Sketches;
+use crate::telemetry::crash::journal;
+use crate::telemetry::usage::UsageStats;
+use crate::workspace::workspace::Workspace;
+
+/// Indent per tree level (px)
+const INDENT: f32 = 12.0;
+/// Factor a helper's size changes by per click
+const HELPER_SIZE_STEP: f32 = 1.5;
+/// Largest helper size multiplier; the smallest is its inverse
+const MAX_HELPER_SIZE: f32 = 16.0;
+
+/// Something listed in the outliner
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OutlinerItem {
+    Group(GroupId),
+    Body(BodyId),
+    /// Index into `Sketches::sketches`
+    Sketch(usize),
+    /// Workspace helper, by id
+    Helper(String),
+    Instance(InstanceId),
+    /// Layer, by name
+    Layer(String),
+}
+
+/// One line of the tree
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutlinerRow {
+    /// `None` for section headings
+    pub item: Option<OutlinerItem>,
+    pub depth: usize,
+    pub label: String,
+    /// Current visibility, for items that can be hidden
+    pub visible: Option<bool>,
+    /// Active body or sketch
+    pub active: bool,
+    /// Lock state, for layers
+    pub locked: Option<bool>,
+    /// Ghost state, for bodies
+    pub ghosted: Option<bool>,
+    /// Color and size, for helpers; the color is `None` while the helper keeps its own colors
+    pub display: Option<(Option<[f32; 3]>, f32)>,
+}
+
+impl OutlinerRow {
+    fn heading(label: &str) -> Self {
+        Self { item: None, depth: 0, label: label.to_string(), visible: None, active: false, locked: None, ghosted: None, display: None }
+    }
+}
+
+/// Document state the tree is built from
+pub struct OutlinerSource<'a> {
+    /// Ids of the model's bodies
+    pub bodies: Vec<BodyId>,
+    pub properties: &'a BodyPropertiesCollection,
+    pub groups: &'a BodyGroups,
+    pub sketches: &'a Sketches,
+    pub workspace: &'a Workspace,
+    pub assembly: &'a Assembly,
+    pub layers: &'a LayerManager,
+    pub active_body: Option<BodyId>,
+    pub rename: &'a RenameSession,
+}
+
+impl OutlinerSource<'_> {
+    /// Rows of the tree, top to bottom
+    pub fn rows(&self) -> Vec<OutlinerRow> {
+        let mut rows = vec![OutlinerRow::heading("Bodies")];
+        self.group_rows(None, 1, &mut rows);
+        for body in self.bodies.iter().filter(|b| self.groups.group_of(**b).is_none()) {
+            rows.push(self.body_row(*body, 1));
+        }
+        if !self.sketches.sketches.is_empty() {
+            rows.push(OutlinerRow::heading("Sketches"));
+            for (i, sketch) in self.sketches.sketches.iter().enumerate() {
+                rows.push(OutlinerRow {
+                    item: Some(OutlinerItem::Sketch(i)),
+                    depth: 1,
+                    label: sketch.name.clone(),
+                    visible: Some(!sketch.hidden),
+                    active: self.sketches.active == Some(i),
+                    locked: None,
+                    ghosted: None,
+                    display: None,
+                });
+            }
+        }
+        if !self.workspace.helpers.is_empty() {
+            rows.push(OutlinerRow::heading("Helpers"));
+            for helper in &self.workspace.helpers {
+                rows.push(OutlinerRow {
+                    item: Some(OutlinerItem::Helper(helper.id.clone())),
+                    depth: 1,
+                    label: helper.id.clone(),
+                    visible: Some(helper.is_visible()),
+                    active: false,
+                    locked: None,
+                    ghosted: None,
+                    display: Some((helper.color, helper.size)),
+                });
+            }
+        }
+        if !self.layers.is_empty() {
+            rows.push(OutlinerRow::heading("Layers"));
+            for layer in self.layers.iter() {
+                rows.push(OutlinerRow {
+                    item: Some(OutlinerItem::Layer(layer.name.clone())),
+                    depth: 1,
+                    label: layer.name.clone(),
+                    visible: Some(layer.visible),
+                    active: false,
+                    locked: Some(layer.locked),
+                    ghosted: None,
+                    display: None,
+                });
+            }
+        }
+        let occurrences = self.assembly.occurrences();
+        if occurrences.iter().any(|o| !o.path.is_empty()) {
+            rows.push(OutlinerRow::heading("Assembly"));
+            for occurrence in occurrences {
+                let Some(instance) = occurrence.path.last().and_then(|i| self.assembly.instance(*i)) else { continue };
+                rows.push(OutlinerRow {
+                    item: Some(OutlinerItem::Instance(instance.id)),
+                    depth: occurrence.path.len(),
+                    label: instance.name.clone(),
+                    visible: None,
+                    active: false,
+                    locked: None,
+                    ghosted: None,
+                    display: None,
+                });
+            }
+        }
+        rows
+    }
+
+    fn group_rows(&self, parent: Option<GroupId>, depth: usize, rows: &mut Vec<OutlinerRow>) {
+        for group in self.groups.children(parent).into_iter().filter_map(|g| self.groups.get(g)) {
+            rows.push(OutlinerRow { item: Some(OutlinerItem::Group(group.id)), depth, label: group.name.clone(), visible: Some(group.visible), active: false, locked: None, ghosted: None, display: None });
+            self.group_rows(Some(group.id), depth + 1, rows);
+            for body in group.bodies.iter().filter(|b| self.bodies.contains(b)) {
+                rows.push(self.body_row(*body, depth + 1));
+            }
+        }
+    }
+
+    fn body_row(&self, body: BodyId, depth: usize) -> OutlinerRow {
+        let props = self.properties.get(body);
+        let label = match (self.rename.target == Some(body), props) {
+            (true, _) => format!("{}_", self.rename.buffer),
+            (false, Some(p)) => p.name.clone(),
+            (false, None) => format!("Body {}", body.0),
+        };
+        OutlinerRow {
+            item: Some(OutlinerItem::Body(body)),
+            depth,
+            label,
+            visible: Some(props.is_none_or(|p| p.visible)),
+            active: self.active_body == Some(body),
+            locked: None,
+            ghosted: Some(props.is_some_and(|p| p.ghosted)),
+            display: None,
+        }
+    }
+}
+
+/// What a click in the outliner asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutlinerAction {
+    /// Select the item; on the active body, start renaming it
+    Select,
+    ToggleVisibility,
+    /// Lock or unlock a layer
+    ToggleLock,
+    /// Ghost or unghost a body
+    ToggleGhost,
+    /// Give a helper the next palette color, or back its own colors after the last
+    CycleColor,
+    /// Draw a helper larger
+    Grow,
+    /// Draw a helper smaller
+    Shrink,
+    Delete,
+}
+
+/// Request from the outliner (or scripts) to act on a listed item
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct OutlinerRequest {
+    pub item: OutlinerItem,
+    pub action: OutlinerAction,
+}
+
+/// Document resources the outliner edits
+#[derive(SystemParam)]
+pub struct OutlinerDocument<'w> {
+    properties: ResMut<'w, BodyPropertiesCollection>,
+    groups: ResMut<'w, BodyGroups>,
+    sketches: ResMut<'w, Sketches>,
+    workspace: ResMut<'w, Workspace>,
+    assembly: ResMut<'w, Assembly>,
+    layers: ResMut<'w, LayerManager>,
+    selection: ResMut<'w, Selection>,
+    active: ResMut<'w, ActiveBody>,
+    rename: ResMut<'w, RenameSession>,
+    commands: EventWriter<'w, ModelCommand>,
+}
+
+impl OutlinerDocument<'_> {
+    fn select(&mut self, item: &OutlinerItem) {
+        let bodies = match item {
+            OutlinerItem::Body(body) => {
+                if self.active.0 == Some(*body) {
+                    let name = self.properties.get(*body).map(|p| p.name.clone()).unwrap_or_default();
+                    self.rename.begin(*body, &name);
+                }
+                self.active.0 = Some(*body);
+                vec![*body]
+            }
+            OutlinerItem::Group(group) => self.groups.get(*group).map(|g| g.bodies.clone()).unwrap_or_default(),
+            OutlinerItem::Instance(instance) => self
+                .assembly
+                .instance(*instance)
+                .and_then(|i| self.assembly.component(i.component))
+                .map(|c| c.bodies.clone())
+                .unwrap_or_default(),
+            OutlinerItem::Sketch(index) => {
+                if *index < self.sketches.sketches.len() {
+                    self.sketches.active = Some(*index);
+                }
+                return;
+            }
+            OutlinerItem::Layer(name) => {
+                self.properties.iter().filter(|(_, p)| p.layer.as_deref() == Some(name.as_str())).map(|(id, _)| *id).collect()
+            }
+            OutlinerItem::Helper(_) => return,
+        };
+        self.selection.set_filter(SelectionFilter::Bodies);
+        self.selection.clear();
+        // Bodies on hidden or locked layers stay unselected
+        for body in bodies.into_iter().filter(|b| self.layers.is_body_selectable(*b, &self.properties)) {
+            self.selection.add(SelectionItem::Body(body));
+        }
+    }
+
+    fn toggle_visibility(&mut self, item: &OutlinerItem) {
+        match item {
+            OutlinerItem::Body(body) => {
+                if self.properties.get(*body).is_none() {
+                    self.properties.register(*body, "Body");
+                }
+                if let Some(p) = self.properties.get_mut(*body) {
+                    p.visible = !p.visible;
+                }
+            }
+            OutlinerItem::Group(group) => {
+                if let Some(g) = self.groups.get_mut(*group) {
+                    g.visible = !g.visible;
+                }
+            }
+            OutlinerItem::Sketch(index) => {
+                if let Some(sketch) = self.sketches.sketches.get_mut(*index) {
+                    sketch.hidden = !sketch.hidden;
+                }
+            }
+            OutlinerItem::Helper(id) => {
+                if let Some(visible) = self.workspace.get_helper(id).map(|h| h.is_visible()) {
+                    let _ = self.workspace.set_helper_visible(id, !visible);
+                }
+            }
+            OutlinerItem::Layer(name) => {
+                if let Some(layer) = self.layers.get_mut(name) {
+                    layer.visible = !layer.visible;
+                }
+            }
+            OutlinerItem::Instance(_) => {}
+        }
+    }
+
+    fn delete(&mut self, item: &OutlinerItem) {
+        match item {
+            OutlinerItem::Body(body) => self.delete_body(*body),
+            OutlinerItem::Group(group) => {
+                let _ = self.groups.remove_group(*group);
+            }
+            OutlinerItem::Sketch(index) => {
+                if *index < self.sketches.sketches.len() {
+                    self.sketches.sketches.remove(*index);
+                    self.sketches.active = match self.sketches.active {
+                        Some(a) if a == *index => None,
+                        Some(a) if a > *index => Some(a - 1),
+                        other => other,
+                    };
+                }
+            }
+            OutlinerItem::Helper(id) => {
+                let _ = self.workspace.remove_helper(id);
+            }
+            OutlinerItem::Instance(instance) => {
+                let _ = self.assembly.remove_instance(*instance);
+            }
+            OutlinerItem::Layer(name) => {
+                let _ = self.layers.delete(name, &mut self.properties);
+            }
+        }
+    }
+
+    /// Send the body's deletion to the command bus
+    fn delete_body(&mut self, body: BodyId) {
+        self.commands.write(ModelCommand::DeleteBody(body));
+        self.active.0 = None;
+        if self.rename.target.is_some() {
+            self.rename.cancel();
+        }
+    }
+}
+
+/// Carry out outliner requests
+pub fn apply_outliner_requests(mut requests: EventReader<OutlinerRequest>, mut document: OutlinerDocument, mut usage: Option<ResMut<UsageStats>>) {
+    for request in requests.read() {
+        let start = Instant::now();
+        journal(format!("outliner {:?} {:?}", request.action, request.item));
+        match request.action {
+            OutlinerAction::Select => document.select(&request.item),
+            OutlinerAction::ToggleVisibility => document.toggle_visibility(&request.item),
+            OutlinerAction::ToggleLock => {
+                if let OutlinerItem::Layer(name) = &request.item {
+                    if let Some(layer) = document.layers.get_mut(name) {
+                        layer.locked = !layer.locked;
+                    }
+                }
+            }
+            OutlinerAction::ToggleGhost => {
+                if let OutlinerItem::Body(body) = &request.item {
+                    if let Some(props) = document.properties.get_mut(*body) {
+                        props.ghosted = !props.ghosted;
+                    }
+                }
+            }
+            OutlinerAction::CycleColor | OutlinerAction::Grow | OutlinerAction::Shrink => {
+                let OutlinerItem::Helper(id) = &request.item else { continue };
+                let Some(helper) = document.workspace.get_helper_mut(id) else { continue };
+                match request.action {
+                    OutlinerAction::CycleColor => {
+                        let next = helper.color.and_then(|c| LAYER_COLORS.iter().position(|p| *p == c)).map_or(0, |i| i + 1);
+                        helper.color = LAYER_COLORS.get(next).copied();
+                    }
+                    OutlinerAction::Grow => helper.size = (helper.size * HELPER_SIZE_STEP).min(MAX_HELPER_SIZE),
+                    OutlinerAction::Shrink => helper.size = (helper.size / HELPER_SIZE_STEP).max(1.0 / MAX_HELPER_SIZE),
+                    _ => {}
+                }
+            }
+            OutlinerAction::Delete => document.delete(&request.item),
+        }
+        if let Some(usage) = usage.as_mut() {
+            usage.record("outliner", start.elapsed());
+        }
+    }
+}
+
+/// Give every shell of the model a properties entry so it can be named and hidden
+pub fn sync_body_properties(model: Res<BrepModel>, mut properties: ResMut<BodyPropertiesCollection>) {
+    if !model.is_changed() {
+        return;
+    }
+    for body in model.body_ids() {
+        if properties.get(body).is_none() {
+            properties.register(body, "Body");
+        }
+    }
+}
+
+/// Container of the outliner rows
+#[derive(Component, Debug)]
+pub struct OutlinerList;
+
+/// A row of the outliner, rebuilt when the tree changes
+#[derive(Component, Debug)]
+pub struct OutlinerRowNode;
+
+/// Outliner button: the item it acts on and how
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+pub struct OutlinerButton {
+    pub item: OutlinerItem,
+    pub action: OutlinerAction,
+}
+
+/// Outliner panel (top right); rows are filled in by `outliner_panel_system`
+pub fn spawn_outliner_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(8.0),
+                top: Val::Px(8.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                padding: UiRect::all(Val::Px(6.0)),
+                ..default()
+            },
+            BackgroundColor(PANEL_COLOR),
+            UiPanel("outliner"),
+        ))
+        .with_children(|panel| {
+            panel.spawn(Text::new("Outliner (F10 hides)"));
+            panel.spawn((Node { flex_direction: FlexDirection::Column, row_gap: Val::Px(2.0), ..default() }, OutlinerList));
+        });
+}
+
+/// Document resources the panel displays
+#[derive(SystemParam)]
+pub struct OutlinerView<'w> {
+    model: Res<'w, BrepModel>,
+    properties: Res<'w, BodyPropertiesCollection>,
+    groups: Res<'w, BodyGroups>,
+    sketches: Res<'w, Sketches>,
+    workspace: Res<'w, Workspace>,
+    assembly: Res<'w, Assembly>,
+    layers: Res<'w, LayerManager>,
+    active: Res<'w, ActiveBody>,
+    rename: Res<'w, RenameSession>,
+}
+
+impl OutlinerView<'_> {
+    fn is_changed(&self) -> bool {
+        self.model.is_changed()
+            || self.properties.is_changed()
+            || self.groups.is_changed()
+            || self.sketches.is_changed()
+            || self.workspace.is_changed()
+            || self.assembly.is_changed()
+            || self.layers.is_changed()
+            || self.active.is_changed()
+            || self.rename.is_changed()
+    }
+
+    fn rows(&self) -> Vec<OutlinerRow> {
+        OutlinerSource {
+            bodies: self.model.body_ids(),
+            properties: &self.properties,
+            groups: &self.groups,
+            sketches: &self.sketches,
+            workspace: &self.workspace,
+            assembly: &self.assembly,
+            layers: &self.layers,
+            active_body: self.active.0,
+            rename: &self.rename,
+        }
+        .rows()
+    }
+}
+
+/// Send requests for clicked buttons and rebuild the rows when the tree changes
+pub fn outliner_panel_system(
+    mut commands: Commands,
+    view: OutlinerView,
+    pressed: Query<(&Interaction, &OutlinerButton), Changed<Interaction>>,
+    list: Query<Entity, With<OutlinerList>>,
+    old_rows: Query<Entity, With<OutlinerRowNode>>,
+    mut requests: EventWriter<OutlinerRequest>,
+    mut shown: Local<Vec<OutlinerRow>>,
+) {
+    for (interaction, button) in pressed.iter() {
+        if *interaction == Interaction::Pressed {
+            requests.write(OutlinerRequest { item: button.item.clone(), action: button.action });
+        }
+    }
+    if !view.is_changed() {
+        return;
+    }
+    let rows = view.rows();
+    let Ok(list) = list.single() else { return };
+    if rows == *shown {
+        return;
+    }
+    for entity in old_rows.iter() {
+        commands.entity(entity).despawn();
+    }
+    commands.entity(list).with_children(|list| {
+        for row in &rows {
+            let node = Node { column_gap: Val::Px(4.0), padding: UiRect::left(Val::Px(INDENT * row.depth as f32)), ..default() };
+            let Some(item) = row.item.clone() else {
+                list.spawn((node, OutlinerRowNode)).with_child(Text::new(row.label.clone()));
+                continue;
+            };
+            let button = |action| (Button, Node { padding: UiRect::axes(Val::Px(4.0), Val::Px(1.0)), ..default() }, OutlinerButton { item: item.clone(), action });
+            list.spawn((node, OutlinerRowNode)).with_children(|line| {
+                if let Some(visible) = row.visible {
+                    line.spawn((button(OutlinerAction::ToggleVisibility), BackgroundColor(BUTTON_IDLE)))
+                        .with_child(Text::new(if visible { "[o]" } else { "[-]" }));
+                }
+                if let Some(locked) = row.locked {
+                    line.spawn((button(OutlinerAction::ToggleLock), BackgroundColor(BUTTON_IDLE)))
+                        .with_child(Text::new(if locked { "[L]" } else { "[ ]" }));
+                }
+                if let Some(ghosted) = row.ghosted {
+                    line.spawn((button(OutlinerAction::ToggleGhost), BackgroundColor(BUTTON_IDLE)))
+                        .with_child(Text::new(if ghosted { "[g]" } else { "[s]" }));
+                }
+                if let Some((color, size)) = row.display {
+                    let swatch = color.map_or(BUTTON_IDLE, |[r, g, b]| Color::srgb(r, g, b));
+                    line.spawn((button(OutlinerAction::CycleColor), BackgroundColor(swatch))).with_child(Text::new("[c]"));
+                    line.spawn((button(OutlinerAction::Shrink), BackgroundColor(BUTTON_IDLE))).with_child(Text::new("-"));
+                    line.spawn(Text::new(format!("{:.2}x", size)));
+                    line.spawn((button(OutlinerAction::Grow), BackgroundColor(BUTTON_IDLE))).with_child(Text::new("+"));
+                }
+                let color = if row.active { BUTTON_ACTIVE } else { BUTTON_IDLE };
+                line.spawn((button(OutlinerAction::Select), BackgroundColor(color))).with_child(Text::new(row.label.clone()));
+                line.spawn((button(OutlinerAction::Delete), BackgroundColor(BUTTON_IDLE))).with_child(Text::new("x"));
+            });
+        }
+    });
+    *shown = rows;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::cube;
+    use crate::model::command::execute_model_commands;
+    use crate::model::feature_tree::FeatureTree;
+    use crate::model::brep::topology::plane::Plane;
+    use crate::sketch::sketch::Sketch;
+    use crate::testing::support::shifted;
+    use nalgebra::{Isometry3, Vector3};
+
+    fn two_cubes() -> BrepModel {
+        let mut model = cube(2.0);
+        let other = shifted(cube(2.0), Vector3::new(5.0, 0.0, 0.0));
+        model.append(&other);
+        model
+    }
+
+    #[test]
+    fn test_outliner_rows() {
+        let mut properties = BodyPropertiesCollection::new();
+        properties.register(BodyId(0), "Body");
+        let mut groups = BodyGroups::new();
+        let group = groups.create_group("Frame", None).unwrap();
+        groups.move_body(BodyId(1), Some(group)).unwrap();
+        let mut sketches = Sketches::default();
+        sketches.sketches.push(Sketch::new("Profile", Plane::xy()));
+        let mut assembly = Assembly::new();
+        let wheel = assembly.create_component("Wheel");
+        assembly.add_instance(assembly.root(), wheel, Isometry3::identity()).unwrap();
+        let source = OutlinerSource {
+            bodies: vec![BodyId(0), BodyId(1)],
+            properties: &properties,
+            groups: &groups,
+            sketches: &sketches,
+            workspace: &Workspace { helpers: Vec::new() },
+            assembly: &assembly,
+            layers: &LayerManager::new(),
+            active_body: Some(BodyId(0)),
+            rename: &RenameSession::default(),
+        };
+        let rows = source.rows();
+        let labels: Vec<(usize, &str)> = rows.iter().map(|r| (r.depth, r.label.as_str())).collect();
+        assert_eq!(
+            labels,
+            [(0, "Bodies"), (1, "Frame"), (2, "Body 1"), (1, "Body"), (0, "Sketches"), (1, "Profile"), (0, "Assembly"), (1, "Wheel:1")]
+        );
+        assert!(rows[3].active && rows[3].visible == Some(true));
+        assert_eq!(rows[5].item, Some(OutlinerItem::Sketch(0)));
+    }
+
+    #[test]
+    fn test_outliner_requests() {
+        let mut app = App::new();
+        let mut properties = BodyPropertiesCollection::new();
+        properties.register(BodyId(0), "Left");
+        properties.register(BodyId(1), "Right");
+        let mut groups = BodyGroups::new();
+        let group = groups.create_group("Frame", None).unwrap();
+        groups.move_body(BodyId(1), Some(group)).unwrap();
+        app.insert_resource(two_cubes())
+            .insert_resource(properties)
+            .insert_resource(groups)
+            .init_resource::<FeatureTree>()
+            .init_resource::<Sketches>()
+            .init_resource::<Workspace>()
+            .init_resource::<Assembly>()
+            .init_resource::<LayerManager>()
+            .init_resource::<Selection>()
+            .init_resource::<ActiveBody>()
+            .init_resource::<RenameSession>()
+            .add_event::<OutlinerRequest>()
+            .add_event::<ModelCommand>()
+            .add_systems(Update, (apply_outliner_requests, execute_model_commands).chain());
+        let send = |app: &mut App, item, action| {
+            app.world_mut().send_event(OutlinerRequest { item, action });
+            app.update();
+        };
+
+        send(&mut app, OutlinerItem::Body(BodyId(1)), OutlinerAction::Select);
+        assert_eq!(app.world().resource::<Selection>().bodies(), [BodyId(1)]);
+        // Selecting the active body again starts a rename
+        send(&mut app, OutlinerItem::Body(BodyId(1)), OutlinerAction::Select);
+        assert_eq!(app.world().resource::<RenameSession>().buffer, "Right");
+        app.world_mut().resource_mut::<RenameSession>().cancel();
+
+        send(&mut app, OutlinerItem::Body(BodyId(1)), OutlinerAction::ToggleVisibility);
+        assert!(!app.world().resource::<BodyPropertiesCollection>().get(BodyId(1)).unwrap().visible);
+        let model = app.world().resource::<BrepModel>();
+        assert_eq!(model.hidden_vertex_ids(|b| b != BodyId(1)).len(), 8);
+
+        send(&mut app, OutlinerItem::Body(BodyId(0)), OutlinerAction::ToggleGhost);
+        assert!(app.world().resource::<BodyPropertiesCollection>().get(BodyId(0)).unwrap().ghosted);
+
+        // Deleting the first body leaves the second with its id
+        send(&mut app, OutlinerItem::Body(BodyId(0)), OutlinerAction::Delete);
+        assert_eq!(app.world().resource::<BrepModel>().body_ids(), [BodyId(1)]);
+        assert_eq!(app.world().resource::<BrepModel>().vertices.len(), 8);
+        let properties = app.world().resource::<BodyPropertiesCollection>();
+        assert_eq!(properties.get(BodyId(1)).unwrap().name, "Right");
+        assert!(properties.get(BodyId(0)).is_none());
+        assert_eq!(app.world().resource::<BodyGroups>().get(group).unwrap().bodies, [BodyId(1)]);
+
+        let grid = || OutlinerItem::Helper("grid".into());
+        send(&mut app, grid(), OutlinerAction::ToggleVisibility);
+        send(&mut app, grid(), OutlinerAction::CycleColor);
+        send(&mut app, grid(), OutlinerAction::Grow);
+        let helper = app.world().resource::<Workspace>().get_helper("grid").unwrap().clone();
+        assert!(!helper.visible);
+        assert_eq!((helper.color, helper.size), (Some(LAYER_COLORS[0]), HELPER_SIZE_STEP));
+        send(&mut app, grid(), OutlinerAction::Delete);
+        assert!(app.world().resource::<Workspace>().helpers.iter().all(|h| h.id != "grid"));
+    }
+}
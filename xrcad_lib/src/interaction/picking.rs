@@ -0,0 +1,355 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: interaction::picking
+//!
+//! Ray-cast picking of model topology under the cursor, for any camera
+//! orientation. The ray is tested against the tessellated faces, and against
+//! vertices and edges within a screen-sized pick radius. Vertices win over
+//! edges and edges over faces, but only where they are not hidden behind the
+//! nearest face. Only targets the selection filter accepts are picked, and
+//! nothing on a hidden or ghosted body or a locked layer. The ray only visits
+//! the geometry the `SceneBvh` finds near it.
+//! The select action (a click, a tap, an XR trigger) updates the selection;
+//! Ctrl or Shift toggles items.
+
+use std::collections::{BTreeMap, HashSet};
+
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use nalgebra::Vector3;
+
+use crate::input::actions::{ActionState, InputAction};
+use crate::interaction::measure_tool::MeasureTool;
+use crate::interaction::selection::{Selection, SelectionFilter, SelectionItem};
+use crate::interaction::tools::ToolRegistry;
+use crate::interaction::transform_gizmo::TransformGizmo;
+use crate::model::body::BodyId;
+use crate::model::brep_model::{bevy_vec3_to_na, na_vec3_to_bevy, BrepModel};
+use crate::model::bvh::{Aabb, SceneBvh};
+use crate::model::groups::BodyGroups;
+use crate::model::layers::LayerManager;
+use crate::model::properties::BodyPropertiesCollection;
+use crate::render::gizmo_scale::{GizmoScale, EDGE_PICK_PIXELS, VERTEX_PICK_PIXELS};
+
+/// What a pick ray hit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PickTarget {
+    Vertex(usize),
+    Edge(usize),
+    Face(usize),
+}
+
+impl PickTarget {
+    pub fn selection_item(&self) -> SelectionItem {
+        match *self {
+            PickTarget::Vertex(id) => SelectionItem::Vertex(id),
+            PickTarget::Edge(id) => SelectionItem::Edge(id),
+            PickTarget::Face(id) => SelectionItem::Face(id),
+        }
+    }
+
+    /// Lower is preferred when several targets are under the cursor
+    fn priority(&self) -> u8 {
+        match self {
+            PickTarget::Vertex(_) => 0,
+            PickTarget::Edge(_) => 1,
+            PickTarget::Face(_) => 2,
+        }
+    }
+}
+
+/// A pick result
+#[derive(Debug, Clone, PartialEq)]
+pub struct PickHit {
+    pub target: PickTarget,
+    /// Connected shell the target belongs to
+    pub body: Option<BodyId>,
+    /// Closest point of the target to the ray
+    pub point: Vector3<f64>,
+    /// Distance along the ray
+    pub depth: f64,
+}
+
+impl PickHit {
+    /// What selecting this hit adds under `filter`
+    pub fn selection_item(&self, filter: SelectionFilter) -> Option<SelectionItem> {
+        match filter {
+            SelectionFilter::Bodies => self.body.map(SelectionItem::Body),
+            _ => Some(self.target.selection_item()).filter(|item| filter.accepts(item)),
+        }
+    }
+}
+
+/// Ray parameter of a triangle hit (either side), Möller–Trumbore
+fn ray_triangle(origin: &Vector3<f64>, dir: &Vector3<f64>, [a, b, c]: &[Vector3<f64>; 3]) -> Option<f64> {
+    let (e1, e2) = (b - a, c - a);
+    let p = dir.cross(&e2);
+    let det = e1.dot(&p);
+    if det.abs() < 1e-12 {
+        return None;
+    }
+    let s = origin - a;
+    let u = s.dot(&p) / det;
+    let q = s.cross(&e1);
+    let v = dir.dot(&q) / det;
+    let t = e2.dot(&q) / det;
+    (u >= 0.0 && v >= 0.0 && u + v <= 1.0 && t >= 0.0).then_some(t)
+}
+
+/// Ray parameter and closest point of a segment to the ray, with their distance
+fn ray_segment(origin: &Vector3<f64>, dir: &Vector3<f64>, a: &Vector3<f64>, b: &Vector3<f64>) -> (f64, Vector3<f64>, f64) {
+    let (u, r) = (b - a, origin - a);
+    let (bb, cc, dd, ee) = (dir.dot(&u), u.dot(&u), dir.dot(&r), u.dot(&r));
+    let det = bb * bb - cc;
+    let s = if det.abs() < 1e-12 || cc < 1e-24 { 0.0 } else { ((bb * dd - ee) / det).clamp(0.0, 1.0) };
+    let mut point = a + u * s;
+    let mut t = (point - origin).dot(dir);
+    if t < 0.0 {
+        t = 0.0;
+        let s = if cc < 1e-24 { 0.0 } else { (ee / cc).clamp(0.0, 1.0) };
+        point = a + u * s;
+    }
+    (t, point, (origin + dir * t - point).norm())
+}
+
+/// Every target along the ray, nearest first. `radius` is the pick radius of a
+/// kind of target at a point.
+pub fn pick_all(
+    model: &BrepModel,
+    origin: &Vector3<f64>,
+    direction: &Vector3<f64>,
+    radius: impl Fn(PickTarget, &Vector3<f64>) -> f64,
+) -> Vec<PickHit> {
+    pick_all_in(model, &SceneBvh::build(model), origin, direction, radius)
+}
+
+/// Largest pick radius of a kind of target over a box; the radius only
+/// depends on the kind, so the id is a placeholder
+fn box_radius(radius: &impl Fn(PickTarget, &Vector3<f64>) -> f64, kind: fn(usize) -> PickTarget, bounds: &Aabb) -> f64 {
+    bounds.corners().iter().map(|c| radius(kind(0), c)).fold(0.0, f64::max)
+}
+
+/// Like `pick_all`, visiting only the bodies, vertices, edges and triangles of
+/// `bvh` near the ray
+pub fn pick_all_in(
+    model: &BrepModel,
+    bvh: &SceneBvh,
+    origin: &Vector3<f64>,
+    direction: &Vector3<f64>,
+    radius: impl Fn(PickTarget, &Vector3<f64>) -> f64,
+) -> Vec<PickHit> {
+    let dir = direction.normalize();
+    let margin = |b: &Aabb| box_radius(&radius, PickTarget::Vertex, b).max(box_radius(&radius, PickTarget::Edge, b)) + model.tolerance.linear;
+    let mut hits = Vec::new();
+    // Vertices and edges shared by touching bodies are reported once
+    let (mut seen_vertices, mut seen_edges) = (HashSet::new(), HashSet::new());
+    for body in bvh.bodies_near_ray(origin, &dir, margin) {
+        let Some(tree) = bvh.body(body) else { continue };
+        for id in tree.vertices.near_ray(origin, &dir, |b| box_radius(&radius, PickTarget::Vertex, b)) {
+            let Some(position) = model.vertex_position(*id).filter(|_| seen_vertices.insert(*id)) else { continue };
+            let t = (position - origin).dot(&dir);
+            if t >= 0.0 && (origin + dir * t - position).norm() <= radius(PickTarget::Vertex(*id), &position) {
+                hits.push(PickHit { target: PickTarget::Vertex(*id), body: Some(body), point: position, depth: t });
+            }
+        }
+        for id in tree.edges.near_ray(origin, &dir, |b| box_radius(&radius, PickTarget::Edge, b)) {
+            let Some(e) = model.edge(*id).filter(|_| seen_edges.insert(*id)) else { continue };
+            let (Some(a), Some(b)) = (model.vertex_position(e.vertices.0), model.vertex_position(e.vertices.1)) else { continue };
+            let (t, point, distance) = ray_segment(origin, &dir, &a, &b);
+            if distance <= radius(PickTarget::Edge(e.id), &point) {
+                hits.push(PickHit { target: PickTarget::Edge(e.id), body: Some(body), point, depth: t });
+            }
+        }
+        let mut faces: BTreeMap<usize, f64> = BTreeMap::new();
+        for triangle in tree.triangles.near_ray(origin, &dir, |_| model.tolerance.linear) {
+            if let Some(t) = ray_triangle(origin, &dir, &triangle.corners) {
+                let nearest = faces.entry(triangle.face).or_insert(t);
+                *nearest = nearest.min(t);
+            }
+        }
+        for (face, t) in faces {
+            hits.push(PickHit { target: PickTarget::Face(face), body: Some(body), point: origin + dir * t, depth: t });
+        }
+    }
+    hits.sort_by(|a, b| a.depth.total_cmp(&b.depth));
+    hits
+}
+
+/// The preferred visible target along the ray that `filter` accepts
+pub fn pick(
+    model: &BrepModel,
+    origin: &Vector3<f64>,
+    direction: &Vector3<f64>,
+    filter: SelectionFilter,
+    radius: impl Fn(PickTarget, &Vector3<f64>) -> f64,
+) -> Option<PickHit> {
+    pick_where(model, origin, direction, filter, radius, |_| true)
+}
+
+/// Like `pick`, ignoring targets on bodies `pickable` rejects (hidden, ghosted or on a locked layer)
+pub fn pick_where(
+    model: &BrepModel,
+    origin: &Vector3<f64>,
+    direction: &Vector3<f64>,
+    filter: SelectionFilter,
+    radius: impl Fn(PickTarget, &Vector3<f64>) -> f64,
+    pickable: impl Fn(BodyId) -> bool,
+) -> Option<PickHit> {
+    pick_where_in(model, &SceneBvh::build(model), origin, direction, filter, radius, pickable)
+}
+
+/// Like `pick_where`, using a prebuilt `bvh` of the model
+pub fn pick_where_in(
+    model: &BrepModel,
+    bvh: &SceneBvh,
+    origin: &Vector3<f64>,
+    direction: &Vector3<f64>,
+    filter: SelectionFilter,
+    radius: impl Fn(PickTarget, &Vector3<f64>) -> f64,
+    pickable: impl Fn(BodyId) -> bool,
+) -> Option<PickHit> {
+    let hits: Vec<PickHit> = pick_all_in(model, bvh, origin, direction, &radius).into_iter().filter(|h| h.body.is_none_or(&pickable)).collect();
+    // The nearest face hides anything further than a pick radius behind it
+    let surface = hits.iter().find(|h| matches!(h.target, PickTarget::Face(_)));
+    let limit = surface.map_or(f64::INFINITY, |s| s.depth + radius(s.target, &s.point) + model.tolerance.linear);
+    hits.into_iter()
+        .filter(|h| h.depth <= limit && h.selection_item(filter).is_some())
+        .min_by_key(|h| h.target.priority())
+}
+
+/// Whether a body can be picked: shown, not ghosted and not on a locked layer
+pub fn is_body_pickable(body: BodyId, groups: &BodyGroups, layers: &LayerManager, properties: &BodyPropertiesCollection) -> bool {
+    groups.is_body_visible(body, properties) && layers.is_body_selectable(body, properties) && !properties.is_ghosted(body)
+}
+
+/// Cursor ray and the target under it, refreshed every frame
+#[derive(Resource, Debug, Default, Clone)]
+pub struct PickState {
+    /// Cursor ray origin and unit direction (model space)
+    pub ray: Option<(Vector3<f64>, Vector3<f64>)>,
+    pub hover: Option<PickHit>,
+}
+
+/// Cast the cursor ray into the model
+pub fn update_pick(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    (model, bvh): (Res<BrepModel>, Option<Res<SceneBvh>>),
+    scale: Option<Res<GizmoScale>>,
+    selection: Option<Res<Selection>>,
+    (properties, groups, layers): (Option<Res<BodyPropertiesCollection>>, Option<Res<BodyGroups>>, Option<Res<LayerManager>>),
+    mut state: ResMut<PickState>,
+) {
+    let scale = scale.as_deref().copied().unwrap_or_default();
+    let cursor = windows.single().ok().and_then(|w| w.cursor_position());
+    let ray = cameras.single().ok().zip(cursor).and_then(|((camera, transform), cursor)| camera.viewport_to_world(transform, cursor).ok());
+    let Some(ray) = ray else {
+        state.ray = None;
+        state.hover = None;
+        return;
+    };
+    let (origin, dir) = (bevy_vec3_to_na(&ray.origin), bevy_vec3_to_na(&ray.direction.as_vec3()));
+    let radius = |target: PickTarget, p: &Vector3<f64>| {
+        let pixels = if matches!(target, PickTarget::Vertex(_)) { VERTEX_PICK_PIXELS } else { EDGE_PICK_PIXELS };
+        scale.world_size(na_vec3_to_bevy(p), pixels) as f64
+    };
+    let filter = selection.map_or(SelectionFilter::Any, |s| s.filter);
+    // Hidden and ghosted bodies and bodies on locked layers cannot be picked
+    let (no_groups, no_layers, no_properties) = (BodyGroups::default(), LayerManager::default(), BodyPropertiesCollection::default());
+    let groups = groups.as_deref().unwrap_or(&no_groups);
+    let layers = layers.as_deref().unwrap_or(&no_layers);
+    let properties = properties.as_deref().unwrap_or(&no_properties);
+    let pickable = |body: BodyId| is_body_pickable(body, groups, layers, properties);
+    state.hover = match bvh {
+        Some(bvh) => pick_where_in(&model, &bvh, &origin, &dir, filter, radius, pickable),
+        None => pick_where(&model, &origin, &dir, filter, radius, pickable),
+    };
+    state.ray = Some((origin, dir));
+}
+
+/// Select selects the target under the cursor; Ctrl or Shift adds or removes it.
+/// Clicks on a transform gizmo handle are left to the gizmo, and clicks while
+/// measuring to the measure tool.
+pub fn select_on_click(
+    actions: Res<ActionState>,
+    keys: Res<ButtonInput<KeyCode>>,
+    state: Res<PickState>,
+    (gizmo, measure, tools): (Option<Res<TransformGizmo>>, Option<Res<MeasureTool>>, Option<Res<ToolRegistry>>),
+    mut selection: ResMut<Selection>,
+) {
+    let captured = gizmo.is_some_and(|g| g.captures_pointer()) || measure.is_some_and(|m| m.active) || tools.is_some_and(|t| t.is_active());
+    if !actions.just_pressed(InputAction::Select) || captured {
+        return;
+    }
+    let item = state.hover.as_ref().and_then(|h| h.selection_item(selection.filter));
+    if keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight, KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        if let Some(item) = item {
+            selection.toggle(item);
+        }
+    } else {
+        selection.clear();
+        if let Some(item) = item {
+            selection.add(item);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::cube;
+
+    #[test]
+    fn test_pick_prefers_visible_vertices_and_edges() {
+        let model = cube(10.0);
+        let down = -Vector3::z();
+        let radius = |_, _: &Vector3<f64>| 0.5;
+        let hit = pick(&model, &Vector3::new(1.0, 2.0, 20.0), &down, SelectionFilter::Any, radius).unwrap();
+        assert_eq!(hit.target, PickTarget::Face(model.faces[1].id));
+        assert!((hit.depth - 15.0).abs() < 1e-9 && hit.body == Some(BodyId(0)));
+
+        // Near the top-right corner: the top vertex, not the one underneath it
+        let hit = pick(&model, &Vector3::new(5.2, 4.9, 20.0), &down, SelectionFilter::Any, radius).unwrap();
+        let top = model.find_vertex_at(&Vector3::new(5.0, 5.0, 5.0)).unwrap();
+        assert_eq!(hit.target, PickTarget::Vertex(top));
+
+        // Along the top edge at x = 5, seen from the side at an angle
+        let hit = pick(&model, &Vector3::new(25.0, 0.0, 25.0), &Vector3::new(-1.0, 0.0, -1.0), SelectionFilter::Any, radius).unwrap();
+        let PickTarget::Edge(edge) = hit.target else { panic!("expected an edge, got {:?}", hit.target) };
+        let e = model.edge(edge).unwrap();
+        let ends = [model.vertex_position(e.vertices.0).unwrap(), model.vertex_position(e.vertices.1).unwrap()];
+        assert!(ends.iter().all(|p| p.x == 5.0 && p.z == 5.0));
+        assert!(pick(&model, &Vector3::new(30.0, 0.0, 20.0), &down, SelectionFilter::Any, radius).is_none());
+
+        // Filtered to faces, the corner vertex gives way to the top face
+        let hit = pick(&model, &Vector3::new(4.8, 4.9, 20.0), &down, SelectionFilter::Faces, radius).unwrap();
+        assert_eq!(hit.target, PickTarget::Face(model.faces[1].id));
+        let hit = pick(&model, &Vector3::new(1.0, 2.0, 20.0), &down, SelectionFilter::Bodies, radius).unwrap();
+        assert_eq!(hit.selection_item(SelectionFilter::Bodies), Some(SelectionItem::Body(BodyId(0))));
+    }
+
+    #[test]
+    fn test_pick_skips_unpickable_bodies() {
+        let mut model = cube(10.0);
+        let mut below = cube(10.0);
+        for v in below.vertices.iter_mut() {
+            v.position.z -= 20.0;
+        }
+        model.append(&below);
+        let (origin, down) = (Vector3::new(1.0, 2.0, 20.0), -Vector3::z());
+        let radius = |_, _: &Vector3<f64>| 0.5;
+        let hit = pick_where(&model, &origin, &down, SelectionFilter::Any, radius, |_| true).unwrap();
+        assert_eq!(hit.body, Some(BodyId(0)));
+        // With the upper body locked away, the ray reaches the one below
+        let hit = pick_where(&model, &origin, &down, SelectionFilter::Any, radius, |b| b != BodyId(0)).unwrap();
+        assert!(hit.body == Some(BodyId(1)) && (hit.depth - 35.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_ray_segment_distance() {
+        let (t, point, distance) = ray_segment(&Vector3::zeros(), &Vector3::x(), &Vector3::new(3.0, 1.0, -1.0), &Vector3::new(3.0, 1.0, 1.0));
+        assert!((t - 3.0).abs() < 1e-12 && (distance - 1.0).abs() < 1e-12);
+        assert_eq!(point, Vector3::new(3.0, 1.0, 0.0));
+    }
+}
@@ -0,0 +1,229 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: interaction::place_primitive
+//!
+//! B places a box and C a cylinder under the cursor: on the face the cursor is
+//! over, or else on the nearest workspace plane. The primitive is sent to the
+//! model command bus, which records it in the feature tree with its placement
+//! and adds it to the model. The Box and Cylinder toolbar tools do the same on
+//! click, previewing the footprint under the cursor first; value up and down
+//! grow and shrink the primitive they are about to place.
+
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use nalgebra::{Point3, Vector3};
+
+use crate::input::actions::InputAction;
+use crate::input::keyboard::KeyBindings;
+use crate::interaction::tools::{Tool, ToolContext, ToolStatus};
+use crate::model::brep::placement::{placement_at, PlacementFrame};
+use crate::model::brep::topology::plane::Plane;
+use crate::model::brep_model::{bevy_vec3_to_na, na_vec3_to_bevy, BrepModel};
+use crate::model::command::ModelCommand;
+use crate::model::feature_tree::FeatureKind;
+use crate::workspace::workspace::{HelperKind, Workspace};
+
+/// Edge length of placed boxes
+pub const DEFAULT_BOX_SIZE: f64 = 50.0;
+/// Radius and height of placed cylinders
+pub const DEFAULT_CYLINDER_RADIUS: f64 = 25.0;
+pub const DEFAULT_CYLINDER_HEIGHT: f64 = 50.0;
+pub const DEFAULT_CYLINDER_SEGMENTS: usize = 32;
+/// Size factor of one value step while a tool places a primitive
+const SIZE_STEP: f64 = 1.25;
+const FOOTPRINT_COLOR: Color = Color::srgb(0.9, 0.8, 0.2);
+
+/// Primitive shapes that can be placed interactively
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrimitiveShape {
+    Box,
+    Cylinder,
+}
+
+impl PrimitiveShape {
+    /// Feature for this shape at its default size
+    pub fn feature(self, placement: Option<PlacementFrame>) -> FeatureKind {
+        self.scaled_feature(1.0, placement)
+    }
+
+    /// Feature for this shape at `scale` times its default size
+    pub fn scaled_feature(self, scale: f64, placement: Option<PlacementFrame>) -> FeatureKind {
+        match self {
+            PrimitiveShape::Box => FeatureKind::Box { size: Vector3::repeat(DEFAULT_BOX_SIZE * scale), placement },
+            PrimitiveShape::Cylinder => FeatureKind::Cylinder {
+                bottom_radius: DEFAULT_CYLINDER_RADIUS * scale,
+                top_radius: DEFAULT_CYLINDER_RADIUS * scale,
+                height: DEFAULT_CYLINDER_HEIGHT * scale,
+                segments: DEFAULT_CYLINDER_SEGMENTS,
+                placement,
+            },
+        }
+    }
+}
+
+/// Request to create a primitive; without a placement it is centered at the origin
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct PlacePrimitive {
+    pub shape: PrimitiveShape,
+    pub placement: Option<PlacementFrame>,
+}
+
+/// Visible construction planes of the workspace
+fn visible_planes(workspace: &Workspace) -> Vec<Plane> {
+    workspace
+        .helpers
+        .iter()
+        .filter_map(|h| match &h.kind {
+            HelperKind::Plane(plane) if plane.visible => Some(plane.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Turn the place_box / place_cylinder bindings (B / C) into placement requests at the cursor
+pub fn place_primitive_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    model: Res<BrepModel>,
+    workspace: Res<Workspace>,
+    mut requests: EventWriter<PlacePrimitive>,
+) {
+    let shape = if bindings.just_pressed("place_box", &keys) {
+        PrimitiveShape::Box
+    } else if bindings.just_pressed("place_cylinder", &keys) {
+        PrimitiveShape::Cylinder
+    } else {
+        return;
+    };
+    let placement = (|| {
+        let cursor = windows.single().ok()?.cursor_position()?;
+        let (camera, transform) = cameras.single().ok()?;
+        let ray = camera.viewport_to_world(transform, cursor).ok()?;
+        let origin = Point3::from(bevy_vec3_to_na(&ray.origin));
+        placement_at(&model, &visible_planes(&workspace), &origin, &bevy_vec3_to_na(&ray.direction.as_vec3()))
+    })();
+    requests.write(PlacePrimitive { shape, placement });
+}
+
+/// Forward placement requests to the model command bus
+pub fn apply_place_primitive(mut events: EventReader<PlacePrimitive>, mut commands: EventWriter<ModelCommand>) {
+    for ev in events.read() {
+        commands.write(ModelCommand::CreatePrimitive(ev.shape.feature(ev.placement)));
+    }
+}
+
+/// Closed outline of the base of a primitive at `scale` times its default size on its frame
+pub fn footprint(shape: PrimitiveShape, scale: f64, frame: &PlacementFrame) -> Vec<Vector3<f64>> {
+    let y_axis = frame.normal.cross(&frame.x_axis);
+    let local: Vec<(f64, f64)> = match shape {
+        PrimitiveShape::Box => {
+            let h = DEFAULT_BOX_SIZE * scale / 2.0;
+            vec![(-h, -h), (h, -h), (h, h), (-h, h), (-h, -h)]
+        }
+        PrimitiveShape::Cylinder => (0..=DEFAULT_CYLINDER_SEGMENTS)
+            .map(|i| {
+                let angle = std::f64::consts::TAU * i as f64 / DEFAULT_CYLINDER_SEGMENTS as f64;
+                let r = DEFAULT_CYLINDER_RADIUS * scale;
+                (r * angle.cos(), r * angle.sin())
+            })
+            .collect(),
+    };
+    local.into_iter().map(|(u, v)| frame.origin.coords + frame.x_axis * u + y_axis * v).collect()
+}
+
+/// Toolbar tool placing a primitive on the face or plane under the cursor on select or confirm
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PrimitiveTool {
+    pub shape: PrimitiveShape,
+    placement: Option<PlacementFrame>,
+    /// Size relative to the default, stepped by value up and down
+    scale: f64,
+}
+
+impl PrimitiveTool {
+    pub fn new(shape: PrimitiveShape) -> Self {
+        Self { shape, placement: None, scale: 1.0 }
+    }
+}
+
+impl Tool for PrimitiveTool {
+    fn name(&self) -> &str {
+        match self.shape {
+            PrimitiveShape::Box => "Box",
+            PrimitiveShape::Cylinder => "Cylinder",
+        }
+    }
+
+    fn activate(&mut self, _ctx: &mut ToolContext) {
+        self.scale = 1.0;
+    }
+
+    fn input(&mut self, ctx: &mut ToolContext) -> ToolStatus {
+        self.placement = ctx
+            .pick
+            .ray
+            .and_then(|(origin, dir)| placement_at(ctx.model, &visible_planes(ctx.workspace), &Point3::from(origin), &dir));
+        self.scale *= SIZE_STEP.powi(ctx.actions.step());
+        if self.placement.is_some() && (ctx.actions.just_pressed(InputAction::Select) || ctx.actions.just_pressed(InputAction::Confirm)) {
+            ToolStatus::Commit
+        } else {
+            ToolStatus::Continue
+        }
+    }
+
+    fn draw(&self, _ctx: &ToolContext, gizmos: &mut Gizmos) {
+        if let Some(frame) = &self.placement {
+            gizmos.linestrip(footprint(self.shape, self.scale, frame).iter().map(na_vec3_to_bevy), FOOTPRINT_COLOR);
+        }
+    }
+
+    fn commit(&mut self, ctx: &mut ToolContext) {
+        ctx.send(ModelCommand::CreatePrimitive(self.shape.scaled_feature(self.scale, self.placement.take())));
+    }
+
+    fn cancel(&mut self) {
+        self.placement = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::cube;
+    use crate::model::command::execute_model_commands;
+    use crate::model::feature_tree::FeatureTree;
+
+    #[test]
+    fn test_apply_place_primitive() {
+        let mut app = App::new();
+        app.insert_resource(cube(10.0))
+            .init_resource::<FeatureTree>()
+            .add_event::<PlacePrimitive>()
+            .add_event::<ModelCommand>()
+            .add_systems(Update, (apply_place_primitive, execute_model_commands).chain());
+        let frame = PlacementFrame::new(Point3::new(0.0, 0.0, 5.0), Vector3::z());
+        app.world_mut().send_event(PlacePrimitive { shape: PrimitiveShape::Box, placement: Some(frame) });
+        app.update();
+
+        let tree = app.world().resource::<FeatureTree>();
+        assert_eq!(tree.features[0].name, "Box.001");
+        let model = app.world().resource::<BrepModel>();
+        assert_eq!(model.faces.len(), 12);
+        let top = model.vertices.iter().map(|v| v.position.z).fold(f64::NEG_INFINITY, f64::max);
+        assert!((top - (5.0 + DEFAULT_BOX_SIZE)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_footprints_lie_on_the_frame() {
+        let frame = PlacementFrame::new(Point3::new(0.0, 0.0, 5.0), Vector3::z());
+        let square = footprint(PrimitiveShape::Box, 1.0, &frame);
+        assert_eq!(square.len(), 5);
+        assert_eq!(square.first(), square.last());
+        assert!(square.iter().all(|p| (p.z - 5.0).abs() < 1e-9 && (p.x.abs() - DEFAULT_BOX_SIZE / 2.0).abs() < 1e-9));
+        let circle = footprint(PrimitiveShape::Cylinder, 2.0, &frame);
+        assert!(circle.iter().all(|p| (p.xy().norm() - 2.0 * DEFAULT_CYLINDER_RADIUS).abs() < 1e-9));
+    }
+}
@@ -0,0 +1,251 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: interaction::plane_suggestion
+//!
+//! N ("new sketch") proposes sketch planes from the selection: three vertices,
+//! or an edge and a vertex. The proposal is shown as a ghosted plane; N again
+//! cycles through the alternatives, Shift+N flips the normal, Enter starts a sketch
+//! on it and Escape cancels. The keys are the suggest_plane, flip_plane, confirm
+//! and cancel entries in `KeyBindings`.
+
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+use nalgebra::Point3;
+
+use crate::input::keyboard::KeyBindings;
+use crate::interaction::selection::Selection;
+use crate::model::brep::topology::plane::{Plane, PlaneRenderMode};
+use crate::model::brep_model::BrepModel;
+use crate::sketch::sketch::{Sketch, Sketches};
+use crate::telemetry::crash::journal;
+use crate::telemetry::usage::UsageStats;
+
+/// A proposed sketch plane and how it was derived
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaneSuggestion {
+    pub label: &'static str,
+    pub plane: Plane,
+}
+
+impl PlaneSuggestion {
+    fn new(label: &'static str, mut plane: Plane) -> Self {
+        plane.render_mode = PlaneRenderMode::Ghosted;
+        Self { label, plane }
+    }
+}
+
+/// Planes implied by the selection, most likely first; empty if it implies none
+pub fn suggest_planes(model: &BrepModel, selection: &Selection) -> Vec<PlaneSuggestion> {
+    let tol = &model.tolerance;
+    let point = |id: usize| model.vertex_position(id).map(Point3::from);
+    let vertices: Vec<Point3<f64>> = selection.vertices().into_iter().filter_map(point).collect();
+    let edges: Vec<(Point3<f64>, Point3<f64>)> = selection
+        .edges()
+        .into_iter()
+        .filter_map(|id| {
+            let e = model.edge(id)?;
+            Some((point(e.vertices.0)?, point(e.vertices.1)?))
+        })
+        .collect();
+
+    let mut out = Vec::new();
+    match (vertices.as_slice(), edges.as_slice()) {
+        ([a, b, c], []) => {
+            if let Some(plane) = Plane::from_points_with_tolerance(*a, *b, *c, tol) {
+                out.push(PlaneSuggestion::new("Through three vertices", plane.clone()));
+                out.push(PlaneSuggestion::new("Through three vertices, flipped", plane.flip_normal()));
+            }
+        }
+        ([p], [(a, b)]) => {
+            if let Some(plane) = Plane::from_points_with_tolerance(*a, *b, *p, tol) {
+                out.push(PlaneSuggestion::new("Through edge and vertex", plane.clone()));
+                out.push(PlaneSuggestion::new("Through edge and vertex, flipped", plane.flip_normal()));
+            }
+            if !tol.is_zero_length((b - a).norm()) {
+                out.push(PlaneSuggestion::new("Normal to edge at vertex", Plane::from_point_normal(*p, b - a, None)));
+            }
+        }
+        _ => {}
+    }
+    out
+}
+
+/// Proposed planes while choosing where to start a sketch
+#[derive(Resource, Debug, Default, Clone)]
+pub struct PlaneSuggestionSession {
+    pub candidates: Vec<PlaneSuggestion>,
+    pub index: usize,
+}
+
+impl PlaneSuggestionSession {
+    pub fn is_active(&self) -> bool {
+        !self.candidates.is_empty()
+    }
+
+    pub fn current(&self) -> Option<&PlaneSuggestion> {
+        self.candidates.get(self.index)
+    }
+
+    pub fn begin(&mut self, candidates: Vec<PlaneSuggestion>) {
+        self.candidates = candidates;
+        self.index = 0;
+    }
+
+    pub fn cycle(&mut self) {
+        if !self.candidates.is_empty() {
+            self.index = (self.index + 1) % self.candidates.len();
+        }
+    }
+
+    /// Reverse the normal of the current proposal
+    pub fn flip(&mut self) {
+        if let Some(s) = self.candidates.get_mut(self.index) {
+            s.plane = s.plane.flip_normal();
+        }
+    }
+
+    pub fn cancel(&mut self) {
+        self.candidates.clear();
+        self.index = 0;
+    }
+
+    /// Finish choosing and return the accepted plane
+    pub fn accept(&mut self) -> Option<Plane> {
+        let mut plane = self.current()?.plane.clone();
+        plane.render_mode = PlaneRenderMode::Simple;
+        self.cancel();
+        Some(plane)
+    }
+}
+
+/// Run condition: true unless a plane proposal is capturing the keyboard
+pub fn not_suggesting_plane(session: Option<Res<PlaneSuggestionSession>>) -> bool {
+    !session.is_some_and(|s| s.is_active())
+}
+
+/// Request to start a new sketch on a plane
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct NewSketch {
+    pub plane: Plane,
+}
+
+/// Keyboard flow for proposing, cycling, flipping and accepting sketch planes
+pub fn plane_suggestion_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    model: Res<BrepModel>,
+    selection: Res<Selection>,
+    mut session: ResMut<PlaneSuggestionSession>,
+    mut requests: EventWriter<NewSketch>,
+) {
+    if !session.is_active() {
+        if bindings.just_pressed("suggest_plane", &keys) {
+            let candidates = suggest_planes(&model, &selection);
+            if candidates.is_empty() {
+                info!("Select three vertices, or an edge and a vertex, to propose a sketch plane");
+            }
+            session.begin(candidates);
+        }
+        return;
+    }
+    if bindings.just_pressed("suggest_plane", &keys) {
+        session.cycle();
+    } else if bindings.just_pressed("flip_plane", &keys) {
+        session.flip();
+    } else if bindings.just_pressed("confirm", &keys) {
+        if let Some(plane) = session.accept() {
+            requests.write(NewSketch { plane });
+        }
+    } else if bindings.just_pressed("cancel", &keys) {
+        session.cancel();
+    }
+}
+
+/// Ghost preview of the proposed plane
+pub fn render_plane_suggestion(mut gizmos: Gizmos, session: Res<PlaneSuggestionSession>) {
+    if let Some(s) = session.current() {
+        s.plane.render(&mut gizmos);
+    }
+}
+
+/// Create sketches for accepted planes and make the newest one active
+pub fn apply_new_sketch(
+    mut events: EventReader<NewSketch>,
+    mut sketches: ResMut<Sketches>,
+    mut usage: Option<ResMut<UsageStats>>,
+) {
+    for ev in events.read() {
+        let start = Instant::now();
+        let name = format!("Sketch.{:03}", sketches.sketches.len() + 1);
+        journal(format!("new_sketch {} normal {:?}", name, ev.plane.normal));
+        let index = sketches.add(Sketch::new(name, ev.plane.clone()));
+        sketches.active = Some(index);
+        if let Some(usage) = usage.as_mut() {
+            usage.record("new_sketch", start.elapsed());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interaction::selection::SelectionItem;
+    use crate::model::brep::primitives::cube;
+    use crate::model::brep::topology::plane::PlaneOrigin;
+    use nalgebra::Vector3;
+
+    #[test]
+    fn test_suggestions_from_selection() {
+        let model = cube(2.0);
+        let corner = |p: Vector3<f64>| model.find_vertex_at(&p).unwrap();
+        let mut selection = Selection::default();
+        for p in [Vector3::new(-1.0, -1.0, 1.0), Vector3::new(1.0, -1.0, 1.0), Vector3::new(1.0, 1.0, 1.0)] {
+            selection.add(SelectionItem::Vertex(corner(p)));
+        }
+        let planes = suggest_planes(&model, &selection);
+        assert_eq!(planes.len(), 2);
+        assert!(matches!(planes[0].plane.origin, PlaneOrigin::ThreePoints { .. }));
+        assert!((planes[0].plane.normal - Vector3::z()).norm() < 1e-9);
+        assert!((planes[1].plane.normal + Vector3::z()).norm() < 1e-9);
+
+        // Bottom edge along X and a vertex on the top face
+        let (a, b) = (corner(Vector3::new(-1.0, -1.0, -1.0)), corner(Vector3::new(1.0, -1.0, -1.0)));
+        let edge = model.find_edge_between(a, b).unwrap();
+        selection.clear();
+        selection.add(SelectionItem::Edge(edge));
+        selection.add(SelectionItem::Vertex(corner(Vector3::new(1.0, -1.0, 1.0))));
+        let planes = suggest_planes(&model, &selection);
+        assert_eq!(planes.len(), 3);
+        assert!(planes[0].plane.normal.y.abs() > 1.0 - 1e-9);
+        assert!(matches!(planes[2].plane.origin, PlaneOrigin::PointNormal { .. }));
+        assert!((planes[2].plane.normal.x.abs() - 1.0).abs() < 1e-9);
+
+        selection.clear();
+        selection.add(SelectionItem::Vertex(a));
+        assert!(suggest_planes(&model, &selection).is_empty());
+    }
+
+    #[test]
+    fn test_accept_creates_active_sketch() {
+        let mut session = PlaneSuggestionSession::default();
+        session.begin(vec![
+            PlaneSuggestion::new("a", Plane::xy()),
+            PlaneSuggestion::new("b", Plane::yz()),
+        ]);
+        session.cycle();
+        session.flip();
+        let plane = session.accept().unwrap();
+        assert!(!session.is_active());
+        assert!((plane.normal + Vector3::x()).norm() < 1e-9);
+        assert_eq!(plane.render_mode, PlaneRenderMode::Simple);
+
+        let mut app = App::new();
+        app.init_resource::<Sketches>().add_event::<NewSketch>().add_systems(Update, apply_new_sketch);
+        app.world_mut().send_event(NewSketch { plane });
+        app.update();
+        let sketches = app.world().resource::<Sketches>();
+        assert_eq!(sketches.active().unwrap().name, "Sketch.001");
+        assert!((sketches.active().unwrap().plane.normal + Vector3::x()).norm() < 1e-9);
+    }
+}
@@ -0,0 +1,310 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: interaction::quick_boolean
+//!
+//! One-key booleans on the two selected bodies: Ctrl+J joins them, Ctrl+M
+//! subtracts the second from the first and Ctrl+G keeps their overlap. The
+//! operation is recorded in the feature tree on the features the two bodies
+//! are linked to; a body without one, such as an imported body or one reshaped
+//! by vertex edits, is first recorded as it is. The result replaces the bodies
+//! in the model and takes over the first body's id, name and material; when
+//! it falls apart into several shells, each extra shell gets a copy of those
+//! properties under a generated name. When the app runs background jobs the
+//! boolean runs as one, so large bodies do not stall the view.
+
+use std::fmt;
+
+use bevy::prelude::*;
+
+use crate::input::keyboard::KeyBindings;
+use crate::interaction::selection::{Selection, SelectionItem};
+use crate::measure::mass_properties::compute_mass_properties;
+use crate::model::body::{Body, BodyId};
+use crate::model::brep::operations::boolean::BooleanOp;
+use crate::model::brep_model::BrepModel;
+use crate::model::command::ModelCommand;
+use crate::model::feature_tree::{FeatureError, FeatureId, FeatureKind, FeatureOutput, FeatureTree};
+use crate::model::jobs::{BackgroundJobs, Job};
+use crate::model::properties::{base_name, BodyProperties, BodyPropertiesCollection};
+
+/// Why a quick boolean was refused
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuickBooleanError {
+    /// Exactly two bodies must be selected
+    BodyCount(usize),
+    UnknownBody(BodyId),
+    Feature(FeatureError),
+}
+
+impl fmt::Display for QuickBooleanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuickBooleanError::BodyCount(n) => write!(f, "select two bodies, not {}", n),
+            QuickBooleanError::UnknownBody(id) => write!(f, "no body with id {}", id.0),
+            QuickBooleanError::Feature(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for QuickBooleanError {}
+
+/// Bodies (connected shells) touched by the selection, in the order they were picked
+pub fn selected_bodies(model: &BrepModel, selection: &Selection) -> Vec<BodyId> {
+    let face_bodies = model.face_bodies();
+    let mut bodies = Vec::new();
+    for item in &selection.items {
+        let body = match *item {
+            SelectionItem::Vertex(id) => model.faces_using_vertex(id).first().and_then(|f| face_bodies.get(f).copied()),
+            SelectionItem::Edge(id) => model.faces_using_edge(id).first().and_then(|f| face_bodies.get(f).copied()),
+            SelectionItem::Face(id) => face_bodies.get(&id).copied(),
+            SelectionItem::Body(id) => model.has_body(id).then_some(id),
+        };
+        if let Some(body) = body.filter(|b| !bodies.contains(b)) {
+            bodies.push(body);
+        }
+    }
+    bodies
+}
+
+/// Feature a body is linked to. A body without a usable one is recorded as a
+/// `Solid` feature of its current shape and linked to that.
+pub fn body_feature(model: &BrepModel, features: &mut FeatureTree, body: BodyId) -> Result<FeatureId, QuickBooleanError> {
+    if let Some(feature) = features.linked_feature(body).filter(|f| features.body(*f).is_some()) {
+        return Ok(feature);
+    }
+    let shape = model.body(body).ok_or(QuickBooleanError::UnknownBody(body))?;
+    let count = features.features.iter().filter(|f| matches!(f.kind, FeatureKind::Solid(_))).count();
+    let feature = features.add(format!("Body.{:03}", count + 1), FeatureKind::Solid(Box::new(shape))).map_err(QuickBooleanError::Feature)?;
+    features.link(body, feature);
+    Ok(feature)
+}
+
+/// Drop the features recorded after the first `len`, and the links to them
+fn truncate_history(features: &mut FeatureTree, len: usize) {
+    let added: Vec<FeatureId> = features.features.iter().skip(len).map(|f| f.id).collect();
+    for id in added {
+        let _ = features.remove(id);
+    }
+}
+
+/// Record `op` between two bodies of the model and replace them with its
+/// result, which keeps the first body's id and properties. Shells the result
+/// falls apart into beyond the first become bodies of their own, with copies
+/// of those properties; other bodies are untouched. A refused boolean leaves
+/// the history as it was.
+pub fn boolean_bodies(
+    model: &mut BrepModel,
+    features: &mut FeatureTree,
+    properties: Option<&mut BodyPropertiesCollection>,
+    op: BooleanOp,
+    target: BodyId,
+    tool: BodyId,
+) -> Result<FeatureId, QuickBooleanError> {
+    let history = features.features.len();
+    let recorded = record_boolean(model, features, op, target, tool);
+    let (id, result) = match recorded {
+        Ok(recorded) => recorded,
+        Err(err) => {
+            truncate_history(features, history);
+            return Err(err);
+        }
+    };
+
+    let consumed: Vec<usize> = [target, tool].iter().flat_map(|b| model.body_faces(*b).unwrap_or_default()).collect();
+    model.faces.retain(|f| !consumed.contains(&f.id));
+    model.remove_unused();
+    let added = model.append(&result);
+    for face in model.faces.iter_mut().filter(|f| added.contains(&f.id)) {
+        face.body = Some(target);
+    }
+    model.assign_body_ids();
+    let pieces: Vec<BodyId> =
+        model.bodies().into_iter().filter(|(_, faces)| faces.iter().any(|f| added.contains(f))).map(|(body, _)| body).collect();
+    features.unlink(tool);
+    features.unlink(target);
+    if pieces.contains(&target) {
+        features.link(target, id);
+    }
+
+    if let Some(properties) = properties {
+        properties.remove(tool);
+        let template = if pieces.contains(&target) { properties.get(target).cloned() } else { properties.remove(target) };
+        for piece in pieces {
+            let Some(shape) = model.body(piece) else { continue };
+            if let Some(template) = template.as_ref().filter(|_| piece != target) {
+                let name = properties.generate_name(base_name(&template.name));
+                properties.insert(piece, BodyProperties { name, ..template.clone() });
+            }
+            if let Some(props) = properties.get_mut(piece) {
+                compute_mass_properties(&Body::new(piece, shape), props);
+            }
+        }
+    }
+    Ok(id)
+}
+
+/// Record the boolean feature on the two bodies' features and return it with its result
+fn record_boolean(
+    model: &BrepModel,
+    features: &mut FeatureTree,
+    op: BooleanOp,
+    target: BodyId,
+    tool: BodyId,
+) -> Result<(FeatureId, BrepModel), QuickBooleanError> {
+    let kind = FeatureKind::Boolean { op, target: body_feature(model, features, target)?, tool: body_feature(model, features, tool)? };
+    let count = features.features.iter().filter(|f| f.kind.label() == op.label()).count();
+    let id = features.add(format!("{}.{:03}", op.label(), count + 1), kind).map_err(QuickBooleanError::Feature)?;
+    match features.result(id) {
+        Some(Ok(FeatureOutput::Body(body))) => Ok((id, body.clone())),
+        Some(Err(err)) => Err(QuickBooleanError::Feature(err.clone())),
+        _ => Err(QuickBooleanError::Feature(FeatureError::UnknownFeature(id))),
+    }
+}
+
+/// Request to combine the two selected bodies
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BooleanSelection(pub BooleanOp);
+
+/// The union / subtract / intersect bindings (Ctrl+J / Ctrl+M / Ctrl+G) combine the two selected bodies
+pub fn quick_boolean_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    model: Res<BrepModel>,
+    selection: Res<Selection>,
+    mut requests: EventWriter<BooleanSelection>,
+) {
+    let op = if bindings.just_pressed("union", &keys) {
+        BooleanOp::Union
+    } else if bindings.just_pressed("subtract", &keys) {
+        BooleanOp::Subtract
+    } else if bindings.just_pressed("intersect", &keys) {
+        BooleanOp::Intersect
+    } else {
+        return;
+    };
+    if selected_bodies(&model, &selection).len() == 2 {
+        requests.write(BooleanSelection(op));
+    }
+}
+
+/// Forward requested booleans on the selected bodies to the background jobs,
+/// or to the model command bus when there are none
+pub fn apply_boolean_selection(
+    mut requests: EventReader<BooleanSelection>,
+    model: Res<BrepModel>,
+    selection: Res<Selection>,
+    mut commands: EventWriter<ModelCommand>,
+    mut jobs: Option<ResMut<BackgroundJobs>>,
+) {
+    for BooleanSelection(op) in requests.read() {
+        match selected_bodies(&model, &selection)[..] {
+            [target, tool] => {
+                let command = ModelCommand::Boolean { op: *op, target, tool };
+                match jobs.as_mut() {
+                    Some(jobs) => {
+                        jobs.request(Job::Command(command));
+                    }
+                    None => {
+                        commands.write(command);
+                    }
+                }
+            }
+            ref bodies => warn!("{} refused: {}", op.label(), QuickBooleanError::BodyCount(bodies.len())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::measure::mass_properties::mass_properties;
+    use crate::model::brep::primitives::cube;
+    use crate::model::command::execute_model_commands;
+    use crate::model::material::Material;
+    use nalgebra::Vector3;
+
+    #[test]
+    fn test_subtract_selected_bodies() {
+        let mut tree = FeatureTree::new();
+        let a = tree.add("Box.001", FeatureKind::Box { size: Vector3::repeat(10.0), placement: None }).unwrap();
+        let b = tree.add("Box.002", FeatureKind::Box { size: Vector3::repeat(10.0), placement: None }).unwrap();
+        let moved = tree.add("Move.001", FeatureKind::Translate { body: b, offset: Vector3::repeat(5.0) }).unwrap();
+        let mut model = BrepModel::new();
+        model.append(tree.body(a).unwrap());
+        model.append(tree.body(moved).unwrap());
+        tree.link(BodyId(0), a);
+        tree.link(BodyId(1), moved);
+        let mut props = BodyPropertiesCollection::new();
+        props.register(BodyId(0), "Box").material = Material::new("Red", [1.0, 0.0, 0.0]);
+        props.register(BodyId(1), "Box");
+        let selection = Selection { items: vec![SelectionItem::Face(model.faces[0].id), SelectionItem::Face(model.faces[6].id)], ..Default::default() };
+
+        let mut app = App::new();
+        app.insert_resource(model)
+            .insert_resource(tree)
+            .insert_resource(props)
+            .insert_resource(selection)
+            .add_event::<BooleanSelection>()
+            .add_event::<ModelCommand>()
+            .add_systems(Update, (apply_boolean_selection, execute_model_commands).chain());
+        app.world_mut().send_event(BooleanSelection(BooleanOp::Subtract));
+        app.update();
+
+        let tree = app.world().resource::<FeatureTree>();
+        let last = tree.features.last().unwrap();
+        assert_eq!(last.name, "Subtract.001");
+        assert_eq!(last.kind.inputs(), vec![a, moved]);
+        assert_eq!((tree.linked_feature(BodyId(0)), tree.linked_feature(BodyId(1))), (Some(last.id), None));
+        let model = app.world().resource::<BrepModel>();
+        assert!((mass_properties(model).volume - 875.0).abs() < 1e-6);
+        let props = app.world().resource::<BodyPropertiesCollection>();
+        assert_eq!(props.len(), 1);
+        let result = props.get(BodyId(0)).unwrap();
+        assert_eq!((result.name.as_str(), result.material.name.as_str()), ("Box.001", "Red"));
+        assert!(app.world().resource::<Selection>().is_empty());
+    }
+
+    #[test]
+    fn test_bodies_without_history_are_recorded_first() {
+        let mut tree = FeatureTree::new();
+        let mut model = BrepModel::new();
+        let imported = model.add_body(&cube(10.0));
+        let other = model.add_body(&cube(4.0));
+        let union = boolean_bodies(&mut model, &mut tree, None, BooleanOp::Union, imported, other).unwrap();
+        let labels: Vec<&str> = tree.features.iter().map(|f| f.kind.label()).collect();
+        assert_eq!(labels, ["Body", "Body", "Union"]);
+        assert_eq!(tree.linked_feature(imported), Some(union));
+        assert!((mass_properties(&model).volume - 1000.0).abs() < 1e-6);
+
+        // A refused boolean takes its snapshots back out of the history
+        let extra = model.add_body(&cube(2.0));
+        let missing = boolean_bodies(&mut model, &mut tree, None, BooleanOp::Union, extra, BodyId(9));
+        assert_eq!(missing, Err(QuickBooleanError::UnknownBody(BodyId(9))));
+        assert_eq!((tree.features.len(), tree.linked_feature(extra)), (3, None));
+    }
+
+    #[test]
+    fn test_every_result_shell_gets_properties() {
+        let mut tree = FeatureTree::new();
+        let mut model = BrepModel::new();
+        let a = model.add_body(&cube(10.0));
+        let mut far = cube(10.0);
+        for v in &mut far.vertices {
+            v.position.x += 100.0;
+        }
+        let b = model.add_body(&far);
+        let mut props = BodyPropertiesCollection::new();
+        let first = props.register(a, "Box");
+        first.material = Material::new("Red", [1.0, 0.0, 0.0]);
+        first.layer = Some("Frame".into());
+        props.register(b, "Box");
+
+        boolean_bodies(&mut model, &mut tree, Some(&mut props), BooleanOp::Union, a, b).unwrap();
+        assert_eq!(model.body_ids(), [a, BodyId(2)]);
+        let piece = props.get(BodyId(2)).unwrap();
+        assert_eq!((piece.name.as_str(), piece.material.name.as_str(), piece.layer.as_deref()), ("Box.002", "Red", Some("Frame")));
+        assert!((piece.volume.unwrap() - 1000.0).abs() < 1e-6);
+        assert_eq!(props.len(), 2);
+    }
+}
@@ -0,0 +1,148 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: interaction::rename
+//!
+//! F2 (the `rename_body` key binding) starts renaming the active body; typed
+//! characters edit the name, Enter commits it through a `RenameBody` event and
+//! Escape cancels. Renames reach the model as `ModelCommand::RenameBody`.
+
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+
+use crate::input::keyboard::KeyBindings;
+use crate::interaction::state::ActiveBody;
+use crate::model::body::BodyId;
+use crate::model::command::ModelCommand;
+use crate::model::properties::BodyPropertiesCollection;
+
+/// Request to rename a body (from the keyboard flow, the model tree, or scripts)
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct RenameBody {
+    pub body: BodyId,
+    pub name: String,
+}
+
+/// In-progress rename, if any
+#[derive(Resource, Debug, Default, Clone)]
+pub struct RenameSession {
+    pub target: Option<BodyId>,
+    pub buffer: String,
+}
+
+impl RenameSession {
+    pub fn is_active(&self) -> bool {
+        self.target.is_some()
+    }
+
+    pub fn begin(&mut self, body: BodyId, current_name: &str) {
+        self.target = Some(body);
+        self.buffer = current_name.to_string();
+    }
+
+    pub fn cancel(&mut self) {
+        self.target = None;
+        self.buffer.clear();
+    }
+
+    /// Finish editing and return the rename request
+    pub fn commit(&mut self) -> Option<RenameBody> {
+        let body = self.target.take()?;
+        Some(RenameBody { body, name: std::mem::take(&mut self.buffer) })
+    }
+}
+
+/// Run condition: true unless a rename is capturing the keyboard
+pub fn not_renaming(session: Option<Res<RenameSession>>) -> bool {
+    !session.is_some_and(|s| s.is_active())
+}
+
+/// Keyboard-driven rename of the active body
+pub fn rename_input_system(
+    (keys, bindings): (Res<ButtonInput<KeyCode>>, Res<KeyBindings>),
+    mut key_events: EventReader<KeyboardInput>,
+    active: Res<ActiveBody>,
+    properties: Res<BodyPropertiesCollection>,
+    mut session: ResMut<RenameSession>,
+    mut renames: EventWriter<RenameBody>,
+) {
+    if !session.is_active() {
+        key_events.clear();
+        if bindings.just_pressed("rename_body", &keys) {
+            if let Some(id) = active.0 {
+                let current = properties.get(id).map(|p| p.name.as_str()).unwrap_or_default();
+                session.begin(id, current);
+            }
+        }
+        return;
+    }
+    for ev in key_events.read() {
+        if ev.state != ButtonState::Pressed {
+            continue;
+        }
+        match &ev.logical_key {
+            Key::Enter => {
+                if let Some(request) = session.commit() {
+                    renames.write(request);
+                }
+                return;
+            }
+            Key::Escape => {
+                session.cancel();
+                return;
+            }
+            Key::Backspace => {
+                session.buffer.pop();
+            }
+            Key::Space => session.buffer.push(' '),
+            Key::Character(text) => session.buffer.extend(text.chars().filter(|c| !c.is_control())),
+            _ => {}
+        }
+    }
+}
+
+/// Send rename requests to the model command bus, which enforces unique names
+pub fn apply_rename_requests(mut events: EventReader<RenameBody>, mut commands: EventWriter<ModelCommand>) {
+    for ev in events.read() {
+        commands.write(ModelCommand::RenameBody { body: ev.body, name: ev.name.clone() });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::cube;
+    use crate::model::command::execute_model_commands;
+    use crate::model::feature_tree::FeatureTree;
+
+    #[test]
+    fn test_session_commit_and_cancel() {
+        let mut s = RenameSession::default();
+        assert!(!s.is_active());
+        s.begin(BodyId(3), "Cube.001");
+        s.buffer.push('x');
+        assert_eq!(s.commit(), Some(RenameBody { body: BodyId(3), name: "Cube.001x".into() }));
+        assert!(!s.is_active());
+        s.begin(BodyId(3), "Cube.001");
+        s.cancel();
+        assert_eq!(s.commit(), None);
+    }
+
+    #[test]
+    fn test_apply_rename_requests() {
+        let mut app = App::new();
+        let mut props = BodyPropertiesCollection::new();
+        props.register(BodyId(0), "Cube");
+        app.insert_resource(cube(10.0))
+            .init_resource::<FeatureTree>()
+            .insert_resource(props)
+            .add_event::<RenameBody>()
+            .add_event::<ModelCommand>()
+            .add_systems(Update, (apply_rename_requests, execute_model_commands).chain());
+        app.world_mut().send_event(RenameBody { body: BodyId(0), name: "Bracket".into() });
+        app.update();
+        let props = app.world().resource::<BodyPropertiesCollection>();
+        assert_eq!(props.get(BodyId(0)).unwrap().name, "Bracket");
+    }
+}
@@ -0,0 +1,227 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: interaction::selection
+//!
+//! Topology the user has selected, in the order it was picked. Commands that
+//! take their inputs from the selection (sketch planes, measurements, delete)
+//! read it, or listen for `SelectionChanged`. A filter limits picking to one
+//! kind of element; Q cycles it.
+
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::input::keyboard::KeyBindings;
+use crate::model::body::BodyId;
+use crate::telemetry::crash::journal;
+use crate::telemetry::usage::UsageStats;
+
+/// A selected topological element of the model, by id
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SelectionItem {
+    Vertex(usize),
+    Edge(usize),
+    Face(usize),
+    /// A whole connected shell
+    Body(BodyId),
+}
+
+/// Which kinds of element picking may select
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SelectionFilter {
+    /// Vertices, edges and faces
+    #[default]
+    Any,
+    Vertices,
+    Edges,
+    Faces,
+    Bodies,
+}
+
+impl SelectionFilter {
+    pub const ALL: [SelectionFilter; 5] =
+        [SelectionFilter::Any, SelectionFilter::Vertices, SelectionFilter::Edges, SelectionFilter::Faces, SelectionFilter::Bodies];
+
+    pub fn accepts(&self, item: &SelectionItem) -> bool {
+        matches!(
+            (self, item),
+            (SelectionFilter::Any, SelectionItem::Vertex(_) | SelectionItem::Edge(_) | SelectionItem::Face(_))
+                | (SelectionFilter::Vertices, SelectionItem::Vertex(_))
+                | (SelectionFilter::Edges, SelectionItem::Edge(_))
+                | (SelectionFilter::Faces, SelectionItem::Face(_))
+                | (SelectionFilter::Bodies, SelectionItem::Body(_))
+        )
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SelectionFilter::Any => "Any",
+            SelectionFilter::Vertices => "Vertices",
+            SelectionFilter::Edges => "Edges",
+            SelectionFilter::Faces => "Faces",
+            SelectionFilter::Bodies => "Bodies",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|f| *f == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+}
+
+/// Current selection, oldest pick first
+#[derive(Resource, Debug, Default, Clone, PartialEq)]
+pub struct Selection {
+    pub items: Vec<SelectionItem>,
+    pub filter: SelectionFilter,
+}
+
+impl Selection {
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn contains(&self, item: SelectionItem) -> bool {
+        self.items.contains(&item)
+    }
+
+    /// Add an item if it is not already selected
+    pub fn add(&mut self, item: SelectionItem) {
+        if !self.contains(item) {
+            self.items.push(item);
+        }
+    }
+
+    pub fn remove(&mut self, item: SelectionItem) {
+        self.items.retain(|i| *i != item);
+    }
+
+    /// Add an item, or remove it if it was already selected
+    pub fn toggle(&mut self, item: SelectionItem) {
+        if self.contains(item) {
+            self.remove(item);
+        } else {
+            self.items.push(item);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    pub fn vertices(&self) -> Vec<usize> {
+        self.items.iter().filter_map(|i| if let SelectionItem::Vertex(id) = i { Some(*id) } else { None }).collect()
+    }
+
+    pub fn edges(&self) -> Vec<usize> {
+        self.items.iter().filter_map(|i| if let SelectionItem::Edge(id) = i { Some(*id) } else { None }).collect()
+    }
+
+    pub fn faces(&self) -> Vec<usize> {
+        self.items.iter().filter_map(|i| if let SelectionItem::Face(id) = i { Some(*id) } else { None }).collect()
+    }
+
+    pub fn bodies(&self) -> Vec<BodyId> {
+        self.items.iter().filter_map(|i| if let SelectionItem::Body(id) = i { Some(*id) } else { None }).collect()
+    }
+
+    /// Change the filter, dropping items it does not accept
+    pub fn set_filter(&mut self, filter: SelectionFilter) {
+        self.filter = filter;
+        self.items.retain(|i| filter.accepts(i));
+    }
+}
+
+/// Sent whenever the selected items change
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct SelectionChanged {
+    pub items: Vec<SelectionItem>,
+}
+
+/// Announce selection changes to other systems
+pub fn notify_selection_changes(
+    selection: Res<Selection>,
+    mut previous: Local<Vec<SelectionItem>>,
+    mut events: EventWriter<SelectionChanged>,
+) {
+    if selection.items != *previous {
+        *previous = selection.items.clone();
+        events.write(SelectionChanged { items: selection.items.clone() });
+    }
+}
+
+/// Request to change the selection filter
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetSelectionFilter(pub SelectionFilter);
+
+/// The selection_filter binding (Q) cycles the selection filter
+pub fn selection_filter_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    selection: Res<Selection>,
+    mut requests: EventWriter<SetSelectionFilter>,
+) {
+    if bindings.just_pressed("selection_filter", &keys) {
+        requests.write(SetSelectionFilter(selection.filter.next()));
+    }
+}
+
+pub fn apply_selection_filter(
+    mut requests: EventReader<SetSelectionFilter>,
+    mut selection: ResMut<Selection>,
+    mut usage: Option<ResMut<UsageStats>>,
+) {
+    for SetSelectionFilter(filter) in requests.read() {
+        let start = Instant::now();
+        journal(format!("selection_filter {:?}", filter));
+        selection.set_filter(*filter);
+        if let Some(usage) = usage.as_mut() {
+            usage.record("selection_filter", start.elapsed());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggle_keeps_pick_order() {
+        let mut s = Selection::default();
+        s.add(SelectionItem::Vertex(3));
+        s.add(SelectionItem::Edge(1));
+        s.add(SelectionItem::Vertex(3));
+        s.toggle(SelectionItem::Vertex(0));
+        assert_eq!(s.vertices(), vec![3, 0]);
+        s.toggle(SelectionItem::Vertex(3));
+        assert_eq!(s.items, vec![SelectionItem::Edge(1), SelectionItem::Vertex(0)]);
+        assert_eq!(s.edges(), vec![1]);
+        assert!(s.faces().is_empty());
+    }
+
+    #[test]
+    fn test_filter_drops_other_kinds() {
+        let mut s = Selection::default();
+        s.add(SelectionItem::Vertex(1));
+        s.add(SelectionItem::Face(2));
+        s.add(SelectionItem::Body(BodyId(0)));
+        assert!(!SelectionFilter::Any.accepts(&SelectionItem::Body(BodyId(0))));
+        s.set_filter(SelectionFilter::Faces);
+        assert_eq!(s.items, vec![SelectionItem::Face(2)]);
+        assert_eq!(SelectionFilter::Bodies.next(), SelectionFilter::Any);
+    }
+
+    #[test]
+    fn test_changes_are_announced() {
+        let mut app = App::new();
+        app.init_resource::<Selection>().add_event::<SelectionChanged>().add_systems(Update, notify_selection_changes);
+        app.update();
+        app.world_mut().resource_mut::<Selection>().add(SelectionItem::Edge(4));
+        app.update();
+        app.update();
+        let events = app.world().resource::<Events<SelectionChanged>>();
+        let sent: Vec<_> = events.get_cursor().read(events).cloned().collect();
+        assert_eq!(sent, vec![SelectionChanged { items: vec![SelectionItem::Edge(4)] }]);
+    }
+}
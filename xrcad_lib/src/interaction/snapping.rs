@@ -0,0 +1,425 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: interaction::snapping
+//!
+//! Object snapping for everything that places a point under the cursor: vertex
+//! dragging, sketching and measuring. Candidates are vertices, edge midpoints,
+//! face and circle centres, edge intersections and the grid of the active
+//! sketch plane. Those projecting within a pixel radius of the cursor compete by
+//! kind first (vertex beats intersection beats midpoint beats centre beats grid)
+//! and screen distance second. The winner is kept in [`SnapState`] and drawn as
+//! a marker whose shape tells the kind. Alt suppresses snapping. Model
+//! candidates are looked up in the [`SceneBvh`] near the cursor ray rather
+//! than across every vertex and edge.
+
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use nalgebra::{Point3, Vector2, Vector3};
+
+use crate::color::YELLOW;
+use crate::interaction::grid_snap::{snap_suppressed, GridSnap};
+use crate::interaction::picking::PickState;
+use crate::measure::distance::segment_closest_points;
+use crate::model::brep::geometry::polygon::segment_distance_2d;
+use crate::model::body::BodyId;
+use crate::model::brep_model::{na_vec3_to_bevy, BrepModel};
+use crate::model::bvh::{Aabb, SceneBvh};
+use crate::render::gizmo_scale::{GizmoScale, VERTEX_PICK_PIXELS};
+use crate::sketch::sketch::{SketchEntity, Sketches};
+
+/// Size of the snap marker (pixels)
+const MARKER_PIXELS: f32 = 7.0;
+
+/// Straight segment between two model-space points
+type Segment = (Vector3<f64>, Vector3<f64>);
+
+/// What a snap point lies on, in order of priority
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SnapKind {
+    Vertex,
+    Intersection,
+    Midpoint,
+    /// Face centroid, or circle and arc centre
+    Center,
+    Grid,
+}
+
+impl SnapKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SnapKind::Vertex => "Vertex",
+            SnapKind::Intersection => "Intersection",
+            SnapKind::Midpoint => "Midpoint",
+            SnapKind::Center => "Center",
+            SnapKind::Grid => "Grid",
+        }
+    }
+}
+
+/// Which snaps are active and how far they reach
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct SnapSettings {
+    pub enabled: bool,
+    pub vertex: bool,
+    pub intersection: bool,
+    pub midpoint: bool,
+    pub center: bool,
+    /// Snap to the active sketch plane grid (spacing from [`GridSnap`])
+    pub grid: bool,
+    /// Cursor distance within which a candidate is caught (pixels)
+    pub radius_pixels: f32,
+}
+
+impl Default for SnapSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            vertex: true,
+            intersection: true,
+            midpoint: true,
+            center: true,
+            grid: true,
+            radius_pixels: VERTEX_PICK_PIXELS,
+        }
+    }
+}
+
+impl SnapSettings {
+    pub fn allows(&self, kind: SnapKind) -> bool {
+        self.enabled
+            && match kind {
+                SnapKind::Vertex => self.vertex,
+                SnapKind::Intersection => self.intersection,
+                SnapKind::Midpoint => self.midpoint,
+                SnapKind::Center => self.center,
+                SnapKind::Grid => self.grid,
+            }
+    }
+}
+
+/// A point the cursor snapped to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SnapPoint {
+    pub kind: SnapKind,
+    /// Model-space position
+    pub position: Vector3<f64>,
+}
+
+/// Cursor ray that narrows the model candidates to those near it
+pub struct SnapRay<'a> {
+    pub origin: Vector3<f64>,
+    /// Unit direction
+    pub dir: Vector3<f64>,
+    /// Snap radius in model units at a point
+    pub radius: &'a dyn Fn(&Vector3<f64>) -> f64,
+}
+
+/// Geometry and cursor a snap is searched in
+pub struct SnapQuery<'a> {
+    pub model: &'a BrepModel,
+    /// Hierarchies of `model` the candidates are looked up in
+    pub bvh: &'a SceneBvh,
+    pub sketches: Option<&'a Sketches>,
+    /// Cursor in window coordinates
+    pub cursor: Vec2,
+    /// Without a ray every body is a candidate
+    pub ray: Option<SnapRay<'a>>,
+    /// Vertex being moved; it and the edges and faces it shapes are skipped
+    pub exclude_vertex: Option<usize>,
+    /// Grid point under the cursor, when a grid applies
+    pub grid_point: Option<Vector3<f64>>,
+}
+
+/// Model-space candidates of every kind except grid and intersection, and the
+/// segments intersections are looked for between
+fn candidates(query: &SnapQuery) -> (Vec<SnapPoint>, Vec<Segment>) {
+    let model = query.model;
+    let skip = |id: usize| query.exclude_vertex == Some(id);
+    let mut points = Vec::new();
+    let mut segments = Vec::new();
+    // Largest snap radius over a box, so nothing within reach of the ray is culled
+    let margin = |ray: &SnapRay, b: &Aabb| b.corners().iter().map(|c| (ray.radius)(c)).fold(0.0, f64::max) + model.tolerance.linear;
+    let bodies: Vec<BodyId> = match &query.ray {
+        Some(ray) => query.bvh.bodies_near_ray(&ray.origin, &ray.dir, |b| margin(ray, b)),
+        None => model.body_ids(),
+    };
+    // Vertices and edges shared by touching bodies are offered once
+    let (mut seen_vertices, mut seen_edges) = (HashSet::new(), HashSet::new());
+    for tree in bodies.into_iter().filter_map(|b| query.bvh.body(b)) {
+        let (vertices, edges) = match &query.ray {
+            Some(ray) => (
+                tree.vertices.near_ray(&ray.origin, &ray.dir, |b| margin(ray, b)),
+                tree.edges.near_ray(&ray.origin, &ray.dir, |b| margin(ray, b)),
+            ),
+            None => (tree.vertices.query(|_| true), tree.edges.query(|_| true)),
+        };
+        for id in vertices.into_iter().filter(|id| !skip(**id) && seen_vertices.insert(**id)) {
+            if let Some(position) = model.vertex_position(*id) {
+                points.push(SnapPoint { kind: SnapKind::Vertex, position });
+            }
+        }
+        for e in edges.into_iter().filter(|id| seen_edges.insert(**id)).filter_map(|id| model.edge(*id)) {
+            if skip(e.vertices.0) || skip(e.vertices.1) {
+                continue;
+            }
+            if let (Some(a), Some(b)) = (model.vertex_position(e.vertices.0), model.vertex_position(e.vertices.1)) {
+                points.push(SnapPoint { kind: SnapKind::Midpoint, position: (a + b) / 2.0 });
+                segments.push((a, b));
+            }
+        }
+        for f in tree.faces.iter().filter_map(|id| model.face(*id)) {
+            let Some(outer) = model.face_loops(f).first().map(|l| model.loop_vertex_ids(l)) else { continue };
+            if outer.is_empty() || outer.iter().any(|id| skip(*id)) {
+                continue;
+            }
+            let sum: Vector3<f64> = outer.iter().filter_map(|id| model.vertex_position(*id)).sum();
+            points.push(SnapPoint { kind: SnapKind::Center, position: sum / outer.len() as f64 });
+        }
+    }
+    if let Some(sketch) = query.sketches.and_then(|s| s.active()) {
+        let world = |id: usize| sketch.point_position(id).map(|p: Vector2<f64>| sketch.plane.point_at_2d(&p).coords);
+        for p in &sketch.points {
+            points.push(SnapPoint { kind: SnapKind::Vertex, position: sketch.plane.point_at_2d(&p.position).coords });
+        }
+        for entity in &sketch.entities {
+            match *entity {
+                SketchEntity::Line { start, end, .. } => {
+                    if let (Some(a), Some(b)) = (world(start), world(end)) {
+                        points.push(SnapPoint { kind: SnapKind::Midpoint, position: (a + b) / 2.0 });
+                        segments.push((a, b));
+                    }
+                }
+                SketchEntity::Circle { center, .. } | SketchEntity::Arc { center, .. } => {
+                    if let Some(c) = world(center) {
+                        points.push(SnapPoint { kind: SnapKind::Center, position: c });
+                    }
+                }
+            }
+        }
+    }
+    (points, segments)
+}
+
+/// Best snap within `settings.radius_pixels` of the cursor. `project` maps model
+/// positions to window positions.
+pub fn find_snap(query: &SnapQuery, settings: &SnapSettings, project: impl Fn(&Vector3<f64>) -> Option<Vec2>) -> Option<SnapPoint> {
+    if !settings.enabled {
+        return None;
+    }
+    let (mut points, segments) = candidates(query);
+    if settings.allows(SnapKind::Intersection) {
+        // Only segments passing near the cursor can intersect under it
+        let to_2d = |v: Vec2| Vector2::new(v.x as f64, v.y as f64);
+        let cursor = to_2d(query.cursor);
+        let near: Vec<&Segment> = segments
+            .iter()
+            .filter(|(a, b)| match (project(a), project(b)) {
+                (Some(sa), Some(sb)) => segment_distance_2d(&to_2d(sa), &to_2d(sb), &cursor) <= settings.radius_pixels as f64,
+                _ => false,
+            })
+            .collect();
+        let tolerance = query.model.tolerance.linear;
+        for (i, (a0, a1)) in near.iter().enumerate() {
+            for (b0, b1) in &near[i + 1..] {
+                let (p, q) = segment_closest_points(a0, a1, b0, b1);
+                let at_end = [a0, a1].iter().any(|e| (p - *e).norm() <= tolerance) && [b0, b1].iter().any(|e| (q - *e).norm() <= tolerance);
+                if (p - q).norm() <= tolerance && !at_end {
+                    points.push(SnapPoint { kind: SnapKind::Intersection, position: (p + q) / 2.0 });
+                }
+            }
+        }
+    }
+    if let Some(position) = query.grid_point {
+        points.push(SnapPoint { kind: SnapKind::Grid, position });
+    }
+    points
+        .into_iter()
+        .filter(|p| settings.allows(p.kind))
+        .filter_map(|p| Some((p, project(&p.position)?.distance(query.cursor))))
+        .filter(|(_, d)| *d <= settings.radius_pixels)
+        .min_by(|(a, da), (b, db)| a.kind.cmp(&b.kind).then(da.total_cmp(db)))
+        .map(|(p, _)| p)
+}
+
+/// Nearest grid point on the active sketch plane along the cursor ray
+pub fn sketch_grid_point(sketches: &Sketches, grid: &GridSnap, ray: (Vector3<f64>, Vector3<f64>)) -> Option<Vector3<f64>> {
+    let plane = &sketches.active()?.plane;
+    let (origin, dir) = ray;
+    let denom = plane.normal.dot(&dir);
+    if denom.abs() <= f64::EPSILON {
+        return None;
+    }
+    let hit = Point3::from(origin + dir * (-plane.distance(&Point3::from(origin)) / denom));
+    let uv = plane.project_2d(&hit);
+    let snapped = if grid.enabled && grid.increment > 0.0 { uv.map(|c| (c / grid.increment).round() * grid.increment) } else { uv };
+    Some(plane.point_at_2d(&snapped).coords)
+}
+
+/// The current snap under the cursor, refreshed every frame
+#[derive(Resource, Debug, Default, Clone, PartialEq)]
+pub struct SnapState {
+    pub current: Option<SnapPoint>,
+}
+
+/// Find the snap under the cursor
+pub fn update_snap(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    (keys, settings): (Res<ButtonInput<KeyCode>>, Res<SnapSettings>),
+    (model, bvh): (Res<BrepModel>, Option<Res<SceneBvh>>),
+    (pick, scale): (Res<PickState>, Option<Res<GizmoScale>>),
+    (sketches, grid): (Option<Res<Sketches>>, Option<Res<GridSnap>>),
+    mut state: ResMut<SnapState>,
+) {
+    let cursor = windows.single().ok().and_then(|w| w.cursor_position());
+    let (Some(cursor), Ok((camera, transform))) = (cursor, cameras.single()) else {
+        state.current = None;
+        return;
+    };
+    if snap_suppressed(&keys) {
+        state.current = None;
+        return;
+    }
+    let grid = grid.as_deref().cloned().unwrap_or_default();
+    let scale = scale.as_deref().copied().unwrap_or_default();
+    let radius = |p: &Vector3<f64>| scale.world_size(na_vec3_to_bevy(p), settings.radius_pixels) as f64;
+    let built;
+    let bvh = match bvh.as_deref() {
+        Some(bvh) => bvh,
+        None => {
+            built = SceneBvh::build(&model);
+            &built
+        }
+    };
+    let query = SnapQuery {
+        model: &model,
+        bvh,
+        sketches: sketches.as_deref(),
+        cursor,
+        ray: pick.ray.map(|(origin, dir)| SnapRay { origin, dir, radius: &radius }),
+        exclude_vertex: model.selected_vertex,
+        grid_point: sketches.as_deref().zip(pick.ray).and_then(|(s, ray)| sketch_grid_point(s, &grid, ray)),
+    };
+    let project = |p: &Vector3<f64>| camera.world_to_viewport(transform, na_vec3_to_bevy(p)).ok();
+    let current = find_snap(&query, &settings, project);
+    if state.current != current {
+        state.current = current;
+    }
+}
+
+/// Draw the current snap: square for a vertex, cross for an intersection,
+/// triangle for a midpoint, circle for a centre, plus for the grid
+pub fn render_snap_marker(
+    mut gizmos: Gizmos,
+    state: Res<SnapState>,
+    cameras: Query<&GlobalTransform, With<Camera3d>>,
+    scale: Option<Res<GizmoScale>>,
+) {
+    let (Some(snap), Ok(camera)) = (state.current, cameras.single()) else { return };
+    let scale = scale.as_deref().copied().unwrap_or_default();
+    let center = na_vec3_to_bevy(&snap.position);
+    let size = scale.world_size(center, MARKER_PIXELS);
+    let (right, up) = (camera.right() * size, camera.up() * size);
+    let at = |x: f32, y: f32| center + right * x + up * y;
+    match snap.kind {
+        SnapKind::Vertex => gizmos.linestrip([at(-1.0, -1.0), at(1.0, -1.0), at(1.0, 1.0), at(-1.0, 1.0), at(-1.0, -1.0)], YELLOW),
+        SnapKind::Intersection => {
+            gizmos.line(at(-1.0, -1.0), at(1.0, 1.0), YELLOW);
+            gizmos.line(at(-1.0, 1.0), at(1.0, -1.0), YELLOW);
+        }
+        SnapKind::Midpoint => gizmos.linestrip([at(-1.0, -0.8), at(1.0, -0.8), at(0.0, 1.0), at(-1.0, -0.8)], YELLOW),
+        SnapKind::Center => {
+            let points = (0..=16).map(|i| {
+                let a = i as f32 / 16.0 * std::f32::consts::TAU;
+                at(a.cos(), a.sin())
+            });
+            gizmos.linestrip(points, YELLOW);
+        }
+        SnapKind::Grid => {
+            gizmos.line(at(-1.0, 0.0), at(1.0, 0.0), YELLOW);
+            gizmos.line(at(0.0, -1.0), at(0.0, 1.0), YELLOW);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::cube;
+
+    /// Top view at 10 pixels per millimetre
+    fn top_view(p: &Vector3<f64>) -> Option<Vec2> {
+        Some(Vec2::new(p.x as f32 * 10.0, p.y as f32 * 10.0))
+    }
+
+    fn query<'a>(model: &'a BrepModel, bvh: &'a SceneBvh, cursor: Vec2) -> SnapQuery<'a> {
+        SnapQuery { model, bvh, sketches: None, cursor, ray: None, exclude_vertex: None, grid_point: None }
+    }
+
+    #[test]
+    fn test_snap_priority() {
+        let model = cube(10.0);
+        let bvh = SceneBvh::build(&model);
+        let settings = SnapSettings::default();
+        // Corner (5, 5) from above: a top and a bottom vertex coincide on screen
+        let snap = find_snap(&query(&model, &bvh, Vec2::new(49.0, 48.0)), &settings, top_view).unwrap();
+        assert_eq!(snap.kind, SnapKind::Vertex);
+        assert_eq!((snap.position.x, snap.position.y), (5.0, 5.0));
+        let snap = find_snap(&query(&model, &bvh, Vec2::new(2.0, 49.0)), &settings, top_view).unwrap();
+        assert_eq!((snap.kind, snap.position.x, snap.position.y), (SnapKind::Midpoint, 0.0, 5.0));
+        let snap = find_snap(&query(&model, &bvh, Vec2::new(3.0, -4.0)), &settings, top_view).unwrap();
+        assert_eq!((snap.kind, snap.position.x, snap.position.y), (SnapKind::Center, 0.0, 0.0));
+        assert!(find_snap(&query(&model, &bvh, Vec2::new(25.0, 25.0)), &settings, top_view).is_none());
+        let vertices_off = SnapSettings { vertex: false, ..settings.clone() };
+        assert_eq!(find_snap(&query(&model, &bvh, Vec2::new(49.0, 48.0)), &vertices_off, top_view).unwrap().kind, SnapKind::Midpoint);
+
+        // The dragged vertex and the edges and faces it shapes do not attract
+        let mut q = query(&model, &bvh, Vec2::new(49.0, 48.0));
+        let dragged = model.vertices.iter().find(|v| v.position == Vector3::new(5.0, 5.0, 5.0)).unwrap().id;
+        q.exclude_vertex = Some(dragged);
+        assert_eq!(find_snap(&q, &settings, top_view).unwrap().position, Vector3::new(5.0, 5.0, -5.0));
+    }
+
+    #[test]
+    fn test_intersection_and_grid() {
+        let mut model = BrepModel::new();
+        model.add_face(&[Vector3::new(0.0, 0.0, 0.0), Vector3::new(4.0, 0.0, 0.0), Vector3::new(4.0, 4.0, 0.0), Vector3::new(0.0, 4.0, 0.0)]);
+        model.add_face(&[Vector3::new(2.0, 2.0, 0.0), Vector3::new(6.0, 2.0, 0.0), Vector3::new(6.0, 6.0, 0.0), Vector3::new(2.0, 6.0, 0.0)]);
+        let bvh = SceneBvh::build(&model);
+        let settings = SnapSettings::default();
+        let snap = find_snap(&query(&model, &bvh, Vec2::new(41.0, 21.0)), &settings, top_view).unwrap();
+        assert_eq!(snap.kind, SnapKind::Intersection);
+        assert!((snap.position - Vector3::new(4.0, 2.0, 0.0)).norm() < 1e-9);
+
+        let mut q = query(&model, &bvh, Vec2::new(101.0, 99.0));
+        q.grid_point = Some(Vector3::new(10.0, 10.0, 0.0));
+        assert_eq!(find_snap(&q, &settings, top_view).unwrap().kind, SnapKind::Grid);
+        let mut sketches = Sketches::default();
+        sketches.active = Some(sketches.add(crate::sketch::sketch::Sketch::new("Sketch.001", crate::model::brep::topology::plane::Plane::xy())));
+        let grid = GridSnap { enabled: true, increment: 5.0 };
+        let point = sketch_grid_point(&sketches, &grid, (Vector3::new(6.2, 8.9, 10.0), Vector3::new(0.0, 0.0, -1.0))).unwrap();
+        assert!((point - Vector3::new(5.0, 10.0, 0.0)).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_candidates_near_the_ray() {
+        let mut model = cube(10.0);
+        let mut far = cube(10.0);
+        for v in far.vertices.iter_mut() {
+            v.position.x += 100.0;
+        }
+        model.append(&far);
+        let bvh = SceneBvh::build(&model);
+        let radius = |_: &Vector3<f64>| 0.5;
+        let mut q = query(&model, &bvh, Vec2::new(49.0, 48.0));
+        q.ray = Some(SnapRay { origin: Vector3::new(5.0, 5.0, 50.0), dir: Vector3::new(0.0, 0.0, -1.0), radius: &radius });
+        let (points, _) = candidates(&q);
+        assert!(!points.is_empty());
+        assert!(points.iter().all(|p| p.position.x < 50.0));
+        let snap = find_snap(&q, &SnapSettings::default(), top_view).unwrap();
+        assert_eq!((snap.kind, snap.position.x, snap.position.y), (SnapKind::Vertex, 5.0, 5.0));
+    }
+}
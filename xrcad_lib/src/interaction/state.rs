@@ -3,6 +3,59 @@
 
 //! Module: interaction::state
 
+use std::collections::BTreeSet;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::model::body::BodyId;
+
+/// The body that body-level commands (rename, properties) apply to
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ActiveBody(pub Option<BodyId>);
+
+/// Marks a UI panel whose visibility is kept in the `UiLayout`
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UiPanel(pub &'static str);
+
+/// Per-session UI state: hidden panels and the active tool
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UiLayout {
+    pub hidden_panels: BTreeSet<String>,
+    /// Name of the active tool, filled in when the session is saved
+    pub active_tool: Option<String>,
+}
+
+impl Default for UiLayout {
+    /// The analysis panel shares the right side with the outliner, so it starts hidden
+    fn default() -> Self {
+        Self { hidden_panels: BTreeSet::from(["analysis".to_string()]), active_tool: None }
+    }
+}
+
+impl UiLayout {
+    pub fn is_panel_visible(&self, panel: &str) -> bool {
+        !self.hidden_panels.contains(panel)
+    }
+
+    pub fn toggle_panel(&mut self, panel: &str) {
+        if !self.hidden_panels.remove(panel) {
+            self.hidden_panels.insert(panel.to_string());
+        }
+    }
+}
+
+/// Show or hide tagged panels to match the layout
+#[cfg(feature = "render")]
+pub fn apply_ui_layout(layout: Res<UiLayout>, mut panels: Query<(&UiPanel, &mut Visibility)>) {
+    for (panel, mut visibility) in panels.iter_mut() {
+        let wanted = if layout.is_panel_visible(panel.0) { Visibility::Inherited } else { Visibility::Hidden };
+        if *visibility != wanted {
+            *visibility = wanted;
+        }
+    }
+}
+
 /// Represents the state of an interaction.
 pub struct InteractionState;
 
@@ -20,4 +73,14 @@ mod tests {
         let s = InteractionState::new();
         let _ = s;
     }
+
+    #[test]
+    fn test_ui_layout_toggles() {
+        let mut layout = UiLayout::default();
+        layout.toggle_panel("lighting");
+        assert!(!layout.is_panel_visible("lighting") && layout.is_panel_visible("camera"));
+        layout.toggle_panel("lighting");
+        assert!(layout.is_panel_visible("lighting"));
+        assert!(!layout.is_panel_visible("analysis"));
+    }
 }
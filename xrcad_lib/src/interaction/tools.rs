@@ -0,0 +1,476 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: interaction::tools
+//!
+//! Modeling tools, built in or from other crates. A tool implements `Tool` and
+//! is registered on the app with `register_tool`; it then gets a toolbar button
+//! and a `tool_<name>` action in `KeyBindings`, bound to the tool's default
+//! shortcut if it has one, so tool shortcuts are rebound and checked for
+//! conflicts like any other. One tool is active at a time: it sees the
+//! input actions and cursor ray every frame, draws its previews with gizmos, and
+//! ends by committing, which sends its `ModelCommand`s, or by the cancel action
+//! (Escape by default), which leaves the model unchanged. Tools read
+//! `ActionState` rather than the devices, so they work alike from the mouse, a
+//! gamepad, touch or an XR controller.
+
+use std::fmt;
+
+use bevy::prelude::*;
+
+use crate::input::actions::{ActionState, InputAction};
+use crate::input::keyboard::{KeyBindings, KeyChord};
+use crate::interaction::picking::PickState;
+use crate::interaction::selection::Selection;
+use crate::interaction::state::UiPanel;
+use crate::model::brep_model::BrepModel;
+use crate::model::command::ModelCommand;
+use crate::render::ui_style::{BUTTON_ACTIVE, BUTTON_IDLE, PANEL_COLOR};
+use crate::telemetry::crash::journal;
+use crate::workspace::workspace::Workspace;
+
+/// What a tool wants after handling a frame of input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolStatus {
+    Continue,
+    /// Send the tool's commands and deactivate it
+    Commit,
+    /// Deactivate the tool without changing the model
+    Cancel,
+}
+
+/// What an active tool can see, and the commands it has sent
+pub struct ToolContext<'a> {
+    pub keys: &'a ButtonInput<KeyCode>,
+    pub mouse: &'a ButtonInput<MouseButton>,
+    /// Select, confirm, value steps and view axes from any device
+    pub actions: &'a ActionState,
+    /// Cursor ray and the target under it
+    pub pick: &'a PickState,
+    pub model: &'a BrepModel,
+    pub workspace: &'a Workspace,
+    pub selection: &'a Selection,
+    commands: Vec<ModelCommand>,
+}
+
+impl<'a> ToolContext<'a> {
+    pub fn new(
+        keys: &'a ButtonInput<KeyCode>,
+        mouse: &'a ButtonInput<MouseButton>,
+        actions: &'a ActionState,
+        pick: &'a PickState,
+        model: &'a BrepModel,
+        workspace: &'a Workspace,
+        selection: &'a Selection,
+    ) -> Self {
+        Self { keys, mouse, actions, pick, model, workspace, selection, commands: Vec::new() }
+    }
+
+    /// Queue a command for the command bus
+    pub fn send(&mut self, command: ModelCommand) {
+        self.commands.push(command);
+    }
+}
+
+/// A modeling tool. Only `name`, `input` and `commit` are required.
+pub trait Tool: Send + Sync + 'static {
+    /// Unique name, shown on the toolbar
+    fn name(&self) -> &str;
+
+    /// Default chord of the tool's key binding action
+    fn shortcut(&self) -> Option<KeyChord> {
+        None
+    }
+
+    /// Called when the tool becomes active
+    fn activate(&mut self, _ctx: &mut ToolContext) {}
+
+    /// Handle one frame of input while active. The cancel action is handled
+    /// by the registry and cancels the tool.
+    fn input(&mut self, ctx: &mut ToolContext) -> ToolStatus;
+
+    /// Draw previews and handles while active
+    fn draw(&self, _ctx: &ToolContext, _gizmos: &mut Gizmos) {}
+
+    /// Send the commands that make the tool's change
+    fn commit(&mut self, ctx: &mut ToolContext);
+
+    /// Drop any work in progress
+    fn cancel(&mut self) {}
+}
+
+/// Why a tool could not be registered or activated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToolError {
+    UnknownTool(String),
+    NameTaken(String),
+}
+
+impl fmt::Display for ToolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ToolError::UnknownTool(name) => write!(f, "no tool named {}", name),
+            ToolError::NameTaken(name) => write!(f, "a tool named {} is already registered", name),
+        }
+    }
+}
+
+impl std::error::Error for ToolError {}
+
+/// Registered tools, in toolbar order, and the active one
+#[derive(Resource, Default)]
+pub struct ToolRegistry {
+    tools: Vec<Box<dyn Tool>>,
+    active: Option<usize>,
+}
+
+impl ToolRegistry {
+    pub fn register(&mut self, tool: impl Tool) -> Result<usize, ToolError> {
+        if self.index_of(tool.name()).is_some() {
+            return Err(ToolError::NameTaken(tool.name().to_string()));
+        }
+        self.tools.push(Box::new(tool));
+        Ok(self.tools.len() - 1)
+    }
+
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.tools.iter().position(|t| t.name() == name)
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.tools.iter().map(|t| t.name()).collect()
+    }
+
+    pub fn active(&self) -> Option<&dyn Tool> {
+        self.tools.get(self.active?).map(|t| t.as_ref())
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active.is_some()
+    }
+}
+
+/// Registering tools from plugins, including those of other crates
+pub trait RegisterTool {
+    fn register_tool(&mut self, tool: impl Tool) -> &mut Self;
+}
+
+impl RegisterTool for App {
+    fn register_tool(&mut self, tool: impl Tool) -> &mut Self {
+        let action = tool_action(tool.name());
+        let shortcut = tool.shortcut();
+        let mut registry = self.world_mut().get_resource_or_insert_with(ToolRegistry::default);
+        if let Err(err) = registry.register(tool) {
+            warn!("Could not register tool: {}", err);
+            return self;
+        }
+        // Bindings loaded from the user's file keep their chord for the tool
+        let mut bindings = self.world_mut().get_resource_or_insert_with(KeyBindings::default);
+        if let (Some(chord), None) = (shortcut, bindings.chord(&action)) {
+            if let Err(err) = bindings.bind(&action, chord) {
+                warn!("Tool shortcut left unbound: {}", err);
+            }
+        }
+        self
+    }
+}
+
+/// Key binding action that activates a tool, e.g. "tool_box" for "Box"
+pub fn tool_action(name: &str) -> String {
+    let name: String = name.chars().map(|c| if c.is_alphanumeric() { c.to_ascii_lowercase() } else { '_' }).collect();
+    format!("tool_{}", name)
+}
+
+/// Request to activate a tool by name; the active tool's name cancels it
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct ActivateTool(pub String);
+
+/// Run condition: true unless a tool is taking the pointer
+pub fn no_active_tool(registry: Option<Res<ToolRegistry>>) -> bool {
+    !registry.is_some_and(|r| r.is_active())
+}
+
+/// Send activation requests for the bound tool actions
+pub fn tool_shortcut_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    registry: Res<ToolRegistry>,
+    mut requests: EventWriter<ActivateTool>,
+) {
+    for tool in &registry.tools {
+        if bindings.just_pressed(&tool_action(tool.name()), &keys) {
+            requests.write(ActivateTool(tool.name().to_string()));
+        }
+    }
+}
+
+/// Switch tools on request, feed the active tool its input and send the
+/// commands of tools that commit. A tool activated this frame gets its first
+/// input next frame, so the toolbar click that chose it is not also its click.
+pub fn run_tools(
+    mut requests: EventReader<ActivateTool>,
+    mut registry: ResMut<ToolRegistry>,
+    input: (Res<ButtonInput<KeyCode>>, Res<ButtonInput<MouseButton>>, Res<ActionState>),
+    (pick, model, workspace, selection): (Res<PickState>, Res<BrepModel>, Res<Workspace>, Res<Selection>),
+    mut commands: EventWriter<ModelCommand>,
+) {
+    let mut ctx = ToolContext::new(&input.0, &input.1, &input.2, &pick, &model, &workspace, &selection);
+    let registry = &mut *registry;
+    let mut activated = false;
+    for ActivateTool(name) in requests.read() {
+        let Some(index) = registry.index_of(name) else {
+            warn!("Could not activate tool: {}", ToolError::UnknownTool(name.clone()));
+            continue;
+        };
+        if let Some(active) = registry.active.take() {
+            registry.tools[active].cancel();
+            if active == index {
+                journal(format!("cancel_tool {}", name));
+                continue;
+            }
+        }
+        journal(format!("activate_tool {}", name));
+        registry.tools[index].activate(&mut ctx);
+        registry.active = Some(index);
+        activated = true;
+    }
+    if let Some(active) = registry.active.filter(|_| !activated) {
+        let tool = &mut registry.tools[active];
+        let status = if ctx.actions.just_pressed(InputAction::Cancel) { ToolStatus::Cancel } else { tool.input(&mut ctx) };
+        match status {
+            ToolStatus::Continue => {}
+            ToolStatus::Commit => {
+                journal(format!("commit_tool {}", tool.name()));
+                tool.commit(&mut ctx);
+                registry.active = None;
+            }
+            ToolStatus::Cancel => {
+                journal(format!("cancel_tool {}", tool.name()));
+                tool.cancel();
+                ctx.commands.clear();
+                registry.active = None;
+            }
+        }
+    }
+    for command in ctx.commands {
+        commands.write(command);
+    }
+}
+
+/// Let the active tool draw its previews
+pub fn render_active_tool(
+    registry: Res<ToolRegistry>,
+    input: (Res<ButtonInput<KeyCode>>, Res<ButtonInput<MouseButton>>, Res<ActionState>),
+    (pick, model, workspace, selection): (Res<PickState>, Res<BrepModel>, Res<Workspace>, Res<Selection>),
+    mut gizmos: Gizmos,
+) {
+    if let Some(tool) = registry.active() {
+        let ctx = ToolContext::new(&input.0, &input.1, &input.2, &pick, &model, &workspace, &selection);
+        tool.draw(&ctx, &mut gizmos);
+    }
+}
+
+/// Container of the tool buttons
+#[derive(Component, Debug)]
+pub struct Toolbar;
+
+/// Button activating a tool, by index
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ToolButton(pub usize);
+
+/// Spawn the toolbar (left edge)
+pub fn spawn_toolbar(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(8.0),
+            top: Val::Percent(30.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(4.0),
+            padding: UiRect::all(Val::Px(4.0)),
+            ..default()
+        },
+        BackgroundColor(PANEL_COLOR),
+        UiPanel("tools"),
+        Toolbar,
+    ));
+}
+
+/// Send activation requests for clicked buttons and rebuild the toolbar when
+/// the tools or the active one change
+pub fn toolbar_system(
+    mut commands: Commands,
+    registry: Res<ToolRegistry>,
+    pressed: Query<(&Interaction, &ToolButton), Changed<Interaction>>,
+    toolbar: Query<Entity, With<Toolbar>>,
+    old_buttons: Query<Entity, With<ToolButton>>,
+    mut requests: EventWriter<ActivateTool>,
+    mut shown: Local<(Vec<String>, Option<usize>)>,
+) {
+    for (interaction, ToolButton(index)) in pressed.iter() {
+        if *interaction == Interaction::Pressed {
+            if let Some(name) = registry.names().get(*index) {
+                requests.write(ActivateTool(name.to_string()));
+            }
+        }
+    }
+    let state = (registry.names().iter().map(|n| n.to_string()).collect(), registry.active);
+    if *shown == state {
+        return;
+    }
+    let Ok(toolbar) = toolbar.single() else { return };
+    for entity in old_buttons.iter() {
+        commands.entity(entity).despawn();
+    }
+    commands.entity(toolbar).with_children(|toolbar| {
+        for (index, name) in state.0.iter().enumerate() {
+            let color = if Some(index) == state.1 { BUTTON_ACTIVE } else { BUTTON_IDLE };
+            toolbar
+                .spawn((Button, Node { padding: UiRect::axes(Val::Px(6.0), Val::Px(2.0)), ..default() }, BackgroundColor(color), ToolButton(index)))
+                .with_child(Text::new(name.clone()));
+        }
+    });
+    *shown = state;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::input::mouse::{MouseMotion, MouseWheel};
+    use crate::input::actions::{begin_actions, binding_actions, finish_actions, mouse_actions};
+    use crate::model::body::BodyId;
+
+    /// Deletes the first body on select
+    struct DeleteFirst;
+
+    impl Tool for DeleteFirst {
+        fn name(&self) -> &str {
+            "Delete first"
+        }
+
+        fn input(&mut self, ctx: &mut ToolContext) -> ToolStatus {
+            if ctx.actions.just_pressed(InputAction::Select) {
+                ToolStatus::Commit
+            } else {
+                ToolStatus::Continue
+            }
+        }
+
+        fn commit(&mut self, ctx: &mut ToolContext) {
+            ctx.send(ModelCommand::DeleteBody(BodyId(0)));
+        }
+    }
+
+    /// Does nothing, on a given shortcut
+    struct Idle(&'static str, KeyChord);
+
+    impl Tool for Idle {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn shortcut(&self) -> Option<KeyChord> {
+            Some(self.1)
+        }
+
+        fn input(&mut self, _ctx: &mut ToolContext) -> ToolStatus {
+            ToolStatus::Continue
+        }
+
+        fn commit(&mut self, _ctx: &mut ToolContext) {}
+    }
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<ButtonInput<MouseButton>>()
+            .init_resource::<Time>()
+            .init_resource::<KeyBindings>()
+            .init_resource::<ActionState>()
+            .add_event::<MouseMotion>()
+            .add_event::<MouseWheel>()
+            .init_resource::<PickState>()
+            .init_resource::<BrepModel>()
+            .insert_resource(Workspace::new())
+            .init_resource::<Selection>()
+            .add_event::<ActivateTool>()
+            .add_event::<ModelCommand>()
+            .register_tool(DeleteFirst)
+            .add_systems(PreUpdate, (begin_actions, (mouse_actions, binding_actions), finish_actions).chain())
+            .add_systems(Update, run_tools);
+        app
+    }
+
+    fn sent(app: &App) -> usize {
+        app.world().resource::<Events<ModelCommand>>().len()
+    }
+
+    #[test]
+    fn test_active_tool_commits_on_click() {
+        let mut app = app();
+        app.world_mut().send_event(ActivateTool("Delete first".into()));
+        app.update();
+        assert_eq!(app.world().resource::<ToolRegistry>().active().map(|t| t.name().to_string()), Some("Delete first".into()));
+        assert_eq!(sent(&app), 0);
+
+        app.world_mut().resource_mut::<ButtonInput<MouseButton>>().press(MouseButton::Left);
+        app.update();
+        assert!(!app.world().resource::<ToolRegistry>().is_active());
+        assert_eq!(sent(&app), 1);
+    }
+
+    #[test]
+    fn test_activating_click_is_not_the_tools_click() {
+        let mut app = app();
+        app.world_mut().send_event(ActivateTool("Delete first".into()));
+        app.world_mut().resource_mut::<ButtonInput<MouseButton>>().press(MouseButton::Left);
+        app.update();
+        assert!(app.world().resource::<ToolRegistry>().is_active());
+        assert_eq!(sent(&app), 0);
+    }
+
+    #[test]
+    fn test_escape_and_reactivation_cancel() {
+        let mut app = app();
+        app.world_mut().send_event(ActivateTool("Delete first".into()));
+        app.update();
+        app.world_mut().send_event(ActivateTool("Delete first".into()));
+        app.update();
+        assert!(!app.world().resource::<ToolRegistry>().is_active());
+
+        app.world_mut().send_event(ActivateTool("Delete first".into()));
+        app.update();
+        app.world_mut().resource_mut::<ButtonInput<KeyCode>>().press(KeyCode::Escape);
+        app.world_mut().resource_mut::<ButtonInput<MouseButton>>().press(MouseButton::Left);
+        app.update();
+        assert!(!app.world().resource::<ToolRegistry>().is_active());
+        assert_eq!(sent(&app), 0);
+
+        let mut registry = ToolRegistry::default();
+        registry.register(DeleteFirst).unwrap();
+        assert_eq!(registry.register(DeleteFirst), Err(ToolError::NameTaken("Delete first".into())));
+    }
+
+    #[test]
+    fn test_shortcuts_are_key_bindings() {
+        let mut app = app();
+        let mut custom = KeyBindings::default();
+        custom.bind("tool_rebound", KeyChord::key(KeyCode::KeyJ)).unwrap();
+        app.insert_resource(custom)
+            .register_tool(Idle("Sweep tool", KeyChord::shift(KeyCode::KeyJ)))
+            .register_tool(Idle("Clashing", KeyChord::key(KeyCode::KeyB)))
+            .register_tool(Idle("Rebound", KeyChord::key(KeyCode::KeyG)))
+            .add_systems(Update, tool_shortcut_keys.before(run_tools));
+        let bindings = app.world().resource::<KeyBindings>();
+        assert_eq!(bindings.chord("tool_sweep_tool"), Some(KeyChord::shift(KeyCode::KeyJ)));
+        // Place box keeps B; the user's chord wins over the tool's default
+        assert_eq!(bindings.chord("tool_clashing"), None);
+        assert_eq!(bindings.chord("tool_rebound"), Some(KeyChord::key(KeyCode::KeyJ)));
+
+        let mut keys = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        keys.press(KeyCode::ShiftLeft);
+        keys.press(KeyCode::KeyJ);
+        app.update();
+        assert_eq!(app.world().resource::<ToolRegistry>().active().map(|t| t.name().to_string()), Some("Sweep tool".into()));
+    }
+}
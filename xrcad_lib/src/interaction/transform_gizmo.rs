@@ -0,0 +1,574 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: interaction::transform_gizmo
+//!
+//! Move, rotate and scale handles at the centroid of the selection. Dragging an
+//! axis handle constrains the edit to that axis (rotation rings turn about it);
+//! the small squares between two axes constrain it to their plane. W cycles the
+//! mode. After a handle has been used, typing a number and pressing Enter applies
+//! an exact edit along it: a distance in the document unit, an angle in
+//! degrees or a scale factor. Minus flips the sign, Escape cancels the number
+//! or, mid-drag, puts the geometry back. Finished drags and exact edits reach
+//! the model as `ModelCommand::MoveVertices`.
+
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use nalgebra::{Rotation3, Unit, Vector3};
+
+use crate::color::{BLUE, GREEN, RED, YELLOW};
+use crate::input::keyboard::KeyBindings;
+use crate::interaction::measure_tool::MeasureTool;
+use crate::interaction::picking::PickState;
+use crate::interaction::selection::{Selection, SelectionItem};
+use crate::model::brep::geometry::polygon::segment_distance_2d;
+use crate::model::brep_model::{na_vec3_to_bevy, BrepModel};
+use crate::model::command::{commit_vertex_moves, CommandRelay, ModelCommand};
+use crate::model::units::UnitSystem;
+use crate::render::gizmo_scale::{GizmoScale, EDGE_PICK_PIXELS};
+use crate::telemetry::crash::journal;
+
+/// Length of the axis handles (pixels)
+pub const GIZMO_PIXELS: f32 = 90.0;
+/// Plane handles sit this far along both of their axes, as a fraction of the handle length
+const PLANE_HANDLE_OFFSET: f64 = 0.3;
+/// Half the side of a plane handle, as a fraction of the handle length
+const PLANE_HANDLE_HALF: f64 = 0.08;
+/// Segments used to draw and hit-test rotation rings
+const RING_SEGMENTS: usize = 48;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GizmoMode {
+    #[default]
+    Translate,
+    Rotate,
+    Scale,
+}
+
+impl GizmoMode {
+    pub const ALL: [GizmoMode; 3] = [GizmoMode::Translate, GizmoMode::Rotate, GizmoMode::Scale];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            GizmoMode::Translate => "Move",
+            GizmoMode::Rotate => "Rotate",
+            GizmoMode::Scale => "Scale",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|m| *m == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GizmoAxis {
+    X,
+    Y,
+    Z,
+}
+
+impl GizmoAxis {
+    pub const ALL: [GizmoAxis; 3] = [GizmoAxis::X, GizmoAxis::Y, GizmoAxis::Z];
+
+    pub fn vector(&self) -> Vector3<f64> {
+        match self {
+            GizmoAxis::X => Vector3::x(),
+            GizmoAxis::Y => Vector3::y(),
+            GizmoAxis::Z => Vector3::z(),
+        }
+    }
+
+    /// The other two axes, in cyclic order
+    pub fn others(&self) -> (GizmoAxis, GizmoAxis) {
+        match self {
+            GizmoAxis::X => (GizmoAxis::Y, GizmoAxis::Z),
+            GizmoAxis::Y => (GizmoAxis::Z, GizmoAxis::X),
+            GizmoAxis::Z => (GizmoAxis::X, GizmoAxis::Y),
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            GizmoAxis::X => RED,
+            GizmoAxis::Y => GREEN,
+            GizmoAxis::Z => BLUE,
+        }
+    }
+}
+
+/// What a handle restricts the edit to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GizmoConstraint {
+    Axis(GizmoAxis),
+    /// The plane with this axis as its normal
+    Plane(GizmoAxis),
+}
+
+impl GizmoConstraint {
+    /// Axis rotations turn about: the axis itself, or the plane normal
+    pub fn normal(&self) -> Vector3<f64> {
+        match self {
+            GizmoConstraint::Axis(a) | GizmoConstraint::Plane(a) => a.vector(),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            GizmoConstraint::Axis(a) => format!("{:?}", a),
+            GizmoConstraint::Plane(a) => {
+                let (u, v) = a.others();
+                format!("{:?}{:?}", u, v)
+            }
+        }
+    }
+}
+
+/// A rigid or scaling change about the gizmo pivot
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GizmoDelta {
+    Translate(Vector3<f64>),
+    Rotate { axis: Vector3<f64>, angle: f64 },
+    /// Scale factor along each model axis
+    Scale(Vector3<f64>),
+}
+
+impl GizmoDelta {
+    /// Exact edit typed for a handle: a distance, an angle (radians) or a factor.
+    /// A single distance cannot move along a plane.
+    pub fn from_amount(mode: GizmoMode, constraint: GizmoConstraint, amount: f64) -> Option<Self> {
+        match (mode, constraint) {
+            (GizmoMode::Translate, GizmoConstraint::Axis(a)) => Some(GizmoDelta::Translate(a.vector() * amount)),
+            (GizmoMode::Translate, GizmoConstraint::Plane(_)) => None,
+            (GizmoMode::Rotate, c) => Some(GizmoDelta::Rotate { axis: c.normal(), angle: amount }),
+            (GizmoMode::Scale, GizmoConstraint::Axis(a)) => Some(GizmoDelta::Scale(Vector3::repeat(1.0) + a.vector() * (amount - 1.0))),
+            (GizmoMode::Scale, GizmoConstraint::Plane(a)) => Some(GizmoDelta::Scale(Vector3::repeat(amount) - a.vector() * (amount - 1.0))),
+        }
+    }
+
+    pub fn apply(&self, pivot: &Vector3<f64>, p: &Vector3<f64>) -> Vector3<f64> {
+        match self {
+            GizmoDelta::Translate(offset) => p + offset,
+            GizmoDelta::Rotate { axis, angle } => pivot + Rotation3::from_axis_angle(&Unit::new_normalize(*axis), *angle) * (p - pivot),
+            GizmoDelta::Scale(factors) => pivot + (p - pivot).component_mul(factors),
+        }
+    }
+}
+
+/// Every vertex shaping the selected items, sorted
+pub fn selection_vertices(model: &BrepModel, selection: &Selection) -> Vec<usize> {
+    let face_vertices = |id: usize| -> Vec<usize> {
+        model.face(id).map_or(Vec::new(), |f| model.face_loops(f).iter().flat_map(|l| model.loop_vertex_ids(l)).collect())
+    };
+    let mut ids: Vec<usize> = selection
+        .items
+        .iter()
+        .flat_map(|item| match item {
+            SelectionItem::Vertex(id) => vec![*id],
+            SelectionItem::Edge(id) => model.edge(*id).map_or(Vec::new(), |e| vec![e.vertices.0, e.vertices.1]),
+            SelectionItem::Face(id) => face_vertices(*id),
+            SelectionItem::Body(body) => model.body_faces(*body).into_iter().flatten().flat_map(face_vertices).collect(),
+        })
+        .filter(|id| model.vertex(*id).is_some())
+        .collect();
+    ids.sort_unstable();
+    ids.dedup();
+    ids
+}
+
+/// Centroid of the given vertices
+pub fn pivot(model: &BrepModel, vertices: &[usize]) -> Option<Vector3<f64>> {
+    let points: Vec<Vector3<f64>> = vertices.iter().filter_map(|id| model.vertex_position(*id)).collect();
+    (!points.is_empty()).then(|| points.iter().sum::<Vector3<f64>>() / points.len() as f64)
+}
+
+/// Move the vertices from their `original` positions by `delta` about `pivot`
+pub fn transform_vertices(model: &mut BrepModel, original: &[(usize, Vector3<f64>)], pivot: &Vector3<f64>, delta: &GizmoDelta) {
+    for (id, p) in original {
+        if let Some(v) = model.vertices.iter_mut().find(|v| v.id == *id) {
+            v.position = delta.apply(pivot, p);
+        }
+    }
+}
+
+/// Parameter along the line `point + axis * t` closest to the ray (both directions unit length)
+fn axis_parameter(point: &Vector3<f64>, axis: &Vector3<f64>, ray: &(Vector3<f64>, Vector3<f64>)) -> Option<f64> {
+    let (origin, dir) = ray;
+    let w = point - origin;
+    let b = axis.dot(dir);
+    let denom = 1.0 - b * b;
+    (denom > 1e-9).then(|| (b * dir.dot(&w) - axis.dot(&w)) / denom)
+}
+
+/// Where the ray meets the plane through `point` with `normal`
+fn plane_hit(point: &Vector3<f64>, normal: &Vector3<f64>, ray: &(Vector3<f64>, Vector3<f64>)) -> Option<Vector3<f64>> {
+    let (origin, dir) = ray;
+    let denom = normal.dot(dir);
+    (denom.abs() > 1e-9).then(|| origin + dir * ((point - origin).dot(normal) / denom))
+}
+
+/// Change produced by dragging a handle from `start` to `current` (cursor rays).
+/// `length` is the handle length, which sets the scale sensitivity along an axis.
+pub fn drag_delta(
+    mode: GizmoMode,
+    constraint: GizmoConstraint,
+    pivot: &Vector3<f64>,
+    length: f64,
+    start: &(Vector3<f64>, Vector3<f64>),
+    current: &(Vector3<f64>, Vector3<f64>),
+) -> Option<GizmoDelta> {
+    let normal = constraint.normal();
+    match (mode, constraint) {
+        (GizmoMode::Translate, GizmoConstraint::Axis(_)) => {
+            Some(GizmoDelta::Translate(normal * (axis_parameter(pivot, &normal, current)? - axis_parameter(pivot, &normal, start)?)))
+        }
+        (GizmoMode::Translate, GizmoConstraint::Plane(_)) => {
+            Some(GizmoDelta::Translate(plane_hit(pivot, &normal, current)? - plane_hit(pivot, &normal, start)?))
+        }
+        (GizmoMode::Rotate, _) => {
+            let (a, b) = (plane_hit(pivot, &normal, start)? - pivot, plane_hit(pivot, &normal, current)? - pivot);
+            Some(GizmoDelta::Rotate { axis: normal, angle: a.cross(&b).dot(&normal).atan2(a.dot(&b)) })
+        }
+        (GizmoMode::Scale, GizmoConstraint::Axis(_)) => {
+            let t = axis_parameter(pivot, &normal, current)? - axis_parameter(pivot, &normal, start)?;
+            GizmoDelta::from_amount(mode, constraint, 1.0 + t / length)
+        }
+        (GizmoMode::Scale, GizmoConstraint::Plane(_)) => {
+            let (a, b) = (plane_hit(pivot, &normal, start)? - pivot, plane_hit(pivot, &normal, current)? - pivot);
+            if a.norm() <= 1e-9 {
+                return None;
+            }
+            GizmoDelta::from_amount(mode, constraint, b.norm() / a.norm())
+        }
+    }
+}
+
+/// Model-space polylines of a handle, for drawing and hit-testing
+fn handle_polylines(mode: GizmoMode, constraint: GizmoConstraint, pivot: &Vector3<f64>, length: f64) -> Vec<Vec<Vector3<f64>>> {
+    match (mode, constraint) {
+        (GizmoMode::Rotate, c) => {
+            let (u, v) = match c {
+                GizmoConstraint::Axis(a) | GizmoConstraint::Plane(a) => a.others(),
+            };
+            let ring = (0..=RING_SEGMENTS)
+                .map(|i| {
+                    let t = i as f64 / RING_SEGMENTS as f64 * std::f64::consts::TAU;
+                    pivot + (u.vector() * t.cos() + v.vector() * t.sin()) * length
+                })
+                .collect();
+            vec![ring]
+        }
+        (_, GizmoConstraint::Axis(a)) => vec![vec![*pivot, pivot + a.vector() * length]],
+        (_, GizmoConstraint::Plane(a)) => {
+            let (u, v) = a.others();
+            let (u, v) = (u.vector() * length, v.vector() * length);
+            let center = pivot + (u + v) * PLANE_HANDLE_OFFSET;
+            let corner = |x: f64, y: f64| center + (u * x + v * y) * PLANE_HANDLE_HALF;
+            vec![vec![corner(-1.0, -1.0), corner(1.0, -1.0), corner(1.0, 1.0), corner(-1.0, 1.0), corner(-1.0, -1.0)]]
+        }
+    }
+}
+
+/// Handles offered in a mode, plane handles first since they are the smaller targets
+pub fn handles(mode: GizmoMode) -> Vec<GizmoConstraint> {
+    let axes = GizmoAxis::ALL.iter().map(|a| GizmoConstraint::Axis(*a));
+    match mode {
+        GizmoMode::Rotate => axes.collect(),
+        _ => GizmoAxis::ALL.iter().map(|a| GizmoConstraint::Plane(*a)).chain(axes).collect(),
+    }
+}
+
+/// Handle under the cursor. `project` maps model positions to window positions.
+pub fn handle_at(
+    mode: GizmoMode,
+    pivot: &Vector3<f64>,
+    length: f64,
+    cursor: Vec2,
+    project: impl Fn(&Vector3<f64>) -> Option<Vec2>,
+) -> Option<GizmoConstraint> {
+    let to_2d = |v: Vec2| nalgebra::Vector2::new(v.x as f64, v.y as f64);
+    let cursor_2d = to_2d(cursor);
+    handles(mode).into_iter().find(|handle| {
+        handle_polylines(mode, *handle, pivot, length).iter().any(|line| {
+            let screen: Option<Vec<Vec2>> = line.iter().map(&project).collect();
+            let Some(screen) = screen else { return false };
+            if matches!(handle, GizmoConstraint::Plane(_)) && mode != GizmoMode::Rotate {
+                let (min, max) = screen.iter().fold((Vec2::MAX, Vec2::MIN), |(lo, hi), p| (lo.min(*p), hi.max(*p)));
+                return cursor.cmpge(min).all() && cursor.cmple(max).all();
+            }
+            screen.windows(2).any(|w| segment_distance_2d(&to_2d(w[0]), &to_2d(w[1]), &cursor_2d) <= EDGE_PICK_PIXELS as f64)
+        })
+    })
+}
+
+/// A handle drag in progress
+#[derive(Debug, Clone, PartialEq)]
+pub struct GizmoDrag {
+    pub constraint: GizmoConstraint,
+    pub pivot: Vector3<f64>,
+    pub length: f64,
+    pub start: (Vector3<f64>, Vector3<f64>),
+    /// Selected vertices where they were when the drag began
+    pub original: Vec<(usize, Vector3<f64>)>,
+}
+
+/// Gizmo mode, the handle under the cursor, the drag and any typed value
+#[derive(Resource, Debug, Default, Clone)]
+pub struct TransformGizmo {
+    pub mode: GizmoMode,
+    pub hover: Option<GizmoConstraint>,
+    /// Handle last used; typed values apply along it
+    pub constraint: Option<GizmoConstraint>,
+    pub drag: Option<GizmoDrag>,
+    pub buffer: String,
+}
+
+impl TransformGizmo {
+    /// True while the gizmo owns the left mouse button
+    pub fn captures_pointer(&self) -> bool {
+        self.hover.is_some() || self.drag.is_some()
+    }
+
+    pub fn is_entering(&self) -> bool {
+        !self.buffer.is_empty()
+    }
+}
+
+/// Run condition: true unless a transform value is being typed
+pub fn not_entering_transform(gizmo: Option<Res<TransformGizmo>>) -> bool {
+    !gizmo.is_some_and(|g| g.is_entering())
+}
+
+/// Request to transform the selection exactly along a handle
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct TransformSelection {
+    pub mode: GizmoMode,
+    pub constraint: GizmoConstraint,
+    /// Distance (model units), angle (radians) or scale factor
+    pub amount: f64,
+}
+
+/// Hover and drag the handles, editing the selected geometry live
+pub fn transform_gizmo_drag(
+    mouse: Res<ButtonInput<MouseButton>>,
+    (keys, bindings): (Res<ButtonInput<KeyCode>>, Res<KeyBindings>),
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    (pick, selection, scale, measure): (Res<PickState>, Res<Selection>, Option<Res<GizmoScale>>, Option<Res<MeasureTool>>),
+    mut model: ResMut<BrepModel>,
+    mut gizmo: ResMut<TransformGizmo>,
+    (relay, mut commands): (Option<Res<CommandRelay>>, EventWriter<ModelCommand>),
+) {
+    if let Some(drag) = gizmo.drag.clone() {
+        let Some(ray) = pick.ray else { return };
+        if bindings.just_pressed("cancel", &keys) {
+            transform_vertices(&mut model, &drag.original, &drag.pivot, &GizmoDelta::Translate(Vector3::zeros()));
+            gizmo.drag = None;
+            return;
+        }
+        if let Some(delta) = drag_delta(gizmo.mode, drag.constraint, &drag.pivot, drag.length, &drag.start, &ray) {
+            transform_vertices(&mut model, &drag.original, &drag.pivot, &delta);
+        }
+        if !mouse.pressed(MouseButton::Left) {
+            journal(format!("transform_drag {} {}", gizmo.mode.label(), drag.constraint.label()));
+            commit_vertex_moves(&mut model, &drag.original, relay.as_deref(), &mut commands);
+            gizmo.drag = None;
+        }
+        return;
+    }
+    let vertices = selection_vertices(&model, &selection);
+    let cursor = windows.single().ok().and_then(|w| w.cursor_position()).filter(|_| !measure.is_some_and(|m| m.active));
+    let (Some(pivot), Some(cursor), Ok((camera, transform))) = (pivot(&model, &vertices), cursor, cameras.single()) else {
+        gizmo.hover = None;
+        return;
+    };
+    let scale = scale.as_deref().copied().unwrap_or_default();
+    let length = scale.world_size(na_vec3_to_bevy(&pivot), GIZMO_PIXELS) as f64;
+    let project = |p: &Vector3<f64>| camera.world_to_viewport(transform, na_vec3_to_bevy(p)).ok();
+    let hover = handle_at(gizmo.mode, &pivot, length, cursor, project);
+    if gizmo.hover != hover {
+        gizmo.hover = hover;
+    }
+    if let (true, Some(constraint), Some(start)) = (mouse.just_pressed(MouseButton::Left), hover, pick.ray) {
+        let original = vertices.iter().filter_map(|id| Some((*id, model.vertex_position(*id)?))).collect();
+        gizmo.constraint = Some(constraint);
+        gizmo.drag = Some(GizmoDrag { constraint, pivot, length, start, original });
+    }
+}
+
+/// The gizmo_mode binding (W) cycles the gizmo mode
+pub fn transform_gizmo_keys(keys: Res<ButtonInput<KeyCode>>, bindings: Res<KeyBindings>, mut gizmo: ResMut<TransformGizmo>) {
+    if bindings.just_pressed("gizmo_mode", &keys) {
+        gizmo.mode = gizmo.mode.next();
+        gizmo.buffer.clear();
+    }
+}
+
+/// Typed value for the last used handle; Enter applies it
+pub fn transform_value_input(
+    mut key_events: EventReader<KeyboardInput>,
+    selection: Res<Selection>,
+    units: Option<Res<UnitSystem>>,
+    mut gizmo: ResMut<TransformGizmo>,
+    mut requests: EventWriter<TransformSelection>,
+) {
+    let Some(constraint) = gizmo.constraint.filter(|_| !selection.is_empty() && gizmo.drag.is_none()) else {
+        key_events.clear();
+        gizmo.buffer.clear();
+        return;
+    };
+    for ev in key_events.read() {
+        if ev.state != ButtonState::Pressed {
+            continue;
+        }
+        match &ev.logical_key {
+            Key::Enter if gizmo.is_entering() => {
+                let text = std::mem::take(&mut gizmo.buffer);
+                let units = units.as_deref().cloned().unwrap_or_default();
+                let amount = match gizmo.mode {
+                    GizmoMode::Translate => units.parse_length(&text),
+                    GizmoMode::Rotate => units.parse_angle(&text),
+                    GizmoMode::Scale => text.parse().ok(),
+                };
+                match amount {
+                    Some(amount) => {
+                        requests.write(TransformSelection { mode: gizmo.mode, constraint, amount });
+                    }
+                    None => warn!("Not a valid {} value: {}", gizmo.mode.label(), text),
+                }
+            }
+            Key::Escape => gizmo.buffer.clear(),
+            Key::Backspace => {
+                gizmo.buffer.pop();
+            }
+            Key::Character(text) => {
+                for c in text.chars() {
+                    match c {
+                        '0'..='9' | '.' => gizmo.buffer.push(c),
+                        // Only once a value is started, so Minus keeps its usual meaning otherwise
+                        '-' if gizmo.is_entering() => {
+                            if gizmo.buffer.starts_with('-') {
+                                gizmo.buffer.remove(0);
+                            } else {
+                                gizmo.buffer.insert(0, '-');
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Send exact transforms of the selection to the command bus
+pub fn apply_transform_selection(
+    mut events: EventReader<TransformSelection>,
+    selection: Res<Selection>,
+    model: Res<BrepModel>,
+    mut commands: EventWriter<ModelCommand>,
+) {
+    for ev in events.read() {
+        journal(format!("transform {} {} {}", ev.mode.label(), ev.constraint.label(), ev.amount));
+        let vertices = selection_vertices(&model, &selection);
+        let (Some(pivot), Some(delta)) = (pivot(&model, &vertices), GizmoDelta::from_amount(ev.mode, ev.constraint, ev.amount)) else {
+            warn!("{} needs an axis handle and a selection", ev.mode.label());
+            continue;
+        };
+        let moves = vertices.iter().filter_map(|id| Some((*id, delta.apply(&pivot, &model.vertex_position(*id)?)))).collect();
+        commands.write(ModelCommand::MoveVertices(moves));
+    }
+}
+
+/// Draw the handles of the current mode at the selection centroid
+pub fn render_transform_gizmo(
+    mut gizmos: Gizmos,
+    model: Res<BrepModel>,
+    selection: Res<Selection>,
+    gizmo: Res<TransformGizmo>,
+    scale: Option<Res<GizmoScale>>,
+) {
+    let (pivot, length) = match &gizmo.drag {
+        Some(drag) => (drag.pivot, drag.length),
+        None => {
+            let Some(pivot) = pivot(&model, &selection_vertices(&model, &selection)) else { return };
+            let scale = scale.as_deref().copied().unwrap_or_default();
+            (pivot, scale.world_size(na_vec3_to_bevy(&pivot), GIZMO_PIXELS) as f64)
+        }
+    };
+    let active = gizmo.drag.as_ref().map(|d| d.constraint).or(gizmo.hover);
+    for handle in handles(gizmo.mode) {
+        let color = match handle {
+            _ if active == Some(handle) => YELLOW,
+            GizmoConstraint::Axis(a) | GizmoConstraint::Plane(a) => a.color(),
+        };
+        for line in handle_polylines(gizmo.mode, handle, &pivot, length) {
+            gizmos.linestrip(line.iter().map(na_vec3_to_bevy), color);
+        }
+        if let (GizmoConstraint::Axis(a), GizmoMode::Scale) = (handle, gizmo.mode) {
+            let tip = na_vec3_to_bevy(&(pivot + a.vector() * length));
+            gizmos.cuboid(Transform::from_translation(tip).with_scale(Vec3::splat(length as f32 * 0.08)), color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::body::BodyId;
+    use crate::model::brep::primitives::cube;
+
+    #[test]
+    fn test_drag_deltas() {
+        let pivot = Vector3::zeros();
+        let down = |x: f64, y: f64| (Vector3::new(x, y, 10.0), -Vector3::z());
+        let translate = drag_delta(GizmoMode::Translate, GizmoConstraint::Axis(GizmoAxis::X), &pivot, 1.0, &down(1.0, 0.0), &down(3.0, 2.0));
+        assert_eq!(translate, Some(GizmoDelta::Translate(Vector3::new(2.0, 0.0, 0.0))));
+        let planar = drag_delta(GizmoMode::Translate, GizmoConstraint::Plane(GizmoAxis::Z), &pivot, 1.0, &down(1.0, 0.0), &down(3.0, 2.0));
+        assert_eq!(planar, Some(GizmoDelta::Translate(Vector3::new(2.0, 2.0, 0.0))));
+        let Some(GizmoDelta::Rotate { angle, .. }) = drag_delta(GizmoMode::Rotate, GizmoConstraint::Axis(GizmoAxis::Z), &pivot, 1.0, &down(1.0, 0.0), &down(0.0, 2.0)) else {
+            panic!("expected a rotation")
+        };
+        assert!((angle - std::f64::consts::FRAC_PI_2).abs() < 1e-12);
+        let Some(GizmoDelta::Scale(f)) = drag_delta(GizmoMode::Scale, GizmoConstraint::Axis(GizmoAxis::X), &pivot, 2.0, &down(1.0, 0.0), &down(3.0, 0.0)) else {
+            panic!("expected a scale")
+        };
+        assert_eq!(f, Vector3::new(2.0, 1.0, 1.0));
+        // Looking straight down an axis gives no usable drag
+        assert!(drag_delta(GizmoMode::Translate, GizmoConstraint::Axis(GizmoAxis::Z), &pivot, 1.0, &down(0.0, 0.0), &down(1.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn test_exact_transform_of_selection() {
+        let mut model = cube(10.0);
+        let selection = Selection { items: vec![SelectionItem::Body(BodyId(0))], ..Default::default() };
+        let vertices = selection_vertices(&model, &selection);
+        assert_eq!(vertices.len(), 8);
+        let pivot = pivot(&model, &vertices).unwrap();
+        assert!(pivot.norm() < 1e-12);
+        let original: Vec<_> = vertices.iter().map(|id| (*id, model.vertex_position(*id).unwrap())).collect();
+        let rotate = GizmoDelta::from_amount(GizmoMode::Rotate, GizmoConstraint::Axis(GizmoAxis::Z), 90f64.to_radians()).unwrap();
+        transform_vertices(&mut model, &original, &pivot, &rotate);
+        let corner = model.find_vertex_at(&Vector3::new(-5.0, 5.0, 5.0)).unwrap();
+        assert_eq!(original.iter().find(|(id, _)| *id == corner).unwrap().1, Vector3::new(5.0, 5.0, 5.0));
+        let scale = GizmoDelta::from_amount(GizmoMode::Scale, GizmoConstraint::Plane(GizmoAxis::Z), 2.0).unwrap();
+        assert_eq!(scale, GizmoDelta::Scale(Vector3::new(2.0, 2.0, 1.0)));
+        assert!(GizmoDelta::from_amount(GizmoMode::Translate, GizmoConstraint::Plane(GizmoAxis::Z), 1.0).is_none());
+    }
+
+    #[test]
+    fn test_handle_at() {
+        // Top view, 10 pixels per unit, handles 10 units long
+        let project = |p: &Vector3<f64>| Some(Vec2::new(p.x as f32 * 10.0, -p.y as f32 * 10.0));
+        let pivot = Vector3::zeros();
+        let at = |mode, x: f32, y: f32| handle_at(mode, &pivot, 10.0, Vec2::new(x, y), project);
+        assert_eq!(at(GizmoMode::Translate, 70.0, 2.0), Some(GizmoConstraint::Axis(GizmoAxis::X)));
+        assert_eq!(at(GizmoMode::Translate, 30.0, -30.0), Some(GizmoConstraint::Plane(GizmoAxis::Z)));
+        assert_eq!(at(GizmoMode::Translate, 60.0, -60.0), None);
+        assert_eq!(at(GizmoMode::Rotate, 70.0, -71.0), Some(GizmoConstraint::Axis(GizmoAxis::Z)));
+    }
+}
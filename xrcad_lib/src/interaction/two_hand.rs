@@ -0,0 +1,226 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: interaction::two_hand
+//!
+//! Two-handed scale and rotate. Holding the grip on both XR controllers
+//! starts the gesture. With bodies selected (a one-handed grab selects the
+//! body it holds), they follow the hands: they move with the point between
+//! the hands, scale with the distance between them and turn as the line
+//! between them turns; releasing a grip sends the result to the command bus
+//! as one `ModelCommand::MoveVertices`. With no body selected the gesture works the scene
+//! instead: moving the hands apart or together scales the world through
+//! `ScaleWorld`, and turning the line between them about the vertical turns
+//! the XR view about the point between the hands. Hand positions for the
+//! scene are read in physical space, so the view changing under the hands
+//! does not feed back into the gesture.
+
+use bevy::prelude::*;
+use nalgebra::{UnitQuaternion, Vector3};
+
+use crate::interaction::selection::Selection;
+use crate::interaction::xr_controller::XrController;
+use crate::model::brep_model::{bevy_vec3_to_na, BrepModel};
+use crate::model::command::{commit_vertex_moves, CommandRelay, ModelCommand};
+use crate::telemetry::crash::journal;
+use crate::viewport::camera::ViewRig;
+use crate::viewport::xr_scale::{ScaleWorld, XrViewScale};
+
+/// Hands closer than this cannot scale (model units)
+const MIN_HAND_DISTANCE: f64 = 1e-3;
+/// Scene scale changes smaller than this are not sent
+const MIN_SCALE_STEP: f32 = 1e-4;
+
+/// What the gesture is manipulating
+#[derive(Debug, Clone, PartialEq)]
+enum GestureTarget {
+    /// Vertices of the selected bodies at the start of the gesture
+    Bodies(Vec<(usize, Vector3<f64>)>),
+    /// Hand line in physical space as of the previous frame
+    Scene(Vec3),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct GestureStart {
+    target: GestureTarget,
+    /// Point between the hands and the line from left to right hand, in model space
+    midpoint: Vector3<f64>,
+    line: Vector3<f64>,
+}
+
+/// The two-handed gesture in progress
+#[derive(Resource, Debug, Default, Clone)]
+pub struct TwoHandGesture {
+    start: Option<GestureStart>,
+}
+
+impl TwoHandGesture {
+    pub fn is_active(&self) -> bool {
+        self.start.is_some()
+    }
+}
+
+/// Scale and rotation taking the hand line at the start onto `line`
+fn similarity(start: &Vector3<f64>, line: &Vector3<f64>) -> (f64, UnitQuaternion<f64>) {
+    let rotation = UnitQuaternion::rotation_between(start, line).unwrap_or_else(UnitQuaternion::identity);
+    (line.norm() / start.norm(), rotation)
+}
+
+/// Both grips held: scale and turn the selected bodies, or the scene when none are selected
+pub fn two_hand_gesture(
+    mut controllers: Query<(&GlobalTransform, &mut XrController)>,
+    mut gesture: ResMut<TwoHandGesture>,
+    mut model: ResMut<BrepModel>,
+    selection: Option<Res<Selection>>,
+    view: Option<Res<XrViewScale>>,
+    mut cameras: Query<(&mut Transform, &ViewRig)>,
+    mut scales: EventWriter<ScaleWorld>,
+    (relay, mut commands): (Option<Res<CommandRelay>>, EventWriter<ModelCommand>),
+) {
+    let mut hands: Vec<_> = controllers.iter_mut().filter(|(_, c)| c.grip).collect();
+    hands.sort_by_key(|(_, c)| c.hand as u8);
+    let [(left, _), (right, _)] = hands[..] else {
+        if let Some(start) = gesture.start.take() {
+            let label = if matches!(start.target, GestureTarget::Scene(_)) { "scene" } else { "bodies" };
+            journal(format!("two_hand {}", label));
+            if let GestureTarget::Bodies(original) = &start.target {
+                commit_vertex_moves(&mut model, original, relay.as_deref(), &mut commands);
+            }
+        }
+        return;
+    };
+    let (left, right) = (left.translation(), right.translation());
+    let view = view.as_deref().copied().unwrap_or_default();
+    let midpoint = bevy_vec3_to_na(&((left + right) * 0.5));
+    let line = bevy_vec3_to_na(&(right - left));
+    if line.norm() < MIN_HAND_DISTANCE {
+        return;
+    }
+    let physical = view.to_physical(right) - view.to_physical(left);
+    if gesture.start.is_none() {
+        // The gesture takes over from one-handed grabs, which are committed first
+        for (_, controller) in hands.iter_mut() {
+            if let Some(grab) = controller.grab.take() {
+                commit_vertex_moves(&mut model, grab.original(), relay.as_deref(), &mut commands);
+            }
+        }
+        let bodies = selection.map_or_else(Vec::new, |s| s.bodies());
+        let target = if bodies.is_empty() {
+            GestureTarget::Scene(physical)
+        } else {
+            let shells = model.bodies();
+            let vertices = shells.iter().filter(|(id, _)| bodies.contains(id)).flat_map(|(_, faces)| model.shell_vertex_ids(faces));
+            GestureTarget::Bodies(vertices.filter_map(|id| Some((id, model.vertex_position(id)?))).collect())
+        };
+        gesture.start = Some(GestureStart { target, midpoint, line });
+        return;
+    }
+    let Some(start) = gesture.start.as_mut() else { return };
+    match &mut start.target {
+        GestureTarget::Bodies(original) => {
+            let (scale, rotation) = similarity(&start.line, &line);
+            for (id, p) in original.iter() {
+                if let Some(v) = model.vertices.iter_mut().find(|v| v.id == *id) {
+                    v.position = midpoint + rotation.transform_vector(&((p - start.midpoint) * scale));
+                }
+            }
+        }
+        GestureTarget::Scene(last) => {
+            let factor = physical.length() / last.length();
+            if (factor - 1.0).abs() > MIN_SCALE_STEP {
+                scales.write(ScaleWorld { factor });
+            }
+            // Turn of the hand line about the vertical; the view turns the other way
+            let turn = last.cross(physical).y.atan2(last.x * physical.x + last.z * physical.z);
+            if turn.abs() > f32::EPSILON {
+                let pivot = (left + right) * 0.5;
+                for (mut transform, rig) in cameras.iter_mut() {
+                    if rig.is_xr() {
+                        transform.rotate_around(pivot, Quat::from_rotation_y(-turn));
+                    }
+                }
+            }
+            *last = physical;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interaction::selection::SelectionItem;
+    use crate::interaction::xr_controller::XrHand;
+    use crate::model::body::BodyId;
+    use crate::model::brep::primitives::cube;
+
+    fn gesture_app(selection: Selection) -> (App, [Entity; 2]) {
+        let mut app = App::new();
+        app.insert_resource(cube(10.0))
+            .insert_resource(selection)
+            .init_resource::<TwoHandGesture>()
+            .add_event::<ScaleWorld>()
+            .add_event::<ModelCommand>()
+            .add_systems(Update, two_hand_gesture);
+        let hands = [XrHand::Left, XrHand::Right].map(|hand| {
+            let mut controller = XrController::new(hand);
+            controller.grip = true;
+            app.world_mut().spawn(controller).id()
+        });
+        (app, hands)
+    }
+
+    fn place(app: &mut App, hands: [Entity; 2], left: Vec3, right: Vec3) {
+        for (entity, at) in hands.into_iter().zip([left, right]) {
+            app.world_mut().entity_mut(entity).insert(GlobalTransform::from_translation(at));
+        }
+    }
+
+    #[test]
+    fn test_selected_body_follows_both_hands() {
+        let (mut app, hands) = gesture_app(Selection { items: vec![SelectionItem::Body(BodyId(0))], ..default() });
+        place(&mut app, hands, Vec3::new(-5.0, 0.0, 20.0), Vec3::new(5.0, 0.0, 20.0));
+        app.update();
+        assert!(app.world().resource::<TwoHandGesture>().is_active());
+
+        // Hands twice as far apart: the cube doubles about the point between them
+        place(&mut app, hands, Vec3::new(-10.0, 0.0, 20.0), Vec3::new(10.0, 0.0, 20.0));
+        app.update();
+        let model = app.world().resource::<BrepModel>();
+        assert!(model.vertices.iter().any(|v| (v.position - Vector3::new(10.0, 10.0, -10.0)).norm() < 1e-4));
+
+        // Turning the hands a quarter turn about Z turns the cube with them
+        place(&mut app, hands, Vec3::new(0.0, -10.0, 20.0), Vec3::new(0.0, 10.0, 20.0));
+        app.update();
+        let model = app.world().resource::<BrepModel>();
+        assert!(model.vertices.iter().any(|v| (v.position - Vector3::new(-10.0, 10.0, -10.0)).norm() < 1e-4));
+
+        app.world_mut().get_mut::<XrController>(hands[0]).unwrap().grip = false;
+        app.update();
+        assert!(!app.world().resource::<TwoHandGesture>().is_active());
+        // The release sends the whole gesture as one command
+        let events = app.world().resource::<Events<ModelCommand>>();
+        let sent: Vec<&ModelCommand> = events.get_cursor().read(events).collect();
+        assert!(matches!(sent.as_slice(), [ModelCommand::MoveVertices(moves)] if moves.len() == 8), "{:?}", sent);
+    }
+
+    #[test]
+    fn test_hands_scale_and_turn_the_scene() {
+        let (mut app, hands) = gesture_app(Selection::default());
+        let camera = app.world_mut().spawn((Transform::from_xyz(0.0, 0.0, 30.0), ViewRig::HEADSET)).id();
+        place(&mut app, hands, Vec3::new(-5.0, 0.0, 0.0), Vec3::new(5.0, 0.0, 0.0));
+        app.update();
+
+        // Hands twice as far apart and turned a quarter turn about the vertical
+        place(&mut app, hands, Vec3::new(0.0, 0.0, -10.0), Vec3::new(0.0, 0.0, 10.0));
+        app.update();
+        let mut cursor = app.world().resource::<Events<ScaleWorld>>().get_cursor();
+        let factors: Vec<f32> = cursor.read(app.world().resource::<Events<ScaleWorld>>()).map(|e| e.factor).collect();
+        assert_eq!(factors.len(), 1);
+        assert!((factors[0] - 2.0).abs() < 1e-5);
+        let at = app.world().get::<Transform>(camera).unwrap().translation;
+        assert!((at - Vec3::new(30.0, 0.0, 0.0)).length() < 1e-3, "{at}");
+        // The model itself is untouched
+        assert!(app.world().resource::<BrepModel>().vertices.iter().all(|v| v.position.abs().max() == 5.0));
+        assert!(app.world().resource::<Events<ModelCommand>>().is_empty());
+    }
+}
@@ -0,0 +1,283 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: interaction::xr_controller
+//!
+//! Selecting and grabbing with XR controllers. The XR backend keeps an entity
+//! per controller with `XrController`, its transform set to the controller's
+//! aim pose and its buttons and thumbstick updated each frame. Each controller
+//! casts a ray along its forward axis and picks like the mouse cursor does,
+//! with a pick radius that grows with distance. Pulling the
+//! trigger selects the target under the ray in the same `Selection` the mouse
+//! edits; holding the grip grabs the body under the ray and moves it with the
+//! controller, following both its translation and its rotation. Releasing the
+//! grip sends the move to the command bus as `ModelCommand::MoveVertices`, so it
+//! is logged and shared like a gizmo drag. No XR backend ships in this
+//! repository yet; an app that adds one spawns the controller entities.
+
+use bevy::prelude::*;
+use nalgebra::{Isometry3, Point3, Quaternion, Translation3, UnitQuaternion, Vector3};
+
+use crate::interaction::picking::{is_body_pickable, pick_where_in, PickHit, PickTarget};
+use crate::interaction::selection::{Selection, SelectionFilter, SelectionItem};
+use crate::model::body::BodyId;
+use crate::model::brep_model::{bevy_vec3_to_na, na_vec3_to_bevy, BrepModel};
+use crate::model::bvh::SceneBvh;
+use crate::model::command::{commit_vertex_moves, CommandRelay, ModelCommand};
+use crate::model::groups::BodyGroups;
+use crate::model::layers::LayerManager;
+use crate::model::properties::BodyPropertiesCollection;
+use crate::render::hilighting::HOVER_COLOR;
+use crate::telemetry::crash::journal;
+
+/// Pick radius of vertices per unit of distance along the ray (radians)
+const VERTEX_PICK_ANGLE: f64 = 0.012;
+/// Pick radius of edges per unit of distance along the ray (radians)
+const EDGE_PICK_ANGLE: f64 = 0.008;
+/// Length of a ray that hits nothing (model units)
+const RAY_LENGTH: f32 = 2000.0;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum XrHand {
+    #[default]
+    Left,
+    Right,
+}
+
+/// A body held by a controller
+#[derive(Debug, Clone, PartialEq)]
+pub struct XrGrab {
+    pub body: BodyId,
+    /// Controller pose when the grab started
+    start: Isometry3<f64>,
+    /// Body vertex positions when the grab started
+    original: Vec<(usize, Vector3<f64>)>,
+}
+
+impl XrGrab {
+    /// Body vertex positions when the grab started
+    pub fn original(&self) -> &[(usize, Vector3<f64>)] {
+        &self.original
+    }
+}
+
+/// An XR controller: buttons set by the XR backend, ray and grab kept here
+#[derive(Component, Debug, Default, Clone)]
+pub struct XrController {
+    pub hand: XrHand,
+    pub trigger: bool,
+    pub grip: bool,
+    /// Primary face button (A or X)
+    pub primary: bool,
+    /// Thumbstick deflection, x to the right and y forward, -1..=1
+    pub thumbstick: Vec2,
+    /// Ray origin and unit direction (model space)
+    pub ray: Option<(Vector3<f64>, Vector3<f64>)>,
+    /// Target under the ray
+    pub hover: Option<PickHit>,
+    pub grab: Option<XrGrab>,
+    /// Buttons as of the previous frame, for presses
+    was: (bool, bool, bool),
+}
+
+impl XrController {
+    pub fn new(hand: XrHand) -> Self {
+        Self { hand, ..default() }
+    }
+
+    pub fn trigger_pressed(&self) -> bool {
+        self.trigger && !self.was.0
+    }
+
+    pub fn grip_pressed(&self) -> bool {
+        self.grip && !self.was.1
+    }
+
+    pub fn primary_pressed(&self) -> bool {
+        self.primary && !self.was.2
+    }
+}
+
+/// Controller pose as a rigid transform; scale is ignored
+fn isometry(transform: &GlobalTransform) -> Isometry3<f64> {
+    let (_, rotation, translation) = transform.to_scale_rotation_translation();
+    let rotation = Quaternion::new(rotation.w as f64, rotation.x as f64, rotation.y as f64, rotation.z as f64);
+    Isometry3::from_parts(Translation3::from(bevy_vec3_to_na(&translation)), UnitQuaternion::from_quaternion(rotation))
+}
+
+/// Cast each controller's ray into the model
+pub fn update_xr_pointers(
+    mut controllers: Query<(&GlobalTransform, &mut XrController)>,
+    (model, bvh): (Res<BrepModel>, Option<Res<SceneBvh>>),
+    selection: Option<Res<Selection>>,
+    (properties, groups, layers): (Option<Res<BodyPropertiesCollection>>, Option<Res<BodyGroups>>, Option<Res<LayerManager>>),
+) {
+    if controllers.is_empty() {
+        return;
+    }
+    let built;
+    let bvh = match bvh.as_deref() {
+        Some(bvh) => bvh,
+        None => {
+            built = SceneBvh::build(&model);
+            &built
+        }
+    };
+    let filter = selection.map_or(SelectionFilter::Any, |s| s.filter);
+    let (no_groups, no_layers, no_properties) = (BodyGroups::default(), LayerManager::default(), BodyPropertiesCollection::default());
+    let groups = groups.as_deref().unwrap_or(&no_groups);
+    let layers = layers.as_deref().unwrap_or(&no_layers);
+    let properties = properties.as_deref().unwrap_or(&no_properties);
+    for (transform, mut controller) in controllers.iter_mut() {
+        let origin = bevy_vec3_to_na(&transform.translation());
+        let dir = bevy_vec3_to_na(&transform.forward().as_vec3());
+        let radius = |target: PickTarget, p: &Vector3<f64>| {
+            let angle = if matches!(target, PickTarget::Vertex(_)) { VERTEX_PICK_ANGLE } else { EDGE_PICK_ANGLE };
+            (p - origin).norm() * angle
+        };
+        let hover = if controller.grab.is_some() {
+            None
+        } else {
+            pick_where_in(&model, bvh, &origin, &dir, filter, radius, |body| is_body_pickable(body, groups, layers, properties))
+        };
+        controller.ray = Some((origin, dir));
+        controller.hover = hover;
+    }
+}
+
+/// Trigger selects the target under the controller's ray, or clears the selection over nothing
+pub fn xr_controller_select(controllers: Query<&XrController>, mut selection: ResMut<Selection>) {
+    for controller in controllers.iter().filter(|c| c.trigger_pressed()) {
+        let item = controller.hover.as_ref().and_then(|h| h.selection_item(selection.filter));
+        selection.clear();
+        if let Some(item) = item {
+            selection.add(item);
+        }
+    }
+}
+
+/// Grip grabs the body under the ray and moves it rigidly with the controller;
+/// the release commits the move to the command bus
+pub fn xr_controller_grab(
+    mut controllers: Query<(&GlobalTransform, &mut XrController)>,
+    mut model: ResMut<BrepModel>,
+    mut selection: Option<ResMut<Selection>>,
+    (relay, mut commands): (Option<Res<CommandRelay>>, EventWriter<ModelCommand>),
+) {
+    for (transform, mut controller) in controllers.iter_mut() {
+        let pose = isometry(transform);
+        if let Some(grab) = controller.grab.clone() {
+            if !controller.grip {
+                journal(format!("xr_grab body {}", grab.body.0));
+                commit_vertex_moves(&mut model, &grab.original, relay.as_deref(), &mut commands);
+                controller.grab = None;
+                continue;
+            }
+            let delta = pose * grab.start.inverse();
+            for (id, p) in &grab.original {
+                if let Some(v) = model.vertices.iter_mut().find(|v| v.id == *id) {
+                    v.position = delta.transform_point(&Point3::from(*p)).coords;
+                }
+            }
+            continue;
+        }
+        let Some(body) = controller.hover.as_ref().and_then(|h| h.body).filter(|_| controller.grip_pressed()) else { continue };
+        let Some(faces) = model.body_faces(body) else { continue };
+        let original = model.shell_vertex_ids(&faces).into_iter().filter_map(|id| Some((id, model.vertex_position(id)?))).collect();
+        controller.grab = Some(XrGrab { body, start: pose, original });
+        if let Some(selection) = selection.as_mut() {
+            selection.clear();
+            selection.add(SelectionItem::Body(body));
+        }
+    }
+}
+
+/// Remember this frame's buttons so the next frame sees presses
+pub fn latch_xr_buttons(mut controllers: Query<&mut XrController>) {
+    for mut controller in controllers.iter_mut() {
+        let buttons = (controller.trigger, controller.grip, controller.primary);
+        if controller.was != buttons {
+            controller.was = buttons;
+        }
+    }
+}
+
+/// Draw each controller's ray up to the target under it
+pub fn render_xr_rays(mut gizmos: Gizmos, controllers: Query<&XrController>) {
+    for controller in controllers.iter() {
+        let Some((origin, dir)) = controller.ray else { continue };
+        let start = na_vec3_to_bevy(&origin);
+        match &controller.hover {
+            Some(hit) => {
+                let end = na_vec3_to_bevy(&hit.point);
+                gizmos.line(start, end, HOVER_COLOR);
+                gizmos.sphere(Isometry3d::from_translation(end), 0.01 * start.distance(end), HOVER_COLOR);
+            }
+            None => gizmos.line(start, start + na_vec3_to_bevy(&dir) * RAY_LENGTH, Color::WHITE.with_alpha(0.4)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::cube;
+
+    fn controller_app() -> (App, Entity) {
+        let mut app = App::new();
+        app.insert_resource(cube(10.0))
+            .init_resource::<Selection>()
+            .add_event::<ModelCommand>()
+            .add_systems(Update, (update_xr_pointers, xr_controller_select, xr_controller_grab, latch_xr_buttons).chain());
+        let pose = Transform::from_xyz(1.0, 2.0, 20.0).looking_at(Vec3::new(1.0, 2.0, 0.0), Vec3::Y);
+        let controller = app.world_mut().spawn((XrController::new(XrHand::Right), GlobalTransform::from(pose))).id();
+        (app, controller)
+    }
+
+    fn controller(app: &mut App, entity: Entity) -> Mut<'_, XrController> {
+        app.world_mut().get_mut::<XrController>(entity).unwrap()
+    }
+
+    #[test]
+    fn test_trigger_selects_target_under_ray() {
+        let (mut app, entity) = controller_app();
+        app.update();
+        let top = app.world().resource::<BrepModel>().faces[1].id;
+        assert_eq!(controller(&mut app, entity).hover.as_ref().map(|h| h.target), Some(PickTarget::Face(top)));
+
+        controller(&mut app, entity).trigger = true;
+        app.update();
+        assert!(app.world().resource::<Selection>().contains(SelectionItem::Face(top)));
+        // Holding the trigger does not select again
+        app.world_mut().resource_mut::<Selection>().clear();
+        app.update();
+        assert!(app.world().resource::<Selection>().is_empty());
+    }
+
+    #[test]
+    fn test_grip_moves_body_with_controller() {
+        let (mut app, entity) = controller_app();
+        app.update();
+        controller(&mut app, entity).grip = true;
+        app.update();
+        assert_eq!(controller(&mut app, entity).grab.as_ref().map(|g| g.body), Some(BodyId(0)));
+        assert!(app.world().resource::<Selection>().contains(SelectionItem::Body(BodyId(0))));
+
+        // Move the controller up by 3 and turn it a quarter turn about its own position
+        let pose = Transform::from_xyz(1.0, 2.0, 23.0).looking_at(Vec3::new(1.0, 2.0, 0.0), Vec3::Y) * Transform::from_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_2));
+        app.world_mut().entity_mut(entity).insert(GlobalTransform::from(pose));
+        app.update();
+        let model = app.world().resource::<BrepModel>();
+        // The corner at (5, 5, 5) turns about the controller's axis through (1, 2) and rises by 3
+        let moved = model.vertices.iter().map(|v| v.position).find(|p| (p - Vector3::new(-2.0, 6.0, 8.0)).norm() < 1e-4);
+        assert!(moved.is_some(), "{:?}", model.vertices.iter().map(|v| v.position).collect::<Vec<_>>());
+
+        controller(&mut app, entity).grip = false;
+        app.update();
+        assert!(controller(&mut app, entity).grab.is_none());
+        // The release sends the whole move as one command
+        let events = app.world().resource::<Events<ModelCommand>>();
+        let sent: Vec<&ModelCommand> = events.get_cursor().read(events).collect();
+        assert!(matches!(sent.as_slice(), [ModelCommand::MoveVertices(moves)] if moves.len() == 8), "{:?}", sent);
+    }
+}
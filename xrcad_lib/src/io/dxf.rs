@@ -0,0 +1,414 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: io::dxf
+//!
+//! DXF exchange of 2D sketches. Import reads LINE, ARC, CIRCLE, LWPOLYLINE and
+//! old-style POLYLINE entities (bulges become arcs) onto a construction plane,
+//! welding coincident end points; `$INSUNITS` is honoured and unitless drawings
+//! are read in the document unit; the drawing is added with a `ModelCommand`.
+//! Export writes lines, arcs and circles in millimetres, which laser cutting
+//! software reads as-is.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Write as _};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+use nalgebra::Vector2;
+
+use crate::model::brep::topology::plane::Plane;
+use crate::model::command::ModelCommand;
+use crate::model::units::{LengthUnit, UnitSystem};
+use crate::sketch::sketch::{Sketch, SketchEntity, Sketches};
+use crate::telemetry::crash::journal;
+use crate::telemetry::usage::UsageStats;
+
+/// Why a DXF file could not be read or written
+#[derive(Debug)]
+pub enum DxfError {
+    Io(io::Error),
+    /// Malformed group code structure; `line` is 1-based
+    Parse { line: usize, message: String },
+}
+
+impl fmt::Display for DxfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DxfError::Io(err) => write!(f, "{}", err),
+            DxfError::Parse { line, message } => write!(f, "invalid DXF at line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for DxfError {}
+
+impl From<io::Error> for DxfError {
+    fn from(err: io::Error) -> Self {
+        DxfError::Io(err)
+    }
+}
+
+/// What an import brought in and what it left out
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DxfReport {
+    pub entities: usize,
+    /// Entity types that were skipped, with counts
+    pub skipped: BTreeMap<String, usize>,
+}
+
+/// One group code / value pair and the line its code was on
+#[derive(Clone, Copy)]
+struct Group<'a> {
+    code: i32,
+    value: &'a str,
+    line: usize,
+}
+
+fn groups(text: &str) -> Result<Vec<Group<'_>>, DxfError> {
+    let lines: Vec<&str> = text.lines().collect();
+    lines
+        .chunks(2)
+        .enumerate()
+        .filter(|(_, pair)| pair.len() == 2 || !pair[0].trim().is_empty())
+        .map(|(i, pair)| {
+            let line = i * 2 + 1;
+            let code = pair[0].trim().parse().map_err(|_| DxfError::Parse { line, message: format!("bad group code {:?}", pair[0]) })?;
+            let value = pair.get(1).ok_or(DxfError::Parse { line, message: "missing value".into() })?.trim();
+            Ok(Group { code, value, line })
+        })
+        .collect()
+}
+
+/// Polyline vertices, each with the bulge of the segment to the next vertex
+type Vertices = Vec<(Vector2<f64>, f64)>;
+
+/// Entity type and its groups, up to the next entity
+struct Entity<'a> {
+    kind: &'a str,
+    groups: Vec<Group<'a>>,
+}
+
+impl Entity<'_> {
+    fn real(&self, code: i32) -> Result<f64, DxfError> {
+        let Some(g) = self.groups.iter().find(|g| g.code == code) else {
+            return Ok(0.0);
+        };
+        g.value.parse().map_err(|_| DxfError::Parse { line: g.line, message: format!("bad number {:?}", g.value) })
+    }
+
+    fn flags(&self) -> i64 {
+        self.groups.iter().find(|g| g.code == 70).and_then(|g| g.value.parse().ok()).unwrap_or(0)
+    }
+
+    fn lw_vertices(&self) -> Result<Vertices, DxfError> {
+        let mut out = Vertices::new();
+        for g in &self.groups {
+            let parse = || g.value.parse::<f64>().map_err(|_| DxfError::Parse { line: g.line, message: format!("bad number {:?}", g.value) });
+            match (g.code, out.last_mut()) {
+                (10, _) => out.push((Vector2::new(parse()?, 0.0), 0.0)),
+                (20, Some(last)) => last.0.y = parse()?,
+                (42, Some(last)) => last.1 = parse()?,
+                _ => {}
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Drawing units scale to millimetres from the header; unitless drawings are in `unitless`
+fn units_scale(header: Option<&[Group<'_>]>, unitless: LengthUnit) -> f64 {
+    let insunits = header
+        .and_then(|h| h.windows(2).find(|w| w[0].code == 9 && w[0].value == "$INSUNITS"))
+        .and_then(|w| w[1].value.parse::<i32>().ok());
+    match insunits {
+        // Feet
+        Some(2) => 304.8,
+        Some(code) => LengthUnit::from_dxf_code(code).unwrap_or(unitless).mm(),
+        None => unitless.mm(),
+    }
+}
+
+/// Adds entities to a sketch, reusing points that already exist at a position
+struct Builder {
+    sketch: Sketch,
+    scale: f64,
+}
+
+impl Builder {
+    fn point(&mut self, p: Vector2<f64>) -> usize {
+        let p = p * self.scale;
+        let tol = self.sketch.tolerance.linear;
+        match self.sketch.points.iter().find(|q| (q.position - p).norm() <= tol) {
+            Some(q) => q.id,
+            None => self.sketch.add_point(p),
+        }
+    }
+
+    fn line(&mut self, a: Vector2<f64>, b: Vector2<f64>) {
+        let (a, b) = (self.point(a), self.point(b));
+        if a != b {
+            self.sketch.add_line(a, b);
+        }
+    }
+
+    /// Counter-clockwise arc about `c` from angle `a0` to `a1` (degrees)
+    fn arc(&mut self, c: Vector2<f64>, r: f64, a0: f64, a1: f64) {
+        let at = |a: f64| c + Vector2::new(a.to_radians().cos(), a.to_radians().sin()) * r;
+        let (start, end) = (self.point(at(a0)), self.point(at(a1)));
+        let center = self.point(c);
+        self.sketch.add_arc(center, start, end);
+    }
+
+    /// Polyline segment from `a` to `b` with the given bulge (tan of a quarter of the sweep).
+    /// A segment whose arc strays from its chord by no more than the sketch's
+    /// tolerance is taken as a line.
+    fn segment(&mut self, a: Vector2<f64>, b: Vector2<f64>, bulge: f64) {
+        let chord = b - a;
+        let tol = self.sketch.tolerance.linear;
+        // The sagitta (height of the arc over the chord) is half the chord times the bulge
+        let length = chord.norm() * self.scale;
+        if length <= tol || (length * bulge * 0.5).abs() <= tol {
+            return self.line(a, b);
+        }
+        let left = Vector2::new(-chord.y, chord.x).normalize();
+        let c = (a + b) * 0.5 + left * chord.norm() * (1.0 - bulge * bulge) / (4.0 * bulge);
+        let angle = |p: Vector2<f64>| (p - c).y.atan2((p - c).x).to_degrees();
+        // Sketch arcs run counter-clockwise, so a clockwise bulge swaps the ends
+        let (from, to) = if bulge > 0.0 { (a, b) } else { (b, a) };
+        self.arc(c, (a - c).norm(), angle(from), angle(to));
+    }
+
+    fn polyline(&mut self, vertices: &[(Vector2<f64>, f64)], closed: bool) {
+        let n = vertices.len();
+        let segments = if closed { n } else { n.saturating_sub(1) };
+        for i in 0..segments {
+            let ((a, bulge), (b, _)) = (vertices[i], vertices[(i + 1) % n]);
+            self.segment(a, b, bulge);
+        }
+    }
+}
+
+/// Read the entities of a DXF drawing into a new sketch on `plane`; values of
+/// drawings without `$INSUNITS` are taken to be in `unitless`
+pub fn read_dxf(name: &str, text: &str, plane: Plane, unitless: LengthUnit) -> Result<(Sketch, DxfReport), DxfError> {
+    let groups = groups(text)?;
+    let section = |name: &str| {
+        let start = groups.windows(2).position(|w| w[0].code == 0 && w[0].value == "SECTION" && w[1].code == 2 && w[1].value == name)?;
+        let end = groups[start..].iter().position(|g| g.code == 0 && g.value == "ENDSEC")?;
+        Some(&groups[start + 2..start + end])
+    };
+    let scale = units_scale(section("HEADER"), unitless);
+    let body = section("ENTITIES").ok_or(DxfError::Parse { line: 1, message: "no ENTITIES section".into() })?;
+
+    let mut entities: Vec<Entity<'_>> = Vec::new();
+    for g in body {
+        match (g.code, entities.last_mut()) {
+            (0, _) => entities.push(Entity { kind: g.value, groups: Vec::new() }),
+            (_, Some(e)) => e.groups.push(*g),
+            (_, None) => {}
+        }
+    }
+
+    let mut builder = Builder { sketch: Sketch::new(name, plane), scale };
+    let mut report = DxfReport::default();
+    let mut polyline: Option<(Vertices, bool)> = None;
+    for e in &entities {
+        let xy = |x: i32, y: i32| -> Result<Vector2<f64>, DxfError> { Ok(Vector2::new(e.real(x)?, e.real(y)?)) };
+        match e.kind {
+            "LINE" => builder.line(xy(10, 20)?, xy(11, 21)?),
+            "CIRCLE" => {
+                let center = builder.point(xy(10, 20)?);
+                builder.sketch.add_circle(center, e.real(40)? * scale);
+            }
+            "ARC" => builder.arc(xy(10, 20)?, e.real(40)?, e.real(50)?, e.real(51)?),
+            "LWPOLYLINE" => builder.polyline(&e.lw_vertices()?, e.flags() & 1 != 0),
+            "POLYLINE" => polyline = Some((Vec::new(), e.flags() & 1 != 0)),
+            "VERTEX" => {
+                if let Some((vertices, _)) = polyline.as_mut() {
+                    vertices.push((xy(10, 20)?, e.real(42)?));
+                }
+                continue;
+            }
+            "SEQEND" => {
+                if let Some((vertices, closed)) = polyline.take() {
+                    builder.polyline(&vertices, closed);
+                }
+                continue;
+            }
+            other => {
+                *report.skipped.entry(other.to_string()).or_insert(0) += 1;
+                continue;
+            }
+        }
+        report.entities += 1;
+    }
+    Ok((builder.sketch, report))
+}
+
+/// `$INSUNITS` code of written drawings
+const MILLIMETRES: &str = "4";
+
+/// Write a sketch's curves as a DXF drawing in millimetres
+pub fn write_dxf(sketch: &Sketch) -> String {
+    let mut out = String::new();
+    let mut group = |code: i32, value: String| {
+        let _ = writeln!(out, "{}\n{}", code, value);
+    };
+    for (code, value) in [(0, "SECTION"), (2, "HEADER"), (9, "$INSUNITS"), (70, MILLIMETRES), (0, "ENDSEC"), (0, "SECTION"), (2, "ENTITIES")] {
+        group(code, value.to_string());
+    }
+    let angle = |c: Vector2<f64>, p: Vector2<f64>| (p - c).y.atan2((p - c).x).to_degrees().rem_euclid(360.0);
+    for entity in &sketch.entities {
+        let p = |id: usize| sketch.point_position(id);
+        let (kind, values) = match entity {
+            SketchEntity::Line { start, end, .. } => {
+                let (Some(a), Some(b)) = (p(*start), p(*end)) else { continue };
+                ("LINE", vec![(10, a.x), (20, a.y), (30, 0.0), (11, b.x), (21, b.y), (31, 0.0)])
+            }
+            SketchEntity::Circle { center, radius, .. } => {
+                let Some(c) = p(*center) else { continue };
+                ("CIRCLE", vec![(10, c.x), (20, c.y), (30, 0.0), (40, *radius)])
+            }
+            SketchEntity::Arc { center, start, end, .. } => {
+                let (Some(c), Some(s), Some(e)) = (p(*center), p(*start), p(*end)) else { continue };
+                ("ARC", vec![(10, c.x), (20, c.y), (30, 0.0), (40, (s - c).norm()), (50, angle(c, s)), (51, angle(c, e))])
+            }
+        };
+        group(0, kind.to_string());
+        group(8, "0".to_string());
+        for (code, v) in values {
+            group(code, format!("{}", v));
+        }
+    }
+    group(0, "ENDSEC".to_string());
+    group(0, "EOF".to_string());
+    out
+}
+
+/// Import a `.dxf` file as a sketch named after the file stem
+pub fn load_dxf(path: &Path, plane: Plane, unitless: LengthUnit) -> Result<(Sketch, DxfReport), DxfError> {
+    let name = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_else(|| "Drawing".into());
+    read_dxf(&name, &fs::read_to_string(path)?, plane, unitless)
+}
+
+pub fn save_dxf(path: &Path, sketch: &Sketch) -> Result<(), DxfError> {
+    fs::write(path, write_dxf(sketch))?;
+    Ok(())
+}
+
+/// Request to import a DXF drawing onto the active sketch's plane (XY if none)
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct ImportDxf {
+    pub path: PathBuf,
+}
+
+/// Request to export the active sketch as DXF
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct ExportDxf {
+    pub path: PathBuf,
+}
+
+/// Carry out DXF import and export requests
+pub fn apply_dxf_requests(
+    mut imports: EventReader<ImportDxf>,
+    mut exports: EventReader<ExportDxf>,
+    sketches: Res<Sketches>,
+    mut commands: EventWriter<ModelCommand>,
+    units: Option<Res<UnitSystem>>,
+    mut usage: Option<ResMut<UsageStats>>,
+) {
+    let unitless = units.map_or(LengthUnit::default(), |u| u.length);
+    for ev in imports.read() {
+        let start = Instant::now();
+        journal(format!("import_dxf {:?}", ev.path));
+        let plane = sketches.active().map(|s| s.plane.clone()).unwrap_or_else(Plane::xy);
+        match load_dxf(&ev.path, plane, unitless) {
+            Ok((sketch, report)) => {
+                info!("Imported {} entities from {}", report.entities, ev.path.display());
+                for (kind, count) in &report.skipped {
+                    warn!("Skipped {} unsupported {} entities", count, kind);
+                }
+                commands.write(ModelCommand::AddSketch(sketch));
+            }
+            Err(err) => warn!("Could not import {}: {}", ev.path.display(), err),
+        }
+        if let Some(usage) = usage.as_mut() {
+            usage.record("import_dxf", start.elapsed());
+        }
+    }
+    for ev in exports.read() {
+        let start = Instant::now();
+        journal(format!("export_dxf {:?}", ev.path));
+        match sketches.active().map(|sketch| save_dxf(&ev.path, sketch)) {
+            Some(Ok(())) => info!("Exported sketch to {}", ev.path.display()),
+            Some(Err(err)) => warn!("Could not export {}: {}", ev.path.display(), err),
+            None => info!("No active sketch to export"),
+        }
+        if let Some(usage) = usage.as_mut() {
+            usage.record("export_dxf", start.elapsed());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::{PI, TAU};
+
+    /// Counter-clockwise sweep of a sketch arc in radians
+    fn arc_sweep(sketch: &Sketch, entity: &SketchEntity) -> f64 {
+        let SketchEntity::Arc { center, start, end, .. } = entity else { return 0.0 };
+        let p = |id: &usize| sketch.point_position(*id).unwrap();
+        let (c, s, e) = (p(center), p(start), p(end));
+        ((e - c).y.atan2((e - c).x) - (s - c).y.atan2((s - c).x)).rem_euclid(TAU)
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut sketch = Sketch::new("Plate", Plane::xy());
+        let a = sketch.add_point(Vector2::new(0.0, 0.0));
+        let b = sketch.add_point(Vector2::new(100.0, 0.0));
+        let c = sketch.add_point(Vector2::new(100.0, 50.0));
+        let m = sketch.add_point(Vector2::new(100.0, 25.0));
+        sketch.add_line(a, b);
+        sketch.add_arc(m, b, c);
+        sketch.add_circle(a, 5.0);
+
+        let text = write_dxf(&sketch);
+        let (back, report) = read_dxf("Plate", &text, Plane::xy(), LengthUnit::Inch).unwrap();
+        assert_eq!(report, DxfReport { entities: 3, skipped: BTreeMap::new() });
+        assert_eq!(back.points.len(), 4);
+        assert_eq!(back.entities.len(), 3);
+        assert!((arc_sweep(&back, &back.entities[1]) - PI).abs() < 1e-9);
+        assert!(matches!(back.entities[2], SketchEntity::Circle { radius, .. } if radius == 5.0));
+    }
+
+    #[test]
+    fn test_polylines_bulges_and_units() {
+        // Closed slot in inches: two straight sides and two half-circle ends
+        let text = "0\nSECTION\n2\nHEADER\n9\n$INSUNITS\n70\n1\n0\nENDSEC\n0\nSECTION\n2\nENTITIES\n\
+0\nLWPOLYLINE\n8\n0\n90\n4\n70\n1\n10\n0\n20\n0\n10\n2\n20\n0\n42\n1\n10\n2\n20\n1\n10\n0\n20\n1\n42\n1\n\
+0\nPOLYLINE\n70\n0\n0\nVERTEX\n10\n0\n20\n-1\n42\n-0.41421356237\n0\nVERTEX\n10\n1\n20\n-2\n0\nSEQEND\n\
+0\nTEXT\n1\nhi\n0\nENDSEC\n0\nEOF\n";
+        let (sketch, report) = read_dxf("Slot", text, Plane::xy(), LengthUnit::Millimetre).unwrap();
+        assert_eq!(report.entities, 2);
+        assert_eq!(report.skipped.get("TEXT"), Some(&1));
+        let arcs: Vec<&SketchEntity> = sketch.entities.iter().filter(|e| matches!(e, SketchEntity::Arc { .. })).collect();
+        assert_eq!((sketch.entities.len(), arcs.len()), (5, 3));
+        assert!((arc_sweep(&sketch, arcs[0]) - PI).abs() < 1e-9);
+        // Clockwise quarter turn, stored as a counter-clockwise arc from its far end
+        let SketchEntity::Arc { center, start, .. } = arcs[2] else { unreachable!() };
+        assert!((sketch.point_position(*center).unwrap() - Vector2::new(0.0, -50.8)).norm() < 1e-6);
+        assert!((sketch.point_position(*start).unwrap() - Vector2::new(25.4, -50.8)).norm() < 1e-6);
+        assert!((arc_sweep(&sketch, arcs[2]) - PI / 2.0).abs() < 1e-6);
+
+        // Without a header the document unit applies
+        let line = "0\nSECTION\n2\nENTITIES\n0\nLINE\n10\n0\n20\n0\n11\n3\n21\n0\n0\nENDSEC\n0\nEOF\n";
+        let (sketch, _) = read_dxf("Line", line, Plane::xy(), LengthUnit::Centimetre).unwrap();
+        assert!(sketch.points.iter().any(|p| (p.position - Vector2::new(30.0, 0.0)).norm() < 1e-9));
+    }
+}
@@ -0,0 +1,248 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: io::gltf
+//!
+//! glTF 2.0 export of tessellated bodies, as a single binary `.glb` or a `.gltf`
+//! with the buffer embedded as a data URI. Each body becomes one node and one
+//! mesh with flat per-face normals and a metallic-roughness material mapped from
+//! its `Material`. Millimetre model units are written as glTF metres; textures
+//! are not exported.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::model::brep::tessellate::tessellate;
+use crate::model::brep_model::{na_vec3_to_bevy, BrepModel};
+use crate::model::material::Material;
+use crate::model::metadata::DocumentMetadata;
+
+/// Metres per model unit
+const METRES_PER_UNIT: f64 = 0.001;
+/// Component types and buffer view targets from the glTF specification
+const FLOAT: u32 = 5126;
+const UNSIGNED_INT: u32 = 5125;
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+/// JSON string literal
+fn string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// JSON number; non-finite values (not allowed in JSON) become 0
+fn number(v: f32) -> String {
+    if v.is_finite() { format!("{}", v) } else { "0".into() }
+}
+
+fn array(values: &[f32]) -> String {
+    format!("[{}]", values.iter().map(|v| number(*v)).collect::<Vec<_>>().join(","))
+}
+
+/// sRGB component to the linear value glTF color factors expect
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+}
+
+/// glTF material object for a `Material`
+fn material_json(material: &Material) -> String {
+    let [r, g, b] = material.base_color.map(|c| srgb_to_linear(c.clamp(0.0, 1.0)));
+    let alpha = material.alpha.clamp(0.0, 1.0);
+    let mut out = format!(
+        "{{\"name\":{},\"pbrMetallicRoughness\":{{\"baseColorFactor\":{},\"metallicFactor\":{},\"roughnessFactor\":{}}}",
+        string(&material.name),
+        array(&[r, g, b, alpha]),
+        number(material.metallic.clamp(0.0, 1.0)),
+        number(material.roughness.clamp(0.0, 1.0))
+    );
+    if alpha < 1.0 {
+        out.push_str(",\"alphaMode\":\"BLEND\"");
+    }
+    out.push('}');
+    out
+}
+
+/// glTF document and its single binary buffer
+struct Document {
+    json: String,
+    buffer: Vec<u8>,
+}
+
+fn build(metadata: &DocumentMetadata, bodies: &[(&str, &BrepModel, &Material)], buffer_uri: Option<&str>) -> Document {
+    let mut buffer: Vec<u8> = Vec::new();
+    let (mut views, mut accessors, mut meshes, mut materials, mut nodes) = (vec![], vec![], vec![], vec![], vec![]);
+    let mut view = |buffer: &mut Vec<u8>, bytes: Vec<u8>, target: u32| {
+        let offset = buffer.len();
+        buffer.extend(&bytes);
+        views.push(format!("{{\"buffer\":0,\"byteOffset\":{},\"byteLength\":{},\"target\":{}}}", offset, bytes.len(), target));
+        views.len() - 1
+    };
+
+    for (name, model, material) in bodies {
+        let (mut positions, mut normals, mut indices) = (Vec::<[f32; 3]>::new(), Vec::<[f32; 3]>::new(), Vec::<u32>::new());
+        for face in tessellate(model) {
+            let base = positions.len() as u32;
+            let n = face.normal.normalize();
+            for p in &face.positions {
+                let p = p * METRES_PER_UNIT;
+                positions.push(na_vec3_to_bevy(&p).to_array());
+                normals.push(na_vec3_to_bevy(&n).to_array());
+            }
+            indices.extend(face.triangles.iter().flatten().map(|i| base + *i as u32));
+        }
+        if indices.is_empty() {
+            continue;
+        }
+        let (min, max) = positions.iter().fold(([f32::INFINITY; 3], [f32::NEG_INFINITY; 3]), |(lo, hi), p| {
+            ([lo[0].min(p[0]), lo[1].min(p[1]), lo[2].min(p[2])], [hi[0].max(p[0]), hi[1].max(p[1]), hi[2].max(p[2])])
+        });
+        let floats = |v: &[[f32; 3]]| v.iter().flatten().flat_map(|f| f.to_le_bytes()).collect::<Vec<u8>>();
+        let position_view = view(&mut buffer, floats(&positions), ARRAY_BUFFER);
+        let normal_view = view(&mut buffer, floats(&normals), ARRAY_BUFFER);
+        let index_view = view(&mut buffer, indices.iter().flat_map(|i| i.to_le_bytes()).collect(), ELEMENT_ARRAY_BUFFER);
+
+        let first = accessors.len();
+        accessors.push(format!(
+            "{{\"bufferView\":{},\"componentType\":{},\"count\":{},\"type\":\"VEC3\",\"min\":{},\"max\":{}}}",
+            position_view,
+            FLOAT,
+            positions.len(),
+            array(&min),
+            array(&max)
+        ));
+        accessors.push(format!("{{\"bufferView\":{},\"componentType\":{},\"count\":{},\"type\":\"VEC3\"}}", normal_view, FLOAT, normals.len()));
+        accessors.push(format!("{{\"bufferView\":{},\"componentType\":{},\"count\":{},\"type\":\"SCALAR\"}}", index_view, UNSIGNED_INT, indices.len()));
+
+        materials.push(material_json(material));
+        meshes.push(format!(
+            "{{\"name\":{},\"primitives\":[{{\"attributes\":{{\"POSITION\":{},\"NORMAL\":{}}},\"indices\":{},\"material\":{}}}]}}",
+            string(name),
+            first,
+            first + 1,
+            first + 2,
+            materials.len() - 1
+        ));
+        nodes.push(format!("{{\"name\":{},\"mesh\":{}}}", string(name), meshes.len() - 1));
+    }
+
+    let uri = buffer_uri.map(|uri| format!(",\"uri\":{}", string(uri))).unwrap_or_default();
+    let mut json = format!(
+        "{{\"asset\":{{\"version\":\"2.0\",\"generator\":\"xrcad\"}},\"scene\":0,\"scenes\":[{{\"name\":{},\"nodes\":[{}]}}],\"nodes\":[{}],\"meshes\":[{}],\"materials\":[{}],\"accessors\":[{}],\"bufferViews\":[{}]",
+        string(&metadata.title),
+        (0..nodes.len()).map(|i| i.to_string()).collect::<Vec<_>>().join(","),
+        nodes.join(","),
+        meshes.join(","),
+        materials.join(","),
+        accessors.join(","),
+        views.join(",")
+    );
+    if !buffer.is_empty() {
+        let _ = write!(json, ",\"buffers\":[{{\"byteLength\":{}{}}}]", buffer.len(), uri);
+    }
+    json.push('}');
+    Document { json, buffer }
+}
+
+/// Standard base64 with padding, for data URIs
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Bodies with their materials as a `.gltf` JSON document with an embedded buffer
+pub fn write_gltf(metadata: &DocumentMetadata, bodies: &[(&str, &BrepModel, &Material)]) -> String {
+    let buffer = build(metadata, bodies, None).buffer;
+    let uri = format!("data:application/octet-stream;base64,{}", base64(&buffer));
+    build(metadata, bodies, Some(&uri)).json
+}
+
+/// Bodies with their materials as a binary `.glb`
+pub fn write_glb(metadata: &DocumentMetadata, bodies: &[(&str, &BrepModel, &Material)]) -> Vec<u8> {
+    let Document { json, mut buffer } = build(metadata, bodies, None);
+    let mut json = json.into_bytes();
+    json.resize(json.len().next_multiple_of(4), b' ');
+    buffer.resize(buffer.len().next_multiple_of(4), 0);
+    let bin_chunk = if buffer.is_empty() { 0 } else { 8 + buffer.len() };
+    let mut out = Vec::with_capacity(12 + 8 + json.len() + bin_chunk);
+    out.extend(b"glTF");
+    out.extend(2u32.to_le_bytes());
+    out.extend(((12 + 8 + json.len() + bin_chunk) as u32).to_le_bytes());
+    out.extend((json.len() as u32).to_le_bytes());
+    out.extend(b"JSON");
+    out.extend(json);
+    if !buffer.is_empty() {
+        out.extend((buffer.len() as u32).to_le_bytes());
+        out.extend(b"BIN\0");
+        out.extend(buffer);
+    }
+    out
+}
+
+/// Write bodies to `path`: binary for `.glb`, JSON with an embedded buffer otherwise
+pub fn save_gltf(path: &Path, metadata: &DocumentMetadata, bodies: &[(&str, &BrepModel, &Material)]) -> io::Result<()> {
+    let binary = path.extension().is_some_and(|e| e.eq_ignore_ascii_case("glb"));
+    if binary {
+        fs::write(path, write_glb(metadata, bodies))
+    } else {
+        fs::write(path, write_gltf(metadata, bodies))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::cube;
+
+    #[test]
+    fn test_glb_layout() {
+        let glass = Material { alpha: 0.25, ..Material::new("Glass", [1.0, 0.5, 0.0]) };
+        let glb = write_glb(&DocumentMetadata::new("Cubes"), &[("A", &cube(10.0), &Material::default()), ("B", &cube(20.0), &glass)]);
+        let word = |at: usize| u32::from_le_bytes([glb[at], glb[at + 1], glb[at + 2], glb[at + 3]]) as usize;
+        assert_eq!(&glb[0..4], b"glTF");
+        assert_eq!((word(4), word(8)), (2, glb.len()));
+        let json_len = word(12);
+        assert_eq!(&glb[16..20], b"JSON");
+        let json = std::str::from_utf8(&glb[20..20 + json_len]).unwrap();
+        // 6 faces * 4 corners * 2 attributes * 12 bytes + 6 * 2 triangles * 12 bytes, per body
+        let bin = 20 + json_len;
+        assert_eq!((word(bin), &glb[bin + 4..bin + 8]), (2 * (576 + 144), &b"BIN\0"[..]));
+        assert!(json.contains("\"count\":24,\"type\":\"VEC3\",\"min\":[-0.01,-0.01,-0.01],\"max\":[0.01,0.01,0.01]"));
+        assert!(json.contains("\"nodes\":[0,1]"));
+        assert_eq!(json.matches("\"alphaMode\":\"BLEND\"").count(), 1);
+        assert!(json.contains("\"baseColorFactor\":[1,0.21404114,0,0.25]"));
+    }
+
+    #[test]
+    fn test_gltf_embeds_buffer() {
+        assert_eq!(base64(b"xrcad"), "eHJjYWQ=");
+        assert_eq!(base64(b"glTF"), "Z2xURg==");
+        let text = write_gltf(&DocumentMetadata::new("Say \"hi\""), &[("Cube", &cube(1.0), &Material::default())]);
+        assert!(text.contains("\"name\":\"Say \\\"hi\\\"\""));
+        assert!(text.contains("\"byteLength\":720,\"uri\":\"data:application/octet-stream;base64,"));
+    }
+}
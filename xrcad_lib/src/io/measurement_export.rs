@@ -0,0 +1,261 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: io::measurement_export
+//!
+//! Exports every stored measurement and every sketch dimension annotation of a
+//! document as CSV or JSON, one row per item, for QA traceability. Values are in
+//! the document's units; each row names the entities measured and, for
+//! measurements, when they were taken (UTC, ISO 8601).
+
+use std::fmt::Write as _;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+
+use crate::interaction::selection::SelectionItem;
+use crate::measure::measurements::{Measurements, Quantity};
+use crate::model::units::{Length, UnitSystem};
+use crate::sketch::dimension::DimensionKind;
+use crate::sketch::sketch::Sketches;
+use crate::telemetry::crash::journal;
+use crate::telemetry::usage::UsageStats;
+
+/// One exported measurement or annotation
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeasurementRow {
+    /// "measurement", or "sketch:<name>" for sketch dimensions
+    pub source: String,
+    pub id: usize,
+    pub kind: String,
+    /// Value in the document unit
+    pub value: f64,
+    pub unit: String,
+    /// Entities measured, e.g. "face 3; edge 7"
+    pub references: String,
+    pub note: String,
+    pub timestamp: Option<String>,
+}
+
+/// Value and unit symbol of a model-unit quantity in the document units
+fn in_units(value: f64, quantity: Quantity, units: &UnitSystem) -> (f64, String) {
+    let symbol = units.length.symbol();
+    match quantity {
+        Quantity::Length => (Length::mm(value).value_in(units.length), symbol.to_string()),
+        Quantity::Area => (value / units.length.mm().powi(2), format!("{}²", symbol)),
+        Quantity::Angle => (value.to_degrees(), "°".to_string()),
+    }
+}
+
+fn reference(item: &SelectionItem) -> String {
+    match item {
+        SelectionItem::Vertex(id) => format!("vertex {}", id),
+        SelectionItem::Edge(id) => format!("edge {}", id),
+        SelectionItem::Face(id) => format!("face {}", id),
+        SelectionItem::Body(id) => format!("body {}", id.0),
+    }
+}
+
+/// Seconds since the Unix epoch as an ISO 8601 UTC date and time
+pub fn format_timestamp(secs: u64) -> String {
+    let (days, rem) = ((secs / 86_400) as i64, secs % 86_400);
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", year, month, day, rem / 3600, rem / 60 % 60, rem % 60)
+}
+
+/// Rows for all stored measurements, then all sketch dimensions
+pub fn measurement_rows(measurements: &Measurements, sketches: &Sketches, units: &UnitSystem) -> Vec<MeasurementRow> {
+    let mut rows = Vec::new();
+    for r in &measurements.records {
+        let (value, unit) = in_units(r.value, r.kind.quantity(), units);
+        rows.push(MeasurementRow {
+            source: "measurement".to_string(),
+            id: r.id,
+            kind: r.kind.label().to_string(),
+            value,
+            unit,
+            references: r.references.iter().map(reference).collect::<Vec<_>>().join("; "),
+            note: r.note.clone(),
+            timestamp: Some(format_timestamp(r.timestamp)),
+        });
+    }
+    for sketch in &sketches.sketches {
+        for dim in &sketch.dimensions {
+            let (kind, quantity, references) = match dim.kind {
+                DimensionKind::Linear { a, b } => ("Linear", Quantity::Length, format!("point {}; point {}", a, b)),
+                DimensionKind::Angular { a, b } => ("Angular", Quantity::Angle, format!("line {}; line {}", a, b)),
+                DimensionKind::Radial { entity } => ("Radial", Quantity::Length, format!("entity {}", entity)),
+            };
+            let (value, unit) = in_units(dim.value, quantity, units);
+            rows.push(MeasurementRow {
+                source: format!("sketch:{}", sketch.name),
+                id: dim.id,
+                kind: kind.to_string(),
+                value,
+                unit,
+                references,
+                note: String::new(),
+                timestamp: None,
+            });
+        }
+    }
+    rows
+}
+
+/// CSV field, quoted when it holds a separator, quote or line break
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+pub fn write_measurements_csv(rows: &[MeasurementRow]) -> String {
+    let mut out = String::from("source,id,kind,value,unit,references,note,timestamp\n");
+    for r in rows {
+        let fields = [
+            csv_field(&r.source),
+            r.id.to_string(),
+            csv_field(&r.kind),
+            r.value.to_string(),
+            csv_field(&r.unit),
+            csv_field(&r.references),
+            csv_field(&r.note),
+            r.timestamp.clone().unwrap_or_default(),
+        ];
+        let _ = writeln!(out, "{}", fields.join(","));
+    }
+    out
+}
+
+/// JSON string literal
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+pub fn write_measurements_json(rows: &[MeasurementRow]) -> String {
+    let objects: Vec<String> = rows
+        .iter()
+        .map(|r| {
+            format!(
+                "  {{\"source\": {}, \"id\": {}, \"kind\": {}, \"value\": {}, \"unit\": {}, \"references\": {}, \"note\": {}, \"timestamp\": {}}}",
+                json_string(&r.source),
+                r.id,
+                json_string(&r.kind),
+                if r.value.is_finite() { r.value.to_string() } else { "null".to_string() },
+                json_string(&r.unit),
+                json_string(&r.references),
+                json_string(&r.note),
+                r.timestamp.as_deref().map_or("null".to_string(), json_string),
+            )
+        })
+        .collect();
+    format!("[\n{}\n]\n", objects.join(",\n"))
+}
+
+/// Write the rows as JSON if `path` ends in .json, otherwise as CSV
+pub fn save_measurements(path: &Path, rows: &[MeasurementRow]) -> io::Result<()> {
+    let json = path.extension().is_some_and(|e| e.eq_ignore_ascii_case("json"));
+    std::fs::write(path, if json { write_measurements_json(rows) } else { write_measurements_csv(rows) })
+}
+
+/// Request to export the document's measurements
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct ExportMeasurements {
+    pub path: PathBuf,
+}
+
+pub fn apply_measurement_exports(
+    mut requests: EventReader<ExportMeasurements>,
+    measurements: Option<Res<Measurements>>,
+    sketches: Option<Res<Sketches>>,
+    units: Option<Res<UnitSystem>>,
+    mut usage: Option<ResMut<UsageStats>>,
+) {
+    for ev in requests.read() {
+        let start = Instant::now();
+        journal(format!("export_measurements {:?}", ev.path));
+        let rows = measurement_rows(
+            &measurements.as_deref().cloned().unwrap_or_default(),
+            &sketches.as_deref().cloned().unwrap_or_default(),
+            &units.as_deref().cloned().unwrap_or_default(),
+        );
+        match save_measurements(&ev.path, &rows) {
+            Ok(()) => info!("Exported {} measurements to {}", rows.len(), ev.path.display()),
+            Err(err) => warn!("Could not export {}: {}", ev.path.display(), err),
+        }
+        if let Some(usage) = usage.as_mut() {
+            usage.record("export_measurements", start.elapsed());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::measure::measurements::MeasurementKind;
+    use crate::model::brep::topology::plane::Plane;
+    use crate::model::units::LengthUnit;
+    use crate::sketch::sketch::Sketch;
+    use nalgebra::{Point3, Vector2};
+
+    fn document() -> (Measurements, Sketches) {
+        let mut measurements = Measurements::default();
+        measurements.add(MeasurementKind::Area, 250.0, vec![SelectionItem::Face(3)], Point3::origin());
+        measurements.records[0].timestamp = 86_400 * 365 + 3661;
+        measurements.records[0].note = "top, \"as built\"".to_string();
+        let mut sketch = Sketch::new("Sketch.001", Plane::xy());
+        let a = sketch.add_point(Vector2::new(0.0, 0.0));
+        let b = sketch.add_point(Vector2::new(30.0, 0.0));
+        sketch.add_dimension(DimensionKind::Linear { a, b }, None).unwrap();
+        let mut sketches = Sketches::default();
+        sketches.add(sketch);
+        (measurements, sketches)
+    }
+
+    #[test]
+    fn test_csv_rows_in_document_units() {
+        let (measurements, sketches) = document();
+        let rows = measurement_rows(&measurements, &sketches, &UnitSystem::new(LengthUnit::Centimetre));
+        let csv = write_measurements_csv(&rows);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "source,id,kind,value,unit,references,note,timestamp");
+        assert_eq!(lines[1], "measurement,0,Area,2.5,cm²,face 3,\"top, \"\"as built\"\"\",1971-01-01T01:01:01Z");
+        assert!(lines[2].starts_with("sketch:Sketch.001,") && lines[2].contains(",Linear,3,cm,point "));
+        assert!(lines[2].ends_with(",,"));
+    }
+
+    #[test]
+    fn test_json_and_timestamps() {
+        let (measurements, sketches) = document();
+        let json = write_measurements_json(&measurement_rows(&measurements, &sketches, &UnitSystem::default()));
+        assert!(json.contains("\"note\": \"top, \\\"as built\\\"\""));
+        assert!(json.contains("\"timestamp\": null"));
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+    }
+}
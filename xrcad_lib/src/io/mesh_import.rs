@@ -0,0 +1,255 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: io::mesh_import
+//!
+//! STL (ASCII and binary) and Wavefront OBJ import into mesh bodies. Polygons
+//! are fan-triangulated; normals, texture coordinates, groups and materials are
+//! ignored. Neither format records units, so files are read in the document unit.
+//! A loaded mesh is added with a `ModelCommand`, so the import is logged and
+//! shared like an edit.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+use nalgebra::Vector3;
+
+use crate::model::command::ModelCommand;
+use crate::model::mesh_body::MeshBody;
+use crate::model::tolerance::Tolerance;
+use crate::model::units::{LengthUnit, UnitSystem};
+use crate::telemetry::crash::journal;
+use crate::telemetry::usage::UsageStats;
+
+/// Why a mesh file could not be imported
+#[derive(Debug)]
+pub enum MeshImportError {
+    Io(io::Error),
+    /// Malformed content; `line` is 1-based for text formats and 0 for binary STL
+    Parse { line: usize, message: String },
+    /// Neither an `.stl` nor an `.obj` file
+    UnsupportedFormat(String),
+}
+
+impl fmt::Display for MeshImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MeshImportError::Io(err) => write!(f, "{}", err),
+            MeshImportError::Parse { line: 0, message } => write!(f, "invalid mesh: {}", message),
+            MeshImportError::Parse { line, message } => write!(f, "invalid mesh at line {}: {}", line, message),
+            MeshImportError::UnsupportedFormat(ext) => write!(f, "unsupported mesh format {:?}", ext),
+        }
+    }
+}
+
+impl std::error::Error for MeshImportError {}
+
+impl From<io::Error> for MeshImportError {
+    fn from(err: io::Error) -> Self {
+        MeshImportError::Io(err)
+    }
+}
+
+fn parse_error(line: usize, message: impl Into<String>) -> MeshImportError {
+    MeshImportError::Parse { line, message: message.into() }
+}
+
+/// Three floats from whitespace-separated tokens
+fn vector<'a>(tokens: impl Iterator<Item = &'a str>, line: usize) -> Result<Vector3<f64>, MeshImportError> {
+    let v: Vec<f64> = tokens.take(3).map(|t| t.parse::<f64>()).collect::<Result<_, _>>().map_err(|e| parse_error(line, e.to_string()))?;
+    match v.as_slice() {
+        [x, y, z] => Ok(Vector3::new(*x, *y, *z)),
+        _ => Err(parse_error(line, "expected three coordinates")),
+    }
+}
+
+/// Binary STL: 80-byte header, triangle count, then 50 bytes per triangle
+fn read_binary_stl(bytes: &[u8]) -> Result<Vec<[Vector3<f64>; 3]>, MeshImportError> {
+    let count = bytes.get(80..84).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize);
+    let count = count.ok_or_else(|| parse_error(0, "truncated header"))?;
+    if bytes.len() < 84 + count * 50 {
+        return Err(parse_error(0, format!("expected {} triangles", count)));
+    }
+    let float = |at: usize| f32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]]) as f64;
+    Ok((0..count)
+        .map(|i| {
+            // Skip the 12-byte facet normal
+            let base = 84 + i * 50 + 12;
+            let corner = |k: usize| Vector3::new(float(base + k * 12), float(base + k * 12 + 4), float(base + k * 12 + 8));
+            [corner(0), corner(1), corner(2)]
+        })
+        .collect())
+}
+
+fn read_ascii_stl(text: &str) -> Result<Vec<[Vector3<f64>; 3]>, MeshImportError> {
+    let mut soup = Vec::new();
+    let mut corners = Vec::with_capacity(3);
+    for (i, line) in text.lines().enumerate() {
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("vertex") => corners.push(vector(tokens, i + 1)?),
+            Some("endfacet") => {
+                let [a, b, c] = corners.as_slice() else {
+                    return Err(parse_error(i + 1, "facet without three vertices"));
+                };
+                soup.push([*a, *b, *c]);
+                corners.clear();
+            }
+            _ => {}
+        }
+    }
+    Ok(soup)
+}
+
+/// Read an STL file's triangles, detecting ASCII or binary encoding
+pub fn read_stl(name: &str, bytes: &[u8], tol: &Tolerance) -> Result<MeshBody, MeshImportError> {
+    // Binary headers may also start with "solid", so trust the size check first
+    let binary_size = bytes.get(80..84).map(|b| 84 + u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize * 50);
+    let is_ascii = |text: &str| text.trim_start().starts_with("solid") && text.contains("endsolid");
+    let soup = match std::str::from_utf8(bytes) {
+        Ok(text) if is_ascii(text) && binary_size != Some(bytes.len()) => read_ascii_stl(text)?,
+        _ => read_binary_stl(bytes)?,
+    };
+    Ok(MeshBody::from_triangles(name, &soup, tol))
+}
+
+/// Read an OBJ file's faces; negative indices count back from the latest vertex
+pub fn read_obj(name: &str, text: &str, tol: &Tolerance) -> Result<MeshBody, MeshImportError> {
+    let mut positions = Vec::new();
+    let mut soup = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("v") => positions.push(vector(tokens, i + 1)?),
+            Some("f") => {
+                let corners = tokens
+                    .map(|t| {
+                        let index: i64 = t.split('/').next().unwrap_or("").parse().map_err(|_| parse_error(i + 1, format!("invalid index {:?}", t)))?;
+                        let resolved = if index < 0 { positions.len() as i64 + index } else { index - 1 };
+                        usize::try_from(resolved)
+                            .ok()
+                            .and_then(|k| positions.get(k).copied())
+                            .ok_or_else(|| parse_error(i + 1, format!("vertex {} does not exist", index)))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                if corners.len() < 3 {
+                    return Err(parse_error(i + 1, "face with fewer than three vertices"));
+                }
+                soup.extend((1..corners.len() - 1).map(|k| [corners[0], corners[k], corners[k + 1]]));
+            }
+            _ => {}
+        }
+    }
+    Ok(MeshBody::from_triangles(name, &soup, tol))
+}
+
+/// Import an `.stl` or `.obj` file, named after the file stem
+/// Load a mesh file whose coordinates are in `unit`
+pub fn load_mesh(path: &Path, unit: LengthUnit) -> Result<MeshBody, MeshImportError> {
+    let name = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_else(|| "Mesh".into());
+    let ext = path.extension().map(|e| e.to_string_lossy().to_ascii_lowercase()).unwrap_or_default();
+    let tol = Tolerance::default();
+    let mut mesh = match ext.as_str() {
+        "stl" => read_stl(&name, &fs::read(path)?, &tol)?,
+        "obj" => read_obj(&name, &fs::read_to_string(path)?, &tol)?,
+        _ => return Err(MeshImportError::UnsupportedFormat(ext)),
+    };
+    for p in &mut mesh.positions {
+        *p *= unit.mm();
+    }
+    Ok(mesh)
+}
+
+/// Request to import a mesh file as a mesh body
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct ImportMesh {
+    pub path: PathBuf,
+}
+
+/// Load requested mesh files and send them to the document's mesh bodies
+pub fn apply_mesh_imports(
+    mut events: EventReader<ImportMesh>,
+    mut commands: EventWriter<ModelCommand>,
+    units: Option<Res<UnitSystem>>,
+    mut usage: Option<ResMut<UsageStats>>,
+) {
+    let unit = units.map_or(LengthUnit::default(), |u| u.length);
+    for ev in events.read() {
+        let start = Instant::now();
+        journal(format!("import_mesh {:?}", ev.path));
+        match load_mesh(&ev.path, unit) {
+            Ok(mesh) => {
+                info!(
+                    "Imported {} ({} triangles, {} shell(s){})",
+                    mesh.name,
+                    mesh.triangles.len(),
+                    mesh.shells().len(),
+                    if mesh.is_closed() { ", closed" } else { "" }
+                );
+                commands.write(ModelCommand::AddMesh(mesh));
+            }
+            Err(err) => warn!("Could not import {}: {}", ev.path.display(), err),
+        }
+        if let Some(usage) = usage.as_mut() {
+            usage.record("import_mesh", start.elapsed());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TETRA_OBJ: &str = "# tetrahedron
+v 0 0 0
+v 1 0 0
+v 0 1 0
+v 0 0 1
+f 1 3 2
+f 1/1 2/2 4/3
+f -4//1 -1//1 -2//1
+f 2 3 4
+";
+
+    fn binary_stl(triangles: &[[[f32; 3]; 3]]) -> Vec<u8> {
+        let mut out = b"solid but actually binary".to_vec();
+        out.resize(80, 0);
+        out.extend((triangles.len() as u32).to_le_bytes());
+        for t in triangles {
+            out.extend([0u8; 12]);
+            for c in t.iter().flatten() {
+                out.extend(c.to_le_bytes());
+            }
+            out.extend([0u8; 2]);
+        }
+        out
+    }
+
+    #[test]
+    fn test_read_obj() {
+        let mesh = read_obj("Tetra", TETRA_OBJ, &Tolerance::default()).unwrap();
+        assert_eq!((mesh.positions.len(), mesh.triangles.len()), (4, 4));
+        assert!(mesh.is_closed());
+        let quad = read_obj("Quad", "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3 4\n", &Tolerance::default()).unwrap();
+        assert_eq!(quad.triangles.len(), 2);
+        assert!(!quad.is_closed());
+        assert!(matches!(read_obj("Bad", "v 0 0 0\nf 1 2 3\n", &Tolerance::default()), Err(MeshImportError::Parse { line: 2, .. })));
+    }
+
+    #[test]
+    fn test_read_stl_ascii_and_binary() {
+        let ascii = "solid t\n facet normal 0 0 1\n  outer loop\n   vertex 0 0 0\n   vertex 1 0 0\n   vertex 0 1 0\n  endloop\n endfacet\nendsolid t\n";
+        let mesh = read_stl("A", ascii.as_bytes(), &Tolerance::default()).unwrap();
+        assert_eq!(mesh.triangles, vec![[0, 1, 2]]);
+
+        let bytes = binary_stl(&[[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]], [[1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]]]);
+        let mesh = read_stl("B", &bytes, &Tolerance::default()).unwrap();
+        assert_eq!((mesh.positions.len(), mesh.triangles.len()), (4, 2));
+        assert_eq!(mesh.shells().len(), 1);
+        assert!(matches!(read_stl("C", &bytes[..100], &Tolerance::default()), Err(MeshImportError::Parse { .. })));
+    }
+}
@@ -0,0 +1,484 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: io::project
+//!
+//! Native `.xrcad` project files: the whole document (topology, body properties,
+//! groups, layers, assembly, sketches, feature history, imported meshes,
+//! workspace helpers, workbenches, camera and saved views) as versioned RON.
+//! Files written by a newer version are rejected rather than half-read.
+//! Opening a file replaces the document: what referred to the old one (the
+//! selection, active body, change tracking, running jobs and commands waiting
+//! to be shared) is reset. While a shared session is open the document
+//! belongs to the session, so opening a file is refused.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+use crate::interaction::selection::Selection;
+use crate::interaction::state::{ActiveBody, UiLayout};
+use crate::io::session::{session_path, SessionState};
+use crate::measure::measurements::Measurements;
+use crate::model::assembly::Assembly;
+use crate::model::brep_model::BrepModel;
+use crate::model::changes::BodyChanges;
+use crate::model::command::{CommandRelay, ModelRevision};
+use crate::model::feature_tree::FeatureTree;
+use crate::model::groups::BodyGroups;
+use crate::model::jobs::BackgroundJobs;
+use crate::model::layers::LayerManager;
+use crate::model::mesh_body::MeshBodies;
+use crate::model::metadata::DocumentMetadata;
+use crate::model::units::UnitSystem;
+use crate::model::properties::BodyPropertiesCollection;
+use crate::net::collab::CollabSession;
+use crate::sketch::sketch::Sketches;
+use crate::telemetry::crash::journal;
+use crate::viewport::saved_views::SavedViews;
+use crate::workspace::workbenches::Workbenches;
+use crate::workspace::workspace::Workspace;
+
+/// Format version written by this build
+pub const PROJECT_VERSION: u32 = 1;
+/// File extension of project files
+pub const PROJECT_EXTENSION: &str = "xrcad";
+
+/// Why a project could not be saved or opened
+#[derive(Debug)]
+pub enum ProjectError {
+    Io(io::Error),
+    Serialize(String),
+    Parse(String),
+    /// Written by a newer version of the application
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for ProjectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProjectError::Io(err) => write!(f, "{}", err),
+            ProjectError::Serialize(msg) => write!(f, "could not serialize project: {}", msg),
+            ProjectError::Parse(msg) => write!(f, "invalid project file: {}", msg),
+            ProjectError::UnsupportedVersion(v) => {
+                write!(f, "project format version {} is newer than supported version {}", v, PROJECT_VERSION)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ProjectError {}
+
+impl From<io::Error> for ProjectError {
+    fn from(err: io::Error) -> Self {
+        ProjectError::Io(err)
+    }
+}
+
+/// Viewpoint of the main camera
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CameraState {
+    pub translation: [f32; 3],
+    /// Quaternion as x, y, z, w
+    pub rotation: [f32; 4],
+}
+
+impl CameraState {
+    pub fn from_transform(transform: &Transform) -> Self {
+        Self { translation: transform.translation.to_array(), rotation: transform.rotation.to_array() }
+    }
+
+    pub fn to_transform(&self) -> Transform {
+        Transform::from_translation(Vec3::from_array(self.translation))
+            .with_rotation(Quat::from_array(self.rotation).normalize())
+    }
+
+    /// Viewpoint of a world's main camera
+    #[cfg(feature = "render")]
+    pub fn capture(world: &mut World) -> Option<Self> {
+        world.query_filtered::<&Transform, With<Camera3d>>().iter(world).next().map(Self::from_transform)
+    }
+
+    /// Move a world's main camera here
+    #[cfg(feature = "render")]
+    pub fn apply(&self, world: &mut World) {
+        for mut transform in world.query_filtered::<&mut Transform, With<Camera3d>>().iter_mut(world) {
+            *transform = self.to_transform();
+        }
+    }
+
+    // Headless worlds have no camera
+    #[cfg(not(feature = "render"))]
+    pub fn capture(_world: &mut World) -> Option<Self> {
+        None
+    }
+
+    #[cfg(not(feature = "render"))]
+    pub fn apply(&self, _world: &mut World) {}
+}
+
+/// Just the version field, read before the rest of the file
+#[derive(Deserialize)]
+struct VersionHeader {
+    version: u32,
+}
+
+/// Everything persisted in a project file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectDocument {
+    pub version: u32,
+    pub metadata: DocumentMetadata,
+    /// Display and input units; absent in files from older builds
+    #[serde(default)]
+    pub units: UnitSystem,
+    pub model: BrepModel,
+    pub properties: BodyPropertiesCollection,
+    pub groups: BodyGroups,
+    /// Layers; absent in files from older builds
+    #[serde(default)]
+    pub layers: LayerManager,
+    /// Components, instances and mates; absent in files from older builds
+    #[serde(default)]
+    pub assembly: Assembly,
+    pub sketches: Sketches,
+    /// Feature history; results are recomputed after loading
+    pub features: FeatureTree,
+    /// Imported reference meshes; absent in files from older builds
+    #[serde(default)]
+    pub meshes: MeshBodies,
+    /// Stored measurements; absent in files from older builds
+    #[serde(default)]
+    pub measurements: Measurements,
+    /// Helpers of the active workbench
+    pub workspace: Workspace,
+    /// Workbenches; absent in files from older builds
+    #[serde(default)]
+    pub workbenches: Workbenches,
+    pub camera: Option<CameraState>,
+    /// Saved camera views; absent in files from older builds
+    #[serde(default)]
+    pub views: SavedViews,
+}
+
+impl Default for ProjectDocument {
+    fn default() -> Self {
+        Self {
+            version: PROJECT_VERSION,
+            metadata: DocumentMetadata::default(),
+            units: UnitSystem::default(),
+            model: BrepModel::default(),
+            properties: BodyPropertiesCollection::default(),
+            groups: BodyGroups::default(),
+            layers: LayerManager::default(),
+            assembly: Assembly::default(),
+            sketches: Sketches::default(),
+            features: FeatureTree::default(),
+            meshes: MeshBodies::default(),
+            measurements: Measurements::default(),
+            workspace: Workspace::new(),
+            workbenches: Workbenches::default(),
+            camera: None,
+            views: SavedViews::default(),
+        }
+    }
+}
+
+impl ProjectDocument {
+    pub fn to_ron(&self) -> Result<String, ProjectError> {
+        ron::ser::to_string_pretty(self, PrettyConfig::default()).map_err(|e| ProjectError::Serialize(e.to_string()))
+    }
+
+    pub fn from_ron(text: &str) -> Result<Self, ProjectError> {
+        let header: VersionHeader = ron::from_str(text).map_err(|e| ProjectError::Parse(e.to_string()))?;
+        if header.version > PROJECT_VERSION {
+            return Err(ProjectError::UnsupportedVersion(header.version));
+        }
+        let mut doc: ProjectDocument = ron::from_str(text).map_err(|e| ProjectError::Parse(e.to_string()))?;
+        doc.version = PROJECT_VERSION;
+        doc.features.rebuild();
+        Ok(doc)
+    }
+
+    /// Write the project, replacing `path` only once the new file is complete
+    pub fn save(&self, path: &Path) -> Result<(), ProjectError> {
+        let text = self.to_ron()?;
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, text)?;
+        fs::rename(tmp, path)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, ProjectError> {
+        Self::from_ron(&fs::read_to_string(path)?)
+    }
+
+    /// Snapshot the document resources and main camera of a world
+    pub fn capture(world: &mut World) -> Self {
+        let camera = CameraState::capture(world);
+        let workspace = world.get_resource::<Workspace>().cloned().unwrap_or_else(Workspace::new);
+        let mut workbenches = world.get_resource::<Workbenches>().cloned().unwrap_or_default();
+        workbenches.store_active(&workspace, &world.get_resource::<UiLayout>().cloned().unwrap_or_default());
+        Self {
+            version: PROJECT_VERSION,
+            metadata: world.get_resource::<DocumentMetadata>().cloned().unwrap_or_default(),
+            units: world.get_resource::<UnitSystem>().cloned().unwrap_or_default(),
+            model: world.get_resource::<BrepModel>().cloned().unwrap_or_default(),
+            properties: world.get_resource::<BodyPropertiesCollection>().cloned().unwrap_or_default(),
+            groups: world.get_resource::<BodyGroups>().cloned().unwrap_or_default(),
+            layers: world.get_resource::<LayerManager>().cloned().unwrap_or_default(),
+            assembly: world.get_resource::<Assembly>().cloned().unwrap_or_default(),
+            sketches: world.get_resource::<Sketches>().cloned().unwrap_or_default(),
+            features: world.get_resource::<FeatureTree>().cloned().unwrap_or_default(),
+            meshes: world.get_resource::<MeshBodies>().cloned().unwrap_or_default(),
+            measurements: world.get_resource::<Measurements>().cloned().unwrap_or_default(),
+            workspace: workspace.clone(),
+            workbenches,
+            camera,
+            views: world.get_resource::<SavedViews>().cloned().unwrap_or_default(),
+        }
+    }
+
+    /// Replace the document resources of a world and move the main camera.
+    /// The selection and active body are cleared, every body counts as
+    /// changed, and jobs and unshared commands of the old document are dropped.
+    pub fn apply(self, world: &mut World) {
+        if let Some(camera) = self.camera {
+            camera.apply(world);
+        }
+        world.insert_resource(self.metadata);
+        world.insert_resource(self.units);
+        world.insert_resource(self.model);
+        world.insert_resource(self.properties);
+        world.insert_resource(self.groups);
+        world.insert_resource(self.layers);
+        world.insert_resource(self.assembly);
+        world.insert_resource(self.sketches);
+        world.insert_resource(self.features);
+        world.insert_resource(self.meshes);
+        world.insert_resource(self.measurements);
+        world.insert_resource(self.workspace);
+        world.insert_resource(self.workbenches);
+        world.insert_resource(self.views);
+        if let Some(mut selection) = world.get_resource_mut::<Selection>() {
+            selection.items.clear();
+        }
+        world.insert_resource(ActiveBody(None));
+        if world.contains_resource::<BodyChanges>() {
+            world.insert_resource(BodyChanges::default());
+        }
+        if let Some(mut jobs) = world.get_resource_mut::<BackgroundJobs>() {
+            jobs.cancel_all();
+        }
+        // Results of jobs that still finish are stale against the new document
+        if let Some(mut revision) = world.get_resource_mut::<ModelRevision>() {
+            revision.0 += 1;
+        }
+        if let Some(mut relay) = world.get_resource_mut::<CommandRelay>() {
+            relay.outgoing.clear();
+            relay.incoming.clear();
+        }
+    }
+}
+
+/// A shared session is open, so the document must not be replaced locally
+pub fn in_shared_session(world: &World) -> bool {
+    world.get_resource::<CollabSession>().is_some_and(|session| session.is_active())
+}
+
+/// Request to write the open document to a file
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct SaveProject {
+    pub path: PathBuf,
+}
+
+/// Request to replace the open document with a file
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct OpenProject {
+    pub path: PathBuf,
+}
+
+/// File backing the open document, if it has been saved or opened
+#[derive(Resource, Debug, Default, Clone)]
+pub struct ProjectFile {
+    pub path: Option<PathBuf>,
+}
+
+/// Path with the project extension added when missing
+pub fn with_project_extension(path: impl Into<PathBuf>) -> PathBuf {
+    let mut path = path.into();
+    if path.extension().is_none() {
+        path.set_extension(PROJECT_EXTENSION);
+    }
+    path
+}
+
+/// Carry out save and open requests
+pub fn handle_project_requests(world: &mut World) {
+    let saves: Vec<SaveProject> = world
+        .get_resource_mut::<Events<SaveProject>>()
+        .map(|mut events| events.drain().collect())
+        .unwrap_or_default();
+    let opens: Vec<OpenProject> = world
+        .get_resource_mut::<Events<OpenProject>>()
+        .map(|mut events| events.drain().collect())
+        .unwrap_or_default();
+    for request in saves {
+        journal(format!("save_project {:?}", request.path));
+        match ProjectDocument::capture(world).save(&request.path) {
+            Ok(()) => {
+                info!("Saved project to {}", request.path.display());
+                if let Err(err) = SessionState::capture(world).save(&session_path(&request.path)) {
+                    warn!("Could not save session state: {}", err);
+                }
+                world.insert_resource(ProjectFile { path: Some(request.path) });
+            }
+            Err(err) => warn!("Could not save {}: {}", request.path.display(), err),
+        }
+    }
+    for request in opens {
+        if in_shared_session(world) {
+            warn!("Leave the shared session before opening {}", request.path.display());
+            continue;
+        }
+        journal(format!("open_project {:?}", request.path));
+        match ProjectDocument::load(&request.path) {
+            Ok(doc) => {
+                doc.apply(world);
+                info!("Opened project {}", request.path.display());
+                let session = session_path(&request.path);
+                if session.exists() {
+                    match SessionState::load(&session) {
+                        Ok(state) => state.apply(world),
+                        Err(err) => warn!("Could not restore session state: {}", err),
+                    }
+                }
+                world.insert_resource(ProjectFile { path: Some(request.path) });
+            }
+            Err(err) => warn!("Could not open {}: {}", request.path.display(), err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interaction::selection::{SelectionFilter, SelectionItem};
+    use crate::model::body::BodyId;
+    use crate::model::brep::primitives::cube;
+    use crate::model::command::ModelCommand;
+    use crate::model::feature_tree::FeatureKind;
+    use crate::model::brep::topology::plane::Plane;
+    use crate::sketch::sketch::Sketch;
+    use nalgebra::{Vector2, Vector3};
+
+    fn sample() -> ProjectDocument {
+        let mut doc = ProjectDocument { model: cube(10.0), ..Default::default() };
+        doc.metadata.title = "Bracket".into();
+        doc.units = UnitSystem::new(crate::model::units::LengthUnit::Inch);
+        doc.properties.register(BodyId(0), "Body");
+        let bracket = doc.assembly.create_component("Bracket");
+        doc.assembly.add_body(bracket, BodyId(0)).unwrap();
+        doc.assembly.add_instance(doc.assembly.root(), bracket, nalgebra::Isometry3::translation(0.0, 20.0, 0.0)).unwrap();
+        let mut sketch = Sketch::new("Sketch.001", Plane::xy());
+        let a = sketch.add_point(Vector2::new(0.0, 0.0));
+        let b = sketch.add_point(Vector2::new(5.0, 0.0));
+        sketch.add_line(a, b);
+        doc.sketches.active = Some(doc.sketches.add(sketch));
+        doc.features.add("Box", FeatureKind::Box { size: Vector3::new(1.0, 2.0, 3.0), placement: None }).unwrap();
+        doc.camera = Some(CameraState::from_transform(&Transform::from_xyz(-5.0, 5.0, 5.0)));
+        doc
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let doc = sample();
+        let loaded = ProjectDocument::from_ron(&doc.to_ron().unwrap()).unwrap();
+        assert_eq!(loaded.metadata, doc.metadata);
+        assert_eq!(loaded.units, doc.units);
+        assert_eq!(loaded.model.faces.len(), 6);
+        assert_eq!(loaded.model.vertices[3].position, doc.model.vertices[3].position);
+        assert_eq!(loaded.properties.get(BodyId(0)), doc.properties.get(BodyId(0)));
+        assert_eq!(loaded.assembly.body_placements(), doc.assembly.body_placements());
+        assert_eq!(loaded.sketches.active().unwrap().points, doc.sketches.active().unwrap().points);
+        assert_eq!(loaded.camera, doc.camera);
+        // Feature results are recomputed on load
+        assert!(loaded.features.body(loaded.features.features[0].id).is_some());
+    }
+
+    #[test]
+    fn test_rejects_newer_version() {
+        let text = doc_with_version(PROJECT_VERSION + 1);
+        assert!(matches!(ProjectDocument::from_ron(&text), Err(ProjectError::UnsupportedVersion(_))));
+        assert!(matches!(ProjectDocument::from_ron("(nonsense"), Err(ProjectError::Parse(_))));
+    }
+
+    fn doc_with_version(version: u32) -> String {
+        ProjectDocument { version, ..Default::default() }.to_ron().unwrap()
+    }
+
+    #[test]
+    fn test_save_and_open_requests() {
+        let path = std::env::temp_dir().join(format!("xrcad_project_test_{}.xrcad", std::process::id()));
+        let mut app = App::new();
+        app.insert_resource(sample().model)
+            .insert_resource(DocumentMetadata::new("Saved"))
+            .add_event::<SaveProject>()
+            .add_event::<OpenProject>()
+            .add_systems(Update, handle_project_requests);
+        app.world_mut().send_event(SaveProject { path: path.clone() });
+        app.update();
+        app.world_mut().insert_resource(DocumentMetadata::new("Changed"));
+        app.world_mut().insert_resource(BrepModel::default());
+        app.world_mut().send_event(OpenProject { path: path.clone() });
+        app.update();
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_file(session_path(&path));
+        assert_eq!(app.world().resource::<DocumentMetadata>().title, "Saved");
+        assert_eq!(app.world().resource::<BrepModel>().faces.len(), 6);
+        assert_eq!(app.world().resource::<ProjectFile>().path.as_deref(), Some(path.as_path()));
+    }
+
+    #[test]
+    fn test_apply_resets_references_to_the_old_document() {
+        let mut world = World::new();
+        world.insert_resource(Selection { items: vec![SelectionItem::Face(40)], filter: SelectionFilter::Faces });
+        world.insert_resource(ActiveBody(Some(BodyId(7))));
+        world.insert_resource(ModelRevision(3));
+        world.insert_resource(CommandRelay { outgoing: vec![ModelCommand::DeleteBody(BodyId(7))], ..default() });
+        sample().apply(&mut world);
+        assert_eq!(*world.resource::<Selection>(), Selection { items: Vec::new(), filter: SelectionFilter::Faces });
+        assert_eq!(world.resource::<ActiveBody>().0, None);
+        assert_eq!(world.resource::<ModelRevision>().0, 4);
+        assert!(world.resource::<CommandRelay>().outgoing.is_empty());
+    }
+
+    #[test]
+    fn test_open_refused_in_shared_session() {
+        let path = std::env::temp_dir().join(format!("xrcad_project_session_test_{}.xrcad", std::process::id()));
+        sample().save(&path).unwrap();
+        let mut session = CollabSession::default();
+        session.host(0, "host").unwrap();
+        let mut app = App::new();
+        app.init_resource::<BrepModel>()
+            .insert_resource(session)
+            .add_event::<SaveProject>()
+            .add_event::<OpenProject>()
+            .add_systems(Update, handle_project_requests);
+        app.world_mut().send_event(OpenProject { path: path.clone() });
+        app.update();
+        let _ = fs::remove_file(&path);
+        assert!(app.world().resource::<BrepModel>().faces.is_empty());
+        assert!(app.world().get_resource::<ProjectFile>().is_none());
+    }
+
+    #[test]
+    fn test_with_project_extension() {
+        assert_eq!(with_project_extension("part"), PathBuf::from("part.xrcad"));
+        assert_eq!(with_project_extension("part.xrcad"), PathBuf::from("part.xrcad"));
+    }
+}
@@ -0,0 +1,180 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: io::session
+//!
+//! Per-document session state, written next to the project file as
+//! `<project>.session` whenever the project is saved and restored when it is
+//! opened: camera pose, selection, active body and sketch, and the UI layout
+//! with the active tool, which is activated again on restore.
+//! It is not part of the document, so a missing or unreadable session file
+//! never stops a project from opening.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+use crate::interaction::selection::{Selection, SelectionFilter, SelectionItem};
+use crate::interaction::state::{ActiveBody, UiLayout};
+#[cfg(feature = "render")]
+use crate::interaction::tools::{ActivateTool, ToolRegistry};
+use crate::io::project::{CameraState, ProjectError};
+use crate::model::body::BodyId;
+use crate::model::brep_model::BrepModel;
+use crate::sketch::sketch::Sketches;
+
+/// Suffix appended to the project file name
+pub const SESSION_SUFFIX: &str = "session";
+
+/// Session file belonging to a project file
+pub fn session_path(project: &Path) -> PathBuf {
+    let mut name = project.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(SESSION_SUFFIX);
+    project.with_file_name(name)
+}
+
+/// Where the user left off in a document
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionState {
+    pub camera: Option<CameraState>,
+    pub selection: Vec<SelectionItem>,
+    #[serde(default)]
+    pub selection_filter: SelectionFilter,
+    pub active_body: Option<BodyId>,
+    pub active_sketch: Option<usize>,
+    #[serde(default)]
+    pub layout: UiLayout,
+}
+
+/// True if the item still refers to topology in the model
+fn exists(model: &BrepModel, item: &SelectionItem) -> bool {
+    match *item {
+        SelectionItem::Vertex(id) => model.vertex(id).is_some(),
+        SelectionItem::Edge(id) => model.edge(id).is_some(),
+        SelectionItem::Face(id) => model.face(id).is_some(),
+        SelectionItem::Body(id) => model.has_body(id),
+    }
+}
+
+/// Name of a world's active tool
+#[cfg(feature = "render")]
+fn active_tool(world: &World) -> Option<String> {
+    world.get_resource::<ToolRegistry>()?.active().map(|t| t.name().to_string())
+}
+
+/// Ask for a tool to be active again, unless it already is
+#[cfg(feature = "render")]
+fn restore_tool(world: &mut World, name: &str) {
+    if active_tool(world).as_deref() != Some(name) && world.contains_resource::<Events<ActivateTool>>() {
+        world.send_event(ActivateTool(name.to_string()));
+    }
+}
+
+// Headless worlds have no tools
+#[cfg(not(feature = "render"))]
+fn active_tool(_world: &World) -> Option<String> {
+    None
+}
+
+#[cfg(not(feature = "render"))]
+fn restore_tool(_world: &mut World, _name: &str) {}
+
+impl SessionState {
+    pub fn to_ron(&self) -> Result<String, ProjectError> {
+        ron::ser::to_string_pretty(self, PrettyConfig::default()).map_err(|e| ProjectError::Serialize(e.to_string()))
+    }
+
+    pub fn from_ron(text: &str) -> Result<Self, ProjectError> {
+        ron::from_str(text).map_err(|e| ProjectError::Parse(e.to_string()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), ProjectError> {
+        fs::write(path, self.to_ron()?)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, ProjectError> {
+        Self::from_ron(&fs::read_to_string(path)?)
+    }
+
+    /// Snapshot the session resources and main camera of a world
+    pub fn capture(world: &mut World) -> Self {
+        let camera = CameraState::capture(world);
+        let selection = world.get_resource::<Selection>().cloned().unwrap_or_default();
+        let layout = UiLayout { active_tool: active_tool(world), ..world.get_resource::<UiLayout>().cloned().unwrap_or_default() };
+        Self {
+            camera,
+            selection: selection.items,
+            selection_filter: selection.filter,
+            active_body: world.get_resource::<ActiveBody>().and_then(|a| a.0),
+            active_sketch: world.get_resource::<Sketches>().and_then(|s| s.active),
+            layout,
+        }
+    }
+
+    /// Restore onto an opened document, dropping references it no longer has
+    pub fn apply(self, world: &mut World) {
+        if let Some(camera) = self.camera {
+            camera.apply(world);
+        }
+        let model = world.get_resource::<BrepModel>().cloned().unwrap_or_default();
+        let items = self.selection.into_iter().filter(|i| exists(&model, i)).collect();
+        world.insert_resource(Selection { items, filter: self.selection_filter });
+        world.insert_resource(ActiveBody(self.active_body.filter(|b| model.has_body(*b))));
+        if let Some(mut sketches) = world.get_resource_mut::<Sketches>() {
+            sketches.active = self.active_sketch.filter(|i| *i < sketches.sketches.len());
+        }
+        if let Some(tool) = &self.layout.active_tool {
+            restore_tool(world, tool);
+        }
+        world.insert_resource(self.layout);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::cube;
+
+    #[test]
+    fn test_session_path() {
+        assert_eq!(session_path(Path::new("/tmp/part.xrcad")), PathBuf::from("/tmp/part.xrcad.session"));
+    }
+
+    #[test]
+    fn test_capture_and_restore() {
+        let mut world = World::new();
+        world.insert_resource(cube(10.0));
+        world.insert_resource(Selection { items: vec![SelectionItem::Face(2), SelectionItem::Edge(5)], filter: SelectionFilter::Any });
+        world.insert_resource(ActiveBody(Some(BodyId(0))));
+        let mut layout = UiLayout::default();
+        layout.toggle_panel("lighting");
+        world.insert_resource(layout.clone());
+        let session = SessionState::from_ron(&SessionState::capture(&mut world).to_ron().unwrap()).unwrap();
+
+        let mut opened = World::new();
+        let mut model = cube(10.0);
+        model.faces.retain(|f| f.id != 2);
+        opened.insert_resource(model);
+        session.apply(&mut opened);
+        assert_eq!(opened.resource::<Selection>().items, vec![SelectionItem::Edge(5)]);
+        assert_eq!(opened.resource::<ActiveBody>().0, Some(BodyId(0)));
+        assert_eq!(*opened.resource::<UiLayout>(), layout);
+    }
+
+    #[cfg(feature = "render")]
+    #[test]
+    fn test_restores_active_tool() {
+        let mut session = SessionState::default();
+        session.layout.active_tool = Some("Measure".into());
+        let mut opened = World::new();
+        opened.init_resource::<Events<ActivateTool>>();
+        session.apply(&mut opened);
+        let events = opened.resource::<Events<ActivateTool>>();
+        assert_eq!(events.get_cursor().read(events).cloned().collect::<Vec<_>>(), vec![ActivateTool("Measure".into())]);
+    }
+}
@@ -0,0 +1,304 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: io::step
+//!
+//! STEP (ISO 10303-21) export using the AP214 automotive design schema, which
+//! AP203 readers also accept. Each body becomes a `MANIFOLD_SOLID_BREP` of
+//! `ADVANCED_FACE`s on `PLANE` surfaces bounded by `LINE` edges; all solids share
+//! one product in millimetres.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Write as _};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use nalgebra::{Point3, Vector3};
+
+use crate::model::brep::validate::{validate_solid, ValidationIssue};
+use crate::model::brep_model::BrepModel;
+use crate::model::metadata::DocumentMetadata;
+
+/// Schema identifier written to the file header
+pub const STEP_SCHEMA: &str = "AUTOMOTIVE_DESIGN { 1 0 10303 214 1 1 1 }";
+
+/// Why a STEP file could not be read or written
+#[derive(Debug)]
+pub enum StepError {
+    Io(io::Error),
+    /// Malformed exchange structure
+    Parse(String),
+    /// A body is not a closed, consistently oriented solid
+    InvalidSolid { body: String, issues: Vec<ValidationIssue> },
+    /// Stopped by the caller's progress callback
+    Cancelled,
+}
+
+impl fmt::Display for StepError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StepError::Io(err) => write!(f, "{}", err),
+            StepError::Parse(msg) => write!(f, "invalid STEP file: {}", msg),
+            StepError::InvalidSolid { body, issues } => {
+                write!(f, "body {:?} is not a valid solid", body)?;
+                if let Some(first) = issues.first() {
+                    write!(f, " ({})", first)?;
+                }
+                Ok(())
+            }
+            StepError::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for StepError {}
+
+impl From<io::Error> for StepError {
+    fn from(err: io::Error) -> Self {
+        StepError::Io(err)
+    }
+}
+
+/// STEP real: always has a decimal point, exponent as `E`
+fn real(x: f64) -> String {
+    let s = format!("{:?}", x);
+    match s.split_once('e') {
+        Some((mantissa, exp)) if mantissa.contains('.') => format!("{}E{}", mantissa, exp),
+        Some((mantissa, exp)) => format!("{}.E{}", mantissa, exp),
+        None if s.contains('.') => s,
+        None => format!("{}.", s),
+    }
+}
+
+/// STEP string literal: quotes doubled, non-ASCII characters in `\X2\` form
+fn string(s: &str) -> String {
+    let mut out = String::from("'");
+    for c in s.chars() {
+        match c {
+            '\'' => out.push_str("''"),
+            '\\' => out.push_str("\\\\"),
+            ' '..='~' => out.push(c),
+            _ => {
+                out.push_str("\\X2\\");
+                let mut units = [0u16; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    let _ = write!(out, "{:04X}", unit);
+                }
+                out.push_str("\\X0\\");
+            }
+        }
+    }
+    out.push('\'');
+    out
+}
+
+fn refs(ids: &[usize]) -> String {
+    ids.iter().map(|id| format!("#{}", id)).collect::<Vec<_>>().join(",")
+}
+
+/// Numbered entity instances of the DATA section
+#[derive(Default)]
+struct Entities {
+    lines: Vec<String>,
+}
+
+impl Entities {
+    fn add(&mut self, entity: String) -> usize {
+        self.lines.push(entity);
+        self.lines.len()
+    }
+
+    fn point(&mut self, p: &Point3<f64>) -> usize {
+        self.add(format!("CARTESIAN_POINT('',({},{},{}))", real(p.x), real(p.y), real(p.z)))
+    }
+
+    fn direction(&mut self, d: &Vector3<f64>) -> usize {
+        let d = d.normalize();
+        self.add(format!("DIRECTION('',({},{},{}))", real(d.x), real(d.y), real(d.z)))
+    }
+
+    /// One solid; returns the `MANIFOLD_SOLID_BREP` id
+    fn solid(&mut self, name: &str, model: &BrepModel) -> usize {
+        let mut vertex_ids = BTreeMap::new();
+        for v in &model.vertices {
+            let p = self.point(&Point3::from(v.position));
+            vertex_ids.insert(v.id, self.add(format!("VERTEX_POINT('',#{})", p)));
+        }
+        let mut edge_ids = BTreeMap::new();
+        for e in &model.edges {
+            let (Some(a), Some(b)) = (model.vertex_position(e.vertices.0), model.vertex_position(e.vertices.1)) else {
+                continue;
+            };
+            let origin = self.point(&Point3::from(a));
+            let dir = self.direction(&(b - a));
+            let vector = self.add(format!("VECTOR('',#{},{})", dir, real((b - a).norm())));
+            let line = self.add(format!("LINE('',#{},#{})", origin, vector));
+            let curve = self.add(format!(
+                "EDGE_CURVE('',#{},#{},#{},.T.)",
+                vertex_ids[&e.vertices.0], vertex_ids[&e.vertices.1], line
+            ));
+            edge_ids.insert(e.id, curve);
+        }
+        let mut faces = Vec::new();
+        for face in &model.faces {
+            let Some(plane) = model.face_plane(face) else { continue; };
+            let mut bounds = Vec::new();
+            for (i, edge_loop) in model.face_loops(face).into_iter().enumerate() {
+                let starts = model.loop_vertex_ids(edge_loop);
+                let oriented: Vec<usize> = edge_loop
+                    .edges
+                    .iter()
+                    .flatten()
+                    .zip(starts)
+                    .filter_map(|(edge, start)| {
+                        let forward = model.edge(*edge)?.vertices.0 == start;
+                        let sense = if forward { ".T." } else { ".F." };
+                        Some(self.add(format!("ORIENTED_EDGE('',*,*,#{},{})", edge_ids.get(edge)?, sense)))
+                    })
+                    .collect();
+                let lp = self.add(format!("EDGE_LOOP('',({}))", refs(&oriented)));
+                let kind = if i == 0 { "FACE_OUTER_BOUND" } else { "FACE_BOUND" };
+                bounds.push(self.add(format!("{}('',#{},.T.)", kind, lp)));
+            }
+            let outer = model.face_loops(face).first().map(|l| model.loop_positions(l)).unwrap_or_default();
+            let Some(first) = outer.first() else { continue; };
+            let (u, _) = plane.in_plane_axes();
+            let location = self.point(&Point3::from(*first));
+            let axis = self.direction(&plane.normal);
+            let ref_dir = self.direction(&u);
+            let placement = self.add(format!("AXIS2_PLACEMENT_3D('',#{},#{},#{})", location, axis, ref_dir));
+            let surface = self.add(format!("PLANE('',#{})", placement));
+            faces.push(self.add(format!("ADVANCED_FACE('',({}),#{},.T.)", refs(&bounds), surface)));
+        }
+        let shell = self.add(format!("CLOSED_SHELL('',({}))", refs(&faces)));
+        self.add(format!("MANIFOLD_SOLID_BREP({},#{})", string(name), shell))
+    }
+}
+
+/// Write named solid bodies as a STEP file; every body must pass `validate_solid`
+pub fn write_step(metadata: &DocumentMetadata, bodies: &[(&str, &BrepModel)]) -> Result<String, StepError> {
+    for (name, model) in bodies {
+        let issues = validate_solid(model);
+        if !issues.is_empty() {
+            return Err(StepError::InvalidSolid { body: name.to_string(), issues });
+        }
+    }
+
+    let mut e = Entities::default();
+    let app = e.add(format!("APPLICATION_CONTEXT({})", string("core data for automotive mechanical design processes")));
+    e.add(format!("APPLICATION_PROTOCOL_DEFINITION('international standard','automotive_design',2000,#{})", app));
+    let product_context = e.add(format!("PRODUCT_CONTEXT('',#{},'mechanical')", app));
+    let title = string(&metadata.title);
+    let product = e.add(format!("PRODUCT({},{},'',(#{}))", title, title, product_context));
+    e.add(format!("PRODUCT_RELATED_PRODUCT_CATEGORY('part',$,(#{}))", product));
+    let formation = e.add(format!("PRODUCT_DEFINITION_FORMATION({},'',#{})", string(&metadata.revision), product));
+    let definition_context = e.add(format!("PRODUCT_DEFINITION_CONTEXT('part definition',#{},'design')", app));
+    let definition = e.add(format!("PRODUCT_DEFINITION('design','',#{},#{})", formation, definition_context));
+    let shape = e.add(format!("PRODUCT_DEFINITION_SHAPE('','',#{})", definition));
+
+    let length = e.add("(LENGTH_UNIT()NAMED_UNIT(*)SI_UNIT(.MILLI.,.METRE.))".to_string());
+    let angle = e.add("(NAMED_UNIT(*)PLANE_ANGLE_UNIT()SI_UNIT($,.RADIAN.))".to_string());
+    let solid_angle = e.add("(NAMED_UNIT(*)SI_UNIT($,.STERADIAN.)SOLID_ANGLE_UNIT())".to_string());
+    let accuracy = bodies.first().map(|(_, m)| m.tolerance.linear).unwrap_or(1e-6);
+    let uncertainty = e.add(format!(
+        "UNCERTAINTY_MEASURE_WITH_UNIT(LENGTH_MEASURE({}),#{},'distance_accuracy_value','confusion accuracy')",
+        real(accuracy),
+        length
+    ));
+    let context = e.add(format!(
+        "(GEOMETRIC_REPRESENTATION_CONTEXT(3)GLOBAL_UNCERTAINTY_ASSIGNED_CONTEXT((#{}))\
+         GLOBAL_UNIT_ASSIGNED_CONTEXT((#{},#{},#{}))REPRESENTATION_CONTEXT('',''))",
+        uncertainty, length, angle, solid_angle
+    ));
+
+    let origin = e.point(&Point3::origin());
+    let z = e.direction(&Vector3::z());
+    let x = e.direction(&Vector3::x());
+    let world = e.add(format!("AXIS2_PLACEMENT_3D('',#{},#{},#{})", origin, z, x));
+    let mut items = vec![world];
+    for (name, model) in bodies {
+        items.push(e.solid(name, model));
+    }
+    let representation = e.add(format!("ADVANCED_BREP_SHAPE_REPRESENTATION('',({}),#{})", refs(&items), context));
+    e.add(format!("SHAPE_DEFINITION_REPRESENTATION(#{},#{})", shape, representation));
+
+    let mut out = String::new();
+    out.push_str("ISO-10303-21;\nHEADER;\n");
+    let _ = writeln!(out, "FILE_DESCRIPTION(('xrcad model'),'2;1');");
+    let _ = writeln!(
+        out,
+        "FILE_NAME({},{},({}),(''),'xrcad','xrcad','');",
+        string(&metadata.title),
+        string(&metadata.date),
+        string(&metadata.author)
+    );
+    let _ = writeln!(out, "FILE_SCHEMA(('{}'));", STEP_SCHEMA);
+    out.push_str("ENDSEC;\nDATA;\n");
+    for (i, line) in e.lines.iter().enumerate() {
+        let _ = writeln!(out, "#{}={};", i + 1, line);
+    }
+    out.push_str("ENDSEC;\nEND-ISO-10303-21;\n");
+    Ok(out)
+}
+
+/// Write named solid bodies to a `.step` file
+pub fn save_step(path: &Path, metadata: &DocumentMetadata, bodies: &[(&str, &BrepModel)]) -> Result<(), StepError> {
+    fs::write(path, write_step(metadata, bodies)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::{cube, cylinder};
+
+    fn count(text: &str, entity: &str) -> usize {
+        text.matches(&format!("={}(", entity)).count()
+    }
+
+    #[test]
+    fn test_cube_entities() {
+        let text = write_step(&DocumentMetadata::new("Cube"), &[("Cube", &cube(10.0))]).unwrap();
+        assert!(text.starts_with("ISO-10303-21;"));
+        assert!(text.trim_end().ends_with("END-ISO-10303-21;"));
+        assert_eq!(count(&text, "MANIFOLD_SOLID_BREP"), 1);
+        assert_eq!(count(&text, "ADVANCED_FACE"), 6);
+        assert_eq!(count(&text, "PLANE"), 6);
+        assert_eq!(count(&text, "EDGE_CURVE"), 12);
+        assert_eq!(count(&text, "VERTEX_POINT"), 8);
+        assert_eq!(count(&text, "ORIENTED_EDGE"), 24);
+
+        // Every reference points at a defined instance
+        let defined = text.lines().filter(|l| l.starts_with('#')).count();
+        for id in text.split('#').skip(1).filter_map(|s| {
+            s.split(|c: char| !c.is_ascii_digit()).next().and_then(|n| n.parse::<usize>().ok())
+        }) {
+            assert!(id >= 1 && id <= defined, "dangling #{}", id);
+        }
+    }
+
+    #[test]
+    fn test_each_edge_used_once_in_each_direction() {
+        let text = write_step(&DocumentMetadata::default(), &[("Cylinder", &cylinder(2.0, 5.0, 8))]).unwrap();
+        assert_eq!(count(&text, "ADVANCED_FACE"), 10);
+        let oriented: Vec<&str> = text.lines().filter(|l| l.contains("=ORIENTED_EDGE(")).collect();
+        let forward = oriented.iter().filter(|l| l.ends_with(".T.);")).count();
+        assert_eq!(oriented.len(), count(&text, "EDGE_CURVE") * 2);
+        assert_eq!(forward * 2, oriented.len());
+    }
+
+    #[test]
+    fn test_rejects_open_shell_and_formats_literals() {
+        let mut sheet = BrepModel::new();
+        sheet.add_face(&[Vector3::zeros(), Vector3::x(), Vector3::y()]);
+        assert!(matches!(
+            write_step(&DocumentMetadata::default(), &[("Sheet", &sheet)]),
+            Err(StepError::InvalidSolid { .. })
+        ));
+        assert_eq!(real(1.0), "1.0");
+        assert_eq!(real(1e-6), "1.E-6");
+        assert_eq!(real(-2.5e20), "-2.5E20");
+        assert_eq!(string("Bob's ø"), "'Bob''s \\X2\\00F8\\X0\\'");
+    }
+}
@@ -0,0 +1,873 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: io::step_import
+//!
+//! STEP (ISO 10303-21) import for the subset of AP203/AP214 geometry the kernel
+//! can represent: solids bounded by planar and cylindrical faces whose edges are
+//! lines and circles. Circles are faceted and cylinders become bands of planar
+//! quads, so neighbouring faces share vertices. Anything else is left out and
+//! listed in the import report.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use nalgebra::{Point3, Vector3};
+
+use crate::io::step::StepError;
+use crate::model::brep::topology::plane::Plane;
+use crate::model::brep::validate::validate_solid;
+use crate::model::brep_model::{area_vector, BrepModel};
+use crate::model::tolerance::Tolerance;
+
+/// Segments used for a full circle; arcs get a proportional share
+pub const CIRCLE_SEGMENTS: usize = 32;
+
+/// Parameter of an entity instance
+#[derive(Debug, Clone, PartialEq)]
+enum Param {
+    Ref(usize),
+    Number(f64),
+    Str(String),
+    Enum(String),
+    List(Vec<Param>),
+    /// Typed value such as `LENGTH_MEASURE(1.)`; the type name is not kept
+    Typed(Vec<Param>),
+    /// `$` (unset) or `*` (derived)
+    Unset,
+}
+
+impl Param {
+    fn reference(&self) -> Option<usize> {
+        match self {
+            Param::Ref(id) => Some(*id),
+            _ => None,
+        }
+    }
+    fn number(&self) -> Option<f64> {
+        match self {
+            Param::Number(x) => Some(*x),
+            Param::Typed(inner) => inner.first()?.number(),
+            _ => None,
+        }
+    }
+    fn list(&self) -> Option<&[Param]> {
+        match self {
+            Param::List(items) => Some(items),
+            _ => None,
+        }
+    }
+    fn flag(&self) -> Option<bool> {
+        match self {
+            Param::Enum(e) if e == "T" => Some(true),
+            Param::Enum(e) if e == "F" => Some(false),
+            _ => None,
+        }
+    }
+    fn text(&self) -> Option<&str> {
+        match self {
+            Param::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+/// `NAME(params)`; complex instances are a list of these
+#[derive(Debug, Clone, PartialEq)]
+struct Record {
+    name: String,
+    params: Vec<Param>,
+}
+
+impl Record {
+    fn param(&self, index: usize) -> Option<&Param> {
+        self.params.get(index)
+    }
+}
+
+type Instances = BTreeMap<usize, Vec<Record>>;
+
+/// Decode a STEP string literal body: doubled quotes, `\\` and `\X2\…\X0\` runs
+fn decode_string(raw: &str) -> String {
+    let mut out = String::new();
+    let mut rest = raw;
+    while let Some(i) = rest.find(['\'', '\\']) {
+        out.push_str(&rest[..i]);
+        rest = &rest[i..];
+        if let Some(tail) = rest.strip_prefix("''") {
+            out.push('\'');
+            rest = tail;
+        } else if let Some(tail) = rest.strip_prefix("\\\\") {
+            out.push('\\');
+            rest = tail;
+        } else if let Some(tail) = rest.strip_prefix("\\X2\\") {
+            let end = tail.find("\\X0\\").unwrap_or(tail.len());
+            let units: Vec<u16> = tail.as_bytes()[..end]
+                .chunks(4)
+                .filter_map(|c| u16::from_str_radix(std::str::from_utf8(c).ok()?, 16).ok())
+                .collect();
+            out.push_str(&String::from_utf16_lossy(&units));
+            rest = tail.get(end + 4..).unwrap_or("");
+        } else {
+            out.push_str(&rest[..1]);
+            rest = &rest[1..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Recursive-descent reader for the DATA section
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, message: &str) -> StepError {
+        StepError::Parse(format!("{} at offset {}", message, self.pos))
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        let rest = &self.src[self.pos..];
+        let trimmed = rest.trim_start();
+        self.pos += rest.len() - trimmed.len();
+        trimmed.chars().next()
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), StepError> {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", c)))
+        }
+    }
+
+    fn take_while(&mut self, pred: impl Fn(char) -> bool) -> &'a str {
+        let rest = &self.src[self.pos..];
+        let end = rest.find(|c: char| !pred(c)).unwrap_or(rest.len());
+        self.pos += end;
+        &rest[..end]
+    }
+
+    fn keyword(&mut self) -> Result<String, StepError> {
+        self.peek();
+        let word = self.take_while(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if word.is_empty() {
+            return Err(self.error("expected keyword"));
+        }
+        Ok(word.to_ascii_uppercase())
+    }
+
+    fn id(&mut self) -> Result<usize, StepError> {
+        self.expect('#')?;
+        self.take_while(|c| c.is_ascii_digit()).parse().map_err(|_| self.error("invalid instance id"))
+    }
+
+    fn params(&mut self) -> Result<Vec<Param>, StepError> {
+        self.expect('(')?;
+        let mut out = Vec::new();
+        if self.peek() == Some(')') {
+            self.pos += 1;
+            return Ok(out);
+        }
+        loop {
+            out.push(self.param()?);
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(')') => {
+                    self.pos += 1;
+                    return Ok(out);
+                }
+                _ => return Err(self.error("expected ',' or ')'")),
+            }
+        }
+    }
+
+    fn param(&mut self) -> Result<Param, StepError> {
+        match self.peek().ok_or_else(|| self.error("unexpected end of file"))? {
+            '$' | '*' => {
+                self.pos += 1;
+                Ok(Param::Unset)
+            }
+            '#' => Ok(Param::Ref(self.id()?)),
+            '(' => Ok(Param::List(self.params()?)),
+            '.' => {
+                self.pos += 1;
+                let value = self.take_while(|c| c != '.').to_ascii_uppercase();
+                self.expect('.')?;
+                Ok(Param::Enum(value))
+            }
+            '\'' => {
+                self.pos += 1;
+                let rest = &self.src[self.pos..];
+                let mut end = 0;
+                loop {
+                    let close = rest[end..].find('\'').ok_or_else(|| self.error("unterminated string"))? + end;
+                    if rest[close + 1..].starts_with('\'') {
+                        end = close + 2;
+                    } else {
+                        end = close;
+                        break;
+                    }
+                }
+                self.pos += end + 1;
+                Ok(Param::Str(decode_string(&rest[..end])))
+            }
+            c if c == '-' || c == '+' || c.is_ascii_digit() => {
+                let text = self.take_while(|c| c.is_ascii_digit() || matches!(c, '+' | '-' | '.' | 'E' | 'e'));
+                text.parse().map(Param::Number).map_err(|_| self.error("invalid number"))
+            }
+            _ => {
+                self.keyword()?;
+                Ok(Param::Typed(self.params()?))
+            }
+        }
+    }
+
+    /// `#id = NAME(...);` or `#id = (NAME(...) NAME(...));`
+    fn instance(&mut self) -> Result<(usize, Vec<Record>), StepError> {
+        let id = self.id()?;
+        self.expect('=')?;
+        let mut records = Vec::new();
+        if self.peek() == Some('(') {
+            self.pos += 1;
+            while self.peek() != Some(')') {
+                let name = self.keyword()?;
+                records.push(Record { name, params: self.params()? });
+            }
+            self.pos += 1;
+        } else {
+            let name = self.keyword()?;
+            records.push(Record { name, params: self.params()? });
+        }
+        self.expect(';')?;
+        Ok((id, records))
+    }
+}
+
+/// Remove `/* … */` comments outside string literals
+fn strip_comments(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_string = false;
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if !in_string && rest.starts_with("/*") {
+            rest = rest.find("*/").map(|i| &rest[i + 2..]).unwrap_or("");
+            continue;
+        }
+        if c == '\'' {
+            in_string = !in_string;
+        }
+        out.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    out
+}
+
+fn parse_instances(text: &str) -> Result<Instances, StepError> {
+    let text = strip_comments(text);
+    let start = text.find("DATA;").ok_or_else(|| StepError::Parse("missing DATA section".into()))?;
+    let mut parser = Parser { src: &text, pos: start + "DATA;".len() };
+    let mut instances = Instances::new();
+    while parser.peek() == Some('#') {
+        let (id, records) = parser.instance()?;
+        instances.insert(id, records);
+    }
+    if !parser.src[parser.pos..].starts_with("ENDSEC") {
+        return Err(parser.error("expected ENDSEC"));
+    }
+    Ok(instances)
+}
+
+/// What an import produced and what it had to leave out
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportReport {
+    pub solids: usize,
+    /// STEP faces imported (a cylindrical face counts once, however many facets it becomes)
+    pub faces: usize,
+    pub skipped_faces: usize,
+    /// Unsupported entity types met while reading, with how often each occurred
+    pub unsupported: BTreeMap<String, usize>,
+    /// Other problems: dangling references, malformed entities, invalid solids
+    pub warnings: Vec<String>,
+}
+
+impl ImportReport {
+    /// True if everything in the file was imported without problems
+    pub fn is_complete(&self) -> bool {
+        self.skipped_faces == 0 && self.unsupported.is_empty() && self.warnings.is_empty()
+    }
+}
+
+impl fmt::Display for ImportReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} solid(s), {} face(s) imported", self.solids, self.faces)?;
+        if self.skipped_faces > 0 {
+            write!(f, ", {} face(s) skipped", self.skipped_faces)?;
+        }
+        if !self.unsupported.is_empty() {
+            let list: Vec<String> = self.unsupported.iter().map(|(name, n)| format!("{} ({})", name, n)).collect();
+            write!(f, "; unsupported: {}", list.join(", "))?;
+        }
+        for warning in &self.warnings {
+            write!(f, "; {}", warning)?;
+        }
+        Ok(())
+    }
+}
+
+/// Solids read from a STEP file, in file order, with the import report
+#[derive(Debug, Clone, Default)]
+pub struct StepImport {
+    pub solids: Vec<(String, BrepModel)>,
+    pub report: ImportReport,
+}
+
+/// Why part of the file was left out
+enum Skip {
+    Unsupported(String),
+    Invalid(String),
+}
+
+type Mapped<T> = Result<T, Skip>;
+
+/// Face bound: whether it is the outer bound, and its oriented edge chains
+type Bound = (bool, Vec<Vec<Vector3<f64>>>);
+
+/// Millimetres per unit of the file's length unit
+fn length_scale(instances: &Instances) -> f64 {
+    for records in instances.values() {
+        if !records.iter().any(|r| r.name == "LENGTH_UNIT") {
+            continue;
+        }
+        if let Some(si) = records.iter().find(|r| r.name == "SI_UNIT") {
+            return match si.param(0) {
+                Some(Param::Enum(prefix)) => match prefix.as_str() {
+                    "KILO" => 1e6,
+                    "DECI" => 100.0,
+                    "CENTI" => 10.0,
+                    "MICRO" => 1e-3,
+                    "NANO" => 1e-6,
+                    _ => 1.0,
+                },
+                _ => 1000.0,
+            };
+        }
+        if let Some(unit) = records.iter().find(|r| r.name == "CONVERSION_BASED_UNIT") {
+            return match unit.param(0).and_then(Param::text).map(str::to_ascii_uppercase).as_deref() {
+                Some("INCH") => 25.4,
+                Some("FOOT") => 304.8,
+                _ => 1.0,
+            };
+        }
+    }
+    1.0
+}
+
+/// Maps entity instances onto kernel topology
+struct Reader<'a> {
+    instances: &'a Instances,
+    scale: f64,
+    tolerance: Tolerance,
+    /// Faceted edges in their own direction, by `EDGE_CURVE` id
+    edges: BTreeMap<usize, Vec<Vector3<f64>>>,
+}
+
+impl<'a> Reader<'a> {
+    fn record(&self, id: usize) -> Mapped<&'a Record> {
+        match self.instances.get(&id).map(Vec::as_slice) {
+            Some([record]) => Ok(record),
+            Some([first, ..]) => Err(Skip::Unsupported(first.name.clone())),
+            _ => Err(Skip::Invalid(format!("dangling reference #{}", id))),
+        }
+    }
+
+    fn reference(&self, record: &Record, index: usize) -> Mapped<&'a Record> {
+        let id = record.param(index).and_then(Param::reference).ok_or_else(|| malformed(record))?;
+        self.record(id)
+    }
+
+    fn point(&self, record: &Record) -> Mapped<Vector3<f64>> {
+        let coords = match record.name.as_str() {
+            "CARTESIAN_POINT" => record.param(1).and_then(Param::list).ok_or_else(|| malformed(record))?,
+            "VERTEX_POINT" => return self.point(self.reference(record, 1)?),
+            other => return Err(Skip::Unsupported(other.to_string())),
+        };
+        let c: Vec<f64> = coords.iter().filter_map(Param::number).collect();
+        match c.as_slice() {
+            [x, y, z] => Ok(Vector3::new(*x, *y, *z) * self.scale),
+            [x, y] => Ok(Vector3::new(*x, *y, 0.0) * self.scale),
+            _ => Err(malformed(record)),
+        }
+    }
+
+    fn direction(&self, record: &Record) -> Mapped<Vector3<f64>> {
+        let c: Vec<f64> = record
+            .param(1)
+            .and_then(Param::list)
+            .ok_or_else(|| malformed(record))?
+            .iter()
+            .filter_map(Param::number)
+            .collect();
+        match c.as_slice() {
+            [x, y, z] if record.name == "DIRECTION" => Ok(Vector3::new(*x, *y, *z).normalize()),
+            _ => Err(malformed(record)),
+        }
+    }
+
+    /// Origin, unit axis and unit reference direction of an `AXIS2_PLACEMENT_3D`
+    fn placement(&self, record: &Record) -> Mapped<(Vector3<f64>, Vector3<f64>, Vector3<f64>)> {
+        if record.name != "AXIS2_PLACEMENT_3D" {
+            return Err(Skip::Unsupported(record.name.clone()));
+        }
+        let origin = self.point(self.reference(record, 1)?)?;
+        let axis = match record.param(2) {
+            Some(Param::Ref(id)) => self.direction(self.record(*id)?)?,
+            _ => Vector3::z(),
+        };
+        let hint = match record.param(3) {
+            Some(Param::Ref(id)) => self.direction(self.record(*id)?)?,
+            _ => Vector3::x(),
+        };
+        let x = hint - axis * hint.dot(&axis);
+        let x = if self.tolerance.is_zero_length(x.norm()) {
+            Plane::from_point_normal(Point3::from(origin), axis, None).in_plane_axes().0
+        } else {
+            x.normalize()
+        };
+        Ok((origin, axis, x))
+    }
+
+    /// Points along a curve from `start` to `end`, against the curve's direction if `!same_sense`
+    fn curve(&self, record: &Record, start: Vector3<f64>, end: Vector3<f64>, same_sense: bool) -> Mapped<Vec<Vector3<f64>>> {
+        match record.name.as_str() {
+            "LINE" | "POLYLINE" => Ok(vec![start, end]),
+            "SURFACE_CURVE" | "SEAM_CURVE" => self.curve(self.reference(record, 1)?, start, end, same_sense),
+            "CIRCLE" => {
+                let (center, axis, x) = self.placement(self.reference(record, 1)?)?;
+                let radius = record.param(2).and_then(Param::number).ok_or_else(|| malformed(record))? * self.scale;
+                let y = axis.cross(&x);
+                let angle = |p: Vector3<f64>| (p - center).dot(&y).atan2((p - center).dot(&x));
+                let tau = std::f64::consts::TAU;
+                let mut sweep = (angle(end) - angle(start)).rem_euclid(tau);
+                if !same_sense {
+                    sweep -= tau;
+                }
+                if self.tolerance.coincident(&start, &end) {
+                    sweep = if same_sense { tau } else { -tau };
+                }
+                let steps = ((CIRCLE_SEGMENTS as f64 * sweep.abs() / tau).ceil() as usize).max(1);
+                let a0 = angle(start);
+                let mut points: Vec<Vector3<f64>> = (0..=steps)
+                    .map(|i| {
+                        let a = a0 + sweep * i as f64 / steps as f64;
+                        center + (x * a.cos() + y * a.sin()) * radius
+                    })
+                    .collect();
+                points[0] = start;
+                points[steps] = end;
+                Ok(points)
+            }
+            other => Err(Skip::Unsupported(other.to_string())),
+        }
+    }
+
+    /// Faceted `EDGE_CURVE`, from its start vertex to its end vertex
+    fn edge(&mut self, id: usize) -> Mapped<Vec<Vector3<f64>>> {
+        if let Some(points) = self.edges.get(&id) {
+            return Ok(points.clone());
+        }
+        let record = self.record(id)?;
+        if record.name != "EDGE_CURVE" {
+            return Err(Skip::Unsupported(record.name.clone()));
+        }
+        let start = self.point(self.reference(record, 1)?)?;
+        let end = self.point(self.reference(record, 2)?)?;
+        let same_sense = record.param(4).and_then(Param::flag).unwrap_or(true);
+        let points = self.curve(self.reference(record, 3)?, start, end, same_sense)?;
+        self.edges.insert(id, points.clone());
+        Ok(points)
+    }
+
+    /// Oriented edge chains of a loop, in traversal order
+    fn edge_loop(&mut self, record: &Record) -> Mapped<Vec<Vec<Vector3<f64>>>> {
+        let items = record.param(1).and_then(Param::list).ok_or_else(|| malformed(record))?;
+        match record.name.as_str() {
+            "EDGE_LOOP" => items
+                .iter()
+                .map(|item| {
+                    let oriented = self.record(item.reference().ok_or_else(|| malformed(record))?)?;
+                    if oriented.name != "ORIENTED_EDGE" {
+                        return Err(Skip::Unsupported(oriented.name.clone()));
+                    }
+                    let edge = oriented.param(3).and_then(Param::reference).ok_or_else(|| malformed(oriented))?;
+                    let mut points = self.edge(edge)?;
+                    if oriented.param(4).and_then(Param::flag) == Some(false) {
+                        points.reverse();
+                    }
+                    Ok(points)
+                })
+                .collect(),
+            "POLY_LOOP" => {
+                let corners = items
+                    .iter()
+                    .map(|item| self.point(self.record(item.reference().ok_or_else(|| malformed(record))?)?))
+                    .collect::<Mapped<Vec<_>>>()?;
+                Ok((0..corners.len()).map(|i| vec![corners[i], corners[(i + 1) % corners.len()]]).collect())
+            }
+            other => Err(Skip::Unsupported(other.to_string())),
+        }
+    }
+
+    /// Bounds of a face as (is outer bound, oriented edge chains)
+    fn bounds(&mut self, face: &Record) -> Mapped<Vec<Bound>> {
+        let items = face.param(1).and_then(Param::list).ok_or_else(|| malformed(face))?;
+        let mut out = Vec::new();
+        for item in items {
+            let bound = self.record(item.reference().ok_or_else(|| malformed(face))?)?;
+            let outer = match bound.name.as_str() {
+                "FACE_OUTER_BOUND" => true,
+                "FACE_BOUND" => false,
+                other => return Err(Skip::Unsupported(other.to_string())),
+            };
+            let lp = self.reference(bound, 1)?;
+            if lp.name == "VERTEX_LOOP" {
+                continue;
+            }
+            let mut chains = self.edge_loop(lp)?;
+            if bound.param(2).and_then(Param::flag) == Some(false) {
+                chains.reverse();
+                chains.iter_mut().for_each(|c| c.reverse());
+            }
+            out.push((outer, chains));
+        }
+        Ok(out)
+    }
+
+    /// Add one `ADVANCED_FACE` / `FACE_SURFACE` to the model
+    fn face(&mut self, record: &Record, model: &mut BrepModel) -> Mapped<()> {
+        if !matches!(record.name.as_str(), "ADVANCED_FACE" | "FACE_SURFACE") {
+            return Err(Skip::Unsupported(record.name.clone()));
+        }
+        let surface = self.reference(record, 2)?;
+        let bounds = self.bounds(record)?;
+        match surface.name.as_str() {
+            "PLANE" => self.planar_face(record, bounds, model),
+            "CYLINDRICAL_SURFACE" => {
+                let (origin, axis, _) = self.placement(self.reference(surface, 1)?)?;
+                self.cylindrical_face(record, bounds, origin, axis, model)
+            }
+            other => Err(Skip::Unsupported(other.to_string())),
+        }
+    }
+
+    fn planar_face(&self, record: &Record, bounds: Vec<Bound>, model: &mut BrepModel) -> Mapped<()> {
+        let mut polygons: Vec<(bool, Vec<Vector3<f64>>)> = bounds
+            .into_iter()
+            .map(|(outer, chains)| {
+                let mut points: Vec<Vector3<f64>> = Vec::new();
+                for p in chains.iter().flat_map(|c| &c[..c.len().saturating_sub(1)]) {
+                    if points.last().is_none_or(|last| !self.tolerance.coincident(last, p)) {
+                        points.push(*p);
+                    }
+                }
+                if points.len() > 1 && self.tolerance.coincident(&points[0], &points[points.len() - 1]) {
+                    points.pop();
+                }
+                (outer, points)
+            })
+            .filter(|(_, points)| points.len() >= 3)
+            .collect();
+        let outer = polygons.iter().position(|(outer, _)| *outer).or_else(|| {
+            (0..polygons.len()).max_by(|a, b| {
+                let area = |i: usize| area_vector(&polygons[i].1).norm();
+                area(*a).total_cmp(&area(*b))
+            })
+        });
+        let Some(outer) = outer else { return Err(malformed(record)); };
+        let (_, outer) = polygons.remove(outer);
+        let holes: Vec<Vec<Vector3<f64>>> = polygons.into_iter().map(|(_, p)| p).collect();
+        model.add_face_with_holes(&outer, &holes);
+        Ok(())
+    }
+
+    /// Facet a cylindrical band between its bottom and top edges into planar quads
+    fn cylindrical_face(
+        &self,
+        record: &Record,
+        bounds: Vec<Bound>,
+        origin: Vector3<f64>,
+        axis: Vector3<f64>,
+        model: &mut BrepModel,
+    ) -> Mapped<()> {
+        let chains: Vec<Vec<Vector3<f64>>> = bounds.into_iter().flat_map(|(_, c)| c).collect();
+        let height = |p: &Vector3<f64>| (p - origin).dot(&axis);
+        let heights = chains.iter().flatten().map(height);
+        let (h0, h1) = heights.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), h| (lo.min(h), hi.max(h)));
+        let at = |chain: &Vec<Vector3<f64>>, h: f64| chain.iter().all(|p| self.tolerance.is_zero_length(height(p) - h));
+        let join = |level: f64| {
+            let mut points: Vec<Vector3<f64>> = Vec::new();
+            for p in chains.iter().filter(|c| at(c, level)).flatten() {
+                if points.last().is_none_or(|last| !self.tolerance.coincident(last, p)) {
+                    points.push(*p);
+                }
+            }
+            points
+        };
+        let bottom = join(h0);
+        let mut top = join(h1);
+        top.reverse();
+        let closed = |p: &Vec<Vector3<f64>>| p.len() > 2 && self.tolerance.coincident(&p[0], &p[p.len() - 1]);
+        if closed(&bottom) && closed(&top) {
+            // Separate circular bounds: start the top where the bottom starts
+            top.pop();
+            let radial = |p: &Vector3<f64>| (p - origin) - axis * height(p);
+            let start = radial(&bottom[0]);
+            let shift = (0..top.len())
+                .max_by(|a, b| radial(&top[*a]).dot(&start).total_cmp(&radial(&top[*b]).dot(&start)))
+                .unwrap_or(0);
+            top.rotate_left(shift);
+            top.push(top[0]);
+        }
+        if bottom.len() < 2 || bottom.len() != top.len() || self.tolerance.is_zero_length(h1 - h0) {
+            return Err(Skip::Invalid(format!("cylindrical face {} is not a simple band", describe(record))));
+        }
+        for i in 0..bottom.len() - 1 {
+            if !self.tolerance.coincident(&bottom[i], &bottom[i + 1]) {
+                model.add_face(&[bottom[i], bottom[i + 1], top[i + 1], top[i]]);
+            }
+        }
+        Ok(())
+    }
+
+    /// One solid from a `MANIFOLD_SOLID_BREP`, `FACETED_BREP` or the outer shell of a `BREP_WITH_VOIDS`
+    fn solid(&mut self, record: &Record, report: &mut ImportReport) -> Mapped<BrepModel> {
+        let shell = self.reference(record, 1)?;
+        if shell.name != "CLOSED_SHELL" {
+            return Err(Skip::Unsupported(shell.name.clone()));
+        }
+        let faces = shell.param(1).and_then(Param::list).ok_or_else(|| malformed(shell))?;
+        let mut model = BrepModel { tolerance: self.tolerance, ..Default::default() };
+        for face in faces {
+            let result = match face.reference() {
+                Some(id) => self.record(id).and_then(|f| self.face(f, &mut model)),
+                None => Err(malformed(shell)),
+            };
+            match result {
+                Ok(()) => report.faces += 1,
+                Err(skip) => {
+                    report.skipped_faces += 1;
+                    note(report, skip);
+                }
+            }
+        }
+        if record.name == "BREP_WITH_VOIDS" {
+            *report.unsupported.entry("BREP_WITH_VOIDS (voids)".into()).or_default() += 1;
+        }
+        Ok(model)
+    }
+}
+
+fn describe(record: &Record) -> String {
+    match record.param(0).and_then(Param::text) {
+        Some(name) if !name.is_empty() => format!("{} {:?}", record.name, name),
+        _ => record.name.clone(),
+    }
+}
+
+fn malformed(record: &Record) -> Skip {
+    Skip::Invalid(format!("malformed {}", describe(record)))
+}
+
+fn note(report: &mut ImportReport, skip: Skip) {
+    match skip {
+        Skip::Unsupported(name) => *report.unsupported.entry(name).or_default() += 1,
+        Skip::Invalid(message) => report.warnings.push(message),
+    }
+}
+
+/// Shape containers read as solids
+const SOLID_SHAPES: &[&str] = &["MANIFOLD_SOLID_BREP", "FACETED_BREP", "BREP_WITH_VOIDS"];
+
+/// Shape containers that hold geometry the kernel cannot import as solids
+const UNSUPPORTED_SHAPES: &[&str] = &["SHELL_BASED_SURFACE_MODEL", "GEOMETRIC_CURVE_SET", "GEOMETRIC_SET"];
+
+/// Read the solids of a STEP file; coordinates are converted to millimetres
+pub fn read_step(text: &str) -> Result<StepImport, StepError> {
+    read_step_with(text, |_| true)
+}
+
+/// `read_step`, calling `progress` with the fraction of solids read after each
+/// one; it stops with `StepError::Cancelled` when `progress` returns false
+pub fn read_step_with(text: &str, mut progress: impl FnMut(f32) -> bool) -> Result<StepImport, StepError> {
+    let instances = parse_instances(text)?;
+    let mut reader = Reader {
+        instances: &instances,
+        scale: length_scale(&instances),
+        tolerance: Tolerance::default(),
+        edges: BTreeMap::new(),
+    };
+    let mut import = StepImport::default();
+    let total = instances.values().filter(|r| matches!(r.as_slice(), [r] if SOLID_SHAPES.contains(&r.name.as_str()))).count();
+    let mut read = 0;
+    for records in instances.values() {
+        let [record] = records.as_slice() else { continue; };
+        match record.name.as_str() {
+            name if SOLID_SHAPES.contains(&name) => {}
+            name if UNSUPPORTED_SHAPES.contains(&name) => {
+                *import.report.unsupported.entry(name.to_string()).or_default() += 1;
+                continue;
+            }
+            _ => continue,
+        }
+        if !progress(read as f32 / total as f32) {
+            return Err(StepError::Cancelled);
+        }
+        read += 1;
+        match reader.solid(record, &mut import.report) {
+            Ok(model) => {
+                let name = match record.param(0).and_then(Param::text) {
+                    Some(name) if !name.is_empty() => name.to_string(),
+                    _ => format!("Solid.{:03}", import.solids.len() + 1),
+                };
+                if let Some(issue) = validate_solid(&model).first() {
+                    import.report.warnings.push(format!("solid {:?} is not closed: {}", name, issue));
+                }
+                import.report.solids += 1;
+                import.solids.push((name, model));
+            }
+            Err(skip) => note(&mut import.report, skip),
+        }
+    }
+    Ok(import)
+}
+
+/// Read the solids of a `.step` / `.stp` file
+pub fn load_step(path: &Path) -> Result<StepImport, StepError> {
+    read_step(&fs::read_to_string(path)?)
+}
+
+/// `load_step` with progress and cancellation, as in `read_step_with`
+pub fn load_step_with(path: &Path, progress: impl FnMut(f32) -> bool) -> Result<StepImport, StepError> {
+    read_step_with(&fs::read_to_string(path)?, progress)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::step::write_step;
+    use crate::measure::mass_properties::mass_properties;
+    use crate::model::brep::primitives::cube;
+    use crate::model::metadata::DocumentMetadata;
+
+    /// Pin of radius 1 and height 2 with true circles and a cylindrical side
+    const PIN: &str = "ISO-10303-21;
+HEADER;
+FILE_SCHEMA(('CONFIG_CONTROL_DESIGN'));
+ENDSEC;
+DATA;
+/* placements */
+#1=CARTESIAN_POINT('',(0.,0.,0.));
+#2=DIRECTION('',(0.,0.,1.));
+#3=DIRECTION('',(1.,0.,0.));
+#4=AXIS2_PLACEMENT_3D('',#1,#2,#3);
+#5=CARTESIAN_POINT('',(0.,0.,2.));
+#6=AXIS2_PLACEMENT_3D('',#5,#2,#3);
+#7=CIRCLE('',#4,1.);
+#8=CIRCLE('',#6,1.);
+#9=CARTESIAN_POINT('',(1.,0.,0.));
+#10=CARTESIAN_POINT('',(1.,0.,2.));
+#11=VERTEX_POINT('',#9);
+#12=VERTEX_POINT('',#10);
+#13=EDGE_CURVE('',#11,#11,#7,.T.);
+#14=EDGE_CURVE('',#12,#12,#8,.T.);
+#15=VECTOR('',#2,2.);
+#16=LINE('',#9,#15);
+#17=EDGE_CURVE('',#11,#12,#16,.T.);
+#18=ORIENTED_EDGE('',*,*,#13,.F.);
+#19=EDGE_LOOP('',(#18));
+#20=FACE_OUTER_BOUND('',#19,.T.);
+#21=PLANE('',#4);
+#22=ADVANCED_FACE('',(#20),#21,.F.);
+#23=ORIENTED_EDGE('',*,*,#14,.T.);
+#24=EDGE_LOOP('',(#23));
+#25=FACE_OUTER_BOUND('',#24,.T.);
+#26=PLANE('',#6);
+#27=ADVANCED_FACE('',(#25),#26,.T.);
+#28=ORIENTED_EDGE('',*,*,#13,.T.);
+#29=ORIENTED_EDGE('',*,*,#17,.T.);
+#30=ORIENTED_EDGE('',*,*,#14,.F.);
+#31=ORIENTED_EDGE('',*,*,#17,.F.);
+#32=EDGE_LOOP('',(#28,#29,#30,#31));
+#33=FACE_OUTER_BOUND('',#32,.T.);
+#34=CYLINDRICAL_SURFACE('',#4,1.);
+#35=ADVANCED_FACE('',(#33),#34,.T.);
+#36=CLOSED_SHELL('',(#22,#27,#35));
+#37=MANIFOLD_SOLID_BREP('Pin',#36);
+#38=(LENGTH_UNIT()NAMED_UNIT(*)SI_UNIT($,.METRE.));
+ENDSEC;
+END-ISO-10303-21;
+";
+
+    #[test]
+    fn test_round_trip_exported_cube() {
+        let text = write_step(&DocumentMetadata::new("Cube"), &[("Block ø", &cube(10.0))]).unwrap();
+        let import = read_step(&text).unwrap();
+        assert!(import.report.is_complete(), "{}", import.report);
+        let (name, model) = &import.solids[0];
+        assert_eq!(name, "Block ø");
+        assert_eq!((model.vertices.len(), model.edges.len(), model.faces.len()), (8, 12, 6));
+        assert!((mass_properties(model).volume - 1000.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_progress_per_solid_and_cancel() {
+        let text = write_step(&DocumentMetadata::new("Cubes"), &[("A", &cube(10.0)), ("B", &cube(20.0))]).unwrap();
+        let mut fractions = Vec::new();
+        let import = read_step_with(&text, |f| {
+            fractions.push(f);
+            true
+        })
+        .unwrap();
+        assert_eq!(import.solids.len(), 2);
+        assert_eq!(fractions, vec![0.0, 0.5]);
+        assert!(matches!(read_step_with(&text, |f| f < 0.5), Err(StepError::Cancelled)));
+    }
+
+    #[test]
+    fn test_cylinder_with_circles_in_metres() {
+        let import = read_step(PIN).unwrap();
+        assert!(import.report.is_complete(), "{}", import.report);
+        assert_eq!(import.report.faces, 3);
+        let (name, model) = &import.solids[0];
+        assert_eq!(name, "Pin");
+        // Two caps and one facet per circle segment, sharing their vertices
+        assert_eq!(model.faces.len(), 2 + CIRCLE_SEGMENTS);
+        assert_eq!(model.vertices.len(), 2 * CIRCLE_SEGMENTS);
+        let n = CIRCLE_SEGMENTS as f64;
+        let expected = 0.5 * n * (std::f64::consts::TAU / n).sin() * 2.0 * 1e9;
+        assert!((mass_properties(model).volume - expected).abs() < 1e-3 * expected);
+    }
+
+    #[test]
+    fn test_report_lists_unsupported_entities() {
+        let text = PIN
+            .replace("#34=CYLINDRICAL_SURFACE('',#4,1.);", "#34=B_SPLINE_SURFACE_WITH_KNOTS('',1,1,(),.UNSPECIFIED.,.F.,.F.,.F.,(),(),(),.UNSPECIFIED.);")
+            .replace("#38=", "#39=SHELL_BASED_SURFACE_MODEL('',());\n#38=");
+        let import = read_step(&text).unwrap();
+        assert_eq!(import.report.faces, 2);
+        assert_eq!(import.report.skipped_faces, 1);
+        assert_eq!(import.report.unsupported.get("B_SPLINE_SURFACE_WITH_KNOTS"), Some(&1));
+        assert_eq!(import.report.unsupported.get("SHELL_BASED_SURFACE_MODEL"), Some(&1));
+        // The solid is kept, but flagged as open
+        assert_eq!(import.solids.len(), 1);
+        assert!(import.report.warnings[0].contains("not closed"));
+        assert!(import.report.to_string().contains("1 face(s) skipped"));
+
+        assert!(matches!(read_step("ISO-10303-21;\nDATA;\n#1=LINE('',#2"), Err(StepError::Parse(_))));
+    }
+}
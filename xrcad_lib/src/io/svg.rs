@@ -0,0 +1,204 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: io::svg
+//!
+//! SVG export for documentation and laser cutting, in millimetres. A body is
+//! projected onto a construction plane as seen from its positive side: the
+//! silhouette plus the visible sharp edges, with edges sampled and tested for
+//! occlusion by other faces. Sketches are written as their curves.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use nalgebra::{Point3, Vector2};
+
+use crate::model::brep::placement::raycast_faces;
+use crate::model::brep::topology::edge::EdgeKind;
+use crate::model::brep::topology::plane::Plane;
+use crate::model::brep_model::BrepModel;
+use crate::sketch::sketch::{Sketch, SketchEntity};
+
+/// Occlusion samples per edge
+const EDGE_SAMPLES: usize = 16;
+/// Margin around the drawing, as a fraction of its larger extent
+const MARGIN: f64 = 0.05;
+/// Stroke widths (mm)
+const SILHOUETTE_WIDTH: f64 = 0.5;
+const EDGE_WIDTH: f64 = 0.25;
+
+/// SVG user coordinates: y grows downwards
+fn svg_point(p: &Vector2<f64>) -> (f64, f64) {
+    (p.x, -p.y)
+}
+
+/// Wrap drawing content in an SVG document sized to the given points
+fn document(points: &[Vector2<f64>], body: &str) -> String {
+    let (lo, hi) = points.iter().fold(
+        (Vector2::repeat(f64::INFINITY), Vector2::repeat(f64::NEG_INFINITY)),
+        |(lo, hi), p| (lo.inf(p), hi.sup(p)),
+    );
+    let (lo, hi) = if points.is_empty() { (Vector2::zeros(), Vector2::zeros()) } else { (lo, hi) };
+    let margin = ((hi - lo).max() * MARGIN).max(1.0);
+    let (x, y) = (lo.x - margin, -hi.y - margin);
+    let (w, h) = (hi.x - lo.x + 2.0 * margin, hi.y - lo.y + 2.0 * margin);
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}mm\" height=\"{h}mm\" viewBox=\"{x} {y} {w} {h}\">"
+    );
+    let _ = writeln!(out, "<g fill=\"none\" stroke=\"black\" stroke-linecap=\"round\" stroke-linejoin=\"round\">");
+    out.push_str(body);
+    out.push_str("</g>\n</svg>\n");
+    out
+}
+
+/// Polyline as path data
+fn path_data(points: &[Vector2<f64>]) -> String {
+    points
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let (x, y) = svg_point(p);
+            format!("{}{} {}", if i == 0 { "M" } else { " L" }, x, y)
+        })
+        .collect()
+}
+
+/// Silhouette and visible edges of a body projected onto `plane`
+pub fn projection_svg(model: &BrepModel, plane: &Plane) -> String {
+    let view = plane.normal.normalize();
+    let tol = &model.tolerance;
+    let facing = |face_id: usize| {
+        // Turned towards the viewer by more than the angular tolerance
+        model.face(face_id).and_then(|f| model.face_normal(f)).is_some_and(|n| n.normalize().dot(&view) > tol.angular.sin())
+    };
+    // Visible unless a face lies between the point and the viewer; the ray
+    // starts just off the surface so the edge's own faces don't count
+    let clearance = tol.linear * 10.0;
+    let visible = |p: &Point3<f64>| raycast_faces(model, &(p + view * clearance), &view).is_none();
+
+    let mut body = String::new();
+    let mut extent = Vec::new();
+    for edge in &model.edges {
+        let faces = model.faces_using_edge(edge.id);
+        let front = faces.iter().filter(|f| facing(**f)).count();
+        let silhouette = front > 0 && (front < faces.len() || faces.len() == 1);
+        let crease = front > 0 && model.classify_edge(edge.id, tol.angular) == EdgeKind::Sharp;
+        if !silhouette && !crease {
+            continue;
+        }
+        let (Some(a), Some(b)) = (model.vertex_position(edge.vertices.0), model.vertex_position(edge.vertices.1)) else {
+            continue;
+        };
+        let (class, width) = if silhouette { ("silhouette", SILHOUETTE_WIDTH) } else { ("edge", EDGE_WIDTH) };
+        // Runs of consecutive visible samples become separate paths
+        let mut runs: Vec<Vec<Vector2<f64>>> = vec![Vec::new()];
+        for i in 0..EDGE_SAMPLES {
+            let at = |t: f64| Point3::from(a + (b - a) * t);
+            let (t0, t1) = (i as f64 / EDGE_SAMPLES as f64, (i + 1) as f64 / EDGE_SAMPLES as f64);
+            let run = runs.last_mut().expect("runs is never empty");
+            if visible(&at((t0 + t1) * 0.5)) {
+                if run.is_empty() {
+                    run.push(plane.project_2d(&at(t0)));
+                }
+                run.push(plane.project_2d(&at(t1)));
+            } else if !run.is_empty() {
+                runs.push(Vec::new());
+            }
+        }
+        for run in runs.iter().filter(|r| r.len() >= 2) {
+            // Collinear samples: only the ends are needed
+            let ends = [run[0], run[run.len() - 1]];
+            let _ = writeln!(body, "<path class=\"{}\" stroke-width=\"{}\" d=\"{}\"/>", class, width, path_data(&ends));
+            extent.extend(ends);
+        }
+    }
+    document(&extent, &body)
+}
+
+/// A sketch's curves as SVG paths in plane coordinates
+pub fn sketch_svg(sketch: &Sketch) -> String {
+    let mut body = String::new();
+    let mut extent = Vec::new();
+    for entity in &sketch.entities {
+        let p = |id: usize| sketch.point_position(id);
+        match entity {
+            SketchEntity::Line { start, end, .. } => {
+                let (Some(a), Some(b)) = (p(*start), p(*end)) else { continue };
+                let _ = writeln!(body, "<path stroke-width=\"{}\" d=\"{}\"/>", EDGE_WIDTH, path_data(&[a, b]));
+                extent.extend([a, b]);
+            }
+            SketchEntity::Circle { center, radius, .. } => {
+                let Some(c) = p(*center) else { continue };
+                let (x, y) = svg_point(&c);
+                let _ = writeln!(body, "<circle stroke-width=\"{}\" cx=\"{}\" cy=\"{}\" r=\"{}\"/>", EDGE_WIDTH, x, y, radius);
+                extent.extend([c - Vector2::repeat(*radius), c + Vector2::repeat(*radius)]);
+            }
+            SketchEntity::Arc { center, start, end, .. } => {
+                let (Some(c), Some(s), Some(e)) = (p(*center), p(*start), p(*end)) else { continue };
+                let r = (s - c).norm();
+                let sweep = ((e - c).y.atan2((e - c).x) - (s - c).y.atan2((s - c).x)).rem_euclid(std::f64::consts::TAU);
+                let ((sx, sy), (ex, ey)) = (svg_point(&s), svg_point(&e));
+                // Counter-clockwise in the plane is a negative sweep once y points down
+                let large = u8::from(sweep > std::f64::consts::PI);
+                let _ = writeln!(body, "<path stroke-width=\"{}\" d=\"M{} {} A{} {} 0 {} 0 {} {}\"/>", EDGE_WIDTH, sx, sy, r, r, large, ex, ey);
+                extent.extend(sketch.entity_polyline(entity, 16));
+            }
+        }
+    }
+    document(&extent, &body)
+}
+
+pub fn save_projection_svg(path: &Path, model: &BrepModel, plane: &Plane) -> io::Result<()> {
+    fs::write(path, projection_svg(model, plane))
+}
+
+pub fn save_sketch_svg(path: &Path, sketch: &Sketch) -> io::Result<()> {
+    fs::write(path, sketch_svg(sketch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::cube;
+    use nalgebra::Vector3;
+
+    #[test]
+    fn test_projection_silhouette_and_occlusion() {
+        let top = projection_svg(&cube(10.0), &Plane::xy());
+        assert_eq!(top.matches("class=\"silhouette\"").count(), 4);
+        assert_eq!(top.matches("class=\"edge\"").count(), 0);
+        assert!(top.contains("width=\"12mm\" height=\"12mm\""));
+
+        let iso = Plane::from_point_normal(Point3::origin(), Vector3::new(1.0, 1.0, 1.0), None);
+        let svg = projection_svg(&cube(10.0), &iso);
+        assert_eq!((svg.matches("class=\"silhouette\"").count(), svg.matches("class=\"edge\"").count()), (6, 3));
+
+        // A smaller cube directly behind the first adds nothing
+        let mut hidden = cube(4.0);
+        for v in &mut hidden.vertices {
+            v.position -= Vector3::new(0.0, 0.0, 20.0);
+        }
+        let mut model = cube(10.0);
+        model.append(&hidden);
+        assert_eq!(projection_svg(&model, &Plane::xy()).matches("<path").count(), 4);
+    }
+
+    #[test]
+    fn test_sketch_paths() {
+        let mut sketch = Sketch::new("Part", Plane::xy());
+        let c = sketch.add_point(Vector2::new(0.0, 0.0));
+        let a = sketch.add_point(Vector2::new(10.0, 0.0));
+        let b = sketch.add_point(Vector2::new(0.0, 10.0));
+        sketch.add_line(a, b);
+        sketch.add_arc(c, a, b);
+        sketch.add_circle(c, 2.0);
+        let svg = sketch_svg(&sketch);
+        assert!(svg.contains("d=\"M10 -0 L0 -10\""));
+        assert!(svg.contains("d=\"M10 -0 A10 10 0 0 0 0 -10\""));
+        assert!(svg.contains("<circle stroke-width=\"0.25\" cx=\"0\" cy=\"-0\" r=\"2\"/>"));
+    }
+}
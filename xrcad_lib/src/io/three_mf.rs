@@ -0,0 +1,181 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: io::three_mf
+//!
+//! 3MF export for printing. Unlike STL, one file keeps every body as its own
+//! object with its name, display color and material, and states the unit
+//! (millimetres). Meshes are tessellated faces with coincident corners welded,
+//! so closed bodies stay watertight. The package is written uncompressed.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::model::brep::tessellate::tessellate;
+use crate::model::brep_model::BrepModel;
+use crate::model::material::Material;
+use crate::model::mesh_body::MeshBody;
+use crate::model::metadata::DocumentMetadata;
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+<Default Extension="model" ContentType="application/vnd.ms-package.3dmanufacturing-3dmodel+xml"/>
+</Types>
+"#;
+
+const RELATIONSHIPS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Target="/3D/3dmodel.model" Id="rel0" Type="http://schemas.microsoft.com/3dmanufacturing/2013/01/3dmodel"/>
+</Relationships>
+"#;
+
+/// XML attribute / text escaping
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// sRGB color with alpha as `#RRGGBBAA`
+fn display_color(material: &Material) -> String {
+    let [r, g, b] = material.base_color.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+    let a = (material.alpha.clamp(0.0, 1.0) * 255.0).round() as u8;
+    format!("#{:02X}{:02X}{:02X}{:02X}", r, g, b, a)
+}
+
+/// The 3D model part: one object per body, each referencing its material
+pub fn model_xml(metadata: &DocumentMetadata, bodies: &[(&str, &BrepModel, &Material)]) -> String {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<model unit=\"millimeter\" xml:lang=\"en-US\" xmlns=\"http://schemas.microsoft.com/3dmanufacturing/core/2015/02\">\n");
+    let _ = writeln!(out, "<metadata name=\"Title\">{}</metadata>", escape(&metadata.title));
+    if !metadata.author.is_empty() {
+        let _ = writeln!(out, "<metadata name=\"Designer\">{}</metadata>", escape(&metadata.author));
+    }
+    out.push_str("<resources>\n<basematerials id=\"1\">\n");
+    for (_, _, material) in bodies {
+        let _ = writeln!(out, "<base name=\"{}\" displaycolor=\"{}\"/>", escape(&material.name), display_color(material));
+    }
+    out.push_str("</basematerials>\n");
+    for (i, (name, model, _)) in bodies.iter().enumerate() {
+        let soup: Vec<_> = tessellate(model).iter().flat_map(|f| f.triangle_positions().collect::<Vec<_>>()).collect();
+        let mesh = MeshBody::from_triangles(*name, &soup, &model.tolerance);
+        let _ = writeln!(out, "<object id=\"{}\" type=\"model\" name=\"{}\" pid=\"1\" pindex=\"{}\">", i + 2, escape(name), i);
+        out.push_str("<mesh>\n<vertices>\n");
+        for p in &mesh.positions {
+            let _ = writeln!(out, "<vertex x=\"{}\" y=\"{}\" z=\"{}\"/>", p.x, p.y, p.z);
+        }
+        out.push_str("</vertices>\n<triangles>\n");
+        for [a, b, c] in &mesh.triangles {
+            let _ = writeln!(out, "<triangle v1=\"{}\" v2=\"{}\" v3=\"{}\"/>", a, b, c);
+        }
+        out.push_str("</triangles>\n</mesh>\n</object>\n");
+    }
+    out.push_str("</resources>\n<build>\n");
+    for i in 0..bodies.len() {
+        let _ = writeln!(out, "<item objectid=\"{}\"/>", i + 2);
+    }
+    out.push_str("</build>\n</model>\n");
+    out
+}
+
+/// CRC-32 (IEEE) as used by ZIP
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, b| {
+        (0..8).fold(crc ^ *b as u32, |c, _| if c & 1 != 0 { (c >> 1) ^ 0xEDB8_8320 } else { c >> 1 })
+    })
+}
+
+/// ZIP archive of stored (uncompressed) entries
+fn zip(entries: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut directory = Vec::new();
+    for (name, data) in entries {
+        let offset = out.len() as u32;
+        let (crc, size, name_len) = (crc32(data), data.len() as u32, name.len() as u16);
+        // Version 2.0, no flags, stored, zero DOS time and date
+        let common = |buf: &mut Vec<u8>| {
+            for v in [0u16, 0, 0, 0] {
+                buf.extend(v.to_le_bytes());
+            }
+            buf.extend(crc.to_le_bytes());
+            buf.extend(size.to_le_bytes());
+            buf.extend(size.to_le_bytes());
+            buf.extend(name_len.to_le_bytes());
+            buf.extend(0u16.to_le_bytes());
+        };
+        out.extend(0x0403_4b50u32.to_le_bytes());
+        out.extend(20u16.to_le_bytes());
+        common(&mut out);
+        out.extend(name.as_bytes());
+        out.extend(*data);
+
+        directory.extend(0x0201_4b50u32.to_le_bytes());
+        directory.extend(20u16.to_le_bytes());
+        directory.extend(20u16.to_le_bytes());
+        common(&mut directory);
+        // Comment length, disk, internal and external attributes
+        for v in [0u16, 0, 0] {
+            directory.extend(v.to_le_bytes());
+        }
+        directory.extend(0u32.to_le_bytes());
+        directory.extend(offset.to_le_bytes());
+        directory.extend(name.as_bytes());
+    }
+    let (dir_offset, dir_size, count) = (out.len() as u32, directory.len() as u32, entries.len() as u16);
+    out.extend(directory);
+    out.extend(0x0605_4b50u32.to_le_bytes());
+    for v in [0u16, 0, count, count] {
+        out.extend(v.to_le_bytes());
+    }
+    out.extend(dir_size.to_le_bytes());
+    out.extend(dir_offset.to_le_bytes());
+    out.extend(0u16.to_le_bytes());
+    out
+}
+
+/// Bodies with their materials as a 3MF package
+pub fn write_3mf(metadata: &DocumentMetadata, bodies: &[(&str, &BrepModel, &Material)]) -> Vec<u8> {
+    let model = model_xml(metadata, bodies);
+    zip(&[
+        ("[Content_Types].xml", CONTENT_TYPES.as_bytes()),
+        ("_rels/.rels", RELATIONSHIPS.as_bytes()),
+        ("3D/3dmodel.model", model.as_bytes()),
+    ])
+}
+
+pub fn save_3mf(path: &Path, metadata: &DocumentMetadata, bodies: &[(&str, &BrepModel, &Material)]) -> io::Result<()> {
+    fs::write(path, write_3mf(metadata, bodies))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::cube;
+
+    #[test]
+    fn test_model_xml() {
+        let red = Material { alpha: 0.5, ..Material::new("PLA <red>", [1.0, 0.0, 0.0]) };
+        let xml = model_xml(&DocumentMetadata::new("Parts"), &[("A", &cube(10.0), &Material::default()), ("B", &cube(5.0), &red)]);
+        assert!(xml.contains("unit=\"millimeter\""));
+        assert!(xml.contains("<base name=\"PLA &lt;red&gt;\" displaycolor=\"#FF000080\"/>"));
+        assert!(xml.contains("name=\"B\" pid=\"1\" pindex=\"1\""));
+        // Two welded cubes: 8 vertices and 12 triangles each
+        assert_eq!(xml.matches("<vertex ").count(), 16);
+        assert_eq!(xml.matches("<triangle ").count(), 24);
+        assert_eq!(xml.matches("<item ").count(), 2);
+    }
+
+    #[test]
+    fn test_package_layout() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        let package = write_3mf(&DocumentMetadata::new("Cube"), &[("Cube", &cube(1.0), &Material::default())]);
+        assert_eq!(&package[0..4], b"PK\x03\x04");
+        let end = package.len() - 22;
+        assert_eq!(&package[end..end + 4], b"PK\x05\x06");
+        assert_eq!(u16::from_le_bytes([package[end + 10], package[end + 11]]), 3);
+        let text = String::from_utf8_lossy(&package);
+        assert_eq!(text.matches("3D/3dmodel.model").count(), 3);
+        assert!(text.contains("<object id=\"2\" type=\"model\" name=\"Cube\""));
+    }
+}
@@ -2,6 +2,8 @@
 pub use model::brep_model::{BrepModel, na_vec3_to_bevy};
 pub use model::brep::topology::{vertex::Vertex, edge::Edge, face::Face, edge_loop::EdgeLoop};
 pub use workspace::workspace::Workspace;
+pub use model::tolerance::Tolerance;
+pub use model::body::{Body, BodyId};
 pub mod color;
 pub use color::*;
 // SPDX-License-Identifier: MIT OR Apache-2.0
@@ -10,25 +12,81 @@ pub use color::*;
 /// xrcad core library
 
 
+pub mod drawing {
+    pub mod sheet;
+    pub mod title_block;
+}
+
 pub mod input{
+    #[cfg(feature = "render")]
+    pub mod actions;
     pub mod mouse;
     pub mod keyboard;
+    #[cfg(feature = "render")]
     pub mod touchscreen;
+    #[cfg(feature = "render")]
     pub mod eyetrack;
+    #[cfg(feature = "render")]
     pub mod stylus;
+    #[cfg(feature = "render")]
+    pub mod bindings;
+    #[cfg(feature = "render")]
     pub mod gamepad;
     pub mod sixdof_delta;
     pub mod sixdof_pose;
 }
 
 pub mod interaction{
+    #[cfg(feature = "render")]
+    pub mod box_select;
+    #[cfg(feature = "render")]
+    pub mod construction_plane;
+    pub mod dimension_edit;
     pub mod event;
+    #[cfg(feature = "render")]
+    pub mod grid_snap;
+    #[cfg(feature = "render")]
+    pub mod measure_tool;
+    #[cfg(feature = "render")]
+    pub mod outliner;
+    #[cfg(feature = "render")]
+    pub mod picking;
+    #[cfg(feature = "render")]
+    pub mod place_primitive;
+    #[cfg(feature = "render")]
+    pub mod plane_suggestion;
+    pub mod quick_boolean;
+    pub mod rename;
+    pub mod selection;
+    #[cfg(feature = "render")]
+    pub mod snapping;
     pub mod state;
+    #[cfg(feature = "render")]
+    pub mod tools;
+    #[cfg(feature = "render")]
+    pub mod transform_gizmo;
+    #[cfg(feature = "render")]
+    pub mod two_hand;
+    #[cfg(feature = "render")]
+    pub mod xr_controller;
     // pub mod gestures;
     // pub mod haptics;
     // pub mod voice;
 }
 
+pub mod io {
+    pub mod dxf;
+    pub mod gltf;
+    pub mod measurement_export;
+    pub mod mesh_import;
+    pub mod project;
+    pub mod session;
+    pub mod step;
+    pub mod step_import;
+    pub mod svg;
+    pub mod three_mf;
+}
+
 pub mod model {
     pub mod brep {
         pub mod topology {
@@ -36,6 +94,7 @@ pub mod model {
             pub mod edge;
             pub mod edge_loop;
             pub mod face;
+            pub mod invariants;
             pub mod plane;
         }
         pub mod geometry {
@@ -46,10 +105,13 @@ pub mod model {
             pub mod point;
         }
         pub mod operations {
+            pub mod boolean;
+            pub mod delete;
             pub mod extrude;
+            pub mod imprint;
+            pub mod merge_faces;
             pub mod split;
             pub mod stitch;
-            // pub mod boolean;
             // pub mod revolve;
             // pub mod loft;
             // pub mod sweep;
@@ -62,8 +124,15 @@ pub mod model {
             // pub mod solid;
             // pub mod trim;
         }
+        pub mod classify;
+        pub mod placement;
+        pub mod primitives;
+        pub mod tessellate;
+        pub mod text_format;
+        pub mod validate;
         pub mod constraints {
             pub mod length;
+            pub mod planarity;
             // pub mod angle;
             // pub mod tangent;
             // pub mod normal;
@@ -78,24 +147,118 @@ pub mod model {
             // pub mod coincident;
         }
     }
+    pub mod assembly;
+    pub mod body;
+    pub mod bvh;
+    pub mod changes;
+    pub mod command;
+    pub mod document;
     pub mod brep_model;
     pub mod composite_model;
+    pub mod compound;
+    pub mod feature_tree;
     pub mod form_model;
+    pub mod groups;
+    pub mod jobs;
+    pub mod layers;
+    pub mod material;
+    pub mod mesh_body;
+    pub mod metadata;
+    pub mod properties;
+    pub mod tolerance;
+    pub mod units;
 }
 
+pub mod measure {
+    pub mod angle;
+    pub mod circular;
+    pub mod clash;
+    pub mod distance;
+    pub mod mass_properties;
+    pub mod measurements;
+}
+
+pub mod net {
+    pub mod collab;
+    #[cfg(feature = "render")]
+    pub mod cursors;
+    #[cfg(feature = "render")]
+    pub mod presence;
+}
+
+#[cfg(feature = "render")]
+pub mod plugin;
+
+#[cfg(feature = "render")]
 pub mod render{
+    pub mod analysis;
+    pub mod brep_refs;
+    pub mod culling;
+    pub mod display_mode;
+    pub mod edge_display;
+    pub mod edge_overlay;
+    pub mod exploded;
     pub mod ghosting;
+    pub mod gizmo_scale;
+    pub mod grid;
     pub mod hilighting;
+    pub mod lighting;
+    pub mod lines;
+    pub mod lod;
     pub mod materials;
-    // pub mod lighting;
+    pub mod section;
+    pub mod settings;
+    pub mod ui_style;
     // pub mod shadows;
     // pub mod textures;
     // pub mod shaders;
 }
 
+pub mod scripting {
+    pub mod console;
+    pub mod engine;
+}
+
+pub mod sketch {
+    pub mod dimension;
+    pub mod regions;
+    pub mod sketch;
+    pub mod solver;
+}
+
+pub mod telemetry {
+    pub mod crash;
+    pub mod usage;
+}
+
+pub mod testing {
+    pub mod corpus;
+    #[cfg(test)]
+    pub mod support;
+}
+
 pub mod viewport{
+    #[cfg(feature = "render")]
     pub mod camera;
+    #[cfg(feature = "render")]
+    pub mod camera_animation;
+    #[cfg(feature = "render")]
     pub mod camera_control;
+    #[cfg(feature = "render")]
+    pub mod comfort;
+    #[cfg(feature = "render")]
+    pub mod framing;
+    #[cfg(feature = "render")]
+    pub mod locomotion;
+    #[cfg(feature = "render")]
+    pub mod passthrough;
+    pub mod saved_views;
+    #[cfg(feature = "render")]
+    pub mod view_cube;
+    #[cfg(feature = "render")]
+    pub mod xr_panels;
+    #[cfg(feature = "render")]
+    pub mod xr_scale;
     // pub mod frustum;
     // pub mod projection;
     // pub mod view;
@@ -109,6 +272,7 @@ pub mod workspace {
         pub mod marker;
         pub mod origin;
     }
+    pub mod workbenches;
     pub mod workspace;
 }
 
@@ -0,0 +1,143 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: measure::angle
+//!
+//! Angle measurements: dihedral angle between two planar faces, angle between a
+//! face and a construction plane, and taper angle of a (faceted) conical face.
+//! Results carry an anchor point so they can be placed as annotations.
+
+use nalgebra::{Point3, Vector3};
+
+use crate::measure::circular::conical_features;
+use crate::model::brep::topology::plane::Plane;
+use crate::model::brep_model::BrepModel;
+
+/// What an angle measurement describes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AngleKind {
+    /// Interior angle between two faces (90° for adjacent cube faces, 180° for coplanar faces)
+    Dihedral,
+    /// Angle between a face and a plane, in [0°, 90°]
+    FaceToPlane,
+    /// Half-angle of a cone relative to its axis
+    Taper,
+}
+
+/// A measured angle, ready to be shown as an annotation
+#[derive(Debug, Clone, PartialEq)]
+pub struct AngleMeasurement {
+    pub kind: AngleKind,
+    /// Angle in radians
+    pub angle: f64,
+    /// Model-space point to attach the annotation to
+    pub anchor: Point3<f64>,
+}
+
+impl AngleMeasurement {
+    pub fn degrees(&self) -> f64 {
+        self.angle.to_degrees()
+    }
+
+    /// Annotation text, e.g. "90.00°"
+    pub fn label(&self) -> String {
+        format!("{:.2}°", self.degrees())
+    }
+}
+
+/// Unsigned angle between two directions, in [0, π]
+fn angle_between(a: &Vector3<f64>, b: &Vector3<f64>) -> f64 {
+    a.cross(b).norm().atan2(a.dot(b))
+}
+
+/// Mean of the face's outer loop vertices
+fn face_anchor(model: &BrepModel, face_id: usize) -> Option<Point3<f64>> {
+    let face = model.face(face_id)?;
+    let outer = *model.face_loops(face).first()?;
+    let points = model.loop_positions(outer);
+    if points.is_empty() {
+        return None;
+    }
+    Some(Point3::from(points.iter().sum::<Vector3<f64>>() / points.len() as f64))
+}
+
+/// Dihedral angle between two planar faces, anchored midway between them
+pub fn dihedral_angle(model: &BrepModel, a: usize, b: usize) -> Option<AngleMeasurement> {
+    let na = model.face_normal(model.face(a)?)?;
+    let nb = model.face_normal(model.face(b)?)?;
+    let anchor = Point3::from((face_anchor(model, a)?.coords + face_anchor(model, b)?.coords) * 0.5);
+    Some(AngleMeasurement {
+        kind: AngleKind::Dihedral,
+        angle: std::f64::consts::PI - angle_between(&na, &nb),
+        anchor,
+    })
+}
+
+/// Acute angle between a planar face and a plane, anchored at the face
+pub fn face_plane_angle(model: &BrepModel, face: usize, plane: &Plane) -> Option<AngleMeasurement> {
+    let n = model.face_normal(model.face(face)?)?;
+    let angle = angle_between(&n, &plane.normal);
+    Some(AngleMeasurement {
+        kind: AngleKind::FaceToPlane,
+        angle: angle.min(std::f64::consts::PI - angle),
+        anchor: face_anchor(model, face)?,
+    })
+}
+
+/// Taper angle of the cone a side facet belongs to, anchored at the facet
+pub fn taper_angle(model: &BrepModel, face: usize) -> Option<AngleMeasurement> {
+    let outer = *model.face_loops(model.face(face)?).first()?;
+    let facet = model.loop_vertex_ids(outer);
+    let cone = conical_features(model).into_iter().find(|c| {
+        let ends: Vec<usize> = [c.end_loops.0, c.end_loops.1]
+            .iter()
+            .filter_map(|l| model.edge_loop(*l))
+            .flat_map(|l| model.loop_vertex_ids(l))
+            .collect();
+        // A side facet touches both end circles and nothing else
+        facet.iter().all(|v| ends.contains(v))
+            && [c.end_loops.0, c.end_loops.1].iter().all(|l| {
+                model.edge_loop(*l).is_some_and(|l| model.loop_vertex_ids(l).iter().any(|v| facet.contains(v)))
+            })
+    })?;
+    Some(AngleMeasurement { kind: AngleKind::Taper, angle: cone.taper_angle(), anchor: face_anchor(model, face)? })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::{cube, frustum};
+    use std::f64::consts::FRAC_PI_2;
+
+    #[test]
+    fn test_dihedral_angle_of_cube() {
+        let c = cube(2.0);
+        // bottom and front faces meet at 90°, bottom and top are parallel
+        let adjacent = dihedral_angle(&c, c.faces[0].id, c.faces[2].id).unwrap();
+        assert!((adjacent.angle - FRAC_PI_2).abs() < 1e-12);
+        assert_eq!(adjacent.label(), "90.00°");
+        let opposite = dihedral_angle(&c, c.faces[0].id, c.faces[1].id).unwrap();
+        assert!(opposite.angle.abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_face_plane_angle() {
+        let c = cube(2.0);
+        let top = c.faces[1].id;
+        assert!(face_plane_angle(&c, top, &Plane::xy()).unwrap().angle.abs() < 1e-12);
+        let side = face_plane_angle(&c, c.faces[2].id, &Plane::xy()).unwrap();
+        assert!((side.angle - FRAC_PI_2).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_taper_angle_of_frustum() {
+        // radius shrinks by 1 over a height of 1: 45° taper
+        let f = frustum(2.0, 1.0, 1.0, 16);
+        let side = f.faces[2].id;
+        let taper = taper_angle(&f, side).unwrap();
+        assert_eq!(taper.kind, AngleKind::Taper);
+        assert!((taper.degrees() - 45.0).abs() < 1e-9);
+        // End caps are not side facets
+        assert!(taper_angle(&f, f.faces[0].id).is_none());
+    }
+}
@@ -0,0 +1,236 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: measure::circular
+//!
+//! Recognizes circular edges and cylindrical faces in faceted BREP topology so
+//! dimensions can report radius, diameter, axis and center-to-center distances
+//! instead of treating them as polylines.
+
+use nalgebra::{Point3, Vector3};
+
+use crate::model::brep_model::BrepModel;
+use crate::model::tolerance::Tolerance;
+
+/// Fewest segments a polyline loop needs to be recognized as a circle
+pub const MIN_CIRCLE_SEGMENTS: usize = 6;
+
+/// A circle recognized from an edge loop
+#[derive(Debug, Clone, PartialEq)]
+pub struct CircularFeature {
+    /// Id of the edge loop the circle was recognized from
+    pub edge_loop: usize,
+    pub center: Point3<f64>,
+    /// Unit normal of the circle's plane
+    pub axis: Vector3<f64>,
+    pub radius: f64,
+}
+
+impl CircularFeature {
+    pub fn diameter(&self) -> f64 {
+        self.radius * 2.0
+    }
+
+    /// Distance between the centers of two circles (e.g. two holes)
+    pub fn center_distance(&self, other: &CircularFeature) -> f64 {
+        (other.center - self.center).norm()
+    }
+}
+
+/// A cylinder recognized from two coaxial circles of equal radius
+#[derive(Debug, Clone, PartialEq)]
+pub struct CylindricalFeature {
+    /// Center of the first end circle
+    pub origin: Point3<f64>,
+    /// Unit axis pointing from the first end circle to the second
+    pub axis: Vector3<f64>,
+    pub radius: f64,
+    pub length: f64,
+    /// Edge loops of the two end circles
+    pub end_loops: (usize, usize),
+}
+
+/// A truncated cone recognized from two coaxial circles of different radius
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConicalFeature {
+    /// Center of the first end circle
+    pub origin: Point3<f64>,
+    /// Unit axis pointing from the first end circle to the second
+    pub axis: Vector3<f64>,
+    /// Radii of the first and second end circles
+    pub radii: (f64, f64),
+    pub length: f64,
+    /// Edge loops of the two end circles
+    pub end_loops: (usize, usize),
+}
+
+impl ConicalFeature {
+    /// Half-angle between the conical surface and its axis (radians)
+    pub fn taper_angle(&self) -> f64 {
+        (self.radii.1 - self.radii.0).abs().atan2(self.length)
+    }
+}
+
+/// Center of the circle through three points, if they are not collinear
+fn circumcenter(a: &Vector3<f64>, b: &Vector3<f64>, c: &Vector3<f64>, tol: &Tolerance) -> Option<Vector3<f64>> {
+    if tol.collinear(a, b, c) {
+        return None;
+    }
+    let ab = b - a;
+    let ac = c - a;
+    let n = ab.cross(&ac);
+    Some(a + (n.cross(&ab) * ac.norm_squared() + ac.cross(&n) * ab.norm_squared()) / (2.0 * n.norm_squared()))
+}
+
+/// Fit a circle through polyline points; all must be coplanar and equidistant from the center
+pub fn fit_circle(points: &[Vector3<f64>], tol: &Tolerance) -> Option<(Point3<f64>, Vector3<f64>, f64)> {
+    let n = points.len();
+    if n < MIN_CIRCLE_SEGMENTS {
+        return None;
+    }
+    let (a, b, c) = (&points[0], &points[n / 3], &points[2 * n / 3]);
+    let center = circumcenter(a, b, c, tol)?;
+    let axis = (b - a).cross(&(c - a)).normalize();
+    let radius = (a - center).norm();
+    let fits = points.iter().all(|p| {
+        tol.is_zero_length((p - center).norm() - radius) && tol.is_zero_length((p - center).dot(&axis))
+    });
+    fits.then(|| (Point3::from(center), axis, radius))
+}
+
+/// All edge loops of the model that form circles
+pub fn circular_edges(model: &BrepModel) -> Vec<CircularFeature> {
+    model
+        .edgeloops
+        .iter()
+        .filter_map(|l| {
+            let (center, axis, radius) = fit_circle(&model.loop_positions(l), &model.tolerance)?;
+            Some(CircularFeature { edge_loop: l.id, center, axis, radius })
+        })
+        .collect()
+}
+
+/// The circle formed by a specific edge loop, if any
+pub fn circular_edge(model: &BrepModel, edge_loop: usize) -> Option<CircularFeature> {
+    circular_edges(model).into_iter().find(|c| c.edge_loop == edge_loop)
+}
+
+/// Pairs of distinct circles sharing an axis
+fn coaxial_pairs(model: &BrepModel) -> Vec<(CircularFeature, CircularFeature)> {
+    let tol = &model.tolerance;
+    let circles = circular_edges(model);
+    let mut out = Vec::new();
+    for (i, a) in circles.iter().enumerate() {
+        for b in &circles[i + 1..] {
+            let span = b.center - a.center;
+            if tol.parallel(&a.axis, &b.axis) && !tol.is_zero_length(span.norm()) && tol.parallel(&span, &a.axis) {
+                out.push((a.clone(), b.clone()));
+            }
+        }
+    }
+    out
+}
+
+/// Cylinders (bosses or holes) bounded by pairs of coaxial, equal-radius circles
+pub fn cylindrical_features(model: &BrepModel) -> Vec<CylindricalFeature> {
+    let tol = &model.tolerance;
+    coaxial_pairs(model)
+        .into_iter()
+        .filter(|(a, b)| tol.is_zero_length(a.radius - b.radius))
+        .map(|(a, b)| {
+            let span = b.center - a.center;
+            CylindricalFeature {
+                origin: a.center,
+                axis: span.normalize(),
+                radius: a.radius,
+                length: span.norm(),
+                end_loops: (a.edge_loop, b.edge_loop),
+            }
+        })
+        .collect()
+}
+
+/// Cones (tapered bosses, countersinks) bounded by pairs of coaxial circles of different radius
+pub fn conical_features(model: &BrepModel) -> Vec<ConicalFeature> {
+    let tol = &model.tolerance;
+    coaxial_pairs(model)
+        .into_iter()
+        .filter(|(a, b)| !tol.is_zero_length(a.radius - b.radius))
+        .map(|(a, b)| {
+            let span = b.center - a.center;
+            ConicalFeature {
+                origin: a.center,
+                axis: span.normalize(),
+                radii: (a.radius, b.radius),
+                length: span.norm(),
+                end_loops: (a.edge_loop, b.edge_loop),
+            }
+        })
+        .collect()
+}
+
+/// A dimension inferred from one or two picked edge loops
+#[derive(Debug, Clone, PartialEq)]
+pub enum SmartDimension {
+    Diameter(CircularFeature),
+    CenterToCenter { a: CircularFeature, b: CircularFeature, distance: f64 },
+}
+
+/// Choose a dimension for the picked loops: diameter for one circle, center distance for two
+pub fn smart_dimension(model: &BrepModel, first: usize, second: Option<usize>) -> Option<SmartDimension> {
+    let a = circular_edge(model, first)?;
+    match second {
+        None => Some(SmartDimension::Diameter(a)),
+        Some(id) => {
+            let b = circular_edge(model, id)?;
+            let distance = a.center_distance(&b);
+            Some(SmartDimension::CenterToCenter { a, b, distance })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::{cube, cylinder};
+
+    #[test]
+    fn test_cylinder_circles_and_axis() {
+        let c = cylinder(5.0, 10.0, 24);
+        let circles = circular_edges(&c);
+        assert_eq!(circles.len(), 2);
+        for circle in &circles {
+            assert!((circle.radius - 5.0).abs() < 1e-9);
+            assert!((circle.diameter() - 10.0).abs() < 1e-9);
+            assert!(c.tolerance.parallel(&circle.axis, &Vector3::z()));
+        }
+        let cyl = cylindrical_features(&c);
+        assert_eq!(cyl.len(), 1);
+        assert!((cyl[0].length - 10.0).abs() < 1e-9);
+        assert!(c.tolerance.parallel(&cyl[0].axis, &Vector3::z()));
+    }
+
+    #[test]
+    fn test_no_circles_on_cube() {
+        assert!(circular_edges(&cube(10.0)).is_empty());
+    }
+
+    #[test]
+    fn test_center_to_center() {
+        let mut m = cylinder(1.0, 1.0, 12);
+        let shifted = cylinder(2.0, 1.0, 12);
+        for f in &shifted.faces {
+            let l = shifted.edge_loop(f.edge_loops[0]).unwrap();
+            let pts: Vec<Vector3<f64>> = shifted.loop_positions(l).iter().map(|p| p + Vector3::new(10.0, 0.0, 0.0)).collect();
+            m.add_face(&pts);
+        }
+        let circles = circular_edges(&m);
+        let small = circles.iter().find(|c| (c.radius - 1.0).abs() < 1e-9 && c.center.z < 0.0).unwrap();
+        let big = circles.iter().find(|c| (c.radius - 2.0).abs() < 1e-9 && c.center.z < 0.0).unwrap();
+        match smart_dimension(&m, small.edge_loop, Some(big.edge_loop)) {
+            Some(SmartDimension::CenterToCenter { distance, .. }) => assert!((distance - 10.0).abs() < 1e-9),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(matches!(smart_dimension(&m, small.edge_loop, None), Some(SmartDimension::Diameter(_))));
+    }
+}
@@ -0,0 +1,372 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: measure::clash
+//!
+//! Clash (interference) detection between bodies. The scene hierarchy gives
+//! the pairs whose bounds overlap; their triangles are tested against each
+//! other through the body hierarchies, and a body lying wholly inside another
+//! clashes too. How much they interfere is estimated by sampling the common
+//! bounds on a grid and counting the points inside both bodies. Bodies that
+//! only touch, to within the model's linear tolerance, do not clash. A check is asked for with `ClashRequest` and is
+//! redone as the model changes until cleared; the clashing faces are outlined
+//! in red.
+
+use bevy::prelude::*;
+use nalgebra::Vector3;
+
+#[cfg(feature = "render")]
+use crate::color::RED;
+use crate::input::keyboard::KeyBindings;
+#[cfg(feature = "render")]
+use crate::interaction::selection::SelectionItem;
+#[cfg(feature = "render")]
+use crate::interaction::state::UiPanel;
+use crate::model::body::BodyId;
+use crate::model::brep_model::BrepModel;
+#[cfg(feature = "render")]
+use crate::model::brep_model::na_vec3_to_bevy;
+use crate::model::bvh::{Aabb, BodyBvh, SceneBvh};
+#[cfg(feature = "render")]
+use crate::model::properties::BodyPropertiesCollection;
+use crate::model::tolerance::Tolerance;
+#[cfg(feature = "render")]
+use crate::model::units::UnitSystem;
+#[cfg(feature = "render")]
+use crate::render::hilighting::item_edges;
+use crate::telemetry::crash::journal;
+
+/// Grid samples per axis of the common bounds when estimating the overlap
+const SAMPLES_PER_AXIS: usize = 16;
+/// Ray directions tried in turn when a cast grazes a triangle edge
+const RAY_DIRECTIONS: [[f64; 3]; 4] = [
+    [1.0, 0.37, 0.23],
+    [0.13, 1.0, 0.61],
+    [0.53, 0.17, 1.0],
+    [-0.71, 0.43, -0.29],
+];
+
+/// Two interfering bodies
+#[derive(Debug, Clone, PartialEq)]
+pub struct Clash {
+    pub bodies: (BodyId, BodyId),
+    /// Faces of either body crossing the other, or every face of a body lying inside the other
+    pub faces: Vec<usize>,
+    /// Triangles of either body crossing the other
+    pub triangles: Vec<[Vector3<f64>; 3]>,
+    /// Estimated volume inside both bodies (mm³)
+    pub penetration_volume: f64,
+}
+
+/// Ray parameter where a ray crosses a triangle, if it does, and how far the
+/// crossing lies from the triangle's nearest edge; the ray may point either way
+/// and need not be unit length. A ray whose travel across the triangle's plane
+/// is within the linear tolerance runs along it and does not cross.
+fn triangle_crossing(origin: &Vector3<f64>, dir: &Vector3<f64>, [a, b, c]: &[Vector3<f64>; 3], tolerance: &Tolerance) -> Option<(f64, f64)> {
+    let (e1, e2) = (b - a, c - a);
+    let p = dir.cross(&e2);
+    let det = e1.dot(&p);
+    // Twice the triangle's area; det / area2 is the ray's travel along the normal
+    let area2 = e1.cross(&e2).norm();
+    if tolerance.is_zero_length(area2) || det.abs() <= tolerance.linear * area2 {
+        return None;
+    }
+    let s = origin - a;
+    let u = s.dot(&p) / det;
+    let q = s.cross(&e1);
+    let v = dir.dot(&q) / det;
+    let t = e2.dot(&q) / det;
+    let w = 1.0 - u - v;
+    if u < 0.0 || v < 0.0 || w < 0.0 {
+        return None;
+    }
+    // Each barycentric weight times the opposite altitude is the distance to that edge
+    let margin = (u * area2 / e2.norm()).min(v * area2 / e1.norm()).min(w * area2 / (c - b).norm());
+    Some((t, margin))
+}
+
+/// Two triangles cross when an edge of one passes through the other;
+/// coplanar triangles and ones meeting only at their edges do not
+pub fn triangles_cross(a: &[Vector3<f64>; 3], b: &[Vector3<f64>; 3], tolerance: &Tolerance) -> bool {
+    let edge_through = |tri: &[Vector3<f64>; 3], other: &[Vector3<f64>; 3]| {
+        (0..3).any(|i| {
+            let (p, q) = (tri[i], tri[(i + 1) % 3]);
+            let length = (q - p).norm();
+            // The crossing must be further than the tolerance from both ends of the edge
+            triangle_crossing(&p, &(q - p), other, tolerance)
+                .is_some_and(|(t, _)| t * length > tolerance.linear && (1.0 - t) * length > tolerance.linear)
+        })
+    };
+    edge_through(a, b) || edge_through(b, a)
+}
+
+/// Whether a point lies inside a closed body, by the parity of ray crossings
+pub fn body_contains(body: &BodyBvh, point: &Vector3<f64>, tolerance: &Tolerance) -> bool {
+    'directions: for d in RAY_DIRECTIONS {
+        let dir = Vector3::new(d[0], d[1], d[2]).normalize();
+        let mut crossings = 0;
+        for triangle in body.triangles.near_ray(point, &dir, |_| 0.0) {
+            let Some((t, margin)) = triangle_crossing(point, &dir, &triangle.corners, tolerance) else { continue };
+            if t <= 0.0 {
+                continue;
+            }
+            if margin <= tolerance.linear {
+                continue 'directions;
+            }
+            crossings += 1;
+        }
+        return crossings % 2 == 1;
+    }
+    false
+}
+
+/// Estimated volume inside both bodies: the share of grid samples over their
+/// common bounds that lie in both
+pub fn penetration_volume(a: &BodyBvh, b: &BodyBvh, tolerance: &Tolerance) -> f64 {
+    let common = a.bounds().intersection(&b.bounds());
+    let size = common.max - common.min;
+    if common.is_empty() || tolerance.is_zero_length(size.min()) {
+        return 0.0;
+    }
+    let cell = size / SAMPLES_PER_AXIS as f64;
+    let mut inside = 0;
+    for i in 0..SAMPLES_PER_AXIS {
+        for j in 0..SAMPLES_PER_AXIS {
+            for k in 0..SAMPLES_PER_AXIS {
+                let point = common.min + Vector3::new(i as f64 + 0.5, j as f64 + 0.5, k as f64 + 0.5).component_mul(&cell);
+                if body_contains(a, &point, tolerance) && body_contains(b, &point, tolerance) {
+                    inside += 1;
+                }
+            }
+        }
+    }
+    size.product() * inside as f64 / SAMPLES_PER_AXIS.pow(3) as f64
+}
+
+/// How two bodies interfere, if they do
+pub fn body_clash(bodies: (BodyId, BodyId), a: &BodyBvh, b: &BodyBvh, tolerance: &Tolerance) -> Option<Clash> {
+    let mut faces = Vec::new();
+    let mut triangles: Vec<[Vector3<f64>; 3]> = Vec::new();
+    for ta in a.triangles.overlapping(&b.bounds()) {
+        let crossing: Vec<_> = b.triangles.overlapping(&Aabb::from_points(&ta.corners)).into_iter().filter(|tb| triangles_cross(&ta.corners, &tb.corners, tolerance)).collect();
+        if crossing.is_empty() {
+            continue;
+        }
+        faces.push(ta.face);
+        triangles.push(ta.corners);
+        for tb in crossing {
+            faces.push(tb.face);
+            if !triangles.contains(&tb.corners) {
+                triangles.push(tb.corners);
+            }
+        }
+    }
+    let penetration_volume = penetration_volume(a, b, tolerance);
+    if triangles.is_empty() {
+        if penetration_volume <= 0.0 {
+            return None;
+        }
+        // No surfaces cross, so one body is inside the other: the one with the smaller bounds
+        let size = |body: &BodyBvh| (body.bounds().max - body.bounds().min).product();
+        let inner = if size(a) <= size(b) { a } else { b };
+        faces = inner.triangles.query(|_| true).into_iter().map(|t| t.face).collect();
+    }
+    faces.sort_unstable();
+    faces.dedup();
+    Some(Clash { bodies, faces, triangles, penetration_volume })
+}
+
+/// Every interfering pair of bodies, in id order
+pub fn find_clashes(scene: &SceneBvh, tolerance: &Tolerance) -> Vec<Clash> {
+    scene
+        .overlapping_bodies()
+        .into_iter()
+        .filter_map(|(a, b)| body_clash((a, b), scene.body(a)?, scene.body(b)?, tolerance))
+        .collect()
+}
+
+/// Result of the last clash check, kept current while `active`
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ClashReport {
+    pub active: bool,
+    pub clashes: Vec<Clash>,
+}
+
+impl ClashReport {
+    /// Whether a body clashes with any other
+    pub fn is_clashing(&self, body: BodyId) -> bool {
+        self.clashes.iter().any(|c| c.bodies.0 == body || c.bodies.1 == body)
+    }
+}
+
+/// Start checking for clashes, or stop and clear the report
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClashRequest {
+    Check,
+    Clear,
+}
+
+/// The clash check key starts a check, or clears one that is showing
+pub fn clash_keys(keys: Res<ButtonInput<KeyCode>>, bindings: Res<KeyBindings>, report: Res<ClashReport>, mut requests: EventWriter<ClashRequest>) {
+    if bindings.just_pressed("check_clashes", &keys) {
+        requests.write(if report.active { ClashRequest::Clear } else { ClashRequest::Check });
+    }
+}
+
+/// Run requested checks, and check again whenever the bodies change while active
+pub fn apply_clash_requests(mut requests: EventReader<ClashRequest>, scene: Res<SceneBvh>, model: Res<BrepModel>, mut report: ResMut<ClashReport>) {
+    let mut check = report.active && scene.is_changed();
+    let mut asked = false;
+    for request in requests.read() {
+        match request {
+            ClashRequest::Check => {
+                report.active = true;
+                check = true;
+                asked = true;
+            }
+            ClashRequest::Clear => {
+                report.active = false;
+                report.clashes.clear();
+                check = false;
+                journal("clear_clashes");
+            }
+        }
+    }
+    if !check {
+        return;
+    }
+    report.clashes = find_clashes(&scene, &model.tolerance);
+    if asked {
+        journal(format!("check_clashes {} found", report.clashes.len()));
+    }
+}
+
+/// Outline the clashing faces in red, with the crossing triangles fainter
+#[cfg(feature = "render")]
+pub fn render_clashes(mut gizmos: Gizmos, report: Res<ClashReport>, model: Res<BrepModel>) {
+    for clash in &report.clashes {
+        for face in &clash.faces {
+            for edge in item_edges(&model, &SelectionItem::Face(*face)) {
+                let ends = model.edge(edge).and_then(|e| Some((model.vertex_position(e.vertices.0)?, model.vertex_position(e.vertices.1)?)));
+                if let Some((a, b)) = ends {
+                    gizmos.line(na_vec3_to_bevy(&a), na_vec3_to_bevy(&b), RED);
+                }
+            }
+        }
+        for [a, b, c] in &clash.triangles {
+            gizmos.linestrip([a, b, c, a].map(na_vec3_to_bevy), RED.with_alpha(0.35));
+        }
+    }
+}
+
+/// Text of the clash panel
+#[cfg(feature = "render")]
+#[derive(Component, Debug)]
+pub struct ClashPanelText;
+
+/// Spawn the clash panel (bottom left, shown while a check is active)
+#[cfg(feature = "render")]
+pub fn spawn_clash_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(8.0),
+                bottom: Val::Px(8.0),
+                padding: UiRect::all(Val::Px(6.0)),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.2, 0.08, 0.08)),
+            UiPanel("clashes"),
+        ))
+        .with_child((Text::new(""), ClashPanelText));
+}
+
+/// List the clashing pairs and their overlap while a check is active
+#[cfg(feature = "render")]
+pub fn clash_panel_system(
+    report: Res<ClashReport>,
+    (bindings, properties, units): (Res<KeyBindings>, Option<Res<BodyPropertiesCollection>>, Option<Res<UnitSystem>>),
+    mut panels: Query<&mut Node, With<UiPanel>>,
+    mut texts: Query<(&mut Text, &ChildOf), With<ClashPanelText>>,
+) {
+    let Ok((mut text, parent)) = texts.single_mut() else { return };
+    let Ok(mut panel) = panels.get_mut(parent.parent()) else { return };
+    let display = if report.active { Display::Flex } else { Display::None };
+    if panel.display != display {
+        panel.display = display;
+    }
+    if !report.is_changed() || !report.active {
+        return;
+    }
+    let units = units.as_deref().cloned().unwrap_or_default();
+    let name = |id: BodyId| properties.as_ref().and_then(|p| p.get(id)).map_or_else(|| format!("Body {}", id.0), |p| p.name.clone());
+    let mut content = format!("Clashes ({} clears)\n", bindings.label("check_clashes"));
+    if report.clashes.is_empty() {
+        content.push_str("  none\n");
+    }
+    for clash in &report.clashes {
+        content.push_str(&format!("  {} × {}: {}\n", name(clash.bodies.0), name(clash.bodies.1), units.format_volume(clash.penetration_volume)));
+    }
+    if text.0 != content {
+        text.0 = content;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::cube;
+    use crate::testing::support::shifted;
+
+    fn scene_of(models: &[BrepModel]) -> SceneBvh {
+        let mut model = BrepModel::new();
+        for other in models {
+            model.append(other);
+        }
+        SceneBvh::build(&model)
+    }
+
+    #[test]
+    fn test_overlapping_bodies_clash_and_touching_ones_do_not() {
+        let scene = scene_of(&[cube(10.0), shifted(cube(10.0), Vector3::new(5.0, 5.0, 5.0)), shifted(cube(10.0), Vector3::new(-10.0, 0.0, 0.0))]);
+        let clashes = find_clashes(&scene, &Tolerance::default());
+        assert_eq!(clashes.len(), 1);
+        let clash = &clashes[0];
+        assert_eq!(clash.bodies, (BodyId(0), BodyId(1)));
+        assert!(!clash.triangles.is_empty() && !clash.faces.is_empty());
+        // The common corner is a 5 mm cube
+        assert!((clash.penetration_volume - 125.0).abs() < 1e-6, "{}", clash.penetration_volume);
+    }
+
+    #[test]
+    fn test_check_finds_enclosed_body_until_cleared() {
+        let mut model = cube(10.0);
+        model.append(&cube(2.0));
+        let mut app = App::new();
+        app.insert_resource(SceneBvh::build(&model))
+            .insert_resource(model)
+            .init_resource::<ClashReport>()
+            .add_event::<ClashRequest>()
+            .add_systems(Update, apply_clash_requests);
+        app.update();
+        assert!(app.world().resource::<ClashReport>().clashes.is_empty());
+
+        app.world_mut().send_event(ClashRequest::Check);
+        app.update();
+        let report = app.world().resource::<ClashReport>();
+        assert_eq!(report.clashes.len(), 1);
+        let clash = &report.clashes[0];
+        // No surfaces cross: the small cube is flagged whole
+        assert!(clash.triangles.is_empty());
+        assert_eq!(clash.faces.len(), 6);
+        assert!((clash.penetration_volume - 8.0).abs() < 1e-6);
+        assert!(report.is_clashing(BodyId(1)));
+
+        app.world_mut().send_event(ClashRequest::Clear);
+        app.update();
+        let report = app.world().resource::<ClashReport>();
+        assert!(!report.active && report.clashes.is_empty());
+    }
+}
@@ -0,0 +1,221 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: measure::distance
+//!
+//! Distances between picked vertices, edges and planar faces (centre to centre
+//! and closest approach), the angle between two edges or faces, and face area
+//! and perimeter. Faces count as their whole area, not just their boundary, so
+//! a vertex above a face is as far from it as it is from its plane.
+
+use nalgebra::{Point3, Vector2, Vector3};
+
+use crate::interaction::selection::SelectionItem;
+use crate::measure::angle::dihedral_angle;
+use crate::model::brep::geometry::polygon::contains_point_2d;
+use crate::model::brep::topology::edge_loop::EdgeLoop;
+use crate::model::brep_model::{area_vector, BrepModel};
+
+/// Closest points between segments `a0`-`a1` and `b0`-`b1`
+pub fn segment_closest_points(a0: &Vector3<f64>, a1: &Vector3<f64>, b0: &Vector3<f64>, b1: &Vector3<f64>) -> (Vector3<f64>, Vector3<f64>) {
+    let (d1, d2, r) = (a1 - a0, b1 - b0, a0 - b0);
+    let (a, e, f) = (d1.norm_squared(), d2.norm_squared(), d2.dot(&r));
+    let (c, b) = (d1.dot(&r), d1.dot(&d2));
+    let denom = a * e - b * b;
+    let mut s = if denom > f64::EPSILON { ((b * f - c * e) / denom).clamp(0.0, 1.0) } else { 0.0 };
+    let mut t = if e > f64::EPSILON { (b * s + f) / e } else { 0.0 };
+    if t < 0.0 {
+        t = 0.0;
+        s = if a > f64::EPSILON { (-c / a).clamp(0.0, 1.0) } else { 0.0 };
+    } else if t > 1.0 {
+        t = 1.0;
+        s = if a > f64::EPSILON { ((b - c) / a).clamp(0.0, 1.0) } else { 0.0 };
+    }
+    (a0 + d1 * s, b0 + d2 * t)
+}
+
+/// A picked element broken into what distances are measured against
+struct Shape {
+    /// Segments; a vertex is a segment of zero length
+    segments: Vec<(Vector3<f64>, Vector3<f64>)>,
+    /// Plane origin, unit normal and boundary loops of a face
+    face: Option<(Vector3<f64>, Vector3<f64>, Vec<Vec<Vector3<f64>>>)>,
+}
+
+fn shape(model: &BrepModel, item: &SelectionItem) -> Option<Shape> {
+    match *item {
+        SelectionItem::Vertex(id) => {
+            let p = model.vertex_position(id)?;
+            Some(Shape { segments: vec![(p, p)], face: None })
+        }
+        SelectionItem::Edge(id) => {
+            let e = model.edge(id)?;
+            Some(Shape { segments: vec![(model.vertex_position(e.vertices.0)?, model.vertex_position(e.vertices.1)?)], face: None })
+        }
+        SelectionItem::Face(id) => {
+            let face = model.face(id)?;
+            let normal = model.face_normal(face)?;
+            let loops: Vec<Vec<Vector3<f64>>> = model.face_loops(face).iter().map(|l| model.loop_positions(l)).collect();
+            let segments = loops.iter().flat_map(|l| (0..l.len()).map(|i| (l[i], l[(i + 1) % l.len()]))).collect();
+            Some(Shape { segments, face: Some((*loops.first()?.first()?, normal, loops)) })
+        }
+        SelectionItem::Body(_) => None,
+    }
+}
+
+/// True if `p`, taken to lie on the face plane, is inside the face (holes excluded)
+fn inside_face(p: &Vector3<f64>, origin: &Vector3<f64>, normal: &Vector3<f64>, loops: &[Vec<Vector3<f64>>]) -> bool {
+    let u = normal.cross(&if normal.x.abs() < 0.9 { Vector3::x() } else { Vector3::y() }).normalize();
+    let v = normal.cross(&u);
+    let to_2d = |q: &Vector3<f64>| Vector2::new((q - origin).dot(&u), (q - origin).dot(&v));
+    let p = to_2d(p);
+    loops.iter().filter(|l| contains_point_2d(&l.iter().map(to_2d).collect::<Vec<_>>(), &p)).count() % 2 == 1
+}
+
+/// Closest approach of a segment to a face interior, when it is not on the boundary
+fn segment_to_face(a: &Vector3<f64>, b: &Vector3<f64>, face: &(Vector3<f64>, Vector3<f64>, Vec<Vec<Vector3<f64>>>)) -> Option<(Vector3<f64>, Vector3<f64>)> {
+    let (origin, normal, loops) = face;
+    let (da, db) = ((a - origin).dot(normal), (b - origin).dot(normal));
+    if da * db < 0.0 {
+        // Crosses the plane: touching if it does so inside the face
+        let hit = a + (b - a) * (da / (da - db));
+        return inside_face(&hit, origin, normal, loops).then_some((hit, hit));
+    }
+    // Otherwise the end nearer the plane, dropped onto it
+    let (p, d) = if da.abs() <= db.abs() { (a, da) } else { (b, db) };
+    let foot = p - normal * d;
+    inside_face(&foot, origin, normal, loops).then_some((*p, foot))
+}
+
+/// Closest approach between two picked elements: the distance and the points on
+/// each that realise it
+pub fn min_distance(model: &BrepModel, a: &SelectionItem, b: &SelectionItem) -> Option<(f64, Vector3<f64>, Vector3<f64>)> {
+    let (sa, sb) = (shape(model, a)?, shape(model, b)?);
+    let mut pairs = Vec::new();
+    for (a0, a1) in &sa.segments {
+        for (b0, b1) in &sb.segments {
+            pairs.push(segment_closest_points(a0, a1, b0, b1));
+        }
+        if let Some(face) = &sb.face {
+            pairs.extend(segment_to_face(a0, a1, face));
+        }
+    }
+    if let Some(face) = &sa.face {
+        for (b0, b1) in &sb.segments {
+            pairs.extend(segment_to_face(b0, b1, face).map(|(q, p)| (p, q)));
+        }
+    }
+    pairs.into_iter().map(|(p, q)| ((p - q).norm(), p, q)).min_by(|x, y| x.0.total_cmp(&y.0))
+}
+
+/// Representative point of an element: a vertex, an edge midpoint or a face centroid
+pub fn reference_point(model: &BrepModel, item: &SelectionItem) -> Option<Vector3<f64>> {
+    match *item {
+        SelectionItem::Vertex(id) => model.vertex_position(id),
+        SelectionItem::Edge(id) => {
+            let e = model.edge(id)?;
+            Some((model.vertex_position(e.vertices.0)? + model.vertex_position(e.vertices.1)?) / 2.0)
+        }
+        SelectionItem::Face(id) => {
+            let outer = model.loop_positions(model.face_loops(model.face(id)?).first()?);
+            (!outer.is_empty()).then(|| outer.iter().sum::<Vector3<f64>>() / outer.len() as f64)
+        }
+        SelectionItem::Body(_) => None,
+    }
+}
+
+/// Unsigned angle between two directions, in [0, π]
+fn angle_between(a: &Vector3<f64>, b: &Vector3<f64>) -> f64 {
+    a.cross(b).norm().atan2(a.dot(b))
+}
+
+/// Angle between two edges (acute, between their lines), two faces (dihedral)
+/// or an edge and a face (between the edge and the face plane)
+pub fn item_angle(model: &BrepModel, a: &SelectionItem, b: &SelectionItem) -> Option<f64> {
+    let edge_dir = |id: usize| -> Option<Vector3<f64>> {
+        let e = model.edge(id)?;
+        let d = model.vertex_position(e.vertices.1)? - model.vertex_position(e.vertices.0)?;
+        (!model.tolerance.is_zero_length(d.norm())).then_some(d)
+    };
+    let normal = |id: usize| model.face(id).and_then(|f| model.face_normal(f));
+    let acute = |angle: f64| angle.min(std::f64::consts::PI - angle);
+    match (*a, *b) {
+        (SelectionItem::Edge(x), SelectionItem::Edge(y)) => Some(acute(angle_between(&edge_dir(x)?, &edge_dir(y)?))),
+        (SelectionItem::Face(x), SelectionItem::Face(y)) => dihedral_angle(model, x, y).map(|m| m.angle),
+        (SelectionItem::Edge(e), SelectionItem::Face(f)) | (SelectionItem::Face(f), SelectionItem::Edge(e)) => {
+            Some(std::f64::consts::FRAC_PI_2 - acute(angle_between(&edge_dir(e)?, &normal(f)?)))
+        }
+        _ => None,
+    }
+}
+
+/// Area of a planar face, less its holes
+pub fn face_area(model: &BrepModel, face_id: usize) -> Option<f64> {
+    let loops = model.face_loops(model.face(face_id)?);
+    let (outer, holes) = loops.split_first()?;
+    let area = |l: &&EdgeLoop| area_vector(&model.loop_positions(l)).norm();
+    Some(area(outer) - holes.iter().map(area).sum::<f64>())
+}
+
+/// Total length of a face's boundary, holes included
+pub fn face_perimeter(model: &BrepModel, face_id: usize) -> Option<f64> {
+    let face = model.face(face_id)?;
+    let lengths = model.face_edge_ids(face).into_iter().map(|id| edge_length(model, id));
+    lengths.sum()
+}
+
+/// Straight-line length of an edge
+pub fn edge_length(model: &BrepModel, edge_id: usize) -> Option<f64> {
+    let e = model.edge(edge_id)?;
+    Some((model.vertex_position(e.vertices.1)? - model.vertex_position(e.vertices.0)?).norm())
+}
+
+/// Midpoint of two points, for anchoring an annotation between them
+pub fn midpoint(a: &Vector3<f64>, b: &Vector3<f64>) -> Point3<f64> {
+    Point3::from((a + b) / 2.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::cube;
+
+    fn face_with_normal(model: &BrepModel, n: Vector3<f64>) -> usize {
+        model.faces.iter().find(|f| model.face_normal(f).is_some_and(|m| (m - n).norm() < 1e-9)).unwrap().id
+    }
+
+    #[test]
+    fn test_min_distance_and_angles() {
+        let mut model = cube(10.0);
+        let apex = model.add_vertex(Vector3::new(1.0, 2.0, 12.0));
+        let top = face_with_normal(&model, Vector3::z());
+        let side = face_with_normal(&model, Vector3::x());
+        let bottom = face_with_normal(&model, -Vector3::z());
+
+        // Above the top face: straight down onto its interior
+        let (d, p, q) = min_distance(&model, &SelectionItem::Vertex(apex), &SelectionItem::Face(top)).unwrap();
+        assert!((d - 7.0).abs() < 1e-9);
+        assert!((p - Vector3::new(1.0, 2.0, 12.0)).norm() < 1e-9 && (q - Vector3::new(1.0, 2.0, 5.0)).norm() < 1e-9);
+        let (d, ..) = min_distance(&model, &SelectionItem::Face(top), &SelectionItem::Face(bottom)).unwrap();
+        assert!((d - 10.0).abs() < 1e-9);
+        assert!(min_distance(&model, &SelectionItem::Face(top), &SelectionItem::Face(side)).unwrap().0 < 1e-9);
+
+        assert!((item_angle(&model, &SelectionItem::Face(top), &SelectionItem::Face(side)).unwrap() - std::f64::consts::FRAC_PI_2).abs() < 1e-9);
+        let vertical = model.edges.iter().find(|e| {
+            let (a, b) = (model.vertex_position(e.vertices.0).unwrap(), model.vertex_position(e.vertices.1).unwrap());
+            (a - b).cross(&Vector3::z()).norm() < 1e-9
+        });
+        let vertical = SelectionItem::Edge(vertical.unwrap().id);
+        assert!(item_angle(&model, &vertical, &SelectionItem::Face(side)).unwrap().abs() < 1e-9);
+        assert!((item_angle(&model, &vertical, &SelectionItem::Face(top)).unwrap() - std::f64::consts::FRAC_PI_2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_area_and_perimeter() {
+        let model = cube(10.0);
+        let top = face_with_normal(&model, Vector3::z());
+        assert!((face_area(&model, top).unwrap() - 100.0).abs() < 1e-9);
+        assert!((face_perimeter(&model, top).unwrap() - 40.0).abs() < 1e-9);
+        assert!((reference_point(&model, &SelectionItem::Face(top)).unwrap() - Vector3::new(0.0, 0.0, 5.0)).norm() < 1e-9);
+    }
+}
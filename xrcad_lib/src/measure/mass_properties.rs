@@ -0,0 +1,111 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: measure::mass_properties
+//!
+//! Volume, surface area, center of mass and inertia tensor of closed bodies, from
+//! the divergence theorem over the tessellated faces (unit density).
+
+use nalgebra::{Matrix3, Point3, Vector3};
+
+use crate::model::body::Body;
+use crate::model::brep::tessellate::tessellate;
+use crate::model::brep_model::BrepModel;
+use crate::model::properties::BodyProperties;
+
+/// Mass properties of a body at unit density
+#[derive(Debug, Clone, PartialEq)]
+pub struct MassProperties {
+    pub volume: f64,
+    pub surface_area: f64,
+    pub center_of_mass: Point3<f64>,
+    /// Inertia tensor about the center of mass, in model axes
+    pub inertia: Matrix3<f64>,
+}
+
+/// Integrate over the closed, outward-oriented shell of a model
+pub fn mass_properties(model: &BrepModel) -> MassProperties {
+    let mut volume = 0.0;
+    let mut surface_area = 0.0;
+    let mut first_moment = Vector3::zeros();
+    // Second moments about the origin: integral of x x^T over the solid
+    let mut second_moment = Matrix3::zeros();
+
+    for mesh in tessellate(model) {
+        for [a, b, c] in mesh.triangle_positions() {
+            surface_area += (b - a).cross(&(c - a)).norm() * 0.5;
+            // Signed tetrahedron (origin, a, b, c)
+            let det = a.dot(&b.cross(&c));
+            volume += det / 6.0;
+            let sum = a + b + c;
+            first_moment += sum * (det / 24.0);
+            second_moment += (a * a.transpose() + b * b.transpose() + c * c.transpose() + sum * sum.transpose())
+                * (det / 120.0);
+        }
+    }
+
+    let com = if model.tolerance.is_zero_length(volume) { Vector3::zeros() } else { first_moment / volume };
+    // Inertia about the origin, then shifted to the center of mass (parallel axis theorem)
+    let inertia_origin = Matrix3::identity() * second_moment.trace() - second_moment;
+    let shift = (Matrix3::identity() * com.norm_squared() - com * com.transpose()) * volume;
+    MassProperties {
+        volume,
+        surface_area,
+        center_of_mass: Point3::from(com),
+        inertia: inertia_origin - shift,
+    }
+}
+
+/// Compute a body's mass properties and store them in its properties
+pub fn compute_mass_properties(body: &Body, props: &mut BodyProperties) -> MassProperties {
+    let mp = mass_properties(&body.brep);
+    props.set_volume(mp.volume);
+    props.set_surface_area(mp.surface_area);
+    props.set_center_of_mass(mp.center_of_mass);
+    props.set_inertia_tensor(mp.inertia);
+    mp
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::body::BodyId;
+    use crate::model::brep::primitives::{cuboid, cylinder};
+    use crate::testing::support::shifted;
+
+    #[test]
+    fn test_cuboid_mass_properties() {
+        let body = Body::new(BodyId(0), cuboid(Vector3::new(2.0, 4.0, 6.0)));
+        let mut props = BodyProperties::new("Cube.001");
+        let mp = compute_mass_properties(&body, &mut props);
+        assert!((mp.volume - 48.0).abs() < 1e-9);
+        assert!((mp.surface_area - 88.0).abs() < 1e-9);
+        assert!(mp.center_of_mass.coords.norm() < 1e-9);
+        // I_xx = m (b^2 + c^2) / 12 for a box
+        let expected = Matrix3::from_diagonal(&Vector3::new(
+            48.0 * (16.0 + 36.0) / 12.0,
+            48.0 * (4.0 + 36.0) / 12.0,
+            48.0 * (4.0 + 16.0) / 12.0,
+        ));
+        assert!((mp.inertia - expected).norm() < 1e-9);
+        assert_eq!(props.volume, Some(mp.volume));
+        assert_eq!(props.inertia_tensor, Some(mp.inertia));
+    }
+
+    #[test]
+    fn test_offset_center_of_mass() {
+        let model = shifted(cuboid(Vector3::new(1.0, 1.0, 1.0)), Vector3::new(10.0, -2.0, 3.0));
+        let mp = mass_properties(&model);
+        assert!((mp.center_of_mass - Point3::new(10.0, -2.0, 3.0)).norm() < 1e-9);
+        // Inertia about the COM is translation invariant
+        assert!((mp.inertia - Matrix3::identity() / 6.0).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_faceted_cylinder_volume() {
+        let n = 32;
+        let mp = mass_properties(&cylinder(2.0, 5.0, n));
+        let expected = 0.5 * n as f64 * 4.0 * (std::f64::consts::TAU / n as f64).sin() * 5.0;
+        assert!((mp.volume - expected).abs() < 1e-9);
+    }
+}
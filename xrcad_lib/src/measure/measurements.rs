@@ -0,0 +1,119 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: measure::measurements
+//!
+//! Measurements kept with the document. Each record holds the measured value in
+//! model units (millimetres, radians, square millimetres), the topology it was
+//! taken from, where its annotation is anchored, and when it was taken, so the
+//! document can be audited later.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::ecs::resource::Resource;
+use nalgebra::Point3;
+use serde::{Deserialize, Serialize};
+
+use crate::interaction::selection::SelectionItem;
+
+/// What a measurement describes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MeasurementKind {
+    Distance,
+    /// Closest approach between two elements
+    MinDistance,
+    Angle,
+    Radius,
+    Diameter,
+    Area,
+    Perimeter,
+}
+
+/// Physical quantity of a measurement, which decides its unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quantity {
+    Length,
+    Angle,
+    Area,
+}
+
+impl MeasurementKind {
+    pub fn quantity(&self) -> Quantity {
+        match self {
+            MeasurementKind::Angle => Quantity::Angle,
+            MeasurementKind::Area => Quantity::Area,
+            _ => Quantity::Length,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            MeasurementKind::Distance => "Distance",
+            MeasurementKind::MinDistance => "Min Distance",
+            MeasurementKind::Angle => "Angle",
+            MeasurementKind::Radius => "Radius",
+            MeasurementKind::Diameter => "Diameter",
+            MeasurementKind::Area => "Area",
+            MeasurementKind::Perimeter => "Perimeter",
+        }
+    }
+}
+
+/// A stored measurement
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MeasurementRecord {
+    pub id: usize,
+    pub kind: MeasurementKind,
+    /// Value in model units
+    pub value: f64,
+    /// Elements measured, in pick order
+    pub references: Vec<SelectionItem>,
+    /// Model-space point the annotation is attached to
+    pub anchor: Point3<f64>,
+    /// Free-text note shown with the annotation
+    pub note: String,
+    /// Seconds since the Unix epoch when the measurement was taken
+    pub timestamp: u64,
+}
+
+/// Persistent measurements of a document
+#[derive(Resource, Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Measurements {
+    pub records: Vec<MeasurementRecord>,
+}
+
+impl Measurements {
+    /// Store a measurement taken now; returns its id
+    pub fn add(&mut self, kind: MeasurementKind, value: f64, references: Vec<SelectionItem>, anchor: Point3<f64>) -> usize {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        let id = self.records.iter().map(|r| r.id + 1).max().unwrap_or(0);
+        self.records.push(MeasurementRecord { id, kind, value, references, anchor, note: String::new(), timestamp });
+        id
+    }
+
+    pub fn get(&self, id: usize) -> Option<&MeasurementRecord> {
+        self.records.iter().find(|r| r.id == id)
+    }
+
+    pub fn remove(&mut self, id: usize) -> Option<MeasurementRecord> {
+        let index = self.records.iter().position(|r| r.id == id)?;
+        Some(self.records.remove(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_and_remove() {
+        let mut m = Measurements::default();
+        let a = m.add(MeasurementKind::Area, 100.0, vec![SelectionItem::Face(2)], Point3::origin());
+        let b = m.add(MeasurementKind::Angle, 1.0, vec![SelectionItem::Face(0), SelectionItem::Face(1)], Point3::origin());
+        assert_eq!((a, b), (0, 1));
+        assert!(m.get(a).unwrap().timestamp > 0);
+        assert_eq!(m.get(b).unwrap().kind.quantity(), Quantity::Angle);
+        assert!(m.remove(a).is_some());
+        assert_eq!(m.add(MeasurementKind::Distance, 5.0, Vec::new(), Point3::origin()), 2);
+    }
+}
@@ -0,0 +1,564 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: model::assembly
+//!
+//! Assemblies: components hold bodies and instances of other components, each
+//! instance placed by its own transform within the component that owns it, so
+//! the same part can be used many times and sub-assemblies nest. Mates relate
+//! plane and axis features of two sibling instances (coincident planes,
+//! concentric axes, planes a distance apart); the solver moves the instances
+//! that are not grounded until every mate holds. `solve_assembly` runs it
+//! whenever the assembly changes, and project files save the assembly.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use bevy::ecs::resource::Resource;
+use bevy::ecs::system::ResMut;
+use bevy::log::warn;
+use nalgebra::{Isometry3, Point3, Translation3, Unit, UnitQuaternion, Vector3};
+use serde::{Deserialize, Serialize};
+
+use crate::model::body::BodyId;
+use crate::model::tolerance::Tolerance;
+use crate::telemetry::crash::journal;
+
+/// Iteration limit for a single solve
+pub const MAX_ITERATIONS: usize = 50;
+
+/// Identifier of a component
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ComponentId(pub usize);
+
+/// Identifier of a component instance
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct InstanceId(pub usize);
+
+/// Identifier of a mate
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct MateId(pub usize);
+
+/// A part or sub-assembly definition
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Component {
+    pub id: ComponentId,
+    pub name: String,
+    pub bodies: Vec<BodyId>,
+    /// Instances placed inside this component
+    pub instances: Vec<InstanceId>,
+}
+
+/// One placement of a component inside another
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Instance {
+    pub id: InstanceId,
+    pub name: String,
+    pub component: ComponentId,
+    /// Component the instance is placed in
+    pub parent: ComponentId,
+    /// Placement relative to the parent component
+    pub transform: Isometry3<f64>,
+    /// Grounded instances are never moved by the solver
+    pub grounded: bool,
+}
+
+/// Mate geometry, in the frame of the instance's component
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum MateFeature {
+    Plane { point: Point3<f64>, normal: Vector3<f64> },
+    Axis { point: Point3<f64>, direction: Vector3<f64> },
+}
+
+impl MateFeature {
+    fn transformed(&self, t: &Isometry3<f64>) -> MateFeature {
+        match *self {
+            MateFeature::Plane { point, normal } => MateFeature::Plane { point: t * point, normal: (t * normal).normalize() },
+            MateFeature::Axis { point, direction } => MateFeature::Axis { point: t * point, direction: (t * direction).normalize() },
+        }
+    }
+
+    fn frame(&self) -> (Point3<f64>, Vector3<f64>) {
+        match *self {
+            MateFeature::Plane { point, normal } => (point, normal),
+            MateFeature::Axis { point, direction } => (point, direction),
+        }
+    }
+}
+
+/// A feature of a particular instance
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MateRef {
+    pub instance: InstanceId,
+    pub feature: MateFeature,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum MateKind {
+    /// Planes touch, facing each other
+    CoincidentPlane,
+    /// Axes lie on one line (either sense)
+    ConcentricAxis,
+    /// Planes face each other this far apart (model units)
+    Distance(f64),
+}
+
+impl MateKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            MateKind::CoincidentPlane => "Coincident",
+            MateKind::ConcentricAxis => "Concentric",
+            MateKind::Distance(_) => "Distance",
+        }
+    }
+
+    fn accepts(&self, feature: &MateFeature) -> bool {
+        matches!(
+            (self, feature),
+            (MateKind::CoincidentPlane | MateKind::Distance(_), MateFeature::Plane { .. }) | (MateKind::ConcentricAxis, MateFeature::Axis { .. })
+        )
+    }
+}
+
+/// A constraint between features of two instances of the same component
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Mate {
+    pub id: MateId,
+    pub kind: MateKind,
+    pub a: MateRef,
+    pub b: MateRef,
+}
+
+/// Why an assembly edit or solve was rejected
+#[derive(Debug, Clone, PartialEq)]
+pub enum AssemblyError {
+    UnknownComponent(ComponentId),
+    UnknownInstance(InstanceId),
+    UnknownMate(MateId),
+    /// The placement would make a component contain itself
+    Cycle,
+    /// The root component cannot be removed or placed
+    Root,
+    /// The mate kind does not apply to the given features
+    FeatureMismatch(MateKind),
+    /// Mated instances must be placed in the same component
+    NotSiblings(InstanceId, InstanceId),
+    /// No placement satisfying all mates was found; transforms are unchanged
+    NotConverged { residual: f64 },
+}
+
+impl fmt::Display for AssemblyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssemblyError::UnknownComponent(id) => write!(f, "no component with id {}", id.0),
+            AssemblyError::UnknownInstance(id) => write!(f, "no instance with id {}", id.0),
+            AssemblyError::UnknownMate(id) => write!(f, "no mate with id {}", id.0),
+            AssemblyError::Cycle => write!(f, "a component cannot be placed inside itself"),
+            AssemblyError::Root => write!(f, "the root assembly cannot be placed or removed"),
+            AssemblyError::FeatureMismatch(kind) => write!(f, "{} mates need matching plane or axis features", kind.label()),
+            AssemblyError::NotSiblings(a, b) => write!(f, "instances {} and {} are in different components", a.0, b.0),
+            AssemblyError::NotConverged { residual } => write!(f, "assembly did not converge (residual {:.3e})", residual),
+        }
+    }
+}
+
+impl std::error::Error for AssemblyError {}
+
+/// A component placed in the world through a chain of instances
+#[derive(Debug, Clone, PartialEq)]
+pub struct Occurrence {
+    /// Instances from the root down; empty for the root itself
+    pub path: Vec<InstanceId>,
+    pub component: ComponentId,
+    pub transform: Isometry3<f64>,
+}
+
+/// The assembly structure of a document
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+pub struct Assembly {
+    components: BTreeMap<ComponentId, Component>,
+    instances: BTreeMap<InstanceId, Instance>,
+    mates: BTreeMap<MateId, Mate>,
+    root: ComponentId,
+    next_id: usize,
+    pub tolerance: Tolerance,
+}
+
+impl Default for Assembly {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Assembly {
+    /// An assembly with an empty root component
+    pub fn new() -> Self {
+        let root = ComponentId(0);
+        let mut components = BTreeMap::new();
+        components.insert(root, Component { id: root, name: "Assembly".into(), bodies: Vec::new(), instances: Vec::new() });
+        Self { components, instances: BTreeMap::new(), mates: BTreeMap::new(), root, next_id: 1, tolerance: Tolerance::default() }
+    }
+
+    pub fn root(&self) -> ComponentId {
+        self.root
+    }
+    pub fn component(&self, id: ComponentId) -> Option<&Component> {
+        self.components.get(&id)
+    }
+    pub fn component_mut(&mut self, id: ComponentId) -> Option<&mut Component> {
+        self.components.get_mut(&id)
+    }
+    pub fn instance(&self, id: InstanceId) -> Option<&Instance> {
+        self.instances.get(&id)
+    }
+    pub fn instance_mut(&mut self, id: InstanceId) -> Option<&mut Instance> {
+        self.instances.get_mut(&id)
+    }
+    pub fn mate(&self, id: MateId) -> Option<&Mate> {
+        self.mates.get(&id)
+    }
+    pub fn components(&self) -> impl Iterator<Item = &Component> {
+        self.components.values()
+    }
+    pub fn instances(&self) -> impl Iterator<Item = &Instance> {
+        self.instances.values()
+    }
+    pub fn mates(&self) -> impl Iterator<Item = &Mate> {
+        self.mates.values()
+    }
+
+    fn next(&mut self) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    /// Define a new, empty component
+    pub fn create_component(&mut self, name: impl Into<String>) -> ComponentId {
+        let id = ComponentId(self.next());
+        self.components.insert(id, Component { id, name: name.into(), bodies: Vec::new(), instances: Vec::new() });
+        id
+    }
+
+    /// Put a body in a component, taking it out of any other
+    pub fn add_body(&mut self, component: ComponentId, body: BodyId) -> Result<(), AssemblyError> {
+        if !self.components.contains_key(&component) {
+            return Err(AssemblyError::UnknownComponent(component));
+        }
+        self.remove_body(body);
+        if let Some(c) = self.components.get_mut(&component) {
+            c.bodies.push(body);
+        }
+        Ok(())
+    }
+
+    /// Forget a deleted body
+    pub fn remove_body(&mut self, body: BodyId) {
+        for c in self.components.values_mut() {
+            c.bodies.retain(|b| *b != body);
+        }
+    }
+
+    /// True if `inner` is `outer` or is placed somewhere inside it
+    fn contains_component(&self, outer: ComponentId, inner: ComponentId) -> bool {
+        outer == inner
+            || self.components.get(&outer).is_some_and(|c| {
+                c.instances.iter().filter_map(|i| self.instances.get(i)).any(|i| self.contains_component(i.component, inner))
+            })
+    }
+
+    /// Place `component` inside `parent` at `transform`
+    pub fn add_instance(&mut self, parent: ComponentId, component: ComponentId, transform: Isometry3<f64>) -> Result<InstanceId, AssemblyError> {
+        for id in [parent, component] {
+            if !self.components.contains_key(&id) {
+                return Err(AssemblyError::UnknownComponent(id));
+            }
+        }
+        if component == self.root {
+            return Err(AssemblyError::Root);
+        }
+        if self.contains_component(component, parent) {
+            return Err(AssemblyError::Cycle);
+        }
+        let id = InstanceId(self.next());
+        let name = format!("{}:{}", self.components[&component].name, self.instances.values().filter(|i| i.component == component).count() + 1);
+        self.instances.insert(id, Instance { id, name, component, parent, transform, grounded: false });
+        if let Some(p) = self.components.get_mut(&parent) {
+            p.instances.push(id);
+        }
+        Ok(id)
+    }
+
+    /// Remove an instance and the mates that reference it
+    pub fn remove_instance(&mut self, id: InstanceId) -> Result<Instance, AssemblyError> {
+        let instance = self.instances.remove(&id).ok_or(AssemblyError::UnknownInstance(id))?;
+        if let Some(p) = self.components.get_mut(&instance.parent) {
+            p.instances.retain(|i| *i != id);
+        }
+        self.mates.retain(|_, m| m.a.instance != id && m.b.instance != id);
+        Ok(instance)
+    }
+
+    /// Remove a component, its instances everywhere, and the instances it holds
+    pub fn remove_component(&mut self, id: ComponentId) -> Result<Component, AssemblyError> {
+        if id == self.root {
+            return Err(AssemblyError::Root);
+        }
+        let component = self.components.remove(&id).ok_or(AssemblyError::UnknownComponent(id))?;
+        let placed: Vec<InstanceId> = self.instances.values().filter(|i| i.component == id || i.parent == id).map(|i| i.id).collect();
+        for instance in placed {
+            // Already gone if it was one of the removed component's own instances
+            let _ = self.remove_instance(instance);
+        }
+        Ok(component)
+    }
+
+    /// Add a mate between features of two sibling instances
+    pub fn add_mate(&mut self, kind: MateKind, a: MateRef, b: MateRef) -> Result<MateId, AssemblyError> {
+        let parent = |r: &MateRef| self.instances.get(&r.instance).map(|i| i.parent).ok_or(AssemblyError::UnknownInstance(r.instance));
+        if parent(&a)? != parent(&b)? {
+            return Err(AssemblyError::NotSiblings(a.instance, b.instance));
+        }
+        if !kind.accepts(&a.feature) || !kind.accepts(&b.feature) {
+            return Err(AssemblyError::FeatureMismatch(kind));
+        }
+        let id = MateId(self.next());
+        self.mates.insert(id, Mate { id, kind, a, b });
+        Ok(id)
+    }
+
+    pub fn remove_mate(&mut self, id: MateId) -> Result<Mate, AssemblyError> {
+        self.mates.remove(&id).ok_or(AssemblyError::UnknownMate(id))
+    }
+
+    /// Every placed component, walking down from the root
+    pub fn occurrences(&self) -> Vec<Occurrence> {
+        let mut out = Vec::new();
+        let mut stack = vec![Occurrence { path: Vec::new(), component: self.root, transform: Isometry3::identity() }];
+        while let Some(occurrence) = stack.pop() {
+            if let Some(c) = self.components.get(&occurrence.component) {
+                for instance in c.instances.iter().rev().filter_map(|i| self.instances.get(i)) {
+                    let mut path = occurrence.path.clone();
+                    path.push(instance.id);
+                    stack.push(Occurrence { path, component: instance.component, transform: occurrence.transform * instance.transform });
+                }
+            }
+            out.push(occurrence);
+        }
+        out
+    }
+
+    /// World placements of every body, once per occurrence of its component
+    pub fn body_placements(&self) -> Vec<(BodyId, Isometry3<f64>)> {
+        self.occurrences()
+            .iter()
+            .flat_map(|o| self.components.get(&o.component).into_iter().flat_map(|c| c.bodies.iter().map(|b| (*b, o.transform))))
+            .collect()
+    }
+
+    /// Feature of a mate reference in its instance's parent frame
+    fn placed_feature(&self, r: &MateRef) -> Option<MateFeature> {
+        self.instances.get(&r.instance).map(|i| r.feature.transformed(&i.transform))
+    }
+
+
+    /// Linear and angular error of a mate, in its instances' parent frame
+    pub fn mate_error(&self, mate: &Mate) -> Option<(f64, f64)> {
+        let ((pa, na), (pb, nb)) = (self.placed_feature(&mate.a)?.frame(), self.placed_feature(&mate.b)?.frame());
+        Some(match mate.kind {
+            MateKind::CoincidentPlane | MateKind::Distance(_) => {
+                let facing = nb.cross(&-na).norm().atan2(nb.dot(&-na));
+                (((pb - pa).dot(&na) - plane_offset(mate.kind)).abs(), facing)
+            }
+            MateKind::ConcentricAxis => {
+                let v = pb - pa;
+                ((v - na * v.dot(&na)).norm(), na.cross(&nb).norm().atan2(na.dot(&nb).abs()))
+            }
+        })
+    }
+
+    /// Move one instance of a mate (b unless it is grounded) so the mate holds
+    fn solve_mate(&mut self, mate: &Mate) {
+        let grounded = |r: &MateRef| self.instances.get(&r.instance).is_none_or(|i| i.grounded);
+        let (fixed, moving) = match (grounded(&mate.a), grounded(&mate.b)) {
+            (_, false) => (&mate.a, &mate.b),
+            (false, true) => (&mate.b, &mate.a),
+            (true, true) => return,
+        };
+        let (Some(f), Some(m)) = (self.placed_feature(fixed), self.placed_feature(moving)) else { return };
+        let ((pf, nf), (pm, nm)) = (f.frame(), m.frame());
+        // Turn the moving feature about its own point: planes face each other, axes keep their nearer sense
+        let target = match mate.kind {
+            MateKind::ConcentricAxis if nm.dot(&nf) >= 0.0 => nf,
+            _ => -nf,
+        };
+        let rotation = UnitQuaternion::rotation_between(&nm, &target).unwrap_or_else(|| {
+            let other = if nm.x.abs() < 0.9 { Vector3::x() } else { Vector3::y() };
+            UnitQuaternion::from_axis_angle(&Unit::new_normalize(nm.cross(&other)), std::f64::consts::PI)
+        });
+        // Then slide it onto the fixed plane or axis
+        let v = pm - pf;
+        let shift = match mate.kind {
+            MateKind::ConcentricAxis => -(v - nf * v.dot(&nf)),
+            kind => nf * (plane_offset(kind) - v.dot(&nf)),
+        };
+        if let Some(instance) = self.instances.get_mut(&moving.instance) {
+            instance.transform = Translation3::from(shift) * Isometry3::rotation_wrt_point(rotation, pm) * instance.transform;
+        }
+    }
+
+    /// Largest mate error, linear or angular; `None` once all are within tolerance
+    fn residual(&self, mates: &[Mate]) -> Option<f64> {
+        let errors: Vec<(f64, f64)> = mates.iter().filter_map(|m| self.mate_error(m)).collect();
+        let tol = self.tolerance;
+        let worst = errors.iter().fold(0.0f64, |acc, (l, a)| acc.max(*l).max(*a));
+        errors.iter().any(|(l, a)| *l > tol.linear || *a > tol.angular).then_some(worst)
+    }
+
+    /// Position the instances so every mate holds. Mates are satisfied one at a
+    /// time, moving the instance that is not grounded, and the pass repeats until
+    /// all hold together. Returns the number of passes taken.
+    pub fn solve(&mut self) -> Result<usize, AssemblyError> {
+        let original: Vec<(InstanceId, Isometry3<f64>)> = self.instances.values().map(|i| (i.id, i.transform)).collect();
+        let mates: Vec<Mate> = self.mates.values().cloned().collect();
+        for iteration in 0..MAX_ITERATIONS {
+            if self.residual(&mates).is_none() {
+                return Ok(iteration);
+            }
+            for mate in &mates {
+                self.solve_mate(mate);
+            }
+        }
+        let Some(residual) = self.residual(&mates) else { return Ok(MAX_ITERATIONS) };
+        for (id, transform) in original {
+            if let Some(i) = self.instances.get_mut(&id) {
+                i.transform = transform;
+            }
+        }
+        Err(AssemblyError::NotConverged { residual })
+    }
+}
+
+/// Re-solve the mates after the assembly changed. A solve that moves nothing
+/// leaves the resource unchanged, so it does not run again the next frame.
+pub fn solve_assembly(mut assembly: ResMut<Assembly>) {
+    if !assembly.is_changed() || assembly.mates.is_empty() {
+        return;
+    }
+    match assembly.bypass_change_detection().solve() {
+        Ok(0) => {}
+        Ok(passes) => {
+            journal(format!("solve_assembly {} passes", passes));
+            assembly.set_changed();
+        }
+        Err(err) => warn!("Assembly mates do not hold: {}", err),
+    }
+}
+
+/// Signed gap a plane mate asks for
+fn plane_offset(kind: MateKind) -> f64 {
+    match kind {
+        MateKind::Distance(d) => d,
+        _ => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plane(point: [f64; 3], normal: [f64; 3]) -> MateFeature {
+        MateFeature::Plane { point: Point3::from(point), normal: Vector3::from(normal) }
+    }
+
+    fn axis(point: [f64; 3], direction: [f64; 3]) -> MateFeature {
+        MateFeature::Axis { point: Point3::from(point), direction: Vector3::from(direction) }
+    }
+
+    #[test]
+    fn test_components_and_instances() {
+        let mut asm = Assembly::new();
+        let wheel = asm.create_component("Wheel");
+        let axle = asm.create_component("Axle");
+        asm.add_body(wheel, BodyId(1)).unwrap();
+        asm.add_body(axle, BodyId(2)).unwrap();
+        let shift = |x: f64| Isometry3::translation(x, 0.0, 0.0);
+        let w1 = asm.add_instance(axle, wheel, shift(-5.0)).unwrap();
+        asm.add_instance(axle, wheel, shift(5.0)).unwrap();
+        let a = asm.add_instance(asm.root(), axle, shift(100.0)).unwrap();
+        assert_eq!(asm.add_instance(wheel, axle, shift(0.0)), Err(AssemblyError::Cycle));
+        assert_eq!(asm.add_instance(axle, asm.root(), shift(0.0)), Err(AssemblyError::Root));
+        assert_eq!(asm.instance(w1).unwrap().name, "Wheel:1");
+
+        assert_eq!(asm.occurrences().len(), 4);
+        let mut wheels: Vec<f64> = asm.body_placements().iter().filter(|(b, _)| *b == BodyId(1)).map(|(_, t)| t.translation.x).collect();
+        wheels.sort_by(f64::total_cmp);
+        assert_eq!(wheels, vec![95.0, 105.0]);
+
+        let mate = asm.add_mate(MateKind::ConcentricAxis, MateRef { instance: w1, feature: axis([0.0; 3], [1.0, 0.0, 0.0]) }, MateRef { instance: a, feature: axis([0.0; 3], [1.0, 0.0, 0.0]) });
+        assert_eq!(mate, Err(AssemblyError::NotSiblings(w1, a)));
+        asm.remove_component(wheel).unwrap();
+        assert_eq!(asm.instances().count(), 1);
+        assert!(asm.component(axle).unwrap().instances.is_empty());
+    }
+
+    #[test]
+    fn test_solve_mates() {
+        let mut asm = Assembly::new();
+        let block = asm.create_component("Block");
+        let plate = asm.create_component("Plate");
+        let base = asm.add_instance(asm.root(), block, Isometry3::identity()).unwrap();
+        asm.instance_mut(base).unwrap().grounded = true;
+        let start = Isometry3::new(Vector3::new(7.0, -3.0, 20.0), Vector3::new(0.5, 0.2, 1.0));
+        let top = asm.add_instance(asm.root(), plate, start).unwrap();
+
+        // Plate underside on the block's top face, its pin in the block's hole
+        let on_top = MateRef { instance: base, feature: plane([0.0, 0.0, 5.0], [0.0, 0.0, 1.0]) };
+        let underside = MateRef { instance: top, feature: plane([0.0, 0.0, -1.0], [0.0, 0.0, -1.0]) };
+        assert_eq!(asm.add_mate(MateKind::ConcentricAxis, on_top, underside), Err(AssemblyError::FeatureMismatch(MateKind::ConcentricAxis)));
+        asm.add_mate(MateKind::CoincidentPlane, on_top, underside).unwrap();
+        let hole = MateRef { instance: base, feature: axis([2.0, 0.0, 0.0], [0.0, 0.0, 1.0]) };
+        let pin = MateRef { instance: top, feature: axis([0.0, 0.0, 0.0], [0.0, 0.0, -1.0]) };
+        let concentric = asm.add_mate(MateKind::ConcentricAxis, hole, pin).unwrap();
+        assert!(asm.solve().is_ok());
+        let t = asm.instance(top).unwrap().transform;
+        assert!((t * Point3::new(0.0, 0.0, -1.0) - Point3::new(2.0, 0.0, 5.0)).norm() < 1e-6);
+        assert!(asm.mates().all(|m| asm.mate_error(m).is_some_and(|(l, a)| l < 1e-6 && a < 1e-6)));
+        assert_eq!(asm.instance(base).unwrap().transform, Isometry3::identity());
+
+        // Lift the plate 3 above the block instead of onto it
+        asm.remove_mate(concentric).unwrap();
+        let mate = asm.mates().next().unwrap().clone();
+        asm.remove_mate(mate.id).unwrap();
+        asm.add_mate(MateKind::Distance(3.0), mate.a, mate.b).unwrap();
+        asm.solve().unwrap();
+        let t = asm.instance(top).unwrap().transform;
+        assert!(((t * Point3::new(0.0, 0.0, -1.0)).z - 8.0).abs() < 1e-6);
+
+        // Nothing can move: the solve fails and leaves the plate where it was
+        asm.instance_mut(top).unwrap().grounded = true;
+        asm.instance_mut(top).unwrap().transform = start;
+        assert!(matches!(asm.solve(), Err(AssemblyError::NotConverged { .. })));
+        assert_eq!(asm.instance(top).unwrap().transform, start);
+    }
+
+    #[test]
+    fn test_mates_solve_when_the_assembly_changes() {
+        use bevy::prelude::{App, Update};
+
+        let mut asm = Assembly::new();
+        let (block, plate) = (asm.create_component("Block"), asm.create_component("Plate"));
+        let base = asm.add_instance(asm.root(), block, Isometry3::identity()).unwrap();
+        asm.instance_mut(base).unwrap().grounded = true;
+        let top = asm.add_instance(asm.root(), plate, Isometry3::translation(0.0, 0.0, 20.0)).unwrap();
+        let on_top = MateRef { instance: base, feature: plane([0.0, 0.0, 5.0], [0.0, 0.0, 1.0]) };
+        let underside = MateRef { instance: top, feature: plane([0.0, 0.0, 0.0], [0.0, 0.0, -1.0]) };
+        asm.add_mate(MateKind::CoincidentPlane, on_top, underside).unwrap();
+
+        let mut app = App::new();
+        app.insert_resource(asm).add_systems(Update, solve_assembly);
+        app.update();
+        let t = app.world().resource::<Assembly>().instance(top).unwrap().transform;
+        assert!((t.translation.vector.z - 5.0).abs() < 1e-6);
+    }
+}
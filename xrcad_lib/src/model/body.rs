@@ -0,0 +1,88 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: model::body
+
+use nalgebra::Vector3;
+use serde::{Deserialize, Serialize};
+
+use crate::model::brep::classify::{classify_point_in_solid, PointClassification};
+use crate::model::brep_model::BrepModel;
+
+/// Identifier of a body within a document
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct BodyId(pub usize);
+
+/// Hands out body ids in increasing order. An id is never handed out twice, so
+/// a deleted body's id does not come back as another body.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdGenerator {
+    next: usize,
+}
+
+impl IdGenerator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The id `generate` hands out next
+    pub fn peek(&self) -> BodyId {
+        BodyId(self.next)
+    }
+
+    pub fn generate(&mut self) -> BodyId {
+        self.next += 1;
+        BodyId(self.next - 1)
+    }
+
+    /// Never hand out `id` or an id below it
+    pub fn reserve(&mut self, id: BodyId) {
+        self.next = self.next.max(id.0 + 1);
+    }
+}
+
+/// A body: a BREP topology container with an identity
+#[derive(Debug, Default, Clone)]
+pub struct Body {
+    pub id: BodyId,
+    pub brep: BrepModel,
+}
+
+impl Body {
+    pub fn new(id: BodyId, brep: BrepModel) -> Self {
+        Self { id, brep }
+    }
+
+    /// Classify a point against the closed shell of this body
+    pub fn classify(&self, point: &Vector3<f64>) -> PointClassification {
+        classify_point_in_solid(&self.brep, point)
+    }
+
+    /// True if the point is inside the body or on its boundary
+    pub fn contains_point(&self, point: &Vector3<f64>) -> bool {
+        matches!(self.classify(point), PointClassification::Inside | PointClassification::OnBoundary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::cube;
+
+    #[test]
+    fn test_id_generator_never_repeats() {
+        let mut ids = IdGenerator::new();
+        assert_eq!((ids.generate(), ids.generate()), (BodyId(0), BodyId(1)));
+        ids.reserve(BodyId(5));
+        ids.reserve(BodyId(3));
+        assert_eq!((ids.peek(), ids.generate()), (BodyId(6), BodyId(6)));
+    }
+
+    #[test]
+    fn test_body_contains_point() {
+        let body = Body::new(BodyId(1), cube(10.0));
+        assert!(body.contains_point(&Vector3::new(1.0, 2.0, 3.0)));
+        assert!(body.contains_point(&Vector3::new(5.0, 0.0, 0.0)));
+        assert!(!body.contains_point(&Vector3::new(6.0, 0.0, 0.0)));
+    }
+}
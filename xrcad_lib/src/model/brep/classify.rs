@@ -0,0 +1,176 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: brep::classify
+//!
+//! Point classification against faces (parity over all loops, so holes work) and
+//! closed solids (ray casting), used by booleans, selection and measurement.
+
+use nalgebra::{Point3, Vector2, Vector3};
+
+use crate::model::brep::geometry::polygon::{boundary_distance_2d, contains_point_2d};
+use crate::model::brep::topology::face::Face;
+use crate::model::brep_model::BrepModel;
+
+/// Where a point lies relative to a face or solid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointClassification {
+    Inside,
+    Outside,
+    OnBoundary,
+    /// Every ray cast from the point grazed an edge or vertex of the solid
+    Indeterminate,
+}
+
+/// Ray directions tried in turn when a cast grazes an edge or vertex
+const RAY_DIRECTIONS: [[f64; 3]; 4] = [
+    [1.0, 0.37, 0.23],
+    [0.13, 1.0, 0.61],
+    [0.53, 0.17, 1.0],
+    [-0.71, 0.43, -0.29],
+];
+/// Directions spread over the sphere that are tried once all of `RAY_DIRECTIONS` graze
+const PERTURBED_RAYS: usize = 32;
+
+/// The `k`th of `PERTURBED_RAYS` unit directions on a Fibonacci sphere
+fn perturbed_direction(k: usize) -> Vector3<f64> {
+    let golden_angle = std::f64::consts::PI * (3.0 - 5f64.sqrt());
+    let z = 1.0 - (k as f64 + 0.5) * 2.0 / PERTURBED_RAYS as f64;
+    let r = (1.0 - z * z).sqrt();
+    let a = golden_angle * k as f64;
+    Vector3::new(r * a.cos(), r * a.sin(), z)
+}
+
+/// Classify a point within the projected region of a face, ignoring distance to its plane
+fn classify_in_face_region(model: &BrepModel, face: &Face, point: &Vector3<f64>) -> Option<PointClassification> {
+    let plane = model.face_plane(face)?;
+    let project = |p: &Vector3<f64>| plane.project_2d(&Point3::from(*p));
+    let loops: Vec<Vec<Vector2<f64>>> = model
+        .face_loops(face)
+        .into_iter()
+        .map(|l| model.loop_positions(l).iter().map(project).collect())
+        .collect();
+    let p2 = project(point);
+    if loops.iter().any(|l| model.tolerance.is_zero_length(boundary_distance_2d(l, &p2))) {
+        return Some(PointClassification::OnBoundary);
+    }
+    // Even-odd over all loops: holes flip the parity back to outside
+    let crossings = loops.iter().filter(|l| contains_point_2d(l, &p2)).count();
+    Some(if crossings % 2 == 1 { PointClassification::Inside } else { PointClassification::Outside })
+}
+
+/// Classify a point against a planar face: off-plane points are outside
+pub fn classify_point_on_face(model: &BrepModel, face: &Face, point: &Vector3<f64>) -> PointClassification {
+    let Some(plane) = model.face_plane(face) else { return PointClassification::Outside; };
+    if !plane.contains_point(&Point3::from(*point), &model.tolerance) {
+        return PointClassification::Outside;
+    }
+    classify_in_face_region(model, face, point).unwrap_or(PointClassification::Outside)
+}
+
+/// Count ray crossings through faces; `None` if the ray grazes a boundary
+fn count_crossings(model: &BrepModel, origin: &Vector3<f64>, dir: &Vector3<f64>) -> Option<usize> {
+    let tol = &model.tolerance;
+    let mut count = 0;
+    for face in &model.faces {
+        let Some(plane) = model.face_plane(face) else { continue; };
+        let denom = plane.normal.dot(dir);
+        if tol.perpendicular(&plane.normal, dir) {
+            continue;
+        }
+        let t = -plane.distance(&Point3::from(*origin)) / denom;
+        if t <= tol.linear {
+            continue;
+        }
+        match classify_in_face_region(model, face, &(origin + dir * t)) {
+            Some(PointClassification::Inside) => count += 1,
+            Some(PointClassification::OnBoundary) => return None,
+            _ => {}
+        }
+    }
+    Some(count)
+}
+
+/// Classify a point against the closed shell formed by all faces of the model.
+/// A ray that grazes a boundary is cast again in other directions; a point for
+/// which every ray grazes is `Indeterminate` rather than guessed.
+pub fn classify_point_in_solid(model: &BrepModel, point: &Vector3<f64>) -> PointClassification {
+    if model
+        .faces
+        .iter()
+        .any(|f| classify_point_on_face(model, f, point) != PointClassification::Outside)
+    {
+        return PointClassification::OnBoundary;
+    }
+    let fixed = RAY_DIRECTIONS.iter().map(|d| Vector3::new(d[0], d[1], d[2]).normalize());
+    let perturbed = (0..PERTURBED_RAYS).map(perturbed_direction);
+    match fixed.chain(perturbed).find_map(|dir| count_crossings(model, point, &dir)) {
+        Some(n) if n % 2 == 1 => PointClassification::Inside,
+        Some(_) => PointClassification::Outside,
+        None => PointClassification::Indeterminate,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::cube;
+
+    #[test]
+    fn test_classify_in_cube() {
+        let c = cube(2.0);
+        assert_eq!(classify_point_in_solid(&c, &Vector3::zeros()), PointClassification::Inside);
+        assert_eq!(classify_point_in_solid(&c, &Vector3::new(0.9, -0.9, 0.5)), PointClassification::Inside);
+        assert_eq!(classify_point_in_solid(&c, &Vector3::new(3.0, 0.0, 0.0)), PointClassification::Outside);
+        assert_eq!(classify_point_in_solid(&c, &Vector3::new(1.0, 0.2, 0.3)), PointClassification::OnBoundary);
+        assert_eq!(classify_point_in_solid(&c, &Vector3::new(1.0, 1.0, 1.0)), PointClassification::OnBoundary);
+    }
+
+    #[test]
+    fn test_classify_along_cube_diagonal() {
+        let c = cube(2.0);
+        assert_eq!(classify_point_in_solid(&c, &Vector3::new(0.5, 0.5, 0.5)), PointClassification::Inside);
+        assert_eq!(classify_point_in_solid(&c, &Vector3::new(-2.0, -2.0, -2.0)), PointClassification::Outside);
+    }
+
+    #[test]
+    fn test_classify_retries_grazing_ray() {
+        // The first ray direction from this point runs through the cube's edge at (1, 1, 0.5)
+        let c = cube(2.0);
+        let first = Vector3::new(1.0, 0.37, 0.23).normalize();
+        assert_eq!(count_crossings(&c, &Vector3::new(0.5, 0.815, 0.385), &first), None);
+        assert_eq!(classify_point_in_solid(&c, &Vector3::new(0.5, 0.815, 0.385)), PointClassification::Inside);
+        for k in 0..PERTURBED_RAYS {
+            assert!((perturbed_direction(k).norm() - 1.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_face_with_hole() {
+        let mut m = BrepModel::new();
+        let f = m.add_face(&[
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(4.0, 0.0, 0.0),
+            Vector3::new(4.0, 4.0, 0.0),
+            Vector3::new(0.0, 4.0, 0.0),
+        ]);
+        let hole: Vec<usize> = [
+            Vector3::new(1.0, 1.0, 0.0),
+            Vector3::new(1.0, 3.0, 0.0),
+            Vector3::new(3.0, 3.0, 0.0),
+            Vector3::new(3.0, 1.0, 0.0),
+        ]
+        .iter()
+        .map(|p| m.add_vertex(*p))
+        .collect();
+        let edges = (0..4).map(|i| m.add_edge(hole[i], hole[(i + 1) % 4])).collect();
+        let l = m.add_edge_loop(edges);
+        m.face_mut(f).unwrap().edge_loops.push(l);
+
+        let face = m.face(f).unwrap().clone();
+        assert_eq!(classify_point_on_face(&m, &face, &Vector3::new(0.5, 0.5, 0.0)), PointClassification::Inside);
+        assert_eq!(classify_point_on_face(&m, &face, &Vector3::new(2.0, 2.0, 0.0)), PointClassification::Outside);
+        assert_eq!(classify_point_on_face(&m, &face, &Vector3::new(1.0, 2.0, 0.0)), PointClassification::OnBoundary);
+        assert_eq!(classify_point_on_face(&m, &face, &Vector3::new(0.5, 0.5, 1.0)), PointClassification::Outside);
+    }
+}
@@ -0,0 +1,231 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: model::brep::constraints::planarity
+//!
+//! Keeping faces planar during direct vertex edits. The tessellator assumes
+//! planar faces, so a dragged vertex can either be held to the subspace where
+//! every face around it stays planar (the planes through each face's other
+//! vertices), or be moved freely while its faces shift parallel to themselves
+//! to follow it, carrying their other vertices with them by the shortest
+//! distance. Faces further out act as fixed constraints on those vertices.
+
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use nalgebra::{DMatrix, DVector, Vector3};
+
+use crate::input::keyboard::KeyBindings;
+use crate::model::brep::topology::face::Face;
+use crate::model::brep_model::{area_vector, BrepModel};
+use crate::model::tolerance::Tolerance;
+use crate::telemetry::crash::journal;
+
+/// How vertex drags treat the faces around the vertex
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PlanarityMode {
+    /// Move the vertex alone; faces may end up non-planar
+    #[default]
+    Off,
+    /// Restrict the motion so neighbouring faces stay planar
+    Constrain,
+    /// Move the vertex freely and offset neighbouring faces to follow it
+    Propagate,
+}
+
+impl PlanarityMode {
+    pub fn next(self) -> Self {
+        match self {
+            PlanarityMode::Off => PlanarityMode::Constrain,
+            PlanarityMode::Constrain => PlanarityMode::Propagate,
+            PlanarityMode::Propagate => PlanarityMode::Off,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PlanarityMode::Off => "",
+            PlanarityMode::Constrain => "keep planar",
+            PlanarityMode::Propagate => "move faces",
+        }
+    }
+}
+
+/// Planarity handling for vertex edits
+#[derive(Resource, Debug, Clone, Default, PartialEq)]
+pub struct PlanarEdit {
+    pub mode: PlanarityMode,
+}
+
+/// A plane as unit normal `n` and offset `d`, holding the points with `n . p = d`
+type Support = (Vector3<f64>, f64);
+
+/// Plane through a face's outer loop, leaving out one vertex
+fn face_support(model: &BrepModel, face: &Face, exclude: Option<usize>) -> Option<Support> {
+    let outer = model.face_loops(face).into_iter().next()?;
+    let points: Vec<Vector3<f64>> = model
+        .loop_vertex_ids(outer)
+        .into_iter()
+        .filter(|id| Some(*id) != exclude)
+        .filter_map(|id| model.vertex_position(id))
+        .collect();
+    let area = area_vector(&points);
+    if points.len() < 3 || model.tolerance.is_zero_length(area.norm()) {
+        return None;
+    }
+    let normal = area.normalize();
+    let centroid = points.iter().sum::<Vector3<f64>>() / points.len() as f64;
+    Some((normal, normal.dot(&centroid)))
+}
+
+/// Whether a face's outer loop lies on one plane, within the linear tolerance.
+/// A face too small to have a plane counts as planar.
+pub fn is_face_planar(model: &BrepModel, face: &Face) -> bool {
+    let Some((normal, d)) = face_support(model, face, None) else { return true };
+    let outer = model.face_loops(face).into_iter().next();
+    outer.is_none_or(|l| model.loop_positions(l).iter().all(|p| (normal.dot(p) - d).abs() <= model.tolerance.linear))
+}
+
+/// Nearest point to `p` lying on all the planes (least squares if they disagree).
+/// Plane directions that differ by less than the linear tolerance count as one.
+pub fn project_onto_planes(p: &Vector3<f64>, planes: &[Support], tolerance: &Tolerance) -> Vector3<f64> {
+    if planes.is_empty() {
+        return *p;
+    }
+    let n = DMatrix::from_fn(planes.len(), 3, |i, j| planes[i].0[j]);
+    let residual = DVector::from_iterator(planes.len(), planes.iter().map(|(normal, d)| d - normal.dot(p)));
+    match n.pseudo_inverse(tolerance.linear) {
+        Ok(inverse) => {
+            let delta = inverse * residual;
+            p + Vector3::new(delta[0], delta[1], delta[2])
+        }
+        Err(_) => *p,
+    }
+}
+
+/// Where the vertex may go towards `target` while its faces stay planar
+pub fn constrained_position(model: &BrepModel, vertex_id: usize, target: &Vector3<f64>) -> Vector3<f64> {
+    let planes: Vec<Support> = model
+        .faces_using_vertex(vertex_id)
+        .into_iter()
+        .filter_map(|id| model.face(id))
+        .filter_map(|face| face_support(model, face, Some(vertex_id)))
+        .collect();
+    project_onto_planes(target, &planes, &model.tolerance)
+}
+
+/// Vertex moves that take the vertex to `target` and keep every face planar:
+/// its faces are offset through `target` and their other vertices projected
+/// onto the moved planes. The dragged vertex comes first.
+pub fn propagated_moves(model: &BrepModel, vertex_id: usize, target: &Vector3<f64>) -> Vec<(usize, Vector3<f64>)> {
+    let offset: BTreeMap<usize, Support> = model
+        .faces_using_vertex(vertex_id)
+        .into_iter()
+        .filter_map(|id| {
+            let normal = model.face_normal(model.face(id)?)?;
+            Some((id, (normal, normal.dot(target))))
+        })
+        .collect();
+    let neighbours: Vec<usize> = {
+        let mut ids: Vec<usize> = offset
+            .keys()
+            .filter_map(|id| model.face(*id))
+            .filter_map(|f| model.face_loops(f).into_iter().next())
+            .flat_map(|l| model.loop_vertex_ids(l))
+            .filter(|id| *id != vertex_id)
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    };
+    let mut moves = vec![(vertex_id, *target)];
+    for id in neighbours {
+        let Some(position) = model.vertex_position(id) else { continue };
+        let planes: Vec<Support> = model
+            .faces_using_vertex(id)
+            .into_iter()
+            .filter_map(|face_id| offset.get(&face_id).copied().or_else(|| face_support(model, model.face(face_id)?, None)))
+            .collect();
+        moves.push((id, project_onto_planes(&position, &planes, &model.tolerance)));
+    }
+    moves
+}
+
+impl PlanarEdit {
+    /// Vertex moves for dragging `vertex_id` towards `target` in the current mode
+    pub fn moves(&self, model: &BrepModel, vertex_id: usize, target: &Vector3<f64>) -> Vec<(usize, Vector3<f64>)> {
+        match self.mode {
+            PlanarityMode::Off => vec![(vertex_id, *target)],
+            PlanarityMode::Constrain => vec![(vertex_id, constrained_position(model, vertex_id, target))],
+            PlanarityMode::Propagate => propagated_moves(model, vertex_id, target),
+        }
+    }
+}
+
+/// Request to change how vertex drags treat faces
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetPlanarityMode(pub PlanarityMode);
+
+/// The planarity_mode binding (K) cycles the planarity mode
+pub fn planar_edit_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    edit: Res<PlanarEdit>,
+    mut requests: EventWriter<SetPlanarityMode>,
+) {
+    if bindings.just_pressed("planarity_mode", &keys) {
+        requests.write(SetPlanarityMode(edit.mode.next()));
+    }
+}
+
+pub fn apply_planar_edit_requests(mut requests: EventReader<SetPlanarityMode>, mut edit: ResMut<PlanarEdit>) {
+    for SetPlanarityMode(mode) in requests.read() {
+        journal(format!("planarity_mode {:?}", mode));
+        edit.mode = *mode;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::cube;
+
+    fn corner(model: &BrepModel) -> usize {
+        let (_, hi) = model.bounds().unwrap();
+        model.find_vertex_at(&hi).unwrap()
+    }
+
+    fn all_planar(model: &BrepModel) -> bool {
+        model.faces.iter().all(|face| is_face_planar(model, face))
+    }
+
+    #[test]
+    fn test_constrained_position() {
+        // A box corner is held by three faces
+        let model = cube(10.0);
+        let id = corner(&model);
+        let start = model.vertex_position(id).unwrap();
+        assert!((constrained_position(&model, id, &(start + Vector3::new(3.0, -1.0, 2.0))) - start).norm() < 1e-9);
+
+        // A lone quad lets the vertex slide within its plane only
+        let mut quad = BrepModel::new();
+        quad.add_face(&[Vector3::zeros(), Vector3::x(), Vector3::new(1.0, 1.0, 0.0), Vector3::y()]);
+        let moved = constrained_position(&quad, 2, &Vector3::new(2.0, 3.0, 4.0));
+        assert!((moved - Vector3::new(2.0, 3.0, 0.0)).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_propagated_moves_keep_faces_planar() {
+        let mut model = cube(10.0);
+        let id = corner(&model);
+        let (lo, hi) = model.bounds().unwrap();
+        let moves = PlanarEdit { mode: PlanarityMode::Propagate }.moves(&model, id, &(hi + Vector3::new(1.0, 2.0, 3.0)));
+        assert_eq!(moves.len(), 7);
+        for (v, p) in moves {
+            model.vertices.iter_mut().find(|x| x.id == v).unwrap().position = p;
+        }
+        assert!(all_planar(&model));
+        let (new_lo, new_hi) = model.bounds().unwrap();
+        assert!((new_lo - lo).norm() < 1e-9 && (new_hi - hi - Vector3::new(1.0, 2.0, 3.0)).norm() < 1e-9);
+    }
+}
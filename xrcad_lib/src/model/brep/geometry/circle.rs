@@ -6,6 +6,8 @@
 use bevy::math::Vec3 as Vec3_sp;
 use nalgebra::Point3;
 
+use crate::model::brep_model::na_vec3_to_bevy;
+
 #[derive(Debug, Default, Clone)]
 pub struct Circle{
     pub position: Point3<f64>,
@@ -23,14 +25,7 @@ impl Circle {
 
     /// Convert to Bevy-compatible types (Vec3, f32 radius)
     pub fn as_sp(&self) -> (Vec3_sp, f32) {
-        (
-            Vec3_sp::new(
-                self.position.x as f32,
-                self.position.y as f32,
-                self.position.z as f32,
-            ),
-            self.radius as f32,
-        )
+        (na_vec3_to_bevy(&self.position.coords), self.radius as f32)
     }
     /// Example: add two radii using f64
     pub fn add_radius_hp(&self, other: &Self) -> f64 {
@@ -38,7 +33,7 @@ impl Circle {
     }
     /// Example: get position as array of f32
     pub fn position_as_sp(&self) -> [f32; 3] {
-        [self.position.x as f32, self.position.y as f32, self.position.z as f32]
+        na_vec3_to_bevy(&self.position.coords).to_array()
     }
 }
 
@@ -1,6 +1,85 @@
 
+use nalgebra::Vector2;
+
 use super::super::topology::vertex::Vertex;
 
 pub struct Polygon {
     pub vertices: Vec<Vertex>,
 }
+
+/// Signed area of a closed 2D polygon (positive when counter-clockwise)
+pub fn signed_area_2d(poly: &[Vector2<f64>]) -> f64 {
+    let n = poly.len();
+    (0..n)
+        .map(|i| {
+            let a = poly[i];
+            let b = poly[(i + 1) % n];
+            a.x * b.y - b.x * a.y
+        })
+        .sum::<f64>()
+        * 0.5
+}
+
+/// Even-odd containment test of a 2D point against a closed polygon
+pub fn contains_point_2d(poly: &[Vector2<f64>], p: &Vector2<f64>) -> bool {
+    let n = poly.len();
+    let mut inside = false;
+    let mut j = n.wrapping_sub(1);
+    for i in 0..n {
+        let (a, b) = (poly[i], poly[j]);
+        if (a.y > p.y) != (b.y > p.y) && p.x < (b.x - a.x) * (p.y - a.y) / (b.y - a.y) + a.x {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+/// Distance from a 2D point to a segment
+pub fn segment_distance_2d(a: &Vector2<f64>, b: &Vector2<f64>, p: &Vector2<f64>) -> f64 {
+    let ab = b - a;
+    let len2 = ab.norm_squared();
+    if len2 == 0.0 {
+        return (p - a).norm();
+    }
+    let t = ((p - a).dot(&ab) / len2).clamp(0.0, 1.0);
+    (p - (a + ab * t)).norm()
+}
+
+/// Distance from a 2D point to the boundary of a closed polygon
+pub fn boundary_distance_2d(poly: &[Vector2<f64>], p: &Vector2<f64>) -> f64 {
+    let n = poly.len();
+    (0..n)
+        .map(|i| segment_distance_2d(&poly[i], &poly[(i + 1) % n], p))
+        .fold(f64::INFINITY, f64::min)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit_square() -> Vec<Vector2<f64>> {
+        vec![
+            Vector2::new(0.0, 0.0),
+            Vector2::new(1.0, 0.0),
+            Vector2::new(1.0, 1.0),
+            Vector2::new(0.0, 1.0),
+        ]
+    }
+
+    #[test]
+    fn test_signed_area() {
+        let mut sq = unit_square();
+        assert_eq!(signed_area_2d(&sq), 1.0);
+        sq.reverse();
+        assert_eq!(signed_area_2d(&sq), -1.0);
+    }
+
+    #[test]
+    fn test_contains_and_boundary_distance() {
+        let sq = unit_square();
+        assert!(contains_point_2d(&sq, &Vector2::new(0.5, 0.5)));
+        assert!(!contains_point_2d(&sq, &Vector2::new(1.5, 0.5)));
+        assert!((boundary_distance_2d(&sq, &Vector2::new(0.5, 0.25)) - 0.25).abs() < 1e-12);
+    }
+}
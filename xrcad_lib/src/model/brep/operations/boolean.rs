@@ -0,0 +1,404 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: brep::operations::boolean
+//!
+//! Union, subtraction and intersection of closed planar solids. Both operands
+//! are triangulated and sorted into binary space partitioning trees; each tree
+//! clips away the polygons of the other that the operation discards. The
+//! surviving polygons are rebuilt into a model, with their T-junctions split so
+//! neighbours share edges, then coplanar faces are merged and collinear
+//! vertices left on straight edges removed. Operands whose bounding boxes do
+//! not meet are combined without clipping.
+
+use std::fmt;
+
+use nalgebra::Vector3;
+use serde::{Deserialize, Serialize};
+
+use crate::model::brep::operations::merge_faces::{remove_collinear_vertices, MergeFaces};
+use crate::model::brep::tessellate::tessellate;
+use crate::model::brep::validate::{validate_solid, ValidationIssue};
+use crate::model::brep_model::{area_vector, BrepModel};
+use crate::model::bvh::Aabb;
+use crate::model::tolerance::Tolerance;
+
+/// Boolean operations between two solids
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BooleanOp {
+    Union,
+    /// Remove the tool from the target
+    Subtract,
+    Intersect,
+}
+
+impl BooleanOp {
+    pub fn label(&self) -> &'static str {
+        match self {
+            BooleanOp::Union => "Union",
+            BooleanOp::Subtract => "Subtract",
+            BooleanOp::Intersect => "Intersect",
+        }
+    }
+}
+
+/// Why a boolean could not be computed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BooleanError {
+    TargetNotSolid,
+    ToolNotSolid,
+    /// Nothing is left, e.g. intersecting disjoint bodies
+    EmptyResult,
+    /// The rebuilt result is not a valid solid
+    Invalid(Vec<ValidationIssue>),
+}
+
+impl fmt::Display for BooleanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BooleanError::TargetNotSolid => write!(f, "target is not a closed solid"),
+            BooleanError::ToolNotSolid => write!(f, "tool is not a closed solid"),
+            BooleanError::EmptyResult => write!(f, "result is empty"),
+            BooleanError::Invalid(issues) => {
+                let text: Vec<String> = issues.iter().map(|i| i.to_string()).collect();
+                write!(f, "result is invalid: {}", text.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for BooleanError {}
+
+/// Oriented splitting plane: points with `normal · p > w` are in front
+#[derive(Debug, Clone, Copy)]
+struct Partition {
+    normal: Vector3<f64>,
+    w: f64,
+}
+
+/// Convex planar polygon, counter-clockwise about its plane normal
+#[derive(Debug, Clone)]
+struct Polygon {
+    points: Vec<Vector3<f64>>,
+    plane: Partition,
+}
+
+impl Polygon {
+    /// None for a sliver no wider than the linear tolerance
+    fn new(points: Vec<Vector3<f64>>, tol: &Tolerance) -> Option<Self> {
+        let area = area_vector(&points);
+        let span = points.iter().map(|p| (p - points[0]).norm()).fold(0.0, f64::max);
+        if tol.is_zero_length(span) || area.norm() <= tol.linear * span {
+            return None;
+        }
+        let normal = area.normalize();
+        let w = normal.dot(&points[0]);
+        Some(Self { points, plane: Partition { normal, w } })
+    }
+
+    fn flip(&mut self) {
+        self.points.reverse();
+        self.plane.normal = -self.plane.normal;
+        self.plane.w = -self.plane.w;
+    }
+}
+
+/// Where a polygon lies relative to a partition
+enum Split {
+    /// In the plane; true if it faces the same way
+    Coplanar(Polygon, bool),
+    Sides { front: Option<Polygon>, back: Option<Polygon> },
+}
+
+const COPLANAR: u8 = 0;
+const FRONT: u8 = 1;
+const BACK: u8 = 2;
+const SPANNING: u8 = 3;
+
+impl Partition {
+    fn split(&self, polygon: Polygon, eps: f64) -> Split {
+        let sides: Vec<u8> = polygon
+            .points
+            .iter()
+            .map(|p| {
+                let t = self.normal.dot(p) - self.w;
+                if t < -eps {
+                    BACK
+                } else if t > eps {
+                    FRONT
+                } else {
+                    COPLANAR
+                }
+            })
+            .collect();
+        match sides.iter().fold(COPLANAR, |a, s| a | s) {
+            COPLANAR => {
+                let same = self.normal.dot(&polygon.plane.normal) > 0.0;
+                Split::Coplanar(polygon, same)
+            }
+            FRONT => Split::Sides { front: Some(polygon), back: None },
+            BACK => Split::Sides { front: None, back: Some(polygon) },
+            _ => {
+                let (mut front, mut back) = (Vec::new(), Vec::new());
+                let n = polygon.points.len();
+                for i in 0..n {
+                    let (si, sj) = (sides[i], sides[(i + 1) % n]);
+                    let (a, b) = (polygon.points[i], polygon.points[(i + 1) % n]);
+                    if si != BACK {
+                        front.push(a);
+                    }
+                    if si != FRONT {
+                        back.push(a);
+                    }
+                    if si | sj == SPANNING {
+                        let t = (self.w - self.normal.dot(&a)) / self.normal.dot(&(b - a));
+                        let p = a + (b - a) * t;
+                        front.push(p);
+                        back.push(p);
+                    }
+                }
+                let piece = |points: Vec<Vector3<f64>>| (points.len() >= 3).then_some(Polygon { points, plane: polygon.plane });
+                Split::Sides { front: piece(front), back: piece(back) }
+            }
+        }
+    }
+}
+
+/// Node of a BSP tree; polygons in front of the node's plane go to `front`
+#[derive(Debug, Default)]
+struct Node {
+    plane: Option<Partition>,
+    front: Option<Box<Node>>,
+    back: Option<Box<Node>>,
+    polygons: Vec<Polygon>,
+}
+
+impl Node {
+    fn new(polygons: Vec<Polygon>, eps: f64) -> Self {
+        let mut node = Node::default();
+        node.build(polygons, eps);
+        node
+    }
+
+    /// Swap inside and outside
+    fn invert(&mut self) {
+        for p in &mut self.polygons {
+            p.flip();
+        }
+        if let Some(plane) = &mut self.plane {
+            plane.normal = -plane.normal;
+            plane.w = -plane.w;
+        }
+        for child in [&mut self.front, &mut self.back].into_iter().flatten() {
+            child.invert();
+        }
+        std::mem::swap(&mut self.front, &mut self.back);
+    }
+
+    /// The parts of `polygons` outside this tree's solid
+    fn clip_polygons(&self, polygons: Vec<Polygon>, eps: f64) -> Vec<Polygon> {
+        let Some(plane) = self.plane else { return polygons };
+        let (mut front, mut back) = (Vec::new(), Vec::new());
+        for polygon in polygons {
+            match plane.split(polygon, eps) {
+                Split::Coplanar(p, true) => front.push(p),
+                Split::Coplanar(p, false) => back.push(p),
+                Split::Sides { front: f, back: b } => {
+                    front.extend(f);
+                    back.extend(b);
+                }
+            }
+        }
+        let mut out = match &self.front {
+            Some(node) => node.clip_polygons(front, eps),
+            None => front,
+        };
+        if let Some(node) = &self.back {
+            out.extend(node.clip_polygons(back, eps));
+        }
+        out
+    }
+
+    /// Remove the polygons of this tree that lie inside `other`
+    fn clip_to(&mut self, other: &Node, eps: f64) {
+        self.polygons = other.clip_polygons(std::mem::take(&mut self.polygons), eps);
+        for child in [&mut self.front, &mut self.back].into_iter().flatten() {
+            child.clip_to(other, eps);
+        }
+    }
+
+    fn all_polygons(&self) -> Vec<Polygon> {
+        let mut out = self.polygons.clone();
+        for child in [&self.front, &self.back].into_iter().flatten() {
+            out.extend(child.all_polygons());
+        }
+        out
+    }
+
+    fn build(&mut self, polygons: Vec<Polygon>, eps: f64) {
+        let Some(first) = polygons.first() else { return };
+        let plane = *self.plane.get_or_insert(first.plane);
+        let (mut front, mut back) = (Vec::new(), Vec::new());
+        for polygon in polygons {
+            match plane.split(polygon, eps) {
+                Split::Coplanar(p, _) => self.polygons.push(p),
+                Split::Sides { front: f, back: b } => {
+                    front.extend(f);
+                    back.extend(b);
+                }
+            }
+        }
+        if !front.is_empty() {
+            self.front.get_or_insert_with(Default::default).build(front, eps);
+        }
+        if !back.is_empty() {
+            self.back.get_or_insert_with(Default::default).build(back, eps);
+        }
+    }
+}
+
+/// Triangles of every face, as BSP polygons
+fn polygons(model: &BrepModel, tol: &Tolerance) -> Vec<Polygon> {
+    tessellate(model)
+        .iter()
+        .flat_map(|mesh| mesh.triangle_positions().filter_map(|t| Polygon::new(t.to_vec(), tol)).collect::<Vec<_>>())
+        .collect()
+}
+
+/// Interior points of segment a-b among `points`, ordered from a to b
+fn points_on_segment(a: &Vector3<f64>, b: &Vector3<f64>, points: &[Vector3<f64>], tol: &Tolerance) -> Vec<Vector3<f64>> {
+    let d = b - a;
+    let length_sq = d.norm_squared();
+    let mut on: Vec<(f64, Vector3<f64>)> = points
+        .iter()
+        .filter(|p| !tol.coincident(p, a) && !tol.coincident(p, b))
+        .filter_map(|p| {
+            let t = (p - a).dot(&d) / length_sq;
+            (t > 0.0 && t < 1.0 && tol.is_zero_length((a + d * t - p).norm())).then_some((t, *p))
+        })
+        .collect();
+    on.sort_by(|x, y| x.0.total_cmp(&y.0));
+    on.dedup_by(|x, y| tol.coincident(&x.1, &y.1));
+    on.into_iter().map(|(_, p)| p).collect()
+}
+
+/// Build faces from polygons, splitting their sides at the corners of neighbours
+fn rebuild(polygons: &[Polygon], tolerance: Tolerance) -> BrepModel {
+    let mut model = BrepModel::new();
+    model.tolerance = tolerance;
+    let corners: Vec<Vector3<f64>> = polygons.iter().flat_map(|p| p.points.iter().copied()).collect();
+    for polygon in polygons {
+        let n = polygon.points.len();
+        let mut points = Vec::new();
+        for i in 0..n {
+            let (a, b) = (polygon.points[i], polygon.points[(i + 1) % n]);
+            points.push(a);
+            points.extend(points_on_segment(&a, &b, &corners, &tolerance));
+        }
+        points.dedup_by(|x, y| tolerance.coincident(x, y));
+        while points.len() > 1 && tolerance.coincident(&points[0], &points[points.len() - 1]) {
+            points.pop();
+        }
+        if points.len() >= 3 && !tolerance.is_zero_length(area_vector(&points).norm()) {
+            model.add_face(&points);
+        }
+    }
+    model
+}
+
+/// Combine two closed solids. The result keeps the target's tolerance.
+pub fn boolean(target: &BrepModel, tool: &BrepModel, op: BooleanOp) -> Result<BrepModel, BooleanError> {
+    if !validate_solid(target).is_empty() {
+        return Err(BooleanError::TargetNotSolid);
+    }
+    if !validate_solid(tool).is_empty() {
+        return Err(BooleanError::ToolNotSolid);
+    }
+    let eps = target.tolerance.linear;
+    let bounds = |model: &BrepModel| Aabb::from_points(model.vertices.iter().map(|v| &v.position));
+    if !bounds(target).expanded(eps).overlaps(&bounds(tool)) {
+        return match op {
+            BooleanOp::Union => {
+                let mut result = target.clone();
+                result.append(tool);
+                Ok(result)
+            }
+            BooleanOp::Subtract => Ok(target.clone()),
+            BooleanOp::Intersect => Err(BooleanError::EmptyResult),
+        };
+    }
+    let mut a = Node::new(polygons(target, &target.tolerance), eps);
+    let mut b = Node::new(polygons(tool, &target.tolerance), eps);
+    match op {
+        BooleanOp::Union => {
+            a.clip_to(&b, eps);
+            b.clip_to(&a, eps);
+            b.invert();
+            b.clip_to(&a, eps);
+            b.invert();
+            a.build(b.all_polygons(), eps);
+        }
+        BooleanOp::Subtract => {
+            a.invert();
+            a.clip_to(&b, eps);
+            b.clip_to(&a, eps);
+            b.invert();
+            b.clip_to(&a, eps);
+            b.invert();
+            a.build(b.all_polygons(), eps);
+            a.invert();
+        }
+        BooleanOp::Intersect => {
+            a.invert();
+            b.clip_to(&a, eps);
+            b.invert();
+            a.clip_to(&b, eps);
+            b.clip_to(&a, eps);
+            a.build(b.all_polygons(), eps);
+            a.invert();
+        }
+    }
+    let mut result = rebuild(&a.all_polygons(), target.tolerance);
+    if result.faces.is_empty() {
+        return Err(BooleanError::EmptyResult);
+    }
+    MergeFaces::new().apply(&mut result);
+    // Also the splits of faces that merged with none
+    let corners: Vec<usize> = result.vertices.iter().map(|v| v.id).collect();
+    remove_collinear_vertices(&mut result, &corners);
+    let issues = validate_solid(&result);
+    if !issues.is_empty() {
+        return Err(BooleanError::Invalid(issues));
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::measure::mass_properties::mass_properties;
+    use crate::model::brep::primitives::cube;
+    use crate::testing::support::shifted;
+
+    #[test]
+    fn test_overlapping_cubes() {
+        let (a, b) = (cube(10.0), shifted(cube(10.0), Vector3::repeat(5.0)));
+        for (op, volume) in [(BooleanOp::Union, 1875.0), (BooleanOp::Subtract, 875.0), (BooleanOp::Intersect, 125.0)] {
+            let result = boolean(&a, &b, op).unwrap();
+            assert!((mass_properties(&result).volume - volume).abs() < 1e-6, "{:?}", op);
+        }
+        let common = boolean(&a, &b, BooleanOp::Intersect).unwrap();
+        assert_eq!((common.vertices.len(), common.edges.len(), common.faces.len()), (8, 12, 6));
+    }
+
+    #[test]
+    fn test_disjoint_and_open_operands() {
+        let (a, far) = (cube(10.0), shifted(cube(10.0), Vector3::new(50.0, 0.0, 0.0)));
+        assert_eq!(boolean(&a, &far, BooleanOp::Intersect).unwrap_err(), BooleanError::EmptyResult);
+        assert_eq!(boolean(&a, &far, BooleanOp::Union).unwrap().shells().len(), 2);
+        assert_eq!(boolean(&a, &far, BooleanOp::Subtract).unwrap().faces.len(), 6);
+        let mut open = cube(10.0);
+        open.faces.pop();
+        assert_eq!(boolean(&a, &open, BooleanOp::Union).unwrap_err(), BooleanError::ToolNotSolid);
+    }
+}
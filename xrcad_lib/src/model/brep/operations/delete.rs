@@ -0,0 +1,233 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: brep::operations::delete
+//!
+//! Deleting vertices and edges while healing the topology around them. An edge
+//! collapses, merging its two vertices at its midpoint; a vertex with exactly
+//! two edges is removed by joining those edges into one. Each edit is made on
+//! a copy and only kept if it leaves no face with fewer than three edges, no
+//! doubled edges, no planar face around a collapsed edge bent out of its plane,
+//! and no validation issue the model did not already have.
+//! The Delete key sends the edits to the model command bus.
+
+use std::fmt;
+
+use bevy::prelude::*;
+
+use crate::input::keyboard::KeyBindings;
+use crate::interaction::selection::Selection;
+use crate::model::brep::constraints::planarity::is_face_planar;
+use crate::model::brep::validate::{validate, validate_solid, ValidationIssue};
+use crate::model::brep_model::BrepModel;
+use crate::model::command::ModelCommand;
+
+/// Why a delete was refused
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeleteError {
+    UnknownVertex(usize),
+    UnknownEdge(usize),
+    /// Only vertices joining exactly two edges can be removed
+    Valence { vertex: usize, edges: usize },
+    /// The face would be left with fewer than three edges
+    DegenerateFace(usize),
+    /// Two edges would join the same pair of vertices
+    NonManifold,
+    /// The face would no longer be planar
+    NonPlanar(usize),
+    /// The result fails validation in ways the original did not
+    Invalid(Vec<ValidationIssue>),
+}
+
+impl fmt::Display for DeleteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeleteError::UnknownVertex(id) => write!(f, "no vertex {}", id),
+            DeleteError::UnknownEdge(id) => write!(f, "no edge {}", id),
+            DeleteError::Valence { vertex, edges } => write!(f, "vertex {} joins {} edges, not 2", vertex, edges),
+            DeleteError::DegenerateFace(id) => write!(f, "face {} would collapse", id),
+            DeleteError::NonManifold => write!(f, "result would not be manifold"),
+            DeleteError::NonPlanar(id) => write!(f, "face {} would no longer be planar", id),
+            DeleteError::Invalid(issues) => {
+                let text: Vec<String> = issues.iter().map(|i| i.to_string()).collect();
+                write!(f, "result would be invalid: {}", text.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for DeleteError {}
+
+/// Accept `edited` in place of `model` if it stays well formed
+fn commit(model: &mut BrepModel, edited: BrepModel) -> Result<(), DeleteError> {
+    for face in &edited.faces {
+        let edges = edited.face_edge_ids(face).len();
+        if edges < 3 {
+            return Err(DeleteError::DegenerateFace(face.id));
+        }
+    }
+    let mut pairs: Vec<(usize, usize)> = edited.edges.iter().map(|e| (e.vertices.0.min(e.vertices.1), e.vertices.0.max(e.vertices.1))).collect();
+    pairs.sort_unstable();
+    if pairs.windows(2).any(|w| w[0] == w[1]) {
+        return Err(DeleteError::NonManifold);
+    }
+    // Solids must stay solids; sheets must not gain defects
+    let before = validate_solid(model);
+    let (before, after) = if before.is_empty() { (before, validate_solid(&edited)) } else { (validate(model), validate(&edited)) };
+    let new: Vec<ValidationIssue> = after.into_iter().filter(|i| !before.contains(i)).collect();
+    if !new.is_empty() {
+        return Err(DeleteError::Invalid(new));
+    }
+    *model = edited;
+    Ok(())
+}
+
+/// Collapse an edge, merging its vertices at its midpoint. Returns the surviving vertex.
+pub fn collapse_edge(model: &mut BrepModel, edge_id: usize) -> Result<usize, DeleteError> {
+    let (keep, gone) = model.edge(edge_id).ok_or(DeleteError::UnknownEdge(edge_id))?.vertices;
+    let (Some(a), Some(b)) = (model.vertex_position(keep), model.vertex_position(gone)) else {
+        return Err(DeleteError::UnknownEdge(edge_id));
+    };
+    let mut edited = model.clone();
+    for l in &mut edited.edgeloops {
+        for chain in &mut l.edges {
+            chain.retain(|e| *e != edge_id);
+        }
+    }
+    edited.edges.retain(|e| e.id != edge_id);
+    for e in &mut edited.edges {
+        for end in [&mut e.vertices.0, &mut e.vertices.1] {
+            if *end == gone {
+                *end = keep;
+            }
+        }
+    }
+    edited.vertices.retain(|v| v.id != gone);
+    if let Some(v) = edited.vertices.iter_mut().find(|v| v.id == keep) {
+        v.position = (a + b) * 0.5;
+    }
+    // The merged vertex moved; faces around it that were planar must stay so
+    let bent = edited.faces_using_vertex(keep).into_iter().find(|id| {
+        edited.face(*id).is_some_and(|f| !is_face_planar(&edited, f)) && model.face(*id).is_none_or(|f| is_face_planar(model, f))
+    });
+    if let Some(face) = bent {
+        return Err(DeleteError::NonPlanar(face));
+    }
+    commit(model, edited)?;
+    Ok(keep)
+}
+
+/// Remove a vertex joining exactly two edges, merging them. Returns the surviving edge.
+pub fn delete_vertex(model: &mut BrepModel, vertex_id: usize) -> Result<usize, DeleteError> {
+    model.vertex(vertex_id).ok_or(DeleteError::UnknownVertex(vertex_id))?;
+    let edges: Vec<(usize, usize)> = model
+        .edges
+        .iter()
+        .filter_map(|e| match e.vertices {
+            (a, b) if a == vertex_id => Some((e.id, b)),
+            (a, b) if b == vertex_id => Some((e.id, a)),
+            _ => None,
+        })
+        .collect();
+    let [(keep, _), (gone, far)] = edges[..] else {
+        return Err(DeleteError::Valence { vertex: vertex_id, edges: edges.len() });
+    };
+    let mut edited = model.clone();
+    for l in &mut edited.edgeloops {
+        for chain in &mut l.edges {
+            chain.retain(|e| *e != gone);
+        }
+    }
+    edited.edges.retain(|e| e.id != gone);
+    if let Some(e) = edited.edges.iter_mut().find(|e| e.id == keep) {
+        if e.vertices.0 == vertex_id {
+            e.vertices.0 = far;
+        } else {
+            e.vertices.1 = far;
+        }
+    }
+    edited.vertices.retain(|v| v.id != vertex_id);
+    commit(model, edited)?;
+    Ok(keep)
+}
+
+/// Request to delete the selected vertices and edges
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeleteSelection;
+
+/// The delete_selection binding (Delete) removes the selected vertices and edges
+pub fn delete_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    selection: Res<Selection>,
+    mut requests: EventWriter<DeleteSelection>,
+) {
+    if bindings.just_pressed("delete_selection", &keys) && !selection.is_empty() {
+        requests.write(DeleteSelection);
+    }
+}
+
+/// Send the deletion of the selected vertices and edges to the command bus;
+/// refused deletes leave the model unchanged
+pub fn apply_delete_selection(mut requests: EventReader<DeleteSelection>, selection: Res<Selection>, mut commands: EventWriter<ModelCommand>) {
+    for _ in requests.read() {
+        for id in selection.vertices() {
+            commands.write(ModelCommand::DeleteVertex(id));
+        }
+        for id in selection.edges() {
+            commands.write(ModelCommand::CollapseEdge(id));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::cube;
+    use nalgebra::Vector3;
+
+    #[test]
+    fn test_collapse_edge() {
+        // Merging a cube edge at its midpoint would bend the faces at its ends
+        let mut model = cube(10.0);
+        let edge = model.edges[0].id;
+        assert!(matches!(collapse_edge(&mut model, edge), Err(DeleteError::NonPlanar(_))));
+        assert_eq!((model.vertices.len(), model.edges.len()), (8, 12));
+
+        // A flat pentagon loses a side and stays flat
+        let mut pentagon = BrepModel::new();
+        let corners: Vec<Vector3<f64>> = (0..5)
+            .map(|i| {
+                let a = i as f64 * std::f64::consts::TAU / 5.0;
+                Vector3::new(10.0 * a.cos(), 10.0 * a.sin(), 0.0)
+            })
+            .collect();
+        pentagon.add_face(&corners);
+        let edge = pentagon.edges[0].id;
+        let keep = collapse_edge(&mut pentagon, edge).unwrap();
+        assert_eq!((pentagon.vertices.len(), pentagon.edges.len(), pentagon.faces.len()), (4, 4, 1));
+        assert_eq!(pentagon.vertex_position(keep).unwrap().z, 0.0);
+
+        // Collapsing a side of a lone triangle would leave a two-edge face
+        let mut triangle = BrepModel::new();
+        triangle.add_face(&[Vector3::zeros(), Vector3::x(), Vector3::y()]);
+        let before = triangle.edges.len();
+        assert!(matches!(collapse_edge(&mut triangle, 0), Err(DeleteError::DegenerateFace(_))));
+        assert_eq!(triangle.edges.len(), before);
+    }
+
+    #[test]
+    fn test_delete_vertex_heals_split_edge() {
+        let mut model = cube(10.0);
+        let (a, b) = model.edges[0].vertices;
+        let mid = (model.vertex_position(a).unwrap() + model.vertex_position(b).unwrap()) * 0.5;
+        let m = model.split_edge(model.edges[0].id, mid).unwrap();
+        assert_eq!(model.edges.len(), 13);
+        delete_vertex(&mut model, m).unwrap();
+        assert_eq!((model.vertices.len(), model.edges.len()), (8, 12));
+        assert!(validate_solid(&model).is_empty());
+
+        // Corners join three edges
+        assert_eq!(delete_vertex(&mut model, a), Err(DeleteError::Valence { vertex: a, edges: 3 }));
+    }
+}
@@ -2,22 +2,120 @@
 // Copyright (c) 2025 Adrian Scarlett
 
 //! Module: brep::opt::extrude
+//!
+//! Linear extrusion of a closed sketch region along its plane normal into a solid.
+
+use nalgebra::{Vector2, Vector3};
+
+use crate::model::brep::topology::plane::Plane;
+use crate::model::brep_model::BrepModel;
+use crate::sketch::regions::SketchRegion;
+
+/// Outer corner positions and hole corner positions of a planar face
+type FaceLoops = (Vec<Vector3<f64>>, Vec<Vec<Vector3<f64>>>);
 
 /// Extrude operation struct.
+#[derive(Debug, Default, Clone)]
 pub struct Extrude;
 
 impl Extrude {
     pub fn new() -> Self {
         Extrude
     }
+
+    /// Sweep `region` (in `plane` coordinates) by `distance` along the plane normal.
+    /// Negative distances extrude the other way. Faces of the result point outward.
+    pub fn apply(&self, plane: &Plane, region: &SketchRegion, distance: f64) -> BrepModel {
+        let offset = plane.normal.normalize() * distance;
+        let lift = |points: &[Vector2<f64>], shift: Vector3<f64>| -> Vec<Vector3<f64>> {
+            points.iter().map(|p| plane.point_at_2d(p).coords + shift).collect()
+        };
+        // Counter-clockwise outer loop, clockwise holes, so side faces come out outward
+        let mut outer = region.outer.points.clone();
+        if region.outer.signed_area() < 0.0 {
+            outer.reverse();
+        }
+        let holes: Vec<Vec<Vector2<f64>>> = region
+            .holes
+            .iter()
+            .map(|h| {
+                let mut points = h.points.clone();
+                if h.signed_area() > 0.0 {
+                    points.reverse();
+                }
+                points
+            })
+            .collect();
+
+        let mut faces: Vec<FaceLoops> = Vec::new();
+        let reversed = |mut v: Vec<Vector3<f64>>| {
+            v.reverse();
+            v
+        };
+        // Caps: the bottom faces against the extrusion direction
+        faces.push((
+            reversed(lift(&outer, Vector3::zeros())),
+            holes.iter().map(|h| reversed(lift(h, Vector3::zeros()))).collect(),
+        ));
+        faces.push((lift(&outer, offset), holes.iter().map(|h| lift(h, offset)).collect()));
+        for ring in std::iter::once(&outer).chain(holes.iter()) {
+            let (bottom, top) = (lift(ring, Vector3::zeros()), lift(ring, offset));
+            for i in 0..ring.len() {
+                let j = (i + 1) % ring.len();
+                faces.push((vec![bottom[i], bottom[j], top[j], top[i]], Vec::new()));
+            }
+        }
+
+        let mut model = BrepModel::new();
+        for (outer, holes) in faces {
+            if distance < 0.0 {
+                model.add_face_with_holes(&reversed(outer), &holes.into_iter().map(reversed).collect::<Vec<_>>());
+            } else {
+                model.add_face_with_holes(&outer, &holes);
+            }
+        }
+        model
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::measure::mass_properties::mass_properties;
+    use crate::sketch::regions::RegionLoop;
+
+    fn square(x0: f64, y0: f64, size: f64) -> RegionLoop {
+        RegionLoop {
+            points: vec![
+                Vector2::new(x0, y0),
+                Vector2::new(x0 + size, y0),
+                Vector2::new(x0 + size, y0 + size),
+                Vector2::new(x0, y0 + size),
+            ],
+            entities: vec![0; 4],
+        }
+    }
+
+    #[test]
+    fn test_extrude_square_with_hole() {
+        let mut hole = square(1.0, 1.0, 2.0);
+        hole.points.reverse();
+        let region = SketchRegion { outer: square(0.0, 0.0, 4.0), holes: vec![hole] };
+        let solid = Extrude::new().apply(&Plane::xy(), &region, 5.0);
+        assert_eq!(solid.faces.len(), 2 + 4 + 4);
+        for e in &solid.edges {
+            assert_eq!(solid.faces_using_edge(e.id).len(), 2);
+        }
+        let mp = mass_properties(&solid);
+        assert!((mp.volume - 12.0 * 5.0).abs() < 1e-9);
+    }
+
     #[test]
-    fn test_extrude_new() {
-        let e = Extrude::new();
-        let _ = e;
+    fn test_negative_distance_stays_outward() {
+        let region = SketchRegion { outer: square(0.0, 0.0, 1.0), holes: Vec::new() };
+        let solid = Extrude::new().apply(&Plane::xy(), &region, -2.0);
+        let mp = mass_properties(&solid);
+        assert!((mp.volume - 2.0).abs() < 1e-9);
+        assert!((mp.center_of_mass.z + 1.0).abs() < 1e-9);
     }
 }
@@ -0,0 +1,268 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: brep::opt::imprint
+//!
+//! Transfers the boundaries of a tool body onto coplanar faces of a target body,
+//! so that mating regions become faces of their own.
+
+use nalgebra::{Point3, Vector2, Vector3};
+
+use crate::model::brep::geometry::polygon::{boundary_distance_2d, contains_point_2d};
+use crate::model::brep::topology::plane::Plane;
+use crate::model::brep_model::{area_vector, BrepModel};
+use crate::model::tolerance::Tolerance;
+
+/// Imprint operation struct.
+#[derive(Debug, Default, Clone)]
+pub struct Imprint;
+
+impl Imprint {
+    pub fn new() -> Self {
+        Imprint
+    }
+
+    /// Imprint the face boundaries of `tool` onto the faces of `target` they lie on.
+    ///
+    /// A tool loop lying strictly inside a target face becomes a new face with a
+    /// matching hole in the original; a tool edge running across a target face
+    /// from boundary to boundary splits it in two. Returns the number of faces created.
+    pub fn apply(&self, target: &mut BrepModel, tool: &BrepModel) -> usize {
+        let mut created = 0;
+        for tool_face in &tool.faces {
+            let Some(tool_plane) = tool.face_plane(tool_face) else { continue; };
+            let Some(outer) = tool.face_loops(tool_face).into_iter().next() else { continue; };
+            let points = tool.loop_positions(outer);
+
+            let face_ids: Vec<usize> = target.faces.iter().map(|f| f.id).collect();
+            if face_ids.iter().any(|fid| imprint_loop(target, *fid, &tool_plane, &points)) {
+                created += 1;
+                continue;
+            }
+            for i in 0..points.len() {
+                let (p, q) = (points[i], points[(i + 1) % points.len()]);
+                let face_ids: Vec<usize> = target.faces.iter().map(|f| f.id).collect();
+                if face_ids.iter().any(|fid| imprint_chord(target, *fid, &p, &q)) {
+                    created += 1;
+                }
+            }
+        }
+        created
+    }
+}
+
+/// Plane and projected loops (outer first) of a target face
+fn face_region(model: &BrepModel, face_id: usize) -> Option<(Plane, Vec<Vec<Vector2<f64>>>)> {
+    let face = model.face(face_id)?;
+    let plane = model.face_plane(face)?;
+    let loops = model
+        .face_loops(face)
+        .into_iter()
+        .map(|l| {
+            model
+                .loop_positions(l)
+                .iter()
+                .map(|p| plane.project_2d(&Point3::from(*p)))
+                .collect()
+        })
+        .collect();
+    Some((plane, loops))
+}
+
+/// True if a projected point lies strictly inside the face region (outside holes, off boundaries)
+fn strictly_inside(loops: &[Vec<Vector2<f64>>], p: &Vector2<f64>, tol: &Tolerance) -> bool {
+    let Some((outer, holes)) = loops.split_first() else { return false; };
+    contains_point_2d(outer, p)
+        && !tol.is_zero_length(boundary_distance_2d(outer, p))
+        && holes
+            .iter()
+            .all(|h| !contains_point_2d(h, p) && !tol.is_zero_length(boundary_distance_2d(h, p)))
+}
+
+/// Imprint a closed tool loop contained in a target face as a new inner face,
+/// wound counter-clockwise about the target face's normal, with a clockwise
+/// hole in the target face whatever the tool loop's own winding
+fn imprint_loop(target: &mut BrepModel, face_id: usize, tool_plane: &Plane, points: &[Vector3<f64>]) -> bool {
+    let Some((plane, loops)) = face_region(target, face_id) else { return false; };
+    let tol = target.tolerance;
+    if points.len() < 3 || !plane.is_coplanar_with(tool_plane, &tol) {
+        return false;
+    }
+    if !points.iter().all(|p| strictly_inside(&loops, &plane.project_2d(&Point3::from(*p)), &tol)) {
+        return false;
+    }
+    // Already imprinted: every edge of the loop exists in the target
+    let existing: Vec<Option<usize>> = points.iter().map(|p| target.find_vertex_at(p)).collect();
+    if existing.iter().all(|v| v.is_some()) {
+        let ids: Vec<usize> = existing.into_iter().flatten().collect();
+        if (0..ids.len()).all(|i| target.find_edge_between(ids[i], ids[(i + 1) % ids.len()]).is_some()) {
+            return false;
+        }
+    }
+
+    let ids: Vec<usize> = points.iter().map(|p| target.add_vertex(*p)).collect();
+    let mut edges: Vec<usize> = (0..ids.len())
+        .map(|i| target.add_edge(ids[i], ids[(i + 1) % ids.len()]))
+        .collect();
+    if area_vector(points).dot(&plane.normal) < 0.0 {
+        edges.reverse();
+    }
+    let hole = target.add_edge_loop(edges.iter().rev().copied().collect());
+    if let Some(face) = target.face_mut(face_id) {
+        face.edge_loops.push(hole);
+    }
+    target.add_face_from_loops(vec![edges]);
+    true
+}
+
+/// Distance from a point to a 3D segment
+fn segment_distance(a: &Vector3<f64>, b: &Vector3<f64>, p: &Vector3<f64>) -> f64 {
+    let ab = b - a;
+    let len2 = ab.norm_squared();
+    if len2 == 0.0 {
+        return (p - a).norm();
+    }
+    let t = ((p - a).dot(&ab) / len2).clamp(0.0, 1.0);
+    (p - (a + ab * t)).norm()
+}
+
+/// Find or create a vertex on the outer loop of a face at `p`
+fn vertex_on_boundary(target: &mut BrepModel, face_id: usize, p: &Vector3<f64>) -> Option<usize> {
+    let tol = target.tolerance;
+    let face = target.face(face_id)?;
+    let outer = target.edge_loop(*face.edge_loops.first()?)?;
+    let on_loop = target
+        .loop_vertex_ids(outer)
+        .into_iter()
+        .find(|id| target.vertex_position(*id).is_some_and(|v| tol.coincident(&v, p)));
+    if on_loop.is_some() {
+        return on_loop;
+    }
+    let edge_id = outer.edges.iter().flatten().copied().find(|id| {
+        target.edge(*id).is_some_and(|e| {
+            match (target.vertex_position(e.vertices.0), target.vertex_position(e.vertices.1)) {
+                (Some(a), Some(b)) => tol.is_zero_length(segment_distance(&a, &b, p)),
+                _ => false,
+            }
+        })
+    })?;
+    target.split_edge(edge_id, *p)
+}
+
+/// Split a single-loop target face along the chord p-q if it runs across the face
+fn imprint_chord(target: &mut BrepModel, face_id: usize, p: &Vector3<f64>, q: &Vector3<f64>) -> bool {
+    let Some((plane, loops)) = face_region(target, face_id) else { return false; };
+    let tol = target.tolerance;
+    if loops.len() != 1 || !plane.contains_point(&Point3::from(*p), &tol) || !plane.contains_point(&Point3::from(*q), &tol) {
+        return false;
+    }
+    let (p2, q2) = (plane.project_2d(&Point3::from(*p)), plane.project_2d(&Point3::from(*q)));
+    let outer = &loops[0];
+    if !tol.is_zero_length(boundary_distance_2d(outer, &p2)) || !tol.is_zero_length(boundary_distance_2d(outer, &q2)) {
+        return false;
+    }
+    if !strictly_inside(&loops, &((p2 + q2) * 0.5), &tol) {
+        return false;
+    }
+
+    let Some(vp) = vertex_on_boundary(target, face_id, p) else { return false; };
+    let Some(vq) = vertex_on_boundary(target, face_id, q) else { return false; };
+    let Some(face) = target.face(face_id) else { return false; };
+    let outer_id = face.edge_loops[0];
+    let Some(outer_loop) = target.edge_loop(outer_id) else { return false; };
+    let verts = target.loop_vertex_ids(outer_loop);
+    let flat: Vec<usize> = outer_loop.edges.iter().flatten().copied().collect();
+    let (Some(ip), Some(iq)) = (verts.iter().position(|v| *v == vp), verts.iter().position(|v| *v == vq)) else {
+        return false;
+    };
+
+    let chord = target.add_edge(vq, vp);
+    let mut first = cyclic_range(&flat, ip, iq);
+    first.push(chord);
+    let mut second = cyclic_range(&flat, iq, ip);
+    second.push(chord);
+    if let Some(l) = target.edgeloops.iter_mut().find(|l| l.id == outer_id) {
+        l.edges = vec![first];
+    }
+    target.add_face_from_loops(vec![second]);
+    true
+}
+
+/// Items of a cyclic list from index `from` up to (not including) index `to`
+fn cyclic_range(items: &[usize], from: usize, to: usize) -> Vec<usize> {
+    let mut out = Vec::new();
+    let mut i = from;
+    while i != to {
+        out.push(items[i]);
+        i = (i + 1) % items.len();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x0: f64, y0: f64, x1: f64, y1: f64) -> Vec<Vector3<f64>> {
+        vec![
+            Vector3::new(x0, y0, 0.0),
+            Vector3::new(x1, y0, 0.0),
+            Vector3::new(x1, y1, 0.0),
+            Vector3::new(x0, y1, 0.0),
+        ]
+    }
+
+    #[test]
+    fn test_imprint_contained_loop() {
+        let mut target = BrepModel::new();
+        target.add_face(&rect(0.0, 0.0, 10.0, 10.0));
+        let mut tool = BrepModel::new();
+        tool.add_face(&rect(2.0, 2.0, 4.0, 4.0));
+
+        assert_eq!(Imprint::new().apply(&mut target, &tool), 1);
+        assert_eq!(target.faces.len(), 2);
+        assert_eq!(target.faces[0].edge_loops.len(), 2);
+        // Imprinting again is a no-op
+        assert_eq!(Imprint::new().apply(&mut target, &tool), 0);
+    }
+
+    #[test]
+    fn test_imprinted_loop_follows_target_orientation() {
+        let mut target = BrepModel::new();
+        target.add_face(&rect(0.0, 0.0, 10.0, 10.0));
+        let mut tool = BrepModel::new();
+        // Wound clockwise, against the target face
+        tool.add_face(&rect(2.0, 2.0, 4.0, 4.0).into_iter().rev().collect::<Vec<_>>());
+
+        assert_eq!(Imprint::new().apply(&mut target, &tool), 1);
+        let hole = target.face_loops(&target.faces[0])[1];
+        assert!((area_vector(&target.loop_positions(hole)).z + 4.0).abs() < 1e-12);
+        assert!((target.face_normal(&target.faces[1]).unwrap() - Vector3::z()).norm() < 1e-12);
+    }
+
+    #[test]
+    fn test_imprint_chord_splits_face() {
+        let mut target = BrepModel::new();
+        target.add_face(&rect(0.0, 0.0, 10.0, 10.0));
+        let mut tool = BrepModel::new();
+        // Overlaps the right half; only its left edge (x = 5) crosses the target
+        tool.add_face(&rect(5.0, 0.0, 15.0, 10.0));
+
+        assert_eq!(Imprint::new().apply(&mut target, &tool), 1);
+        assert_eq!(target.faces.len(), 2);
+        for face in &target.faces {
+            let l = target.edge_loop(face.edge_loops[0]).unwrap();
+            assert_eq!(target.loop_vertex_ids(l).len(), 4);
+        }
+    }
+
+    #[test]
+    fn test_imprint_ignores_other_planes() {
+        let mut target = BrepModel::new();
+        target.add_face(&rect(0.0, 0.0, 10.0, 10.0));
+        let mut tool = BrepModel::new();
+        tool.add_face(&rect(2.0, 2.0, 4.0, 4.0).iter().map(|p| p + Vector3::z()).collect::<Vec<_>>());
+        assert_eq!(Imprint::new().apply(&mut target, &tool), 0);
+        assert_eq!(target.faces.len(), 1);
+    }
+}
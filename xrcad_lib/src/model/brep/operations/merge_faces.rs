@@ -0,0 +1,198 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: brep::opt::merge_faces
+//!
+//! Cleans up geometry (e.g. after booleans or imprinting) by merging adjacent
+//! coplanar faces that share an edge, then removing the vertices left joining
+//! collinear edges of the merged face.
+
+use nalgebra::Vector3;
+
+use crate::model::brep::operations::delete::delete_vertex;
+use crate::model::brep_model::BrepModel;
+
+/// Merge-faces operation struct.
+#[derive(Debug, Default, Clone)]
+pub struct MergeFaces;
+
+impl MergeFaces {
+    pub fn new() -> Self {
+        MergeFaces
+    }
+
+    /// Merge all adjacent coplanar faces with matching orientation; a pair that
+    /// cannot be merged is left as it is. Returns the number of merges performed.
+    pub fn apply(&self, model: &mut BrepModel) -> usize {
+        let mut merged = 0;
+        let mut refused = Vec::new();
+        while let Some((keep, absorb)) = find_candidate(model, &refused) {
+            if !merge_pair(model, keep, absorb) {
+                refused.push((keep, absorb));
+                continue;
+            }
+            merged += 1;
+        }
+        model.remove_unused();
+        merged
+    }
+}
+
+/// Find two distinct faces sharing an edge that lie in the same plane and face
+/// the same way, other than the `refused` pairs
+fn find_candidate(model: &BrepModel, refused: &[(usize, usize)]) -> Option<(usize, usize)> {
+    for edge in &model.edges {
+        let faces = model.faces_using_edge(edge.id);
+        let [a, b] = faces[..] else { continue; };
+        if refused.contains(&(a, b)) {
+            continue;
+        }
+        let (Some(fa), Some(fb)) = (model.face(a), model.face(b)) else { continue; };
+        let (Some(pa), Some(pb)) = (model.face_plane(fa), model.face_plane(fb)) else { continue; };
+        if model.tolerance.codirectional(&pa.normal, &pb.normal) && pa.is_coplanar_with(&pb, &model.tolerance) {
+            return Some((a, b));
+        }
+    }
+    None
+}
+
+/// Replace face `keep` by the union of `keep` and `absorb`, dropping their shared
+/// edges and any vertex of the union left between two collinear edges
+fn merge_pair(model: &mut BrepModel, keep: usize, absorb: usize) -> bool {
+    let (Some(fk), Some(fa)) = (model.face(keep), model.face(absorb)) else { return false; };
+    let Some(normal) = model.face_normal(fk) else { return false; };
+    let ek = model.face_edge_ids(fk);
+    let ea = model.face_edge_ids(fa);
+    let remaining: Vec<usize> = ek
+        .iter()
+        .filter(|id| !ea.contains(id))
+        .chain(ea.iter().filter(|id| !ek.contains(id)))
+        .copied()
+        .collect();
+    if remaining.is_empty() {
+        return false;
+    }
+
+    // The chain enclosing the largest area is the new outer boundary
+    let mut chains = model.chain_edges(&remaining);
+    chains.sort_by(|a, b| {
+        model
+            .chain_area_vector(b)
+            .norm()
+            .partial_cmp(&model.chain_area_vector(a).norm())
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    // Counter-clockwise outer boundary and clockwise holes about the face normal
+    for (i, chain) in chains.iter_mut().enumerate() {
+        let winding = model.chain_area_vector(chain).dot(&normal);
+        if (i == 0) == (winding < 0.0) {
+            chain.reverse();
+        }
+    }
+
+    let loop_ids: Vec<usize> = chains.into_iter().map(|c| model.add_edge_loop(c)).collect();
+    if let Some(face) = model.face_mut(keep) {
+        face.edge_loops = loop_ids;
+    }
+    model.faces.retain(|f| f.id != absorb);
+    model.remove_unused();
+    let corners: Vec<usize> = model
+        .face(keep)
+        .map(|f| model.face_loops(f).into_iter().flat_map(|l| model.loop_vertex_ids(l)).collect())
+        .unwrap_or_default();
+    remove_collinear_vertices(model, &corners);
+    true
+}
+
+/// Remove those of the `candidates` that join exactly two collinear edges
+pub fn remove_collinear_vertices(model: &mut BrepModel, candidates: &[usize]) {
+    for id in candidates {
+        let Some(p) = model.vertex_position(*id) else { continue };
+        let neighbours: Vec<Vector3<f64>> = model
+            .edges
+            .iter()
+            .filter_map(|e| match e.vertices {
+                (a, b) if a == *id => model.vertex_position(b),
+                (a, b) if b == *id => model.vertex_position(a),
+                _ => None,
+            })
+            .collect();
+        let [a, b] = neighbours[..] else { continue };
+        let (u, v) = ((a - p).normalize(), (b - p).normalize());
+        // Collinear: the edges leave the vertex within the angular tolerance of opposite ways
+        if u.dot(&v) < (std::f64::consts::PI - model.tolerance.angular).cos() {
+            // Refused removals leave the vertex in place
+            let _ = delete_vertex(model, *id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::topology::edge_loop::EdgeLoop;
+    use crate::model::brep_model::area_vector;
+
+    #[test]
+    fn test_merge_two_coplanar_squares() {
+        let mut m = BrepModel::new();
+        m.add_face(&[
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(1.0, 1.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+        ]);
+        m.add_face(&[
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(2.0, 0.0, 0.0),
+            Vector3::new(2.0, 1.0, 0.0),
+            Vector3::new(1.0, 1.0, 0.0),
+        ]);
+        assert_eq!(MergeFaces::new().apply(&mut m), 1);
+        assert_eq!(m.faces.len(), 1);
+        // The ends of the shared edge were left between collinear edges
+        assert_eq!((m.vertices.len(), m.edges.len()), (4, 4));
+        let face = &m.faces[0];
+        let n = m.face_normal(face).unwrap();
+        assert!((n - Vector3::z()).norm() < 1e-12);
+        assert!((m.face_area_vector(face).unwrap().norm() - 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_merged_ring_has_clockwise_hole() {
+        let p = |x: f64, y: f64| Vector3::new(x, y, 0.0);
+        let mut m = BrepModel::new();
+        m.add_face(&[p(0.0, 0.0), p(3.0, 0.0), p(3.0, 1.0), p(2.0, 1.0), p(1.0, 1.0), p(0.0, 1.0)]);
+        m.add_face(&[p(0.0, 1.0), p(1.0, 1.0), p(1.0, 2.0), p(0.0, 2.0)]);
+        m.add_face(&[p(2.0, 1.0), p(3.0, 1.0), p(3.0, 2.0), p(2.0, 2.0)]);
+        m.add_face(&[p(0.0, 2.0), p(1.0, 2.0), p(2.0, 2.0), p(3.0, 2.0), p(3.0, 3.0), p(0.0, 3.0)]);
+        assert_eq!(MergeFaces::new().apply(&mut m), 3);
+        assert_eq!(m.faces.len(), 1);
+        let loops = m.face_loops(&m.faces[0]);
+        assert_eq!(loops.len(), 2);
+        let area = |l: &EdgeLoop| area_vector(&m.loop_positions(l)).z;
+        assert!((area(loops[0]) - 9.0).abs() < 1e-12);
+        assert!((area(loops[1]) + 1.0).abs() < 1e-12);
+        // Only the square corners of the outline and the hole are left
+        assert_eq!((m.vertices.len(), m.edges.len()), (8, 8));
+    }
+
+    #[test]
+    fn test_no_merge_across_fold() {
+        let mut m = BrepModel::new();
+        m.add_face(&[
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(1.0, 1.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+        ]);
+        m.add_face(&[
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(1.0, 0.0, 1.0),
+            Vector3::new(1.0, 1.0, 1.0),
+            Vector3::new(1.0, 1.0, 0.0),
+        ]);
+        assert_eq!(MergeFaces::new().apply(&mut m), 0);
+        assert_eq!(m.faces.len(), 2);
+    }
+}
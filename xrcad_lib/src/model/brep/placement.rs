@@ -0,0 +1,165 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: brep::placement
+//!
+//! Placement of new primitives on a picked face or plane: a ray from the cursor
+//! hits the model (or a construction plane), and the hit point and normal give the
+//! frame the primitive's base is set down on.
+
+use nalgebra::{Isometry3, Point3, Rotation3, Translation3, UnitQuaternion, Vector3};
+use serde::{Deserialize, Serialize};
+
+use crate::model::brep::classify::{classify_point_on_face, PointClassification};
+use crate::model::brep::topology::plane::Plane;
+use crate::model::brep_model::BrepModel;
+use crate::model::tolerance::Tolerance;
+
+/// Frame a primitive is built in: its base is centered on `origin` and it grows along `normal`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PlacementFrame {
+    pub origin: Point3<f64>,
+    /// Unit direction of the primitive's local X axis, perpendicular to `normal`
+    pub x_axis: Vector3<f64>,
+    /// Unit direction of the primitive's local Z axis
+    pub normal: Vector3<f64>,
+}
+
+impl PlacementFrame {
+    /// Frame at `origin` growing along `normal`, with X taken from the plane's in-plane axes
+    pub fn new(origin: Point3<f64>, normal: Vector3<f64>) -> Self {
+        let normal = normal.normalize();
+        let (x_axis, _) = Plane::from_point_normal(origin, normal, None).in_plane_axes();
+        Self { origin, x_axis, normal }
+    }
+
+    /// Rigid transform from primitive coordinates to world coordinates
+    pub fn isometry(&self) -> Isometry3<f64> {
+        let y_axis = self.normal.cross(&self.x_axis);
+        let rotation = Rotation3::from_basis_unchecked(&[self.x_axis, y_axis, self.normal]);
+        Isometry3::from_parts(Translation3::from(self.origin.coords), UnitQuaternion::from_rotation_matrix(&rotation))
+    }
+
+    /// Move a primitive built around the world origin so its lowest point sits on the frame
+    pub fn place(&self, model: &mut BrepModel) {
+        let base = model.vertices.iter().map(|v| v.position.z).fold(f64::INFINITY, f64::min);
+        if !base.is_finite() {
+            return;
+        }
+        let iso = self.isometry();
+        for v in &mut model.vertices {
+            v.position = iso.transform_point(&Point3::from(v.position - Vector3::z() * base)).coords;
+        }
+    }
+}
+
+/// Where a ray met a face or plane
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit {
+    /// Id of the face that was hit, `None` for a construction plane
+    pub face: Option<usize>,
+    pub point: Point3<f64>,
+    /// Unit normal at the hit
+    pub normal: Vector3<f64>,
+    /// Distance along the (unit) ray
+    pub distance: f64,
+}
+
+impl RayHit {
+    pub fn frame(&self) -> PlacementFrame {
+        PlacementFrame::new(self.point, self.normal)
+    }
+}
+
+/// Parameter at which a unit ray meets a plane, if in front of the origin
+fn ray_plane_distance(plane: &Plane, origin: &Point3<f64>, dir: &Vector3<f64>, tol: &Tolerance) -> Option<f64> {
+    let denom = plane.normal.dot(dir);
+    if denom.abs() <= tol.angular.sin() * plane.normal.norm() {
+        return None;
+    }
+    let t = -plane.distance(origin) / denom;
+    (t >= 0.0).then_some(t)
+}
+
+/// Nearest face of the model hit by the ray; the normal is the face's outward normal
+pub fn raycast_faces(model: &BrepModel, origin: &Point3<f64>, dir: &Vector3<f64>) -> Option<RayHit> {
+    let dir = dir.normalize();
+    model
+        .faces
+        .iter()
+        .filter_map(|face| {
+            let plane = model.face_plane(face)?;
+            let t = ray_plane_distance(&plane, origin, &dir, &model.tolerance)?;
+            let point = origin + dir * t;
+            if classify_point_on_face(model, face, &point.coords) == PointClassification::Outside {
+                return None;
+            }
+            Some(RayHit { face: Some(face.id), point, normal: plane.normal.normalize(), distance: t })
+        })
+        .min_by(|a, b| a.distance.total_cmp(&b.distance))
+}
+
+/// Where the ray crosses a plane; the normal faces back towards the ray origin
+pub fn raycast_plane(plane: &Plane, origin: &Point3<f64>, dir: &Vector3<f64>, tol: &Tolerance) -> Option<RayHit> {
+    let dir = dir.normalize();
+    let t = ray_plane_distance(plane, origin, &dir, tol)?;
+    let normal = plane.normal.normalize();
+    let normal = if normal.dot(&dir) > 0.0 { -normal } else { normal };
+    Some(RayHit { face: None, point: origin + dir * t, normal, distance: t })
+}
+
+/// Placement under the cursor: the nearest model face, or else the nearest construction plane
+pub fn placement_at(model: &BrepModel, planes: &[Plane], origin: &Point3<f64>, dir: &Vector3<f64>) -> Option<PlacementFrame> {
+    raycast_faces(model, origin, dir)
+        .or_else(|| {
+            planes
+                .iter()
+                .filter_map(|p| raycast_plane(p, origin, dir, &model.tolerance))
+                .min_by(|a, b| a.distance.total_cmp(&b.distance))
+        })
+        .map(|hit| hit.frame())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::{cube, cylinder};
+
+    fn bounds(model: &BrepModel) -> (Vector3<f64>, Vector3<f64>) {
+        model.vertices.iter().fold(
+            (Vector3::repeat(f64::INFINITY), Vector3::repeat(f64::NEG_INFINITY)),
+            |(lo, hi), v| (lo.inf(&v.position), hi.sup(&v.position)),
+        )
+    }
+
+    #[test]
+    fn test_place_on_top_face() {
+        let target = cube(10.0);
+        let frame = placement_at(&target, &[], &Point3::new(1.0, 2.0, 50.0), &-Vector3::z()).unwrap();
+        assert!((frame.origin - Point3::new(1.0, 2.0, 5.0)).norm() < 1e-9);
+        assert!((frame.normal - Vector3::z()).norm() < 1e-9);
+
+        let mut tool = cylinder(1.0, 4.0, 12);
+        frame.place(&mut tool);
+        let (lo, hi) = bounds(&tool);
+        assert!((lo.z - 5.0).abs() < 1e-9 && (hi.z - 9.0).abs() < 1e-9);
+        let center = (lo + hi) * 0.5;
+        assert!((center.x - 1.0).abs() < 1e-9 && (center.y - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_place_on_side_face_and_plane() {
+        let target = cube(10.0);
+        let hit = raycast_faces(&target, &Point3::new(50.0, 0.0, 0.0), &-Vector3::x()).unwrap();
+        let mut tool = cube(2.0);
+        hit.frame().place(&mut tool);
+        let (lo, hi) = bounds(&tool);
+        assert!((lo.x - 5.0).abs() < 1e-9 && (hi.x - 7.0).abs() < 1e-9);
+
+        // Missing the model falls back to the construction plane, facing the viewer
+        let frame = placement_at(&target, &[Plane::xy()], &Point3::new(20.0, 0.0, -10.0), &Vector3::z()).unwrap();
+        assert!((frame.origin - Point3::new(20.0, 0.0, 0.0)).norm() < 1e-9);
+        assert!((frame.normal + Vector3::z()).norm() < 1e-9);
+        assert!(placement_at(&target, &[], &Point3::new(20.0, 0.0, -10.0), &Vector3::z()).is_none());
+    }
+}
@@ -0,0 +1,109 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: brep::primitives
+//!
+//! Closed primitive solids built as BREP topology, centered at the origin with
+//! outward-facing (counter-clockwise seen from outside) faces.
+
+use nalgebra::Vector3;
+
+use crate::model::brep_model::BrepModel;
+use crate::model::units::Length;
+
+/// Axis-aligned box with the given edge lengths
+pub fn cuboid(size: Vector3<f64>) -> BrepModel {
+    let h = size * 0.5;
+    let p = |x: f64, y: f64, z: f64| Vector3::new(x * h.x, y * h.y, z * h.z);
+    let mut model = BrepModel::new();
+    // bottom, top
+    model.add_face(&[p(-1.0, -1.0, -1.0), p(-1.0, 1.0, -1.0), p(1.0, 1.0, -1.0), p(1.0, -1.0, -1.0)]);
+    model.add_face(&[p(-1.0, -1.0, 1.0), p(1.0, -1.0, 1.0), p(1.0, 1.0, 1.0), p(-1.0, 1.0, 1.0)]);
+    // front (-y), back (+y)
+    model.add_face(&[p(-1.0, -1.0, -1.0), p(1.0, -1.0, -1.0), p(1.0, -1.0, 1.0), p(-1.0, -1.0, 1.0)]);
+    model.add_face(&[p(-1.0, 1.0, -1.0), p(-1.0, 1.0, 1.0), p(1.0, 1.0, 1.0), p(1.0, 1.0, -1.0)]);
+    // left (-x), right (+x)
+    model.add_face(&[p(-1.0, -1.0, -1.0), p(-1.0, -1.0, 1.0), p(-1.0, 1.0, 1.0), p(-1.0, 1.0, -1.0)]);
+    model.add_face(&[p(1.0, -1.0, -1.0), p(1.0, 1.0, -1.0), p(1.0, 1.0, 1.0), p(1.0, -1.0, 1.0)]);
+    model
+}
+
+/// Cube with the given edge length
+pub fn cube(size: impl Into<Length>) -> BrepModel {
+    let size = size.into().as_mm();
+    cuboid(Vector3::new(size, size, size))
+}
+
+/// Faceted cylinder along Z with `segments` side faces
+pub fn cylinder(radius: impl Into<Length>, height: impl Into<Length>, segments: usize) -> BrepModel {
+    let radius = radius.into();
+    frustum(radius, radius, height, segments)
+}
+
+/// Faceted truncated cone along Z with `segments` side faces; both radii must be positive
+pub fn frustum(
+    bottom_radius: impl Into<Length>,
+    top_radius: impl Into<Length>,
+    height: impl Into<Length>,
+    segments: usize,
+) -> BrepModel {
+    let (bottom_radius, top_radius) = (bottom_radius.into().as_mm(), top_radius.into().as_mm());
+    let segments = segments.max(3);
+    let h = height.into().as_mm() * 0.5;
+    let ring = |radius: f64, z: f64| -> Vec<Vector3<f64>> {
+        (0..segments)
+            .map(|i| {
+                let a = std::f64::consts::TAU * i as f64 / segments as f64;
+                Vector3::new(radius * a.cos(), radius * a.sin(), z)
+            })
+            .collect()
+    };
+    let (bottom, top) = (ring(bottom_radius, -h), ring(top_radius, h));
+    let mut model = BrepModel::new();
+    model.add_face(&bottom.iter().rev().copied().collect::<Vec<_>>());
+    model.add_face(&top);
+    for i in 0..segments {
+        let j = (i + 1) % segments;
+        model.add_face(&[bottom[i], bottom[j], top[j], top[i]]);
+    }
+    model
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cube_topology() {
+        let c = cube(2.0);
+        assert_eq!(c.vertices.len(), 8);
+        assert_eq!(c.edges.len(), 12);
+        assert_eq!(c.faces.len(), 6);
+        for e in &c.edges {
+            assert_eq!(c.faces_using_edge(e.id).len(), 2);
+        }
+    }
+
+    #[test]
+    fn test_cylinder_topology() {
+        let c = cylinder(1.0, 2.0, 8);
+        assert_eq!(c.vertices.len(), 16);
+        assert_eq!(c.edges.len(), 24);
+        assert_eq!(c.faces.len(), 10);
+        for f in &c.faces {
+            let plane = c.face_plane(f).unwrap();
+            assert!(plane.distance(&nalgebra::Point3::origin()) < 0.0);
+        }
+    }
+
+    #[test]
+    fn test_cuboid_faces_point_outward() {
+        let c = cuboid(Vector3::new(1.0, 2.0, 3.0));
+        for f in &c.faces {
+            let n = c.face_normal(f).unwrap();
+            let centre = c.face_plane(f).unwrap();
+            // The origin is behind every outward face
+            assert!(centre.distance(&nalgebra::Point3::origin()) < 0.0, "face {} normal {:?}", f.id, n);
+        }
+    }
+}
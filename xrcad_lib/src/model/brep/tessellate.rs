@@ -0,0 +1,246 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: brep::tessellate
+//!
+//! Triangulates planar faces (with holes) by ear clipping. Triangles wind
+//! counter-clockwise about the face normal.
+
+use nalgebra::{Point3, Vector2, Vector3};
+
+use crate::model::brep::geometry::polygon::signed_area_2d;
+use crate::model::brep::topology::face::Face;
+use crate::model::brep_model::BrepModel;
+
+/// Triangles of a single face
+#[derive(Debug, Clone, PartialEq)]
+pub struct FaceMesh {
+    pub face_id: usize,
+    pub normal: Vector3<f64>,
+    pub positions: Vec<Vector3<f64>>,
+    pub triangles: Vec<[usize; 3]>,
+}
+
+impl FaceMesh {
+    /// Corner positions of each triangle
+    pub fn triangle_positions(&self) -> impl Iterator<Item = [Vector3<f64>; 3]> + '_ {
+        self.triangles
+            .iter()
+            .map(|t| [self.positions[t[0]], self.positions[t[1]], self.positions[t[2]]])
+    }
+}
+
+fn cross_2d(a: &Vector2<f64>, b: &Vector2<f64>, c: &Vector2<f64>) -> f64 {
+    (b - a).perp(&(c - a))
+}
+
+/// Strictly inside the counter-clockwise triangle a, b, c
+fn in_triangle(p: &Vector2<f64>, a: &Vector2<f64>, b: &Vector2<f64>, c: &Vector2<f64>) -> bool {
+    cross_2d(a, b, p) > 0.0 && cross_2d(b, c, p) > 0.0 && cross_2d(c, a, p) > 0.0
+}
+
+/// Inside or on the boundary of the counter-clockwise triangle a, b, c
+fn on_or_in_triangle(p: &Vector2<f64>, a: &Vector2<f64>, b: &Vector2<f64>, c: &Vector2<f64>) -> bool {
+    cross_2d(a, b, p) >= 0.0 && cross_2d(b, c, p) >= 0.0 && cross_2d(c, a, p) >= 0.0
+}
+
+/// Splice a clockwise hole into the counter-clockwise polygon through a visible bridge vertex
+fn bridge_hole(pts: &[Vector2<f64>], poly: &mut Vec<usize>, hole: &[usize]) {
+    let start = (0..hole.len())
+        .max_by(|a, b| pts[hole[*a]].x.total_cmp(&pts[hole[*b]].x))
+        .unwrap_or(0);
+    let m = pts[hole[start]];
+
+    // Closest edge hit by a ray from M towards +x
+    let mut best: Option<(f64, usize)> = None;
+    for i in 0..poly.len() {
+        let (a, b) = (pts[poly[i]], pts[poly[(i + 1) % poly.len()]]);
+        if (a.y > m.y) == (b.y > m.y) {
+            continue;
+        }
+        let x = a.x + (m.y - a.y) / (b.y - a.y) * (b.x - a.x);
+        if x >= m.x && best.is_none_or(|(bx, _)| x < bx) {
+            best = Some((x, i));
+        }
+    }
+    let Some((ix, edge)) = best else { return; };
+    let i_point = Vector2::new(ix, m.y);
+    let (ea, eb) = (edge, (edge + 1) % poly.len());
+    let mut bridge = if pts[poly[ea]].x > pts[poly[eb]].x { ea } else { eb };
+
+    // A vertex inside triangle (M, I, P) would block the bridge; take the one closest in angle
+    let p = pts[poly[bridge]];
+    let (t0, t1, t2) = if cross_2d(&m, &i_point, &p) > 0.0 { (m, i_point, p) } else { (m, p, i_point) };
+    let mut best_angle = f64::INFINITY;
+    for (k, idx) in poly.iter().enumerate() {
+        let q = pts[*idx];
+        if k != bridge && in_triangle(&q, &t0, &t1, &t2) {
+            let angle = (q.y - m.y).atan2(q.x - m.x).abs();
+            if angle < best_angle {
+                best_angle = angle;
+                bridge = k;
+            }
+        }
+    }
+
+    let mut spliced: Vec<usize> = poly[..=bridge].to_vec();
+    spliced.extend((0..=hole.len()).map(|k| hole[(start + k) % hole.len()]));
+    spliced.extend_from_slice(&poly[bridge..]);
+    *poly = spliced;
+}
+
+/// Triangulate a 2D polygon with holes. Vertex indices refer to the outer loop
+/// followed by each hole in order; triangles are counter-clockwise.
+pub fn triangulate_polygon_2d(outer: &[Vector2<f64>], holes: &[Vec<Vector2<f64>>]) -> Vec<[usize; 3]> {
+    let pts: Vec<Vector2<f64>> = outer.iter().chain(holes.iter().flatten()).copied().collect();
+    let mut poly: Vec<usize> = (0..outer.len()).collect();
+    if signed_area_2d(outer) < 0.0 {
+        poly.reverse();
+    }
+
+    let mut offset = outer.len();
+    let mut hole_indices: Vec<Vec<usize>> = holes
+        .iter()
+        .map(|h| {
+            let mut idx: Vec<usize> = (offset..offset + h.len()).collect();
+            offset += h.len();
+            if signed_area_2d(h) > 0.0 {
+                idx.reverse();
+            }
+            idx
+        })
+        .filter(|h| h.len() >= 3)
+        .collect();
+    hole_indices.sort_by(|a, b| {
+        let max_x = |h: &Vec<usize>| h.iter().map(|i| pts[*i].x).fold(f64::NEG_INFINITY, f64::max);
+        max_x(b).total_cmp(&max_x(a))
+    });
+    for hole in &hole_indices {
+        bridge_hole(&pts, &mut poly, hole);
+    }
+
+    let mut triangles = Vec::new();
+    while poly.len() > 3 {
+        let n = poly.len();
+        let ear = (0..n).find(|&i| {
+            let (a, b, c) = (pts[poly[(i + n - 1) % n]], pts[poly[i]], pts[poly[(i + 1) % n]]);
+            cross_2d(&a, &b, &c) > 0.0
+                && poly.iter().all(|j| {
+                    let q = pts[*j];
+                    q == a || q == b || q == c || !on_or_in_triangle(&q, &a, &b, &c)
+                })
+        });
+        match ear {
+            Some(i) => {
+                triangles.push([poly[(i + n - 1) % n], poly[i], poly[(i + 1) % n]]);
+                poly.remove(i);
+            }
+            None => {
+                // Drop a collinear corner, or give up on a malformed polygon
+                let flat = (0..n).find(|&i| {
+                    cross_2d(&pts[poly[(i + n - 1) % n]], &pts[poly[i]], &pts[poly[(i + 1) % n]]) == 0.0
+                });
+                match flat {
+                    Some(i) => {
+                        poly.remove(i);
+                    }
+                    None => break,
+                }
+            }
+        }
+    }
+    if poly.len() == 3 && cross_2d(&pts[poly[0]], &pts[poly[1]], &pts[poly[2]]) > 0.0 {
+        triangles.push([poly[0], poly[1], poly[2]]);
+    }
+    triangles
+}
+
+/// Triangulate one planar face
+pub fn tessellate_face(model: &BrepModel, face: &Face) -> Option<FaceMesh> {
+    let plane = model.face_plane(face)?;
+    let loops: Vec<Vec<Vector3<f64>>> = model
+        .face_loops(face)
+        .into_iter()
+        .map(|l| model.loop_positions(l))
+        .collect();
+    let (outer, holes) = loops.split_first()?;
+    let project = |l: &Vec<Vector3<f64>>| -> Vec<Vector2<f64>> {
+        l.iter().map(|p| plane.project_2d(&Point3::from(*p))).collect()
+    };
+    let holes_2d: Vec<Vec<Vector2<f64>>> = holes.iter().map(project).collect();
+    let triangles = triangulate_polygon_2d(&project(outer), &holes_2d);
+    Some(FaceMesh {
+        face_id: face.id,
+        normal: plane.normal,
+        positions: loops.into_iter().flatten().collect(),
+        triangles,
+    })
+}
+
+/// Triangulate every face of the model
+pub fn tessellate(model: &BrepModel) -> Vec<FaceMesh> {
+    model.faces.iter().filter_map(|f| tessellate_face(model, f)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::cube;
+
+    fn area(mesh: &FaceMesh) -> f64 {
+        mesh.triangle_positions().map(|[a, b, c]| (b - a).cross(&(c - a)).norm() * 0.5).sum()
+    }
+
+    #[test]
+    fn test_triangulate_concave() {
+        let l_shape = [
+            Vector2::new(0.0, 0.0),
+            Vector2::new(2.0, 0.0),
+            Vector2::new(2.0, 1.0),
+            Vector2::new(1.0, 1.0),
+            Vector2::new(1.0, 2.0),
+            Vector2::new(0.0, 2.0),
+        ];
+        let tris = triangulate_polygon_2d(&l_shape, &[]);
+        assert_eq!(tris.len(), 4);
+        let total: f64 = tris.iter().map(|t| cross_2d(&l_shape[t[0]], &l_shape[t[1]], &l_shape[t[2]]) * 0.5).sum();
+        assert!((total - 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_face_with_hole() {
+        let mut m = BrepModel::new();
+        let f = m.add_face(&[
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(4.0, 0.0, 0.0),
+            Vector3::new(4.0, 4.0, 0.0),
+            Vector3::new(0.0, 4.0, 0.0),
+        ]);
+        let hole: Vec<usize> = [
+            Vector3::new(1.0, 1.0, 0.0),
+            Vector3::new(3.0, 1.0, 0.0),
+            Vector3::new(3.0, 3.0, 0.0),
+            Vector3::new(1.0, 3.0, 0.0),
+        ]
+        .iter()
+        .map(|p| m.add_vertex(*p))
+        .collect();
+        let edges = (0..4).map(|i| m.add_edge(hole[i], hole[(i + 1) % 4])).collect();
+        let l = m.add_edge_loop(edges);
+        m.face_mut(f).unwrap().edge_loops.push(l);
+
+        let mesh = tessellate_face(&m, m.face(f).unwrap()).unwrap();
+        assert_eq!(mesh.triangles.len(), 8);
+        assert!((area(&mesh) - 12.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_triangles_follow_face_normal() {
+        for mesh in tessellate(&cube(2.0)) {
+            assert_eq!(mesh.triangles.len(), 2);
+            for [a, b, c] in mesh.triangle_positions() {
+                assert!((b - a).cross(&(c - a)).dot(&mesh.normal) > 0.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,123 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: brep::text_format
+//!
+//! Line-oriented plain-text form of BREP topology, used for crash dumps and
+//! test fixtures:
+//!
+//! ```text
+//! vertex <id> <x> <y> <z>
+//! edge <id> <v0> <v1>
+//! loop <id> <e> <e> ... [/ <e> ...]
+//! face <id> <loop> [<loop> ...]
+//! ```
+//!
+//! Blank lines and lines starting with `#` are ignored.
+
+use std::fmt::{self, Write as _};
+
+use nalgebra::Vector3;
+
+use crate::model::brep::topology::{edge::Edge, edge_loop::EdgeLoop, face::Face, vertex::Vertex};
+use crate::model::brep_model::BrepModel;
+
+/// A line that could not be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Serialize the topology of a model
+pub fn to_text(model: &BrepModel) -> String {
+    let mut out = String::new();
+    for v in &model.vertices {
+        let _ = writeln!(out, "vertex {} {} {} {}", v.id, v.position.x, v.position.y, v.position.z);
+    }
+    for e in &model.edges {
+        let _ = writeln!(out, "edge {} {} {}", e.id, e.vertices.0, e.vertices.1);
+    }
+    for l in &model.edgeloops {
+        let chains: Vec<String> =
+            l.edges.iter().map(|c| c.iter().map(|e| e.to_string()).collect::<Vec<_>>().join(" ")).collect();
+        let _ = writeln!(out, "loop {} {}", l.id, chains.join(" / "));
+    }
+    for f in &model.faces {
+        let loops: Vec<String> = f.edge_loops.iter().map(|l| l.to_string()).collect();
+        let _ = writeln!(out, "face {} {}", f.id, loops.join(" "));
+    }
+    out
+}
+
+/// Parse topology written by `to_text`
+pub fn from_text(text: &str) -> Result<BrepModel, ParseError> {
+    let mut model = BrepModel::new();
+    for (index, raw) in text.lines().enumerate() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let err = |message: &str| ParseError { line: index + 1, message: message.to_string() };
+        let mut tokens = line.split_whitespace();
+        let keyword = tokens.next().unwrap_or_default();
+        let id: usize = tokens.next().and_then(|t| t.parse().ok()).ok_or_else(|| err("missing or invalid id"))?;
+        let rest: Vec<&str> = tokens.collect();
+        let ids = |tokens: &[&str]| -> Result<Vec<usize>, ParseError> {
+            tokens.iter().map(|t| t.parse().map_err(|_| err(&format!("invalid id '{}'", t)))).collect()
+        };
+        match keyword {
+            "vertex" => {
+                let coords: Vec<f64> = rest
+                    .iter()
+                    .map(|t| t.parse().map_err(|_| err(&format!("invalid coordinate '{}'", t))))
+                    .collect::<Result<_, _>>()?;
+                let [x, y, z] = coords[..] else { return Err(err("vertex needs 3 coordinates")); };
+                model.vertices.push(Vertex { id, position: Vector3::new(x, y, z) });
+            }
+            "edge" => {
+                let [a, b] = ids(&rest)?[..] else { return Err(err("edge needs 2 vertices")); };
+                model.edges.push(Edge { id, vertices: (a, b) });
+            }
+            "loop" => {
+                let chains = rest
+                    .split(|t| *t == "/")
+                    .map(&ids)
+                    .collect::<Result<Vec<_>, _>>()?;
+                model.edgeloops.push(EdgeLoop::new(id, chains));
+            }
+            "face" => model.faces.push(Face::new(id, ids(&rest)?)),
+            other => return Err(err(&format!("unknown record '{}'", other))),
+        }
+    }
+    Ok(model)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::cylinder;
+
+    #[test]
+    fn test_round_trip() {
+        let model = cylinder(2.0, 3.0, 8);
+        let parsed = from_text(&to_text(&model)).unwrap();
+        assert_eq!(to_text(&parsed), to_text(&model));
+        assert_eq!(parsed.faces.len(), 10);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(from_text("# comment\n\nvertex 0 1 2\n").unwrap_err().line, 3);
+        assert!(from_text("widget 0").is_err());
+        assert!(from_text("edge 0 1 x").is_err());
+    }
+}
@@ -4,7 +4,31 @@
 //! Module: brep::core::topo::edge
 
 
-#[derive(Debug, Default, Clone)]
+use serde::{Deserialize, Serialize};
+
+/// Display classification of an edge from the faces meeting at it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeKind {
+    /// Not shared by exactly two faces (open boundary or non-manifold)
+    Boundary,
+    /// Adjacent faces meet at a crease
+    Sharp,
+    /// Adjacent faces meet smoothly (e.g. along a fillet)
+    Tangent,
+}
+
+impl EdgeKind {
+    /// Classify from the dihedral angle (radians between face normals) and a smoothness threshold
+    pub fn from_dihedral(angle: Option<f64>, tangent_threshold: f64) -> Self {
+        match angle {
+            None => EdgeKind::Boundary,
+            Some(a) if a <= tangent_threshold => EdgeKind::Tangent,
+            Some(_) => EdgeKind::Sharp,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Edge{
     pub id: usize,
     pub vertices: (usize, usize), // IDs of the start and end vertices
@@ -27,4 +51,11 @@ mod tests {
         let _ = Edge::new(1, 0, 1);
         // Add more meaningful tests as needed
     }
+    #[test]
+    fn test_edge_kind_from_dihedral() {
+        let threshold = 1f64.to_radians();
+        assert_eq!(EdgeKind::from_dihedral(None, threshold), EdgeKind::Boundary);
+        assert_eq!(EdgeKind::from_dihedral(Some(0.0), threshold), EdgeKind::Tangent);
+        assert_eq!(EdgeKind::from_dihedral(Some(std::f64::consts::FRAC_PI_2), threshold), EdgeKind::Sharp);
+    }
 }
@@ -3,7 +3,9 @@
 
 //! Module: brep::core::topo::edge_loop
 
-#[derive(Debug, Default, Clone)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct EdgeLoop{
     pub id: usize,
     pub edges: Vec<Vec<usize>>,
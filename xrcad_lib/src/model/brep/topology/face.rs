@@ -3,19 +3,36 @@
 
 //! Module: brep::core::topo::face
 
+use nalgebra::Vector3;
+use serde::{Deserialize, Serialize};
 
+use crate::model::body::BodyId;
+use crate::model::brep::classify::{classify_point_on_face, PointClassification};
+use crate::model::brep_model::BrepModel;
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Face{
     pub id: usize,
     pub edge_loops: Vec<usize>,
+    /// Body the face belongs to, once the model has given its shell an id
+    #[serde(default)]
+    pub body: Option<BodyId>,
 }
 
 impl Face {
     pub fn new(id: usize, edge_loops: Vec<usize>) -> Self {
-        Self { id, edge_loops }
+        Self { id, edge_loops, body: None }
+    }
+
+    /// Classify a point against this face (holes excluded by loop parity)
+    pub fn classify(&self, model: &BrepModel, point: &Vector3<f64>) -> PointClassification {
+        classify_point_on_face(model, self, point)
+    }
+
+    /// True if the point lies on the face, including its boundary
+    pub fn contains_point(&self, model: &BrepModel, point: &Vector3<f64>) -> bool {
+        self.classify(model, point) != PointClassification::Outside
     }
-    // ...other inherent methods...
 }
 
 #[cfg(test)]
@@ -0,0 +1,181 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: brep::topology::invariants
+//!
+//! Invariants every closed shell keeps through modeling operations: the
+//! Euler-Poincaré formula, every edge shared by exactly two faces, and faces
+//! oriented consistently (the two faces at an edge walk it in opposite
+//! directions) and outwards. Property tests check them over random primitives
+//! and transforms; debug builds check bodies as they are edited.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::measure::mass_properties::mass_properties;
+use crate::model::brep_model::BrepModel;
+
+/// A closed shell invariant that does not hold
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvariantViolation {
+    /// V - E + F - H (H: inner loops) must be 2 - 2 * genus: even and at most 2
+    Euler { shell: usize, characteristic: i64 },
+    /// An edge is not used by exactly two faces
+    EdgeSharing { edge: usize, uses: usize },
+    /// The two faces at an edge walk it in the same direction
+    Orientation { edge: usize },
+    /// The shell encloses no volume with its faces pointing outwards
+    Inverted { shell: usize },
+}
+
+impl fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvariantViolation::Euler { shell, characteristic } => write!(f, "shell {} has Euler characteristic {}", shell, characteristic),
+            InvariantViolation::EdgeSharing { edge, uses } => write!(f, "edge {} is used {} time(s), not twice", edge, uses),
+            InvariantViolation::Orientation { edge } => write!(f, "faces at edge {} are oriented inconsistently", edge),
+            InvariantViolation::Inverted { shell } => write!(f, "shell {} has non-positive volume", shell),
+        }
+    }
+}
+
+/// Check one closed shell, given by its face ids; `shell` labels the violations
+pub fn check_shell(model: &BrepModel, shell: usize, faces: &[usize]) -> Vec<InvariantViolation> {
+    let mut violations = Vec::new();
+    // Each use of an edge as (from, to) vertex ids, in loop order
+    let mut uses: HashMap<usize, Vec<(usize, usize)>> = HashMap::new();
+    let mut inner_loops = 0;
+    let mut vertices = HashSet::new();
+    for face in faces.iter().filter_map(|id| model.face(*id)) {
+        let loops = model.face_loops(face);
+        inner_loops += loops.len().saturating_sub(1);
+        for edge_loop in loops {
+            let corners = model.loop_vertex_ids(edge_loop);
+            let edges: Vec<usize> = edge_loop.edges.iter().flatten().copied().collect();
+            // Dangling edge references are for `validate` to report
+            if corners.len() != edges.len() {
+                continue;
+            }
+            vertices.extend(corners.iter().copied());
+            for (i, edge) in edges.iter().enumerate() {
+                let from = corners[i];
+                let to = corners[(i + 1) % corners.len()];
+                uses.entry(*edge).or_default().push((from, to));
+            }
+        }
+    }
+    let characteristic = vertices.len() as i64 - uses.len() as i64 + faces.len() as i64 - inner_loops as i64;
+    if characteristic % 2 != 0 || characteristic > 2 {
+        violations.push(InvariantViolation::Euler { shell, characteristic });
+    }
+    let mut edges: Vec<_> = uses.into_iter().collect();
+    edges.sort_unstable_by_key(|(edge, _)| *edge);
+    for (edge, walks) in edges {
+        match walks[..] {
+            [a, b] if a.0 == b.0 => violations.push(InvariantViolation::Orientation { edge }),
+            [_, _] => {}
+            _ => violations.push(InvariantViolation::EdgeSharing { edge, uses: walks.len() }),
+        }
+    }
+    if violations.is_empty() && mass_properties(&model.extract_faces(faces)).volume <= 0.0 {
+        violations.push(InvariantViolation::Inverted { shell });
+    }
+    violations
+}
+
+/// Check every shell of the model
+pub fn check(model: &BrepModel) -> Vec<InvariantViolation> {
+    model.shells().iter().enumerate().flat_map(|(shell, faces)| check_shell(model, shell, faces)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::{Rotation3, Unit, Vector3};
+    use proptest::prelude::*;
+
+    use crate::model::brep::primitives::{cube, cuboid, cylinder, frustum};
+
+    /// A rigid move or uniform scale applied to every vertex
+    #[derive(Debug, Clone)]
+    enum Transform {
+        Translate(Vector3<f64>),
+        Rotate(Vector3<f64>, f64),
+        Scale(f64),
+    }
+
+    fn vector(range: f64) -> impl Strategy<Value = Vector3<f64>> {
+        (-range..range, -range..range, -range..range).prop_map(|(x, y, z)| Vector3::new(x, y, z))
+    }
+
+    fn primitive() -> impl Strategy<Value = BrepModel> {
+        prop_oneof![
+            (0.1f64..100.0, 0.1f64..100.0, 0.1f64..100.0).prop_map(|(x, y, z)| cuboid(Vector3::new(x, y, z))),
+            (0.1f64..50.0, 0.1f64..100.0, 3usize..48).prop_map(|(r, h, n)| cylinder(r, h, n)),
+            (0.1f64..50.0, 0.1f64..50.0, 0.1f64..100.0, 3usize..48).prop_map(|(a, b, h, n)| frustum(a, b, h, n)),
+        ]
+    }
+
+    fn transform() -> impl Strategy<Value = Transform> {
+        prop_oneof![
+            vector(1000.0).prop_map(Transform::Translate),
+            (vector(1.0).prop_filter("axis", |a| a.norm() > 1e-3), -3.2f64..3.2).prop_map(|(axis, angle)| Transform::Rotate(axis, angle)),
+            (0.1f64..10.0).prop_map(Transform::Scale),
+        ]
+    }
+
+    fn apply(model: &mut BrepModel, transform: &Transform) {
+        for v in model.vertices.iter_mut() {
+            v.position = match transform {
+                Transform::Translate(offset) => v.position + offset,
+                Transform::Rotate(axis, angle) => Rotation3::from_axis_angle(&Unit::new_normalize(*axis), *angle) * v.position,
+                Transform::Scale(factor) => v.position * *factor,
+            };
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_transformed_primitives_keep_invariants(
+            mut model in primitive(),
+            transforms in prop::collection::vec(transform(), 0..6),
+        ) {
+            for transform in &transforms {
+                apply(&mut model, transform);
+            }
+            let violations = check(&model);
+            prop_assert!(violations.is_empty(), "{:?}", violations);
+        }
+
+        #[test]
+        fn test_separate_bodies_keep_invariants(
+            a in primitive(),
+            b in primitive(),
+            offset in vector(1000.0),
+        ) {
+            let mut model = a;
+            let mut other = b;
+            apply(&mut other, &Transform::Translate(offset));
+            model.append(&other);
+            prop_assert_eq!(model.shells().len(), 2);
+            let violations = check(&model);
+            prop_assert!(violations.is_empty(), "{:?}", violations);
+        }
+    }
+
+    #[test]
+    fn test_broken_shells_are_reported() {
+        let mut flipped = cube(2.0);
+        let outer = flipped.faces[0].edge_loops[0];
+        flipped.edgeloops.iter_mut().filter(|l| l.id == outer).for_each(|l| l.edges.iter_mut().for_each(|c| c.reverse()));
+        let violations = check(&flipped);
+        assert_eq!(violations.len(), 4);
+        assert!(violations.iter().all(|v| matches!(v, InvariantViolation::Orientation { .. })));
+
+        let mut open = cube(2.0);
+        open.faces.pop();
+        let violations = check(&open);
+        assert!(violations.contains(&InvariantViolation::Euler { shell: 0, characteristic: 1 }));
+        assert_eq!(violations.iter().filter(|v| matches!(v, InvariantViolation::EdgeSharing { uses: 1, .. })).count(), 4);
+    }
+}
@@ -4,13 +4,43 @@ impl Default for Plane {
         Self::xy()
     }
 }
-use bevy::{color::Alpha};
+use bevy::color::{Alpha, Color};
+use bevy::math::Vec3;
+#[cfg(feature = "render")]
 use bevy::prelude::Gizmos;
 
 use crate::color::*;
 use crate::model::brep_model::na_vec3_to_bevy;
+use crate::model::tolerance::Tolerance;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Half size of the quad drawn for a plane by default
+pub const PLANE_RENDER_SIZE: f64 = 100.0;
+
+/// How large a plane is drawn
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PlaneExtent {
+    /// Square of this half size around the plane's center
+    Fixed(f64),
+    /// Fitted around the model vertices within `reach` of the plane, `margin` wider on each side
+    Auto { reach: f64, margin: f64 },
+}
+
+impl Default for PlaneExtent {
+    fn default() -> Self {
+        PlaneExtent::Fixed(PLANE_RENDER_SIZE)
+    }
+}
+
+/// Drawn rectangle of a plane: its center and half sizes along the in-plane axes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlaneRect {
+    pub center: Point3<f64>,
+    pub half_u: f64,
+    pub half_v: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PlaneRenderMode {
     Simple,
     Ghosted,
@@ -18,11 +48,11 @@ pub enum PlaneRenderMode {
     Grid,
 }
 
-use nalgebra::{Vector3, Point3};
+use nalgebra::{Vector2, Vector3, Point3};
 
 /// A geometric plane in 3D, defined by normal and distance from origin (ax + by + cz + d = 0)
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum PlaneOrigin {
     PointNormal { point: Point3<f64>, normal: Vector3<f64>, offset: Option<f64> },
     ThreePoints { a: Point3<f64>, b: Point3<f64>, c: Point3<f64> },
@@ -33,7 +63,7 @@ pub enum PlaneOrigin {
     Unknown,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Plane {
     pub normal: Vector3<f64>,
     pub d: f64,
@@ -46,6 +76,9 @@ pub struct Plane {
     pub visible: bool,
     /// Current render mode
     pub render_mode: PlaneRenderMode,
+    /// Drawn size
+    #[serde(default)]
+    pub extent: PlaneExtent,
 }
 
 impl Plane {
@@ -55,6 +88,11 @@ impl Plane {
         self.render_mode = mode;
     }
 
+    /// Set how large the plane is drawn
+    pub fn set_extent(&mut self, extent: PlaneExtent) {
+        self.extent = extent;
+    }
+
     /// Returns a new plane rotated around its normal by the given angle (radians), about the given center point (default: origin of plane)
     pub fn rotate_around_normal(&self, angle: f64, center: Option<Point3<f64>>) -> Self {
         use nalgebra::{Rotation3, Unit};
@@ -152,6 +190,16 @@ impl Plane {
         flipped.facing = !self.facing;
         flipped
     }
+    /// Returns a copy moved `distance` along its normal, keeping its center, extent and render mode
+    pub fn offset(&self, distance: f64) -> Self {
+        let normal = self.normal.normalize();
+        let mut plane = Plane::from_point_normal(self.render_center() + normal * distance, normal, None);
+        plane.rotation = self.rotation;
+        plane.render_mode = self.render_mode;
+        plane.extent = self.extent;
+        plane
+    }
+
     /// Construct from a point and normal (optionally offset by distance along normal)
     pub fn from_point_normal(point: Point3<f64>, normal: Vector3<f64>, offset: Option<f64>) -> Self {
         let n = normal.normalize();
@@ -164,17 +212,21 @@ impl Plane {
             facing: true,
             visible: true,
             render_mode: PlaneRenderMode::Simple,
+            extent: PlaneExtent::default(),
         }
     }
 
     /// Construct from three non-collinear points
     pub fn from_points(a: Point3<f64>, b: Point3<f64>, c: Point3<f64>) -> Option<Self> {
-        let ab = b - a;
-        let ac = c - a;
-        let n = ab.cross(&ac);
-        if n.norm() < 1e-10 {
+        Self::from_points_with_tolerance(a, b, c, &Tolerance::default())
+    }
+
+    /// Construct from three points, rejecting them if collinear within `tol`
+    pub fn from_points_with_tolerance(a: Point3<f64>, b: Point3<f64>, c: Point3<f64>, tol: &Tolerance) -> Option<Self> {
+        if tol.collinear(&a.coords, &b.coords, &c.coords) {
             return None; // Degenerate
         }
+        let n = (b - a).cross(&(c - a));
         let mut plane = Self::from_point_normal(a, n, None);
         plane.origin = PlaneOrigin::ThreePoints { a, b, c };
         plane.rotation = 0.0;
@@ -209,6 +261,7 @@ impl Plane {
             facing: true,
             visible: true,
             render_mode: PlaneRenderMode::Simple,
+            extent: PlaneExtent::default(),
         }
     }
 
@@ -222,6 +275,7 @@ impl Plane {
             facing: true,
             visible: true,
             render_mode: PlaneRenderMode::Simple,
+            extent: PlaneExtent::default(),
         }
     }
 
@@ -235,27 +289,106 @@ impl Plane {
             facing: true,
             visible: true,
             render_mode: PlaneRenderMode::Simple,
+            extent: PlaneExtent::default(),
         }
     }
-    /// Render the plane using Bevy gizmos, with mode and visibility toggle
-    pub fn render(&self, gizmos: &mut Gizmos) {
-        if !self.visible {
-            return;
-        }
-        // Pick a color and style based on mode
+    /// Color of the plane's outline in its render mode; grid lines are fainter
+    pub fn render_color(&self) -> Color {
         let (color, alpha) = match self.render_mode {
             PlaneRenderMode::Simple => (CYAN, 0.5),
             PlaneRenderMode::Ghosted => (GREEN, 0.15),
             PlaneRenderMode::Highlighted => (YELLOW, 0.7),
             PlaneRenderMode::Grid => (MAGENTA, 0.3),
         };
-        // Draw a quad in the plane (centered at origin or construction point)
-        let center = if let PlaneOrigin::PointNormal { point, .. } = &self.origin {
-            *point
-        } else {
-            Point3::origin() - self.normal * self.d
+        color.with_alpha(alpha)
+    }
+
+    /// Center of the drawn quad: its construction points, else the point nearest the origin
+    fn render_center(&self) -> Point3<f64> {
+        match &self.origin {
+            PlaneOrigin::PointNormal { point, .. } | PlaneOrigin::LineAngle { point, .. } => *point,
+            PlaneOrigin::ThreePoints { a, b, c } => Point3::from((a.coords + b.coords + c.coords) / 3.0),
+            _ => Point3::origin() - self.normal * self.d,
+        }
+    }
+
+    /// Rectangle drawn for the plane. A fixed extent is a square around the
+    /// plane's center; an automatic one covers the projections of the
+    /// `vertices` within reach of the plane, or falls back to the default size
+    /// when none are.
+    pub fn render_rect(&self, vertices: &[Point3<f64>]) -> PlaneRect {
+        let square = |half: f64| PlaneRect { center: self.render_center(), half_u: half, half_v: half };
+        let (reach, margin) = match self.extent {
+            PlaneExtent::Fixed(half) => return square(half),
+            PlaneExtent::Auto { reach, margin } => (reach, margin),
         };
-        // Get two perpendicular axes in the plane
+        let bounds = vertices
+            .iter()
+            .filter(|p| (self.distance(p) / self.normal.norm()).abs() <= reach)
+            .map(|p| self.project_2d(p))
+            .fold(None, |bounds: Option<(Vector2<f64>, Vector2<f64>)>, uv| {
+                Some(bounds.map_or((uv, uv), |(min, max)| (min.inf(&uv), max.sup(&uv))))
+            });
+        let Some((min, max)) = bounds else { return square(PLANE_RENDER_SIZE) };
+        PlaneRect {
+            center: self.point_at_2d(&((min + max) / 2.0)),
+            half_u: (max.x - min.x) / 2.0 + margin,
+            half_v: (max.y - min.y) / 2.0 + margin,
+        }
+    }
+
+    /// Segments of the quad outline
+    pub fn outline_segments(&self, rect: &PlaneRect) -> Vec<[Vec3; 2]> {
+        let (u, v) = self.in_plane_axes();
+        let (center, du, dv) = (rect.center, u * rect.half_u, v * rect.half_v);
+        let corners = [center + du + dv, center - du + dv, center - du - dv, center + du - dv].map(|c| na_vec3_to_bevy(&c.coords));
+        (0..4).map(|i| [corners[i], corners[(i + 1) % 4]]).collect()
+    }
+
+    /// Segments of the grid drawn across the quad in grid mode; empty otherwise
+    pub fn grid_segments(&self, rect: &PlaneRect) -> Vec<[Vec3; 2]> {
+        if self.render_mode != PlaneRenderMode::Grid {
+            return Vec::new();
+        }
+        let (u, v) = self.in_plane_axes();
+        let (center, du, dv) = (rect.center, u * rect.half_u, v * rect.half_v);
+        let steps = 10;
+        let point = |p: Point3<f64>| na_vec3_to_bevy(&p.coords);
+        (-steps..=steps)
+            .flat_map(|i| {
+                let t = i as f64 / steps as f64;
+                [
+                    [point(center + du * t + dv), point(center + du * t - dv)],
+                    [point(center + dv * t + du), point(center + dv * t - du)],
+                ]
+            })
+            .collect()
+    }
+
+    /// Render the plane using Bevy gizmos, with mode and visibility toggle
+    #[cfg(feature = "render")]
+    pub fn render(&self, gizmos: &mut Gizmos) {
+        if !self.visible {
+            return;
+        }
+        let color = self.render_color();
+        let rect = self.render_rect(&[]);
+        for [a, b] in self.outline_segments(&rect) {
+            gizmos.line(a, b, color);
+        }
+        let grid = color.with_alpha(color.alpha() * 0.7);
+        for [a, b] in self.grid_segments(&rect) {
+            gizmos.line(a, b, grid);
+        }
+    }
+
+    /// Signed distance from a point to the plane
+    pub fn distance(&self, point: &Point3<f64>) -> f64 {
+        self.normal.dot(&point.coords) + self.d
+    }
+
+    /// Two orthonormal in-plane axes (u, v) such that u x v points along the normal
+    pub fn in_plane_axes(&self) -> (Vector3<f64>, Vector3<f64>) {
         let n = self.normal.normalize();
         let u = if n.x.abs() < 0.9 {
             n.cross(&Vector3::x()).normalize()
@@ -263,46 +396,34 @@ impl Plane {
             n.cross(&Vector3::y()).normalize()
         };
         let v = n.cross(&u).normalize();
-        let size = 100.0; // TODO: parameterize
-        let corners = [
-            center + u * size + v * size,
-            center - u * size + v * size,
-            center - u * size - v * size,
-            center + u * size - v * size,
-        ];
-        // Draw quad outline
-        for i in 0..4 {
-            gizmos.line(
-                na_vec3_to_bevy(&(corners[i].coords)),
-                na_vec3_to_bevy(&(corners[(i + 1) % 4].coords)),
-                color.with_alpha(alpha),
-            );
-        }
-        // Optionally draw grid
-        if self.render_mode == PlaneRenderMode::Grid {
-            let steps = 10;
-            for i in -steps..=steps {
-                let t = i as f64 / steps as f64 * size;
-                // u lines
-                gizmos.line(
-                    na_vec3_to_bevy(&((center + u * t + v * size).coords)),
-                    na_vec3_to_bevy(&((center + u * t - v * size).coords)),
-                    color.with_alpha(alpha * 0.7),
-                );
-                // v lines
-                gizmos.line(
-                    na_vec3_to_bevy(&((center + v * t + u * size).coords)),
-                    na_vec3_to_bevy(&((center + v * t - u * size).coords)),
-                    color.with_alpha(alpha * 0.7),
-                );
-            }
+        (u, v)
+    }
+
+    /// Project a point into 2D plane coordinates along the in-plane axes
+    pub fn project_2d(&self, point: &Point3<f64>) -> Vector2<f64> {
+        let (u, v) = self.in_plane_axes();
+        Vector2::new(u.dot(&point.coords), v.dot(&point.coords))
+    }
+
+    /// Point on the plane at 2D plane coordinates; the inverse of `project_2d`
+    pub fn point_at_2d(&self, uv: &Vector2<f64>) -> Point3<f64> {
+        let (u, v) = self.in_plane_axes();
+        let origin = -self.normal * self.d / self.normal.norm_squared();
+        Point3::from(origin + u * uv.x + v * uv.y)
+    }
+
+    /// True if both planes describe the same infinite plane (either facing)
+    pub fn is_coplanar_with(&self, other: &Plane, tol: &Tolerance) -> bool {
+        if !tol.parallel(&self.normal, &other.normal) {
+            return false;
         }
+        let on_self = Point3::origin() - self.normal * self.d / self.normal.norm_squared();
+        tol.is_zero_length(other.distance(&on_self) / other.normal.norm())
     }
-    
 
-    /// Signed distance from a point to the plane
-    pub fn distance(&self, point: &Point3<f64>) -> f64 {
-        self.normal.dot(&point.coords) + self.d
+    /// True if the point lies on the plane
+    pub fn contains_point(&self, point: &Point3<f64>, tol: &Tolerance) -> bool {
+        tol.is_zero_length(self.distance(point) / self.normal.norm())
     }
 }
 
@@ -4,8 +4,9 @@
 //! Module: brep::core::topo::vertex
 
 use nalgebra::Vector3;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Vertex{
     pub id: usize,
     pub position: Vector3<f64>,
@@ -0,0 +1,163 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: brep::validate
+//!
+//! Consistency checks of BREP topology: dangling references, duplicate ids,
+//! degenerate edges and faces, open loops and, for solids, manifold closure.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::measure::mass_properties::mass_properties;
+use crate::model::brep_model::BrepModel;
+
+/// A topology or geometry defect
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationIssue {
+    DuplicateId { kind: &'static str, id: usize },
+    MissingVertex { edge: usize, vertex: usize },
+    MissingEdge { edge_loop: usize, edge: usize },
+    MissingLoop { face: usize, edge_loop: usize },
+    ZeroLengthEdge(usize),
+    /// Loop edges do not connect head to tail into a closed ring
+    OpenLoop(usize),
+    /// Face has no well-defined plane (fewer than three distinct corners or zero area)
+    DegenerateFace(usize),
+    /// In a closed solid, every edge is shared by exactly two faces
+    NonManifoldEdge { edge: usize, faces: usize },
+    /// A closed solid must enclose positive volume with outward faces
+    InvertedSolid,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationIssue::DuplicateId { kind, id } => write!(f, "duplicate {} id {}", kind, id),
+            ValidationIssue::MissingVertex { edge, vertex } => write!(f, "edge {} references missing vertex {}", edge, vertex),
+            ValidationIssue::MissingEdge { edge_loop, edge } => write!(f, "loop {} references missing edge {}", edge_loop, edge),
+            ValidationIssue::MissingLoop { face, edge_loop } => write!(f, "face {} references missing loop {}", face, edge_loop),
+            ValidationIssue::ZeroLengthEdge(id) => write!(f, "edge {} has zero length", id),
+            ValidationIssue::OpenLoop(id) => write!(f, "loop {} is not closed", id),
+            ValidationIssue::DegenerateFace(id) => write!(f, "face {} is degenerate", id),
+            ValidationIssue::NonManifoldEdge { edge, faces } => write!(f, "edge {} is used by {} faces", edge, faces),
+            ValidationIssue::InvertedSolid => write!(f, "solid has non-positive volume"),
+        }
+    }
+}
+
+fn duplicates(kind: &'static str, ids: impl Iterator<Item = usize>, issues: &mut Vec<ValidationIssue>) {
+    let mut seen = HashSet::new();
+    for id in ids {
+        if !seen.insert(id) {
+            issues.push(ValidationIssue::DuplicateId { kind, id });
+        }
+    }
+}
+
+/// True if the edges connect head to tail and return to the start
+fn is_closed_chain(model: &BrepModel, edge_ids: &[usize]) -> bool {
+    let edges: Vec<(usize, usize)> = edge_ids.iter().filter_map(|id| model.edge(*id).map(|e| e.vertices)).collect();
+    if edges.len() < 2 {
+        return false;
+    }
+    let start = model.chain_vertex_ids(edge_ids)[0];
+    let mut current = start;
+    for (a, b) in edges {
+        current = match (current == a, current == b) {
+            (true, _) => b,
+            (_, true) => a,
+            _ => return false,
+        };
+    }
+    current == start
+}
+
+/// Check topology consistency of any model (sheets or solids)
+pub fn validate(model: &BrepModel) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let tol = &model.tolerance;
+    duplicates("vertex", model.vertices.iter().map(|v| v.id), &mut issues);
+    duplicates("edge", model.edges.iter().map(|e| e.id), &mut issues);
+    duplicates("loop", model.edgeloops.iter().map(|l| l.id), &mut issues);
+    duplicates("face", model.faces.iter().map(|f| f.id), &mut issues);
+
+    for e in &model.edges {
+        let ends = [e.vertices.0, e.vertices.1];
+        for v in ends {
+            if model.vertex(v).is_none() {
+                issues.push(ValidationIssue::MissingVertex { edge: e.id, vertex: v });
+            }
+        }
+        if let (Some(a), Some(b)) = (model.vertex_position(ends[0]), model.vertex_position(ends[1])) {
+            if tol.coincident(&a, &b) {
+                issues.push(ValidationIssue::ZeroLengthEdge(e.id));
+            }
+        }
+    }
+    for l in &model.edgeloops {
+        let flat: Vec<usize> = l.edges.iter().flatten().copied().collect();
+        let missing: Vec<usize> = flat.iter().copied().filter(|id| model.edge(*id).is_none()).collect();
+        for edge in &missing {
+            issues.push(ValidationIssue::MissingEdge { edge_loop: l.id, edge: *edge });
+        }
+        if missing.is_empty() && !is_closed_chain(model, &flat) {
+            issues.push(ValidationIssue::OpenLoop(l.id));
+        }
+    }
+    for f in &model.faces {
+        for l in &f.edge_loops {
+            if model.edge_loop(*l).is_none() {
+                issues.push(ValidationIssue::MissingLoop { face: f.id, edge_loop: *l });
+            }
+        }
+        let area = model.face_area_vector(f).map_or(0.0, |a| a.norm());
+        if tol.is_zero_length(area) {
+            issues.push(ValidationIssue::DegenerateFace(f.id));
+        }
+    }
+    issues
+}
+
+/// Check a model that should be a closed, outward-oriented solid
+pub fn validate_solid(model: &BrepModel) -> Vec<ValidationIssue> {
+    let mut issues = validate(model);
+    for e in &model.edges {
+        let faces = model.faces_using_edge(e.id).len();
+        if faces != 2 {
+            issues.push(ValidationIssue::NonManifoldEdge { edge: e.id, faces });
+        }
+    }
+    if issues.is_empty() && mass_properties(model).volume <= 0.0 {
+        issues.push(ValidationIssue::InvertedSolid);
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::{cube, cylinder};
+
+    #[test]
+    fn test_primitives_are_valid_solids() {
+        assert!(validate_solid(&cube(1.0)).is_empty());
+        assert!(validate_solid(&cylinder(1.0, 2.0, 12)).is_empty());
+    }
+
+    #[test]
+    fn test_detects_defects() {
+        let mut m = cube(1.0);
+        m.faces.pop();
+        let issues = validate_solid(&m);
+        assert_eq!(issues.iter().filter(|i| matches!(i, ValidationIssue::NonManifoldEdge { faces: 1, .. })).count(), 4);
+
+        let mut m = cube(1.0);
+        m.edges[0].vertices.1 = 99;
+        assert!(validate(&m).contains(&ValidationIssue::MissingVertex { edge: 0, vertex: 99 }));
+
+        let mut flipped = cube(1.0);
+        flipped.vertices.iter_mut().for_each(|v| v.position.x = -v.position.x);
+        assert!(validate_solid(&flipped).contains(&ValidationIssue::InvertedSolid));
+    }
+}
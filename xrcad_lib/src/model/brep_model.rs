@@ -1,19 +1,43 @@
 
+use std::collections::{HashMap, HashSet};
+
 use bevy::prelude::*;
-use bevy::window::PrimaryWindow;
+use serde::{Deserialize, Serialize};
 
-use super::brep::topology::{vertex::Vertex, edge::Edge, edge_loop::EdgeLoop, face::Face};
+use super::brep::topology::{vertex::Vertex, edge::{Edge, EdgeKind}, edge_loop::EdgeLoop, face::Face, plane::Plane};
+use crate::model::body::{BodyId, IdGenerator};
 use nalgebra as na;
-use crate::color::{YELLOW, WHITE};
+use super::tolerance::Tolerance;
+// Vertex handles and dragging
+#[cfg(feature = "render")]
+use crate::{
+    color::YELLOW,
+    interaction::grid_snap::DragConstraints,
+    interaction::measure_tool::MeasureTool,
+    interaction::picking::{PickState, PickTarget},
+    interaction::transform_gizmo::TransformGizmo,
+    model::command::{commit_vertex_moves, CommandRelay, ModelCommand},
+    model::{groups::BodyGroups, layers::LayerManager, properties::BodyPropertiesCollection},
+    render::display_mode::DisplaySettings,
+    render::exploded::ExplodedView,
+    render::gizmo_scale::{GizmoScale, VERTEX_HANDLE_PIXELS},
+    render::section::SectionView,
+};
 
-#[derive(Resource)]
+#[derive(Resource, Debug, Default, Clone, Serialize, Deserialize)]
 pub struct BrepModel {
     pub vertices: Vec<Vertex>,
     pub edges: Vec<Edge>,
     pub edgeloops: Vec<EdgeLoop>,
     pub faces: Vec<Face>,
     /// Currently selected vertex (by id/index), if any
+    #[serde(skip)]
     pub selected_vertex: Option<usize>,
+    /// Precision policy for all geometric comparisons on this model
+    pub tolerance: Tolerance,
+    /// Ids for new bodies; the ids in use are kept on the faces
+    #[serde(default)]
+    pub body_ids: IdGenerator,
 }
 
 // --- Conversion helpers for f64 <-> f32 (nalgebra <-> bevy) ---
@@ -24,53 +48,666 @@ pub fn bevy_vec3_to_na(v: &bevy::prelude::Vec3) -> na::Vector3<f64> {
     na::Vector3::new(v.x as f64, v.y as f64, v.z as f64)
 }
 
+/// Area-weighted normal of a closed polygon (Newell's method); its length is the polygon area
+pub fn area_vector(points: &[na::Vector3<f64>]) -> na::Vector3<f64> {
+    let mut n = na::Vector3::zeros();
+    for i in 0..points.len() {
+        n += points[i].cross(&points[(i + 1) % points.len()]);
+    }
+    n * 0.5
+}
 
 impl BrepModel {
-        pub fn render(
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // --- Lookup by id ---
+
+    pub fn vertex(&self, id: usize) -> Option<&Vertex> {
+        self.vertices.iter().find(|v| v.id == id)
+    }
+    pub fn edge(&self, id: usize) -> Option<&Edge> {
+        self.edges.iter().find(|e| e.id == id)
+    }
+    pub fn edge_loop(&self, id: usize) -> Option<&EdgeLoop> {
+        self.edgeloops.iter().find(|l| l.id == id)
+    }
+    pub fn face(&self, id: usize) -> Option<&Face> {
+        self.faces.iter().find(|f| f.id == id)
+    }
+    pub fn face_mut(&mut self, id: usize) -> Option<&mut Face> {
+        self.faces.iter_mut().find(|f| f.id == id)
+    }
+    pub fn vertex_position(&self, id: usize) -> Option<na::Vector3<f64>> {
+        self.vertex(id).map(|v| v.position)
+    }
+
+    pub fn next_vertex_id(&self) -> usize {
+        self.vertices.iter().map(|v| v.id + 1).max().unwrap_or(0)
+    }
+    pub fn next_edge_id(&self) -> usize {
+        self.edges.iter().map(|e| e.id + 1).max().unwrap_or(0)
+    }
+    pub fn next_edge_loop_id(&self) -> usize {
+        self.edgeloops.iter().map(|l| l.id + 1).max().unwrap_or(0)
+    }
+    pub fn next_face_id(&self) -> usize {
+        self.faces.iter().map(|f| f.id + 1).max().unwrap_or(0)
+    }
+
+    // --- Construction ---
+
+    /// Find an existing vertex coincident with `position`
+    pub fn find_vertex_at(&self, position: &na::Vector3<f64>) -> Option<usize> {
+        self.vertices.iter().find(|v| self.tolerance.coincident(&v.position, position)).map(|v| v.id)
+    }
+
+    /// Add a vertex, welding to an existing one at the same position
+    pub fn add_vertex(&mut self, position: na::Vector3<f64>) -> usize {
+        if let Some(id) = self.find_vertex_at(&position) {
+            return id;
+        }
+        let id = self.next_vertex_id();
+        self.vertices.push(Vertex { id, position });
+        id
+    }
+
+    /// Find the edge joining two vertices (in either direction)
+    pub fn find_edge_between(&self, a: usize, b: usize) -> Option<usize> {
+        self.edges
+            .iter()
+            .find(|e| e.vertices == (a, b) || e.vertices == (b, a))
+            .map(|e| e.id)
+    }
+
+    /// Add an edge between two vertices, reusing an existing one if present
+    pub fn add_edge(&mut self, a: usize, b: usize) -> usize {
+        if let Some(id) = self.find_edge_between(a, b) {
+            return id;
+        }
+        let id = self.next_edge_id();
+        self.edges.push(Edge::new(id, a, b));
+        id
+    }
+
+    /// Add an edge loop from an ordered list of edge ids
+    pub fn add_edge_loop(&mut self, edge_ids: Vec<usize>) -> usize {
+        let id = self.next_edge_loop_id();
+        self.edgeloops.push(EdgeLoop::new(id, vec![edge_ids]));
+        id
+    }
+
+    /// Add a face bounded by the given ordered edge loops (first is the outer boundary)
+    pub fn add_face_from_loops(&mut self, loops: Vec<Vec<usize>>) -> usize {
+        let loop_ids = loops.into_iter().map(|l| self.add_edge_loop(l)).collect();
+        let id = self.next_face_id();
+        self.faces.push(Face::new(id, loop_ids));
+        id
+    }
+
+    /// Add a planar polygon face from its ordered corner positions
+    pub fn add_face(&mut self, points: &[na::Vector3<f64>]) -> usize {
+        self.add_face_with_holes(points, &[])
+    }
+
+    /// Add a planar face from its outer corner positions and the corners of each hole
+    pub fn add_face_with_holes(&mut self, outer: &[na::Vector3<f64>], holes: &[Vec<na::Vector3<f64>>]) -> usize {
+        let loops = std::iter::once(outer)
+            .chain(holes.iter().map(|h| h.as_slice()))
+            .map(|points| {
+                let ids: Vec<usize> = points.iter().map(|p| self.add_vertex(*p)).collect();
+                (0..ids.len()).map(|i| self.add_edge(ids[i], ids[(i + 1) % ids.len()])).collect()
+            })
+            .collect();
+        self.add_face_from_loops(loops)
+    }
+
+    /// Copy all topology of `other` into this model without welding, renumbering its
+    /// ids past the existing ones; returns the ids of the copied faces. Faces keep
+    /// the body ids they had in `other`.
+    pub fn append(&mut self, other: &BrepModel) -> Vec<usize> {
+        let (dv, de, dl, df) =
+            (self.next_vertex_id(), self.next_edge_id(), self.next_edge_loop_id(), self.next_face_id());
+        self.vertices.extend(other.vertices.iter().map(|v| Vertex { id: v.id + dv, position: v.position }));
+        self.edges.extend(other.edges.iter().map(|e| Edge::new(e.id + de, e.vertices.0 + dv, e.vertices.1 + dv)));
+        self.edgeloops.extend(other.edgeloops.iter().map(|l| {
+            EdgeLoop::new(l.id + dl, l.edges.iter().map(|c| c.iter().map(|e| e + de).collect()).collect())
+        }));
+        let faces: Vec<Face> = other
+            .faces
+            .iter()
+            .map(|f| Face { body: f.body, ..Face::new(f.id + df, f.edge_loops.iter().map(|l| l + dl).collect()) })
+            .collect();
+        let ids = faces.iter().map(|f| f.id).collect();
+        self.faces.extend(faces);
+        ids
+    }
+
+    // --- Topology queries ---
+
+    /// Ordered vertex ids around a loop; vertex i is the start of the loop's i-th edge
+    pub fn loop_vertex_ids(&self, edge_loop: &EdgeLoop) -> Vec<usize> {
+        let flat: Vec<usize> = edge_loop.edges.iter().flatten().copied().collect();
+        self.chain_vertex_ids(&flat)
+    }
+
+    /// Ordered vertex ids along a head-to-tail chain of edge ids
+    pub fn chain_vertex_ids(&self, edge_ids: &[usize]) -> Vec<usize> {
+        let edges: Vec<(usize, usize)> = edge_ids
+            .iter()
+            .filter_map(|id| self.edge(*id).map(|e| e.vertices))
+            .collect();
+        let Some(&(a, b)) = edges.first() else { return Vec::new(); };
+        if edges.len() == 1 {
+            return vec![a];
+        }
+        let (n0, n1) = edges[1];
+        let mut current = if b == n0 || b == n1 { a } else { b };
+        let mut out = Vec::with_capacity(edges.len());
+        for (s, e) in edges {
+            out.push(current);
+            current = if current == s { e } else { s };
+        }
+        out
+    }
+
+    /// Ordered corner positions around a loop
+    pub fn loop_positions(&self, edge_loop: &EdgeLoop) -> Vec<na::Vector3<f64>> {
+        self.loop_vertex_ids(edge_loop)
+            .into_iter()
+            .filter_map(|id| self.vertex_position(id))
+            .collect()
+    }
+
+    /// The loops bounding a face, outer boundary first
+    pub fn face_loops(&self, face: &Face) -> Vec<&EdgeLoop> {
+        face.edge_loops.iter().filter_map(|id| self.edge_loop(*id)).collect()
+    }
+
+    /// All edge ids used by a face's loops
+    pub fn face_edge_ids(&self, face: &Face) -> Vec<usize> {
+        self.face_loops(face)
+            .into_iter()
+            .flat_map(|l| l.edges.iter().flatten().copied())
+            .collect()
+    }
+
+    /// Area-weighted normal of the face's outer loop (Newell's method)
+    pub fn face_area_vector(&self, face: &Face) -> Option<na::Vector3<f64>> {
+        let outer = self.face_loops(face).into_iter().next()?;
+        let pts = self.loop_positions(outer);
+        (pts.len() >= 3).then(|| area_vector(&pts))
+    }
+
+    /// Area-weighted normal of a closed chain of edge ids
+    pub fn chain_area_vector(&self, edge_ids: &[usize]) -> na::Vector3<f64> {
+        let pts: Vec<na::Vector3<f64>> = self
+            .chain_vertex_ids(edge_ids)
+            .into_iter()
+            .filter_map(|id| self.vertex_position(id))
+            .collect();
+        area_vector(&pts)
+    }
+
+    /// Unit face normal, if the outer loop is not degenerate
+    pub fn face_normal(&self, face: &Face) -> Option<na::Vector3<f64>> {
+        self.face_area_vector(face)
+            .filter(|n| !self.tolerance.is_zero_length(n.norm()))
+            .map(|n| n.normalize())
+    }
+
+    /// Supporting plane of a planar face
+    pub fn face_plane(&self, face: &Face) -> Option<Plane> {
+        let normal = self.face_normal(face)?;
+        let outer = self.face_loops(face).into_iter().next()?;
+        let pts = self.loop_positions(outer);
+        let centroid = pts.iter().fold(na::Vector3::zeros(), |acc, p| acc + p) / pts.len() as f64;
+        Some(Plane::from_point_normal(na::Point3::from(centroid), normal, None))
+    }
+
+    /// Ids of faces whose loops pass through the given vertex
+    pub fn faces_using_vertex(&self, vertex_id: usize) -> Vec<usize> {
+        self.faces
+            .iter()
+            .filter(|f| {
+                self.face_edge_ids(f)
+                    .iter()
+                    .filter_map(|e| self.edge(*e))
+                    .any(|e| e.vertices.0 == vertex_id || e.vertices.1 == vertex_id)
+            })
+            .map(|f| f.id)
+            .collect()
+    }
+
+    /// Ids of faces whose loops use the given edge
+    pub fn faces_using_edge(&self, edge_id: usize) -> Vec<usize> {
+        self.faces
+            .iter()
+            .filter(|f| self.face_edge_ids(f).contains(&edge_id))
+            .map(|f| f.id)
+            .collect()
+    }
+
+    /// Angle (radians) between the normals of the two faces meeting at an edge,
+    /// or `None` if the edge is not shared by exactly two faces
+    pub fn edge_dihedral_angle(&self, edge_id: usize) -> Option<f64> {
+        let faces = self.faces_using_edge(edge_id);
+        let [a, b] = faces[..] else { return None; };
+        let na = self.face_normal(self.face(a)?)?;
+        let nb = self.face_normal(self.face(b)?)?;
+        Some(na.dot(&nb).clamp(-1.0, 1.0).acos())
+    }
+
+    /// Classify an edge as sharp, tangent or boundary for display purposes
+    pub fn classify_edge(&self, edge_id: usize, tangent_threshold: f64) -> EdgeKind {
+        EdgeKind::from_dihedral(self.edge_dihedral_angle(edge_id), tangent_threshold)
+    }
+
+    /// Group an unordered set of edges into closed chains, each ordered head-to-tail
+    pub fn chain_edges(&self, edge_ids: &[usize]) -> Vec<Vec<usize>> {
+        let mut remaining: Vec<usize> = edge_ids.to_vec();
+        let mut chains = Vec::new();
+        while let Some(first) = remaining.pop() {
+            let Some(e) = self.edge(first) else { continue; };
+            let start = e.vertices.0;
+            let mut tip = e.vertices.1;
+            let mut chain = vec![first];
+            while tip != start {
+                let next = remaining.iter().position(|id| {
+                    self.edge(*id).is_some_and(|e| e.vertices.0 == tip || e.vertices.1 == tip)
+                });
+                let Some(pos) = next else { break; };
+                let id = remaining.swap_remove(pos);
+                let (a, b) = self.edge(id).map(|e| e.vertices).unwrap_or((tip, tip));
+                tip = if a == tip { b } else { a };
+                chain.push(id);
+            }
+            chains.push(chain);
+        }
+        chains
+    }
+
+    // --- Euler-style edits ---
+
+    /// Split an edge at `position`, inserting the new edge into every loop that uses it.
+    /// Returns the id of the vertex at the split point.
+    pub fn split_edge(&mut self, edge_id: usize, position: na::Vector3<f64>) -> Option<usize> {
+        let (a, b) = self.edge(edge_id)?.vertices;
+        let m = self.add_vertex(position);
+        if m == a || m == b {
+            return Some(m);
+        }
+        // Record traversal direction of each using loop before the edge changes
+        let loops: Vec<(usize, Vec<usize>)> = self
+            .edgeloops
+            .iter()
+            .filter(|l| l.edges.iter().flatten().any(|id| *id == edge_id))
+            .map(|l| (l.id, self.loop_vertex_ids(l)))
+            .collect();
+        let new_edge = self.next_edge_id();
+        self.edges.push(Edge::new(new_edge, m, b));
+        if let Some(e) = self.edges.iter_mut().find(|e| e.id == edge_id) {
+            e.vertices = (a, m);
+        }
+        // Insert the new half after or before the old one depending on traversal direction
+        for (loop_id, verts) in loops {
+            let Some(l) = self.edgeloops.iter_mut().find(|l| l.id == loop_id) else { continue; };
+            let mut flat: Vec<usize> = l.edges.iter().flatten().copied().collect();
+            let Some(i) = flat.iter().position(|id| *id == edge_id) else { continue; };
+            // After the split, (a, m) keeps the old id; forward traversal starts at a
+            let forward = verts.get(i).is_some_and(|v| *v == a);
+            if forward {
+                flat.insert(i + 1, new_edge);
+            } else {
+                flat.insert(i, new_edge);
+            }
+            l.edges = vec![flat];
+        }
+        Some(m)
+    }
+
+    /// Face ids grouped into connected shells (faces linked through shared edges),
+    /// in order of each shell's first face
+    pub fn shells(&self) -> Vec<Vec<usize>> {
+        let mut shell_of: Vec<Option<usize>> = vec![None; self.faces.len()];
+        let mut shells: Vec<Vec<usize>> = Vec::new();
+        for start in 0..self.faces.len() {
+            if shell_of[start].is_some() {
+                continue;
+            }
+            let shell = shells.len();
+            shell_of[start] = Some(shell);
+            let mut members = Vec::new();
+            let mut stack = vec![start];
+            while let Some(i) = stack.pop() {
+                members.push(self.faces[i].id);
+                for edge in self.face_edge_ids(&self.faces[i]) {
+                    for (j, face) in self.faces.iter().enumerate() {
+                        if shell_of[j].is_none() && self.face_edge_ids(face).contains(&edge) {
+                            shell_of[j] = Some(shell);
+                            stack.push(j);
+                        }
+                    }
+                }
+            }
+            members.sort_unstable();
+            shells.push(members);
+        }
+        shells
+    }
+
+    /// Faces of every body, in shell order. A shell takes the lowest body id on
+    /// its faces; a shell with no id, or with the id of an earlier shell (the
+    /// part a subtract split off), takes the next free id, the one
+    /// `assign_body_ids` records for it.
+    pub fn bodies(&self) -> Vec<(BodyId, Vec<usize>)> {
+        let tagged = self.faces.iter().filter_map(|f| f.body).max().map_or(0, |id| id.0 + 1);
+        let mut next = self.body_ids.peek().0.max(tagged);
+        let mut bodies: Vec<(BodyId, Vec<usize>)> = Vec::new();
+        for faces in self.shells() {
+            let own = faces.iter().filter_map(|id| self.face(*id)?.body).min();
+            let id = match own.filter(|id| !bodies.iter().any(|(b, _)| b == id)) {
+                Some(id) => id,
+                None => {
+                    next += 1;
+                    BodyId(next - 1)
+                }
+            };
+            bodies.push((id, faces));
+        }
+        bodies
+    }
+
+    /// Ids of every body, in shell order
+    pub fn body_ids(&self) -> Vec<BodyId> {
+        self.bodies().into_iter().map(|(id, _)| id).collect()
+    }
+
+    /// Face ids of a body
+    pub fn body_faces(&self, id: BodyId) -> Option<Vec<usize>> {
+        self.bodies().into_iter().find(|(b, _)| *b == id).map(|(_, faces)| faces)
+    }
+
+    pub fn has_body(&self, id: BodyId) -> bool {
+        self.body_faces(id).is_some()
+    }
+
+    /// A copy of one body's topology
+    pub fn body(&self, id: BodyId) -> Option<BrepModel> {
+        self.body_faces(id).map(|faces| self.extract_faces(&faces))
+    }
+
+    /// Body each face belongs to
+    pub fn face_bodies(&self) -> HashMap<usize, BodyId> {
+        self.bodies().into_iter().flat_map(|(id, faces)| faces.into_iter().map(move |f| (f, id))).collect()
+    }
+
+    /// Record each shell's body id on its faces, so ids stay put when other
+    /// bodies are added or removed
+    pub fn assign_body_ids(&mut self) {
+        for (id, faces) in self.bodies() {
+            self.body_ids.reserve(id);
+            for face in self.faces.iter_mut().filter(|f| faces.contains(&f.id)) {
+                face.body = Some(id);
+            }
+        }
+    }
+
+    /// Append `body` as one new body and return its id
+    pub fn add_body(&mut self, body: &BrepModel) -> BodyId {
+        self.assign_body_ids();
+        let id = self.body_ids.generate();
+        let faces = self.append(body);
+        for face in self.faces.iter_mut().filter(|f| faces.contains(&f.id)) {
+            face.body = Some(id);
+        }
+        id
+    }
+
+    /// Sorted ids of the vertices used by the given faces
+    pub fn shell_vertex_ids(&self, face_ids: &[usize]) -> Vec<usize> {
+        let mut vertices: Vec<usize> =
+            face_ids.iter().filter_map(|id| self.face(*id)).flat_map(|f| self.face_loops(f)).flat_map(|l| self.loop_vertex_ids(l)).collect();
+        vertices.sort_unstable();
+        vertices.dedup();
+        vertices
+    }
+
+    /// Body each vertex belongs to
+    pub fn vertex_bodies(&self) -> HashMap<usize, BodyId> {
+        self.bodies().into_iter().flat_map(|(id, faces)| self.shell_vertex_ids(&faces).into_iter().map(move |v| (v, id))).collect()
+    }
+
+    /// Vertices of the bodies that `visible` rejects
+    pub fn hidden_vertex_ids(&self, visible: impl Fn(BodyId) -> bool) -> HashSet<usize> {
+        self.vertex_bodies().into_iter().filter(|(_, body)| !visible(*body)).map(|(v, _)| v).collect()
+    }
+
+    /// Copy of the model holding only the given faces and the topology they use
+    pub fn extract_faces(&self, face_ids: &[usize]) -> BrepModel {
+        let mut out = self.clone();
+        out.selected_vertex = None;
+        out.faces.retain(|f| face_ids.contains(&f.id));
+        out.remove_unused();
+        out
+    }
+
+    /// Axis-aligned bounding box (min, max) of the vertices, if there are any
+    pub fn bounds(&self) -> Option<(na::Vector3<f64>, na::Vector3<f64>)> {
+        let first = self.vertices.first()?.position;
+        Some(self.vertices.iter().fold((first, first), |(lo, hi), v| (lo.inf(&v.position), hi.sup(&v.position))))
+    }
+
+    /// Drop loops, edges and vertices no longer referenced by any face or edge
+    pub fn remove_unused(&mut self) {
+        let used_loops: Vec<usize> = self.faces.iter().flat_map(|f| f.edge_loops.clone()).collect();
+        self.edgeloops.retain(|l| used_loops.contains(&l.id));
+        let used_edges: Vec<usize> = self.edgeloops.iter().flat_map(|l| l.edges.iter().flatten().copied()).collect();
+        self.edges.retain(|e| used_edges.contains(&e.id));
+        let used_vertices: Vec<usize> = self.edges.iter().flat_map(|e| [e.vertices.0, e.vertices.1]).collect();
+        self.vertices.retain(|v| used_vertices.contains(&v.id));
+    }
+
+    /// Draw vertex handles; edges are drawn by `render::edge_overlay`
+    #[cfg(feature = "render")]
+    pub fn render(
         mut gizmos: Gizmos,
         brepmodel: Res<BrepModel>,
+        display: Option<Res<DisplaySettings>>,
+        scale: Option<Res<GizmoScale>>,
+        section: Option<Res<SectionView>>,
+        exploded: Option<Res<ExplodedView>>,
+        (properties, groups, layers): (Option<Res<BodyPropertiesCollection>>, Option<Res<BodyGroups>>, Option<Res<LayerManager>>),
     ) {
-        for edge in &brepmodel.edges {
-            let v0 = &brepmodel.vertices[edge.vertices.0];
-            let v1 = &brepmodel.vertices[edge.vertices.1];
-            gizmos.line(na_vec3_to_bevy(&v0.position), na_vec3_to_bevy(&v1.position), WHITE);
+        let scale = scale.as_deref().copied().unwrap_or_default();
+        let section = section.as_deref().cloned().unwrap_or_default();
+        // Bodies hidden themselves, through a group or by their layer are skipped
+        let (no_groups, no_layers, no_properties) = (BodyGroups::default(), LayerManager::default(), BodyPropertiesCollection::default());
+        let groups = groups.as_deref().unwrap_or(&no_groups);
+        let layers = layers.as_deref().unwrap_or(&no_layers);
+        let properties = properties.as_deref().unwrap_or(&no_properties);
+        // Shaded bodies have no vertex handles
+        let display = display.as_deref().copied().unwrap_or_default();
+        if !display.mode.shows_edges() && !display.has_overrides(properties) {
+            return;
         }
-        for v in &brepmodel.vertices {
-            gizmos.circle(na_vec3_to_bevy(&v.position), 8.0, YELLOW);
+        let per_body = !layers.is_empty()
+            || properties.iter().any(|(_, p)| !p.visible)
+            || groups.iter().any(|g| !g.visible)
+            || display.has_overrides(properties);
+        let bodies = if per_body { brepmodel.vertex_bodies() } else { HashMap::new() };
+        let shown = |v: &usize| match bodies.get(v) {
+            Some(b) => {
+                groups.is_body_visible(*b, properties) && layers.is_body_visible(*b, properties) && display.mode_of(*b, properties).shows_edges()
+            }
+            None => display.mode.shows_edges(),
+        };
+        // Bodies are drawn at their exploded positions, if any
+        let offsets = exploded.map(|e| e.vertex_offsets(&brepmodel)).unwrap_or_default();
+        let placed = |id: usize| brepmodel.vertex_position(id).map(|p| offsets.get(&id).map_or(p, |o| p + o));
+        for p in brepmodel.vertices.iter().filter(|v| shown(&v.id)).filter_map(|v| placed(v.id)).filter(|p| !section.clips(p)) {
+            let position = na_vec3_to_bevy(&p);
+            gizmos.circle(position, scale.world_size(position, VERTEX_HANDLE_PIXELS), YELLOW);
         }
     }
 
+    /// Drag the picked vertex across the plane through it facing the cursor ray.
+    /// The vertices it moved are sent as one command on release.
+    #[cfg(feature = "render")]
     pub fn vertex_drag(
         mouse: Res<ButtonInput<MouseButton>>,
-        window_q: Query<&Window, With<PrimaryWindow>>,
-        q_camera: Query<(&Camera, &GlobalTransform)>,
+        pick: Res<PickState>,
         mut brepmodel: ResMut<BrepModel>,
+        constraints: DragConstraints,
+        (gizmo, measure): (Option<Res<TransformGizmo>>, Option<Res<MeasureTool>>),
+        (relay, mut commands): (Option<Res<CommandRelay>>, EventWriter<ModelCommand>),
+        mut drag_plane: Local<Option<(na::Vector3<f64>, na::Vector3<f64>)>>,
+        // Moved vertices where they were when the drag began
+        mut original: Local<Vec<(usize, na::Vector3<f64>)>>,
     ) {
-        let Ok(window) = window_q.single() else { return; };
-        let Ok((camera, camera_transform)) = q_camera.single() else { return; };
-        if let Some(cursor_pos) = window.cursor_position() {
-            if let Ok(ray) = camera.viewport_to_world(camera_transform, cursor_pos) {
-                let denom = ray.direction.z;
-                if denom.abs() > 1e-6 {
-                    let t = -ray.origin.z / denom;
-                    let world_pos = ray.origin + ray.direction * t;
-                    if mouse.just_pressed(MouseButton::Left) {
-                        if let Some(selected_id) = brepmodel.vertices.iter_mut().find(|v| (na_vec3_to_bevy(&v.position).xy() - world_pos.xy()).length() < 12.0).map(|v| v.id as usize) {
-                            brepmodel.selected_vertex = Some(selected_id);
-                        }
-                    }
-                    if mouse.pressed(MouseButton::Left) {
-                        if let Some(id) = brepmodel.selected_vertex {
-                            if let Some(v) = brepmodel.vertices.iter_mut().find(|v| v.id as usize == id) {
-                                v.position = bevy_vec3_to_na(&world_pos);
+        // A transform gizmo handle under the cursor, or the measure tool, takes the click instead
+        let claimed = gizmo.is_some_and(|g| g.captures_pointer()) || measure.is_some_and(|m| m.active);
+        if mouse.just_pressed(MouseButton::Left) && !claimed {
+            if let (Some(PickTarget::Vertex(id)), Some((_, dir))) = (pick.hover.as_ref().map(|h| h.target), pick.ray) {
+                brepmodel.selected_vertex = Some(id);
+                *drag_plane = brepmodel.vertex_position(id).map(|p| (p, dir));
+            }
+        }
+        if mouse.pressed(MouseButton::Left) {
+            if let (Some(id), Some((point, normal)), Some((origin, dir))) = (brepmodel.selected_vertex, *drag_plane, pick.ray) {
+                let denom = normal.dot(&dir);
+                if denom.abs() > brepmodel.tolerance.angular.sin() {
+                    let target = origin + dir * ((point - origin).dot(&normal) / denom);
+                    for (moved, position) in constraints.moves(&brepmodel, id, &target) {
+                        if let Some(v) = brepmodel.vertices.iter_mut().find(|v| v.id == moved) {
+                            if !original.iter().any(|(o, _)| *o == moved) {
+                                original.push((moved, v.position));
                             }
+                            v.position = position;
                         }
                     }
-                    if mouse.just_released(MouseButton::Left) {
-                        brepmodel.selected_vertex = None;
-                    }
                 }
             }
         }
+        if mouse.just_released(MouseButton::Left) {
+            commit_vertex_moves(&mut brepmodel, &original, relay.as_deref(), &mut commands);
+            original.clear();
+            brepmodel.selected_vertex = None;
+            *drag_plane = None;
+        }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::Vector3;
+
+    fn square(z: f64) -> Vec<Vector3<f64>> {
+        vec![
+            Vector3::new(0.0, 0.0, z),
+            Vector3::new(1.0, 0.0, z),
+            Vector3::new(1.0, 1.0, z),
+            Vector3::new(0.0, 1.0, z),
+        ]
+    }
+
+    #[test]
+    fn test_add_face_welds_shared_vertices() {
+        let mut m = BrepModel::new();
+        m.add_face(&square(0.0));
+        m.add_face(&[
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(2.0, 0.0, 0.0),
+            Vector3::new(2.0, 1.0, 0.0),
+            Vector3::new(1.0, 1.0, 0.0),
+        ]);
+        assert_eq!(m.vertices.len(), 6);
+        assert_eq!(m.edges.len(), 7);
+        assert_eq!(m.faces.len(), 2);
+        let shared = m.find_edge_between(1, 2).unwrap();
+        assert_eq!(m.faces_using_edge(shared).len(), 2);
+    }
+
+    #[test]
+    fn test_loop_vertex_ids_and_normal() {
+        let mut m = BrepModel::new();
+        let f = m.add_face(&square(2.0));
+        let face = m.face(f).unwrap();
+        let l = m.edge_loop(face.edge_loops[0]).unwrap();
+        assert_eq!(m.loop_vertex_ids(l), vec![0, 1, 2, 3]);
+        let n = m.face_normal(face).unwrap();
+        assert!((n - Vector3::z()).norm() < 1e-12);
+        let plane = m.face_plane(face).unwrap();
+        assert!((plane.d + 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_split_edge_keeps_loop_ordered() {
+        let mut m = BrepModel::new();
+        let f = m.add_face(&square(0.0));
+        let e = m.find_edge_between(2, 1).unwrap();
+        let mid = m.split_edge(e, Vector3::new(1.0, 0.5, 0.0)).unwrap();
+        let face = m.face(f).unwrap();
+        let l = m.edge_loop(face.edge_loops[0]).unwrap();
+        assert_eq!(m.loop_vertex_ids(l), vec![0, 1, mid, 2, 3]);
+    }
+
+    #[test]
+    fn test_classify_edges_of_fold() {
+        let mut m = BrepModel::new();
+        m.add_face(&square(0.0));
+        m.add_face(&[
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(2.0, 0.0, 0.0),
+            Vector3::new(2.0, 1.0, 0.0),
+            Vector3::new(1.0, 1.0, 0.0),
+        ]);
+        m.add_face(&[
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 1.0, 1.0),
+            Vector3::new(0.0, 0.0, 1.0),
+        ]);
+        let threshold = 1f64.to_radians();
+        let flat = m.find_edge_between(1, 2).unwrap();
+        let crease = m.find_edge_between(0, 3).unwrap();
+        let open = m.find_edge_between(0, 1).unwrap();
+        assert_eq!(m.classify_edge(flat, threshold), EdgeKind::Tangent);
+        assert_eq!(m.classify_edge(crease, threshold), EdgeKind::Sharp);
+        assert_eq!(m.classify_edge(open, threshold), EdgeKind::Boundary);
+    }
+
+    #[test]
+    fn test_chain_edges_and_remove_unused() {
+        let mut m = BrepModel::new();
+        m.add_face(&square(0.0));
+        let chains = m.chain_edges(&[0, 1, 2, 3]);
+        assert_eq!(chains.len(), 1);
+        assert_eq!(chains[0].len(), 4);
+        m.faces.clear();
+        m.remove_unused();
+        assert!(m.vertices.is_empty() && m.edges.is_empty() && m.edgeloops.is_empty());
+    }
+
+    #[test]
+    fn test_body_ids_survive_removals() {
+        let cube = crate::model::brep::primitives::cube;
+        let mut m = BrepModel::new();
+        let ids: Vec<BodyId> = [1.0, 2.0, 3.0].iter().map(|size| m.add_body(&cube(*size))).collect();
+        assert_eq!(ids, [BodyId(0), BodyId(1), BodyId(2)]);
+        let first = m.body_faces(BodyId(0)).unwrap();
+        m.faces.retain(|f| !first.contains(&f.id));
+        m.remove_unused();
+        assert_eq!(m.body_ids(), [BodyId(1), BodyId(2)]);
+        assert_eq!(m.add_body(&cube(4.0)), BodyId(3));
+        assert!(!m.has_body(BodyId(0)));
+
+        // A shell without an id takes the next one, and keeps it once recorded
+        m.append(&cube(5.0));
+        assert_eq!(m.body_ids(), [BodyId(1), BodyId(2), BodyId(3), BodyId(4)]);
+        m.assign_body_ids();
+        assert_eq!(m.add_body(&cube(6.0)), BodyId(5));
+    }
+}
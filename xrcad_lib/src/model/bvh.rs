@@ -0,0 +1,371 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: model::bvh
+//!
+//! Bounding volume hierarchies over the model, so picking and overlap tests
+//! visit only the geometry near a ray or a box instead of every vertex, edge
+//! and triangle. `SceneBvh` keeps one `BodyBvh` per body (its vertices, edges
+//! and tessellated triangles) under a tree of body bounds. Only bodies that
+//! `BodyChanges` reports dirty are rebuilt; the body tree is cheap and is
+//! rebuilt whenever any body changes.
+
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use nalgebra::Vector3;
+
+use crate::model::body::BodyId;
+use crate::model::brep::tessellate::tessellate_face;
+use crate::model::brep_model::BrepModel;
+use crate::model::changes::BodyChanges;
+
+/// Items per leaf of a hierarchy
+const LEAF_SIZE: usize = 4;
+
+/// Axis-aligned bounding box
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vector3<f64>,
+    pub max: Vector3<f64>,
+}
+
+impl Default for Aabb {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+impl Aabb {
+    /// Box containing nothing; the identity of `union`
+    pub const EMPTY: Aabb = Aabb {
+        min: Vector3::new(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+        max: Vector3::new(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+    };
+
+    pub fn from_points<'a>(points: impl IntoIterator<Item = &'a Vector3<f64>>) -> Self {
+        points.into_iter().fold(Self::EMPTY, |b, p| Aabb { min: b.min.inf(p), max: b.max.sup(p) })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        (0..3).any(|i| self.min[i] > self.max[i])
+    }
+
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb { min: self.min.inf(&other.min), max: self.max.sup(&other.max) }
+    }
+
+    /// Box common to both, empty if they do not overlap
+    pub fn intersection(&self, other: &Aabb) -> Aabb {
+        Aabb { min: self.min.sup(&other.min), max: self.max.inf(&other.max) }
+    }
+
+    /// Box grown by `margin` on every side
+    pub fn expanded(&self, margin: f64) -> Aabb {
+        Aabb { min: self.min.add_scalar(-margin), max: self.max.add_scalar(margin) }
+    }
+
+    pub fn center(&self) -> Vector3<f64> {
+        (self.min + self.max) / 2.0
+    }
+
+    pub fn corners(&self) -> [Vector3<f64>; 8] {
+        let (a, b) = (self.min, self.max);
+        [
+            Vector3::new(a.x, a.y, a.z),
+            Vector3::new(b.x, a.y, a.z),
+            Vector3::new(a.x, b.y, a.z),
+            Vector3::new(b.x, b.y, a.z),
+            Vector3::new(a.x, a.y, b.z),
+            Vector3::new(b.x, a.y, b.z),
+            Vector3::new(a.x, b.y, b.z),
+            Vector3::new(b.x, b.y, b.z),
+        ]
+    }
+
+    /// Whether the boxes share any point, touching included
+    pub fn overlaps(&self, other: &Aabb) -> bool {
+        (0..3).all(|i| self.min[i] <= other.max[i] && other.min[i] <= self.max[i])
+    }
+
+    /// Ray parameter where the ray enters the box, 0 if it starts inside
+    pub fn ray_entry(&self, origin: &Vector3<f64>, dir: &Vector3<f64>) -> Option<f64> {
+        let (mut near, mut far) = (0.0f64, f64::INFINITY);
+        for i in 0..3 {
+            if dir[i].abs() < 1e-15 {
+                if origin[i] < self.min[i] || origin[i] > self.max[i] {
+                    return None;
+                }
+                continue;
+            }
+            let (t0, t1) = ((self.min[i] - origin[i]) / dir[i], (self.max[i] - origin[i]) / dir[i]);
+            near = near.max(t0.min(t1));
+            far = far.min(t0.max(t1));
+            if near > far {
+                return None;
+            }
+        }
+        Some(near)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum BvhNode {
+    Leaf { bounds: Aabb, start: usize, end: usize },
+    Branch { bounds: Aabb, left: usize, right: usize },
+}
+
+impl BvhNode {
+    fn bounds(&self) -> &Aabb {
+        match self {
+            BvhNode::Leaf { bounds, .. } | BvhNode::Branch { bounds, .. } => bounds,
+        }
+    }
+}
+
+/// Hierarchy of boxed items, split at the median of the longest axis
+#[derive(Debug, Clone)]
+pub struct Bvh<T> {
+    nodes: Vec<BvhNode>,
+    items: Vec<(Aabb, T)>,
+}
+
+impl<T> Default for Bvh<T> {
+    fn default() -> Self {
+        Self { nodes: Vec::new(), items: Vec::new() }
+    }
+}
+
+impl<T> Bvh<T> {
+    pub fn build(items: Vec<(Aabb, T)>) -> Self {
+        let mut bvh = Bvh { nodes: Vec::new(), items };
+        if !bvh.items.is_empty() {
+            bvh.build_node(0, bvh.items.len());
+        }
+        bvh
+    }
+
+    fn build_node(&mut self, start: usize, end: usize) -> usize {
+        let bounds = self.items[start..end].iter().fold(Aabb::EMPTY, |b, (item, _)| b.union(item));
+        let index = self.nodes.len();
+        if end - start <= LEAF_SIZE {
+            self.nodes.push(BvhNode::Leaf { bounds, start, end });
+            return index;
+        }
+        let extent = bounds.max - bounds.min;
+        let axis = extent.imax();
+        self.items[start..end].sort_by(|a, b| a.0.center()[axis].total_cmp(&b.0.center()[axis]));
+        self.nodes.push(BvhNode::Leaf { bounds, start, end });
+        let middle = (start + end) / 2;
+        let left = self.build_node(start, middle);
+        let right = self.build_node(middle, end);
+        self.nodes[index] = BvhNode::Branch { bounds, left, right };
+        index
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Bounds of everything in the hierarchy
+    pub fn bounds(&self) -> Aabb {
+        self.nodes.first().map_or(Aabb::EMPTY, |n| *n.bounds())
+    }
+
+    /// Items whose box `accept` passes, visiting only branches whose box it passes
+    pub fn query(&self, accept: impl Fn(&Aabb) -> bool) -> Vec<&T> {
+        let mut found = Vec::new();
+        let mut stack: Vec<usize> = if self.nodes.is_empty() { Vec::new() } else { vec![0] };
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !accept(node.bounds()) {
+                continue;
+            }
+            match node {
+                BvhNode::Leaf { start, end, .. } => {
+                    found.extend(self.items[*start..*end].iter().filter(|(b, _)| accept(b)).map(|(_, item)| item));
+                }
+                BvhNode::Branch { left, right, .. } => stack.extend([*right, *left]),
+            }
+        }
+        found
+    }
+
+    /// Items whose box the ray passes within `margin` of; `margin` is asked per box
+    pub fn near_ray(&self, origin: &Vector3<f64>, dir: &Vector3<f64>, margin: impl Fn(&Aabb) -> f64) -> Vec<&T> {
+        self.query(|b| b.expanded(margin(b)).ray_entry(origin, dir).is_some())
+    }
+
+    /// Items whose box overlaps `bounds`
+    pub fn overlapping(&self, bounds: &Aabb) -> Vec<&T> {
+        self.query(|b| b.overlaps(bounds))
+    }
+}
+
+/// A tessellated triangle and the face it belongs to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BvhTriangle {
+    pub face: usize,
+    pub corners: [Vector3<f64>; 3],
+}
+
+/// Hierarchies over one body's vertices, edges and triangles
+#[derive(Debug, Clone, Default)]
+pub struct BodyBvh {
+    /// Faces of the body
+    pub faces: Vec<usize>,
+    pub vertices: Bvh<usize>,
+    pub edges: Bvh<usize>,
+    pub triangles: Bvh<BvhTriangle>,
+}
+
+impl BodyBvh {
+    /// Hierarchies over the given faces of the model
+    pub fn build(model: &BrepModel, faces: &[usize]) -> Self {
+        let vertices = model
+            .shell_vertex_ids(faces)
+            .into_iter()
+            .filter_map(|id| Some((Aabb::from_points([&model.vertex_position(id)?]), id)))
+            .collect();
+        let mut edge_ids: Vec<usize> = faces.iter().filter_map(|id| model.face(*id)).flat_map(|f| model.face_edge_ids(f)).collect();
+        edge_ids.sort_unstable();
+        edge_ids.dedup();
+        let edges = edge_ids
+            .into_iter()
+            .filter_map(|id| {
+                let e = model.edge(id)?;
+                let (a, b) = (model.vertex_position(e.vertices.0)?, model.vertex_position(e.vertices.1)?);
+                Some((Aabb::from_points([&a, &b]), id))
+            })
+            .collect();
+        let triangles = faces
+            .iter()
+            .filter_map(|id| tessellate_face(model, model.face(*id)?))
+            .flat_map(|mesh| {
+                let face = mesh.face_id;
+                mesh.triangle_positions().map(move |corners| (Aabb::from_points(&corners), BvhTriangle { face, corners })).collect::<Vec<_>>()
+            })
+            .collect();
+        BodyBvh { faces: faces.to_vec(), vertices: Bvh::build(vertices), edges: Bvh::build(edges), triangles: Bvh::build(triangles) }
+    }
+
+    pub fn bounds(&self) -> Aabb {
+        self.vertices.bounds()
+    }
+}
+
+/// Hierarchies over every body of the model, kept up to date by `update_scene_bvh`
+#[derive(Resource, Debug, Clone, Default)]
+pub struct SceneBvh {
+    bodies: BTreeMap<BodyId, BodyBvh>,
+    tree: Bvh<BodyId>,
+}
+
+impl SceneBvh {
+    pub fn build(model: &BrepModel) -> Self {
+        let mut scene = SceneBvh::default();
+        scene.update(model, &model.body_ids());
+        scene
+    }
+
+    /// Rebuild the given bodies, add new ones, drop removed ones and rebuild the body tree
+    pub fn update(&mut self, model: &BrepModel, dirty: &[BodyId]) {
+        let bodies = model.bodies();
+        self.bodies.retain(|id, _| bodies.iter().any(|(b, _)| b == id));
+        for (id, faces) in &bodies {
+            if dirty.contains(id) || !self.bodies.contains_key(id) {
+                self.bodies.insert(*id, BodyBvh::build(model, faces));
+            }
+        }
+        self.tree = Bvh::build(self.bodies.iter().map(|(id, b)| (b.bounds(), *id)).collect());
+    }
+
+    pub fn body(&self, id: BodyId) -> Option<&BodyBvh> {
+        self.bodies.get(&id)
+    }
+
+    pub fn body_bounds(&self, id: BodyId) -> Option<Aabb> {
+        self.body(id).map(BodyBvh::bounds)
+    }
+
+    /// Bodies whose bounds the ray passes within `margin` of, in id order
+    pub fn bodies_near_ray(&self, origin: &Vector3<f64>, dir: &Vector3<f64>, margin: impl Fn(&Aabb) -> f64) -> Vec<BodyId> {
+        let mut bodies: Vec<BodyId> = self.tree.near_ray(origin, dir, margin).into_iter().copied().collect();
+        bodies.sort_unstable();
+        bodies
+    }
+
+    /// Pairs of bodies whose bounds overlap: the candidates for clashes and booleans
+    pub fn overlapping_bodies(&self) -> Vec<(BodyId, BodyId)> {
+        let mut pairs: Vec<(BodyId, BodyId)> = self
+            .bodies
+            .iter()
+            .flat_map(|(id, body)| {
+                self.tree.overlapping(&body.bounds()).into_iter().filter(move |other| **other > *id).map(move |other| (*id, *other))
+            })
+            .collect();
+        pairs.sort_unstable();
+        pairs
+    }
+}
+
+/// Rebuild the hierarchies of bodies that changed
+pub fn update_scene_bvh(model: Res<BrepModel>, changes: Option<Res<BodyChanges>>, mut scene: ResMut<SceneBvh>) {
+    match changes {
+        Some(changes) if changes.is_changed() && changes.any() => scene.update(&model, changes.dirty()),
+        None if model.is_changed() => *scene = SceneBvh::build(&model),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::cube;
+    use crate::testing::support::shifted;
+
+    #[test]
+    fn test_bvh_queries_match_brute_force() {
+        let items: Vec<(Aabb, usize)> = (0..100)
+            .map(|i| {
+                let p = Vector3::new((i % 10) as f64 * 3.0, (i / 10) as f64 * 3.0, (i * 7 % 5) as f64);
+                (Aabb::from_points([&p, &(p + Vector3::repeat(1.0))]), i)
+            })
+            .collect();
+        let bvh = Bvh::build(items.clone());
+        let (origin, dir) = (Vector3::new(-5.0, 4.5, 10.0), Vector3::new(1.0, 0.0, -0.2).normalize());
+        let mut found: Vec<usize> = bvh.near_ray(&origin, &dir, |_| 0.25).into_iter().copied().collect();
+        found.sort_unstable();
+        let expected: Vec<usize> = items.iter().filter(|(b, _)| b.expanded(0.25).ray_entry(&origin, &dir).is_some()).map(|(_, i)| *i).collect();
+        assert!(!expected.is_empty());
+        assert_eq!(found, expected);
+
+        let region = Aabb { min: Vector3::new(4.0, 4.0, 0.0), max: Vector3::new(8.0, 8.0, 5.0) };
+        assert_eq!(bvh.overlapping(&region).len(), items.iter().filter(|(b, _)| b.overlaps(&region)).count());
+    }
+
+    #[test]
+    fn test_scene_updates_changed_bodies() {
+        let mut model = cube(10.0);
+        model.append(&shifted(cube(10.0), Vector3::new(8.0, 0.0, 0.0)));
+        model.append(&shifted(cube(2.0), Vector3::new(50.0, 0.0, 0.0)));
+        let mut scene = SceneBvh::build(&model);
+        assert_eq!(scene.body(BodyId(0)).unwrap().triangles.len(), 12);
+        assert_eq!(scene.overlapping_bodies(), [(BodyId(0), BodyId(1))]);
+        let down = -Vector3::z();
+        assert_eq!(scene.bodies_near_ray(&Vector3::new(50.0, 0.0, 20.0), &down, |_| 0.0), [BodyId(2)]);
+
+        // Move the small cube onto the first one; only it needs rebuilding
+        let small = model.shell_vertex_ids(&model.body_faces(BodyId(2)).unwrap());
+        for v in model.vertices.iter_mut().filter(|v| small.contains(&v.id)) {
+            v.position.x -= 50.0;
+        }
+        scene.update(&model, &[BodyId(2)]);
+        assert_eq!(scene.overlapping_bodies(), [(BodyId(0), BodyId(1)), (BodyId(0), BodyId(2))]);
+        assert!(scene.bodies_near_ray(&Vector3::new(50.0, 0.0, 20.0), &down, |_| 0.0).is_empty());
+    }
+}
@@ -0,0 +1,176 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: model::changes
+//!
+//! Which bodies changed. Bevy only reports that the `BrepModel` resource as a
+//! whole was touched, so every edit would otherwise rebuild every body's mesh
+//! and properties. `BodyChanges` keeps a fingerprint of each body's topology
+//! and vertex positions; when the model changes, bodies whose fingerprint
+//! differs get a new generation and are dirty for that frame.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+
+use bevy::prelude::*;
+
+use crate::measure::mass_properties::compute_mass_properties;
+use crate::model::body::{Body, BodyId};
+use crate::model::brep::topology::invariants::check_shell;
+use crate::model::brep_model::BrepModel;
+use crate::model::properties::BodyPropertiesCollection;
+
+/// Per-body fingerprints, generations and the bodies that changed last update
+#[derive(Resource, Debug, Default, Clone)]
+pub struct BodyChanges {
+    fingerprints: BTreeMap<BodyId, u64>,
+    generations: BTreeMap<BodyId, u64>,
+    dirty: Vec<BodyId>,
+    /// Whether bodies were added or removed in the last update
+    count_changed: bool,
+}
+
+/// Hash of a body's faces, loops, edges and vertex positions
+fn fingerprint(model: &BrepModel, faces: &[usize]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for face in faces.iter().filter_map(|id| model.face(*id)) {
+        face.id.hash(&mut hasher);
+        for edge_loop in model.face_loops(face) {
+            edge_loop.edges.hash(&mut hasher);
+        }
+    }
+    for id in model.shell_vertex_ids(faces) {
+        id.hash(&mut hasher);
+        if let Some(p) = model.vertex_position(id) {
+            p.iter().for_each(|c| c.to_bits().hash(&mut hasher));
+        }
+    }
+    hasher.finish()
+}
+
+impl BodyChanges {
+    /// Compare the model against the last fingerprints and return the dirty bodies
+    pub fn update(&mut self, model: &BrepModel) -> &[BodyId] {
+        let prints: BTreeMap<BodyId, u64> = model.bodies().iter().map(|(id, faces)| (*id, fingerprint(model, faces))).collect();
+        self.count_changed = !prints.keys().eq(self.fingerprints.keys());
+        self.dirty = prints.iter().filter(|(id, print)| self.fingerprints.get(*id) != Some(*print)).map(|(id, _)| *id).collect();
+        self.generations.retain(|id, _| prints.contains_key(id));
+        for body in &self.dirty {
+            *self.generations.entry(*body).or_default() += 1;
+        }
+        self.fingerprints = prints;
+        &self.dirty
+    }
+
+    /// Forget the dirty bodies once their changes have been handled
+    pub fn clear(&mut self) {
+        self.dirty.clear();
+        self.count_changed = false;
+    }
+
+    /// Bodies that changed in the last update
+    pub fn dirty(&self) -> &[BodyId] {
+        &self.dirty
+    }
+
+    pub fn is_dirty(&self, body: BodyId) -> bool {
+        self.dirty.contains(&body)
+    }
+
+    /// Whether bodies were added or removed in the last update
+    pub fn count_changed(&self) -> bool {
+        self.count_changed
+    }
+
+    /// Whether the last update found any change
+    pub fn any(&self) -> bool {
+        !self.dirty.is_empty() || self.count_changed
+    }
+
+    /// Number of times a body has changed; 0 for unknown bodies
+    pub fn generation(&self, body: BodyId) -> u64 {
+        self.generations.get(&body).copied().unwrap_or(0)
+    }
+}
+
+/// Fingerprint the model's bodies when it changes; clear the dirty set otherwise
+pub fn track_body_changes(model: Res<BrepModel>, mut changes: ResMut<BodyChanges>) {
+    if model.is_changed() {
+        changes.update(&model);
+    } else if changes.any() {
+        changes.clear();
+    }
+}
+
+/// Recompute the mass properties of bodies that changed and have properties
+pub fn refresh_mass_properties(model: Res<BrepModel>, changes: Res<BodyChanges>, mut properties: ResMut<BodyPropertiesCollection>) {
+    if !changes.is_changed() || changes.dirty().is_empty() {
+        return;
+    }
+    for body in changes.dirty() {
+        let (Some(brep), Some(props)) = (model.body(*body), properties.get_mut(*body)) else { continue };
+        compute_mass_properties(&Body::new(*body, brep), props);
+    }
+}
+
+/// Warn about edited bodies that break a closed shell invariant; debug builds only
+pub fn check_topology_invariants(model: Res<BrepModel>, changes: Res<BodyChanges>) {
+    if !cfg!(debug_assertions) || !changes.is_changed() || changes.dirty().is_empty() {
+        return;
+    }
+    for body in changes.dirty() {
+        let Some(faces) = model.body_faces(*body) else { continue };
+        if let Some(violation) = check_shell(&model, body.0, &faces).first() {
+            warn!("Body {} breaks a topology invariant: {}", body.0, violation);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::cube;
+
+    #[test]
+    fn test_only_edited_bodies_are_dirty() {
+        let mut model = cube(2.0);
+        let mut other = cube(2.0);
+        other.vertices.iter_mut().for_each(|v| v.position.x += 10.0);
+        model.append(&other);
+
+        let mut changes = BodyChanges::default();
+        assert_eq!(changes.update(&model), [BodyId(0), BodyId(1)]);
+        assert!(changes.count_changed());
+        assert!(changes.update(&model).is_empty());
+
+        let moved = model.shell_vertex_ids(&model.body_faces(BodyId(1)).unwrap());
+        for v in model.vertices.iter_mut().filter(|v| moved.contains(&v.id)) {
+            v.position.z += 1.0;
+        }
+        assert_eq!(changes.update(&model), [BodyId(1)]);
+        assert!(!changes.count_changed());
+        assert_eq!((changes.generation(BodyId(0)), changes.generation(BodyId(1))), (1, 2));
+    }
+
+    #[test]
+    fn test_mass_properties_follow_edits() {
+        let mut app = App::new();
+        app.insert_resource(cube(2.0))
+            .init_resource::<BodyChanges>()
+            .init_resource::<BodyPropertiesCollection>()
+            .add_systems(Update, (track_body_changes, refresh_mass_properties).chain());
+        app.world_mut().resource_mut::<BodyPropertiesCollection>().register(BodyId(0), "Cube");
+        app.update();
+        let volume = |app: &App| app.world().resource::<BodyPropertiesCollection>().get(BodyId(0)).unwrap().volume.unwrap();
+        assert!((volume(&app) - 8.0).abs() < 1e-9);
+
+        for v in app.world_mut().resource_mut::<BrepModel>().vertices.iter_mut().filter(|v| v.position.z > 0.0) {
+            v.position.z += 2.0;
+        }
+        app.update();
+        assert!((volume(&app) - 16.0).abs() < 1e-9);
+        app.update();
+        assert!(app.world().resource::<BodyChanges>().dirty().is_empty());
+    }
+}
@@ -0,0 +1,553 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: model::command
+//!
+//! Command bus for modeling operations. Panels, keyboard shortcuts, XR input
+//! and scripts all send `ModelCommand` events instead of editing the model
+//! themselves, and one executor system applies them in order. Every command
+//! is journaled and kept in the `CommandLog` with its outcome. Live edits such
+//! as vertex drags preview on the model and send their result when they end,
+//! and imports add their bodies, meshes and drawings with commands too.
+//! Commands that replace topology clear the selection, whose ids they
+//! invalidate. Bodies keep their ids; a removed body's id is not reused. In a
+//! shared session the `CommandRelay` passes commands to and from the other
+//! users, so that every copy of the model runs the same commands in the same
+//! order.
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+use nalgebra::{Vector2, Vector3};
+use serde::{Deserialize, Serialize};
+
+use crate::interaction::quick_boolean::{boolean_bodies, QuickBooleanError};
+use crate::interaction::selection::Selection;
+use crate::measure::mass_properties::compute_mass_properties;
+use crate::model::assembly::Assembly;
+use crate::model::body::{Body, BodyId};
+use crate::model::brep::operations::boolean::BooleanOp;
+use crate::model::brep::operations::delete::{collapse_edge, delete_vertex, DeleteError};
+use crate::model::brep_model::BrepModel;
+use crate::model::feature_tree::{FeatureError, FeatureId, FeatureKind, FeatureOutput, FeatureTree};
+use crate::model::groups::BodyGroups;
+use crate::model::material::Material;
+use crate::model::mesh_body::{MeshBodies, MeshBody};
+use crate::model::properties::{BodyPropertiesCollection, RenameError};
+use crate::sketch::sketch::{Sketch, Sketches};
+use crate::telemetry::crash::journal;
+use crate::telemetry::usage::UsageStats;
+
+/// Commands kept in the log before the oldest are dropped
+pub const COMMAND_LOG_LIMIT: usize = 1000;
+
+/// A modeling operation
+#[derive(Event, Debug, Clone, Serialize, Deserialize)]
+pub enum ModelCommand {
+    /// Record a box or cylinder feature and add its body
+    CreatePrimitive(FeatureKind),
+    /// Extrude the region of a sketch feature containing `seed` (sketch coordinates)
+    Extrude { sketch: FeatureId, seed: Vector2<f64>, distance: f64 },
+    /// Combine two bodies; the result takes the target's properties
+    Boolean { op: BooleanOp, target: BodyId, tool: BodyId },
+    /// Move a body; for a body linked to a feature the move is recorded in the history
+    TranslateBody { body: BodyId, offset: Vector3<f64> },
+    /// Remove a body from the model; its features stay in the history
+    DeleteBody(BodyId),
+    /// Remove a vertex joining two edges, merging the edges
+    DeleteVertex(usize),
+    /// Merge an edge's vertices at its midpoint
+    CollapseEdge(usize),
+    /// Put vertices where a drag or exact transform left them
+    MoveVertices(Vec<(usize, Vector3<f64>)>),
+    SetMaterial { body: BodyId, material: Material },
+    RenameBody { body: BodyId, name: String },
+    /// Add a body without history, such as an imported solid, named "<name>.NNN"
+    AddBody { name: String, body: BrepModel },
+    /// Add an imported mesh as a reference mesh body
+    AddMesh(MeshBody),
+    /// Add an imported drawing as a sketch and make it the active one
+    AddSketch(Sketch),
+}
+
+impl ModelCommand {
+    /// Command name for the journal and usage statistics
+    pub fn label(&self) -> &'static str {
+        match self {
+            ModelCommand::CreatePrimitive(_) => "create_primitive",
+            ModelCommand::Extrude { .. } => "extrude",
+            ModelCommand::Boolean { .. } => "boolean",
+            ModelCommand::TranslateBody { .. } => "translate_body",
+            ModelCommand::DeleteBody(_) => "delete_body",
+            ModelCommand::DeleteVertex(_) => "delete_vertex",
+            ModelCommand::CollapseEdge(_) => "collapse_edge",
+            ModelCommand::MoveVertices(_) => "move_vertices",
+            ModelCommand::SetMaterial { .. } => "set_material",
+            ModelCommand::RenameBody { .. } => "rename_body",
+            ModelCommand::AddBody { .. } => "add_body",
+            ModelCommand::AddMesh(_) => "add_mesh",
+            ModelCommand::AddSketch(_) => "add_sketch",
+        }
+    }
+
+    /// Journal line for the command; added geometry is summarised rather than dumped
+    pub fn journal_entry(&self) -> String {
+        match self {
+            ModelCommand::AddBody { name, body } => format!("{} {} ({} faces)", self.label(), name, body.faces.len()),
+            ModelCommand::AddMesh(mesh) => format!("{} {} ({} triangles)", self.label(), mesh.name, mesh.triangles.len()),
+            ModelCommand::AddSketch(sketch) => format!("{} {}", self.label(), sketch.name),
+            _ => format!("{} {:?}", self.label(), self),
+        }
+    }
+
+    /// Whether the command changes face, edge or vertex ids
+    pub fn replaces_topology(&self) -> bool {
+        matches!(
+            self,
+            ModelCommand::Boolean { .. } | ModelCommand::DeleteBody(_) | ModelCommand::DeleteVertex(_) | ModelCommand::CollapseEdge(_)
+        )
+    }
+
+    /// Bodies the command removes from the model; a boolean's result takes the
+    /// target's id, so only the tool goes
+    pub fn removed_bodies(&self) -> Vec<BodyId> {
+        match self {
+            ModelCommand::DeleteBody(id) => vec![*id],
+            ModelCommand::Boolean { tool, .. } => vec![*tool],
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Why a command was refused
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModelCommandError {
+    UnknownBody(BodyId),
+    UnknownVertex(usize),
+    /// `CreatePrimitive` was given a feature that is not a box or cylinder
+    NotAPrimitive(&'static str),
+    Feature(FeatureError),
+    Boolean(QuickBooleanError),
+    Rename(RenameError),
+    Delete(DeleteError),
+    /// `execute` was given a command for the document's meshes or sketches,
+    /// which only the executor system holds
+    OutsideModel(&'static str),
+}
+
+impl fmt::Display for ModelCommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModelCommandError::UnknownBody(id) => write!(f, "no body with id {}", id.0),
+            ModelCommandError::UnknownVertex(id) => write!(f, "no vertex {}", id),
+            ModelCommandError::NotAPrimitive(label) => write!(f, "{} is not a primitive", label),
+            ModelCommandError::Feature(err) => write!(f, "{}", err),
+            ModelCommandError::Boolean(err) => write!(f, "{}", err),
+            ModelCommandError::Rename(err) => write!(f, "{}", err),
+            ModelCommandError::Delete(err) => write!(f, "{}", err),
+            ModelCommandError::OutsideModel(label) => write!(f, "{} does not edit the model", label),
+        }
+    }
+}
+
+impl std::error::Error for ModelCommandError {}
+
+/// Record a feature named after its operation. A feature that fails to
+/// evaluate is not kept in the history.
+fn record_feature(features: &mut FeatureTree, kind: FeatureKind) -> Result<FeatureId, ModelCommandError> {
+    let label = kind.label();
+    let count = features.features.iter().filter(|f| f.kind.label() == label).count();
+    let id = features.add(format!("{}.{:03}", label, count + 1), kind).map_err(ModelCommandError::Feature)?;
+    if let Some(Err(err)) = features.result(id) {
+        let err = err.clone();
+        let _ = features.remove(id);
+        return Err(ModelCommandError::Feature(err));
+    }
+    Ok(id)
+}
+
+/// Record a feature and append its body to the model under the feature's name
+fn add_feature_body(
+    model: &mut BrepModel,
+    features: &mut FeatureTree,
+    properties: &mut BodyPropertiesCollection,
+    kind: FeatureKind,
+) -> Result<BodyId, ModelCommandError> {
+    let label = kind.label();
+    let id = record_feature(features, kind)?;
+    let body = match features.result(id) {
+        Some(Ok(FeatureOutput::Body(body))) => body.clone(),
+        _ => {
+            let _ = features.remove(id);
+            return Err(ModelCommandError::Feature(FeatureError::UnknownFeature(id)));
+        }
+    };
+    let body_id = model.add_body(&body);
+    features.link(body_id, id);
+    compute_mass_properties(&Body::new(body_id, body), properties.register(body_id, label));
+    Ok(body_id)
+}
+
+/// Body id checked against the model's bodies
+fn existing_body(model: &BrepModel, id: BodyId) -> Result<BodyId, ModelCommandError> {
+    if model.has_body(id) {
+        Ok(id)
+    } else {
+        Err(ModelCommandError::UnknownBody(id))
+    }
+}
+
+/// Bodies whose shape the command changes outside the feature history, so
+/// that they no longer show the feature they are linked to
+fn reshaped_bodies(command: &ModelCommand, model: &BrepModel) -> Vec<BodyId> {
+    let vertices: Vec<usize> = match command {
+        ModelCommand::DeleteVertex(id) => vec![*id],
+        ModelCommand::CollapseEdge(id) => model.edge(*id).map(|e| vec![e.vertices.0, e.vertices.1]).unwrap_or_default(),
+        ModelCommand::MoveVertices(moves) => moves.iter().map(|(id, _)| *id).collect(),
+        _ => return Vec::new(),
+    };
+    let owners = model.vertex_bodies();
+    let mut bodies: Vec<BodyId> = vertices.iter().filter_map(|v| owners.get(v).copied()).collect();
+    bodies.sort_unstable();
+    bodies.dedup();
+    bodies
+}
+
+/// Apply one command; refused commands leave the model, history and properties unchanged
+pub fn execute(
+    command: &ModelCommand,
+    model: &mut BrepModel,
+    features: &mut FeatureTree,
+    properties: &mut BodyPropertiesCollection,
+) -> Result<(), ModelCommandError> {
+    let reshaped = reshaped_bodies(command, model);
+    match command {
+        ModelCommand::CreatePrimitive(kind) => {
+            if !matches!(kind, FeatureKind::Box { .. } | FeatureKind::Cylinder { .. }) {
+                return Err(ModelCommandError::NotAPrimitive(kind.label()));
+            }
+            add_feature_body(model, features, properties, kind.clone())?;
+        }
+        ModelCommand::Extrude { sketch, seed, distance } => {
+            add_feature_body(model, features, properties, FeatureKind::Extrude { sketch: *sketch, seed: *seed, distance: *distance })?;
+        }
+        ModelCommand::Boolean { op, target, tool } => {
+            boolean_bodies(model, features, Some(properties), *op, *target, *tool).map_err(ModelCommandError::Boolean)?;
+        }
+        ModelCommand::TranslateBody { body, offset } => {
+            let shell = model.body_faces(*body).ok_or(ModelCommandError::UnknownBody(*body))?;
+            if let Some(source) = features.linked_feature(*body).filter(|f| features.body(*f).is_some()) {
+                let moved = record_feature(features, FeatureKind::Translate { body: source, offset: *offset })?;
+                features.link(*body, moved);
+            }
+            for id in model.shell_vertex_ids(&shell) {
+                if let Some(v) = model.vertices.iter_mut().find(|v| v.id == id) {
+                    v.position += offset;
+                }
+            }
+            if let Some(props) = properties.get_mut(*body) {
+                compute_mass_properties(&Body::new(*body, model.extract_faces(&shell)), props);
+            }
+        }
+        ModelCommand::DeleteBody(id) => {
+            let shell = model.body_faces(*id).ok_or(ModelCommandError::UnknownBody(*id))?;
+            model.faces.retain(|f| !shell.contains(&f.id));
+            model.remove_unused();
+            features.unlink(*id);
+            properties.remove(*id);
+        }
+        ModelCommand::DeleteVertex(id) => {
+            delete_vertex(model, *id).map_err(ModelCommandError::Delete)?;
+        }
+        ModelCommand::CollapseEdge(id) => {
+            collapse_edge(model, *id).map_err(ModelCommandError::Delete)?;
+        }
+        ModelCommand::MoveVertices(moves) => {
+            if let Some((id, _)) = moves.iter().find(|(id, _)| model.vertex(*id).is_none()) {
+                return Err(ModelCommandError::UnknownVertex(*id));
+            }
+            for (id, position) in moves {
+                if let Some(v) = model.vertices.iter_mut().find(|v| v.id == *id) {
+                    v.position = *position;
+                }
+            }
+        }
+        ModelCommand::SetMaterial { body, material } => {
+            let body = existing_body(model, *body)?;
+            properties.register(body, "Body").material = material.clone();
+        }
+        ModelCommand::RenameBody { body, name } => {
+            let body = existing_body(model, *body)?;
+            properties.register(body, "Body");
+            properties.rename(body, name).map_err(ModelCommandError::Rename)?;
+        }
+        ModelCommand::AddBody { name, body } => {
+            let id = model.add_body(body);
+            compute_mass_properties(&Body::new(id, body.clone()), properties.register(id, name));
+        }
+        ModelCommand::AddMesh(_) | ModelCommand::AddSketch(_) => return Err(ModelCommandError::OutsideModel(command.label())),
+    }
+    for body in reshaped {
+        features.unlink(body);
+    }
+    // Shells the command split or created keep the ids they were given
+    model.assign_body_ids();
+    Ok(())
+}
+
+/// An executed command and why it was refused, if it was
+#[derive(Debug, Clone)]
+pub struct CommandRecord {
+    pub command: ModelCommand,
+    pub error: Option<ModelCommandError>,
+}
+
+/// Latest executed commands, oldest first
+#[derive(Resource, Debug, Default, Clone)]
+pub struct CommandLog {
+    pub records: VecDeque<CommandRecord>,
+}
+
+impl CommandLog {
+    pub fn push(&mut self, record: CommandRecord) {
+        if self.records.len() == COMMAND_LOG_LIMIT {
+            self.records.pop_front();
+        }
+        self.records.push_back(record);
+    }
+}
+
+/// Number of commands applied to the document. Background jobs compare it
+/// with the value they started at to tell whether the document was edited
+/// while they ran.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ModelRevision(pub u64);
+
+/// Commands exchanged with a shared editing session
+#[derive(Resource, Debug, Default, Clone)]
+pub struct CommandRelay {
+    /// A session is open: executed local commands are queued in `outgoing`
+    pub sharing: bool,
+    /// Local commands are not executed here but queued for the session to
+    /// order, and run when they come back in `incoming`
+    pub hold_local: bool,
+    pub outgoing: Vec<ModelCommand>,
+    /// Commands of the session, run before this frame's local ones
+    pub incoming: VecDeque<ModelCommand>,
+}
+
+/// End a live edit that moved vertices directly: their final positions are
+/// sent as one command, so the edit is logged and shared. A client of a shared
+/// session puts the vertices back until the host orders the command.
+pub fn commit_vertex_moves(
+    model: &mut BrepModel,
+    original: &[(usize, Vector3<f64>)],
+    relay: Option<&CommandRelay>,
+    commands: &mut EventWriter<ModelCommand>,
+) {
+    if original.iter().all(|(id, p)| model.vertex_position(*id) == Some(*p)) {
+        return;
+    }
+    let moves = original.iter().filter_map(|(id, _)| Some((*id, model.vertex_position(*id)?))).collect();
+    if relay.is_some_and(|r| r.hold_local) {
+        for (id, p) in original {
+            if let Some(v) = model.vertices.iter_mut().find(|v| v.id == *id) {
+                v.position = *p;
+            }
+        }
+    }
+    commands.write(ModelCommand::MoveVertices(moves));
+}
+
+/// Drop the bodies an executed command removed from the groups and
+/// components holding them
+pub fn forget_removed_bodies(command: &ModelCommand, mut groups: Option<&mut BodyGroups>, mut assembly: Option<&mut Assembly>) {
+    for body in command.removed_bodies() {
+        if let Some(groups) = groups.as_mut() {
+            groups.remove_body(body);
+        }
+        if let Some(assembly) = assembly.as_mut() {
+            assembly.remove_body(body);
+        }
+    }
+}
+
+/// Execute model commands in the order they were sent, those from a shared
+/// session first
+pub fn execute_model_commands(
+    mut commands: EventReader<ModelCommand>,
+    mut model: ResMut<BrepModel>,
+    mut features: ResMut<FeatureTree>,
+    mut properties: Option<ResMut<BodyPropertiesCollection>>,
+    mut selection: Option<ResMut<Selection>>,
+    (mut log, mut usage, mut revision): (Option<ResMut<CommandLog>>, Option<ResMut<UsageStats>>, Option<ResMut<ModelRevision>>),
+    mut relay: Option<ResMut<CommandRelay>>,
+    (mut groups, mut assembly): (Option<ResMut<BodyGroups>>, Option<ResMut<Assembly>>),
+    (mut meshes, mut sketches): (Option<ResMut<MeshBodies>>, Option<ResMut<Sketches>>),
+) {
+    let mut queued: Vec<ModelCommand> = relay.as_mut().map(|r| r.incoming.drain(..).collect()).unwrap_or_default();
+    for command in commands.read() {
+        if let Some(relay) = relay.as_mut().filter(|r| r.sharing) {
+            relay.outgoing.push(command.clone());
+            if relay.hold_local {
+                continue;
+            }
+        }
+        queued.push(command.clone());
+    }
+    // Without a properties resource, names and materials are not kept
+    let mut scratch = BodyPropertiesCollection::new();
+    for command in &queued {
+        let start = Instant::now();
+        journal(command.journal_entry());
+        let props = properties.as_deref_mut().unwrap_or(&mut scratch);
+        let outcome = match (command, meshes.as_mut(), sketches.as_mut()) {
+            (ModelCommand::AddMesh(mesh), Some(meshes), _) => {
+                meshes.add(mesh.clone());
+                Ok(())
+            }
+            (ModelCommand::AddSketch(sketch), _, Some(sketches)) => {
+                let index = sketches.add(sketch.clone());
+                sketches.active = Some(index);
+                Ok(())
+            }
+            _ => execute(command, &mut model, &mut features, props),
+        };
+        if outcome.is_ok() {
+            forget_removed_bodies(command, groups.as_deref_mut(), assembly.as_deref_mut());
+            if let Some(revision) = revision.as_mut() {
+                revision.0 += 1;
+            }
+        }
+        match &outcome {
+            Ok(()) if command.replaces_topology() => {
+                if let Some(selection) = selection.as_mut() {
+                    selection.clear();
+                }
+            }
+            Ok(()) => {}
+            Err(err) => warn!("{} refused: {}", command.label(), err),
+        }
+        if let Some(log) = log.as_mut() {
+            log.push(CommandRecord { command: command.clone(), error: outcome.err() });
+        }
+        if let Some(usage) = usage.as_mut() {
+            usage.record(command.label(), start.elapsed());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::placement::PlacementFrame;
+    use nalgebra::{Point3, Vector3};
+
+    fn cube_at(x: f64) -> ModelCommand {
+        let placement = PlacementFrame::new(Point3::new(x, 0.0, 0.0), Vector3::z());
+        ModelCommand::CreatePrimitive(FeatureKind::Box { size: Vector3::repeat(10.0), placement: Some(placement) })
+    }
+
+    #[test]
+    fn test_commands_edit_the_model_through_one_executor() {
+        let mut groups = BodyGroups::new();
+        let group = groups.create_group("Frame", None).unwrap();
+        groups.move_body(BodyId(2), Some(group)).unwrap();
+        let mut assembly = Assembly::new();
+        assembly.add_body(assembly.root(), BodyId(1)).unwrap();
+        let mut app = App::new();
+        app.init_resource::<BrepModel>()
+            .init_resource::<FeatureTree>()
+            .init_resource::<BodyPropertiesCollection>()
+            .init_resource::<CommandLog>()
+            .insert_resource(groups)
+            .insert_resource(assembly)
+            .add_event::<ModelCommand>()
+            .add_systems(Update, execute_model_commands);
+        for command in [
+            cube_at(0.0),
+            cube_at(100.0),
+            cube_at(200.0),
+            ModelCommand::SetMaterial { body: BodyId(2), material: Material::new("Steel", [0.6, 0.6, 0.6]) },
+            ModelCommand::DeleteBody(BodyId(1)),
+            ModelCommand::RenameBody { body: BodyId(0), name: "Base".into() },
+            ModelCommand::DeleteBody(BodyId(5)),
+        ] {
+            app.world_mut().send_event(command);
+        }
+        app.update();
+
+        assert_eq!(app.world().resource::<BrepModel>().body_ids(), [BodyId(0), BodyId(2)]);
+        let props = app.world().resource::<BodyPropertiesCollection>();
+        assert_eq!(props.get(BodyId(0)).unwrap().name, "Base");
+        assert!(props.get(BodyId(1)).is_none());
+        let kept = props.get(BodyId(2)).unwrap();
+        assert_eq!((kept.name.as_str(), kept.material.name.as_str()), ("Box.003", "Steel"));
+        // Bodies keep their ids, so groups still hold them; components forget the deleted one
+        assert_eq!(app.world().resource::<BodyGroups>().get(group).unwrap().bodies, [BodyId(2)]);
+        let assembly = app.world().resource::<Assembly>();
+        assert!(assembly.component(assembly.root()).unwrap().bodies.is_empty());
+        let log = app.world().resource::<CommandLog>();
+        assert_eq!(log.records.len(), 7);
+        assert_eq!(log.records[6].error, Some(ModelCommandError::UnknownBody(BodyId(5))));
+    }
+
+    #[test]
+    fn test_moved_bodies_keep_their_history() {
+        let (mut model, mut features, mut props) = (BrepModel::new(), FeatureTree::new(), BodyPropertiesCollection::new());
+        for command in [
+            cube_at(0.0),
+            cube_at(100.0),
+            ModelCommand::TranslateBody { body: BodyId(1), offset: Vector3::new(-95.0, 0.0, 0.0) },
+            ModelCommand::Boolean { op: BooleanOp::Union, target: BodyId(0), tool: BodyId(1) },
+        ] {
+            execute(&command, &mut model, &mut features, &mut props).unwrap();
+        }
+        let labels: Vec<&str> = features.features.iter().map(|f| f.kind.label()).collect();
+        assert_eq!(labels, ["Box", "Box", "Translate", "Union"]);
+        assert!((crate::measure::mass_properties::mass_properties(&model).volume - 1500.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_dragged_bodies_are_recorded_before_a_boolean() {
+        let (mut model, mut features, mut props) = (BrepModel::new(), FeatureTree::new(), BodyPropertiesCollection::new());
+        execute(&cube_at(0.0), &mut model, &mut features, &mut props).unwrap();
+        execute(&cube_at(100.0), &mut model, &mut features, &mut props).unwrap();
+        // A gizmo drag ends as a vertex move, which the Box feature does not know about
+        let shell = model.body_faces(BodyId(1)).unwrap();
+        let moves = model.shell_vertex_ids(&shell).into_iter().filter_map(|id| Some((id, model.vertex_position(id)? - Vector3::new(95.0, 0.0, 0.0)))).collect();
+        execute(&ModelCommand::MoveVertices(moves), &mut model, &mut features, &mut props).unwrap();
+        assert_eq!(features.linked_feature(BodyId(1)), None);
+
+        let union = ModelCommand::Boolean { op: BooleanOp::Union, target: BodyId(0), tool: BodyId(1) };
+        execute(&union, &mut model, &mut features, &mut props).unwrap();
+        let labels: Vec<&str> = features.features.iter().map(|f| f.kind.label()).collect();
+        assert_eq!(labels, ["Box", "Box", "Body", "Union"]);
+        assert!((crate::measure::mass_properties::mass_properties(&model).volume - 1500.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_vertex_moves_are_all_or_nothing() {
+        let (mut model, mut features, mut props) = (BrepModel::new(), FeatureTree::new(), BodyPropertiesCollection::new());
+        execute(&cube_at(0.0), &mut model, &mut features, &mut props).unwrap();
+        let id = model.vertices[0].id;
+        let target = Vector3::new(1.0, 2.0, 3.0);
+        let refused = ModelCommand::MoveVertices(vec![(id, target), (999, target)]);
+        assert_eq!(execute(&refused, &mut model, &mut features, &mut props), Err(ModelCommandError::UnknownVertex(999)));
+        assert_ne!(model.vertex_position(id), Some(target));
+        execute(&ModelCommand::MoveVertices(vec![(id, target)]), &mut model, &mut features, &mut props).unwrap();
+        assert_eq!(model.vertex_position(id), Some(target));
+    }
+
+    #[test]
+    fn test_only_primitives_are_created() {
+        let (mut model, mut features, mut props) = (BrepModel::new(), FeatureTree::new(), BodyPropertiesCollection::new());
+        let sketch = ModelCommand::CreatePrimitive(FeatureKind::MergeFaces { body: FeatureId(0) });
+        assert_eq!(execute(&sketch, &mut model, &mut features, &mut props), Err(ModelCommandError::NotAPrimitive("Merge Faces")));
+        let extrude = ModelCommand::Extrude { sketch: FeatureId(3), seed: Vector2::zeros(), distance: 5.0 };
+        assert!(execute(&extrude, &mut model, &mut features, &mut props).is_err());
+        assert!(features.features.is_empty());
+    }
+}
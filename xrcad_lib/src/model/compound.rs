@@ -0,0 +1,190 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: model::compound
+//!
+//! Non-boolean combine and separate. Combining gathers several bodies into one
+//! compound body whose shells stay disjoint; separating splits a body into one body
+//! per connected shell. Names, properties and group membership follow the bodies.
+
+use std::fmt;
+
+use crate::measure::mass_properties::compute_mass_properties;
+use crate::model::body::{Body, BodyId};
+use crate::model::brep_model::BrepModel;
+use crate::model::groups::BodyGroups;
+use crate::model::properties::{base_name, BodyProperties, BodyPropertiesCollection};
+
+/// Why a combine or separate was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompoundError {
+    UnknownBody(BodyId),
+    /// Combine needs at least two distinct bodies
+    TooFewBodies,
+    /// The body is a single connected shell and cannot be separated
+    SingleShell(BodyId),
+}
+
+impl fmt::Display for CompoundError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompoundError::UnknownBody(id) => write!(f, "no body with id {}", id.0),
+            CompoundError::TooFewBodies => write!(f, "select at least two bodies to combine"),
+            CompoundError::SingleShell(id) => write!(f, "body {} has only one shell", id.0),
+        }
+    }
+}
+
+impl std::error::Error for CompoundError {}
+
+/// Recompute the stored mass properties of a body
+fn refresh_mass_properties(body: &Body, properties: &mut BodyPropertiesCollection) {
+    if let Some(props) = properties.get_mut(body.id) {
+        compute_mass_properties(body, props);
+    }
+}
+
+/// Merge `ids` into the first of them, which keeps its id, name and group; the
+/// other bodies are removed from the document
+pub fn combine_bodies(
+    bodies: &mut Vec<Body>,
+    properties: &mut BodyPropertiesCollection,
+    groups: &mut BodyGroups,
+    ids: &[BodyId],
+) -> Result<BodyId, CompoundError> {
+    let mut unique: Vec<BodyId> = Vec::new();
+    for id in ids {
+        if !bodies.iter().any(|b| b.id == *id) {
+            return Err(CompoundError::UnknownBody(*id));
+        }
+        if !unique.contains(id) {
+            unique.push(*id);
+        }
+    }
+    let [target, rest @ ..] = unique.as_slice() else { return Err(CompoundError::TooFewBodies); };
+    if rest.is_empty() {
+        return Err(CompoundError::TooFewBodies);
+    }
+
+    let mut merged = BrepModel::new();
+    for (i, id) in unique.iter().enumerate() {
+        if let Some(body) = bodies.iter().find(|b| b.id == *id) {
+            if i == 0 {
+                merged.tolerance = body.brep.tolerance;
+            }
+            merged.append(&body.brep);
+        }
+    }
+    bodies.retain(|b| !rest.contains(&b.id));
+    for id in rest {
+        properties.remove(*id);
+        groups.remove_body(*id);
+    }
+    let body = bodies.iter_mut().find(|b| b.id == *target).expect("target body was checked above");
+    body.brep = merged;
+    refresh_mass_properties(body, properties);
+    Ok(*target)
+}
+
+/// Split a body into one body per connected shell. The first shell keeps the
+/// original id; the others get new ids, copies of its properties under generated
+/// names, and the same group. Returns the ids of all resulting bodies.
+pub fn separate_body(
+    bodies: &mut Vec<Body>,
+    properties: &mut BodyPropertiesCollection,
+    groups: &mut BodyGroups,
+    id: BodyId,
+) -> Result<Vec<BodyId>, CompoundError> {
+    let index = bodies.iter().position(|b| b.id == id).ok_or(CompoundError::UnknownBody(id))?;
+    let shells = bodies[index].brep.shells();
+    if shells.len() < 2 {
+        return Err(CompoundError::SingleShell(id));
+    }
+
+    let source = std::mem::take(&mut bodies[index].brep);
+    let template = properties.get(id).cloned().unwrap_or_else(|| BodyProperties::new("Body"));
+    let group = groups.group_of(id);
+    let first_new_id = bodies.iter().map(|b| b.id.0 + 1).max().unwrap_or(0);
+    let mut out = vec![id];
+    bodies[index].brep = source.extract_faces(&shells[0]);
+    refresh_mass_properties(&bodies[index], properties);
+
+    for (new_id, shell) in (first_new_id..).zip(&shells[1..]) {
+        let body = Body::new(BodyId(new_id), source.extract_faces(shell));
+        let mut props = template.clone();
+        props.name = properties.generate_name(base_name(&template.name));
+        compute_mass_properties(&body, &mut props);
+        properties.insert(body.id, props);
+        // Moving into an existing group (or none) cannot fail
+        let _ = groups.move_body(body.id, group);
+        out.push(body.id);
+        bodies.push(body);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::{cube, cylinder};
+    use crate::testing::support::shifted;
+    use nalgebra::Vector3;
+
+    fn document() -> (Vec<Body>, BodyPropertiesCollection, BodyGroups) {
+        let bodies = vec![
+            Body::new(BodyId(0), cube(2.0)),
+            Body::new(BodyId(1), shifted(cylinder(1.0, 2.0, 8), Vector3::new(10.0, 0.0, 0.0))),
+        ];
+        let mut properties = BodyPropertiesCollection::new();
+        properties.register(BodyId(0), "Cube");
+        properties.register(BodyId(1), "Cylinder");
+        let mut groups = BodyGroups::new();
+        let frame = groups.create_group("Frame", None).unwrap();
+        groups.move_body(BodyId(0), Some(frame)).unwrap();
+        groups.move_body(BodyId(1), Some(frame)).unwrap();
+        (bodies, properties, groups)
+    }
+
+    #[test]
+    fn test_combine_then_separate() {
+        let (mut bodies, mut properties, mut groups) = document();
+        let frame = groups.group_of(BodyId(0));
+        let id = combine_bodies(&mut bodies, &mut properties, &mut groups, &[BodyId(0), BodyId(1)]).unwrap();
+        assert_eq!(id, BodyId(0));
+        assert_eq!(bodies.len(), 1);
+        assert_eq!(bodies[0].brep.faces.len(), 6 + 10);
+        assert_eq!(bodies[0].brep.shells().len(), 2);
+        assert!(properties.get(BodyId(1)).is_none());
+        assert_eq!(groups.get(frame.unwrap()).unwrap().bodies, vec![BodyId(0)]);
+        let volume = properties.get(id).unwrap().volume.unwrap();
+        // Cube plus an octagonal prism of circumradius 1 and height 2
+        assert!((volume - (8.0 + 8.0 * std::f64::consts::FRAC_1_SQRT_2)).abs() < 1e-9);
+
+        let ids = separate_body(&mut bodies, &mut properties, &mut groups, id).unwrap();
+        assert_eq!(ids, vec![BodyId(0), BodyId(1)]);
+        assert_eq!(bodies[0].brep.faces.len(), 6);
+        assert_eq!(bodies[1].brep.faces.len(), 10);
+        assert_eq!(bodies[1].brep.vertices.len(), 16);
+        assert_eq!(properties.get(BodyId(1)).unwrap().name, "Cube.002");
+        assert!((properties.get(BodyId(0)).unwrap().volume.unwrap() - 8.0).abs() < 1e-9);
+        assert_eq!(groups.group_of(BodyId(1)), frame);
+    }
+
+    #[test]
+    fn test_rejected_requests() {
+        let (mut bodies, mut properties, mut groups) = document();
+        assert_eq!(
+            combine_bodies(&mut bodies, &mut properties, &mut groups, &[BodyId(0), BodyId(0)]),
+            Err(CompoundError::TooFewBodies)
+        );
+        assert_eq!(
+            combine_bodies(&mut bodies, &mut properties, &mut groups, &[BodyId(0), BodyId(5)]),
+            Err(CompoundError::UnknownBody(BodyId(5)))
+        );
+        assert_eq!(
+            separate_body(&mut bodies, &mut properties, &mut groups, BodyId(0)),
+            Err(CompoundError::SingleShell(BodyId(0)))
+        );
+        assert_eq!(bodies.len(), 2);
+    }
+}
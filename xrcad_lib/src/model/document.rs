@@ -0,0 +1,192 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: model::document
+//!
+//! One handle on the open document. `CadDocument` groups the resources that
+//! make up a document (bodies, their properties, the feature history,
+//! sketches and workspace helpers) behind accessors, so systems take a single
+//! parameter instead of five and edit bodies in ways that keep them in step.
+//! The document owns its bodies: each one has a `BodyId` from the model's
+//! `IdGenerator`, recorded on its faces, which stays the same while other
+//! bodies come and go. Bodies are added with a `ModelCommand`, so the addition
+//! is logged and shared like any other edit. `DocumentChanged` events report
+//! which parts changed each frame.
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use crate::model::body::{Body, BodyId};
+use crate::model::brep_model::BrepModel;
+use crate::model::command::{execute, ModelCommand, ModelCommandError};
+use crate::model::feature_tree::FeatureTree;
+use crate::model::properties::{BodyProperties, BodyPropertiesCollection};
+use crate::sketch::sketch::Sketches;
+use crate::workspace::workspace::{Workspace, WorkspaceHelper};
+
+/// Part of the document that changed during a frame
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentChanged {
+    /// Body topology or geometry
+    Bodies,
+    /// Names, materials, visibility and other body properties
+    Properties,
+    Features,
+    Sketches,
+    Helpers,
+}
+
+/// The open document's resources
+#[derive(SystemParam)]
+pub struct CadDocument<'w> {
+    model: ResMut<'w, BrepModel>,
+    properties: ResMut<'w, BodyPropertiesCollection>,
+    features: ResMut<'w, FeatureTree>,
+    sketches: ResMut<'w, Sketches>,
+    workspace: ResMut<'w, Workspace>,
+    commands: EventWriter<'w, ModelCommand>,
+}
+
+impl CadDocument<'_> {
+    pub fn model(&self) -> &BrepModel {
+        &self.model
+    }
+    pub fn properties(&self) -> &BodyPropertiesCollection {
+        &self.properties
+    }
+    pub fn features(&self) -> &FeatureTree {
+        &self.features
+    }
+    pub fn sketches(&self) -> &Sketches {
+        &self.sketches
+    }
+    pub fn sketches_mut(&mut self) -> &mut Sketches {
+        &mut self.sketches
+    }
+    pub fn helpers(&self) -> &[WorkspaceHelper] {
+        &self.workspace.helpers
+    }
+    pub fn workspace_mut(&mut self) -> &mut Workspace {
+        &mut self.workspace
+    }
+
+    /// Number of bodies (closed shells) in the model
+    pub fn body_count(&self) -> usize {
+        self.model.shells().len()
+    }
+
+    pub fn body_ids(&self) -> Vec<BodyId> {
+        self.model.body_ids()
+    }
+
+    /// A copy of one body's topology
+    pub fn body(&self, id: BodyId) -> Option<Body> {
+        Some(Body::new(id, self.model.body(id)?))
+    }
+
+    pub fn body_properties(&self, id: BodyId) -> Option<&BodyProperties> {
+        self.properties.get(id)
+    }
+
+    /// Body with the given name
+    pub fn find_body(&self, name: &str) -> Option<BodyId> {
+        self.properties.iter().find(|(_, p)| p.name == name).map(|(id, _)| *id)
+    }
+
+    /// Send a body without history to the command executor, which gives it
+    /// the next id and registers it as "<base_name>.NNN"
+    pub fn add_body(&mut self, brep: &BrepModel, base_name: &str) {
+        self.commands.write(ModelCommand::AddBody { name: base_name.into(), body: brep.clone() });
+    }
+
+    /// Apply a modeling command directly, as the command executor does. Systems
+    /// reacting to user input should send `ModelCommand` events instead, so the
+    /// command is journaled and logged.
+    pub fn apply(&mut self, command: &ModelCommand) -> Result<(), ModelCommandError> {
+        execute(command, &mut self.model, &mut self.features, &mut self.properties)
+    }
+}
+
+/// Send a `DocumentChanged` event for each document resource changed since last run
+pub fn notify_document_changes(
+    model: Res<BrepModel>,
+    properties: Res<BodyPropertiesCollection>,
+    features: Res<FeatureTree>,
+    sketches: Res<Sketches>,
+    workspace: Res<Workspace>,
+    mut changes: EventWriter<DocumentChanged>,
+) {
+    let parts = [
+        (model.is_changed() && !model.is_added(), DocumentChanged::Bodies),
+        (properties.is_changed() && !properties.is_added(), DocumentChanged::Properties),
+        (features.is_changed() && !features.is_added(), DocumentChanged::Features),
+        (sketches.is_changed() && !sketches.is_added(), DocumentChanged::Sketches),
+        (workspace.is_changed() && !workspace.is_added(), DocumentChanged::Helpers),
+    ];
+    for (_, change) in parts.into_iter().filter(|(changed, _)| *changed) {
+        changes.write(change);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::cube;
+    use crate::model::command::execute_model_commands;
+
+    fn document_app() -> App {
+        let mut app = App::new();
+        app.init_resource::<BrepModel>()
+            .init_resource::<BodyPropertiesCollection>()
+            .init_resource::<FeatureTree>()
+            .init_resource::<Sketches>()
+            .init_resource::<Workspace>()
+            .add_event::<DocumentChanged>()
+            .add_event::<ModelCommand>()
+            .add_systems(Last, notify_document_changes);
+        app
+    }
+
+    fn changes(app: &mut App) -> Vec<DocumentChanged> {
+        app.world_mut().resource_mut::<Events<DocumentChanged>>().drain().collect()
+    }
+
+    #[test]
+    fn test_document_adds_and_finds_bodies() {
+        let mut app = document_app();
+        app.update();
+        assert!(changes(&mut app).is_empty());
+
+        let add = |mut doc: CadDocument, mut done: Local<bool>| {
+            if !std::mem::replace(&mut *done, true) {
+                doc.add_body(&cube(10.0), "Cube");
+                doc.add_body(&cube(2.0), "Cube");
+            }
+        };
+        app.add_systems(Update, (add, execute_model_commands).chain());
+        app.update();
+        let changed = changes(&mut app);
+        assert!(changed.contains(&DocumentChanged::Bodies) && changed.contains(&DocumentChanged::Properties));
+
+        app.add_systems(PostUpdate, |doc: CadDocument| {
+            assert_eq!(doc.body_ids(), [BodyId(0), BodyId(1)]);
+            assert_eq!(doc.find_body("Cube.002"), Some(BodyId(1)));
+            assert!((doc.body_properties(BodyId(0)).unwrap().volume.unwrap() - 1000.0).abs() < 1e-6);
+            assert_eq!(doc.body(BodyId(1)).unwrap().brep.faces.len(), 6);
+        });
+        app.update();
+        assert!(changes(&mut app).is_empty());
+    }
+
+    #[test]
+    fn test_document_reads_do_not_report_changes() {
+        let mut app = document_app();
+        app.update();
+        app.add_systems(Update, |doc: CadDocument| {
+            assert_eq!(doc.body_count(), 0);
+            assert!(!doc.helpers().is_empty());
+        });
+        app.update();
+        assert!(changes(&mut app).is_empty());
+    }
+}
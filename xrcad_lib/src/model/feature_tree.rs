@@ -0,0 +1,401 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: model::feature_tree
+//!
+//! Parametric feature history. Each feature records an operation and its
+//! parameters, referencing earlier features as inputs. Features are evaluated
+//! in order; editing a feature re-evaluates it and everything downstream.
+//! The tree also records which feature each body of the model shows, so later
+//! features build on a body's history rather than on a match of its shape.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use bevy::ecs::resource::Resource;
+use nalgebra::{Vector2, Vector3};
+use serde::{Deserialize, Serialize};
+
+use crate::model::body::BodyId;
+use crate::model::brep::operations::boolean::{boolean, BooleanError, BooleanOp};
+use crate::model::brep::operations::extrude::Extrude;
+use crate::model::brep::operations::imprint::Imprint;
+use crate::model::brep::operations::merge_faces::MergeFaces;
+use crate::model::brep::placement::PlacementFrame;
+use crate::model::brep::primitives::{cuboid, frustum};
+use crate::model::brep_model::BrepModel;
+use crate::sketch::regions::{find_regions, region_at};
+use crate::sketch::sketch::Sketch;
+use crate::sketch::solver::{solve, SolveError};
+
+/// Identifier of a feature within a tree
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct FeatureId(pub usize);
+
+/// Operation and parameters of a feature
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum FeatureKind {
+    /// Box centered at the origin, or with its base on `placement`
+    Box { size: Vector3<f64>, placement: Option<PlacementFrame> },
+    /// Faceted truncated cone along Z (a cylinder when both radii match), centered at
+    /// the origin or with its base on `placement`
+    Cylinder { bottom_radius: f64, top_radius: f64, height: f64, segments: usize, placement: Option<PlacementFrame> },
+    /// A sketch, solved when evaluated
+    Sketch(Box<Sketch>),
+    /// Extrude the sketch region containing `seed` (sketch coordinates)
+    Extrude { sketch: FeatureId, seed: Vector2<f64>, distance: f64 },
+    /// Move a body
+    Translate { body: FeatureId, offset: Vector3<f64> },
+    /// Imprint the faces of `tool` onto `target`
+    Imprint { target: FeatureId, tool: FeatureId },
+    /// Merge coplanar faces of a body
+    MergeFaces { body: FeatureId },
+    /// Union, subtract or intersect `tool` with `target`
+    Boolean { op: BooleanOp, target: FeatureId, tool: FeatureId },
+    /// A body without history, such as an imported or reshaped one, as it was
+    /// when a later feature first used it
+    Solid(Box<BrepModel>),
+}
+
+impl FeatureKind {
+    /// Features this one reads from
+    pub fn inputs(&self) -> Vec<FeatureId> {
+        match self {
+            FeatureKind::Box { .. } | FeatureKind::Cylinder { .. } | FeatureKind::Sketch(_) | FeatureKind::Solid(_) => Vec::new(),
+            FeatureKind::Extrude { sketch, .. } => vec![*sketch],
+            FeatureKind::Translate { body, .. } | FeatureKind::MergeFaces { body } => vec![*body],
+            FeatureKind::Imprint { target, tool } | FeatureKind::Boolean { target, tool, .. } => vec![*target, *tool],
+        }
+    }
+
+    /// Short operation name for the timeline
+    pub fn label(&self) -> &'static str {
+        match self {
+            FeatureKind::Box { .. } => "Box",
+            FeatureKind::Cylinder { .. } => "Cylinder",
+            FeatureKind::Sketch(_) => "Sketch",
+            FeatureKind::Extrude { .. } => "Extrude",
+            FeatureKind::Translate { .. } => "Translate",
+            FeatureKind::Imprint { .. } => "Imprint",
+            FeatureKind::MergeFaces { .. } => "Merge Faces",
+            FeatureKind::Boolean { op, .. } => op.label(),
+            FeatureKind::Solid(_) => "Body",
+        }
+    }
+}
+
+/// A recorded operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Feature {
+    pub id: FeatureId,
+    pub name: String,
+    pub kind: FeatureKind,
+    /// Suppressed features are skipped; bodies built on them fail to evaluate
+    pub suppressed: bool,
+}
+
+/// Result of evaluating a feature
+#[derive(Debug, Clone)]
+pub enum FeatureOutput {
+    Sketch(Sketch),
+    Body(BrepModel),
+}
+
+/// Why a feature could not be added or evaluated
+#[derive(Debug, Clone, PartialEq)]
+pub enum FeatureError {
+    UnknownFeature(FeatureId),
+    /// An input is not an earlier feature
+    ForwardReference { feature: FeatureId, input: FeatureId },
+    /// An input failed, is suppressed, or produced the wrong kind of output
+    InvalidInput { feature: FeatureId, input: FeatureId },
+    /// No closed sketch region contains the extrude seed point
+    NoRegion(FeatureId),
+    Sketch(FeatureId, SolveError),
+    Boolean(FeatureId, BooleanError),
+}
+
+impl fmt::Display for FeatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FeatureError::UnknownFeature(id) => write!(f, "no feature with id {}", id.0),
+            FeatureError::ForwardReference { feature, input } => {
+                write!(f, "feature {} references later feature {}", feature.0, input.0)
+            }
+            FeatureError::InvalidInput { feature, input } => {
+                write!(f, "feature {} has an unusable input {}", feature.0, input.0)
+            }
+            FeatureError::NoRegion(id) => write!(f, "feature {}: no closed region at the seed point", id.0),
+            FeatureError::Sketch(id, err) => write!(f, "feature {}: {}", id.0, err),
+            FeatureError::Boolean(id, err) => write!(f, "feature {}: {}", id.0, err),
+        }
+    }
+}
+
+impl std::error::Error for FeatureError {}
+
+/// Ordered, replayable history of features and their latest results
+#[derive(Resource, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct FeatureTree {
+    pub features: Vec<Feature>,
+    #[serde(skip)]
+    results: BTreeMap<FeatureId, Result<FeatureOutput, FeatureError>>,
+    /// Feature whose result each body of the model shows
+    #[serde(default)]
+    bodies: BTreeMap<BodyId, FeatureId>,
+}
+
+impl FeatureTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn feature(&self, id: FeatureId) -> Option<&Feature> {
+        self.features.iter().find(|f| f.id == id)
+    }
+
+    fn position(&self, id: FeatureId) -> Option<usize> {
+        self.features.iter().position(|f| f.id == id)
+    }
+
+    /// Latest result of a feature
+    pub fn result(&self, id: FeatureId) -> Option<&Result<FeatureOutput, FeatureError>> {
+        self.results.get(&id)
+    }
+
+    /// Body produced by a feature, if it evaluated to one
+    pub fn body(&self, id: FeatureId) -> Option<&BrepModel> {
+        match self.results.get(&id)? {
+            Ok(FeatureOutput::Body(body)) => Some(body),
+            _ => None,
+        }
+    }
+
+    /// Feature whose result `body` shows, if it has one
+    pub fn linked_feature(&self, body: BodyId) -> Option<FeatureId> {
+        self.bodies.get(&body).copied()
+    }
+
+    /// Record that `body` shows the result of `feature`
+    pub fn link(&mut self, body: BodyId, feature: FeatureId) {
+        self.bodies.insert(body, feature);
+    }
+
+    /// Forget the feature of a body that was removed or no longer has its shape
+    pub fn unlink(&mut self, body: BodyId) {
+        self.bodies.remove(&body);
+    }
+
+    /// Solved sketch produced by a feature, if it evaluated to one
+    pub fn sketch(&self, id: FeatureId) -> Option<&Sketch> {
+        match self.results.get(&id)? {
+            Ok(FeatureOutput::Sketch(sketch)) => Some(sketch),
+            _ => None,
+        }
+    }
+
+    /// Append a feature and evaluate it. Inputs must be earlier features.
+    pub fn add(&mut self, name: impl Into<String>, kind: FeatureKind) -> Result<FeatureId, FeatureError> {
+        let id = FeatureId(self.features.iter().map(|f| f.id.0 + 1).max().unwrap_or(0));
+        for input in kind.inputs() {
+            if self.feature(input).is_none() {
+                return Err(FeatureError::ForwardReference { feature: id, input });
+            }
+        }
+        self.features.push(Feature { id, name: name.into(), kind, suppressed: false });
+        self.replay_from(self.features.len() - 1);
+        Ok(id)
+    }
+
+    /// Features depending on `id`, directly or transitively, in evaluation order
+    pub fn dependents(&self, id: FeatureId) -> Vec<FeatureId> {
+        let mut affected = vec![id];
+        for f in &self.features {
+            if f.kind.inputs().iter().any(|i| affected.contains(i)) && !affected.contains(&f.id) {
+                affected.push(f.id);
+            }
+        }
+        affected.remove(0);
+        affected
+    }
+
+    /// Edit a feature's parameters and replay it and everything after it.
+    /// The edit is rejected if it would reference a later feature.
+    pub fn edit(&mut self, id: FeatureId, change: impl FnOnce(&mut FeatureKind)) -> Result<(), FeatureError> {
+        let index = self.position(id).ok_or(FeatureError::UnknownFeature(id))?;
+        let mut kind = self.features[index].kind.clone();
+        change(&mut kind);
+        for input in kind.inputs() {
+            if !self.features[..index].iter().any(|f| f.id == input) {
+                return Err(FeatureError::ForwardReference { feature: id, input });
+            }
+        }
+        self.features[index].kind = kind;
+        self.replay_from(index);
+        Ok(())
+    }
+
+    /// Suppress or unsuppress a feature and replay downstream
+    pub fn set_suppressed(&mut self, id: FeatureId, suppressed: bool) -> Result<(), FeatureError> {
+        let index = self.position(id).ok_or(FeatureError::UnknownFeature(id))?;
+        self.features[index].suppressed = suppressed;
+        self.replay_from(index);
+        Ok(())
+    }
+
+    /// Remove a feature; features that depended on it fail on replay until
+    /// re-pointed, and bodies showing it lose their link
+    pub fn remove(&mut self, id: FeatureId) -> Result<Feature, FeatureError> {
+        let index = self.position(id).ok_or(FeatureError::UnknownFeature(id))?;
+        let feature = self.features.remove(index);
+        self.results.remove(&id);
+        self.bodies.retain(|_, f| *f != id);
+        self.replay_from(index);
+        Ok(feature)
+    }
+
+    /// Re-evaluate every feature
+    pub fn rebuild(&mut self) {
+        self.replay_from(0);
+    }
+
+    /// Re-evaluate features from `index` to the end of the history
+    fn replay_from(&mut self, index: usize) {
+        for i in index..self.features.len() {
+            let feature = &self.features[i];
+            if feature.suppressed {
+                self.results.remove(&feature.id);
+                continue;
+            }
+            let result = self.evaluate(feature);
+            self.results.insert(feature.id, result);
+        }
+    }
+
+    fn input_body(&self, feature: FeatureId, input: FeatureId) -> Result<&BrepModel, FeatureError> {
+        self.body(input).ok_or(FeatureError::InvalidInput { feature, input })
+    }
+
+    fn evaluate(&self, feature: &Feature) -> Result<FeatureOutput, FeatureError> {
+        let id = feature.id;
+        let body = match &feature.kind {
+            FeatureKind::Box { size, placement } => placed(cuboid(*size), placement),
+            FeatureKind::Cylinder { bottom_radius, top_radius, height, segments, placement } => {
+                placed(frustum(*bottom_radius, *top_radius, *height, *segments), placement)
+            }
+            FeatureKind::Sketch(sketch) => {
+                let mut solved = sketch.as_ref().clone();
+                solve(&mut solved).map_err(|err| FeatureError::Sketch(id, err))?;
+                return Ok(FeatureOutput::Sketch(solved));
+            }
+            FeatureKind::Extrude { sketch, seed, distance } => {
+                let source = self.sketch(*sketch).ok_or(FeatureError::InvalidInput { feature: id, input: *sketch })?;
+                let regions = find_regions(source);
+                let region = region_at(&regions, seed).ok_or(FeatureError::NoRegion(id))?;
+                Extrude::new().apply(&source.plane, region, *distance)
+            }
+            FeatureKind::Translate { body, offset } => {
+                let mut moved = self.input_body(id, *body)?.clone();
+                for v in &mut moved.vertices {
+                    v.position += offset;
+                }
+                moved
+            }
+            FeatureKind::Imprint { target, tool } => {
+                let mut result = self.input_body(id, *target)?.clone();
+                Imprint::new().apply(&mut result, self.input_body(id, *tool)?);
+                result
+            }
+            FeatureKind::MergeFaces { body } => {
+                let mut result = self.input_body(id, *body)?.clone();
+                MergeFaces::new().apply(&mut result);
+                result
+            }
+            FeatureKind::Boolean { op, target, tool } => {
+                boolean(self.input_body(id, *target)?, self.input_body(id, *tool)?, *op)
+                    .map_err(|err| FeatureError::Boolean(id, err))?
+            }
+            FeatureKind::Solid(body) => body.as_ref().clone(),
+        };
+        Ok(FeatureOutput::Body(body))
+    }
+}
+
+/// A primitive moved onto its placement frame, if it has one
+fn placed(mut body: BrepModel, placement: &Option<PlacementFrame>) -> BrepModel {
+    if let Some(frame) = placement {
+        frame.place(&mut body);
+    }
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::measure::mass_properties::mass_properties;
+    use crate::model::brep::topology::plane::Plane;
+    use crate::sketch::dimension::DimensionKind;
+
+    fn square_sketch(size: f64) -> (Sketch, usize) {
+        let mut s = Sketch::new("Sketch.001", Plane::xy());
+        let p: Vec<usize> = [(0.0, 0.0), (size, 0.0), (size, size), (0.0, size)]
+            .iter()
+            .map(|(x, y)| s.add_point(Vector2::new(*x, *y)))
+            .collect();
+        for i in 0..4 {
+            s.add_line(p[i], p[(i + 1) % 4]);
+        }
+        let d = s.add_dimension(DimensionKind::Linear { a: p[0], b: p[1] }, None).unwrap();
+        if let Some(p0) = s.point_mut(p[0]) {
+            p0.fixed = true;
+        }
+        (s, d)
+    }
+
+    #[test]
+    fn test_edit_replays_downstream() {
+        let mut tree = FeatureTree::new();
+        let (sketch, dim) = square_sketch(2.0);
+        let s = tree.add("Sketch.001", FeatureKind::Sketch(Box::new(sketch))).unwrap();
+        let e = tree
+            .add("Extrude.001", FeatureKind::Extrude { sketch: s, seed: Vector2::new(0.1, 0.1), distance: 3.0 })
+            .unwrap();
+        let t = tree.add("Move.001", FeatureKind::Translate { body: e, offset: Vector3::new(0.0, 0.0, 10.0) }).unwrap();
+        assert_eq!(tree.dependents(s), vec![e, t]);
+        assert!((mass_properties(tree.body(t).unwrap()).volume - 12.0).abs() < 1e-9);
+
+        tree.edit(e, |k| {
+            if let FeatureKind::Extrude { distance, .. } = k {
+                *distance = 5.0;
+            }
+        })
+        .unwrap();
+        assert!((mass_properties(tree.body(t).unwrap()).volume - 20.0).abs() < 1e-9);
+
+        // Changing a driving dimension re-solves the sketch and resizes the solid
+        tree.edit(s, |k| {
+            if let FeatureKind::Sketch(sketch) = k {
+                sketch.dimension_mut(dim).unwrap().value = 4.0;
+            }
+        })
+        .unwrap();
+        let volume = mass_properties(tree.body(t).unwrap()).volume;
+        assert!(volume > 20.0, "volume {}", volume);
+    }
+
+    #[test]
+    fn test_forward_reference_and_suppression() {
+        let mut tree = FeatureTree::new();
+        let b = tree.add("Box.001", FeatureKind::Box { size: Vector3::new(1.0, 1.0, 1.0), placement: None }).unwrap();
+        assert!(matches!(
+            tree.add("Move", FeatureKind::Translate { body: FeatureId(9), offset: Vector3::zeros() }),
+            Err(FeatureError::ForwardReference { .. })
+        ));
+        let m = tree.add("Merge", FeatureKind::MergeFaces { body: b }).unwrap();
+        assert!(tree.body(m).is_some());
+        tree.set_suppressed(b, true).unwrap();
+        assert!(matches!(tree.result(m), Some(Err(FeatureError::InvalidInput { .. }))));
+        tree.set_suppressed(b, false).unwrap();
+        assert_eq!(tree.body(m).unwrap().faces.len(), 6);
+    }
+}
@@ -0,0 +1,251 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: model::groups
+//!
+//! User folders for organizing bodies in the model tree. Groups nest, and their
+//! visibility, color override and transform apply to everything inside them.
+//! This is purely organizational and independent of assemblies.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use bevy::ecs::resource::Resource;
+use nalgebra::Isometry3;
+use serde::{Deserialize, Serialize};
+
+use crate::model::body::BodyId;
+use crate::model::properties::BodyPropertiesCollection;
+
+/// Identifier of a group
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct GroupId(pub usize);
+
+/// A folder of bodies and sub-groups
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BodyGroup {
+    pub id: GroupId,
+    pub name: String,
+    pub parent: Option<GroupId>,
+    pub bodies: Vec<BodyId>,
+    pub visible: bool,
+    /// sRGB color applied to all contained bodies instead of their material color
+    pub color_override: Option<[f32; 3]>,
+    /// Placement relative to the parent group
+    pub transform: Isometry3<f64>,
+}
+
+/// Why a group edit was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GroupError {
+    UnknownGroup(GroupId),
+    /// The move would make a group its own ancestor
+    Cycle,
+}
+
+impl fmt::Display for GroupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GroupError::UnknownGroup(id) => write!(f, "no group with id {}", id.0),
+            GroupError::Cycle => write!(f, "a group cannot be moved into itself or its descendants"),
+        }
+    }
+}
+
+impl std::error::Error for GroupError {}
+
+/// All groups of a document
+#[derive(Resource, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct BodyGroups {
+    groups: BTreeMap<GroupId, BodyGroup>,
+    next_id: usize,
+}
+
+impl BodyGroups {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, id: GroupId) -> Option<&BodyGroup> {
+        self.groups.get(&id)
+    }
+    pub fn get_mut(&mut self, id: GroupId) -> Option<&mut BodyGroup> {
+        self.groups.get_mut(&id)
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &BodyGroup> {
+        self.groups.values()
+    }
+
+    fn check(&self, id: Option<GroupId>) -> Result<(), GroupError> {
+        match id {
+            Some(g) if !self.groups.contains_key(&g) => Err(GroupError::UnknownGroup(g)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Create an empty group under `parent` (or at the top level)
+    pub fn create_group(&mut self, name: impl Into<String>, parent: Option<GroupId>) -> Result<GroupId, GroupError> {
+        self.check(parent)?;
+        let id = GroupId(self.next_id);
+        self.next_id += 1;
+        self.groups.insert(id, BodyGroup {
+            id,
+            name: name.into(),
+            parent,
+            bodies: Vec::new(),
+            visible: true,
+            color_override: None,
+            transform: Isometry3::identity(),
+        });
+        Ok(id)
+    }
+
+    /// Delete a group, moving its bodies and sub-groups up to its parent
+    pub fn remove_group(&mut self, id: GroupId) -> Result<BodyGroup, GroupError> {
+        let group = self.groups.remove(&id).ok_or(GroupError::UnknownGroup(id))?;
+        for child in self.groups.values_mut().filter(|g| g.parent == Some(id)) {
+            child.parent = group.parent;
+            child.transform = group.transform * child.transform;
+        }
+        if let Some(parent) = group.parent.and_then(|p| self.groups.get_mut(&p)) {
+            parent.bodies.extend(group.bodies.iter().copied());
+        }
+        Ok(group)
+    }
+
+    /// Group directly containing a body, if any
+    pub fn group_of(&self, body: BodyId) -> Option<GroupId> {
+        self.groups.values().find(|g| g.bodies.contains(&body)).map(|g| g.id)
+    }
+
+    /// Sub-groups directly under `parent` (`None` for top-level groups)
+    pub fn children(&self, parent: Option<GroupId>) -> Vec<GroupId> {
+        self.groups.values().filter(|g| g.parent == parent).map(|g| g.id).collect()
+    }
+
+    /// The group and all its ancestors, innermost first
+    pub fn ancestry(&self, id: GroupId) -> Vec<GroupId> {
+        let mut out = Vec::new();
+        let mut current = Some(id);
+        while let Some(g) = current.and_then(|c| self.groups.get(&c)) {
+            out.push(g.id);
+            current = g.parent;
+        }
+        out
+    }
+
+    /// Move a body into a group (or out of all groups with `None`)
+    pub fn move_body(&mut self, body: BodyId, group: Option<GroupId>) -> Result<(), GroupError> {
+        self.check(group)?;
+        for g in self.groups.values_mut() {
+            g.bodies.retain(|b| *b != body);
+        }
+        if let Some(g) = group.and_then(|g| self.groups.get_mut(&g)) {
+            g.bodies.push(body);
+        }
+        Ok(())
+    }
+
+    /// Re-parent a group, refusing moves that would create a cycle
+    pub fn move_group(&mut self, id: GroupId, parent: Option<GroupId>) -> Result<(), GroupError> {
+        self.check(Some(id))?;
+        self.check(parent)?;
+        if parent.is_some_and(|p| self.ancestry(p).contains(&id)) {
+            return Err(GroupError::Cycle);
+        }
+        if let Some(g) = self.groups.get_mut(&id) {
+            g.parent = parent;
+        }
+        Ok(())
+    }
+
+    /// Forget a deleted body
+    pub fn remove_body(&mut self, body: BodyId) {
+        for g in self.groups.values_mut() {
+            g.bodies.retain(|b| *b != body);
+        }
+    }
+
+    /// World placement of a group, composed through its ancestors
+    pub fn world_transform(&self, id: GroupId) -> Isometry3<f64> {
+        self.ancestry(id)
+            .iter()
+            .rev()
+            .filter_map(|g| self.groups.get(g))
+            .fold(Isometry3::identity(), |acc, g| acc * g.transform)
+    }
+
+    /// World placement applied to a body by the groups containing it
+    pub fn body_transform(&self, body: BodyId) -> Isometry3<f64> {
+        self.group_of(body).map(|g| self.world_transform(g)).unwrap_or_else(Isometry3::identity)
+    }
+
+    /// A body is shown only if it and every enclosing group are visible
+    pub fn is_body_visible(&self, body: BodyId, properties: &BodyPropertiesCollection) -> bool {
+        let own = properties.get(body).is_none_or(|p| p.visible);
+        own && self
+            .group_of(body)
+            .is_none_or(|g| self.ancestry(g).iter().all(|a| self.groups.get(a).is_some_and(|g| g.visible)))
+    }
+
+    /// Display color of a body: the innermost group override, else its material color
+    pub fn body_color(&self, body: BodyId, properties: &BodyPropertiesCollection) -> [f32; 3] {
+        self.group_of(body)
+            .and_then(|g| self.ancestry(g).iter().find_map(|a| self.groups.get(a)?.color_override))
+            .or_else(|| properties.get(body).map(|p| p.material.base_color))
+            .unwrap_or([0.8, 0.8, 0.8])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::{Translation3, UnitQuaternion};
+
+    #[test]
+    fn test_nesting_and_cycles() {
+        let mut groups = BodyGroups::new();
+        let a = groups.create_group("Frame", None).unwrap();
+        let b = groups.create_group("Brackets", Some(a)).unwrap();
+        assert_eq!(groups.children(Some(a)), vec![b]);
+        assert_eq!(groups.ancestry(b), vec![b, a]);
+        assert_eq!(groups.move_group(a, Some(b)), Err(GroupError::Cycle));
+        assert_eq!(groups.create_group("x", Some(GroupId(99))), Err(GroupError::UnknownGroup(GroupId(99))));
+    }
+
+    #[test]
+    fn test_body_membership_and_remove_group() {
+        let mut groups = BodyGroups::new();
+        let a = groups.create_group("Frame", None).unwrap();
+        let b = groups.create_group("Brackets", Some(a)).unwrap();
+        groups.move_body(BodyId(1), Some(b)).unwrap();
+        groups.move_body(BodyId(1), Some(a)).unwrap();
+        assert_eq!(groups.group_of(BodyId(1)), Some(a));
+        groups.move_body(BodyId(2), Some(b)).unwrap();
+        groups.remove_group(b).unwrap();
+        assert_eq!(groups.group_of(BodyId(2)), Some(a));
+    }
+
+    #[test]
+    fn test_visibility_color_and_transform() {
+        let mut props = BodyPropertiesCollection::new();
+        props.register(BodyId(1), "Cube");
+        let mut groups = BodyGroups::new();
+        let a = groups.create_group("Frame", None).unwrap();
+        let b = groups.create_group("Brackets", Some(a)).unwrap();
+        groups.move_body(BodyId(1), Some(b)).unwrap();
+
+        assert!(groups.is_body_visible(BodyId(1), &props));
+        groups.get_mut(a).unwrap().visible = false;
+        assert!(!groups.is_body_visible(BodyId(1), &props));
+
+        assert_eq!(groups.body_color(BodyId(1), &props), [0.8, 0.8, 0.8]);
+        groups.get_mut(a).unwrap().color_override = Some([1.0, 0.0, 0.0]);
+        assert_eq!(groups.body_color(BodyId(1), &props), [1.0, 0.0, 0.0]);
+
+        groups.get_mut(a).unwrap().transform = Isometry3::from_parts(Translation3::new(10.0, 0.0, 0.0), UnitQuaternion::identity());
+        groups.get_mut(b).unwrap().transform = Isometry3::from_parts(Translation3::new(0.0, 5.0, 0.0), UnitQuaternion::identity());
+        let t = groups.body_transform(BodyId(1));
+        assert_eq!(t.translation.vector, nalgebra::Vector3::new(10.0, 5.0, 0.0));
+    }
+}
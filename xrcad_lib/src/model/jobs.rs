@@ -0,0 +1,610 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: model::jobs
+//!
+//! Long modeling operations off the main thread. Booleans, STEP imports and
+//! exports (which tessellate every face) can take seconds on large bodies, so
+//! they run as jobs on Bevy's async compute pool while the UI and the XR view
+//! keep drawing. A job works on a copy of what it needs and its result is
+//! applied when it finishes; a command whose document was edited in the
+//! meantime, as the `ModelRevision` counter tells, is dropped rather than
+//! overwrite the edit. Jobs report progress with `JobProgressed` events and
+//! end with `JobFinished`; `CancelJob`, or the Cancel button of the progress
+//! panel, stops one. STEP imports report and check for cancellation after each
+//! solid and add what they read with `ModelCommand::AddBody`; commands and
+//! exports run in one piece, so they only report when they start and finish
+//! and a cancel drops their result. Meshes for the viewport are tessellated on
+//! the same pool by `render::display_mode`. In a shared session a host passes
+//! the commands its jobs applied on to the `CommandRelay`, and a client sends
+//! its commands to the host instead of running them.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+use bevy::tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task};
+
+#[cfg(feature = "render")]
+use crate::interaction::state::UiPanel;
+use crate::interaction::selection::Selection;
+use crate::io::gltf::save_gltf;
+use crate::io::step::save_step;
+use crate::io::step::StepError;
+use crate::io::step_import::{load_step_with, StepImport};
+use crate::io::three_mf::save_3mf;
+use crate::model::assembly::Assembly;
+use crate::model::brep_model::BrepModel;
+use crate::model::command::{
+    execute, forget_removed_bodies, CommandLog, CommandRecord, CommandRelay, ModelCommand, ModelCommandError, ModelRevision,
+};
+use crate::model::feature_tree::FeatureTree;
+use crate::model::groups::BodyGroups;
+use crate::model::material::Material;
+use crate::model::metadata::DocumentMetadata;
+use crate::model::properties::BodyPropertiesCollection;
+use crate::telemetry::crash::journal;
+use crate::telemetry::usage::UsageStats;
+
+/// Identifies a job in progress and finish events
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct JobId(pub u64);
+
+/// A long operation to run in the background
+#[derive(Debug, Clone)]
+pub enum Job {
+    /// Model command run on a copy of the document, such as a boolean
+    Command(ModelCommand),
+    /// Read the solids of a STEP file and add them as bodies
+    ImportStep(PathBuf),
+    /// Tessellate the bodies and write them as STEP, glTF, GLB or 3MF, by extension
+    Export(PathBuf),
+}
+
+impl Job {
+    /// Job name for the journal, usage statistics and the progress panel
+    pub fn label(&self) -> &'static str {
+        match self {
+            Job::Command(command) => command.label(),
+            Job::ImportStep(_) => "import_step",
+            Job::Export(_) => "export",
+        }
+    }
+}
+
+/// Why a job ended without its result
+#[derive(Debug, Clone, PartialEq)]
+pub enum JobError {
+    Cancelled,
+    /// The document was edited while a command ran on a copy of it
+    Stale,
+    /// The command was refused
+    Command(ModelCommandError),
+    Failed(String),
+}
+
+impl fmt::Display for JobError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JobError::Cancelled => write!(f, "cancelled"),
+            JobError::Stale => write!(f, "the document changed while it ran"),
+            JobError::Command(err) => write!(f, "{}", err),
+            JobError::Failed(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+/// Progress and cancellation shared between a running job and the main thread
+#[derive(Debug, Default)]
+pub struct JobProgress {
+    /// Fraction done as `f32` bits
+    fraction: AtomicU32,
+    cancelled: AtomicBool,
+}
+
+impl JobProgress {
+    pub fn fraction(&self) -> f32 {
+        f32::from_bits(self.fraction.load(Ordering::Relaxed))
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Record the fraction done; jobs call this between stages and stop on `Err`
+    pub fn step(&self, fraction: f32) -> Result<(), JobError> {
+        if self.is_cancelled() {
+            return Err(JobError::Cancelled);
+        }
+        self.fraction.store(fraction.clamp(0.0, 1.0).to_bits(), Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+/// A job and the copies it works on, moved to the task
+enum Work {
+    Command { command: ModelCommand, model: BrepModel, features: FeatureTree, properties: BodyPropertiesCollection },
+    ImportStep(PathBuf),
+    Export { path: PathBuf, metadata: DocumentMetadata, bodies: Vec<(String, BrepModel, Material)> },
+}
+
+/// What a finished task hands back to the main thread
+enum JobOutput {
+    Document { model: BrepModel, features: FeatureTree, properties: BodyPropertiesCollection },
+    Imported(StepImport),
+    Exported(usize),
+}
+
+/// Write bodies in the format of the path's extension
+fn export_bodies(path: &Path, metadata: &DocumentMetadata, bodies: &[(String, BrepModel, Material)]) -> Result<(), String> {
+    let ext = path.extension().map(|e| e.to_string_lossy().to_ascii_lowercase()).unwrap_or_default();
+    let with_materials: Vec<(&str, &BrepModel, &Material)> = bodies.iter().map(|(n, b, m)| (n.as_str(), b, m)).collect();
+    match ext.as_str() {
+        "step" | "stp" => save_step(path, metadata, &bodies.iter().map(|(n, b, _)| (n.as_str(), b)).collect::<Vec<_>>()).map_err(|e| e.to_string()),
+        "gltf" | "glb" => save_gltf(path, metadata, &with_materials).map_err(|e| e.to_string()),
+        "3mf" => save_3mf(path, metadata, &with_materials).map_err(|e| e.to_string()),
+        _ => Err(format!("unsupported export format {:?}", ext)),
+    }
+}
+
+impl Work {
+    fn run(self, progress: &JobProgress) -> Result<JobOutput, JobError> {
+        progress.step(0.0)?;
+        let output = match self {
+            Work::Command { command, mut model, mut features, mut properties } => {
+                execute(&command, &mut model, &mut features, &mut properties).map_err(JobError::Command)?;
+                JobOutput::Document { model, features, properties }
+            }
+            Work::ImportStep(path) => match load_step_with(&path, |fraction| progress.step(fraction).is_ok()) {
+                Ok(import) => JobOutput::Imported(import),
+                Err(StepError::Cancelled) => return Err(JobError::Cancelled),
+                Err(err) => return Err(JobError::Failed(err.to_string())),
+            },
+            Work::Export { path, metadata, bodies } => {
+                export_bodies(&path, &metadata, &bodies).map_err(JobError::Failed)?;
+                JobOutput::Exported(bodies.len())
+            }
+        };
+        progress.step(1.0)?;
+        Ok(output)
+    }
+}
+
+/// A job on the task pool
+struct RunningJob {
+    id: JobId,
+    job: Job,
+    progress: Arc<JobProgress>,
+    task: Task<Result<JobOutput, JobError>>,
+    /// `ModelRevision` when the job started
+    revision: u64,
+    started: Instant,
+    /// Fraction last sent in a `JobProgressed` event
+    reported: f32,
+}
+
+/// Queued and running jobs
+#[derive(Resource, Default)]
+pub struct BackgroundJobs {
+    next: u64,
+    queued: Vec<(JobId, Job)>,
+    running: Vec<RunningJob>,
+}
+
+impl BackgroundJobs {
+    /// Queue a job; it starts on the next update
+    pub fn request(&mut self, job: Job) -> JobId {
+        let id = JobId(self.next);
+        self.next += 1;
+        self.queued.push((id, job));
+        id
+    }
+
+    /// Cancel a queued or running job; false if there is no such job
+    pub fn cancel(&mut self, id: JobId) -> bool {
+        let queued = self.queued.len();
+        self.queued.retain(|(queued, _)| *queued != id);
+        if self.queued.len() != queued {
+            return true;
+        }
+        match self.running.iter().find(|j| j.id == id) {
+            Some(job) => {
+                job.progress.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Drop queued jobs and cancel running ones, as when the document is replaced
+    pub fn cancel_all(&mut self) {
+        self.queued.clear();
+        for job in &self.running {
+            job.progress.cancel();
+        }
+    }
+
+    pub fn is_busy(&self) -> bool {
+        !self.queued.is_empty() || !self.running.is_empty()
+    }
+
+    /// Running jobs, oldest first, with their name and fraction done
+    pub fn running(&self) -> impl Iterator<Item = (JobId, &'static str, f32)> + '_ {
+        self.running.iter().map(|j| (j.id, j.job.label(), j.progress.fraction()))
+    }
+}
+
+/// Request to run a job in the background
+#[derive(Event, Debug, Clone)]
+pub struct StartJob(pub Job);
+
+/// Request to stop a job; its result is discarded
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CancelJob(pub JobId);
+
+/// A running job got further
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct JobProgressed {
+    pub id: JobId,
+    pub label: &'static str,
+    pub fraction: f32,
+}
+
+/// A job ended, with its result applied unless it failed
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct JobFinished {
+    pub id: JobId,
+    pub label: &'static str,
+    pub outcome: Result<(), JobError>,
+}
+
+/// Queue requested jobs and cancel the ones asked to stop
+pub fn apply_job_requests(mut starts: EventReader<StartJob>, mut cancels: EventReader<CancelJob>, mut jobs: ResMut<BackgroundJobs>) {
+    for StartJob(job) in starts.read() {
+        jobs.request(job.clone());
+    }
+    for CancelJob(id) in cancels.read() {
+        if !jobs.cancel(*id) {
+            warn!("No job {} to cancel", id.0);
+        }
+    }
+}
+
+/// Start queued jobs on the async compute pool, each with copies of the document parts it needs
+pub fn start_jobs(
+    mut jobs: ResMut<BackgroundJobs>,
+    model: Res<BrepModel>,
+    features: Res<FeatureTree>,
+    properties: Res<BodyPropertiesCollection>,
+    metadata: Option<Res<DocumentMetadata>>,
+    revision: Res<ModelRevision>,
+    mut relay: Option<ResMut<CommandRelay>>,
+) {
+    if jobs.queued.is_empty() {
+        return;
+    }
+    let revision = revision.0;
+    for (id, job) in std::mem::take(&mut jobs.queued) {
+        // A client's command runs when the host sends it back, like its other commands
+        if let (Job::Command(command), Some(relay)) = (&job, relay.as_mut().filter(|r| r.hold_local)) {
+            journal(format!("relay {} {:?}", job.label(), command));
+            relay.outgoing.push(command.clone());
+            continue;
+        }
+        journal(format!("start {} {:?}", job.label(), job));
+        let work = match &job {
+            Job::Command(command) => Work::Command {
+                command: command.clone(),
+                model: model.clone(),
+                features: features.clone(),
+                properties: properties.clone(),
+            },
+            Job::ImportStep(path) => Work::ImportStep(path.clone()),
+            Job::Export(path) => {
+                let bodies = model
+                    .bodies()
+                    .into_iter()
+                    .map(|(id, shell)| {
+                        let props = properties.get(id);
+                        let name = props.map_or_else(|| format!("Body {}", id.0 + 1), |p| p.name.clone());
+                        (name, model.extract_faces(&shell), props.map(|p| p.material.clone()).unwrap_or_default())
+                    })
+                    .collect();
+                Work::Export { path: path.clone(), metadata: metadata.as_deref().cloned().unwrap_or_default(), bodies }
+            }
+        };
+        let progress = Arc::new(JobProgress::default());
+        let shared = progress.clone();
+        let task = AsyncComputeTaskPool::get().spawn(async move { work.run(&shared) });
+        jobs.running.push(RunningJob { id, job, progress, task, revision, started: Instant::now(), reported: 0.0 });
+    }
+}
+
+/// Hand a finished job's output to the document; imported solids go to the
+/// command executor
+fn apply_output(
+    job: &Job,
+    output: JobOutput,
+    started: u64,
+    revision: &mut ModelRevision,
+    (model, features, properties): (&mut ResMut<BrepModel>, &mut ResMut<FeatureTree>, &mut ResMut<BodyPropertiesCollection>),
+    commands: &mut EventWriter<ModelCommand>,
+) -> Result<(), JobError> {
+    match output {
+        JobOutput::Document { model: edited, features: history, properties: props } => {
+            if started != revision.0 {
+                return Err(JobError::Stale);
+            }
+            **model = edited;
+            **features = history;
+            **properties = props;
+            revision.0 += 1;
+        }
+        JobOutput::Imported(StepImport { solids, report }) => {
+            for (name, body) in solids {
+                commands.write(ModelCommand::AddBody { name, body });
+            }
+            if let Job::ImportStep(path) = job {
+                info!("Imported {}: {}", path.display(), report);
+            }
+        }
+        JobOutput::Exported(count) => {
+            if let Job::Export(path) = job {
+                info!("Exported {} body(ies) to {}", count, path.display());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Report progress of running jobs and apply the ones that finished or were cancelled
+pub fn poll_jobs(
+    (mut jobs, mut revision): (ResMut<BackgroundJobs>, ResMut<ModelRevision>),
+    (mut model, mut features, mut properties): (ResMut<BrepModel>, ResMut<FeatureTree>, ResMut<BodyPropertiesCollection>),
+    (mut selection, mut log): (Option<ResMut<Selection>>, Option<ResMut<CommandLog>>),
+    (mut progressed, mut finished): (EventWriter<JobProgressed>, EventWriter<JobFinished>),
+    mut commands: EventWriter<ModelCommand>,
+    mut usage: Option<ResMut<UsageStats>>,
+    mut relay: Option<ResMut<CommandRelay>>,
+    (mut groups, mut assembly): (Option<ResMut<BodyGroups>>, Option<ResMut<Assembly>>),
+) {
+    if jobs.running.is_empty() {
+        return;
+    }
+    for mut running in std::mem::take(&mut jobs.running) {
+        let label = running.job.label();
+        let result = if running.progress.is_cancelled() {
+            // An import sees the flag at its next solid and ends; other jobs run
+            // to the end on the pool and their result is dropped with the task
+            Err(JobError::Cancelled)
+        } else if let Some(result) = block_on(future::poll_once(&mut running.task)) {
+            result
+        } else {
+            let fraction = running.progress.fraction();
+            if fraction != running.reported {
+                running.reported = fraction;
+                progressed.write(JobProgressed { id: running.id, label, fraction });
+            }
+            jobs.running.push(running);
+            continue;
+        };
+        let outcome = result.and_then(|output| {
+            let document = (&mut model, &mut features, &mut properties);
+            apply_output(&running.job, output, running.revision, &mut revision, document, &mut commands)
+        });
+        if let (Ok(()), Job::Command(command)) = (&outcome, &running.job) {
+            forget_removed_bodies(command, groups.as_deref_mut(), assembly.as_deref_mut());
+        }
+        match (&outcome, &running.job) {
+            (Ok(()), Job::Command(command)) if command.replaces_topology() => {
+                if let Some(selection) = selection.as_mut() {
+                    selection.clear();
+                }
+            }
+            (Err(err), _) => warn!("{} stopped: {}", label, err),
+            _ => {}
+        }
+        // A host passes applied commands on to the session, in the order they ran here
+        if let (Ok(()), Job::Command(command), Some(relay)) = (&outcome, &running.job, relay.as_mut().filter(|r| r.sharing)) {
+            relay.outgoing.push(command.clone());
+        }
+        // Commands that ran are logged like the ones the executor runs
+        if let (Job::Command(command), Some(log)) = (&running.job, log.as_mut()) {
+            match &outcome {
+                Ok(()) => log.push(CommandRecord { command: command.clone(), error: None }),
+                Err(JobError::Command(err)) => log.push(CommandRecord { command: command.clone(), error: Some(err.clone()) }),
+                Err(_) => {}
+            }
+        }
+        journal(format!("finish {} {:?}", label, outcome));
+        if let Some(usage) = usage.as_mut() {
+            usage.record(label, running.started.elapsed());
+        }
+        finished.write(JobFinished { id: running.id, label, outcome });
+    }
+}
+
+/// Text of the progress panel
+#[cfg(feature = "render")]
+#[derive(Component, Debug)]
+pub struct JobPanelText;
+
+/// Filled part of the progress bar
+#[cfg(feature = "render")]
+#[derive(Component, Debug)]
+pub struct JobProgressBar;
+
+/// Button cancelling the oldest running job
+#[cfg(feature = "render")]
+#[derive(Component, Debug)]
+pub struct CancelJobButton;
+
+/// Spawn the progress panel (bottom middle, shown while jobs run)
+#[cfg(feature = "render")]
+pub fn spawn_job_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(8.0),
+                left: Val::Percent(40.0),
+                width: Val::Px(260.0),
+                padding: UiRect::all(Val::Px(6.0)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.05, 0.05, 0.08, 0.9)),
+            UiPanel("jobs"),
+        ))
+        .with_children(|panel| {
+            panel.spawn((Text::new(""), TextFont { font_size: 13.0, ..default() }, JobPanelText));
+            panel
+                .spawn((Node { width: Val::Percent(100.0), height: Val::Px(6.0), ..default() }, BackgroundColor(Color::srgb(0.2, 0.2, 0.25))))
+                .with_child((Node { width: Val::Percent(0.0), height: Val::Percent(100.0), ..default() }, BackgroundColor(Color::srgb(0.3, 0.6, 1.0)), JobProgressBar));
+            panel
+                .spawn((Button, Node { padding: UiRect::axes(Val::Px(6.0), Val::Px(2.0)), align_self: AlignSelf::End, ..default() }, BackgroundColor(Color::srgb(0.25, 0.25, 0.3)), CancelJobButton))
+                .with_child(Text::new("Cancel"));
+        });
+}
+
+/// Show the oldest running job's name and progress while any job runs
+#[cfg(feature = "render")]
+pub fn job_panel_system(
+    jobs: Res<BackgroundJobs>,
+    mut panels: Query<&mut Node, (With<UiPanel>, Without<JobProgressBar>)>,
+    mut bars: Query<&mut Node, With<JobProgressBar>>,
+    mut texts: Query<(&mut Text, &ChildOf), With<JobPanelText>>,
+) {
+    let Ok((mut text, parent)) = texts.single_mut() else { return };
+    let Ok(mut panel) = panels.get_mut(parent.parent()) else { return };
+    let Some((_, label, fraction)) = jobs.running().next() else {
+        if panel.display != Display::None {
+            panel.display = Display::None;
+        }
+        return;
+    };
+    panel.display = Display::Flex;
+    let others = jobs.running().count() - 1;
+    text.0 = match others {
+        0 => format!("{} {:.0}%", label, fraction * 100.0),
+        n => format!("{} {:.0}% (+{} more)", label, fraction * 100.0, n),
+    };
+    if let Ok(mut bar) = bars.single_mut() {
+        bar.width = Val::Percent(fraction * 100.0);
+    }
+}
+
+/// Cancel the job shown in the panel when its Cancel button is pressed
+#[cfg(feature = "render")]
+pub fn cancel_job_button(pressed: Query<&Interaction, (Changed<Interaction>, With<CancelJobButton>)>, jobs: Res<BackgroundJobs>, mut cancels: EventWriter<CancelJob>) {
+    if pressed.iter().any(|i| *i == Interaction::Pressed) {
+        if let Some((id, _, _)) = jobs.running().next() {
+            cancels.write(CancelJob(id));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::app::TaskPoolPlugin;
+    use bevy::ecs::event::EventCursor;
+    use nalgebra::Vector3;
+
+    use crate::model::command::execute_model_commands;
+    use crate::model::feature_tree::FeatureKind;
+
+    fn jobs_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(TaskPoolPlugin::default())
+            .init_resource::<BrepModel>()
+            .init_resource::<FeatureTree>()
+            .init_resource::<BodyPropertiesCollection>()
+            .init_resource::<BackgroundJobs>()
+            .init_resource::<ModelRevision>()
+            .add_event::<ModelCommand>()
+            .add_event::<StartJob>()
+            .add_event::<CancelJob>()
+            .add_event::<JobProgressed>()
+            .add_event::<JobFinished>()
+            .add_systems(Update, (apply_job_requests, start_jobs).chain());
+        app
+    }
+
+    /// Update until a job finishes and return how it ended
+    fn finish(app: &mut App) -> JobFinished {
+        let mut cursor = EventCursor::<JobFinished>::default();
+        for _ in 0..1000 {
+            app.update();
+            if let Some(finished) = cursor.read(app.world().resource::<Events<JobFinished>>()).next() {
+                return finished.clone();
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        panic!("job did not finish");
+    }
+
+    fn create_box() -> Job {
+        Job::Command(ModelCommand::CreatePrimitive(FeatureKind::Box { size: Vector3::repeat(10.0), placement: None }))
+    }
+
+    #[test]
+    fn test_command_job_applies_its_result() {
+        let mut app = jobs_app();
+        app.add_systems(Update, poll_jobs.after(start_jobs));
+        app.world_mut().send_event(StartJob(create_box()));
+        let finished = finish(&mut app);
+        assert_eq!((finished.label, finished.outcome), ("create_primitive", Ok(())));
+        assert_eq!(app.world().resource::<BrepModel>().shells().len(), 1);
+        assert_eq!(app.world().resource::<FeatureTree>().features.len(), 1);
+        assert!(!app.world().resource::<BackgroundJobs>().is_busy());
+    }
+
+    #[test]
+    fn test_edited_document_drops_the_result() {
+        let mut app = jobs_app();
+        app.world_mut().send_event(StartJob(create_box()));
+        app.update();
+        // A command runs while the job does
+        let Job::Command(command) = create_box() else { unreachable!() };
+        app.world_mut().send_event(command);
+        app.add_systems(Update, (execute_model_commands, poll_jobs.after(start_jobs)).chain());
+        assert_eq!(finish(&mut app).outcome, Err(JobError::Stale));
+        assert_eq!(app.world().resource::<BrepModel>().shells().len(), 1);
+    }
+
+    #[test]
+    fn test_selecting_a_vertex_is_not_an_edit() {
+        let mut app = jobs_app();
+        app.world_mut().resource_mut::<BrepModel>().add_vertex(Vector3::new(1.0, 2.0, 3.0));
+        app.world_mut().send_event(StartJob(create_box()));
+        app.update();
+        app.world_mut().resource_mut::<BrepModel>().selected_vertex = Some(0);
+        app.add_systems(Update, poll_jobs.after(start_jobs));
+        assert_eq!(finish(&mut app).outcome, Ok(()));
+        assert_eq!(app.world().resource::<BrepModel>().shells().len(), 1);
+    }
+
+    #[test]
+    fn test_cancelled_job_stops() {
+        let progress = JobProgress::default();
+        assert_eq!(progress.step(0.5), Ok(()));
+        assert_eq!(progress.fraction(), 0.5);
+        progress.cancel();
+        assert_eq!(progress.step(0.75), Err(JobError::Cancelled));
+
+        let mut jobs = BackgroundJobs::default();
+        let id = jobs.request(create_box());
+        assert!(jobs.cancel(id));
+        assert!(!jobs.is_busy() && !jobs.cancel(id));
+    }
+}
@@ -0,0 +1,373 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: model::layers
+//!
+//! Named layers that bodies are placed on through `BodyProperties::layer`.
+//! Each layer has a display color, and can be hidden (its bodies are not drawn
+//! or picked) or locked (its bodies are drawn but cannot be selected). Bodies
+//! on no layer are always shown and selectable.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use bevy::ecs::system::SystemParam;
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::input::keyboard::KeyBindings;
+use crate::interaction::selection::{Selection, SelectionItem};
+use crate::interaction::state::ActiveBody;
+use crate::model::body::BodyId;
+use crate::model::brep_model::BrepModel;
+use crate::model::properties::BodyPropertiesCollection;
+use crate::telemetry::crash::journal;
+use crate::telemetry::usage::UsageStats;
+
+/// Colors handed out to new layers in turn (sRGB)
+pub const LAYER_COLORS: [[f32; 3]; 6] =
+    [[0.9, 0.3, 0.3], [0.3, 0.8, 0.4], [0.3, 0.5, 0.95], [0.95, 0.8, 0.2], [0.8, 0.4, 0.9], [0.3, 0.85, 0.85]];
+
+/// A named layer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Layer {
+    pub name: String,
+    /// sRGB display color of the layer's bodies
+    pub color: [f32; 3],
+    pub visible: bool,
+    /// Bodies on a locked layer are shown but cannot be selected
+    pub locked: bool,
+}
+
+/// Why a layer edit was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayerError {
+    UnknownLayer(String),
+    EmptyName,
+    NameTaken(String),
+}
+
+impl fmt::Display for LayerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayerError::UnknownLayer(name) => write!(f, "no layer named \"{}\"", name),
+            LayerError::EmptyName => write!(f, "layer name cannot be empty"),
+            LayerError::NameTaken(name) => write!(f, "a layer named \"{}\" already exists", name),
+        }
+    }
+}
+
+impl std::error::Error for LayerError {}
+
+/// Layers of a document, in display order
+#[derive(Resource, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct LayerManager {
+    layers: Vec<Layer>,
+}
+
+impl LayerManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Layer> {
+        self.layers.iter().find(|l| l.name == name)
+    }
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Layer> {
+        self.layers.iter_mut().find(|l| l.name == name)
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &Layer> {
+        self.layers.iter()
+    }
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Next free "Layer N" name
+    pub fn generate_name(&self) -> String {
+        (1..).map(|n| format!("Layer {}", n)).find(|name| self.get(name).is_none()).unwrap_or_default()
+    }
+
+    /// Add a visible, unlocked layer with the next palette color
+    pub fn create(&mut self, name: &str) -> Result<&mut Layer, LayerError> {
+        self.check_name(name)?;
+        let color = LAYER_COLORS[self.layers.len() % LAYER_COLORS.len()];
+        self.layers.push(Layer { name: name.to_string(), color, visible: true, locked: false });
+        Ok(self.layers.last_mut().expect("layer just pushed"))
+    }
+
+    /// Rename a layer, moving its bodies along with it
+    pub fn rename(&mut self, from: &str, to: &str, properties: &mut BodyPropertiesCollection) -> Result<(), LayerError> {
+        if from == to {
+            return self.get(from).map(|_| ()).ok_or_else(|| LayerError::UnknownLayer(from.to_string()));
+        }
+        self.check_name(to)?;
+        let layer = self.get_mut(from).ok_or_else(|| LayerError::UnknownLayer(from.to_string()))?;
+        layer.name = to.to_string();
+        for (_, p) in properties.iter_mut().filter(|(_, p)| p.layer.as_deref() == Some(from)) {
+            p.layer = Some(to.to_string());
+        }
+        Ok(())
+    }
+
+    /// Delete a layer; its bodies are left on no layer
+    pub fn delete(&mut self, name: &str, properties: &mut BodyPropertiesCollection) -> Result<Layer, LayerError> {
+        let index = self.layers.iter().position(|l| l.name == name).ok_or_else(|| LayerError::UnknownLayer(name.to_string()))?;
+        for (_, p) in properties.iter_mut().filter(|(_, p)| p.layer.as_deref() == Some(name)) {
+            p.layer = None;
+        }
+        Ok(self.layers.remove(index))
+    }
+
+    /// Put bodies on a layer, or on no layer with `None`
+    pub fn assign(&self, bodies: &[BodyId], layer: Option<&str>, properties: &mut BodyPropertiesCollection) -> Result<(), LayerError> {
+        if let Some(name) = layer.filter(|name| self.get(name).is_none()) {
+            return Err(LayerError::UnknownLayer(name.to_string()));
+        }
+        for body in bodies {
+            if properties.get(*body).is_none() {
+                properties.register(*body, "Body");
+            }
+            if let Some(p) = properties.get_mut(*body) {
+                p.layer = layer.map(str::to_string);
+            }
+        }
+        Ok(())
+    }
+
+    /// Layer a body is on, if it exists
+    pub fn layer_of(&self, body: BodyId, properties: &BodyPropertiesCollection) -> Option<&Layer> {
+        properties.get(body)?.layer.as_deref().and_then(|name| self.get(name))
+    }
+
+    pub fn is_body_visible(&self, body: BodyId, properties: &BodyPropertiesCollection) -> bool {
+        self.layer_of(body, properties).is_none_or(|l| l.visible)
+    }
+
+    /// A body can be selected when its layer is shown and unlocked
+    pub fn is_body_selectable(&self, body: BodyId, properties: &BodyPropertiesCollection) -> bool {
+        self.layer_of(body, properties).is_none_or(|l| l.visible && !l.locked)
+    }
+
+    /// Display color of a body's layer, if it is on one
+    pub fn body_color(&self, body: BodyId, properties: &BodyPropertiesCollection) -> Option<[f32; 3]> {
+        self.layer_of(body, properties).map(|l| l.color)
+    }
+
+    fn check_name(&self, name: &str) -> Result<(), LayerError> {
+        if name.trim().is_empty() {
+            return Err(LayerError::EmptyName);
+        }
+        if self.get(name).is_some() {
+            return Err(LayerError::NameTaken(name.to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Body a selection item belongs to, given each vertex's body
+pub fn item_body(model: &BrepModel, vertex_bodies: &HashMap<usize, BodyId>, item: &SelectionItem) -> Option<BodyId> {
+    let vertex = match *item {
+        SelectionItem::Body(body) => return Some(body),
+        SelectionItem::Vertex(id) => id,
+        SelectionItem::Edge(id) => model.edges.iter().find(|e| e.id == id)?.vertices.0,
+        SelectionItem::Face(id) => *model.face(id).and_then(|f| model.face_loops(f).first().map(|l| model.loop_vertex_ids(l)))?.first()?,
+    };
+    vertex_bodies.get(&vertex).copied()
+}
+
+/// Request to add a layer
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct CreateLayer(pub String);
+
+/// Request to rename a layer
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct RenameLayer {
+    pub from: String,
+    pub to: String,
+}
+
+/// Request to delete a layer
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct DeleteLayer(pub String);
+
+/// Request to change a layer's display color
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct SetLayerColor {
+    pub layer: String,
+    pub color: [f32; 3],
+}
+
+/// Request to show or hide a layer
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct SetLayerVisible {
+    pub layer: String,
+    pub visible: bool,
+}
+
+/// Request to lock or unlock a layer
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct SetLayerLocked {
+    pub layer: String,
+    pub locked: bool,
+}
+
+/// Request to move bodies to a layer (`None` for no layer)
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct AssignLayer {
+    pub bodies: Vec<BodyId>,
+    pub layer: Option<String>,
+}
+
+/// The new_layer binding (F11) moves the selected bodies (or the active body) to a new layer
+pub fn layer_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    layers: Res<LayerManager>,
+    selection: Res<Selection>,
+    active: Res<ActiveBody>,
+    mut creates: EventWriter<CreateLayer>,
+    mut assigns: EventWriter<AssignLayer>,
+) {
+    if !bindings.just_pressed("new_layer", &keys) {
+        return;
+    }
+    let mut bodies = selection.bodies();
+    if bodies.is_empty() {
+        bodies.extend(active.0);
+    }
+    let name = layers.generate_name();
+    creates.write(CreateLayer(name.clone()));
+    if !bodies.is_empty() {
+        assigns.write(AssignLayer { bodies, layer: Some(name) });
+    }
+}
+
+/// Layer edit requests, grouped to keep the system's parameter list short
+#[derive(SystemParam)]
+pub struct LayerRequests<'w, 's> {
+    creates: EventReader<'w, 's, CreateLayer>,
+    renames: EventReader<'w, 's, RenameLayer>,
+    deletes: EventReader<'w, 's, DeleteLayer>,
+    colors: EventReader<'w, 's, SetLayerColor>,
+    visibility: EventReader<'w, 's, SetLayerVisible>,
+    locks: EventReader<'w, 's, SetLayerLocked>,
+    assigns: EventReader<'w, 's, AssignLayer>,
+}
+
+/// Apply layer edits, in the order create, rename, assign, style, delete
+pub fn apply_layer_requests(
+    mut requests: LayerRequests,
+    mut layers: ResMut<LayerManager>,
+    mut properties: ResMut<BodyPropertiesCollection>,
+    mut selection: ResMut<Selection>,
+    mut usage: Option<ResMut<UsageStats>>,
+) {
+    let start = Instant::now();
+    let mut changed = false;
+    let mut report = |what: String, result: Result<(), LayerError>| {
+        journal(what);
+        changed = true;
+        if let Err(e) = result {
+            warn!("Layer edit failed: {}", e);
+        }
+    };
+    for CreateLayer(name) in requests.creates.read() {
+        report(format!("layer_create {}", name), layers.create(name).map(|_| ()));
+    }
+    for RenameLayer { from, to } in requests.renames.read() {
+        report(format!("layer_rename {} -> {}", from, to), layers.rename(from, to, &mut properties));
+    }
+    for AssignLayer { bodies, layer } in requests.assigns.read() {
+        report(format!("layer_assign {:?} {:?}", bodies, layer), layers.assign(bodies, layer.as_deref(), &mut properties));
+    }
+    for SetLayerColor { layer, color } in requests.colors.read() {
+        let result = layers.get_mut(layer).map(|l| l.color = *color).ok_or_else(|| LayerError::UnknownLayer(layer.clone()));
+        report(format!("layer_color {} {:?}", layer, color), result);
+    }
+    for SetLayerVisible { layer, visible } in requests.visibility.read() {
+        let result = layers.get_mut(layer).map(|l| l.visible = *visible).ok_or_else(|| LayerError::UnknownLayer(layer.clone()));
+        report(format!("layer_visible {} {}", layer, visible), result);
+    }
+    for SetLayerLocked { layer, locked } in requests.locks.read() {
+        let result = layers.get_mut(layer).map(|l| l.locked = *locked).ok_or_else(|| LayerError::UnknownLayer(layer.clone()));
+        report(format!("layer_locked {} {}", layer, locked), result);
+    }
+    for DeleteLayer(name) in requests.deletes.read() {
+        report(format!("layer_delete {}", name), layers.delete(name, &mut properties).map(|_| ()));
+    }
+    if !changed {
+        return;
+    }
+    // Bodies that just became hidden or locked drop out of the selection
+    let locked: Vec<BodyId> = selection.bodies().into_iter().filter(|b| !layers.is_body_selectable(*b, &properties)).collect();
+    for body in locked {
+        selection.remove(SelectionItem::Body(body));
+    }
+    if let Some(usage) = usage.as_mut() {
+        usage.record("layers", start.elapsed());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layer_lifecycle() {
+        let mut props = BodyPropertiesCollection::new();
+        props.register(BodyId(0), "Cube");
+        let mut layers = LayerManager::new();
+        assert_eq!(layers.generate_name(), "Layer 1");
+        layers.create("Layer 1").unwrap();
+        assert_eq!(layers.create("Layer 1").unwrap_err(), LayerError::NameTaken("Layer 1".into()));
+        assert_eq!(layers.create(" ").unwrap_err(), LayerError::EmptyName);
+        assert_eq!(layers.generate_name(), "Layer 2");
+
+        layers.assign(&[BodyId(0), BodyId(1)], Some("Layer 1"), &mut props).unwrap();
+        assert_eq!(props.get(BodyId(1)).unwrap().layer.as_deref(), Some("Layer 1"));
+        assert!(layers.assign(&[BodyId(0)], Some("Nope"), &mut props).is_err());
+        assert_eq!(layers.body_color(BodyId(0), &props), Some(LAYER_COLORS[0]));
+
+        layers.rename("Layer 1", "Brackets", &mut props).unwrap();
+        assert_eq!(props.get(BodyId(0)).unwrap().layer.as_deref(), Some("Brackets"));
+
+        layers.get_mut("Brackets").unwrap().locked = true;
+        assert!(layers.is_body_visible(BodyId(0), &props) && !layers.is_body_selectable(BodyId(0), &props));
+        layers.get_mut("Brackets").unwrap().visible = false;
+        assert!(!layers.is_body_visible(BodyId(0), &props));
+        // Bodies on no layer are unaffected
+        assert!(layers.is_body_selectable(BodyId(2), &props));
+
+        layers.delete("Brackets", &mut props).unwrap();
+        assert!(layers.is_empty() && props.get(BodyId(0)).unwrap().layer.is_none());
+    }
+
+    #[test]
+    fn test_locking_drops_selection() {
+        let mut app = App::new();
+        let mut selection = Selection::default();
+        selection.add(SelectionItem::Body(BodyId(0)));
+        app.init_resource::<LayerManager>()
+            .init_resource::<BodyPropertiesCollection>()
+            .insert_resource(selection)
+            .add_event::<CreateLayer>()
+            .add_event::<RenameLayer>()
+            .add_event::<DeleteLayer>()
+            .add_event::<SetLayerColor>()
+            .add_event::<SetLayerVisible>()
+            .add_event::<SetLayerLocked>()
+            .add_event::<AssignLayer>()
+            .add_systems(Update, apply_layer_requests);
+        app.world_mut().send_event(CreateLayer("Fixtures".into()));
+        app.world_mut().send_event(AssignLayer { bodies: vec![BodyId(0)], layer: Some("Fixtures".into()) });
+        app.world_mut().send_event(SetLayerLocked { layer: "Fixtures".into(), locked: true });
+        app.update();
+        assert!(app.world().resource::<LayerManager>().get("Fixtures").unwrap().locked);
+        assert!(app.world().resource::<Selection>().is_empty());
+    }
+}
@@ -0,0 +1,57 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: model::material
+
+use serde::{Deserialize, Serialize};
+
+/// PBR appearance of a body, independent of any renderer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Material {
+    pub name: String,
+    /// Linear-agnostic sRGB base color
+    pub base_color: [f32; 3],
+    pub alpha: f32,
+    pub metallic: f32,
+    pub roughness: f32,
+    pub reflectance: f32,
+    /// Texture asset paths; they repeat across faces (see `render::materials`)
+    pub diffuse_texture: Option<String>,
+    pub normal_texture: Option<String>,
+    pub roughness_texture: Option<String>,
+    pub metallic_texture: Option<String>,
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Self {
+            name: "Default".to_string(),
+            base_color: [0.8, 0.8, 0.8],
+            alpha: 1.0,
+            metallic: 0.0,
+            roughness: 0.5,
+            reflectance: 0.5,
+            diffuse_texture: None,
+            normal_texture: None,
+            roughness_texture: None,
+            metallic_texture: None,
+        }
+    }
+}
+
+impl Material {
+    pub fn new(name: impl Into<String>, base_color: [f32; 3]) -> Self {
+        Self { name: name.into(), base_color, ..Default::default() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_material_new() {
+        let m = Material::new("Steel", [0.5, 0.5, 0.55]);
+        assert_eq!(m.name, "Steel");
+        assert_eq!(m.alpha, 1.0);
+    }
+}
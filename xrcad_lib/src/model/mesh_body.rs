@@ -0,0 +1,216 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: model::mesh_body
+//!
+//! Mesh bodies: imported triangle meshes (STL, OBJ) kept as reference geometry
+//! next to the BREP bodies. Coincident corners are welded on import so shells
+//! can be found; a closed mesh can be rebuilt as BREP topology on request.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use nalgebra::{Point3, Vector3};
+use serde::{Deserialize, Serialize};
+
+use crate::model::brep::placement::RayHit;
+use crate::model::brep_model::BrepModel;
+use crate::model::tolerance::Tolerance;
+#[cfg(feature = "render")]
+use crate::{color::MAGENTA, model::brep_model::na_vec3_to_bevy};
+
+/// Indexed triangle mesh with welded vertices
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MeshBody {
+    pub name: String,
+    pub positions: Vec<Vector3<f64>>,
+    /// Counter-clockwise (outward) vertex indices of each triangle
+    pub triangles: Vec<[usize; 3]>,
+    pub visible: bool,
+}
+
+impl MeshBody {
+    /// Build from a triangle soup, welding corners closer than the linear tolerance
+    /// and dropping triangles that collapse
+    pub fn from_triangles(name: impl Into<String>, soup: &[[Vector3<f64>; 3]], tol: &Tolerance) -> Self {
+        let cell = tol.linear.max(f64::EPSILON);
+        let mut index: HashMap<[i64; 3], usize> = HashMap::new();
+        let mut positions = Vec::new();
+        let mut weld = |p: &Vector3<f64>| -> usize {
+            let key = [(p.x / cell).round() as i64, (p.y / cell).round() as i64, (p.z / cell).round() as i64];
+            *index.entry(key).or_insert_with(|| {
+                positions.push(*p);
+                positions.len() - 1
+            })
+        };
+        let triangles = soup
+            .iter()
+            .map(|[a, b, c]| [weld(a), weld(b), weld(c)])
+            .filter(|[a, b, c]| a != b && b != c && c != a)
+            .collect();
+        Self { name: name.into(), positions, triangles, visible: true }
+    }
+
+    /// Edges (as sorted index pairs) with the number of triangles using each
+    fn edge_use(&self) -> HashMap<(usize, usize), usize> {
+        let mut uses = HashMap::new();
+        for t in &self.triangles {
+            for i in 0..3 {
+                let (a, b) = (t[i], t[(i + 1) % 3]);
+                *uses.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+            }
+        }
+        uses
+    }
+
+    /// True if every edge is shared by exactly two triangles
+    pub fn is_closed(&self) -> bool {
+        !self.triangles.is_empty() && self.edge_use().values().all(|n| *n == 2)
+    }
+
+    /// Triangle indices grouped into connected shells (triangles sharing an edge)
+    pub fn shells(&self) -> Vec<Vec<usize>> {
+        let mut by_edge: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+        for (i, t) in self.triangles.iter().enumerate() {
+            for k in 0..3 {
+                let (a, b) = (t[k], t[(k + 1) % 3]);
+                by_edge.entry((a.min(b), a.max(b))).or_default().push(i);
+            }
+        }
+        let mut shell_of = vec![None; self.triangles.len()];
+        let mut shells = Vec::new();
+        for start in 0..self.triangles.len() {
+            if shell_of[start].is_some() {
+                continue;
+            }
+            shell_of[start] = Some(shells.len());
+            let mut members = Vec::new();
+            let mut stack = vec![start];
+            while let Some(i) = stack.pop() {
+                members.push(i);
+                let t = self.triangles[i];
+                for k in 0..3 {
+                    let (a, b) = (t[k], t[(k + 1) % 3]);
+                    for &j in &by_edge[&(a.min(b), a.max(b))] {
+                        if shell_of[j].is_none() {
+                            shell_of[j] = Some(shells.len());
+                            stack.push(j);
+                        }
+                    }
+                }
+            }
+            members.sort_unstable();
+            shells.push(members);
+        }
+        shells
+    }
+
+    /// Rebuild each shell as BREP topology with one planar face per triangle
+    pub fn reconstruct_shells(&self) -> Vec<BrepModel> {
+        self.shells()
+            .into_iter()
+            .map(|shell| {
+                let mut model = BrepModel::new();
+                for i in shell {
+                    let [a, b, c] = self.triangles[i];
+                    model.add_face(&[self.positions[a], self.positions[b], self.positions[c]]);
+                }
+                model
+            })
+            .collect()
+    }
+
+    /// Nearest triangle hit by a ray (Möller–Trumbore); `face` is the triangle index
+    pub fn raycast(&self, origin: &Point3<f64>, dir: &Vector3<f64>) -> Option<RayHit> {
+        let dir = dir.normalize();
+        self.triangles
+            .iter()
+            .enumerate()
+            .filter_map(|(i, [a, b, c])| {
+                let (a, b, c) = (self.positions[*a], self.positions[*b], self.positions[*c]);
+                let (e1, e2) = (b - a, c - a);
+                let p = dir.cross(&e2);
+                let det = e1.dot(&p);
+                if det.abs() < f64::EPSILON {
+                    return None;
+                }
+                let s = origin.coords - a;
+                let u = s.dot(&p) / det;
+                let q = s.cross(&e1);
+                let v = dir.dot(&q) / det;
+                let t = e2.dot(&q) / det;
+                if u < 0.0 || v < 0.0 || u + v > 1.0 || t < 0.0 {
+                    return None;
+                }
+                Some(RayHit { face: Some(i), point: origin + dir * t, normal: e1.cross(&e2).normalize(), distance: t })
+            })
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+    }
+}
+
+/// All mesh bodies of the document
+#[derive(Resource, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct MeshBodies {
+    pub meshes: Vec<MeshBody>,
+}
+
+impl MeshBodies {
+    pub fn add(&mut self, mesh: MeshBody) -> usize {
+        self.meshes.push(mesh);
+        self.meshes.len() - 1
+    }
+
+    /// Draw visible meshes as wireframes, each edge once
+    #[cfg(feature = "render")]
+    pub fn render(mut gizmos: Gizmos, meshes: Res<MeshBodies>) {
+        let color = MAGENTA.with_alpha(0.6);
+        for mesh in meshes.meshes.iter().filter(|m| m.visible) {
+            for (a, b) in mesh.edge_use().into_keys() {
+                gizmos.line(na_vec3_to_bevy(&mesh.positions[a]), na_vec3_to_bevy(&mesh.positions[b]), color);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Triangle soup of an axis-aligned cube with outward winding
+    fn cube_soup(size: f64, offset: Vector3<f64>) -> Vec<[Vector3<f64>; 3]> {
+        let h = size * 0.5;
+        let c = |x: f64, y: f64, z: f64| Vector3::new(x * h, y * h, z * h) + offset;
+        let quads = [
+            [c(-1., -1., -1.), c(-1., 1., -1.), c(1., 1., -1.), c(1., -1., -1.)],
+            [c(-1., -1., 1.), c(1., -1., 1.), c(1., 1., 1.), c(-1., 1., 1.)],
+            [c(-1., -1., -1.), c(1., -1., -1.), c(1., -1., 1.), c(-1., -1., 1.)],
+            [c(1., -1., -1.), c(1., 1., -1.), c(1., 1., 1.), c(1., -1., 1.)],
+            [c(1., 1., -1.), c(-1., 1., -1.), c(-1., 1., 1.), c(1., 1., 1.)],
+            [c(-1., 1., -1.), c(-1., -1., -1.), c(-1., -1., 1.), c(-1., 1., 1.)],
+        ];
+        quads.iter().flat_map(|[a, b, c, d]| [[*a, *b, *c], [*a, *c, *d]]).collect()
+    }
+
+    #[test]
+    fn test_weld_shells_and_reconstruct() {
+        let mut soup = cube_soup(2.0, Vector3::zeros());
+        soup.extend(cube_soup(1.0, Vector3::new(10.0, 0.0, 0.0)));
+        let mesh = MeshBody::from_triangles("Parts", &soup, &Tolerance::default());
+        assert_eq!(mesh.positions.len(), 16);
+        assert!(mesh.is_closed());
+        assert_eq!(mesh.shells().len(), 2);
+        let breps = mesh.reconstruct_shells();
+        assert_eq!(breps.len(), 2);
+        assert_eq!((breps[0].vertices.len(), breps[0].edges.len(), breps[0].faces.len()), (8, 18, 12));
+        assert!(crate::model::brep::validate::validate_solid(&breps[0]).is_empty());
+    }
+
+    #[test]
+    fn test_raycast() {
+        let mesh = MeshBody::from_triangles("Cube", &cube_soup(2.0, Vector3::zeros()), &Tolerance::default());
+        let hit = mesh.raycast(&Point3::new(0.2, 0.3, 10.0), &-Vector3::z()).unwrap();
+        assert!((hit.distance - 9.0).abs() < 1e-9);
+        assert!((hit.normal - Vector3::z()).norm() < 1e-9);
+        assert!(mesh.raycast(&Point3::new(5.0, 0.0, 10.0), &-Vector3::z()).is_none());
+    }
+}
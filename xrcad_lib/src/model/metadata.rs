@@ -0,0 +1,74 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: model::metadata
+//!
+//! Document-level metadata (title, author, revision, ...), used by drawing title blocks.
+
+use std::collections::BTreeMap;
+
+use bevy::ecs::resource::Resource;
+use serde::{Deserialize, Serialize};
+
+/// Descriptive metadata of a document
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocumentMetadata {
+    pub title: String,
+    pub author: String,
+    pub revision: String,
+    /// Drawing scale as displayed, e.g. "1:1"
+    pub scale: String,
+    pub date: String,
+    /// Additional user-defined fields
+    pub custom: BTreeMap<String, String>,
+}
+
+impl Default for DocumentMetadata {
+    fn default() -> Self {
+        Self {
+            title: "Untitled".to_string(),
+            author: String::new(),
+            revision: "A".to_string(),
+            scale: "1:1".to_string(),
+            date: String::new(),
+            custom: BTreeMap::new(),
+        }
+    }
+}
+
+impl DocumentMetadata {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self { title: title.into(), ..Default::default() }
+    }
+
+    /// Look up a field by name; built-in fields take precedence over custom ones
+    pub fn field(&self, name: &str) -> Option<&str> {
+        match name {
+            "title" => Some(&self.title),
+            "author" => Some(&self.author),
+            "revision" => Some(&self.revision),
+            "scale" => Some(&self.scale),
+            "date" => Some(&self.date),
+            _ => self.custom.get(name).map(String::as_str),
+        }
+    }
+
+    pub fn set_custom(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        self.custom.insert(name.into(), value.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_lookup() {
+        let mut m = DocumentMetadata::new("Bracket");
+        m.set_custom("material", "6061-T6");
+        assert_eq!(m.field("title"), Some("Bracket"));
+        assert_eq!(m.field("scale"), Some("1:1"));
+        assert_eq!(m.field("material"), Some("6061-T6"));
+        assert_eq!(m.field("missing"), None);
+    }
+}
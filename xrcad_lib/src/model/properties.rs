@@ -0,0 +1,288 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: model::properties
+//!
+//! Per-body metadata (name, visibility, ghosting, surface analysis, layer,
+//! material, mass properties), kept alongside the topology and keyed by
+//! `BodyId`. Names are unique within a collection.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use bevy::ecs::resource::Resource;
+use nalgebra::{Matrix3, Point3};
+use serde::{Deserialize, Serialize};
+
+use crate::model::body::BodyId;
+use crate::model::material::Material;
+
+/// How a body is drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DisplayMode {
+    #[default]
+    Wireframe,
+    Shaded,
+    ShadedWithEdges,
+    HiddenLine,
+}
+
+impl DisplayMode {
+    pub const ALL: [DisplayMode; 4] = [DisplayMode::Wireframe, DisplayMode::Shaded, DisplayMode::ShadedWithEdges, DisplayMode::HiddenLine];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DisplayMode::Wireframe => "Wireframe",
+            DisplayMode::Shaded => "Shaded",
+            DisplayMode::ShadedWithEdges => "Shaded with edges",
+            DisplayMode::HiddenLine => "Hidden line",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|m| *m == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// Whether edges and vertex handles are drawn
+    pub fn shows_edges(&self) -> bool {
+        *self != DisplayMode::Shaded
+    }
+
+    /// Whether faces are drawn, shaded or as hidden-line fill
+    pub fn shows_faces(&self) -> bool {
+        *self != DisplayMode::Wireframe
+    }
+}
+
+/// Surface analysis drawn on a body's faces in place of its material
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AnalysisMode {
+    /// Draft angle to the pull direction
+    Draft,
+    Curvature,
+    Zebra,
+}
+
+impl AnalysisMode {
+    pub const ALL: [AnalysisMode; 3] = [AnalysisMode::Draft, AnalysisMode::Curvature, AnalysisMode::Zebra];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AnalysisMode::Draft => "Draft",
+            AnalysisMode::Curvature => "Curvature",
+            AnalysisMode::Zebra => "Zebra",
+        }
+    }
+}
+
+/// Metadata of a single body
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BodyProperties {
+    pub name: String,
+    pub visible: bool,
+    pub layer: Option<String>,
+    pub material: Material,
+    pub volume: Option<f64>,
+    pub surface_area: Option<f64>,
+    pub center_of_mass: Option<Point3<f64>>,
+    /// Inertia tensor about the center of mass (unit density)
+    pub inertia_tensor: Option<Matrix3<f64>>,
+    /// Display mode override; `None` follows the global mode
+    #[serde(default)]
+    pub display: Option<DisplayMode>,
+    /// Drawn see-through and skipped by picking, for reference bodies
+    #[serde(default)]
+    pub ghosted: bool,
+    /// Surface analysis shown on the faces, if any
+    #[serde(default)]
+    pub analysis: Option<AnalysisMode>,
+}
+
+impl BodyProperties {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            visible: true,
+            layer: None,
+            material: Material::default(),
+            volume: None,
+            surface_area: None,
+            center_of_mass: None,
+            inertia_tensor: None,
+            display: None,
+            ghosted: false,
+            analysis: None,
+        }
+    }
+
+    pub fn set_volume(&mut self, volume: f64) {
+        self.volume = Some(volume);
+    }
+    pub fn set_surface_area(&mut self, area: f64) {
+        self.surface_area = Some(area);
+    }
+    pub fn set_center_of_mass(&mut self, com: Point3<f64>) {
+        self.center_of_mass = Some(com);
+    }
+    pub fn set_inertia_tensor(&mut self, inertia: Matrix3<f64>) {
+        self.inertia_tensor = Some(inertia);
+    }
+}
+
+/// Why a rename was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RenameError {
+    UnknownBody(BodyId),
+    EmptyName,
+    NameTaken(String),
+}
+
+impl fmt::Display for RenameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenameError::UnknownBody(id) => write!(f, "no body with id {}", id.0),
+            RenameError::EmptyName => write!(f, "body name cannot be empty"),
+            RenameError::NameTaken(name) => write!(f, "a body named \"{}\" already exists", name),
+        }
+    }
+}
+
+impl std::error::Error for RenameError {}
+
+/// Name without a trailing ".NNN" counter, used as the base for generated names
+pub fn base_name(name: &str) -> &str {
+    match name.rsplit_once('.') {
+        Some((base, counter)) if !base.is_empty() && counter.parse::<u32>().is_ok() => base,
+        _ => name,
+    }
+}
+
+/// Properties of all bodies in a document
+#[derive(Resource, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct BodyPropertiesCollection {
+    properties: BTreeMap<BodyId, BodyProperties>,
+}
+
+impl BodyPropertiesCollection {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, id: BodyId) -> Option<&BodyProperties> {
+        self.properties.get(&id)
+    }
+    pub fn get_mut(&mut self, id: BodyId) -> Option<&mut BodyProperties> {
+        self.properties.get_mut(&id)
+    }
+    /// Whether the body is drawn as a see-through reference
+    pub fn is_ghosted(&self, id: BodyId) -> bool {
+        self.get(id).is_some_and(|p| p.ghosted)
+    }
+    /// Surface analysis shown on the body, if any
+    pub fn analysis_of(&self, id: BodyId) -> Option<AnalysisMode> {
+        self.get(id).and_then(|p| p.analysis)
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (&BodyId, &BodyProperties)> {
+        self.properties.iter()
+    }
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&BodyId, &mut BodyProperties)> {
+        self.properties.iter_mut()
+    }
+    pub fn len(&self) -> usize {
+        self.properties.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.properties.is_empty()
+    }
+    pub fn remove(&mut self, id: BodyId) -> Option<BodyProperties> {
+        self.properties.remove(&id)
+    }
+
+    /// Insert properties as given; the caller is responsible for name uniqueness
+    pub fn insert(&mut self, id: BodyId, props: BodyProperties) {
+        self.properties.insert(id, props);
+    }
+
+    /// Register a newly created body under an auto-generated name such as "Cube.001"
+    pub fn register(&mut self, id: BodyId, base_name: &str) -> &mut BodyProperties {
+        let name = self.generate_name(base_name);
+        self.properties.entry(id).or_insert_with(|| BodyProperties::new(name))
+    }
+
+    /// True if another body (not `except`) already uses `name`
+    pub fn is_name_taken(&self, name: &str, except: Option<BodyId>) -> bool {
+        self.properties.iter().any(|(id, p)| Some(*id) != except && p.name == name)
+    }
+
+    /// Next free "<base>.NNN" name
+    pub fn generate_name(&self, base_name: &str) -> String {
+        let prefix = format!("{}.", base_name);
+        let next = self
+            .properties
+            .values()
+            .filter_map(|p| p.name.strip_prefix(&prefix)?.parse::<u32>().ok())
+            .max()
+            .unwrap_or(0)
+            + 1;
+        format!("{}{:03}", prefix, next)
+    }
+
+    /// Rename a body, rejecting empty names and names used by other bodies
+    pub fn rename(&mut self, id: BodyId, name: &str) -> Result<(), RenameError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(RenameError::EmptyName);
+        }
+        if !self.properties.contains_key(&id) {
+            return Err(RenameError::UnknownBody(id));
+        }
+        if self.is_name_taken(name, Some(id)) {
+            return Err(RenameError::NameTaken(name.to_string()));
+        }
+        if let Some(p) = self.properties.get_mut(&id) {
+            p.name = name.to_string();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_names() {
+        let mut props = BodyPropertiesCollection::new();
+        assert_eq!(props.register(BodyId(0), "Cube").name, "Cube.001");
+        assert_eq!(props.register(BodyId(1), "Cube").name, "Cube.002");
+        assert_eq!(props.register(BodyId(2), "Extrude").name, "Extrude.001");
+        props.rename(BodyId(0), "Cube.010").unwrap();
+        assert_eq!(props.generate_name("Cube"), "Cube.011");
+    }
+
+    #[test]
+    fn test_rename_uniqueness() {
+        let mut props = BodyPropertiesCollection::new();
+        props.register(BodyId(0), "Cube");
+        props.register(BodyId(1), "Cube");
+        assert_eq!(props.rename(BodyId(1), "Cube.001"), Err(RenameError::NameTaken("Cube.001".into())));
+        assert_eq!(props.rename(BodyId(1), "  "), Err(RenameError::EmptyName));
+        assert_eq!(props.rename(BodyId(7), "Bracket"), Err(RenameError::UnknownBody(BodyId(7))));
+        assert_eq!(props.rename(BodyId(1), " Bracket "), Ok(()));
+        assert_eq!(props.get(BodyId(1)).unwrap().name, "Bracket");
+        // Renaming a body to its own name is fine
+        assert_eq!(props.rename(BodyId(1), "Bracket"), Ok(()));
+    }
+
+    #[test]
+    fn test_mass_property_setters() {
+        let mut p = BodyProperties::new("Cube.001");
+        p.set_volume(8.0);
+        p.set_surface_area(24.0);
+        p.set_center_of_mass(Point3::origin());
+        assert_eq!(p.volume, Some(8.0));
+        assert_eq!(p.surface_area, Some(24.0));
+        assert_eq!(p.center_of_mass, Some(Point3::origin()));
+    }
+}
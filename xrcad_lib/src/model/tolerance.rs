@@ -0,0 +1,105 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: model::tolerance
+//!
+//! Geometric precision policy shared by all predicates (coincidence, parallelism,
+//! degeneracy). Each model carries its own `Tolerance`, so it can be tuned per document.
+
+use nalgebra::Vector3;
+use serde::{Deserialize, Serialize};
+
+/// Linear (model units) and angular (radians) tolerances for geometric comparisons
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Tolerance {
+    /// Distances at or below this are treated as zero
+    pub linear: f64,
+    /// Angles at or below this are treated as zero
+    pub angular: f64,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl Tolerance {
+    /// 1e-6 in both: a direction turned by the angular tolerance drifts by the
+    /// linear tolerance over one model unit, so `parallel` and `coincident` agree
+    /// for features of about unit size. Larger parts see more drift before two
+    /// directions stop counting as parallel.
+    pub const DEFAULT: Tolerance = Tolerance { linear: 1e-6, angular: 1e-6 };
+
+    pub fn new(linear: f64, angular: f64) -> Self {
+        Self { linear, angular }
+    }
+
+    /// True if a length is indistinguishable from zero
+    pub fn is_zero_length(&self, length: f64) -> bool {
+        length.abs() <= self.linear
+    }
+
+    /// True if two positions are the same point
+    pub fn coincident(&self, a: &Vector3<f64>, b: &Vector3<f64>) -> bool {
+        self.is_zero_length((a - b).norm())
+    }
+
+    /// True if two directions are parallel (same or opposite sense)
+    pub fn parallel(&self, a: &Vector3<f64>, b: &Vector3<f64>) -> bool {
+        let scale = a.norm() * b.norm();
+        scale > 0.0 && a.cross(b).norm() <= self.angular.sin() * scale
+    }
+
+    /// True if two directions are parallel and point the same way
+    pub fn codirectional(&self, a: &Vector3<f64>, b: &Vector3<f64>) -> bool {
+        self.parallel(a, b) && a.dot(b) > 0.0
+    }
+
+    /// True if two directions are perpendicular
+    pub fn perpendicular(&self, a: &Vector3<f64>, b: &Vector3<f64>) -> bool {
+        let scale = a.norm() * b.norm();
+        scale > 0.0 && a.dot(b).abs() <= self.angular.sin() * scale
+    }
+
+    /// True if three points do not span a triangle (its height is within the linear tolerance)
+    pub fn collinear(&self, a: &Vector3<f64>, b: &Vector3<f64>, c: &Vector3<f64>) -> bool {
+        let ab = b - a;
+        let ac = c - a;
+        let base = ab.norm().max(ac.norm()).max((c - b).norm());
+        base <= self.linear || ab.cross(&ac).norm() <= self.linear * base
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coincident_and_zero_length() {
+        let t = Tolerance::default();
+        assert!(t.coincident(&Vector3::new(1.0, 2.0, 3.0), &Vector3::new(1.0, 2.0, 3.0 + 1e-7)));
+        assert!(!t.coincident(&Vector3::zeros(), &Vector3::new(1e-3, 0.0, 0.0)));
+        assert!(Tolerance::new(1e-2, 1e-9).is_zero_length(-1e-3));
+    }
+
+    #[test]
+    fn test_parallel_and_perpendicular() {
+        let t = Tolerance::default();
+        assert!(t.parallel(&Vector3::x(), &(-Vector3::x() * 5.0)));
+        assert!(!t.codirectional(&Vector3::x(), &-Vector3::x()));
+        assert!(t.perpendicular(&Vector3::x(), &Vector3::y()));
+        assert!(!t.parallel(&Vector3::x(), &Vector3::new(1.0, 1e-3, 0.0)));
+        // Round-off in computed normals stays within the default angular tolerance
+        assert!(t.parallel(&Vector3::x(), &Vector3::new(1.0, 1e-8, 0.0)));
+        assert!(!t.parallel(&Vector3::zeros(), &Vector3::x()));
+    }
+
+    #[test]
+    fn test_collinear() {
+        let t = Tolerance::default();
+        let a = Vector3::zeros();
+        assert!(t.collinear(&a, &Vector3::new(1.0, 0.0, 0.0), &Vector3::new(2.0, 1e-8, 0.0)));
+        assert!(!t.collinear(&a, &Vector3::new(1.0, 0.0, 0.0), &Vector3::new(0.0, 1.0, 0.0)));
+    }
+}
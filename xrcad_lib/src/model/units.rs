@@ -0,0 +1,311 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: model::units
+//!
+//! Document unit system and unit-aware quantities. Geometry is always stored in
+//! millimetres and radians; the unit system only decides how lengths are shown
+//! and how typed or imported unitless values are read. `Length` and `Angle`
+//! carry a value together with its unit so call sites such as primitive
+//! construction say what they mean; a bare `f64` converts as millimetres.
+
+use std::ops::{Add, Mul, Neg, Sub};
+
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::input::keyboard::KeyBindings;
+use crate::telemetry::crash::journal;
+use crate::telemetry::usage::UsageStats;
+#[cfg(feature = "render")]
+use crate::interaction::grid_snap::GridSnap;
+
+/// Length units offered for display and input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LengthUnit {
+    #[default]
+    Millimetre,
+    Centimetre,
+    Metre,
+    Inch,
+}
+
+impl LengthUnit {
+    pub const ALL: [LengthUnit; 4] = [LengthUnit::Millimetre, LengthUnit::Centimetre, LengthUnit::Metre, LengthUnit::Inch];
+
+    /// Millimetres in one of this unit
+    pub fn mm(&self) -> f64 {
+        match self {
+            LengthUnit::Millimetre => 1.0,
+            LengthUnit::Centimetre => 10.0,
+            LengthUnit::Metre => 1000.0,
+            LengthUnit::Inch => 25.4,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            LengthUnit::Millimetre => "mm",
+            LengthUnit::Centimetre => "cm",
+            LengthUnit::Metre => "m",
+            LengthUnit::Inch => "in",
+        }
+    }
+
+    pub fn from_symbol(symbol: &str) -> Option<Self> {
+        match symbol.trim().to_ascii_lowercase().as_str() {
+            "mm" => Some(LengthUnit::Millimetre),
+            "cm" => Some(LengthUnit::Centimetre),
+            "m" => Some(LengthUnit::Metre),
+            "in" | "\"" => Some(LengthUnit::Inch),
+            _ => None,
+        }
+    }
+
+    /// DXF `$INSUNITS` code
+    pub fn dxf_code(&self) -> i32 {
+        match self {
+            LengthUnit::Inch => 1,
+            LengthUnit::Millimetre => 4,
+            LengthUnit::Centimetre => 5,
+            LengthUnit::Metre => 6,
+        }
+    }
+
+    pub fn from_dxf_code(code: i32) -> Option<Self> {
+        Self::ALL.into_iter().find(|u| u.dxf_code() == code)
+    }
+
+    /// Grid increment (mm) that suits working in this unit
+    pub fn grid_step(&self) -> f64 {
+        match self {
+            LengthUnit::Millimetre => 1.0,
+            LengthUnit::Centimetre => 5.0,
+            LengthUnit::Metre => 10.0,
+            LengthUnit::Inch => 25.4 / 16.0,
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|u| *u == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+}
+
+/// A length, stored in millimetres
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default, Serialize, Deserialize)]
+pub struct Length(f64);
+
+impl Length {
+    pub fn new(value: f64, unit: LengthUnit) -> Self {
+        Length(value * unit.mm())
+    }
+
+    pub fn mm(value: f64) -> Self {
+        Length(value)
+    }
+
+    pub fn cm(value: f64) -> Self {
+        Self::new(value, LengthUnit::Centimetre)
+    }
+
+    pub fn m(value: f64) -> Self {
+        Self::new(value, LengthUnit::Metre)
+    }
+
+    pub fn inches(value: f64) -> Self {
+        Self::new(value, LengthUnit::Inch)
+    }
+
+    /// Value in model units (millimetres)
+    pub fn as_mm(&self) -> f64 {
+        self.0
+    }
+
+    pub fn value_in(&self, unit: LengthUnit) -> f64 {
+        self.0 / unit.mm()
+    }
+}
+
+/// Bare numbers are model units (millimetres)
+impl From<f64> for Length {
+    fn from(mm: f64) -> Self {
+        Length(mm)
+    }
+}
+
+impl Add for Length {
+    type Output = Length;
+    fn add(self, rhs: Length) -> Length {
+        Length(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Length {
+    type Output = Length;
+    fn sub(self, rhs: Length) -> Length {
+        Length(self.0 - rhs.0)
+    }
+}
+
+impl Mul<f64> for Length {
+    type Output = Length;
+    fn mul(self, rhs: f64) -> Length {
+        Length(self.0 * rhs)
+    }
+}
+
+impl Neg for Length {
+    type Output = Length;
+    fn neg(self) -> Length {
+        Length(-self.0)
+    }
+}
+
+/// An angle, stored in radians
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default, Serialize, Deserialize)]
+pub struct Angle(f64);
+
+impl Angle {
+    pub fn radians(value: f64) -> Self {
+        Angle(value)
+    }
+
+    pub fn degrees(value: f64) -> Self {
+        Angle(value.to_radians())
+    }
+
+    pub fn as_radians(&self) -> f64 {
+        self.0
+    }
+
+    pub fn as_degrees(&self) -> f64 {
+        self.0.to_degrees()
+    }
+}
+
+/// Document unit system: how quantities are shown and how unitless input is read
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnitSystem {
+    pub length: LengthUnit,
+    /// Decimal places shown
+    pub precision: usize,
+}
+
+impl Default for UnitSystem {
+    fn default() -> Self {
+        Self { length: LengthUnit::Millimetre, precision: 2 }
+    }
+}
+
+impl UnitSystem {
+    pub fn new(length: LengthUnit) -> Self {
+        Self { length, ..Default::default() }
+    }
+
+    /// Model-unit length in the document unit, with its symbol
+    pub fn format_length(&self, mm: f64) -> String {
+        format!("{:.*} {}", self.precision, Length::mm(mm).value_in(self.length), self.length.symbol())
+    }
+
+    /// Model-unit area (mm²) in the square of the document unit
+    pub fn format_area(&self, mm2: f64) -> String {
+        format!("{:.*} {}²", self.precision, mm2 / self.length.mm().powi(2), self.length.symbol())
+    }
+
+    /// Model-unit volume (mm³) in the cube of the document unit
+    pub fn format_volume(&self, mm3: f64) -> String {
+        format!("{:.*} {}³", self.precision, mm3 / self.length.mm().powi(3), self.length.symbol())
+    }
+
+    /// Radians as degrees
+    pub fn format_angle(&self, radians: f64) -> String {
+        format!("{:.*}°", self.precision, radians.to_degrees())
+    }
+
+    /// Typed length in model units; a number without a unit is in the document unit
+    pub fn parse_length(&self, text: &str) -> Option<f64> {
+        let (number, unit) = split_unit(text);
+        let unit = if unit.is_empty() { self.length } else { LengthUnit::from_symbol(unit)? };
+        Some(Length::new(number.parse().ok()?, unit).as_mm())
+    }
+
+    /// Typed angle in radians; degrees unless suffixed with "rad"
+    pub fn parse_angle(&self, text: &str) -> Option<f64> {
+        let (number, unit) = split_unit(text);
+        let value: f64 = number.parse().ok()?;
+        match unit {
+            "" | "deg" | "°" => Some(Angle::degrees(value).as_radians()),
+            "rad" => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// Numeric part and unit suffix of typed input
+fn split_unit(text: &str) -> (&str, &str) {
+    let text = text.trim();
+    let split = text.find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-' || c == '+')).unwrap_or(text.len());
+    (text[..split].trim(), text[split..].trim())
+}
+
+/// Request to change the document length unit
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetLengthUnit(pub LengthUnit);
+
+/// The cycle_units binding (U) cycles the document length unit
+pub fn unit_keys(keys: Res<ButtonInput<KeyCode>>, bindings: Res<KeyBindings>, units: Res<UnitSystem>, mut requests: EventWriter<SetLengthUnit>) {
+    if bindings.just_pressed("cycle_units", &keys) {
+        requests.write(SetLengthUnit(units.length.next()));
+    }
+}
+
+/// Switch the document unit; the snapping grid follows it
+pub fn apply_unit_requests(
+    mut requests: EventReader<SetLengthUnit>,
+    mut units: ResMut<UnitSystem>,
+    #[cfg(feature = "render")] mut snap: Option<ResMut<GridSnap>>,
+    mut usage: Option<ResMut<UsageStats>>,
+) {
+    for SetLengthUnit(unit) in requests.read() {
+        let start = Instant::now();
+        journal(format!("length_unit {:?}", unit));
+        units.length = *unit;
+        #[cfg(feature = "render")]
+        if let Some(snap) = snap.as_mut() {
+            snap.increment = unit.grid_step();
+        }
+        if let Some(usage) = usage.as_mut() {
+            usage.record("length_unit", start.elapsed());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quantities_and_formatting() {
+        assert_eq!(Length::inches(2.0).as_mm(), 50.8);
+        assert_eq!((Length::cm(3.0) + Length::mm(5.0)).value_in(LengthUnit::Centimetre), 3.5);
+        assert!((Angle::degrees(90.0).as_radians() - std::f64::consts::FRAC_PI_2).abs() < 1e-12);
+        let units = UnitSystem::new(LengthUnit::Centimetre);
+        assert_eq!(units.format_length(125.0), "12.50 cm");
+        assert_eq!(units.format_angle(std::f64::consts::PI), "180.00°");
+        assert_eq!(units.format_area(250.0), "2.50 cm²");
+        assert_eq!(LengthUnit::from_dxf_code(1), Some(LengthUnit::Inch));
+    }
+
+    #[test]
+    fn test_parse_input() {
+        let units = UnitSystem::new(LengthUnit::Centimetre);
+        assert_eq!(units.parse_length("4"), Some(40.0));
+        assert_eq!(units.parse_length("2 in"), Some(50.8));
+        assert_eq!(units.parse_length("0.5m"), Some(500.0));
+        assert_eq!(units.parse_length("3 ft"), None);
+        assert_eq!(units.parse_angle("1.5rad"), Some(1.5));
+        assert!((units.parse_angle("45").unwrap() - std::f64::consts::FRAC_PI_4).abs() < 1e-12);
+    }
+}
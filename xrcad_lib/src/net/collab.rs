@@ -0,0 +1,672 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: net::collab
+//!
+//! Shared editing sessions between users on the network, for example one in a
+//! headset and one at a desk. One user hosts: the others connect over TCP and
+//! are sent the model as it stands. The host orders all modeling commands:
+//! its own run as soon as they are sent, while a client's are held back, sent
+//! to the host and run when the host sends them back, so every copy runs the
+//! same commands in the same order. Each user also shares a pose (head,
+//! controllers, the model point under their pointer and their selection),
+//! relayed to everyone through the host.
+//! Messages are RON, one per line.
+
+use std::fmt;
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use bevy::prelude::*;
+use nalgebra::Vector3;
+use serde::{Deserialize, Serialize};
+
+use crate::interaction::selection::{Selection, SelectionItem};
+use crate::model::brep_model::BrepModel;
+use crate::model::command::{CommandRelay, ModelCommand};
+use crate::model::feature_tree::FeatureTree;
+use crate::model::properties::BodyPropertiesCollection;
+use crate::telemetry::crash::journal;
+
+/// Give up connecting to a host after this long
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Colors handed out to users in joining order, the host first
+pub const PEER_COLORS: [[f32; 3]; 6] = [
+    [0.2, 0.6, 1.0],
+    [1.0, 0.45, 0.2],
+    [0.35, 0.85, 0.35],
+    [0.85, 0.35, 0.85],
+    [1.0, 0.85, 0.2],
+    [0.3, 0.85, 0.85],
+];
+
+/// A user in a session; the host is 0
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PeerId(pub u32);
+
+pub const HOST: PeerId = PeerId(0);
+
+/// Color of a user
+pub fn peer_color(peer: PeerId) -> [f32; 3] {
+    PEER_COLORS[peer.0 as usize % PEER_COLORS.len()]
+}
+
+/// Position and rotation (quaternion x, y, z, w) of a tracked device, model space
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TrackedPose {
+    pub position: [f32; 3],
+    pub rotation: [f32; 4],
+}
+
+/// What a user shares about where they are
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PeerPose {
+    /// Model point under the user's pointer
+    pub cursor: Option<Vector3<f64>>,
+    /// Headset, or the desktop camera
+    #[serde(default)]
+    pub head: Option<TrackedPose>,
+    /// XR controllers
+    #[serde(default)]
+    pub hands: Vec<TrackedPose>,
+    #[serde(default)]
+    pub selection: Vec<SelectionItem>,
+}
+
+/// Another user in the session
+#[derive(Debug, Clone, PartialEq)]
+pub struct Peer {
+    pub id: PeerId,
+    pub name: String,
+    pub color: [f32; 3],
+    pub pose: PeerPose,
+}
+
+/// What peers send each other
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PeerMessage {
+    /// First message of a client
+    Hello { name: String },
+    /// Host's answer to `Hello`: the client's id and color, and the model to start from
+    Welcome { peer: PeerId, color: [f32; 3], model: BrepModel, features: FeatureTree, properties: BodyPropertiesCollection },
+    Joined { peer: PeerId, name: String, color: [f32; 3] },
+    Left { peer: PeerId },
+    /// A modeling command, in session order when sent by the host
+    Command { peer: PeerId, command: ModelCommand },
+    Pose { peer: PeerId, pose: PeerPose },
+}
+
+/// Why a session could not be opened or was lost
+#[derive(Debug)]
+pub enum CollabError {
+    Io(io::Error),
+    Serialize(String),
+    Parse(String),
+    /// The other end closed the connection
+    Closed,
+}
+
+impl fmt::Display for CollabError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CollabError::Io(err) => write!(f, "{}", err),
+            CollabError::Serialize(msg) => write!(f, "could not serialize message: {}", msg),
+            CollabError::Parse(msg) => write!(f, "invalid message: {}", msg),
+            CollabError::Closed => write!(f, "connection closed"),
+        }
+    }
+}
+
+impl std::error::Error for CollabError {}
+
+impl From<io::Error> for CollabError {
+    fn from(err: io::Error) -> Self {
+        CollabError::Io(err)
+    }
+}
+
+/// A non-blocking connection to one peer
+#[derive(Debug)]
+pub struct Connection {
+    stream: TcpStream,
+    /// Received bytes not yet forming a whole line
+    incoming: Vec<u8>,
+    /// Queued bytes the socket has not taken yet
+    outgoing: Vec<u8>,
+    closed: bool,
+}
+
+impl Connection {
+    pub fn new(stream: TcpStream) -> Result<Self, CollabError> {
+        stream.set_nonblocking(true)?;
+        stream.set_nodelay(true)?;
+        Ok(Self { stream, incoming: Vec::new(), outgoing: Vec::new(), closed: false })
+    }
+
+    /// Queue a message; it goes out on the next `flush`
+    pub fn send(&mut self, message: &PeerMessage) -> Result<(), CollabError> {
+        let line = ron::to_string(message).map_err(|e| CollabError::Serialize(e.to_string()))?;
+        self.outgoing.extend_from_slice(line.as_bytes());
+        self.outgoing.push(b'\n');
+        Ok(())
+    }
+
+    /// Write as much of the queue as the socket takes
+    pub fn flush(&mut self) -> Result<(), CollabError> {
+        while !self.outgoing.is_empty() {
+            match self.stream.write(&self.outgoing) {
+                Ok(0) => return Err(CollabError::Closed),
+                Ok(n) => {
+                    self.outgoing.drain(..n);
+                }
+                Err(err) if err.kind() == ErrorKind::WouldBlock => break,
+                Err(err) if err.kind() == ErrorKind::Interrupted => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Ok(())
+    }
+
+    /// Messages that have arrived completely since the last call
+    pub fn receive(&mut self) -> Result<Vec<PeerMessage>, CollabError> {
+        let mut buf = [0u8; 8192];
+        loop {
+            match self.stream.read(&mut buf) {
+                Ok(0) => {
+                    self.closed = true;
+                    break;
+                }
+                Ok(n) => self.incoming.extend_from_slice(&buf[..n]),
+                Err(err) if err.kind() == ErrorKind::WouldBlock => break,
+                Err(err) if err.kind() == ErrorKind::Interrupted => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+        let mut messages = Vec::new();
+        while let Some(end) = self.incoming.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.incoming.drain(..=end).collect();
+            let text = std::str::from_utf8(&line[..end]).map_err(|e| CollabError::Parse(e.to_string()))?;
+            messages.push(ron::from_str(text).map_err(|e| CollabError::Parse(e.to_string()))?);
+        }
+        Ok(messages)
+    }
+
+    /// The peer closed the connection
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+}
+
+/// A client connected to the host
+#[derive(Debug)]
+struct Client {
+    peer: PeerId,
+    connection: Connection,
+    /// Said hello and was sent the model
+    joined: bool,
+}
+
+#[derive(Debug)]
+enum Role {
+    Host { listener: TcpListener, clients: Vec<Client>, next: u32 },
+    Client(Connection),
+}
+
+/// The shared session this app is in, if any
+#[derive(Resource, Debug, Default)]
+pub struct CollabSession {
+    role: Option<Role>,
+    /// This user's id, once the host has answered
+    pub me: PeerId,
+    pub name: String,
+    pub color: [f32; 3],
+    /// The other users
+    pub peers: Vec<Peer>,
+    /// What this user shares; sent whenever it changes
+    pub pose: PeerPose,
+    sent_pose: Option<PeerPose>,
+}
+
+impl CollabSession {
+    pub fn is_active(&self) -> bool {
+        self.role.is_some()
+    }
+
+    pub fn is_host(&self) -> bool {
+        matches!(self.role, Some(Role::Host { .. }))
+    }
+
+    pub fn peer(&self, id: PeerId) -> Option<&Peer> {
+        self.peers.iter().find(|p| p.id == id)
+    }
+
+    /// Port the host accepts peers on
+    pub fn local_port(&self) -> Option<u16> {
+        match &self.role {
+            Some(Role::Host { listener, .. }) => listener.local_addr().ok().map(|a| a.port()),
+            _ => None,
+        }
+    }
+
+    /// Accept peers on a port (0 picks a free one), leaving any session first
+    pub fn host(&mut self, port: u16, name: &str) -> Result<(), CollabError> {
+        let listener = TcpListener::bind(("0.0.0.0", port))?;
+        listener.set_nonblocking(true)?;
+        self.leave();
+        self.role = Some(Role::Host { listener, clients: Vec::new(), next: 1 });
+        self.me = HOST;
+        self.name = name.to_string();
+        self.color = peer_color(HOST);
+        Ok(())
+    }
+
+    /// Connect to a host ("address:port"), leaving any session first. The
+    /// model is replaced by the host's once it answers.
+    pub fn join(&mut self, address: &str, name: &str) -> Result<(), CollabError> {
+        let addr = address.to_socket_addrs()?.next().ok_or_else(|| CollabError::Io(io::Error::new(ErrorKind::NotFound, "no such address")))?;
+        let mut connection = Connection::new(TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT)?)?;
+        connection.send(&PeerMessage::Hello { name: name.to_string() })?;
+        self.leave();
+        self.role = Some(Role::Client(connection));
+        self.name = name.to_string();
+        Ok(())
+    }
+
+    /// Close the session; the model stays as it is
+    pub fn leave(&mut self) {
+        self.role = None;
+        self.me = HOST;
+        self.peers.clear();
+        self.sent_pose = None;
+    }
+
+    /// Add a user, or update one already known
+    fn joined(&mut self, id: PeerId, name: String, color: [f32; 3]) {
+        match self.peers.iter_mut().find(|p| p.id == id) {
+            Some(peer) => {
+                peer.name = name;
+                peer.color = color;
+            }
+            None => self.peers.push(Peer { id, name, color, pose: PeerPose::default() }),
+        }
+    }
+
+    fn set_pose(&mut self, id: PeerId, pose: PeerPose) {
+        if let Some(peer) = self.peers.iter_mut().find(|p| p.id == id) {
+            peer.pose = pose;
+        }
+    }
+
+    /// This user's pose if it changed since it was last sent
+    fn pose_to_send(&mut self) -> Option<PeerPose> {
+        if self.sent_pose.as_ref() == Some(&self.pose) {
+            return None;
+        }
+        self.sent_pose = Some(self.pose.clone());
+        Some(self.pose.clone())
+    }
+}
+
+/// Request to open or leave a shared session
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub enum SessionRequest {
+    /// Accept peers on a port
+    Host { port: u16, name: String },
+    /// Connect to a host ("address:port")
+    Join { address: String, name: String },
+    Leave,
+}
+
+/// Session to open once the app has started
+#[derive(Resource, Debug, Default, Clone)]
+pub struct StartupSession(pub Option<SessionRequest>);
+
+/// Send the startup session request
+pub fn start_session(startup: Option<Res<StartupSession>>, mut requests: EventWriter<SessionRequest>) {
+    if let Some(request) = startup.and_then(|s| s.0.clone()) {
+        requests.write(request);
+    }
+}
+
+/// Open and leave sessions on request
+pub fn apply_session_requests(mut requests: EventReader<SessionRequest>, mut session: ResMut<CollabSession>, mut relay: ResMut<CommandRelay>) {
+    for request in requests.read() {
+        journal(format!("session {:?}", request));
+        let outcome = match request {
+            SessionRequest::Host { port, name } => session.host(*port, name),
+            SessionRequest::Join { address, name } => session.join(address, name),
+            SessionRequest::Leave => {
+                session.leave();
+                Ok(())
+            }
+        };
+        match outcome {
+            Ok(()) => match request {
+                SessionRequest::Host { .. } => info!("Hosting a shared session on port {}", session.local_port().unwrap_or_default()),
+                SessionRequest::Join { address, .. } => info!("Joined the shared session at {}", address),
+                SessionRequest::Leave => info!("Left the shared session"),
+            },
+            Err(err) => warn!("Could not open the shared session: {}", err),
+        }
+    }
+    relay.sharing = session.is_active();
+    relay.hold_local = session.is_active() && !session.is_host();
+}
+
+/// Send a message to every client that has joined, except one
+fn broadcast(clients: &mut [Client], except: Option<PeerId>, message: &PeerMessage) {
+    for client in clients.iter_mut().filter(|c| c.joined && Some(c.peer) != except) {
+        if let Err(err) = client.connection.send(message) {
+            warn!("Could not send to peer {}: {}", client.peer.0, err);
+        }
+    }
+}
+
+/// One frame of a host: pass on commands in the order they run here, welcome
+/// new clients and relay what clients send
+fn host_frame(
+    session: &mut CollabSession,
+    (listener, clients, next): (&TcpListener, &mut Vec<Client>, &mut u32),
+    relay: &mut CommandRelay,
+    (model, features, properties): (&BrepModel, &FeatureTree, &BodyPropertiesCollection),
+) {
+    // Local commands that ran last frame, after everything already sent
+    for command in relay.outgoing.drain(..) {
+        broadcast(clients, None, &PeerMessage::Command { peer: HOST, command });
+    }
+    loop {
+        match listener.accept() {
+            Ok((stream, addr)) => match Connection::new(stream) {
+                Ok(connection) => {
+                    journal(format!("session_connect {}", addr));
+                    clients.push(Client { peer: PeerId(*next), connection, joined: false });
+                    *next += 1;
+                }
+                Err(err) => warn!("Could not accept peer {}: {}", addr, err),
+            },
+            Err(err) if err.kind() == ErrorKind::WouldBlock => break,
+            Err(err) => {
+                warn!("Could not accept peers: {}", err);
+                break;
+            }
+        }
+    }
+    // Client commands passed on this frame; they run after the model sent to newcomers
+    let mut relayed: Vec<(PeerId, ModelCommand)> = Vec::new();
+    let mut gone = Vec::new();
+    for index in 0..clients.len() {
+        let from = clients[index].peer;
+        let messages = match clients[index].connection.receive() {
+            Ok(messages) => messages,
+            Err(err) => {
+                warn!("Dropping peer {}: {}", from.0, err);
+                gone.push(from);
+                continue;
+            }
+        };
+        for message in messages {
+            match message {
+                PeerMessage::Hello { name } if !clients[index].joined => {
+                    let color = peer_color(from);
+                    let mut welcome = vec![
+                        PeerMessage::Welcome { peer: from, color, model: model.clone(), features: features.clone(), properties: properties.clone() },
+                        PeerMessage::Joined { peer: HOST, name: session.name.clone(), color: session.color },
+                    ];
+                    welcome.extend(session.peers.iter().map(|p| PeerMessage::Joined { peer: p.id, name: p.name.clone(), color: p.color }));
+                    welcome.extend(relayed.iter().map(|(peer, command)| PeerMessage::Command { peer: *peer, command: command.clone() }));
+                    welcome.push(PeerMessage::Pose { peer: HOST, pose: session.pose.clone() });
+                    for message in &welcome {
+                        if let Err(err) = clients[index].connection.send(message) {
+                            warn!("Could not send to peer {}: {}", from.0, err);
+                        }
+                    }
+                    broadcast(clients, Some(from), &PeerMessage::Joined { peer: from, name: name.clone(), color });
+                    clients[index].joined = true;
+                    journal(format!("session_joined {} {}", from.0, name));
+                    info!("{} joined the shared session", name);
+                    session.joined(from, name, color);
+                }
+                PeerMessage::Command { command, .. } if clients[index].joined => {
+                    broadcast(clients, None, &PeerMessage::Command { peer: from, command: command.clone() });
+                    relay.incoming.push_back(command.clone());
+                    relayed.push((from, command));
+                }
+                PeerMessage::Pose { pose, .. } if clients[index].joined => {
+                    broadcast(clients, Some(from), &PeerMessage::Pose { peer: from, pose: pose.clone() });
+                    session.set_pose(from, pose);
+                }
+                _ => warn!("Peer {} sent an unexpected message", from.0),
+            }
+        }
+        if clients[index].connection.is_closed() {
+            gone.push(from);
+        }
+    }
+    for peer in gone {
+        clients.retain(|c| c.peer != peer);
+        if let Some(index) = session.peers.iter().position(|p| p.id == peer) {
+            info!("{} left the shared session", session.peers.remove(index).name);
+            broadcast(clients, None, &PeerMessage::Left { peer });
+        }
+    }
+    if let Some(pose) = session.pose_to_send() {
+        broadcast(clients, None, &PeerMessage::Pose { peer: HOST, pose });
+    }
+    for client in clients.iter_mut() {
+        if let Err(err) = client.connection.flush() {
+            warn!("Could not send to peer {}: {}", client.peer.0, err);
+        }
+    }
+}
+
+/// One frame of a client: send local commands to the host and queue the ones
+/// it orders
+fn client_frame(
+    session: &mut CollabSession,
+    connection: &mut Connection,
+    relay: &mut CommandRelay,
+    (model, features, properties): (&mut BrepModel, &mut FeatureTree, &mut BodyPropertiesCollection),
+    mut selection: Option<&mut Selection>,
+) -> Result<(), CollabError> {
+    for command in relay.outgoing.drain(..) {
+        connection.send(&PeerMessage::Command { peer: session.me, command })?;
+    }
+    for message in connection.receive()? {
+        match message {
+            PeerMessage::Welcome { peer, color, model: shared, features: mut history, properties: props } => {
+                session.me = peer;
+                session.color = color;
+                history.rebuild();
+                *model = shared;
+                *features = history;
+                *properties = props;
+                if let Some(selection) = selection.as_deref_mut() {
+                    selection.clear();
+                }
+                journal(format!("session_welcome {}", peer.0));
+            }
+            PeerMessage::Joined { peer, name, color } => session.joined(peer, name, color),
+            PeerMessage::Left { peer } => session.peers.retain(|p| p.id != peer),
+            PeerMessage::Command { command, .. } => relay.incoming.push_back(command),
+            PeerMessage::Pose { peer, pose } => session.set_pose(peer, pose),
+            PeerMessage::Hello { .. } => warn!("The host sent an unexpected message"),
+        }
+    }
+    if let Some(pose) = session.pose_to_send() {
+        connection.send(&PeerMessage::Pose { peer: session.me, pose })?;
+    }
+    connection.flush()?;
+    if connection.is_closed() {
+        return Err(CollabError::Closed);
+    }
+    Ok(())
+}
+
+/// Exchange commands and poses with the session; runs before the command executor
+pub fn sync_collab_session(
+    mut session: ResMut<CollabSession>,
+    mut relay: ResMut<CommandRelay>,
+    (mut model, mut features, mut properties): (ResMut<BrepModel>, ResMut<FeatureTree>, ResMut<BodyPropertiesCollection>),
+    mut selection: Option<ResMut<Selection>>,
+) {
+    let session = &mut *session;
+    let Some(mut role) = session.role.take() else { return };
+    match &mut role {
+        Role::Host { listener, clients, next } => host_frame(session, (listener, clients, next), &mut relay, (&model, &features, &properties)),
+        Role::Client(connection) => {
+            let outcome = client_frame(session, connection, &mut relay, (&mut model, &mut features, &mut properties), selection.as_deref_mut());
+            if let Err(err) = outcome {
+                warn!("Left the shared session: {}", err);
+                journal(format!("session_lost {}", err));
+                session.leave();
+                relay.sharing = false;
+                relay.hold_local = false;
+                return;
+            }
+        }
+    }
+    session.role = Some(role);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::app::TaskPoolPlugin;
+    use crate::measure::mass_properties::mass_properties;
+    use crate::model::body::BodyId;
+    use crate::model::brep::operations::boolean::BooleanOp;
+    use crate::model::brep::placement::PlacementFrame;
+    use crate::model::command::execute_model_commands;
+    use crate::model::feature_tree::FeatureKind;
+    use crate::model::jobs::{poll_jobs, start_jobs, BackgroundJobs, Job, JobFinished, JobProgressed};
+    use nalgebra::Point3;
+
+    fn cube_at(x: f64) -> ModelCommand {
+        let placement = PlacementFrame::new(Point3::new(x, 0.0, 0.0), Vector3::z());
+        ModelCommand::CreatePrimitive(FeatureKind::Box { size: Vector3::repeat(10.0), placement: Some(placement) })
+    }
+
+    fn peer_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(TaskPoolPlugin::default())
+            .init_resource::<BrepModel>()
+            .init_resource::<FeatureTree>()
+            .init_resource::<BodyPropertiesCollection>()
+            .init_resource::<CollabSession>()
+            .init_resource::<CommandRelay>()
+            .init_resource::<BackgroundJobs>()
+            .add_event::<ModelCommand>()
+            .add_event::<SessionRequest>()
+            .add_event::<JobProgressed>()
+            .add_event::<JobFinished>()
+            .add_systems(Update, (apply_session_requests, sync_collab_session, execute_model_commands, start_jobs, poll_jobs).chain());
+        app
+    }
+
+    /// Run host and client until `done` holds, giving the sockets time between frames
+    fn run_until(host: &mut App, client: &mut App, done: impl Fn(&App, &App) -> bool) {
+        for _ in 0..200 {
+            host.update();
+            client.update();
+            if done(host, client) {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        panic!("peers did not converge");
+    }
+
+    fn bodies(app: &App) -> usize {
+        app.world().resource::<BrepModel>().shells().len()
+    }
+
+    #[test]
+    fn test_messages_survive_the_wire() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut a = Connection::new(TcpStream::connect(listener.local_addr().unwrap()).unwrap()).unwrap();
+        let mut b = Connection::new(listener.accept().unwrap().0).unwrap();
+        a.send(&PeerMessage::Hello { name: "Ada\nLovelace".into() }).unwrap();
+        a.send(&PeerMessage::Command { peer: PeerId(2), command: cube_at(5.0) }).unwrap();
+        a.flush().unwrap();
+        let mut received = Vec::new();
+        for _ in 0..200 {
+            received.extend(b.receive().unwrap());
+            if received.len() == 2 {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert!(matches!(&received[0], PeerMessage::Hello { name } if name == "Ada\nLovelace"));
+        assert!(matches!(&received[1], PeerMessage::Command { peer: PeerId(2), command: ModelCommand::CreatePrimitive(_) }));
+
+        drop(a);
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(b.receive().unwrap().is_empty());
+        assert!(b.is_closed());
+    }
+
+    #[test]
+    fn test_peers_run_commands_in_host_order() {
+        let mut host = peer_app();
+        host.world_mut().send_event(ModelCommand::CreatePrimitive(FeatureKind::Box { size: Vector3::repeat(10.0), placement: None }));
+        host.world_mut().send_event(SessionRequest::Host { port: 0, name: "Desk".into() });
+        host.update();
+        let port = host.world().resource::<CollabSession>().local_port().unwrap();
+
+        // The client starts from the host's model
+        let mut client = peer_app();
+        client.world_mut().send_event(SessionRequest::Join { address: format!("127.0.0.1:{}", port), name: "Headset".into() });
+        run_until(&mut host, &mut client, |host, client| bodies(client) == 1 && !host.world().resource::<CollabSession>().peers.is_empty());
+        assert_eq!(client.world().resource::<CollabSession>().me, PeerId(1));
+        assert_eq!(client.world().resource::<CollabSession>().peer(HOST).map(|p| p.name.as_str()), Some("Desk"));
+
+        // A client's command waits for the host, then runs once everywhere
+        client.world_mut().send_event(cube_at(100.0));
+        client.update();
+        assert_eq!(bodies(&client), 1);
+        host.world_mut().send_event(cube_at(200.0));
+        run_until(&mut host, &mut client, |host, client| bodies(host) == 3 && bodies(client) == 3);
+        for _ in 0..5 {
+            host.update();
+            client.update();
+        }
+        let names = |app: &App| -> Vec<String> { app.world().resource::<FeatureTree>().features.iter().map(|f| f.name.clone()).collect() };
+        assert_eq!(names(&host), names(&client));
+        assert_eq!(bodies(&client), 3);
+
+        client.world_mut().send_event(SessionRequest::Leave);
+        run_until(&mut host, &mut client, |host, _| host.world().resource::<CollabSession>().peers.is_empty());
+    }
+
+    #[test]
+    fn test_background_booleans_reach_every_peer() {
+        let mut host = peer_app();
+        for x in [0.0, 5.0, 100.0, 105.0] {
+            host.world_mut().send_event(cube_at(x));
+        }
+        host.world_mut().send_event(SessionRequest::Host { port: 0, name: "Desk".into() });
+        host.update();
+        let port = host.world().resource::<CollabSession>().local_port().unwrap();
+        let mut client = peer_app();
+        client.world_mut().send_event(SessionRequest::Join { address: format!("127.0.0.1:{}", port), name: "Headset".into() });
+        run_until(&mut host, &mut client, |_, client| bodies(client) == 4);
+
+        // Each side joins a pair of overlapping cubes as a background job
+        let union = || Job::Command(ModelCommand::Boolean { op: BooleanOp::Union, target: BodyId(0), tool: BodyId(1) });
+        host.world_mut().resource_mut::<BackgroundJobs>().request(union());
+        run_until(&mut host, &mut client, |host, client| bodies(host) == 3 && bodies(client) == 3);
+        client.world_mut().resource_mut::<BackgroundJobs>().request(union());
+        run_until(&mut host, &mut client, |host, client| bodies(host) == 2 && bodies(client) == 2);
+
+        let names = |app: &App| -> Vec<String> { app.world().resource::<FeatureTree>().features.iter().map(|f| f.name.clone()).collect() };
+        assert_eq!(names(&host), names(&client));
+        let volume = |app: &App| mass_properties(app.world().resource::<BrepModel>()).volume;
+        assert!((volume(&host) - 3000.0).abs() < 1e-6 && (volume(&client) - 3000.0).abs() < 1e-6);
+        let model = |app: &App| app.world().resource::<BrepModel>().clone();
+        let (a, b) = (model(&host), model(&client));
+        assert_eq!((a.faces.len(), a.vertices.len()), (b.faces.len(), b.vertices.len()));
+        assert!(a.vertices.iter().zip(&b.vertices).all(|(u, v)| u.position == v.position));
+    }
+}
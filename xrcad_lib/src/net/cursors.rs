@@ -0,0 +1,45 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: net::cursors
+//!
+//! Cursors of the other users in a shared session: the model point each one
+//! points at, drawn in their color. The local cursor is the point under an XR
+//! controller ray, or else under the mouse.
+
+use bevy::prelude::*;
+
+use crate::interaction::picking::PickState;
+use crate::interaction::xr_controller::XrController;
+use crate::model::brep_model::na_vec3_to_bevy;
+use crate::net::collab::CollabSession;
+use crate::render::gizmo_scale::GizmoScale;
+
+/// Size of a peer's cursor on screen (pixels)
+const CURSOR_PIXELS: f32 = 14.0;
+
+/// Share the point this user points at
+pub fn share_cursor(pick: Option<Res<PickState>>, controllers: Query<&XrController>, mut session: ResMut<CollabSession>) {
+    if !session.is_active() {
+        return;
+    }
+    let xr = controllers.iter().find_map(|c| c.hover.as_ref().map(|h| h.point));
+    let cursor = xr.or_else(|| pick.as_ref().and_then(|p| p.hover.as_ref().map(|h| h.point)));
+    if session.pose.cursor != cursor {
+        session.pose.cursor = cursor;
+    }
+}
+
+/// Draw the other users' cursors as rings with a dot, in their colors
+pub fn render_peer_cursors(mut gizmos: Gizmos, session: Res<CollabSession>, scale: Option<Res<GizmoScale>>) {
+    let scale = scale.as_deref().copied().unwrap_or_default();
+    for peer in &session.peers {
+        let Some(cursor) = peer.pose.cursor else { continue };
+        let point = na_vec3_to_bevy(&cursor);
+        let size = scale.world_size(point, CURSOR_PIXELS);
+        let color = Color::srgb(peer.color[0], peer.color[1], peer.color[2]);
+        let facing = Quat::from_rotation_arc(Vec3::Z, (scale.camera_position - point).normalize_or(Vec3::Z));
+        gizmos.circle(Isometry3d::new(point, facing), size, color);
+        gizmos.sphere(point, size * 0.2, color);
+    }
+}
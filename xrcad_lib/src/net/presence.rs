@@ -0,0 +1,183 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: net::presence
+//!
+//! The other users of a shared session, drawn where they are: a head with a
+//! line showing where it looks, for a desktop user their camera, a short ray
+//! from each XR controller, a name tag above the head and their selection
+//! outlined, all in the user's color. The same pose sync that carries the
+//! cursors carries this user's head, controllers and selection to them.
+
+use bevy::prelude::*;
+
+use crate::interaction::selection::Selection;
+use crate::interaction::xr_controller::XrController;
+use crate::model::brep_model::{na_vec3_to_bevy, BrepModel};
+use crate::net::collab::{CollabSession, PeerId, TrackedPose};
+use crate::render::gizmo_scale::GizmoScale;
+use crate::render::hilighting::item_edges;
+use crate::viewport::camera::ViewRig;
+
+/// Size of a head on screen (pixels)
+const HEAD_PIXELS: f32 = 18.0;
+/// Size of a controller on screen (pixels)
+const HAND_PIXELS: f32 = 8.0;
+/// Length of the look and controller rays, in head sizes
+const RAY_LENGTH: f32 = 4.0;
+/// Name tags sit this far above the head on screen (pixels)
+const TAG_OFFSET: f32 = 28.0;
+
+/// Pose of a device to share
+pub fn tracked_pose(transform: &GlobalTransform) -> TrackedPose {
+    let (_, rotation, translation) = transform.to_scale_rotation_translation();
+    TrackedPose { position: translation.to_array(), rotation: rotation.to_array() }
+}
+
+/// A shared pose as a transform
+pub fn pose_transform(pose: &TrackedPose) -> Transform {
+    Transform::from_translation(Vec3::from_array(pose.position)).with_rotation(Quat::from_array(pose.rotation).normalize())
+}
+
+fn peer_color(color: [f32; 3]) -> Color {
+    Color::srgb(color[0], color[1], color[2])
+}
+
+/// Share this user's head (the XR camera, or else the desktop one),
+/// controllers and selection
+pub fn share_presence(
+    mut session: ResMut<CollabSession>,
+    cameras: Query<(&GlobalTransform, &ViewRig), With<Camera>>,
+    controllers: Query<&GlobalTransform, With<XrController>>,
+    selection: Option<Res<Selection>>,
+) {
+    if !session.is_active() {
+        return;
+    }
+    let head = cameras.iter().max_by_key(|(_, rig)| rig.is_xr()).map(|(transform, _)| tracked_pose(transform));
+    let hands: Vec<TrackedPose> = controllers.iter().map(tracked_pose).collect();
+    let items = selection.map(|s| s.items.clone()).unwrap_or_default();
+    if session.pose.head != head || session.pose.hands != hands || session.pose.selection != items {
+        let pose = &mut session.pose;
+        pose.head = head;
+        pose.hands = hands;
+        pose.selection = items;
+    }
+}
+
+/// Draw the other users' heads, controllers and selections
+pub fn render_avatars(mut gizmos: Gizmos, session: Res<CollabSession>, model: Res<BrepModel>, scale: Option<Res<GizmoScale>>) {
+    let scale = scale.as_deref().copied().unwrap_or_default();
+    for peer in &session.peers {
+        let color = peer_color(peer.color);
+        if let Some(head) = peer.pose.head.as_ref().map(pose_transform) {
+            let size = scale.world_size(head.translation, HEAD_PIXELS);
+            gizmos.sphere(head.translation, size, color);
+            gizmos.line(head.translation, head.translation + head.forward() * size * RAY_LENGTH, color);
+        }
+        for hand in peer.pose.hands.iter().map(pose_transform) {
+            let size = scale.world_size(hand.translation, HAND_PIXELS);
+            gizmos.cuboid(hand.with_scale(Vec3::splat(size)), color);
+            gizmos.line(hand.translation, hand.translation + hand.forward() * size * RAY_LENGTH, color.with_alpha(0.5));
+        }
+        for item in &peer.pose.selection {
+            for edge in item_edges(&model, item) {
+                let ends = model.edge(edge).and_then(|e| Some((model.vertex_position(e.vertices.0)?, model.vertex_position(e.vertices.1)?)));
+                if let Some((a, b)) = ends {
+                    gizmos.line(na_vec3_to_bevy(&a), na_vec3_to_bevy(&b), color);
+                }
+            }
+        }
+    }
+}
+
+/// Name tag floating above a user's head
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeerNameTag(pub PeerId);
+
+/// Keep one name tag per user, placed above their head on screen
+pub fn sync_name_tags(
+    mut commands: Commands,
+    session: Res<CollabSession>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    mut tags: Query<(Entity, &PeerNameTag, &mut Text, &mut Node, &mut Visibility)>,
+) {
+    for (entity, PeerNameTag(id), ..) in tags.iter() {
+        if session.peer(*id).is_none() {
+            commands.entity(entity).despawn();
+        }
+    }
+    for peer in session.peers.iter().filter(|p| !tags.iter().any(|(_, tag, ..)| tag.0 == p.id)) {
+        commands.spawn((
+            Text::new(peer.name.clone()),
+            TextColor(peer_color(peer.color)),
+            Node { position_type: PositionType::Absolute, padding: UiRect::all(Val::Px(3.0)), ..default() },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            Visibility::Hidden,
+            PeerNameTag(peer.id),
+        ));
+    }
+    let camera = cameras.iter().find(|(camera, _)| camera.is_active);
+    for (_, PeerNameTag(id), mut text, mut node, mut visibility) in tags.iter_mut() {
+        let Some(peer) = session.peer(*id) else { continue };
+        let screen = peer.pose.head.as_ref().zip(camera).and_then(|(head, (camera, transform))| camera.world_to_viewport(transform, Vec3::from_array(head.position)).ok());
+        let Some(screen) = screen else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+        if text.0 != peer.name {
+            text.0 = peer.name.clone();
+        }
+        node.left = Val::Px(screen.x);
+        node.top = Val::Px(screen.y - TAG_OFFSET);
+        *visibility = Visibility::Inherited;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interaction::selection::SelectionItem;
+    use crate::interaction::xr_controller::XrHand;
+    use crate::net::collab::{Peer, PeerPose};
+
+    #[test]
+    fn test_share_presence() {
+        let mut app = App::new();
+        let mut selection = Selection::default();
+        selection.add(SelectionItem::Face(3));
+        app.init_resource::<CollabSession>().insert_resource(selection).add_systems(Update, share_presence);
+        let head = Transform::from_xyz(1.0, 2.0, 3.0).looking_at(Vec3::ZERO, Vec3::Y);
+        app.world_mut().spawn((Camera::default(), GlobalTransform::from(head), ViewRig::HEADSET));
+        app.world_mut().spawn((Camera::default(), GlobalTransform::default(), ViewRig::Desktop));
+        app.world_mut().spawn((XrController::new(XrHand::Left), GlobalTransform::from_xyz(0.5, 1.0, 0.0)));
+
+        // Nothing is shared outside a session
+        app.update();
+        assert_eq!(app.world().resource::<CollabSession>().pose, PeerPose::default());
+
+        app.world_mut().resource_mut::<CollabSession>().host(0, "Desk").unwrap();
+        app.update();
+        let pose = &app.world().resource::<CollabSession>().pose;
+        let shared = pose_transform(&pose.head.unwrap());
+        assert!(shared.translation.distance(head.translation) < 1e-6 && shared.rotation.angle_between(head.rotation) < 1e-4);
+        assert_eq!(pose.hands.len(), 1);
+        assert_eq!(pose.selection, vec![SelectionItem::Face(3)]);
+    }
+
+    #[test]
+    fn test_one_name_tag_per_peer() {
+        let mut app = App::new();
+        app.init_resource::<CollabSession>().add_systems(Update, sync_name_tags);
+        let peer = |id, name: &str| Peer { id: PeerId(id), name: name.into(), color: [1.0, 0.5, 0.2], pose: PeerPose::default() };
+        app.world_mut().resource_mut::<CollabSession>().peers = vec![peer(1, "Ada"), peer(2, "Grace")];
+        app.update();
+        app.update();
+        let tags = |app: &mut App| app.world_mut().query::<&PeerNameTag>().iter(app.world()).map(|t| t.0).collect::<Vec<_>>();
+        assert_eq!(tags(&mut app).len(), 2);
+
+        app.world_mut().resource_mut::<CollabSession>().peers.remove(0);
+        app.update();
+        assert_eq!(tags(&mut app), vec![PeerId(2)]);
+    }
+}
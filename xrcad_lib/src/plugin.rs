@@ -0,0 +1,905 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: plugin
+//!
+//! Bevy plugins that register the library's resources, events and systems.
+//! `XrCadPlugin` adds everything: the six sub-plugins below. Each can also be
+//! added alone, though `EditingPlugin` needs `DocumentPlugin`:
+//! - `DocumentPlugin` holds the document and runs commands, jobs, files, recovery, scripts and shared sessions.
+//! - `EditingPlugin` handles picking, selection, tools, transforms, sketches, measuring and the outliner.
+//! - `BrepRenderPlugin` draws bodies, edges, sections, exploded views and analysis.
+//! - `WorkbenchPlugin` draws helpers and handles workbenches and construction planes.
+//! - `CameraPlugin` maps input devices to actions and drives the camera, views and XR comfort.
+//! - `LightingPlugin` handles lights, environments and render profiles.
+//!
+//! Each plugin takes a settings struct with the resources it starts from and
+//! whether it spawns its panels. Add them after `DefaultPlugins`. The app then
+//! only inserts its document and adds its own UI. Shortcut systems go in the
+//! `ShortcutKeys` set, so none of them runs while a text field has the keyboard.
+
+use std::path::PathBuf;
+
+use bevy::core_pipeline::auto_exposure::AutoExposurePlugin;
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use bevy::render::view::VisibilitySystems;
+
+use crate::input::actions::{begin_actions, binding_actions, finish_actions, mouse_actions, pointer_device_actions, touch_actions, ActionState};
+use crate::input::eyetrack::{bias_xr_pointers, gaze_dwell_select, render_gaze_highlight, update_gaze, EyeGaze, EyeTrackSettings};
+use crate::input::gamepad::{gamepad_select_cycle, GamepadSettings};
+use crate::input::keyboard::{
+    capture_key_binding, key_bindings_panel_keys, key_bindings_panel_system, spawn_key_bindings_panel, KeyBindings, KeyBindingsSession, ShortcutKeys,
+    ShortcutKeysPlugin,
+};
+use crate::input::stylus::{render_stylus_stroke, stylus_draw, StylusSettings, StylusStroke};
+use crate::input::touchscreen::{touch_long_press_select, TouchSettings, TouchState};
+use crate::interaction::box_select::{box_select, render_box_select, BoxSelect};
+use crate::interaction::construction_plane::{
+    apply_construction_plane_requests, construction_plane_button_system, spawn_helpers_panel, MidPlane, OffsetPlane, PlaneOnFace,
+};
+use crate::interaction::dimension_edit::{apply_dimension_values, dimension_edit_input_system, not_editing_dimension, DimensionEditSession, SetDimensionValue};
+use crate::interaction::grid_snap::{spawn_drag_readout, update_drag_readout, GridSnap};
+use crate::interaction::measure_tool::{
+    apply_keep_measurements, measure_panel_system, measure_pick, measure_tool_keys, not_measuring, render_measure_annotations, spawn_measure_panel,
+    update_measure_label, KeepMeasurements, MeasureTool,
+};
+use crate::interaction::outliner::{apply_outliner_requests, outliner_panel_system, spawn_outliner_panel, sync_body_properties, OutlinerRequest};
+use crate::interaction::picking::{select_on_click, update_pick, PickState};
+use crate::interaction::place_primitive::{apply_place_primitive, place_primitive_keys, PlacePrimitive, PrimitiveShape, PrimitiveTool};
+use crate::interaction::plane_suggestion::{
+    apply_new_sketch, not_suggesting_plane, plane_suggestion_keys, render_plane_suggestion, NewSketch, PlaneSuggestionSession,
+};
+use crate::interaction::quick_boolean::{apply_boolean_selection, quick_boolean_keys, BooleanSelection};
+use crate::interaction::rename::{apply_rename_requests, not_renaming, rename_input_system, RenameBody, RenameSession};
+use crate::interaction::selection::{apply_selection_filter, notify_selection_changes, selection_filter_keys, Selection, SelectionChanged, SetSelectionFilter};
+use crate::interaction::snapping::{render_snap_marker, update_snap, SnapSettings, SnapState};
+use crate::interaction::state::{apply_ui_layout, ActiveBody, UiLayout};
+use crate::interaction::tools::{render_active_tool, run_tools, spawn_toolbar, tool_shortcut_keys, toolbar_system, ActivateTool, RegisterTool, ToolRegistry};
+use crate::interaction::transform_gizmo::{
+    apply_transform_selection, not_entering_transform, render_transform_gizmo, transform_gizmo_drag, transform_gizmo_keys, transform_value_input,
+    TransformGizmo, TransformSelection,
+};
+use crate::interaction::two_hand::{two_hand_gesture, TwoHandGesture};
+use crate::interaction::xr_controller::{latch_xr_buttons, render_xr_rays, update_xr_pointers, xr_controller_grab, xr_controller_select};
+use crate::io::dxf::{apply_dxf_requests, ExportDxf, ImportDxf};
+use crate::io::measurement_export::{apply_measurement_exports, ExportMeasurements};
+use crate::io::mesh_import::{apply_mesh_imports, ImportMesh};
+use crate::io::project::{handle_project_requests, OpenProject, ProjectFile, SaveProject};
+use crate::measure::clash::{apply_clash_requests, clash_keys, clash_panel_system, render_clashes, spawn_clash_panel, ClashReport, ClashRequest};
+use crate::measure::measurements::Measurements;
+use crate::model::assembly::{solve_assembly, Assembly};
+use crate::model::brep::constraints::planarity::{apply_planar_edit_requests, planar_edit_keys, PlanarEdit, SetPlanarityMode};
+use crate::model::brep::operations::delete::{apply_delete_selection, delete_keys, DeleteSelection};
+use crate::model::brep_model::BrepModel;
+use crate::model::bvh::{update_scene_bvh, SceneBvh};
+use crate::model::changes::{check_topology_invariants, refresh_mass_properties, track_body_changes, BodyChanges};
+use crate::model::command::{execute_model_commands, CommandLog, CommandRelay, ModelCommand, ModelRevision};
+use crate::model::document::{notify_document_changes, DocumentChanged};
+use crate::model::feature_tree::FeatureTree;
+use crate::model::groups::BodyGroups;
+use crate::model::jobs::{
+    apply_job_requests, cancel_job_button, job_panel_system, poll_jobs, spawn_job_panel, start_jobs, BackgroundJobs, CancelJob, JobFinished, JobProgressed, StartJob,
+};
+use crate::model::layers::{
+    apply_layer_requests, layer_keys, AssignLayer, CreateLayer, DeleteLayer, LayerManager, RenameLayer, SetLayerColor, SetLayerLocked, SetLayerVisible,
+};
+use crate::model::mesh_body::MeshBodies;
+use crate::model::metadata::DocumentMetadata;
+use crate::model::properties::BodyPropertiesCollection;
+use crate::model::units::{apply_unit_requests, unit_keys, SetLengthUnit, UnitSystem};
+use crate::net::collab::{apply_session_requests, start_session, sync_collab_session, CollabSession, SessionRequest, StartupSession};
+use crate::net::cursors::{render_peer_cursors, share_cursor};
+use crate::net::presence::{render_avatars, share_presence, sync_name_tags};
+use crate::render::brep_refs::{index_brep_entities, BrepEntities};
+use crate::render::culling::{apply_occlusion_culling, count_culled_meshes, culling_keys, culling_stats_panel, spawn_culling_stats, CullingSettings, CullingStats};
+use crate::render::analysis::{analysis_panel_system, apply_analysis_requests, spawn_analysis_panel, AnalysisSettings, SetBodyAnalysis, SetPullDirection};
+use crate::render::display_mode::{
+    apply_display_mode_requests, apply_edge_depth_bias, display_mode_keys, finish_body_meshes, sync_body_meshes, DisplaySettings, PendingBodyMeshes,
+    SetBodyDisplayMode, SetDisplayMode,
+};
+use crate::render::edge_display::{edge_display_keys, EdgeDisplaySettings};
+use crate::render::edge_overlay::{configure_edge_overlay, render_edge_overlay, update_edge_topology, EdgeOverlayGizmos, EdgeOverlaySettings, EdgeTopology};
+use crate::render::exploded::{
+    animate_exploded_view, apply_exploded_requests, exploded_view_keys, render_exploded_leaders, ExplodedView, SetExplodeFactor, ToggleExploded,
+};
+use crate::render::gizmo_scale::{update_gizmo_scale, GizmoScale};
+use crate::render::grid::{sync_grids, update_grids, GridPlugin};
+use crate::render::hilighting::render_selection;
+use crate::render::lighting::{
+    apply_lighting_requests, apply_scene_light_requests, draw_scene_lights, follow_camera_lights, lighting_keys, lighting_panel_system,
+    scene_lights_panel_system, spawn_lighting_panel, sync_managed_lights, sync_scene_lights, AddSceneLight, LightManager, RemoveSceneLight,
+    ScaleSceneLight, SceneLights, SetLightingPreset, SetRakingAngle, ToggleSceneLight,
+};
+use crate::render::lines::{scale_screen_sized_lines, sync_helper_lines, LinePlugin};
+use crate::render::lod::{select_body_lods, LodSettings};
+use crate::render::materials::update_body_materials;
+use crate::render::section::{apply_section_requests, render_section, section_keys, OffsetSection, SectionView, SetSectionPlane, ToggleSection, ToggleSectionCaps};
+use crate::render::settings::{
+    apply_environment_requests, apply_render_profile, apply_render_settings, environment_fallback, environment_panel_system, render_settings_keys,
+    RenderSettings,
+    SetAmbientOcclusion, SetEnvironment, SetRenderProfile,
+};
+use crate::scripting::console::{
+    apply_script_requests, not_typing_script, run_startup_scripts, script_console_input, script_console_panel, spawn_script_console, RunScript,
+    RunScriptFile, ScriptConsole, StartupScripts,
+};
+use crate::sketch::sketch::Sketches;
+use crate::telemetry::crash::{
+    announce_recovery, handle_recovery_requests, recovery_keys, schedule_recovery_snapshot, update_recovery_snapshot, DiscardRecovery,
+    PendingRecovery, RecoverySnapshot, RestoreRecovery,
+};
+use crate::telemetry::usage::{record_command_usage, save_usage_on_exit, usage_stats_keys, CommandExecuted, UsageStats};
+use crate::viewport::camera::{apply_view_rig_requests, SetViewRig, XrSession};
+use crate::viewport::camera_animation::{
+    apply_camera_animation_requests, camera_animation_button_system, camera_animation_keys, play_camera_animation, CameraAnimation, SetCameraAnimationMode,
+    ToggleCameraAnimation,
+};
+use crate::viewport::camera_control::{
+    apply_projection_requests, camera_control_system, projection_button_system, projection_keys, sync_camera_projection, CustomCameraController,
+    ToggleProjection,
+};
+use crate::viewport::comfort::{
+    apply_snap_turn, comfort_locomotion_system, snap_turn_keys, spawn_comfort_vignette, update_comfort_vignette, ComfortSettings, LocomotionState, SnapTurn,
+};
+use crate::viewport::framing::{
+    animate_camera_framing, apply_framing_requests, double_tap_fit, framing_keys, CameraFraming, FitAll, FitSelection, SetNamedView,
+};
+use crate::viewport::locomotion::{render_teleport_arc, xr_teleport, xr_thumbstick_locomotion, XrLocomotion};
+use crate::viewport::passthrough::{
+    anchor_model, apply_passthrough, ar_scale_panel_system, passthrough_keys, place_anchor, spawn_ar_scale_panel, sync_passthrough_support,
+    AnchorPlaced, PassthroughMode, TogglePassthrough,
+};
+use crate::viewport::saved_views::{
+    apply_saved_view_requests, saved_view_keys, saved_views_panel_system, spawn_saved_views_panel, DeleteView, RecallView, RenameView, SaveView, SavedViews,
+};
+use crate::viewport::view_cube::{apply_view_snaps, draw_view_cube, view_cube_input, SnapToView, ViewCube};
+use crate::viewport::xr_panels::{
+    apply_xr_panel_requests, place_xr_panels, sync_xr_panels, xr_panel_keys, RecenterXrPanels, SetXrPanelAnchor, XrPanelSettings, XrPanelState,
+};
+use crate::viewport::xr_scale::{apply_xr_scale, xr_scale_keys, ScaleWorld, SetXrScalePreset, XrScaleSettings, XrViewScale};
+use crate::workspace::workbenches::{apply_workbench_requests, spawn_workbench_bar, workbench_bar_system, SwitchWorkbench, Workbenches};
+use crate::workspace::workspace::Workspace;
+
+/// Shortcut systems of every sub-plugin share one `ShortcutKeys` set
+fn add_shortcut_keys(app: &mut App) {
+    if !app.is_plugin_added::<ShortcutKeysPlugin>() {
+        app.add_plugins(ShortcutKeysPlugin);
+    }
+}
+
+/// Settings of `BrepRenderPlugin`
+#[derive(Debug, Clone)]
+pub struct BrepRenderSettings {
+    pub display: DisplaySettings,
+    pub edge_overlay: EdgeOverlaySettings,
+    pub lod: LodSettings,
+    /// Spawn the culling statistics and analysis panels
+    pub panels: bool,
+}
+
+impl Default for BrepRenderSettings {
+    fn default() -> Self {
+        Self { display: DisplaySettings::default(), edge_overlay: EdgeOverlaySettings::default(), lod: LodSettings::default(), panels: true }
+    }
+}
+
+/// Draws the model: body meshes and materials, edge overlay, selection
+/// highlights, sections, exploded views and surface analysis
+#[derive(Debug, Clone, Default)]
+pub struct BrepRenderPlugin {
+    pub settings: BrepRenderSettings,
+}
+
+impl Plugin for BrepRenderPlugin {
+    fn build(&self, app: &mut App) {
+        add_shortcut_keys(app);
+        app.insert_resource(self.settings.display)
+            .insert_resource(self.settings.edge_overlay)
+            .insert_resource(self.settings.lod)
+            .init_resource::<BrepModel>()
+            .init_resource::<BodyPropertiesCollection>()
+            .init_resource::<BodyGroups>()
+            .init_resource::<LayerManager>()
+            .init_resource::<MeshBodies>()
+            .init_resource::<Sketches>()
+            .init_resource::<BrepEntities>()
+            .init_resource::<BodyChanges>()
+            .init_resource::<PendingBodyMeshes>()
+            .init_resource::<SceneBvh>()
+            .init_resource::<CullingSettings>()
+            .init_resource::<CullingStats>()
+            .init_resource::<KeyBindings>()
+            .init_resource::<Selection>()
+            .init_resource::<EdgeDisplaySettings>()
+            .init_resource::<EdgeTopology>()
+            .init_resource::<GizmoScale>()
+            .init_resource::<SectionView>()
+            .init_resource::<ExplodedView>()
+            .init_resource::<AnalysisSettings>()
+            .init_gizmo_group::<EdgeOverlayGizmos>()
+            .add_event::<SetDisplayMode>()
+            .add_event::<SetBodyDisplayMode>()
+            .add_event::<ToggleSection>()
+            .add_event::<ToggleSectionCaps>()
+            .add_event::<SetSectionPlane>()
+            .add_event::<OffsetSection>()
+            .add_event::<ToggleExploded>()
+            .add_event::<SetExplodeFactor>()
+            .add_event::<SetBodyAnalysis>()
+            .add_event::<SetPullDirection>()
+            .add_systems(PostUpdate, update_gizmo_scale.after(TransformSystem::TransformPropagate))
+            .add_systems(Update, (BrepModel::render, MeshBodies::render, Sketches::render))
+            .add_systems(Update, (update_edge_topology, configure_edge_overlay, render_edge_overlay).chain())
+            .add_systems(Update, (track_body_changes, refresh_mass_properties, check_topology_invariants, update_scene_bvh).chain().before(sync_body_meshes))
+            .add_systems(Update, select_body_lods.after(finish_body_meshes))
+            .add_systems(
+                Update,
+                (culling_keys.in_set(ShortcutKeys), apply_occlusion_culling, culling_stats_panel).chain(),
+            )
+            .add_systems(PostUpdate, count_culled_meshes.after(VisibilitySystems::CheckVisibility))
+            .add_systems(
+                Update,
+                (
+                    display_mode_keys.in_set(ShortcutKeys).run_if(not_entering_transform),
+                    apply_display_mode_requests,
+                    apply_analysis_requests,
+                    sync_body_meshes,
+                    finish_body_meshes,
+                    index_brep_entities,
+                    update_body_materials,
+                    apply_edge_depth_bias,
+                )
+                    .chain(),
+            )
+            .add_systems(Update, edge_display_keys.in_set(ShortcutKeys))
+            .add_systems(
+                Update,
+                (section_keys.in_set(ShortcutKeys).run_if(not_entering_transform), apply_section_requests, render_section).chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    exploded_view_keys.in_set(ShortcutKeys).run_if(not_entering_transform),
+                    apply_exploded_requests,
+                    animate_exploded_view,
+                    render_exploded_leaders,
+                )
+                    .chain(),
+            )
+            .add_systems(Update, analysis_panel_system.before(apply_analysis_requests))
+            .add_systems(Update, render_selection);
+        if self.settings.panels {
+            app.add_systems(Startup, (spawn_culling_stats, spawn_analysis_panel));
+        }
+    }
+}
+
+/// Settings of `WorkbenchPlugin`
+#[derive(Debug, Clone)]
+pub struct WorkbenchSettings {
+    /// Spawn the helpers panel and the workbench bar
+    pub panels: bool,
+}
+
+impl Default for WorkbenchSettings {
+    fn default() -> Self {
+        Self { panels: true }
+    }
+}
+
+/// Workspace helpers drawn as retained lines and an infinite grid, named
+/// workbenches and construction planes
+#[derive(Debug, Clone, Default)]
+pub struct WorkbenchPlugin {
+    pub settings: WorkbenchSettings,
+}
+
+impl Plugin for WorkbenchPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((LinePlugin, GridPlugin))
+            .init_resource::<Workspace>()
+            .init_resource::<Workbenches>()
+            .init_resource::<BrepModel>()
+            .init_resource::<Selection>()
+            .init_resource::<UiLayout>()
+            .init_resource::<GizmoScale>()
+            .add_event::<SwitchWorkbench>()
+            .add_event::<PlaneOnFace>()
+            .add_event::<OffsetPlane>()
+            .add_event::<MidPlane>()
+            .add_systems(Update, (sync_helper_lines, scale_screen_sized_lines).chain())
+            .add_systems(Update, (sync_grids, update_grids).chain())
+            .add_systems(Update, (construction_plane_button_system, apply_construction_plane_requests).chain())
+            .add_systems(Update, (workbench_bar_system, apply_workbench_requests).chain())
+            .add_systems(Update, apply_ui_layout);
+        if self.settings.panels {
+            app.add_systems(Startup, (spawn_helpers_panel, spawn_workbench_bar));
+        }
+    }
+}
+
+/// Settings of `CameraPlugin`
+#[derive(Resource, Debug, Clone)]
+pub struct CameraSettings {
+    /// Spawn the main camera at `start`
+    pub spawn_camera: bool,
+    pub start: Transform,
+    pub comfort: ComfortSettings,
+    pub xr_scale: XrScaleSettings,
+    pub xr_panels: XrPanelSettings,
+    pub gamepad: GamepadSettings,
+    pub touch: TouchSettings,
+    /// Spawn the saved views panel and the comfort vignette
+    pub panels: bool,
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        Self {
+            spawn_camera: true,
+            start: Transform::from_xyz(-500.0, 500.0, 500.0).looking_at(Vec3::ZERO, Vec3::Y),
+            comfort: ComfortSettings::default(),
+            xr_scale: XrScaleSettings::default(),
+            xr_panels: XrPanelSettings::default(),
+            gamepad: GamepadSettings::default(),
+            touch: TouchSettings::default(),
+            panels: true,
+        }
+    }
+}
+
+/// The orbiting main camera: projection, framing, the view cube, saved views,
+/// presentation animation, gamepad and touch navigation, XR scale and comfort, and passthrough
+#[derive(Debug, Clone, Default)]
+pub struct CameraPlugin {
+    pub settings: CameraSettings,
+}
+
+/// Spawn the main camera where the settings place it
+pub fn spawn_main_camera(mut commands: Commands, settings: Res<CameraSettings>) {
+    if !settings.spawn_camera {
+        return;
+    }
+    commands.spawn((
+        Camera3d { screen_space_specular_transmission_steps: 0, ..default() },
+        settings.start,
+        GlobalTransform::default(),
+        CustomCameraController::default(),
+    ));
+}
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        add_shortcut_keys(app);
+        app.insert_resource(self.settings.clone())
+            .insert_resource(self.settings.comfort.clone())
+            .insert_resource(self.settings.xr_scale.clone())
+            .insert_resource(self.settings.xr_panels.clone())
+            .insert_resource(self.settings.gamepad.clone())
+            .insert_resource(self.settings.touch.clone())
+            .init_resource::<TouchState>()
+            .init_resource::<KeyBindings>()
+            .init_resource::<ActionState>()
+            .init_resource::<BrepModel>()
+            .init_resource::<Selection>()
+            .init_resource::<CameraFraming>()
+            .init_resource::<ViewCube>()
+            .init_resource::<CameraAnimation>()
+            .init_resource::<SavedViews>()
+            .init_resource::<LocomotionState>()
+            .init_resource::<XrLocomotion>()
+            .init_resource::<XrViewScale>()
+            .init_resource::<XrSession>()
+            .init_resource::<PassthroughMode>()
+            .init_resource::<XrPanelState>()
+            .add_event::<FitAll>()
+            .add_event::<FitSelection>()
+            .add_event::<SetNamedView>()
+            .add_event::<ToggleProjection>()
+            .add_event::<SnapToView>()
+            .add_event::<SaveView>()
+            .add_event::<RecallView>()
+            .add_event::<RenameView>()
+            .add_event::<DeleteView>()
+            .add_event::<ToggleCameraAnimation>()
+            .add_event::<SetCameraAnimationMode>()
+            .add_event::<SetViewRig>()
+            .add_event::<SnapTurn>()
+            .add_event::<SetXrScalePreset>()
+            .add_event::<ScaleWorld>()
+            .add_event::<TogglePassthrough>()
+            .add_event::<AnchorPlaced>()
+            .add_event::<SetXrPanelAnchor>()
+            .add_event::<RecenterXrPanels>()
+            .add_systems(Startup, spawn_main_camera)
+            .add_systems(
+                PreUpdate,
+                (begin_actions, (mouse_actions, binding_actions, touch_actions, pointer_device_actions), finish_actions)
+                    .chain()
+                    .after(InputSystem)
+                    .after(capture_key_binding),
+            )
+            .add_systems(
+                Update,
+                (
+                    snap_turn_keys.in_set(ShortcutKeys),
+                    apply_view_rig_requests,
+                    camera_control_system,
+                    xr_scale_keys.in_set(ShortcutKeys).run_if(not_entering_transform),
+                    apply_xr_scale,
+                    xr_thumbstick_locomotion,
+                    xr_teleport,
+                    apply_snap_turn,
+                    comfort_locomotion_system,
+                    update_comfort_vignette,
+                    render_teleport_arc,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    (projection_keys.in_set(ShortcutKeys).run_if(not_entering_transform), projection_button_system),
+                    apply_projection_requests,
+                    sync_camera_projection,
+                )
+                    .chain()
+                    .before(camera_control_system),
+            )
+            .add_systems(
+                Update,
+                (gamepad_select_cycle.in_set(ShortcutKeys), touch_long_press_select)
+                    .before(camera_control_system),
+            )
+            .add_systems(Update, (view_cube_input.before(update_pick).before(camera_control_system), apply_view_snaps.before(animate_camera_framing), draw_view_cube))
+            .add_systems(
+                Update,
+                (
+                    (saved_view_keys.in_set(ShortcutKeys).run_if(not_entering_transform), saved_views_panel_system),
+                    apply_saved_view_requests.before(animate_camera_framing),
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    (camera_animation_keys.in_set(ShortcutKeys).run_if(not_entering_transform), camera_animation_button_system),
+                    apply_camera_animation_requests,
+                    play_camera_animation.after(camera_control_system),
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    (
+                        framing_keys.in_set(ShortcutKeys).run_if(not_suggesting_plane).run_if(not_entering_transform),
+                        double_tap_fit,
+                    ),
+                    apply_framing_requests,
+                    animate_camera_framing.after(camera_control_system),
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    (sync_passthrough_support, passthrough_keys.in_set(ShortcutKeys)),
+                    apply_passthrough,
+                    place_anchor.before(latch_xr_buttons),
+                    ar_scale_panel_system.before(apply_xr_scale),
+                    anchor_model.after(apply_xr_scale),
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    xr_panel_keys.in_set(ShortcutKeys),
+                    apply_xr_panel_requests,
+                    sync_xr_panels,
+                    place_xr_panels.after(comfort_locomotion_system).after(anchor_model),
+                )
+                    .chain(),
+            );
+        if self.settings.panels {
+            app.add_systems(Startup, (spawn_comfort_vignette, spawn_saved_views_panel, spawn_ar_scale_panel));
+        }
+    }
+}
+
+/// Settings of `LightingPlugin`
+#[derive(Debug, Clone)]
+pub struct LightingSettings {
+    pub lights: LightManager,
+    pub render: RenderSettings,
+    /// Spawn the lighting panel
+    pub panels: bool,
+}
+
+impl Default for LightingSettings {
+    fn default() -> Self {
+        Self { lights: LightManager::default(), render: RenderSettings::default(), panels: true }
+    }
+}
+
+/// Managed and scene lights, HDRI environments, SSAO and render profiles
+#[derive(Debug, Clone, Default)]
+pub struct LightingPlugin {
+    pub settings: LightingSettings,
+}
+
+impl Plugin for LightingPlugin {
+    fn build(&self, app: &mut App) {
+        // Render profiles switch auto exposure on
+        if !app.is_plugin_added::<AutoExposurePlugin>() {
+            app.add_plugins(AutoExposurePlugin);
+        }
+        add_shortcut_keys(app);
+        app.insert_resource(self.settings.lights.clone())
+            .insert_resource(self.settings.render.clone())
+            .init_resource::<KeyBindings>()
+            .init_resource::<SceneLights>()
+            .init_resource::<GizmoScale>()
+            .add_event::<SetRenderProfile>()
+            .add_event::<SetEnvironment>()
+            .add_event::<SetAmbientOcclusion>()
+            .add_event::<SetLightingPreset>()
+            .add_event::<SetRakingAngle>()
+            .add_event::<AddSceneLight>()
+            .add_event::<RemoveSceneLight>()
+            .add_event::<ToggleSceneLight>()
+            .add_event::<ScaleSceneLight>()
+            .add_systems(
+                Update,
+                (
+                    (render_settings_keys.in_set(ShortcutKeys), environment_panel_system),
+                    apply_render_profile,
+                    apply_environment_requests,
+                    environment_fallback,
+                    apply_render_settings,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    lighting_keys.in_set(ShortcutKeys),
+                    lighting_panel_system,
+                    apply_lighting_requests,
+                    sync_managed_lights,
+                    follow_camera_lights,
+                )
+                    .chain(),
+            )
+            .add_systems(Update, (scene_lights_panel_system, apply_scene_light_requests, sync_scene_lights, draw_scene_lights).chain());
+        if self.settings.panels {
+            app.add_systems(Startup, spawn_lighting_panel);
+        }
+    }
+}
+
+/// Settings of `DocumentPlugin`
+#[derive(Debug, Clone)]
+pub struct DocumentSettings {
+    /// Spawn the script console and job panels
+    pub panels: bool,
+    /// `.rhai` files run once the app has started
+    pub startup_scripts: Vec<PathBuf>,
+    /// Shared session to host or join once the app has started
+    pub session: Option<SessionRequest>,
+}
+
+impl Default for DocumentSettings {
+    fn default() -> Self {
+        Self { panels: true, startup_scripts: Vec::new(), session: None }
+    }
+}
+
+/// The document and what changes it as a whole: the command bus, background
+/// jobs, layers and units, project files, imports and exports, crash
+/// recovery, usage statistics, scripting and shared sessions
+#[derive(Debug, Clone, Default)]
+pub struct DocumentPlugin {
+    pub settings: DocumentSettings,
+}
+
+impl Plugin for DocumentPlugin {
+    fn build(&self, app: &mut App) {
+        add_shortcut_keys(app);
+        app.init_resource::<BrepModel>()
+            .init_resource::<BodyPropertiesCollection>()
+            .init_resource::<BodyGroups>()
+            .init_resource::<LayerManager>()
+            .init_resource::<MeshBodies>()
+            .init_resource::<Sketches>()
+            .init_resource::<Workspace>()
+            .init_resource::<Assembly>()
+            .init_resource::<FeatureTree>()
+            .init_resource::<DocumentMetadata>()
+            .init_resource::<UnitSystem>()
+            .init_resource::<KeyBindings>()
+            .init_resource::<UsageStats>()
+            .init_resource::<RecoverySnapshot>()
+            .init_resource::<PendingRecovery>()
+            .init_resource::<CommandLog>()
+            .init_resource::<BackgroundJobs>()
+            .init_resource::<ModelRevision>()
+            .init_resource::<ScriptConsole>()
+            .insert_resource(StartupScripts(self.settings.startup_scripts.clone()))
+            .insert_resource(StartupSession(self.settings.session.clone()))
+            .init_resource::<ProjectFile>()
+            .init_resource::<Measurements>()
+            .init_resource::<CollabSession>()
+            .init_resource::<CommandRelay>()
+            .add_event::<CreateLayer>()
+            .add_event::<RenameLayer>()
+            .add_event::<DeleteLayer>()
+            .add_event::<SetLayerColor>()
+            .add_event::<SetLayerVisible>()
+            .add_event::<SetLayerLocked>()
+            .add_event::<AssignLayer>()
+            .add_event::<SetLengthUnit>()
+            .add_event::<CommandExecuted>()
+            .add_event::<SaveProject>()
+            .add_event::<OpenProject>()
+            .add_event::<RestoreRecovery>()
+            .add_event::<DiscardRecovery>()
+            .add_event::<ImportMesh>()
+            .add_event::<ImportDxf>()
+            .add_event::<ExportDxf>()
+            .add_event::<ExportMeasurements>()
+            .add_event::<ModelCommand>()
+            .add_event::<StartJob>()
+            .add_event::<CancelJob>()
+            .add_event::<JobProgressed>()
+            .add_event::<JobFinished>()
+            .add_event::<DocumentChanged>()
+            .add_event::<RunScript>()
+            .add_event::<RunScriptFile>()
+            .add_event::<SessionRequest>()
+            .add_systems(Startup, announce_recovery)
+            .add_systems(Update, (schedule_recovery_snapshot, update_recovery_snapshot).chain())
+            .add_systems(Update, (recovery_keys.in_set(ShortcutKeys), handle_recovery_requests).chain())
+            .add_systems(Update, (usage_stats_keys.in_set(ShortcutKeys), record_command_usage, save_usage_on_exit))
+            .add_systems(Update, handle_project_requests)
+            .add_systems(Update, (apply_mesh_imports, apply_dxf_requests, apply_measurement_exports).chain().before(execute_model_commands))
+            .add_systems(Update, (unit_keys.in_set(ShortcutKeys), apply_unit_requests).chain())
+            .add_systems(Update, (layer_keys.in_set(ShortcutKeys), apply_layer_requests).chain())
+            .add_systems(Startup, run_startup_scripts)
+            .add_systems(
+                Update,
+                (script_console_input.run_if(not_renaming).run_if(not_editing_dimension), apply_script_requests, script_console_panel)
+                    .chain()
+                    .before(execute_model_commands),
+            )
+            .add_systems(Update, execute_model_commands.after(apply_place_primitive).after(apply_boolean_selection))
+            .add_systems(Update, solve_assembly.after(execute_model_commands).after(apply_outliner_requests))
+            .add_systems(Startup, start_session)
+            .add_systems(
+                Update,
+                (apply_session_requests, (share_cursor.after(update_pick), share_presence), sync_collab_session).chain().before(execute_model_commands),
+            )
+            .add_systems(Update, (render_peer_cursors, render_avatars, sync_name_tags))
+            .add_systems(Update, (apply_job_requests, start_jobs, poll_jobs).chain().after(execute_model_commands).after(refresh_mass_properties))
+            .add_systems(Update, (cancel_job_button, job_panel_system).chain().after(poll_jobs))
+            .add_systems(Last, notify_document_changes);
+        if self.settings.panels {
+            app.add_systems(Startup, (spawn_script_console, spawn_job_panel));
+        }
+    }
+}
+
+/// Settings of `EditingPlugin`
+#[derive(Debug, Clone)]
+pub struct EditingSettings {
+    /// Spawn the toolbar and the drag readout, measurement, outliner, key
+    /// bindings and clash panels
+    pub panels: bool,
+}
+
+impl Default for EditingSettings {
+    fn default() -> Self {
+        Self { panels: true }
+    }
+}
+
+/// Working on the document: picking, selection, snapping, tools, transforms,
+/// renaming, dimension edits, sketches, deletes and booleans, measurements,
+/// clashes, the outliner, key bindings, and XR, gaze and stylus input. Sends
+/// its edits as commands, so it needs `DocumentPlugin`.
+#[derive(Debug, Clone, Default)]
+pub struct EditingPlugin {
+    pub settings: EditingSettings,
+}
+
+impl Plugin for EditingPlugin {
+    fn build(&self, app: &mut App) {
+        add_shortcut_keys(app);
+        app.init_resource::<Selection>()
+            .init_resource::<ActiveBody>()
+            .init_resource::<RenameSession>()
+            .init_resource::<DimensionEditSession>()
+            .init_resource::<ToolRegistry>()
+            .init_resource::<ClashReport>()
+            .init_resource::<BoxSelect>()
+            .init_resource::<PickState>()
+            .init_resource::<GridSnap>()
+            .init_resource::<PlanarEdit>()
+            .init_resource::<PlaneSuggestionSession>()
+            .init_resource::<SnapSettings>()
+            .init_resource::<SnapState>()
+            .init_resource::<TransformGizmo>()
+            .init_resource::<TwoHandGesture>()
+            .init_resource::<StylusSettings>()
+            .init_resource::<EyeTrackSettings>()
+            .init_resource::<EyeGaze>()
+            .init_resource::<KeyBindings>()
+            .init_resource::<KeyBindingsSession>()
+            .init_resource::<StylusStroke>()
+            .init_resource::<MeasureTool>()
+            .add_event::<RenameBody>()
+            .add_event::<SetDimensionValue>()
+            .add_event::<ActivateTool>()
+            .add_event::<PlacePrimitive>()
+            .add_event::<SelectionChanged>()
+            .add_event::<SetSelectionFilter>()
+            .add_event::<DeleteSelection>()
+            .add_event::<BooleanSelection>()
+            .add_event::<NewSketch>()
+            .add_event::<SetPlanarityMode>()
+            .add_event::<TransformSelection>()
+            .add_event::<KeepMeasurements>()
+            .add_event::<ClashRequest>()
+            .add_event::<OutlinerRequest>()
+            .add_systems(PreUpdate, capture_key_binding.after(InputSystem))
+            .add_systems(Update, (key_bindings_panel_keys.in_set(ShortcutKeys), key_bindings_panel_system).chain())
+            .add_systems(Update, (rename_input_system.run_if(not_editing_dimension).run_if(not_typing_script), apply_rename_requests).chain().before(execute_model_commands))
+            .add_systems(
+                Update,
+                (
+                    dimension_edit_input_system.run_if(not_renaming).run_if(not_typing_script).run_if(not_suggesting_plane).run_if(not_entering_transform).run_if(not_measuring),
+                    apply_dimension_values,
+                )
+                    .chain(),
+            )
+            .add_systems(Update, (place_primitive_keys.in_set(ShortcutKeys), apply_place_primitive).chain())
+            .add_systems(
+                Update,
+                (
+                    plane_suggestion_keys.in_set(ShortcutKeys).run_if(not_entering_transform).run_if(not_measuring),
+                    apply_new_sketch,
+                )
+                    .chain(),
+            )
+            .add_systems(Update, render_plane_suggestion)
+            .add_systems(Update, (planar_edit_keys.in_set(ShortcutKeys), apply_planar_edit_requests).chain())
+            .add_systems(Update, (delete_keys.in_set(ShortcutKeys), apply_delete_selection).chain().before(execute_model_commands))
+            .add_systems(Update, (quick_boolean_keys.in_set(ShortcutKeys), apply_boolean_selection).chain())
+            .add_systems(
+                Update,
+                (
+                    tool_shortcut_keys.in_set(ShortcutKeys),
+                    toolbar_system,
+                    run_tools.after(select_on_click),
+                    render_active_tool,
+                )
+                    .chain()
+                    .before(execute_model_commands),
+            )
+            .add_systems(
+                Update,
+                (update_pick, update_snap, transform_gizmo_drag, measure_pick, select_on_click, box_select, BrepModel::vertex_drag, update_drag_readout)
+                    .chain()
+                    .after(update_scene_bvh),
+            )
+            .add_systems(
+                Update,
+                (
+                    update_xr_pointers,
+                    update_gaze,
+                    bias_xr_pointers,
+                    xr_controller_select,
+                    gaze_dwell_select,
+                    xr_controller_grab,
+                    two_hand_gesture,
+                    latch_xr_buttons,
+                    render_xr_rays,
+                    render_gaze_highlight,
+                )
+                    .chain()
+                    .after(update_scene_bvh)
+                    .after(select_on_click),
+            )
+            .add_systems(
+                Update,
+                (
+                    transform_gizmo_keys.in_set(ShortcutKeys),
+                    transform_value_input.in_set(ShortcutKeys),
+                    apply_transform_selection,
+                )
+                    .chain()
+                    .before(execute_model_commands),
+            )
+            .add_systems(
+                Update,
+                (
+                    measure_tool_keys.in_set(ShortcutKeys).run_if(not_entering_transform),
+                    apply_keep_measurements,
+                    measure_panel_system,
+                    update_measure_label,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    clash_keys.in_set(ShortcutKeys),
+                    apply_clash_requests,
+                    clash_panel_system,
+                    render_clashes,
+                )
+                    .chain()
+                    .after(update_scene_bvh),
+            )
+            .add_systems(Update, (sync_body_properties, outliner_panel_system, apply_outliner_requests).chain().before(execute_model_commands))
+            .add_systems(Update, (selection_filter_keys.in_set(ShortcutKeys), apply_selection_filter, notify_selection_changes).chain())
+            .add_systems(Update, (stylus_draw, render_stylus_stroke).chain())
+            .add_systems(Update, (render_box_select, render_snap_marker, render_transform_gizmo, render_measure_annotations));
+        app.register_tool(PrimitiveTool::new(PrimitiveShape::Box)).register_tool(PrimitiveTool::new(PrimitiveShape::Cylinder));
+        if self.settings.panels {
+            app.add_systems(Startup, (spawn_drag_readout, spawn_measure_panel, spawn_outliner_panel, spawn_toolbar, spawn_key_bindings_panel, spawn_clash_panel));
+        }
+    }
+}
+
+/// Settings of `XrCadPlugin`
+#[derive(Debug, Clone, Default)]
+pub struct XrCadSettings {
+    pub brep_render: BrepRenderSettings,
+    pub workbench: WorkbenchSettings,
+    pub camera: CameraSettings,
+    pub lighting: LightingSettings,
+    pub document: DocumentSettings,
+    pub editing: EditingSettings,
+}
+
+/// Everything: all six sub-plugins. Resources the app inserted beforehand
+/// (its document) are kept.
+#[derive(Debug, Clone, Default)]
+pub struct XrCadPlugin {
+    pub settings: XrCadSettings,
+}
+
+impl Plugin for XrCadPlugin {
+    fn build(&self, app: &mut App) {
+        let settings = &self.settings;
+        app.add_plugins((
+            DocumentPlugin { settings: settings.document.clone() },
+            EditingPlugin { settings: settings.editing.clone() },
+            BrepRenderPlugin { settings: settings.brep_render.clone() },
+            WorkbenchPlugin { settings: settings.workbench.clone() },
+            CameraPlugin { settings: settings.camera.clone() },
+            LightingPlugin { settings: settings.lighting.clone() },
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_camera_plugin_keeps_inserted_document() {
+        let mut app = App::new();
+        let mut selection = Selection::default();
+        selection.items.push(crate::interaction::selection::SelectionItem::Face(3));
+        let settings = CameraSettings { spawn_camera: false, panels: false, ..default() };
+        app.insert_resource(selection).add_plugins(CameraPlugin { settings });
+        let world = app.world();
+        assert!(!world.resource::<CameraSettings>().spawn_camera);
+        assert!(world.contains_resource::<ComfortSettings>() && world.contains_resource::<Events<SaveView>>());
+        assert_eq!(world.resource::<Selection>().faces(), [3]);
+    }
+}
@@ -0,0 +1,327 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: render::analysis
+//!
+//! Surface analysis display modes, switched per body from the analysis panel
+//! (hidden until the analysis_panel binding, Shift+F12, shows it) and stored
+//! in `BodyProperties::analysis`. Draft colors each face by its
+//! angle to the pull direction: green where it draws out of the mold, red
+//! where it faces away (the other half) and yellow where the draft is below
+//! the minimum either way. Curvature runs from blue (concave) through green
+//! (flat) to red (convex). Zebra lays light and dark bands by the angle
+//! between the normal and the stripe axis, so bands running on smoothly
+//! across an edge show the faces meet tangent. Only planar faces exist so
+//! far: curvature shows every face flat and each face falls in one band.
+//! An analysed body's face mesh gets vertex colors and an unlit material in
+//! place of its own; `render::display_mode` rebuilds it when the analysis or
+//! its settings change.
+
+use bevy::prelude::*;
+use nalgebra::Vector3;
+
+use crate::interaction::selection::Selection;
+use crate::interaction::state::{ActiveBody, UiPanel};
+use crate::model::body::BodyId;
+use crate::model::brep::topology::face::Face;
+use crate::model::brep_model::BrepModel;
+pub use crate::model::properties::AnalysisMode;
+use crate::model::properties::BodyPropertiesCollection;
+use crate::render::display_mode::BodyTriangles;
+use crate::render::ui_style::{BUTTON_ACTIVE, BUTTON_IDLE};
+use crate::telemetry::crash::journal;
+
+const DRAFT_POSITIVE: Color = Color::srgb(0.2, 0.75, 0.25);
+const DRAFT_NEGATIVE: Color = Color::srgb(0.85, 0.2, 0.2);
+const DRAFT_INSUFFICIENT: Color = Color::srgb(0.95, 0.8, 0.15);
+const CURVATURE_CONCAVE: Color = Color::srgb(0.2, 0.3, 0.9);
+const CURVATURE_FLAT: Color = Color::srgb(0.2, 0.75, 0.25);
+const CURVATURE_CONVEX: Color = Color::srgb(0.85, 0.2, 0.2);
+const ZEBRA_LIGHT: Color = Color::srgb(0.95, 0.95, 0.95);
+const ZEBRA_DARK: Color = Color::srgb(0.08, 0.08, 0.08);
+/// Faces whose surface the analysis cannot evaluate
+const NOT_ANALYSED: Color = Color::srgb(0.5, 0.5, 0.5);
+
+/// Settings shared by every analysed body
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct AnalysisSettings {
+    /// Direction the part is drawn out of the mold (unit)
+    pub pull: Vector3<f64>,
+    /// Draft below this counts as too little (radians)
+    pub min_draft: f64,
+    /// Curvature drawn in full color (1/mm)
+    pub curvature_range: f64,
+    /// Axis the zebra bands are measured from (unit)
+    pub stripe_axis: Vector3<f64>,
+    /// Angle spanned by one zebra band (radians)
+    pub stripe_width: f64,
+}
+
+impl Default for AnalysisSettings {
+    fn default() -> Self {
+        Self { pull: Vector3::y(), min_draft: 1f64.to_radians(), curvature_range: 0.1, stripe_axis: Vector3::y(), stripe_width: 10f64.to_radians() }
+    }
+}
+
+/// Draft angle of a surface normal to the pull direction: positive when it
+/// faces along the pull, zero for a wall parallel to it (radians)
+pub fn draft_angle(normal: &Vector3<f64>, pull: &Vector3<f64>) -> f64 {
+    normal.normalize().dot(&pull.normalize()).clamp(-1.0, 1.0).asin()
+}
+
+/// Mean curvature of a face (1/mm), positive where it bulges out; `None`
+/// for surfaces it is not known for. Every face is planar so far.
+pub fn face_curvature(model: &BrepModel, face: &Face) -> Option<f64> {
+    model.face_plane(face).map(|_| 0.0)
+}
+
+pub fn draft_color(angle: f64, min_draft: f64) -> Color {
+    if angle >= min_draft {
+        DRAFT_POSITIVE
+    } else if angle <= -min_draft {
+        DRAFT_NEGATIVE
+    } else {
+        DRAFT_INSUFFICIENT
+    }
+}
+
+pub fn curvature_color(curvature: f64, range: f64) -> Color {
+    let t = (curvature / range.max(f64::EPSILON)).clamp(-1.0, 1.0) as f32;
+    let curved = if t < 0.0 { CURVATURE_CONCAVE } else { CURVATURE_CONVEX };
+    CURVATURE_FLAT.to_srgba().mix(&curved.to_srgba(), t.abs()).into()
+}
+
+/// Light or dark band of a normal, counting bands of `width` away from the axis
+pub fn zebra_color(normal: &Vector3<f64>, axis: &Vector3<f64>, width: f64) -> Color {
+    let band = (normal.angle(axis) / width.max(f64::EPSILON)).floor() as i64;
+    if band % 2 == 0 { ZEBRA_LIGHT } else { ZEBRA_DARK }
+}
+
+/// Color of a face under an analysis
+pub fn face_analysis_color(model: &BrepModel, face: &Face, mode: AnalysisMode, settings: &AnalysisSettings) -> Color {
+    let Some(normal) = model.face_normal(face) else { return NOT_ANALYSED };
+    match mode {
+        AnalysisMode::Draft => draft_color(draft_angle(&normal, &settings.pull), settings.min_draft),
+        AnalysisMode::Curvature => face_curvature(model, face).map_or(NOT_ANALYSED, |k| curvature_color(k, settings.curvature_range)),
+        AnalysisMode::Zebra => zebra_color(&normal, &settings.stripe_axis, settings.stripe_width),
+    }
+}
+
+/// Linear vertex colors of a body's triangles under an analysis
+pub fn analysis_colors(model: &BrepModel, triangles: &BodyTriangles, mode: AnalysisMode, settings: &AnalysisSettings) -> Vec<[f32; 4]> {
+    let mut colors = vec![NOT_ANALYSED.to_linear().to_f32_array(); triangles.positions.len()];
+    for (triangle, face) in triangles.indices.chunks(3).zip(&triangles.faces) {
+        let color = model.face(*face).map_or(NOT_ANALYSED, |f| face_analysis_color(model, f, mode, settings)).to_linear().to_f32_array();
+        for index in triangle {
+            colors[*index as usize] = color;
+        }
+    }
+    colors
+}
+
+/// Unlit material showing a face mesh's vertex colors as they are
+pub fn analysis_material() -> StandardMaterial {
+    StandardMaterial { base_color: Color::WHITE, unlit: true, ..default() }
+}
+
+/// Request to show an analysis on bodies; `None` turns it off
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct SetBodyAnalysis {
+    pub bodies: Vec<BodyId>,
+    pub mode: Option<AnalysisMode>,
+}
+
+/// Request to change the draft pull direction
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct SetPullDirection(pub Vector3<f64>);
+
+/// Apply analysis requests
+pub fn apply_analysis_requests(
+    mut per_body: EventReader<SetBodyAnalysis>,
+    mut pulls: EventReader<SetPullDirection>,
+    mut settings: ResMut<AnalysisSettings>,
+    mut properties: ResMut<BodyPropertiesCollection>,
+) {
+    for SetBodyAnalysis { bodies, mode } in per_body.read() {
+        journal(format!("body analysis {:?} {}", bodies, mode.map_or("off", |m| m.label())));
+        for body in bodies {
+            match properties.get_mut(*body) {
+                Some(props) => props.analysis = *mode,
+                None => warn!("No body {} to analyse", body.0),
+            }
+        }
+    }
+    for SetPullDirection(pull) in pulls.read() {
+        let Some(pull) = pull.try_normalize(f64::EPSILON) else {
+            warn!("Pull direction needs a length");
+            continue;
+        };
+        journal(format!("pull direction {:.3} {:.3} {:.3}", pull.x, pull.y, pull.z));
+        settings.pull = pull;
+    }
+}
+
+/// Analysis panel button: the analysis to show on the selected bodies, `None` for off
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnalysisButton(pub Option<AnalysisMode>);
+
+/// Analysis panel button setting the pull direction
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PullButton {
+    /// Along a model axis (0 = X, 1 = Y, 2 = Z)
+    Axis(usize),
+    Flip,
+}
+
+/// Pull direction and analysed bodies readout of the analysis panel
+#[derive(Component, Debug)]
+pub struct AnalysisText;
+
+/// Analysis panel (right side): one button per analysis for the selected
+/// bodies, the pull direction and the bodies being analysed
+pub fn spawn_analysis_panel(mut commands: Commands) {
+    let button = || (Button, Node { padding: UiRect::axes(Val::Px(6.0), Val::Px(2.0)), ..default() }, BackgroundColor(BUTTON_IDLE));
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(8.0),
+                top: Val::Percent(40.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                padding: UiRect::all(Val::Px(6.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.1, 0.12, 0.15)),
+            UiPanel("analysis"),
+        ))
+        .with_children(|panel| {
+            panel.spawn(Text::new("Analysis (selected bodies, Shift+F12 hides)"));
+            panel.spawn(Node { column_gap: Val::Px(4.0), ..default() }).with_children(|line| {
+                line.spawn((button(), AnalysisButton(None))).with_child(Text::new("Off"));
+                for mode in AnalysisMode::ALL {
+                    line.spawn((button(), AnalysisButton(Some(mode)))).with_child(Text::new(mode.label()));
+                }
+            });
+            panel.spawn(Node { column_gap: Val::Px(4.0), ..default() }).with_children(|line| {
+                line.spawn(Text::new("Pull"));
+                for (axis, label) in ["X", "Y", "Z"].into_iter().enumerate() {
+                    line.spawn((button(), PullButton::Axis(axis))).with_child(Text::new(label));
+                }
+                line.spawn((button(), PullButton::Flip)).with_child(Text::new("Flip"));
+            });
+            panel.spawn((Text::new(""), AnalysisText));
+        });
+}
+
+/// Buttons set the analysis of the selected bodies (or the active body) and
+/// the pull direction; the panel shows the analysis of the first of them
+pub fn analysis_panel_system(
+    analysis_pressed: Query<(&Interaction, &AnalysisButton), Changed<Interaction>>,
+    pull_pressed: Query<(&Interaction, &PullButton), Changed<Interaction>>,
+    (selection, active): (Res<Selection>, Res<ActiveBody>),
+    (properties, settings): (Res<BodyPropertiesCollection>, Res<AnalysisSettings>),
+    (mut per_body, mut pulls): (EventWriter<SetBodyAnalysis>, EventWriter<SetPullDirection>),
+    mut buttons: Query<(&AnalysisButton, &mut BackgroundColor)>,
+    mut texts: Query<&mut Text, With<AnalysisText>>,
+) {
+    let mut bodies = selection.bodies();
+    if bodies.is_empty() {
+        bodies.extend(active.0);
+    }
+    for (interaction, button) in analysis_pressed.iter() {
+        if *interaction == Interaction::Pressed && !bodies.is_empty() {
+            per_body.write(SetBodyAnalysis { bodies: bodies.clone(), mode: button.0 });
+        }
+    }
+    for (interaction, button) in pull_pressed.iter() {
+        if *interaction == Interaction::Pressed {
+            let pull = match button {
+                PullButton::Axis(axis) => Vector3::ith(*axis, 1.0),
+                PullButton::Flip => -settings.pull,
+            };
+            pulls.write(SetPullDirection(pull));
+        }
+    }
+    if !selection.is_changed() && !active.is_changed() && !properties.is_changed() && !settings.is_changed() {
+        return;
+    }
+    let shown = bodies.first().and_then(|b| properties.analysis_of(*b));
+    for (button, mut color) in buttons.iter_mut() {
+        color.0 = if button.0 == shown && !bodies.is_empty() { BUTTON_ACTIVE } else { BUTTON_IDLE };
+    }
+    let analysed: Vec<String> =
+        properties.iter().filter_map(|(_, p)| p.analysis.map(|m| format!("  {}: {}", p.name, m.label()))).collect();
+    let pull = settings.pull;
+    let mut content = format!("Pull ({:.2}, {:.2}, {:.2}), min draft {:.1}°", pull.x, pull.y, pull.z, settings.min_draft.to_degrees());
+    for line in analysed {
+        content.push('\n');
+        content.push_str(&line);
+    }
+    for mut text in texts.iter_mut() {
+        if text.0 != content {
+            text.0 = content.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::cube;
+    use crate::render::display_mode::body_triangles;
+    use crate::render::section::SectionView;
+
+    #[test]
+    fn test_draft_colors_of_a_cube() {
+        let model = cube(2.0);
+        let faces: Vec<usize> = (0..model.faces.len()).collect();
+        let triangles = body_triangles(&model, &faces, &SectionView::default());
+        let settings = AnalysisSettings::default();
+        let colors = analysis_colors(&model, &triangles, AnalysisMode::Draft, &settings);
+        assert_eq!(colors.len(), triangles.positions.len());
+        // The top releases, the bottom is on the other half and the sides have no draft
+        let linear = |c: Color| c.to_linear().to_f32_array();
+        for (triangle, face) in triangles.indices.chunks(3).zip(&triangles.faces) {
+            let normal = model.face_normal(model.face(*face).unwrap()).unwrap();
+            let expected = match normal.y.round() as i32 {
+                1 => DRAFT_POSITIVE,
+                -1 => DRAFT_NEGATIVE,
+                _ => DRAFT_INSUFFICIENT,
+            };
+            assert!(triangle.iter().all(|i| colors[*i as usize] == linear(expected)));
+        }
+
+        // Planes are flat, and each face lies in a single zebra band
+        let flat = analysis_colors(&model, &triangles, AnalysisMode::Curvature, &settings);
+        assert!(flat.iter().all(|c| *c == linear(CURVATURE_FLAT)));
+        assert!((draft_angle(&Vector3::new(1.0, 1.0, 0.0), &Vector3::y()) - 45f64.to_radians()).abs() < 1e-12);
+        assert_eq!(zebra_color(&Vector3::y(), &Vector3::y(), 0.1), ZEBRA_LIGHT);
+        assert_eq!(zebra_color(&Vector3::new(0.0, 1.0, 0.15), &Vector3::y(), 0.1), ZEBRA_DARK);
+    }
+
+    #[test]
+    fn test_analysis_requests() {
+        let mut app = App::new();
+        app.init_resource::<AnalysisSettings>()
+            .init_resource::<BodyPropertiesCollection>()
+            .add_event::<SetBodyAnalysis>()
+            .add_event::<SetPullDirection>()
+            .add_systems(Update, apply_analysis_requests);
+        app.world_mut().resource_mut::<BodyPropertiesCollection>().register(BodyId(0), "Body");
+        app.world_mut().resource_mut::<BodyPropertiesCollection>().register(BodyId(1), "Body");
+        app.world_mut().send_event(SetBodyAnalysis { bodies: vec![BodyId(1)], mode: Some(AnalysisMode::Zebra) });
+        app.world_mut().send_event(SetPullDirection(Vector3::new(0.0, 0.0, -3.0)));
+        app.update();
+        let properties = app.world().resource::<BodyPropertiesCollection>();
+        assert_eq!((properties.analysis_of(BodyId(0)), properties.analysis_of(BodyId(1))), (None, Some(AnalysisMode::Zebra)));
+        assert_eq!(app.world().resource::<AnalysisSettings>().pull, -Vector3::z());
+
+        // A zero pull is refused
+        app.world_mut().send_event(SetPullDirection(Vector3::zeros()));
+        app.world_mut().send_event(SetBodyAnalysis { bodies: vec![BodyId(1)], mode: None });
+        app.update();
+        assert_eq!(app.world().resource::<AnalysisSettings>().pull, -Vector3::z());
+        assert_eq!(app.world().resource::<BodyPropertiesCollection>().analysis_of(BodyId(1)), None);
+    }
+}
@@ -0,0 +1,89 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: render::brep_refs
+//!
+//! Links between spawned entities and the BREP they draw. A body's face mesh
+//! carries `BrepBodyRef` with its body id and `BrepFaceRefs` with the face id
+//! of each of its triangles, so a mesh hit maps back to a face. Going the other
+//! way, `BrepEntities` finds the entity drawing a body.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::model::body::BodyId;
+
+/// The body an entity draws
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BrepBodyRef(pub BodyId);
+
+/// Face id of each triangle of an entity's mesh, in index buffer order
+#[derive(Component, Debug, Clone, Default, PartialEq, Eq)]
+pub struct BrepFaceRefs(pub Vec<usize>);
+
+impl BrepFaceRefs {
+    /// Face a triangle of the mesh belongs to
+    pub fn face(&self, triangle: usize) -> Option<usize> {
+        self.0.get(triangle).copied()
+    }
+
+    /// Triangles of the mesh belonging to a face
+    pub fn triangles(&self, face: usize) -> impl Iterator<Item = usize> + '_ {
+        self.0.iter().enumerate().filter(move |(_, f)| **f == face).map(|(i, _)| i)
+    }
+}
+
+/// Entity drawing each body
+#[derive(Resource, Debug, Default, Clone)]
+pub struct BrepEntities {
+    bodies: HashMap<BodyId, Entity>,
+}
+
+impl BrepEntities {
+    pub fn entity(&self, body: BodyId) -> Option<Entity> {
+        self.bodies.get(&body).copied()
+    }
+}
+
+/// Keep `BrepEntities` in step with the spawned `BrepBodyRef` entities
+pub fn index_brep_entities(
+    refs: Query<(Entity, &BrepBodyRef)>,
+    changed: Query<(), Changed<BrepBodyRef>>,
+    mut removed: RemovedComponents<BrepBodyRef>,
+    mut index: ResMut<BrepEntities>,
+) {
+    if changed.is_empty() && removed.read().count() == 0 {
+        return;
+    }
+    index.bodies = refs.iter().map(|(entity, BrepBodyRef(body))| (*body, entity)).collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entities_are_indexed_by_body() {
+        let mut app = App::new();
+        app.init_resource::<BrepEntities>().add_systems(Update, index_brep_entities);
+        let a = app.world_mut().spawn(BrepBodyRef(BodyId(0))).id();
+        let b = app.world_mut().spawn(BrepBodyRef(BodyId(1))).id();
+        app.update();
+        assert_eq!(app.world().resource::<BrepEntities>().entity(BodyId(1)), Some(b));
+
+        app.world_mut().despawn(b);
+        app.update();
+        let index = app.world().resource::<BrepEntities>();
+        assert_eq!(index.entity(BodyId(0)), Some(a));
+        assert_eq!(index.entity(BodyId(1)), None);
+    }
+
+    #[test]
+    fn test_triangles_map_to_faces() {
+        let refs = BrepFaceRefs(vec![4, 4, 7, 9, 7]);
+        assert_eq!(refs.face(2), Some(7));
+        assert_eq!(refs.face(5), None);
+        assert_eq!(refs.triangles(7).collect::<Vec<_>>(), [2, 4]);
+    }
+}
@@ -0,0 +1,143 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: render::culling
+//!
+//! Culling of body face meshes. Face meshes are spawned with the bounds of
+//! their full-detail mesh, which also enclose their simplified levels, so
+//! Bevy's frustum culling stays correct when the level changes. Occlusion
+//! culling is optional: it turns on Bevy's GPU occlusion culling, with the
+//! depth prepass it needs, on every 3D camera, which pays off in dense
+//! assemblies. Ctrl+F9 shows how many face meshes were drawn and how many were
+//! frustum culled; occlusion results stay on the GPU and are not counted.
+
+use bevy::core_pipeline::prepass::DepthPrepass;
+use bevy::prelude::*;
+use bevy::render::experimental::occlusion_culling::OcclusionCulling;
+
+use crate::input::keyboard::KeyBindings;
+use crate::interaction::state::UiPanel;
+use crate::render::display_mode::BodyFaceMesh;
+
+/// Culling options and the stats overlay
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CullingSettings {
+    /// GPU occlusion culling on the 3D cameras
+    pub occlusion: bool,
+    pub show_stats: bool,
+}
+
+/// Face meshes drawn and culled in the last frame
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CullingStats {
+    pub drawn: usize,
+    pub culled: usize,
+}
+
+/// The bound chords (Ctrl+F9, Ctrl+Shift+F9) toggle the stats overlay and occlusion culling
+pub fn culling_keys(keys: Res<ButtonInput<KeyCode>>, bindings: Res<KeyBindings>, mut settings: ResMut<CullingSettings>) {
+    if bindings.just_pressed("culling_stats", &keys) {
+        settings.show_stats = !settings.show_stats;
+    }
+    if bindings.just_pressed("occlusion_culling", &keys) {
+        settings.occlusion = !settings.occlusion;
+    }
+}
+
+/// Add or remove occlusion culling on the 3D cameras (and cameras spawned since)
+pub fn apply_occlusion_culling(mut commands: Commands, settings: Res<CullingSettings>, cameras: Query<(Entity, Ref<Camera3d>)>) {
+    for (entity, camera) in cameras.iter() {
+        if !settings.is_changed() && !camera.is_added() {
+            continue;
+        }
+        if settings.occlusion {
+            commands.entity(entity).insert((DepthPrepass, OcclusionCulling));
+        } else {
+            commands.entity(entity).remove::<(DepthPrepass, OcclusionCulling)>();
+        }
+    }
+}
+
+/// Count the face meshes visible to some view after visibility checks
+pub fn count_culled_meshes(meshes: Query<&ViewVisibility, With<BodyFaceMesh>>, mut stats: ResMut<CullingStats>) {
+    let drawn = meshes.iter().filter(|v| v.get()).count();
+    let counted = CullingStats { drawn, culled: meshes.iter().count() - drawn };
+    if *stats != counted {
+        *stats = counted;
+    }
+}
+
+/// Text of the stats overlay
+#[derive(Component, Debug)]
+pub struct CullingStatsText;
+
+/// Spawn the stats overlay (top middle, shown while `show_stats` is on)
+pub fn spawn_culling_stats(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(8.0),
+                left: Val::Percent(45.0),
+                padding: UiRect::all(Val::Px(4.0)),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            UiPanel("culling"),
+        ))
+        .with_child((Text::new(""), TextFont { font_size: 13.0, ..default() }, CullingStatsText));
+}
+
+/// Show the overlay while it is on, with the latest counts
+pub fn culling_stats_panel(
+    settings: Res<CullingSettings>,
+    stats: Res<CullingStats>,
+    mut panels: Query<&mut Node, With<UiPanel>>,
+    mut texts: Query<(&mut Text, &ChildOf), With<CullingStatsText>>,
+) {
+    if !settings.is_changed() && !stats.is_changed() {
+        return;
+    }
+    let Ok((mut text, parent)) = texts.single_mut() else { return };
+    if let Ok(mut node) = panels.get_mut(parent.parent()) {
+        node.display = if settings.show_stats { Display::Flex } else { Display::None };
+    }
+    text.0 = format!(
+        "Meshes drawn {}  frustum culled {}  occlusion {}",
+        stats.drawn,
+        stats.culled,
+        if settings.occlusion { "on" } else { "off" }
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_occlusion_culling_follows_settings() {
+        let mut app = App::new();
+        app.init_resource::<CullingSettings>().add_systems(Update, apply_occlusion_culling);
+        let camera = app.world_mut().spawn(Camera3d::default()).id();
+        app.update();
+        assert!(app.world().get::<OcclusionCulling>(camera).is_none());
+
+        app.world_mut().resource_mut::<CullingSettings>().occlusion = true;
+        app.update();
+        assert!(app.world().get::<OcclusionCulling>(camera).is_some() && app.world().get::<DepthPrepass>(camera).is_some());
+    }
+
+    #[test]
+    fn test_counts_drawn_and_culled_meshes() {
+        let mut app = App::new();
+        app.init_resource::<CullingStats>().add_systems(Update, count_culled_meshes);
+        let mut visible = ViewVisibility::default();
+        visible.set();
+        app.world_mut().spawn((BodyFaceMesh, visible));
+        app.world_mut().spawn((BodyFaceMesh, ViewVisibility::default()));
+        app.world_mut().spawn((BodyFaceMesh, ViewVisibility::default()));
+        app.update();
+        assert_eq!(*app.world().resource::<CullingStats>(), CullingStats { drawn: 1, culled: 2 });
+    }
+}
@@ -0,0 +1,520 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: render::display_mode
+//!
+//! How bodies are drawn: wireframe (edges only), shaded (faces only), shaded
+//! with an edge overlay, or hidden line (edges with the faces filled in the
+//! background color, so the depth buffer hides edges behind them). The mode is
+//! set globally and can be overridden per body. Faces are one mesh entity per
+//! body, placed at the body's center so transparent bodies sort back to front,
+//! and rebuilt whenever the model or its display settings change. They are
+//! tessellated on the async compute pool, and a body keeps its old mesh until
+//! the new one is ready. Edges stay gizmo lines drawn by `render::edge_overlay`. Face meshes also get the
+//! simplified levels of `render::lod`, except on bodies under a surface
+//! analysis, whose faces carry `render::analysis` vertex colors instead of
+//! their material. Backquote cycles the global mode,
+//! Shift+Backquote the mode of the selected bodies.
+
+use std::collections::BTreeMap;
+
+use bevy::asset::RenderAssetUsages;
+use bevy::ecs::system::SystemParam;
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::primitives::Aabb;
+use bevy::tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task};
+use nalgebra::{Point3, Vector3};
+
+use crate::input::keyboard::KeyBindings;
+use crate::interaction::state::ActiveBody;
+use crate::interaction::selection::Selection;
+use crate::model::body::BodyId;
+use crate::model::brep::tessellate::tessellate_face;
+use crate::model::brep_model::{na_vec3_to_bevy, BrepModel};
+use crate::model::changes::BodyChanges;
+use crate::model::groups::BodyGroups;
+use crate::model::layers::LayerManager;
+use crate::model::properties::BodyPropertiesCollection;
+pub use crate::model::properties::DisplayMode;
+use crate::render::analysis::{analysis_colors, AnalysisMode, AnalysisSettings};
+use crate::render::brep_refs::{BrepBodyRef, BrepFaceRefs};
+use crate::render::edge_overlay::EdgeOverlayGizmos;
+use crate::render::exploded::ExplodedView;
+use crate::render::lod::{BodyLods, LodSettings};
+use crate::render::materials::face_material;
+use crate::render::section::SectionView;
+use crate::telemetry::crash::journal;
+use crate::telemetry::usage::UsageStats;
+
+/// Gizmo depth bias keeping edges in front of the faces they bound
+const EDGE_DEPTH_BIAS: f32 = -1e-4;
+/// Fill color of hidden-line faces when the app has no clear color
+pub const HIDDEN_LINE_FILL: Color = Color::srgb(0.1, 0.1, 0.1);
+/// Model units one texture repeat spans on a face
+pub const TEXTURE_REPEAT: f64 = 100.0;
+
+/// Global display mode; per-body overrides live in `BodyProperties::display`
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DisplaySettings {
+    pub mode: DisplayMode,
+}
+
+impl DisplaySettings {
+    /// Mode a body is drawn in
+    pub fn mode_of(&self, body: BodyId, properties: &BodyPropertiesCollection) -> DisplayMode {
+        properties.get(body).and_then(|p| p.display).unwrap_or(self.mode)
+    }
+
+    /// Whether any body is drawn in a different mode from the global one
+    pub fn has_overrides(&self, properties: &BodyPropertiesCollection) -> bool {
+        properties.iter().any(|(_, p)| p.display.is_some_and(|m| m != self.mode))
+    }
+}
+
+/// Triangles of a body's faces with flat normals and planar texture coordinates
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BodyTriangles {
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub uvs: Vec<[f32; 2]>,
+    pub indices: Vec<u32>,
+    /// Face id of each triangle
+    pub faces: Vec<usize>,
+}
+
+impl BodyTriangles {
+    /// Render mesh; tangents are only needed for normal maps
+    pub fn into_mesh(self, tangents: bool) -> Mesh {
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default());
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, self.positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, self.normals);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, self.uvs);
+        mesh.insert_indices(Indices::U32(self.indices));
+        if tangents {
+            if let Err(err) = mesh.generate_tangents() {
+                warn!("Could not generate tangents for a normal map: {}", err);
+            }
+        }
+        mesh
+    }
+
+    /// Move the positions so the middle of their bounds is the origin and
+    /// return that middle. A mesh placed there is sorted among transparent
+    /// meshes by the body's own position rather than the world origin.
+    pub fn recenter(&mut self) -> Vec3 {
+        let Some(first) = self.positions.first() else { return Vec3::ZERO };
+        let (min, max) = self.positions.iter().fold((Vec3::from(*first), Vec3::from(*first)), |(min, max), p| {
+            (min.min(Vec3::from(*p)), max.max(Vec3::from(*p)))
+        });
+        let center = (min + max) / 2.0;
+        for p in self.positions.iter_mut() {
+            *p = (Vec3::from(*p) - center).to_array();
+        }
+        center
+    }
+
+    /// Bounds of the positions, for frustum culling. Simplified levels snap
+    /// vertices inside these bounds, so they hold for every level of detail.
+    pub fn bounds(&self) -> Option<Aabb> {
+        Aabb::enclosing(self.positions.iter().map(|p| Vec3::from(*p)))
+    }
+}
+
+/// Triangles of a body's faces. Texture coordinates are the face's plane
+/// coordinates, one texture repeat every `TEXTURE_REPEAT` model units, so
+/// textures line up across faces in the same plane. Triangles in front of an
+/// active section plane are left out.
+pub fn body_triangles(model: &BrepModel, faces: &[usize], section: &SectionView) -> BodyTriangles {
+    let mut out = BodyTriangles::default();
+    for face in faces.iter().filter_map(|i| model.face(*i)) {
+        let (Some(mesh), Some(plane)) = (tessellate_face(model, face), model.face_plane(face)) else { continue };
+        let base = out.positions.len() as u32;
+        out.positions.extend(mesh.positions.iter().map(|p| na_vec3_to_bevy(p).to_array()));
+        out.normals.extend(std::iter::repeat_n(na_vec3_to_bevy(&mesh.normal).to_array(), mesh.positions.len()));
+        out.uvs.extend(mesh.positions.iter().map(|p| {
+            let uv = plane.project_2d(&Point3::from(*p)) / TEXTURE_REPEAT;
+            [uv.x as f32, uv.y as f32]
+        }));
+        for triangle in &mesh.triangles {
+            let centroid = triangle.iter().map(|i| mesh.positions[*i]).sum::<Vector3<f64>>() / 3.0;
+            if !section.clips(&centroid) {
+                out.indices.extend(triangle.iter().map(|i| base + *i as u32));
+                out.faces.push(face.id);
+            }
+        }
+    }
+    out
+}
+
+/// A body's face mesh built on the async compute pool
+struct BuiltBodyMesh {
+    mesh: Mesh,
+    /// Triangles the simplified levels start from, when they are wanted
+    lod_source: Option<BodyTriangles>,
+    faces: BrepFaceRefs,
+    bounds: Option<Aabb>,
+    center: Vec3,
+}
+
+/// Tessellate a body, color its faces for a surface analysis and build its
+/// render mesh; `None` when nothing of it is left to draw
+fn build_body_mesh(
+    body: &BrepModel,
+    faces: &[usize],
+    section: &SectionView,
+    analysis: Option<(AnalysisMode, AnalysisSettings)>,
+    tangents: bool,
+    lod: bool,
+) -> Option<BuiltBodyMesh> {
+    let mut triangles = body_triangles(body, faces, section);
+    if triangles.indices.is_empty() {
+        return None;
+    }
+    let colors = analysis.map(|(mode, settings)| analysis_colors(body, &triangles, mode, &settings));
+    let center = triangles.recenter();
+    // Simplified levels would drop the analysis colors
+    let lod_source = (lod && colors.is_none()).then(|| triangles.clone());
+    let faces = BrepFaceRefs(std::mem::take(&mut triangles.faces));
+    let bounds = triangles.bounds();
+    let mut mesh = triangles.into_mesh(tangents);
+    if let Some(colors) = colors {
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    }
+    Some(BuiltBodyMesh { mesh, lod_source, faces, bounds, center })
+}
+
+/// A face mesh being built, with how the body is drawn
+struct PendingBodyMesh {
+    task: Task<Option<BuiltBodyMesh>>,
+    mode: DisplayMode,
+    tangents: bool,
+}
+
+/// Face meshes being built on the async compute pool, by body
+#[derive(Resource, Default)]
+pub struct PendingBodyMeshes {
+    meshes: BTreeMap<BodyId, PendingBodyMesh>,
+}
+
+/// Marks the face mesh of a body; the body is its `BrepBodyRef`
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BodyFaceMesh;
+
+/// Document resources the face meshes are built from
+#[derive(SystemParam)]
+pub struct DisplayedModel<'w> {
+    model: Res<'w, BrepModel>,
+    properties: Res<'w, BodyPropertiesCollection>,
+    groups: Res<'w, BodyGroups>,
+    layers: Res<'w, LayerManager>,
+    display: Res<'w, DisplaySettings>,
+    section: Option<Res<'w, SectionView>>,
+    exploded: Option<Res<'w, ExplodedView>>,
+    clear_color: Option<Res<'w, ClearColor>>,
+    changes: Option<Res<'w, BodyChanges>>,
+    lod: Option<Res<'w, LodSettings>>,
+    analysis: Option<Res<'w, AnalysisSettings>>,
+}
+
+impl DisplayedModel<'_> {
+    /// Whether the face geometry itself may have changed
+    fn geometry_changed(&self) -> bool {
+        self.bodies_edited() || self.view_changed()
+    }
+
+    /// Whether body geometry was edited, per `BodyChanges` when it is tracked
+    fn bodies_edited(&self) -> bool {
+        match &self.changes {
+            Some(changes) => changes.is_changed() && changes.any(),
+            None => self.model.is_changed(),
+        }
+    }
+
+    /// Whether a setting that changes the faces of every body changed
+    fn view_changed(&self) -> bool {
+        self.display.is_changed()
+            || self.section.as_ref().is_some_and(|s| s.is_changed())
+            || self.exploded.as_ref().is_some_and(|e| e.is_changed())
+            || self.lod.as_ref().is_some_and(|l| l.is_changed())
+            || self.analysis.as_ref().is_some_and(|a| a.is_changed())
+    }
+
+    /// Whether which bodies have faces, or how they are drawn, may have changed
+    fn bodies_changed(&self) -> bool {
+        self.properties.is_changed() || self.groups.is_changed() || self.layers.is_changed()
+    }
+
+    /// Face mode of each body, whether it is normal mapped and its surface
+    /// analysis, `None` for bodies drawn without faces
+    fn drawn_modes(&self) -> BTreeMap<BodyId, Option<(DisplayMode, bool, Option<AnalysisMode>)>> {
+        self.model
+            .body_ids()
+            .into_iter()
+            .map(|body| {
+                let mode = self.display.mode_of(body, &self.properties);
+                let visible = self.groups.is_body_visible(body, &self.properties) && self.layers.is_body_visible(body, &self.properties);
+                let normal_mapped = self.properties.get(body).is_some_and(|p| p.material.normal_texture.is_some());
+                (body, (mode.shows_faces() && visible).then_some((mode, normal_mapped, self.properties.analysis_of(body))))
+            })
+            .collect()
+    }
+}
+
+/// Start rebuilding the body face meshes when the model or display settings
+/// change; `finish_body_meshes` swaps them in. Model edits rebuild only the
+/// bodies `BodyChanges` reports dirty, unless an exploded view moves every body
+/// with them. Material-only changes are left to
+/// `render::materials::update_body_materials`.
+pub fn sync_body_meshes(
+    mut commands: Commands,
+    view: DisplayedModel,
+    mut pending: ResMut<PendingBodyMeshes>,
+    old: Query<(Entity, &BrepBodyRef), With<BodyFaceMesh>>,
+    mut drawn: Local<BTreeMap<BodyId, Option<(DisplayMode, bool, Option<AnalysisMode>)>>>,
+) {
+    let geometry_changed = view.geometry_changed();
+    if !geometry_changed && !view.bodies_changed() {
+        return;
+    }
+    let modes = view.drawn_modes();
+    if !geometry_changed && modes == *drawn {
+        return;
+    }
+    let rebuild_all = view.view_changed() || view.changes.is_none() || view.exploded.as_ref().is_some_and(|e| e.progress > 0.0);
+    let rebuild = |body: BodyId| {
+        rebuild_all
+            || modes.get(&body) != drawn.get(&body)
+            || (view.bodies_edited() && view.changes.as_ref().is_some_and(|c| c.is_dirty(body)))
+    };
+    // Bodies that are gone or drawn without faces lose their mesh now; the
+    // others keep theirs until the new one is built
+    let shown = |body: &BodyId| modes.get(body).copied().flatten().is_some();
+    for (entity, BrepBodyRef(body)) in old.iter() {
+        if !shown(body) {
+            commands.entity(entity).despawn();
+        }
+    }
+    pending.meshes.retain(|body, _| shown(body));
+    let section = view.section.as_deref().cloned().unwrap_or_default();
+    let analysis_settings = view.analysis.as_deref().cloned().unwrap_or_default();
+    let lod = view.lod.as_ref().is_some_and(|l| l.enabled);
+    let pool = AsyncComputeTaskPool::get();
+    for (body, faces) in view.model.bodies() {
+        if !rebuild(body) {
+            continue;
+        }
+        let Some((mode, normal_mapped, analysis)) = modes.get(&body).copied().flatten() else { continue };
+        let tangents = normal_mapped && mode != DisplayMode::HiddenLine && analysis.is_none();
+        let shape = view.model.extract_faces(&faces);
+        let section = section.clone();
+        let analysis = analysis.map(|a| (a, analysis_settings.clone()));
+        let task = pool.spawn(async move { build_body_mesh(&shape, &faces, &section, analysis, tangents, lod) });
+        // A build still running for the body is dropped, which cancels it
+        pending.meshes.insert(body, PendingBodyMesh { task, mode, tangents });
+    }
+    *drawn = modes;
+}
+
+/// Swap in the face meshes whose build finished, replacing the body's old mesh
+pub fn finish_body_meshes(
+    mut commands: Commands,
+    view: DisplayedModel,
+    mut pending: ResMut<PendingBodyMeshes>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    (mut materials, asset_server): (ResMut<Assets<StandardMaterial>>, Res<AssetServer>),
+    old: Query<(Entity, &BrepBodyRef), With<BodyFaceMesh>>,
+) {
+    let mut finished = Vec::new();
+    for (body, build) in pending.meshes.iter_mut() {
+        if let Some(built) = block_on(future::poll_once(&mut build.task)) {
+            finished.push((*body, build.mode, build.tangents, built));
+        }
+    }
+    if finished.is_empty() {
+        return;
+    }
+    let offsets = view.exploded.as_ref().map(|e| e.vertex_offsets(&view.model)).unwrap_or_default();
+    let fill = view.clear_color.as_ref().map_or(HIDDEN_LINE_FILL, |c| c.0);
+    for (body, mode, tangents, built) in finished {
+        pending.meshes.remove(&body);
+        for (entity, _) in old.iter().filter(|(_, r)| r.0 == body) {
+            commands.entity(entity).despawn();
+        }
+        let Some(built) = built else { continue };
+        let mesh = meshes.add(built.mesh);
+        let lods = built.lod_source.map(|full| BodyLods::new(mesh.clone(), &full, tangents, &mut meshes));
+        let material = face_material(body, mode, fill, &view.properties, &view.groups, &asset_server);
+        // Exploded bodies move as a whole, so any vertex gives the body's offset
+        let vertices = view.model.body_faces(body).map(|faces| view.model.shell_vertex_ids(&faces)).unwrap_or_default();
+        let offset = vertices.first().and_then(|v| offsets.get(v)).map_or(Vec3::ZERO, na_vec3_to_bevy);
+        let transform = Transform::from_translation(built.center + offset);
+        let mut entity = commands.spawn((Mesh3d(mesh), MeshMaterial3d(materials.add(material)), transform, BodyFaceMesh, BrepBodyRef(body), built.faces));
+        // Bevy only computes bounds for meshes without them, which would keep the first level's after a swap
+        if let Some(bounds) = built.bounds {
+            entity.insert(bounds);
+        }
+        if let Some(lods) = lods {
+            entity.insert(lods);
+        }
+    }
+}
+
+/// Pull edges slightly towards the camera while faces are drawn, so edges on a face are not hidden by it
+pub fn apply_edge_depth_bias(display: Res<DisplaySettings>, properties: Res<BodyPropertiesCollection>, mut config_store: ResMut<GizmoConfigStore>) {
+    if !display.is_changed() && !properties.is_changed() {
+        return;
+    }
+    let faces = display.mode.shows_faces() || properties.iter().any(|(_, p)| p.display.is_some_and(|m| m.shows_faces()));
+    let (config, _) = config_store.config_mut::<EdgeOverlayGizmos>();
+    config.depth_bias = if faces { EDGE_DEPTH_BIAS } else { 0.0 };
+}
+
+/// Request to change the global display mode
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetDisplayMode(pub DisplayMode);
+
+/// Request to override the display mode of bodies; `None` follows the global mode
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct SetBodyDisplayMode {
+    pub bodies: Vec<BodyId>,
+    pub mode: Option<DisplayMode>,
+}
+
+/// The display_mode binding (Backquote) cycles the global mode; body_display_mode (Shift+Backquote)
+/// cycles the selected bodies (or the active body), going back to the global mode after the last one
+pub fn display_mode_keys(
+    (keys, bindings): (Res<ButtonInput<KeyCode>>, Res<KeyBindings>),
+    display: Res<DisplaySettings>,
+    (properties, selection, active): (Res<BodyPropertiesCollection>, Res<Selection>, Res<ActiveBody>),
+    mut global: EventWriter<SetDisplayMode>,
+    mut per_body: EventWriter<SetBodyDisplayMode>,
+) {
+    if bindings.just_pressed("display_mode", &keys) {
+        global.write(SetDisplayMode(display.mode.next()));
+        return;
+    }
+    if !bindings.just_pressed("body_display_mode", &keys) {
+        return;
+    }
+    let mut bodies = selection.bodies();
+    if bodies.is_empty() {
+        bodies.extend(active.0);
+    }
+    let Some(first) = bodies.first() else { return };
+    let mode = match properties.get(*first).and_then(|p| p.display) {
+        None => Some(display.mode.next()),
+        Some(current) if current.next() == display.mode => None,
+        Some(current) => Some(current.next()),
+    };
+    per_body.write(SetBodyDisplayMode { bodies, mode });
+}
+
+/// Apply display mode requests
+pub fn apply_display_mode_requests(
+    mut global: EventReader<SetDisplayMode>,
+    mut per_body: EventReader<SetBodyDisplayMode>,
+    mut display: ResMut<DisplaySettings>,
+    mut properties: ResMut<BodyPropertiesCollection>,
+    mut usage: Option<ResMut<UsageStats>>,
+) {
+    for SetDisplayMode(mode) in global.read() {
+        let start = Instant::now();
+        journal(format!("display mode {}", mode.label()));
+        display.mode = *mode;
+        if let Some(usage) = usage.as_mut() {
+            usage.record("display_mode", start.elapsed());
+        }
+    }
+    for SetBodyDisplayMode { bodies, mode } in per_body.read() {
+        let start = Instant::now();
+        journal(format!("body display mode {:?} {}", bodies, mode.map_or("global", |m| m.label())));
+        for body in bodies {
+            match properties.get_mut(*body) {
+                Some(props) => props.display = *mode,
+                None => warn!("No body {} to set the display mode of", body.0),
+            }
+        }
+        if let Some(usage) = usage.as_mut() {
+            usage.record("body_display_mode", start.elapsed());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::cube;
+    use crate::model::brep::topology::plane::Plane;
+
+    #[test]
+    fn test_body_triangles_and_section() {
+        let model = cube(2.0);
+        let faces: Vec<usize> = (0..model.faces.len()).collect();
+        let triangles = body_triangles(&model, &faces, &SectionView::default());
+        assert_eq!(triangles.indices.len(), 36);
+        assert_eq!(triangles.faces.iter().filter(|f| **f == model.faces[3].id).count(), 2);
+        assert_eq!(triangles.positions.len(), triangles.normals.len());
+        assert_eq!(triangles.positions.len(), triangles.uvs.len());
+        // Texture coordinates span the face size in texture repeats
+        let span = triangles.uvs[..4].iter().map(|uv| uv[0]).fold(f32::NEG_INFINITY, f32::max)
+            - triangles.uvs[..4].iter().map(|uv| uv[0]).fold(f32::INFINITY, f32::min);
+        assert!((span - (2.0 / TEXTURE_REPEAT) as f32).abs() < 1e-6);
+
+        // A section through the middle leaves out everything on its far side
+        let section = SectionView { enabled: true, plane: Plane::xy(), ..Default::default() };
+        let clipped = body_triangles(&model, &faces, &section).indices;
+        assert!(!clipped.is_empty() && clipped.len() < triangles.indices.len());
+    }
+
+    #[test]
+    fn test_body_triangles_look_faces_up_by_id() {
+        // Faces of later bodies, or after a body was removed, are not at their id's position
+        let mut model = cube(2.0);
+        model.faces.iter_mut().for_each(|f| f.id += 10);
+        let faces: Vec<usize> = model.faces.iter().map(|f| f.id).collect();
+        let triangles = body_triangles(&model, &faces, &SectionView::default());
+        assert_eq!(triangles.indices.len(), 36);
+        assert!(triangles.faces.iter().all(|f| faces.contains(f)));
+    }
+
+    #[test]
+    fn test_recenter_moves_origin_to_body_center() {
+        let mut model = cube(2.0);
+        for v in model.vertices.iter_mut() {
+            v.position.x += 10.0;
+        }
+        let faces: Vec<usize> = (0..model.faces.len()).collect();
+        let mut triangles = body_triangles(&model, &faces, &SectionView::default());
+        assert_eq!(triangles.recenter(), Vec3::new(10.0, 0.0, 0.0));
+        assert!(triangles.positions.iter().all(|p| p.iter().all(|c| c.abs() == 1.0)));
+        assert_eq!(BodyTriangles::default().recenter(), Vec3::ZERO);
+        let bounds = triangles.bounds().unwrap();
+        assert_eq!((Vec3::from(bounds.center), Vec3::from(bounds.half_extents)), (Vec3::ZERO, Vec3::ONE));
+        assert!(BodyTriangles::default().bounds().is_none());
+    }
+
+    #[test]
+    fn test_display_mode_overrides() {
+        let mut app = App::new();
+        app.init_resource::<DisplaySettings>()
+            .init_resource::<BodyPropertiesCollection>()
+            .add_event::<SetDisplayMode>()
+            .add_event::<SetBodyDisplayMode>()
+            .add_systems(Update, apply_display_mode_requests);
+        app.world_mut().resource_mut::<BodyPropertiesCollection>().register(BodyId(0), "Body");
+        app.world_mut().resource_mut::<BodyPropertiesCollection>().register(BodyId(1), "Body");
+        app.world_mut().send_event(SetDisplayMode(DisplayMode::HiddenLine));
+        app.world_mut().send_event(SetBodyDisplayMode { bodies: vec![BodyId(1)], mode: Some(DisplayMode::Shaded) });
+        app.update();
+
+        let display = *app.world().resource::<DisplaySettings>();
+        let properties = app.world().resource::<BodyPropertiesCollection>();
+        assert_eq!(display.mode_of(BodyId(0), properties), DisplayMode::HiddenLine);
+        assert_eq!(display.mode_of(BodyId(1), properties), DisplayMode::Shaded);
+        assert!(display.has_overrides(properties));
+        assert!(!DisplayMode::Shaded.shows_edges() && !DisplayMode::Wireframe.shows_faces());
+        assert_eq!(DisplayMode::HiddenLine.next(), DisplayMode::Wireframe);
+    }
+}
@@ -0,0 +1,100 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: render::edge_display
+//!
+//! How BREP edges are drawn: tangent (smooth) edges can be shown, dashed or hidden,
+//! as CAD viewers usually do for filleted bodies.
+
+use bevy::prelude::*;
+
+use crate::input::keyboard::KeyBindings;
+
+/// Display style for tangent edges
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TangentEdgeMode {
+    #[default]
+    Solid,
+    Dashed,
+    Hidden,
+}
+
+impl TangentEdgeMode {
+    /// Next mode in the Solid -> Dashed -> Hidden cycle
+    pub fn next(self) -> Self {
+        match self {
+            TangentEdgeMode::Solid => TangentEdgeMode::Dashed,
+            TangentEdgeMode::Dashed => TangentEdgeMode::Hidden,
+            TangentEdgeMode::Hidden => TangentEdgeMode::Solid,
+        }
+    }
+}
+
+/// Edge rendering settings resource
+#[derive(Resource, Debug, Clone)]
+pub struct EdgeDisplaySettings {
+    pub tangent_mode: TangentEdgeMode,
+    /// Edges whose adjacent face normals differ by at most this angle (radians) are tangent
+    pub tangent_angle: f64,
+    /// Length of each dash (and gap) when dashing
+    pub dash_length: f32,
+}
+
+impl Default for EdgeDisplaySettings {
+    fn default() -> Self {
+        Self {
+            tangent_mode: TangentEdgeMode::Solid,
+            tangent_angle: 1f64.to_radians(),
+            dash_length: 4.0,
+        }
+    }
+}
+
+/// Split the segment a-b into the visible pieces of a dash pattern
+pub fn dash_segments(a: Vec3, b: Vec3, dash_length: f32) -> Vec<(Vec3, Vec3)> {
+    let length = a.distance(b);
+    if dash_length <= 0.0 || length <= dash_length {
+        return vec![(a, b)];
+    }
+    let dir = (b - a) / length;
+    let mut out = Vec::new();
+    let mut t = 0.0;
+    while t < length {
+        let end = (t + dash_length).min(length);
+        out.push((a + dir * t, a + dir * end));
+        t += 2.0 * dash_length;
+    }
+    out
+}
+
+/// Draw a dashed line with gizmos
+pub fn dashed_line<Config: GizmoConfigGroup>(gizmos: &mut Gizmos<'_, '_, Config>, a: Vec3, b: Vec3, dash_length: f32, color: Color) {
+    for (p, q) in dash_segments(a, b, dash_length) {
+        gizmos.line(p, q, color);
+    }
+}
+
+/// Cycle the tangent edge mode with the edge_display binding (E)
+pub fn edge_display_keys(keys: Res<ButtonInput<KeyCode>>, bindings: Res<KeyBindings>, mut settings: ResMut<EdgeDisplaySettings>) {
+    if bindings.just_pressed("edge_display", &keys) {
+        settings.tangent_mode = settings.tangent_mode.next();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dash_segments() {
+        let dashes = dash_segments(Vec3::ZERO, Vec3::new(10.0, 0.0, 0.0), 2.0);
+        assert_eq!(dashes.len(), 3);
+        assert_eq!(dashes[1], (Vec3::new(4.0, 0.0, 0.0), Vec3::new(6.0, 0.0, 0.0)));
+        assert_eq!(dash_segments(Vec3::ZERO, Vec3::X, 2.0).len(), 1);
+    }
+
+    #[test]
+    fn test_mode_cycle() {
+        assert_eq!(TangentEdgeMode::Solid.next().next().next(), TangentEdgeMode::Solid);
+    }
+}
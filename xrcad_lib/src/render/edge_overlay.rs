@@ -0,0 +1,248 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: render::edge_overlay
+//!
+//! BREP edges drawn over the shaded faces as lines of constant screen width.
+//! Which edges show comes from the topology: feature edges (sharp creases and
+//! open boundaries) always, tangent edges as set in `EdgeDisplaySettings`, and
+//! tangent edges on the silhouette (between a face turned towards the camera
+//! and one turned away) always, so smooth bodies keep their outline. Each
+//! edge's adjacent face normals are cached in `EdgeTopology` and refreshed when
+//! the model changes. Selected and hovered edges take the highlight colors,
+//! and are drawn even on bodies whose display mode has no edges. Edges of
+//! ghosted bodies are drawn faint.
+
+use std::collections::HashMap;
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use crate::color::WHITE;
+use crate::interaction::picking::PickState;
+use crate::interaction::selection::Selection;
+use crate::model::brep::topology::edge::EdgeKind;
+use crate::model::brep_model::{na_vec3_to_bevy, BrepModel};
+use crate::model::body::BodyId;
+use crate::model::groups::BodyGroups;
+use crate::model::layers::LayerManager;
+use crate::model::properties::BodyPropertiesCollection;
+use crate::render::display_mode::DisplaySettings;
+use crate::render::edge_display::{dashed_line, EdgeDisplaySettings, TangentEdgeMode};
+use crate::render::exploded::ExplodedView;
+use crate::render::ghosting::ghost_color;
+use crate::render::hilighting::{item_edges, HOVER_COLOR, SELECTED_COLOR};
+use crate::render::section::SectionView;
+
+/// Gizmo group of the edge overlay, so its lines get their own width and depth bias
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct EdgeOverlayGizmos;
+
+/// Edge overlay appearance
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct EdgeOverlaySettings {
+    /// Line width (pixels)
+    pub width: f32,
+}
+
+impl Default for EdgeOverlaySettings {
+    fn default() -> Self {
+        Self { width: 1.5 }
+    }
+}
+
+/// An edge with what the overlay needs to know about its faces
+#[derive(Debug, Clone, PartialEq)]
+pub struct OverlayEdge {
+    pub id: usize,
+    pub vertices: (usize, usize),
+    pub kind: EdgeKind,
+    /// Normals of the faces meeting at the edge
+    pub normals: Vec<Vec3>,
+}
+
+/// Edges of the model classified for display
+#[derive(Resource, Debug, Default, Clone, PartialEq)]
+pub struct EdgeTopology {
+    pub edges: Vec<OverlayEdge>,
+}
+
+impl EdgeTopology {
+    /// Classify every edge from the faces using it in a single pass over the faces
+    pub fn build(model: &BrepModel, tangent_angle: f64) -> Self {
+        let mut normals: HashMap<usize, Vec<Vec3>> = HashMap::new();
+        for face in &model.faces {
+            let Some(normal) = model.face_normal(face) else { continue };
+            let mut edges = model.face_edge_ids(face);
+            edges.sort_unstable();
+            edges.dedup();
+            for edge in edges {
+                normals.entry(edge).or_default().push(na_vec3_to_bevy(&normal));
+            }
+        }
+        let edges = model
+            .edges
+            .iter()
+            .map(|edge| {
+                let normals = normals.remove(&edge.id).unwrap_or_default();
+                let dihedral = match normals[..] {
+                    [a, b] => Some(a.dot(b).clamp(-1.0, 1.0).acos() as f64),
+                    _ => None,
+                };
+                OverlayEdge { id: edge.id, vertices: edge.vertices, kind: EdgeKind::from_dihedral(dihedral, tangent_angle), normals }
+            })
+            .collect();
+        Self { edges }
+    }
+}
+
+/// True when one face at the edge is turned towards a viewer looking along
+/// `view_dir` and the other away
+pub fn is_silhouette(normals: &[Vec3], view_dir: Vec3) -> bool {
+    match normals {
+        [a, b] => (a.dot(view_dir) < 0.0) != (b.dot(view_dir) < 0.0),
+        _ => false,
+    }
+}
+
+/// Highlight color of each selected or hovered edge; the selection wins
+pub fn highlight_colors(model: &BrepModel, selection: &Selection, pick: Option<&PickState>) -> HashMap<usize, Color> {
+    let mut colors = HashMap::new();
+    let hover = pick.and_then(|p| p.hover.as_ref()).and_then(|h| h.selection_item(selection.filter));
+    if let Some(item) = hover.filter(|i| !selection.contains(*i)) {
+        colors.extend(item_edges(model, &item).into_iter().map(|e| (e, HOVER_COLOR)));
+    }
+    for item in &selection.items {
+        colors.extend(item_edges(model, item).into_iter().map(|e| (e, SELECTED_COLOR)));
+    }
+    colors
+}
+
+/// Refresh the cached edge classification when the model or the tangent angle changes
+pub fn update_edge_topology(model: Res<BrepModel>, edge_display: Option<Res<EdgeDisplaySettings>>, mut topology: ResMut<EdgeTopology>) {
+    let angle_changed = edge_display.as_ref().is_some_and(|e| e.is_changed());
+    if !model.is_changed() && !angle_changed && !topology.is_added() {
+        return;
+    }
+    let tangent_angle = edge_display.as_deref().cloned().unwrap_or_default().tangent_angle;
+    *topology = EdgeTopology::build(&model, tangent_angle);
+}
+
+/// Apply the overlay width
+pub fn configure_edge_overlay(settings: Res<EdgeOverlaySettings>, mut config_store: ResMut<GizmoConfigStore>) {
+    if !settings.is_changed() {
+        return;
+    }
+    let (config, _) = config_store.config_mut::<EdgeOverlayGizmos>();
+    config.line.width = settings.width;
+}
+
+/// Document state the overlay draws from
+#[derive(SystemParam)]
+pub struct OverlaySource<'w> {
+    model: Res<'w, BrepModel>,
+    topology: Res<'w, EdgeTopology>,
+    properties: Option<Res<'w, BodyPropertiesCollection>>,
+    groups: Option<Res<'w, BodyGroups>>,
+    layers: Option<Res<'w, LayerManager>>,
+    display: Option<Res<'w, DisplaySettings>>,
+    section: Option<Res<'w, SectionView>>,
+    exploded: Option<Res<'w, ExplodedView>>,
+}
+
+/// Draw the edges of shown bodies whose display mode has edges
+pub fn render_edge_overlay(
+    mut gizmos: Gizmos<EdgeOverlayGizmos>,
+    source: OverlaySource,
+    edge_display: Option<Res<EdgeDisplaySettings>>,
+    (selection, pick): (Option<Res<Selection>>, Option<Res<PickState>>),
+    cameras: Query<(&GlobalTransform, Option<&Projection>), With<Camera3d>>,
+) {
+    let model = &source.model;
+    let edge_display = edge_display.as_deref().cloned().unwrap_or_default();
+    let section = source.section.as_deref().cloned().unwrap_or_default();
+    let display = source.display.as_deref().copied().unwrap_or_default();
+    let (no_groups, no_layers, no_properties) = (BodyGroups::default(), LayerManager::default(), BodyPropertiesCollection::default());
+    let groups = source.groups.as_deref().unwrap_or(&no_groups);
+    let layers = source.layers.as_deref().unwrap_or(&no_layers);
+    let properties = source.properties.as_deref().unwrap_or(&no_properties);
+    let highlights = selection.as_deref().map(|s| highlight_colors(model, s, pick.as_deref())).unwrap_or_default();
+    // Shaded bodies have no edges, apart from highlighted ones
+    if !display.mode.shows_edges() && !display.has_overrides(properties) && highlights.is_empty() {
+        return;
+    }
+    let per_body = !layers.is_empty()
+        || properties.iter().any(|(_, p)| !p.visible)
+        || groups.iter().any(|g| !g.visible)
+        || properties.iter().any(|(_, p)| p.ghosted)
+        || display.has_overrides(properties);
+    let bodies = if per_body { model.vertex_bodies() } else { HashMap::new() };
+    let shown = |body: Option<&BodyId>| match body {
+        Some(b) => groups.is_body_visible(*b, properties) && layers.is_body_visible(*b, properties) && display.mode_of(*b, properties).shows_edges(),
+        None => display.mode.shows_edges(),
+    };
+    // Bodies are drawn at their exploded positions, if any
+    let offsets = source.exploded.as_ref().map(|e| e.vertex_offsets(model)).unwrap_or_default();
+    let placed = |id: usize| model.vertex_position(id).map(|p| offsets.get(&id).map_or(p, |o| p + o));
+    let camera = cameras.iter().next();
+    let view_dir = |at: Vec3| match camera {
+        Some((transform, Some(Projection::Orthographic(_)))) => transform.forward().as_vec3(),
+        Some((transform, _)) => at - transform.translation(),
+        None => Vec3::NEG_Z,
+    };
+    for edge in &source.topology.edges {
+        let body = bodies.get(&edge.vertices.0);
+        if !shown(body) && !highlights.contains_key(&edge.id) {
+            continue;
+        }
+        let (Some(p0), Some(p1)) = (placed(edge.vertices.0), placed(edge.vertices.1)) else { continue };
+        // Only the part behind an active section plane is drawn
+        let Some((p0, p1)) = section.clip_segment(&p0, &p1) else { continue };
+        let (p0, p1) = (na_vec3_to_bevy(&p0), na_vec3_to_bevy(&p1));
+        let color = highlights.get(&edge.id).copied().unwrap_or_else(|| {
+            let color = body.and_then(|b| layers.body_color(*b, properties)).map_or(WHITE, |[r, g, b]| Color::srgb(r, g, b));
+            if body.is_some_and(|b| properties.is_ghosted(*b)) { ghost_color(color) } else { color }
+        });
+        let tangent_mode = match edge.kind {
+            EdgeKind::Tangent if !is_silhouette(&edge.normals, view_dir((p0 + p1) / 2.0)) => edge_display.tangent_mode,
+            _ => TangentEdgeMode::Solid,
+        };
+        match tangent_mode {
+            TangentEdgeMode::Solid => gizmos.line(p0, p1, color),
+            TangentEdgeMode::Dashed => dashed_line(&mut gizmos, p0, p1, edge_display.dash_length, color),
+            TangentEdgeMode::Hidden => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interaction::selection::SelectionItem;
+    use crate::model::brep::primitives::cube;
+
+    #[test]
+    fn test_edge_topology_and_silhouette() {
+        let model = cube(2.0);
+        let topology = EdgeTopology::build(&model, 1f64.to_radians());
+        assert_eq!(topology.edges.len(), 12);
+        assert!(topology.edges.iter().all(|e| e.kind == EdgeKind::Sharp && e.normals.len() == 2));
+
+        // Both faces turned towards the viewer, one towards and one away, and a boundary edge
+        let view = Vec3::new(-1.0, -1.0, 0.0);
+        assert!(!is_silhouette(&[Vec3::Y, Vec3::X], view));
+        assert!(is_silhouette(&[Vec3::Y, Vec3::NEG_X], view));
+        assert!(!is_silhouette(&[Vec3::Y], view));
+    }
+
+    #[test]
+    fn test_highlight_colors_prefer_selection() {
+        let model = cube(1.0);
+        let mut selection = Selection::default();
+        let face = model.faces[0].id;
+        selection.add(SelectionItem::Face(face));
+        let colors = highlight_colors(&model, &selection, None);
+        assert_eq!(colors.len(), 4);
+        assert!(colors.values().all(|c| *c == SELECTED_COLOR));
+    }
+}
@@ -0,0 +1,227 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: render::exploded
+//!
+//! Exploded view: each body (connected shell) of the model is drawn pushed away
+//! from the assembly centroid along the line through its own centroid, by the
+//! explode factor times its distance from the centroid. Switching between the
+//! collapsed and exploded states eases over a short animation, and dashed leader
+//! lines join each body back to where it sits. Only the display moves; picking
+//! and editing still act on the collapsed model. V toggles the view; , and .
+//! lower and raise the factor.
+
+use std::collections::HashMap;
+
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+use nalgebra::Vector3;
+
+use crate::color::MAGENTA;
+use crate::input::keyboard::KeyBindings;
+use crate::model::brep_model::{na_vec3_to_bevy, BrepModel};
+use crate::render::edge_display::dashed_line;
+use crate::telemetry::crash::journal;
+use crate::telemetry::usage::UsageStats;
+
+/// Factor change per , or . press
+const FACTOR_STEP: f64 = 0.25;
+/// Largest explode factor offered
+const MAX_FACTOR: f64 = 5.0;
+/// Dash length of the leader lines (world units)
+const LEADER_DASH: f32 = 2.0;
+
+/// Explode settings and animation state
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct ExplodedView {
+    /// Target state: exploded or collapsed
+    pub exploded: bool,
+    /// Each body moves this many times its distance from the assembly centroid
+    pub factor: f64,
+    /// Animation position between collapsed (0) and exploded (1)
+    pub progress: f64,
+    /// Seconds a full collapse or explode takes
+    pub duration: f64,
+}
+
+impl Default for ExplodedView {
+    fn default() -> Self {
+        Self { exploded: false, factor: 1.0, progress: 0.0, duration: 0.6 }
+    }
+}
+
+impl ExplodedView {
+    /// Current explode amount, eased so bodies start and stop gently
+    pub fn amount(&self) -> f64 {
+        let t = self.progress.clamp(0.0, 1.0);
+        self.factor * t * t * (3.0 - 2.0 * t)
+    }
+
+    /// Move `progress` towards the target state over `dt` seconds
+    pub fn advance(&mut self, dt: f64) {
+        let target = if self.exploded { 1.0 } else { 0.0 };
+        let step = if self.duration > 0.0 { dt / self.duration } else { 1.0 };
+        self.progress += (target - self.progress).clamp(-step, step);
+    }
+
+    /// Display offset of every vertex; empty when nothing is displaced
+    pub fn vertex_offsets(&self, model: &BrepModel) -> HashMap<usize, Vector3<f64>> {
+        let amount = self.amount();
+        if amount == 0.0 {
+            return HashMap::new();
+        }
+        explode_shells(model, amount).into_iter().flat_map(|s| s.vertices.into_iter().map(move |v| (v, s.offset))).collect()
+    }
+}
+
+/// One body of an exploded model
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShellOffset {
+    /// Vertex ids of the shell
+    pub vertices: Vec<usize>,
+    /// Collapsed centroid of the shell's vertices
+    pub centroid: Vector3<f64>,
+    pub offset: Vector3<f64>,
+}
+
+/// Offsets that push each shell away from the assembly centroid (the mean of
+/// the shell centroids) by `amount` times its distance from it
+pub fn explode_shells(model: &BrepModel, amount: f64) -> Vec<ShellOffset> {
+    let mut shells: Vec<ShellOffset> = model
+        .shells()
+        .into_iter()
+        .filter_map(|faces| {
+            let vertices = model.shell_vertex_ids(&faces);
+            let positions: Vec<Vector3<f64>> = vertices.iter().filter_map(|v| model.vertex_position(*v)).collect();
+            (!positions.is_empty()).then(|| ShellOffset {
+                centroid: positions.iter().sum::<Vector3<f64>>() / positions.len() as f64,
+                vertices,
+                offset: Vector3::zeros(),
+            })
+        })
+        .collect();
+    if shells.len() < 2 {
+        return shells;
+    }
+    let center = shells.iter().map(|s| s.centroid).sum::<Vector3<f64>>() / shells.len() as f64;
+    for shell in &mut shells {
+        shell.offset = (shell.centroid - center) * amount;
+    }
+    shells
+}
+
+/// Request to switch between the collapsed and exploded states
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ToggleExploded;
+
+/// Request to change the explode factor
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct SetExplodeFactor(pub f64);
+
+/// The exploded_view binding (V) toggles the exploded view; explode_less / explode_more (, and .)
+/// lower and raise the factor
+pub fn exploded_view_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    view: Res<ExplodedView>,
+    mut toggles: EventWriter<ToggleExploded>,
+    mut factors: EventWriter<SetExplodeFactor>,
+) {
+    if bindings.just_pressed("exploded_view", &keys) {
+        toggles.write(ToggleExploded);
+    }
+    if view.exploded {
+        if bindings.just_pressed("explode_less", &keys) {
+            factors.write(SetExplodeFactor(view.factor - FACTOR_STEP));
+        }
+        if bindings.just_pressed("explode_more", &keys) {
+            factors.write(SetExplodeFactor(view.factor + FACTOR_STEP));
+        }
+    }
+}
+
+/// Update the explode settings from requests
+pub fn apply_exploded_requests(
+    mut toggles: EventReader<ToggleExploded>,
+    mut factors: EventReader<SetExplodeFactor>,
+    mut view: ResMut<ExplodedView>,
+    mut usage: Option<ResMut<UsageStats>>,
+) {
+    for _ in toggles.read() {
+        let start = Instant::now();
+        view.exploded = !view.exploded;
+        journal(format!("exploded_view {}", if view.exploded { "on" } else { "off" }));
+        if let Some(usage) = usage.as_mut() {
+            usage.record("exploded_view", start.elapsed());
+        }
+    }
+    for SetExplodeFactor(factor) in factors.read() {
+        journal(format!("explode_factor {}", factor));
+        view.factor = factor.clamp(0.0, MAX_FACTOR);
+    }
+}
+
+/// Ease the explode animation towards its target state
+pub fn animate_exploded_view(time: Res<Time>, mut view: ResMut<ExplodedView>) {
+    let target = if view.exploded { 1.0 } else { 0.0 };
+    if view.progress != target {
+        view.advance(time.delta_secs_f64());
+    }
+}
+
+/// Dashed leader lines from each body's collapsed centroid to where it is drawn
+pub fn render_exploded_leaders(mut gizmos: Gizmos, model: Res<BrepModel>, view: Res<ExplodedView>) {
+    let amount = view.amount();
+    if amount == 0.0 {
+        return;
+    }
+    for shell in explode_shells(&model, amount) {
+        let (from, to) = (na_vec3_to_bevy(&shell.centroid), na_vec3_to_bevy(&(shell.centroid + shell.offset)));
+        dashed_line(&mut gizmos, from, to, LEADER_DASH, MAGENTA);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::cube;
+    use crate::testing::support::shifted;
+    use std::time::Duration;
+
+    #[test]
+    fn test_explode_shells() {
+        let mut model = shifted(cube(2.0), Vector3::new(-5.0, 0.0, 0.0));
+        model.append(&shifted(cube(2.0), Vector3::new(5.0, 0.0, 0.0)));
+        let shells = explode_shells(&model, 1.0);
+        assert_eq!(shells.len(), 2);
+        assert!((shells[0].offset - Vector3::new(-5.0, 0.0, 0.0)).norm() < 1e-9);
+        assert!((shells[1].offset - Vector3::new(5.0, 0.0, 0.0)).norm() < 1e-9);
+        // A single body stays put
+        assert_eq!(explode_shells(&cube(2.0), 1.0)[0].offset, Vector3::zeros());
+
+        let view = ExplodedView { progress: 1.0, factor: 0.5, ..default() };
+        let offsets = view.vertex_offsets(&model);
+        assert_eq!(offsets.len(), 16);
+        assert!((offsets[&shells[1].vertices[0]] - Vector3::new(2.5, 0.0, 0.0)).norm() < 1e-9);
+        assert!(ExplodedView::default().vertex_offsets(&model).is_empty());
+    }
+
+    #[test]
+    fn test_exploded_animation() {
+        let mut app = App::new();
+        app.init_resource::<ExplodedView>()
+            .init_resource::<Time>()
+            .add_event::<ToggleExploded>()
+            .add_event::<SetExplodeFactor>()
+            .add_systems(Update, (apply_exploded_requests, animate_exploded_view).chain());
+        app.world_mut().send_event(ToggleExploded);
+        app.world_mut().send_event(SetExplodeFactor(9.0));
+        app.world_mut().resource_mut::<Time>().advance_by(Duration::from_millis(300));
+        app.update();
+        let view = app.world().resource::<ExplodedView>();
+        assert!(view.exploded && view.factor == MAX_FACTOR);
+        // Halfway through the 0.6 s animation, eased to half the factor
+        assert!((view.progress - 0.5).abs() < 1e-9);
+        assert!((view.amount() - MAX_FACTOR / 2.0).abs() < 1e-9);
+    }
+}
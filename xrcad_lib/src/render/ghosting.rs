@@ -2,6 +2,17 @@
 // Copyright (c) 2025 Adrian Scarlett
 
 //! Module: render::ghosting
+//!
+//! Ghosted bodies are reference geometry: their faces and edges are drawn
+//! faint and see-through, and picking passes through them to what lies
+//! behind. Ghosting is a flag on `BodyProperties`, toggled from the outliner.
+//! Blended faces are sorted back to front by their mesh origin, which
+//! `render::display_mode` places at each body's center.
+
+use bevy::prelude::*;
+
+/// Opacity of ghosted faces and edges (at most; more transparent materials stay so)
+pub const GHOST_ALPHA: f32 = 0.2;
 
 /// Ghosting render struct.
 pub struct Ghosting;
@@ -12,6 +23,23 @@ impl Ghosting {
     }
 }
 
+/// Faint color of a ghosted body's edges
+pub fn ghost_color(color: Color) -> Color {
+    color.with_alpha(color.alpha().min(GHOST_ALPHA))
+}
+
+/// See-through version of a face material; both sides are drawn so the far
+/// side of the body shows through the near one
+pub fn ghost_material(material: StandardMaterial) -> StandardMaterial {
+    StandardMaterial {
+        base_color: ghost_color(material.base_color),
+        alpha_mode: AlphaMode::Blend,
+        double_sided: true,
+        cull_mode: None,
+        ..material
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -20,4 +48,15 @@ mod tests {
         let g = Ghosting::new();
         let _ = g;
     }
+
+    #[test]
+    fn test_ghost_material_is_see_through() {
+        let opaque = StandardMaterial { base_color: Color::srgb(1.0, 0.0, 0.0), ..default() };
+        let ghost = ghost_material(opaque);
+        assert_eq!(ghost.alpha_mode, AlphaMode::Blend);
+        assert_eq!(ghost.base_color, Color::srgba(1.0, 0.0, 0.0, GHOST_ALPHA));
+        assert!(ghost.cull_mode.is_none());
+        // Already fainter materials keep their alpha
+        assert_eq!(ghost_color(Color::srgba(1.0, 1.0, 1.0, 0.1)).alpha(), 0.1);
+    }
 }
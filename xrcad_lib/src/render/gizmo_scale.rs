@@ -0,0 +1,135 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: render::gizmo_scale
+//!
+//! Converts on-screen pixel sizes to world sizes at a given point so axes,
+//! vertex handles and sketch points keep a constant screen size while zooming.
+
+use bevy::prelude::*;
+
+/// Length of the workspace axes (pixels)
+pub const AXES_PIXELS: f32 = 100.0;
+/// Radius of BREP vertex handles (pixels)
+pub const VERTEX_HANDLE_PIXELS: f32 = 8.0;
+/// Pick radius around BREP vertex handles (pixels)
+pub const VERTEX_PICK_PIXELS: f32 = 12.0;
+/// Pick radius around BREP edges (pixels)
+pub const EDGE_PICK_PIXELS: f32 = 6.0;
+/// Radius of sketch point markers (pixels)
+pub const SKETCH_POINT_PIXELS: f32 = 4.0;
+
+/// Closest view depth used for sizing, so gizmos at the eye don't collapse to nothing
+const MIN_DEPTH: f32 = 1e-3;
+
+/// How the active camera maps world units to pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScaleProjection {
+    /// Focal length in pixels: viewport height / (2 tan(fov / 2))
+    Perspective { focal_pixels: f32 },
+    Orthographic { units_per_pixel: f32 },
+}
+
+/// Pixel-to-world conversion for the active camera, refreshed every frame
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct GizmoScale {
+    pub camera_position: Vec3,
+    /// Unit view direction
+    pub camera_forward: Vec3,
+    pub projection: ScaleProjection,
+}
+
+impl Default for GizmoScale {
+    /// One world unit per pixel, matching the fixed sizes used without a camera
+    fn default() -> Self {
+        Self {
+            camera_position: Vec3::ZERO,
+            camera_forward: Vec3::NEG_Z,
+            projection: ScaleProjection::Orthographic { units_per_pixel: 1.0 },
+        }
+    }
+}
+
+impl GizmoScale {
+    /// Scale for a perspective camera with vertical field of view `fov` (radians)
+    pub fn perspective(transform: &GlobalTransform, fov: f32, viewport_height: f32) -> Self {
+        Self {
+            camera_position: transform.translation(),
+            camera_forward: transform.forward().into(),
+            projection: ScaleProjection::Perspective {
+                focal_pixels: viewport_height / (2.0 * (fov * 0.5).tan()),
+            },
+        }
+    }
+
+    /// Scale for an orthographic camera showing `view_height` world units vertically
+    pub fn orthographic(transform: &GlobalTransform, view_height: f32, viewport_height: f32) -> Self {
+        Self {
+            camera_position: transform.translation(),
+            camera_forward: transform.forward().into(),
+            projection: ScaleProjection::Orthographic { units_per_pixel: view_height / viewport_height },
+        }
+    }
+
+    /// World size that spans `pixels` on screen at `position`
+    pub fn world_size(&self, position: Vec3, pixels: f32) -> f32 {
+        match self.projection {
+            ScaleProjection::Perspective { focal_pixels } => {
+                let depth = (position - self.camera_position).dot(self.camera_forward).max(MIN_DEPTH);
+                pixels * depth / focal_pixels
+            }
+            ScaleProjection::Orthographic { units_per_pixel } => pixels * units_per_pixel,
+        }
+    }
+}
+
+/// Track the active camera so gizmo sizes follow zoom
+pub fn update_gizmo_scale(
+    cameras: Query<(&Camera, &GlobalTransform, &Projection)>,
+    mut scale: ResMut<GizmoScale>,
+) {
+    let Some((camera, transform, projection)) = cameras
+        .iter()
+        .filter(|(c, _, _)| c.is_active)
+        .max_by_key(|(c, _, _)| c.order)
+    else {
+        return;
+    };
+    let Some(viewport) = camera.logical_viewport_size() else { return; };
+    if viewport.y <= 0.0 {
+        return;
+    }
+    let next = match projection {
+        Projection::Perspective(p) => GizmoScale::perspective(transform, p.fov, viewport.y),
+        Projection::Orthographic(o) => GizmoScale::orthographic(transform, o.area.height(), viewport.y),
+        _ => return,
+    };
+    if *scale != next {
+        *scale = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_perspective_size_grows_with_distance() {
+        let transform = GlobalTransform::from(Transform::from_xyz(0.0, 0.0, 10.0).looking_at(Vec3::ZERO, Vec3::Y));
+        let fov = std::f32::consts::FRAC_PI_2;
+        let scale = GizmoScale::perspective(&transform, fov, 800.0);
+        // At depth 10 a 90° view spans 20 units over 800 pixels
+        assert!((scale.world_size(Vec3::ZERO, 8.0) - 0.2).abs() < 1e-5);
+        let far = scale.world_size(Vec3::new(0.0, 0.0, -10.0), 8.0);
+        assert!((far - 0.4).abs() < 1e-5);
+        // Points behind the camera clamp instead of going negative
+        assert!(scale.world_size(Vec3::new(0.0, 0.0, 20.0), 8.0) > 0.0);
+    }
+
+    #[test]
+    fn test_orthographic_and_default() {
+        let scale = GizmoScale::orthographic(&GlobalTransform::IDENTITY, 50.0, 500.0);
+        assert!((scale.world_size(Vec3::new(3.0, 0.0, -100.0), 10.0) - 1.0).abs() < 1e-6);
+        assert_eq!(GizmoScale::default().world_size(Vec3::ONE, AXES_PIXELS), AXES_PIXELS);
+    }
+}
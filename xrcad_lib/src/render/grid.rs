@@ -0,0 +1,205 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: render::grid
+//!
+//! Draws the workspace grid as one quad under the camera with a grid shader
+//! (`grid.wgsl`), not as gizmo lines. The shader draws minor lines, major lines
+//! every ten minor ones, and the two plane axes in their axis colors. Lines
+//! keep a constant pixel width at any zoom, and the grid fades out with
+//! distance from the camera. Each frame the quad is moved under the camera and
+//! resized to the fade distance. The spacing follows the camera's height above
+//! the grid (see `Grid::level`), and minor lines fade out as the next level
+//! takes over.
+
+use bevy::asset::embedded_asset;
+use bevy::pbr::{MaterialPipeline, MaterialPipelineKey, NotShadowCaster};
+use bevy::prelude::*;
+use bevy::render::mesh::MeshVertexBufferLayoutRef;
+use bevy::render::render_resource::{AsBindGroup, RenderPipelineDescriptor, ShaderRef, ShaderType, SpecializedMeshPipelineError};
+
+use crate::viewport::passthrough::PassthroughMode;
+use crate::workspace::helpers::grid::{Grid, GridPlane};
+use crate::workspace::workspace::{HelperKind, Workspace};
+
+const GRID_SHADER_PATH: &str = "embedded://xrcad_lib/render/grid.wgsl";
+
+/// Color of minor grid lines
+const MINOR_COLOR: Color = Color::srgba(0.5, 0.5, 0.5, 0.25);
+/// Color of major grid lines
+const MAJOR_COLOR: Color = Color::srgba(0.6, 0.6, 0.6, 0.5);
+/// Closest the fade distance gets, so the grid stays visible with the camera on it
+const MIN_FADE_DISTANCE: f32 = 100.0;
+
+/// Shader uniform of the grid material
+#[derive(ShaderType, Debug, Clone, Copy, PartialEq)]
+pub struct GridUniform {
+    pub minor_color: LinearRgba,
+    pub major_color: LinearRgba,
+    pub first_axis_color: LinearRgba,
+    pub second_axis_color: LinearRgba,
+    /// In-plane axes (w unused)
+    pub first_axis: Vec4,
+    pub second_axis: Vec4,
+    /// Minor line spacing (mm)
+    pub spacing: f32,
+    /// How far minor lines have faded (0 to 1)
+    pub blend: f32,
+    /// Distance from the camera at which the grid has faded out
+    pub fade_distance: f32,
+}
+
+impl GridUniform {
+    pub fn new(plane: GridPlane) -> Self {
+        let [(u, u_color), (v, v_color)] = plane.axes();
+        Self {
+            minor_color: MINOR_COLOR.into(),
+            major_color: MAJOR_COLOR.into(),
+            first_axis_color: u_color.into(),
+            second_axis_color: v_color.into(),
+            first_axis: u.extend(0.0),
+            second_axis: v.extend(0.0),
+            spacing: 1.0,
+            blend: 0.0,
+            fade_distance: MIN_FADE_DISTANCE,
+        }
+    }
+}
+
+/// Infinite grid material
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+pub struct GridMaterial {
+    #[uniform(0)]
+    pub uniform: GridUniform,
+}
+
+impl Material for GridMaterial {
+    fn fragment_shader() -> ShaderRef {
+        GRID_SHADER_PATH.into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode {
+        AlphaMode::Blend
+    }
+
+    fn specialize(
+        _pipeline: &MaterialPipeline<Self>,
+        descriptor: &mut RenderPipelineDescriptor,
+        _layout: &MeshVertexBufferLayoutRef,
+        _key: MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        // Seen from below as well as above
+        descriptor.primitive.cull_mode = None;
+        Ok(())
+    }
+}
+
+/// Registers the grid material and its embedded shader
+pub struct GridPlugin;
+
+impl Plugin for GridPlugin {
+    fn build(&self, app: &mut App) {
+        embedded_asset!(app, "grid.wgsl");
+        app.add_plugins(MaterialPlugin::<GridMaterial> { prepass_enabled: false, shadows_enabled: false, ..default() });
+    }
+}
+
+/// The quad of a workspace grid helper
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct InfiniteGrid(pub Grid);
+
+/// Spawn one quad per shown grid helper when the workspace (or passthrough)
+/// changes; over passthrough there is no grid
+pub fn sync_grids(
+    mut commands: Commands,
+    workspace: Res<Workspace>,
+    passthrough: Option<Res<PassthroughMode>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<GridMaterial>>,
+    old: Query<Entity, With<InfiniteGrid>>,
+) {
+    if !workspace.is_changed() && !passthrough.as_ref().is_some_and(|p| p.is_changed()) {
+        return;
+    }
+    for entity in old.iter() {
+        commands.entity(entity).despawn();
+    }
+    if passthrough.is_some_and(|p| p.is_active()) {
+        return;
+    }
+    for helper in workspace.helpers.iter().filter(|h| h.is_visible()) {
+        let HelperKind::Grid(grid) = &helper.kind else { continue };
+        let rotation = Quat::from_rotation_arc(Vec3::Z, grid.plane.normal());
+        let mut uniform = GridUniform::new(grid.plane);
+        if let Some([r, g, b]) = helper.color {
+            uniform.minor_color = Color::srgba(r, g, b, MINOR_COLOR.alpha()).into();
+            uniform.major_color = Color::srgba(r, g, b, MAJOR_COLOR.alpha()).into();
+        }
+        // A larger grid reaches further before fading out
+        let grid = Grid { fade: grid.fade * helper.size, ..grid.clone() };
+        commands.spawn((
+            Mesh3d(meshes.add(Rectangle::new(2.0, 2.0))),
+            MeshMaterial3d(materials.add(GridMaterial { uniform })),
+            Transform::from_rotation(rotation),
+            NotShadowCaster,
+            InfiniteGrid(grid),
+        ));
+    }
+}
+
+/// Keep each grid quad under the camera and its spacing matched to the camera's height
+pub fn update_grids(
+    cameras: Query<&GlobalTransform, With<Camera3d>>,
+    mut grids: Query<(&mut Transform, &MeshMaterial3d<GridMaterial>, &InfiniteGrid)>,
+    mut materials: ResMut<Assets<GridMaterial>>,
+) {
+    let Some(camera) = cameras.iter().next() else { return };
+    let eye = camera.translation();
+    for (mut transform, handle, InfiniteGrid(grid)) in grids.iter_mut() {
+        let normal = grid.plane.normal();
+        let height = eye.dot(normal).abs();
+        let (spacing, blend) = Grid::level(height);
+        let fade_distance = (height * grid.fade).max(MIN_FADE_DISTANCE);
+        // The quad reaches the fade distance in every direction from the point under the camera
+        let center = eye - normal * eye.dot(normal);
+        let placed = Transform { translation: center, rotation: transform.rotation, scale: Vec3::splat(fade_distance) };
+        if *transform != placed {
+            *transform = placed;
+        }
+        let Some(current) = materials.get(&handle.0) else { continue };
+        let uniform = GridUniform { spacing, blend, fade_distance, ..current.uniform };
+        if current.uniform != uniform {
+            if let Some(material) = materials.get_mut(&handle.0) {
+                material.uniform = uniform;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_follows_camera() {
+        let mut app = App::new();
+        app.insert_resource(Workspace::default())
+            .init_resource::<Assets<Mesh>>()
+            .init_resource::<Assets<GridMaterial>>()
+            .add_systems(Update, (sync_grids, update_grids).chain());
+        app.world_mut().spawn((Camera3d::default(), GlobalTransform::from_translation(Vec3::new(30.0, 1000.0, -20.0))));
+        app.update();
+        app.update();
+        let (transform, handle) = app
+            .world_mut()
+            .query_filtered::<(&Transform, &MeshMaterial3d<GridMaterial>), With<InfiniteGrid>>()
+            .single(app.world())
+            .map(|(t, h)| (*t, h.0.clone()))
+            .unwrap();
+        assert_eq!(transform.translation, Vec3::new(30.0, 0.0, -20.0));
+        assert_eq!(transform.scale, Vec3::splat(1000.0 * Grid::default().fade));
+        let material = app.world().resource::<Assets<GridMaterial>>().get(&handle).unwrap();
+        assert_eq!(material.uniform.spacing, 10.0);
+        assert_eq!(material.uniform.first_axis, Vec4::Z);
+    }
+}
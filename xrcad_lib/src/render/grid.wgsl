@@ -0,0 +1,62 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+// Infinite grid for render::grid. Lines are found from the world position's
+// plane coordinates, anti-aliased with screen-space derivatives so they keep
+// about a pixel of width at any distance.
+
+#import bevy_pbr::{
+    forward_io::VertexOutput,
+    mesh_view_bindings::view,
+}
+
+struct GridUniform {
+    minor_color: vec4<f32>,
+    major_color: vec4<f32>,
+    first_axis_color: vec4<f32>,
+    second_axis_color: vec4<f32>,
+    first_axis: vec4<f32>,
+    second_axis: vec4<f32>,
+    spacing: f32,
+    blend: f32,
+    fade_distance: f32,
+};
+
+@group(2) @binding(0) var<uniform> grid: GridUniform;
+
+// Coverage of grid lines `spacing` apart and `width` pixels wide at `coord`
+fn lines(coord: vec2<f32>, spacing: f32, width: f32) -> f32 {
+    let scaled = coord / spacing;
+    let pixels = abs(fract(scaled - 0.5) - 0.5) / max(fwidth(scaled), vec2<f32>(1e-6));
+    return clamp(width * 0.5 + 0.5 - min(pixels.x, pixels.y), 0.0, 1.0);
+}
+
+// Coverage of the line where `coordinate` is zero
+fn axis(coordinate: f32, width: f32) -> f32 {
+    let pixels = abs(coordinate) / max(fwidth(coordinate), 1e-6);
+    return clamp(width * 0.5 + 0.5 - pixels, 0.0, 1.0);
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let position = in.world_position.xyz;
+    let coord = vec2<f32>(dot(position, grid.first_axis.xyz), dot(position, grid.second_axis.xyz));
+
+    var color = vec4<f32>(grid.minor_color.rgb, grid.minor_color.a * lines(coord, grid.spacing, 1.0) * (1.0 - grid.blend));
+    let major = grid.major_color.a * lines(coord, grid.spacing * 10.0, 1.0);
+    if major > color.a {
+        color = vec4<f32>(grid.major_color.rgb, major);
+    }
+    // The first axis runs along the line where the second coordinate is zero
+    let first = axis(coord.y, 2.0);
+    color = mix(color, grid.first_axis_color, first);
+    let second = axis(coord.x, 2.0);
+    color = mix(color, grid.second_axis_color, second);
+
+    let fade = 1.0 - smoothstep(grid.fade_distance * 0.5, grid.fade_distance, distance(position, view.world_position));
+    color.a *= fade;
+    if color.a < 0.002 {
+        discard;
+    }
+    return color;
+}
@@ -2,6 +2,23 @@
 // Copyright (c) 2025 Adrian Scarlett
 
 //! Module: render::hilighting
+//!
+//! Colors for the selected elements and the element under the cursor that a
+//! click would select. Highlighted edges are drawn by `render::edge_overlay`;
+//! highlighted vertices are drawn here.
+
+use bevy::prelude::*;
+
+use crate::color::{CYAN, MAGENTA};
+use crate::interaction::picking::PickState;
+use crate::interaction::selection::{Selection, SelectionItem};
+use crate::model::brep_model::{na_vec3_to_bevy, BrepModel};
+use crate::render::gizmo_scale::{GizmoScale, VERTEX_HANDLE_PIXELS};
+
+/// Selected elements
+pub const SELECTED_COLOR: Color = CYAN;
+/// Element under the cursor
+pub const HOVER_COLOR: Color = MAGENTA;
 
 /// Hilighting render struct.
 pub struct Hilighting;
@@ -12,12 +29,66 @@ impl Hilighting {
     }
 }
 
+/// Edges outlining a selected item; a vertex has none
+pub fn item_edges(model: &BrepModel, item: &SelectionItem) -> Vec<usize> {
+    let face_edges = |id: usize| model.face(id).map_or(Vec::new(), |f| model.face_edge_ids(f));
+    match item {
+        SelectionItem::Vertex(_) => Vec::new(),
+        SelectionItem::Edge(id) => vec![*id],
+        SelectionItem::Face(id) => face_edges(*id),
+        SelectionItem::Body(body) => {
+            let mut edges: Vec<usize> = model.body_faces(*body).into_iter().flatten().flat_map(face_edges).collect();
+            edges.sort_unstable();
+            edges.dedup();
+            edges
+        }
+    }
+}
+
+fn draw_item(gizmos: &mut Gizmos, model: &BrepModel, scale: &GizmoScale, item: &SelectionItem, color: Color) {
+    if let SelectionItem::Vertex(id) = item {
+        if let Some(p) = model.vertex_position(*id) {
+            let position = na_vec3_to_bevy(&p);
+            gizmos.circle(position, scale.world_size(position, VERTEX_HANDLE_PIXELS * 1.5), color);
+        }
+    }
+}
+
+/// Highlight the selected and hovered vertices
+pub fn render_selection(
+    mut gizmos: Gizmos,
+    model: Res<BrepModel>,
+    selection: Res<Selection>,
+    pick: Option<Res<PickState>>,
+    scale: Option<Res<GizmoScale>>,
+) {
+    let scale = scale.as_deref().copied().unwrap_or_default();
+    for item in &selection.items {
+        draw_item(&mut gizmos, &model, &scale, item, SELECTED_COLOR);
+    }
+    let hover = pick.as_ref().and_then(|p| p.hover.as_ref()).and_then(|h| h.selection_item(selection.filter));
+    if let Some(item) = hover.filter(|i| !selection.contains(*i)) {
+        draw_item(&mut gizmos, &model, &scale, &item, HOVER_COLOR);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model::body::BodyId;
+    use crate::model::brep::primitives::cube;
+
     #[test]
     fn test_hilighting_new() {
         let h = Hilighting::new();
         let _ = h;
     }
+
+    #[test]
+    fn test_item_edges() {
+        let model = cube(1.0);
+        assert_eq!(item_edges(&model, &SelectionItem::Face(model.faces[0].id)).len(), 4);
+        assert_eq!(item_edges(&model, &SelectionItem::Body(BodyId(0))).len(), 12);
+        assert!(item_edges(&model, &SelectionItem::Vertex(0)).is_empty());
+    }
 }
@@ -0,0 +1,659 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: render::lighting
+//!
+//! The light manager owns the scene's directional lights and rebuilds them
+//! from a preset whenever the lighting configuration changes. Inspection presets:
+//! a headlight that follows the camera, a three-point studio rig, and a single
+//! raking light that grazes faces turned towards the viewer, so small changes
+//! in face normal show up as changes in shading. Lights are placed relative to
+//! the camera and follow it. The lighting panel also picks the environment
+//! and ambient occlusion of `render::settings`.
+//!
+//! On top of the preset, point, spot and directional lights can be added and
+//! removed at runtime. These scene lights stay fixed in the world: each is
+//! placed at the camera and aimed at the orbit pivot when added, spawned as an
+//! entity with its own `SceneLightController`, listed in the lighting panel
+//! (switch on or off, dim, brighten, remove) and marked in the viewport.
+
+use std::f32::consts::{FRAC_PI_4, PI};
+use std::fmt;
+
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+
+use crate::input::keyboard::KeyBindings;
+use crate::interaction::state::UiPanel;
+use crate::render::gizmo_scale::GizmoScale;
+use crate::render::settings::{AmbientOcclusionButton, AmbientOcclusionLabel, EnvironmentButton, LightingEnvironment};
+use crate::render::ui_style::{BUTTON_ACTIVE, BUTTON_IDLE, PANEL_COLOR};
+use crate::telemetry::crash::journal;
+use crate::telemetry::usage::UsageStats;
+use crate::viewport::camera_control::CustomCameraController;
+
+/// Illuminance of the main light of every preset (lux)
+const KEY_ILLUMINANCE: f32 = 10000.0;
+/// Raking angle change per key press (degrees)
+const RAKING_STEP: f32 = 5.0;
+/// Share of the key light a new scene light gives at the pivot
+const SCENE_LIGHT_SHARE: f32 = 0.5;
+/// Intensity change of the dim and brighten buttons
+const SCENE_LIGHT_STEP: f32 = 2.0;
+/// Size of scene light markers (pixels)
+const LIGHT_MARKER_PIXELS: f32 = 10.0;
+
+/// Inspection lighting setups
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LightingPreset {
+    Headlight,
+    #[default]
+    ThreePoint,
+    Raking,
+}
+
+impl LightingPreset {
+    pub const ALL: [LightingPreset; 3] = [LightingPreset::Headlight, LightingPreset::ThreePoint, LightingPreset::Raking];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LightingPreset::Headlight => "Headlight",
+            LightingPreset::ThreePoint => "Three-point studio",
+            LightingPreset::Raking => "Raking light",
+        }
+    }
+
+    /// Next preset in panel order
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|p| *p == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+}
+
+/// One directional light of a configuration, in the camera's frame
+#[derive(Debug, Clone, PartialEq)]
+pub struct LightSpec {
+    pub name: &'static str,
+    /// Direction the light travels (camera looks along -Z)
+    pub direction: Vec3,
+    pub illuminance: f32,
+    pub shadows: bool,
+}
+
+/// Current lighting configuration
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct LightManager {
+    pub preset: LightingPreset,
+    /// Angle of the raking light above the view plane (degrees); small values graze
+    pub raking_angle: f32,
+}
+
+impl Default for LightManager {
+    fn default() -> Self {
+        Self { preset: LightingPreset::default(), raking_angle: 10.0 }
+    }
+}
+
+impl LightManager {
+    /// Lights making up the current preset
+    pub fn lights(&self) -> Vec<LightSpec> {
+        let light = |name, direction: Vec3, share: f32, shadows| LightSpec {
+            name,
+            direction: direction.normalize(),
+            illuminance: KEY_ILLUMINANCE * share,
+            shadows,
+        };
+        match self.preset {
+            LightingPreset::Headlight => vec![light("Headlight", Vec3::NEG_Z, 1.0, false)],
+            LightingPreset::ThreePoint => vec![
+                light("Key", Vec3::new(0.6, -0.6, -1.0), 1.0, true),
+                light("Fill", Vec3::new(-0.8, -0.2, -1.0), 0.4, false),
+                light("Rim", Vec3::new(0.0, -0.6, 1.0), 0.6, false),
+            ],
+            LightingPreset::Raking => {
+                let a = self.raking_angle.clamp(0.0, 90.0).to_radians();
+                // From the right of the view, tilted into the scene by the raking angle
+                vec![light("Raking", Vec3::new(-a.cos(), 0.0, -a.sin()), 1.0, true)]
+            }
+        }
+    }
+}
+
+/// Marks lights spawned by the light manager
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct ManagedLight {
+    /// Direction in the camera frame
+    pub direction: Vec3,
+}
+
+/// Request to switch the lighting preset
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetLightingPreset(pub LightingPreset);
+
+/// Request to change the raking light angle (degrees)
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct SetRakingAngle(pub f32);
+
+/// L cycles the preset; [ and ] lower and raise the raking light (default bindings)
+pub fn lighting_keys(
+    (keys, bindings): (Res<ButtonInput<KeyCode>>, Res<KeyBindings>),
+    manager: Res<LightManager>,
+    mut presets: EventWriter<SetLightingPreset>,
+    mut angles: EventWriter<SetRakingAngle>,
+) {
+    if bindings.just_pressed("lighting_preset", &keys) {
+        presets.write(SetLightingPreset(manager.preset.next()));
+    }
+    if manager.preset == LightingPreset::Raking {
+        if bindings.just_pressed("raking_lower", &keys) {
+            angles.write(SetRakingAngle(manager.raking_angle - RAKING_STEP));
+        }
+        if bindings.just_pressed("raking_raise", &keys) {
+            angles.write(SetRakingAngle(manager.raking_angle + RAKING_STEP));
+        }
+    }
+}
+
+/// Update the lighting configuration from requests
+pub fn apply_lighting_requests(
+    mut presets: EventReader<SetLightingPreset>,
+    mut angles: EventReader<SetRakingAngle>,
+    mut manager: ResMut<LightManager>,
+    mut usage: Option<ResMut<UsageStats>>,
+) {
+    for SetLightingPreset(preset) in presets.read() {
+        let start = Instant::now();
+        journal(format!("lighting_preset {:?}", preset));
+        manager.preset = *preset;
+        if let Some(usage) = usage.as_mut() {
+            usage.record("lighting_preset", start.elapsed());
+        }
+    }
+    for SetRakingAngle(angle) in angles.read() {
+        journal(format!("raking_angle {}", angle));
+        manager.raking_angle = angle.clamp(0.0, 90.0);
+    }
+}
+
+/// Rebuild the managed lights when the configuration changes
+pub fn sync_managed_lights(mut commands: Commands, manager: Res<LightManager>, lights: Query<Entity, With<ManagedLight>>) {
+    if !manager.is_changed() {
+        return;
+    }
+    for entity in lights.iter() {
+        commands.entity(entity).despawn();
+    }
+    for spec in manager.lights() {
+        commands.spawn((
+            Name::new(spec.name),
+            DirectionalLight { illuminance: spec.illuminance, shadows_enabled: spec.shadows, ..default() },
+            Transform::default(),
+            ManagedLight { direction: spec.direction },
+        ));
+    }
+}
+
+/// Keep managed lights aimed relative to the camera
+pub fn follow_camera_lights(
+    cameras: Query<&Transform, (With<Camera3d>, Without<ManagedLight>)>,
+    mut lights: Query<(&mut Transform, &ManagedLight)>,
+) {
+    let Some(camera) = cameras.iter().next() else { return };
+    for (mut transform, light) in lights.iter_mut() {
+        let direction = camera.rotation * light.direction;
+        let up = if direction.cross(Vec3::Y).length_squared() > 1e-6 { Vec3::Y } else { Vec3::Z };
+        *transform = Transform::default().looking_to(direction, up);
+    }
+}
+
+/// Kinds of light that can be added to the scene
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SceneLightKind {
+    Directional,
+    Point,
+    Spot,
+}
+
+impl SceneLightKind {
+    pub const ALL: [SceneLightKind; 3] = [SceneLightKind::Point, SceneLightKind::Spot, SceneLightKind::Directional];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SceneLightKind::Directional => "Directional",
+            SceneLightKind::Point => "Point",
+            SceneLightKind::Spot => "Spot",
+        }
+    }
+}
+
+/// A light added to the scene, fixed in the world
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneLight {
+    pub id: usize,
+    pub name: String,
+    pub kind: SceneLightKind,
+    pub position: Vec3,
+    /// Unit direction the light shines (unused by point lights)
+    pub direction: Vec3,
+    pub color: Color,
+    /// Illuminance (lux) for directional lights, luminous power (lumens) otherwise
+    pub intensity: f32,
+    /// Reach of point and spot lights
+    pub range: f32,
+    /// Half angle of the spot cone (radians)
+    pub spot_angle: f32,
+    pub shadows: bool,
+    pub enabled: bool,
+}
+
+impl SceneLight {
+    /// A light at `position` aimed at `target`, bright enough to light the
+    /// target like half the key light
+    pub fn new(id: usize, kind: SceneLightKind, position: Vec3, target: Vec3) -> Self {
+        let distance = position.distance(target).max(1.0);
+        let intensity = match kind {
+            SceneLightKind::Directional => KEY_ILLUMINANCE * SCENE_LIGHT_SHARE,
+            // Lumens giving that illuminance at the target's distance
+            _ => KEY_ILLUMINANCE * SCENE_LIGHT_SHARE * 4.0 * PI * distance * distance,
+        };
+        Self {
+            id,
+            name: format!("{} {}", kind.label(), id + 1),
+            kind,
+            position,
+            direction: (target - position).try_normalize().unwrap_or(Vec3::NEG_Z),
+            color: Color::WHITE,
+            intensity,
+            range: distance * 4.0,
+            spot_angle: FRAC_PI_4 / 2.0,
+            shadows: kind != SceneLightKind::Point,
+            enabled: true,
+        }
+    }
+
+    pub fn transform(&self) -> Transform {
+        let up = if self.direction.cross(Vec3::Y).length_squared() > 1e-6 { Vec3::Y } else { Vec3::Z };
+        Transform::from_translation(self.position).looking_to(self.direction, up)
+    }
+}
+
+/// Why a scene light could not be changed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SceneLightError {
+    UnknownLight(usize),
+}
+
+impl fmt::Display for SceneLightError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneLightError::UnknownLight(id) => write!(f, "no scene light with id {}", id),
+        }
+    }
+}
+
+impl std::error::Error for SceneLightError {}
+
+/// Lights added to the scene, in panel order
+#[derive(Resource, Debug, Default, Clone, PartialEq)]
+pub struct SceneLights {
+    pub lights: Vec<SceneLight>,
+    next_id: usize,
+}
+
+impl SceneLights {
+    /// Add a light at `position` aimed at `target`; returns its id
+    pub fn add(&mut self, kind: SceneLightKind, position: Vec3, target: Vec3) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.lights.push(SceneLight::new(id, kind, position, target));
+        id
+    }
+
+    pub fn get(&self, id: usize) -> Option<&SceneLight> {
+        self.lights.iter().find(|l| l.id == id)
+    }
+
+    pub fn get_mut(&mut self, id: usize) -> Result<&mut SceneLight, SceneLightError> {
+        self.lights.iter_mut().find(|l| l.id == id).ok_or(SceneLightError::UnknownLight(id))
+    }
+
+    pub fn remove(&mut self, id: usize) -> Result<SceneLight, SceneLightError> {
+        let index = self.lights.iter().position(|l| l.id == id).ok_or(SceneLightError::UnknownLight(id))?;
+        Ok(self.lights.remove(index))
+    }
+}
+
+/// The entity of a scene light, by light id
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SceneLightController(pub usize);
+
+/// Request to add a light at `position` aimed at `target`
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct AddSceneLight {
+    pub kind: SceneLightKind,
+    pub position: Vec3,
+    pub target: Vec3,
+}
+
+/// Request to remove a scene light, by id
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RemoveSceneLight(pub usize);
+
+/// Request to switch a scene light on or off, by id
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ToggleSceneLight(pub usize);
+
+/// Request to multiply a scene light's intensity
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct ScaleSceneLight {
+    pub id: usize,
+    pub factor: f32,
+}
+
+/// Add, remove, switch and dim scene lights
+pub fn apply_scene_light_requests(
+    mut adds: EventReader<AddSceneLight>,
+    mut removes: EventReader<RemoveSceneLight>,
+    mut toggles: EventReader<ToggleSceneLight>,
+    mut scales: EventReader<ScaleSceneLight>,
+    mut lights: ResMut<SceneLights>,
+    mut usage: Option<ResMut<UsageStats>>,
+) {
+    for add in adds.read() {
+        let start = Instant::now();
+        let id = lights.add(add.kind, add.position, add.target);
+        journal(format!("add_light {:?} {}", add.kind, id));
+        if let Some(usage) = usage.as_mut() {
+            usage.record("add_light", start.elapsed());
+        }
+    }
+    for RemoveSceneLight(id) in removes.read() {
+        journal(format!("remove_light {}", id));
+        if let Err(err) = lights.remove(*id) {
+            warn!("Could not remove light: {}", err);
+        }
+    }
+    for ToggleSceneLight(id) in toggles.read() {
+        journal(format!("toggle_light {}", id));
+        match lights.get_mut(*id) {
+            Ok(light) => light.enabled = !light.enabled,
+            Err(err) => warn!("Could not switch light: {}", err),
+        }
+    }
+    for ScaleSceneLight { id, factor } in scales.read() {
+        journal(format!("scale_light {} {}", id, factor));
+        match lights.get_mut(*id) {
+            Ok(light) => light.intensity *= factor.max(0.0),
+            Err(err) => warn!("Could not change light: {}", err),
+        }
+    }
+}
+
+/// Rebuild the scene light entities when the lights change; switched-off lights have none
+pub fn sync_scene_lights(mut commands: Commands, lights: Res<SceneLights>, spawned: Query<Entity, With<SceneLightController>>) {
+    if !lights.is_changed() {
+        return;
+    }
+    for entity in spawned.iter() {
+        commands.entity(entity).despawn();
+    }
+    for light in lights.lights.iter().filter(|l| l.enabled) {
+        let mut entity = commands.spawn((Name::new(light.name.clone()), light.transform(), SceneLightController(light.id)));
+        match light.kind {
+            SceneLightKind::Directional => {
+                entity.insert(DirectionalLight { color: light.color, illuminance: light.intensity, shadows_enabled: light.shadows, ..default() });
+            }
+            SceneLightKind::Point => {
+                entity.insert(PointLight { color: light.color, intensity: light.intensity, range: light.range, shadows_enabled: light.shadows, ..default() });
+            }
+            SceneLightKind::Spot => {
+                entity.insert(SpotLight {
+                    color: light.color,
+                    intensity: light.intensity,
+                    range: light.range,
+                    outer_angle: light.spot_angle,
+                    inner_angle: light.spot_angle * 0.8,
+                    shadows_enabled: light.shadows,
+                    ..default()
+                });
+            }
+        }
+    }
+}
+
+/// Mark each scene light in the viewport: a sphere for point lights, a cone
+/// for spots and an arrow for directional lights, faded when switched off
+pub fn draw_scene_lights(mut gizmos: Gizmos, lights: Res<SceneLights>, scale: Option<Res<GizmoScale>>) {
+    let scale = scale.as_deref().copied().unwrap_or_default();
+    for light in &lights.lights {
+        let size = scale.world_size(light.position, LIGHT_MARKER_PIXELS);
+        let color = if light.enabled { Color::srgb(1.0, 0.9, 0.3) } else { Color::srgb(0.4, 0.4, 0.3) };
+        match light.kind {
+            SceneLightKind::Point => {
+                gizmos.sphere(Isometry3d::from_translation(light.position), size, color);
+            }
+            SceneLightKind::Spot => {
+                let transform = light.transform();
+                let (length, radius) = (size * 3.0, size * 3.0 * light.spot_angle.tan());
+                let base = light.position + light.direction * length;
+                gizmos.circle(Isometry3d::new(base, transform.rotation), radius, color);
+                for side in [transform.right(), transform.left(), transform.up(), transform.down()] {
+                    gizmos.line(light.position, base + side.as_vec3() * radius, color);
+                }
+            }
+            SceneLightKind::Directional => {
+                gizmos.arrow(light.position, light.position + light.direction * size * 3.0, color);
+            }
+        }
+    }
+}
+
+/// Scene light list of the lighting panel
+#[derive(Component, Debug)]
+pub struct SceneLightList;
+
+/// A row of the scene light list, rebuilt when the lights change
+#[derive(Component, Debug)]
+pub struct SceneLightRow;
+
+/// What a scene light button does
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub enum SceneLightButton {
+    Add(SceneLightKind),
+    Toggle(usize),
+    Scale(usize, f32),
+    Remove(usize),
+}
+
+/// Send requests for clicked scene light buttons and rebuild the list when the lights change
+pub fn scene_lights_panel_system(
+    mut commands: Commands,
+    lights: Res<SceneLights>,
+    pressed: Query<(&Interaction, &SceneLightButton), Changed<Interaction>>,
+    list: Query<Entity, With<SceneLightList>>,
+    old_rows: Query<Entity, With<SceneLightRow>>,
+    cameras: Query<(&Transform, &CustomCameraController)>,
+    (mut adds, mut removes, mut toggles, mut scales): (EventWriter<AddSceneLight>, EventWriter<RemoveSceneLight>, EventWriter<ToggleSceneLight>, EventWriter<ScaleSceneLight>),
+) {
+    for (interaction, button) in pressed.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match *button {
+            SceneLightButton::Add(kind) => {
+                let Some((transform, controller)) = cameras.iter().next() else { continue };
+                adds.write(AddSceneLight { kind, position: transform.translation, target: controller.pivot });
+            }
+            SceneLightButton::Toggle(id) => {
+                toggles.write(ToggleSceneLight(id));
+            }
+            SceneLightButton::Scale(id, factor) => {
+                scales.write(ScaleSceneLight { id, factor });
+            }
+            SceneLightButton::Remove(id) => {
+                removes.write(RemoveSceneLight(id));
+            }
+        }
+    }
+    if !lights.is_changed() {
+        return;
+    }
+    let Ok(list) = list.single() else { return };
+    for entity in old_rows.iter() {
+        commands.entity(entity).despawn();
+    }
+    commands.entity(list).with_children(|list| {
+        for light in &lights.lights {
+            let button = |action| (Button, Node { padding: UiRect::axes(Val::Px(4.0), Val::Px(1.0)), ..default() }, BackgroundColor(BUTTON_IDLE), action);
+            list.spawn((Node { column_gap: Val::Px(4.0), ..default() }, SceneLightRow)).with_children(|line| {
+                line.spawn(button(SceneLightButton::Toggle(light.id))).with_child(Text::new(if light.enabled { "[o]" } else { "[-]" }));
+                line.spawn(Text::new(light.name.clone()));
+                line.spawn(button(SceneLightButton::Scale(light.id, 1.0 / SCENE_LIGHT_STEP))).with_child(Text::new("-"));
+                line.spawn(button(SceneLightButton::Scale(light.id, SCENE_LIGHT_STEP))).with_child(Text::new("+"));
+                line.spawn(button(SceneLightButton::Remove(light.id))).with_child(Text::new("x"));
+            });
+        }
+    });
+}
+
+/// Lighting panel button for a preset
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LightingButton(pub LightingPreset);
+
+/// Raking angle readout of the lighting panel
+#[derive(Component, Debug)]
+pub struct RakingAngleText;
+
+/// Lighting panel (bottom left): one button per preset, the raking angle, one
+/// button per environment, the ambient occlusion toggle and the scene lights
+pub fn spawn_lighting_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(8.0),
+                bottom: Val::Px(8.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                padding: UiRect::all(Val::Px(6.0)),
+                ..default()
+            },
+            BackgroundColor(PANEL_COLOR),
+            UiPanel("lighting"),
+        ))
+        .with_children(|panel| {
+            panel.spawn(Text::new("Lighting (L, F9 hides)"));
+            for preset in LightingPreset::ALL {
+                panel
+                    .spawn((Button, Node { padding: UiRect::axes(Val::Px(6.0), Val::Px(2.0)), ..default() }, BackgroundColor(BUTTON_IDLE), LightingButton(preset)))
+                    .with_child(Text::new(preset.label()));
+            }
+            panel.spawn((Text::new(""), RakingAngleText));
+            panel.spawn(Text::new("Environment"));
+            for environment in LightingEnvironment::ALL {
+                panel
+                    .spawn((Button, Node { padding: UiRect::axes(Val::Px(6.0), Val::Px(2.0)), ..default() }, BackgroundColor(BUTTON_IDLE), EnvironmentButton(environment)))
+                    .with_child(Text::new(environment.label()));
+            }
+            panel
+                .spawn((Button, Node { padding: UiRect::axes(Val::Px(6.0), Val::Px(2.0)), ..default() }, BackgroundColor(BUTTON_IDLE), AmbientOcclusionButton))
+                .with_child((Text::new(""), AmbientOcclusionLabel));
+            panel.spawn(Text::new("Lights"));
+            panel.spawn((Node { flex_direction: FlexDirection::Column, row_gap: Val::Px(2.0), ..default() }, SceneLightList));
+            panel.spawn(Node { column_gap: Val::Px(4.0), ..default() }).with_children(|line| {
+                for kind in SceneLightKind::ALL {
+                    line.spawn((Button, Node { padding: UiRect::axes(Val::Px(4.0), Val::Px(1.0)), ..default() }, BackgroundColor(BUTTON_IDLE), SceneLightButton::Add(kind)))
+                        .with_child(Text::new(format!("+ {}", kind.label())));
+                }
+            });
+        });
+}
+
+/// Preset buttons switch the lighting; the panel shows the current configuration
+pub fn lighting_panel_system(
+    manager: Res<LightManager>,
+    pressed: Query<(&Interaction, &LightingButton), Changed<Interaction>>,
+    mut buttons: Query<(&LightingButton, &mut BackgroundColor)>,
+    mut text: Query<&mut Text, With<RakingAngleText>>,
+    mut presets: EventWriter<SetLightingPreset>,
+) {
+    for (interaction, button) in pressed.iter() {
+        if *interaction == Interaction::Pressed {
+            presets.write(SetLightingPreset(button.0));
+        }
+    }
+    if !manager.is_changed() {
+        return;
+    }
+    for (button, mut color) in buttons.iter_mut() {
+        color.0 = if button.0 == manager.preset { BUTTON_ACTIVE } else { BUTTON_IDLE };
+    }
+    for mut text in text.iter_mut() {
+        text.0 = match manager.preset {
+            LightingPreset::Raking => format!("Angle: {:.0} deg ([ / ])", manager.raking_angle),
+            _ => String::new(),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_rebuild_lights() {
+        let mut app = App::new();
+        app.init_resource::<LightManager>()
+            .add_event::<SetLightingPreset>()
+            .add_event::<SetRakingAngle>()
+            .add_systems(Update, (apply_lighting_requests, sync_managed_lights, follow_camera_lights).chain());
+        app.world_mut().spawn((Camera3d::default(), Transform::default().looking_to(Vec3::X, Vec3::Y)));
+        app.update();
+        let count = |app: &mut App| app.world_mut().query::<&ManagedLight>().iter(app.world()).count();
+        assert_eq!(count(&mut app), 3);
+
+        app.world_mut().send_event(SetLightingPreset(LightingPreset::Raking));
+        app.world_mut().send_event(SetRakingAngle(120.0));
+        app.update();
+        app.update();
+        assert_eq!(count(&mut app), 1);
+        assert_eq!(app.world().resource::<LightManager>().raking_angle, 90.0);
+        // At 90 degrees the raking light shines straight along the view (+X here)
+        let transform = app.world_mut().query_filtered::<&Transform, With<ManagedLight>>().single(app.world()).unwrap();
+        assert!((transform.forward().as_vec3() - Vec3::X).length() < 1e-5);
+    }
+
+    #[test]
+    fn test_scene_lights_add_toggle_remove() {
+        let mut app = App::new();
+        app.init_resource::<SceneLights>()
+            .add_event::<AddSceneLight>()
+            .add_event::<RemoveSceneLight>()
+            .add_event::<ToggleSceneLight>()
+            .add_event::<ScaleSceneLight>()
+            .add_systems(Update, (apply_scene_light_requests, sync_scene_lights).chain());
+        let position = Vec3::new(0.0, 0.0, 10.0);
+        for kind in SceneLightKind::ALL {
+            app.world_mut().send_event(AddSceneLight { kind, position, target: Vec3::ZERO });
+        }
+        app.update();
+        let spawned = |app: &mut App| app.world_mut().query::<&SceneLightController>().iter(app.world()).count();
+        assert_eq!(spawned(&mut app), 3);
+        assert_eq!(app.world_mut().query::<&SpotLight>().iter(app.world()).count(), 1);
+        let lights = app.world().resource::<SceneLights>();
+        let spot = lights.get(1).unwrap();
+        assert_eq!(spot.name, "Spot 2");
+        assert!((spot.direction - Vec3::NEG_Z).length() < 1e-6);
+        assert!((spot.transform().forward().as_vec3() - Vec3::NEG_Z).length() < 1e-6);
+
+        app.world_mut().send_event(ToggleSceneLight(0));
+        app.world_mut().send_event(ScaleSceneLight { id: 2, factor: 2.0 });
+        app.world_mut().send_event(RemoveSceneLight(1));
+        app.update();
+        assert_eq!(spawned(&mut app), 1);
+        let lights = app.world().resource::<SceneLights>();
+        assert!(!lights.get(0).unwrap().enabled && lights.get(1).is_none());
+        assert_eq!(lights.get(2).unwrap().intensity, KEY_ILLUMINANCE * SCENE_LIGHT_SHARE * 2.0);
+        assert_eq!(SceneLights::default().remove(7), Err(SceneLightError::UnknownLight(7)));
+    }
+}
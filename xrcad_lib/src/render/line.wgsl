@@ -0,0 +1,76 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+// Screen-width lines for render::lines. Every segment is a quad whose corners
+// sit on the segment's ends; the vertex shader pushes them sideways by half
+// the line width (plus a pixel for feathering) in screen space.
+
+#import bevy_pbr::{
+    mesh_functions::get_world_from_local,
+    mesh_view_bindings::view,
+    view_transformations::position_world_to_clip,
+}
+
+struct LineUniform {
+    color: vec4<f32>,
+    width: f32,
+    dash: f32,
+    gap: f32,
+};
+
+@group(2) @binding(0) var<uniform> material: LineUniform;
+
+struct Vertex {
+    @builtin(instance_index) instance_index: u32,
+    @location(0) position: vec3<f32>,
+    @location(1) other: vec3<f32>,
+    // side (-1 or 1), distance along the line, 1 at a segment's start or -1 at its end
+    @location(2) params: vec3<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    // Signed distance from the center of the line (pixels)
+    @location(0) offset: f32,
+    @location(1) distance: f32,
+};
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    let world_from_local = get_world_from_local(vertex.instance_index);
+    let clip = position_world_to_clip((world_from_local * vec4<f32>(vertex.position, 1.0)).xyz);
+    let other = position_world_to_clip((world_from_local * vec4<f32>(vertex.other, 1.0)).xyz);
+    let resolution = view.viewport.zw;
+    let screen = clip.xy / clip.w * resolution * 0.5;
+    let other_screen = other.xy / other.w * resolution * 0.5;
+    // Segment direction on screen, always from start to end so both ends agree on the sides
+    var direction = (other_screen - screen) * vertex.params.z;
+    if length(direction) < 1e-6 {
+        direction = vec2<f32>(1.0, 0.0);
+    }
+    let normal = normalize(vec2<f32>(-direction.y, direction.x));
+    let half_width = material.width * 0.5 + 1.0;
+    let offset = normal * vertex.params.x * half_width / (resolution * 0.5);
+
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(clip.xy + offset * clip.w, clip.z, clip.w);
+    out.offset = vertex.params.x * half_width;
+    out.distance = vertex.params.y;
+    return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    if material.dash > 0.0 {
+        let period = material.dash + material.gap;
+        if in.distance - floor(in.distance / period) * period > material.dash {
+            discard;
+        }
+    }
+    // Fade out over the last pixel of either side
+    let coverage = clamp(material.width * 0.5 + 0.5 - abs(in.offset), 0.0, 1.0);
+    if coverage <= 0.0 {
+        discard;
+    }
+    return vec4<f32>(material.color.rgb, material.color.a * coverage);
+}
@@ -0,0 +1,407 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: render::lines
+//!
+//! Retained line rendering for the workspace helpers. Segments are built once
+//! into a mesh with one quad per segment and drawn with `LineMaterial`. Its
+//! vertex shader (`line.wgsl`) widens each quad to a constant width in pixels.
+//! Its fragment shader feathers the long sides for anti-aliasing and cuts
+//! dashes along the line. A line is either depth tested against the model or
+//! drawn on top of it as an overlay. Helper meshes are rebuilt only when the
+//! workspace changes. Axes keep their on-screen length by scaling their entity.
+//! Model edges stay gizmo lines in `render::edge_overlay`, because their
+//! silhouettes and highlights change every frame.
+
+use bevy::asset::{embedded_asset, RenderAssetUsages};
+use bevy::pbr::{MaterialPipeline, MaterialPipelineKey, NotShadowCaster};
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, MeshVertexAttribute, MeshVertexBufferLayoutRef, PrimitiveTopology};
+use bevy::render::render_resource::{
+    AsBindGroup, CompareFunction, RenderPipelineDescriptor, ShaderRef, ShaderType, SpecializedMeshPipelineError, VertexFormat,
+};
+
+use nalgebra::Point3;
+
+use crate::model::brep::topology::plane::PlaneExtent;
+use crate::model::brep_model::BrepModel;
+use crate::render::gizmo_scale::{GizmoScale, AXES_PIXELS};
+use crate::viewport::passthrough::PassthroughMode;
+use crate::workspace::workspace::{HelperKind, Workspace, WorkspaceHelper};
+
+const LINE_SHADER_PATH: &str = "embedded://xrcad_lib/render/line.wgsl";
+
+/// Other end of the vertex's segment
+pub const ATTRIBUTE_LINE_OTHER: MeshVertexAttribute = MeshVertexAttribute::new("LineOther", 988_540_917, VertexFormat::Float32x3);
+/// Side of the line (-1 or 1), distance along the line, and 1 at a segment's start or -1 at its end
+pub const ATTRIBUTE_LINE_PARAMS: MeshVertexAttribute = MeshVertexAttribute::new("LineParams", 988_540_918, VertexFormat::Float32x3);
+
+/// Width of helper lines (pixels)
+const HELPER_LINE_WIDTH: f32 = 1.5;
+
+/// How a line relates to the model's depth
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LineDepth {
+    /// Hidden behind nearer geometry
+    #[default]
+    Tested,
+    /// Always drawn on top
+    Overlay,
+}
+
+/// Appearance of a batch of lines
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineStyle {
+    pub color: Color,
+    /// Width (pixels)
+    pub width: f32,
+    /// Dash and gap lengths (world units); `None` for solid lines
+    pub dash: Option<(f32, f32)>,
+    pub depth: LineDepth,
+}
+
+impl LineStyle {
+    pub fn solid(color: Color, width: f32) -> Self {
+        Self { color, width, dash: None, depth: LineDepth::Tested }
+    }
+
+    pub fn dashed(self, dash: f32, gap: f32) -> Self {
+        Self { dash: Some((dash, gap)), ..self }
+    }
+
+    pub fn overlay(self) -> Self {
+        Self { depth: LineDepth::Overlay, ..self }
+    }
+}
+
+/// One segment with the distance along its line at its start, for dashing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineSegment {
+    pub start: Vec3,
+    pub end: Vec3,
+    pub distance: f32,
+}
+
+/// Segments drawn together in one style
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LineBatch {
+    pub segments: Vec<LineSegment>,
+}
+
+impl LineBatch {
+    /// A lone segment; its dashes start at `start`
+    pub fn line(&mut self, start: Vec3, end: Vec3) {
+        self.segments.push(LineSegment { start, end, distance: 0.0 });
+    }
+
+    /// Connected segments through `points`, dashed continuously along them
+    pub fn polyline(&mut self, points: &[Vec3], closed: bool) {
+        let mut distance = 0.0;
+        let closing = points.first().filter(|_| closed && points.len() > 2);
+        for (start, end) in points.iter().zip(points.iter().skip(1).chain(closing)) {
+            self.segments.push(LineSegment { start: *start, end: *end, distance });
+            distance += start.distance(*end);
+        }
+    }
+
+    /// Mesh of one quad per segment; the shader moves each corner sideways
+    pub fn into_mesh(self) -> Mesh {
+        let count = self.segments.len() * 4;
+        let (mut positions, mut others, mut params) = (Vec::with_capacity(count), Vec::with_capacity(count), Vec::with_capacity(count));
+        let mut indices = Vec::with_capacity(self.segments.len() * 6);
+        for segment in &self.segments {
+            let base = positions.len() as u32;
+            let end_distance = segment.distance + segment.start.distance(segment.end);
+            for (point, other, distance, along) in
+                [(segment.start, segment.end, segment.distance, 1.0), (segment.end, segment.start, end_distance, -1.0)]
+            {
+                for side in [-1.0, 1.0] {
+                    positions.push(point.to_array());
+                    others.push(other.to_array());
+                    params.push([side, distance, along]);
+                }
+            }
+            indices.extend([base, base + 1, base + 2, base + 2, base + 1, base + 3]);
+        }
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default());
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        mesh.insert_attribute(ATTRIBUTE_LINE_OTHER, others);
+        mesh.insert_attribute(ATTRIBUTE_LINE_PARAMS, params);
+        mesh.insert_indices(Indices::U32(indices));
+        mesh
+    }
+}
+
+/// Shader uniform of a line material
+#[derive(ShaderType, Debug, Clone, Copy, PartialEq)]
+pub struct LineUniform {
+    pub color: LinearRgba,
+    pub width: f32,
+    /// Dash length; zero for solid lines
+    pub dash: f32,
+    pub gap: f32,
+}
+
+/// Screen-width, anti-aliased line material
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+#[bind_group_data(LineMaterialKey)]
+pub struct LineMaterial {
+    #[uniform(0)]
+    pub uniform: LineUniform,
+    pub depth: LineDepth,
+}
+
+impl LineMaterial {
+    pub fn new(style: &LineStyle) -> Self {
+        let (dash, gap) = style.dash.unwrap_or((0.0, 0.0));
+        Self { uniform: LineUniform { color: style.color.into(), width: style.width, dash, gap }, depth: style.depth }
+    }
+}
+
+/// Pipeline variant of a line material
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LineMaterialKey {
+    depth: LineDepth,
+}
+
+impl From<&LineMaterial> for LineMaterialKey {
+    fn from(material: &LineMaterial) -> Self {
+        Self { depth: material.depth }
+    }
+}
+
+impl Material for LineMaterial {
+    fn vertex_shader() -> ShaderRef {
+        LINE_SHADER_PATH.into()
+    }
+
+    fn fragment_shader() -> ShaderRef {
+        LINE_SHADER_PATH.into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode {
+        AlphaMode::Blend
+    }
+
+    fn specialize(
+        _pipeline: &MaterialPipeline<Self>,
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayoutRef,
+        key: MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        let vertex_layout = layout.0.get_layout(&[
+            Mesh::ATTRIBUTE_POSITION.at_shader_location(0),
+            ATTRIBUTE_LINE_OTHER.at_shader_location(1),
+            ATTRIBUTE_LINE_PARAMS.at_shader_location(2),
+        ])?;
+        descriptor.vertex.buffers = vec![vertex_layout];
+        descriptor.primitive.cull_mode = None;
+        if key.bind_group_data.depth == LineDepth::Overlay {
+            if let Some(depth_stencil) = descriptor.depth_stencil.as_mut() {
+                depth_stencil.depth_compare = CompareFunction::Always;
+                depth_stencil.depth_write_enabled = false;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Registers the line material and its embedded shader
+pub struct LinePlugin;
+
+impl Plugin for LinePlugin {
+    fn build(&self, app: &mut App) {
+        embedded_asset!(app, "line.wgsl");
+        app.add_plugins(MaterialPlugin::<LineMaterial> { prepass_enabled: false, shadows_enabled: false, ..default() });
+    }
+}
+
+/// Lines of a workspace helper, by helper id
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+pub struct HelperLines(pub String);
+
+/// Lines drawn at a constant screen size: unit length, scaled every frame
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct ScreenSizedLines {
+    /// On-screen length of one unit (pixels)
+    pub pixels: f32,
+}
+
+/// Batches drawing one helper at its size and in its color; `None` for
+/// helpers without lines. Planes with an automatic extent are fitted around
+/// the nearby model `vertices`.
+pub fn helper_batches(helper: &WorkspaceHelper, vertices: &[Point3<f64>]) -> Option<Vec<(LineStyle, LineBatch)>> {
+    let tint = |color: Color| match helper.color {
+        Some([r, g, b]) => Color::srgba(r, g, b, color.alpha()),
+        None => color,
+    };
+    match &helper.kind {
+        HelperKind::Axes(axes) => Some(
+            axes.directions()
+                .into_iter()
+                .map(|(axis, color)| {
+                    let mut batch = LineBatch::default();
+                    batch.line(Vec3::ZERO, axis);
+                    (LineStyle::solid(tint(color), HELPER_LINE_WIDTH * 1.5), batch)
+                })
+                .collect(),
+        ),
+        HelperKind::Plane(plane) => {
+            let color = tint(plane.render_color());
+            let mut rect = plane.render_rect(vertices);
+            rect.half_u *= helper.size as f64;
+            rect.half_v *= helper.size as f64;
+            let mut outline = LineBatch::default();
+            for [a, b] in plane.outline_segments(&rect) {
+                outline.line(a, b);
+            }
+            let mut batches = vec![(LineStyle::solid(color, HELPER_LINE_WIDTH), outline)];
+            let grid = plane.grid_segments(&rect);
+            if !grid.is_empty() {
+                let mut lines = LineBatch::default();
+                for [a, b] in grid {
+                    lines.line(a, b);
+                }
+                batches.push((LineStyle::solid(color.with_alpha(color.alpha() * 0.7), 1.0), lines));
+            }
+            Some(batches)
+        }
+        _ => None,
+    }
+}
+
+/// Rebuild the helper line meshes when the workspace (or passthrough) changes,
+/// or when the model changes under a plane with an automatic extent. Hidden
+/// helpers are skipped, and over passthrough only the axes are drawn.
+pub fn sync_helper_lines(
+    mut commands: Commands,
+    workspace: Res<Workspace>,
+    (passthrough, model): (Option<Res<PassthroughMode>>, Option<Res<BrepModel>>),
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<LineMaterial>>,
+    old: Query<Entity, With<HelperLines>>,
+) {
+    let auto_extent = workspace
+        .helpers
+        .iter()
+        .any(|helper| matches!(&helper.kind, HelperKind::Plane(plane) if matches!(plane.extent, PlaneExtent::Auto { .. })));
+    let model_changed = auto_extent && model.as_ref().is_some_and(|m| m.is_changed());
+    if !workspace.is_changed() && !passthrough.as_ref().is_some_and(|p| p.is_changed()) && !model_changed {
+        return;
+    }
+    let vertices: Vec<Point3<f64>> = match &model {
+        Some(model) if auto_extent => model.vertices.iter().map(|v| Point3::from(v.position)).collect(),
+        _ => Vec::new(),
+    };
+    for entity in old.iter() {
+        commands.entity(entity).despawn();
+    }
+    let minimal = passthrough.is_some_and(|p| p.is_active());
+    for helper in &workspace.helpers {
+        if !helper.is_visible() || (minimal && !matches!(helper.kind, HelperKind::Axes(_))) {
+            continue;
+        }
+        let Some(batches) = helper_batches(helper, &vertices) else { continue };
+        for (style, batch) in batches {
+            let mut entity = commands.spawn((
+                Mesh3d(meshes.add(batch.into_mesh())),
+                MeshMaterial3d(materials.add(LineMaterial::new(&style))),
+                Transform::default(),
+                NotShadowCaster,
+                HelperLines(helper.id.clone()),
+            ));
+            if matches!(helper.kind, HelperKind::Axes(_)) {
+                entity.insert(ScreenSizedLines { pixels: AXES_PIXELS * helper.size });
+            }
+        }
+    }
+}
+
+/// Scale screen-sized lines so they keep their on-screen length while zooming
+pub fn scale_screen_sized_lines(scale: Option<Res<GizmoScale>>, mut lines: Query<(&mut Transform, &ScreenSizedLines)>) {
+    let scale = scale.as_deref().copied().unwrap_or_default();
+    for (mut transform, sized) in lines.iter_mut() {
+        let size = scale.world_size(transform.translation, sized.pixels);
+        if transform.scale.x != size {
+            transform.scale = Vec3::splat(size);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::topology::plane::{Plane, PLANE_RENDER_SIZE};
+    use crate::workspace::helpers::axes::Axes;
+
+    #[test]
+    fn test_polyline_mesh_and_dash_distances() {
+        let mut batch = LineBatch::default();
+        batch.polyline(&[Vec3::ZERO, Vec3::X, Vec3::new(1.0, 2.0, 0.0)], true);
+        assert_eq!(batch.segments.len(), 3);
+        assert_eq!(batch.segments[2].distance, 3.0);
+        let mesh = batch.into_mesh();
+        assert_eq!(mesh.count_vertices(), 12);
+        assert_eq!(mesh.indices().unwrap().len(), 18);
+        assert!(mesh.attribute(ATTRIBUTE_LINE_OTHER).is_some() && mesh.attribute(ATTRIBUTE_LINE_PARAMS).is_some());
+
+        let style = LineStyle::solid(Color::WHITE, 2.0).dashed(5.0, 3.0).overlay();
+        let material = LineMaterial::new(&style);
+        assert_eq!((material.uniform.dash, material.uniform.gap), (5.0, 3.0));
+        assert_eq!(LineMaterialKey::from(&material).depth, LineDepth::Overlay);
+    }
+
+    #[test]
+    fn test_helper_lines_are_retained_and_axes_scaled() {
+        let mut app = App::new();
+        let mut workspace = Workspace::new();
+        workspace.add_helper("axes", HelperKind::Axes(Axes));
+        app.insert_resource(workspace)
+            .init_resource::<Assets<Mesh>>()
+            .init_resource::<Assets<LineMaterial>>()
+            .insert_resource(GizmoScale::default())
+            .add_systems(Update, (sync_helper_lines, scale_screen_sized_lines).chain());
+        app.update();
+        let count = |app: &mut App| app.world_mut().query::<&HelperLines>().iter(app.world()).count();
+        assert_eq!(count(&mut app), 3);
+        let scale = app.world_mut().query_filtered::<&Transform, With<ScreenSizedLines>>().iter(app.world()).next().unwrap().scale;
+        assert_eq!(scale, Vec3::splat(AXES_PIXELS));
+
+        // Nothing is rebuilt while the workspace is unchanged
+        let before: Vec<Entity> = app.world_mut().query_filtered::<Entity, With<HelperLines>>().iter(app.world()).collect();
+        app.update();
+        let after: Vec<Entity> = app.world_mut().query_filtered::<Entity, With<HelperLines>>().iter(app.world()).collect();
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn test_auto_extent_plane_fits_nearby_vertices() {
+        let mut plane = Plane::xy();
+        plane.set_extent(PlaneExtent::Auto { reach: 1.0, margin: 5.0 });
+        let vertices = [
+            Point3::new(10.0, 20.0, 0.0),
+            Point3::new(30.0, 60.0, 0.5),
+            // Too far from the plane to count
+            Point3::new(500.0, 500.0, 50.0),
+        ];
+        let rect = plane.render_rect(&vertices);
+        assert!((rect.center - Point3::new(20.0, 40.0, 0.0)).norm() < 1e-9);
+        let mut halves = [rect.half_u, rect.half_v];
+        halves.sort_by(f64::total_cmp);
+        assert_eq!(halves, [15.0, 25.0]);
+
+        // Without nearby geometry the plane keeps the default size
+        assert_eq!(plane.render_rect(&[]).half_u, PLANE_RENDER_SIZE);
+        let mut workspace = Workspace::new();
+        workspace.add_helper("plane", HelperKind::Plane(plane));
+        let helper = workspace.get_helper_mut("plane").unwrap();
+        helper.size = 2.0;
+        helper.color = Some([1.0, 0.5, 0.0]);
+        let Some(batches) = helper_batches(helper, &vertices) else { panic!() };
+        assert_eq!(batches[0].1.segments.len(), 4);
+        assert_eq!(batches[0].0.color.to_srgba().green, 0.5);
+        // Twice the fitted size: 50 mm from the center along the longer side
+        let far = batches[0].1.segments.iter().map(|s| (s.start - Vec3::new(20.0, 40.0, 0.0)).length()).fold(0.0, f32::max);
+        assert!((far - (50f32.powi(2) + 30f32.powi(2)).sqrt()).abs() < 1e-3);
+    }
+}
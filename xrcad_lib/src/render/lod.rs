@@ -0,0 +1,159 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: render::lod
+//!
+//! Coarser face meshes for bodies that are small on screen. Besides the full
+//! mesh, each body gets simplified levels made by vertex clustering: vertices
+//! are snapped to a grid sized to the body and triangles that collapse are
+//! dropped. Each frame a body shows the level its size on screen calls for,
+//! so dense scenes keep their frame rate in XR.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::render::brep_refs::BrepFaceRefs;
+use crate::render::display_mode::BodyTriangles;
+use crate::render::gizmo_scale::GizmoScale;
+
+/// Grid cells across a body at each simplified level, finest first
+const LOD_CELLS: [f32; 2] = [16.0, 4.0];
+
+/// When bodies switch to simplified meshes
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct LodSettings {
+    pub enabled: bool,
+    /// Bodies smaller than this on screen use the first simplified level (pixels)
+    pub medium_pixels: f32,
+    /// Bodies smaller than this on screen use the coarsest level (pixels)
+    pub low_pixels: f32,
+}
+
+impl Default for LodSettings {
+    fn default() -> Self {
+        Self { enabled: true, medium_pixels: 160.0, low_pixels: 40.0 }
+    }
+}
+
+impl LodSettings {
+    /// Level for a body spanning `pixels` on screen; 0 is the full mesh
+    pub fn level(&self, pixels: f32) -> usize {
+        if !self.enabled || pixels >= self.medium_pixels {
+            0
+        } else if pixels >= self.low_pixels {
+            1
+        } else {
+            2
+        }
+    }
+}
+
+/// Triangles simplified by clustering vertices into cubes of size `cell`.
+/// Triangles whose corners share a cell or that flip are dropped; the rest
+/// keep their texture coordinates and face ids and get flat normals.
+pub fn simplify(triangles: &BodyTriangles, cell: f32) -> BodyTriangles {
+    let key = |p: &[f32; 3]| (Vec3::from(*p) / cell).floor().as_ivec3();
+    let mut clusters: HashMap<IVec3, (Vec3, f32)> = HashMap::new();
+    for p in &triangles.positions {
+        let entry = clusters.entry(key(p)).or_insert((Vec3::ZERO, 0.0));
+        *entry = (entry.0 + Vec3::from(*p), entry.1 + 1.0);
+    }
+    let snapped = |i: u32| {
+        let (sum, count) = clusters[&key(&triangles.positions[i as usize])];
+        sum / count
+    };
+    let mut out = BodyTriangles::default();
+    for (triangle, face) in triangles.indices.chunks_exact(3).zip(&triangles.faces) {
+        let corners = [triangle[0], triangle[1], triangle[2]];
+        let keys = corners.map(|i| key(&triangles.positions[i as usize]));
+        if keys[0] == keys[1] || keys[1] == keys[2] || keys[0] == keys[2] {
+            continue;
+        }
+        let [a, b, c] = [snapped(corners[0]), snapped(corners[1]), snapped(corners[2])];
+        let normal = (b - a).cross(c - a).normalize_or_zero();
+        if normal.dot(Vec3::from(triangles.normals[corners[0] as usize])) <= 0.0 {
+            continue;
+        }
+        let base = out.positions.len() as u32;
+        out.positions.extend([a, b, c].map(|p| p.to_array()));
+        out.normals.extend([normal.to_array(); 3]);
+        out.uvs.extend(corners.iter().map(|i| triangles.uvs[*i as usize]));
+        out.indices.extend([base, base + 1, base + 2]);
+        out.faces.push(*face);
+    }
+    out
+}
+
+/// The meshes of a body at each level and the level shown
+#[derive(Component, Debug, Clone)]
+pub struct BodyLods {
+    pub levels: Vec<(Handle<Mesh>, BrepFaceRefs)>,
+    /// Radius of the body's bounds around the mesh origin
+    pub radius: f32,
+    pub current: usize,
+}
+
+impl BodyLods {
+    /// Full mesh and simplified levels of recentered triangles
+    pub fn new(full: Handle<Mesh>, triangles: &BodyTriangles, tangents: bool, meshes: &mut Assets<Mesh>) -> Self {
+        let radius = triangles.positions.iter().map(|p| Vec3::from(*p).length()).fold(0.0, f32::max);
+        let mut levels = vec![(full, BrepFaceRefs(triangles.faces.clone()))];
+        for cells in LOD_CELLS {
+            let coarse = simplify(triangles, (2.0 * radius / cells).max(f32::EPSILON));
+            // A level that loses every triangle would make the body vanish
+            let coarse = if coarse.indices.is_empty() { triangles.clone() } else { coarse };
+            let faces = BrepFaceRefs(coarse.faces.clone());
+            levels.push((meshes.add(coarse.into_mesh(tangents)), faces));
+        }
+        Self { levels, radius, current: 0 }
+    }
+}
+
+/// Show each body at the level its size on screen calls for
+pub fn select_body_lods(
+    settings: Res<LodSettings>,
+    scale: Option<Res<GizmoScale>>,
+    mut bodies: Query<(&mut BodyLods, &GlobalTransform, &mut Mesh3d, &mut BrepFaceRefs)>,
+) {
+    let scale = scale.as_deref().copied().unwrap_or_default();
+    for (mut lods, transform, mut mesh, mut faces) in bodies.iter_mut() {
+        let pixels = 2.0 * lods.radius / scale.world_size(transform.translation(), 1.0);
+        let level = settings.level(pixels).min(lods.levels.len() - 1);
+        if level == lods.current {
+            continue;
+        }
+        lods.current = level;
+        mesh.0 = lods.levels[level].0.clone();
+        *faces = lods.levels[level].1.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::cylinder;
+    use crate::render::display_mode::body_triangles;
+    use crate::render::section::SectionView;
+
+    #[test]
+    fn test_simplified_levels_have_fewer_triangles() {
+        let model = cylinder(10.0, 5.0, 64);
+        let faces: Vec<usize> = model.faces.iter().map(|f| f.id).collect();
+        let full = body_triangles(&model, &faces, &SectionView::default());
+        let medium = simplify(&full, 20.0 / 16.0);
+        let low = simplify(&full, 20.0 / 4.0);
+        let count = |t: &BodyTriangles| t.indices.len() / 3;
+        assert!(count(&low) < count(&medium) && count(&medium) < count(&full), "{} {} {}", count(&full), count(&medium), count(&low));
+        assert!(count(&low) > 0);
+        assert_eq!(medium.faces.len(), count(&medium));
+        assert_eq!(medium.positions.len(), medium.uvs.len());
+    }
+
+    #[test]
+    fn test_levels_follow_screen_size() {
+        let settings = LodSettings::default();
+        assert_eq!([settings.level(500.0), settings.level(100.0), settings.level(10.0)], [0, 1, 2]);
+        assert_eq!(LodSettings { enabled: false, ..settings }.level(10.0), 0);
+    }
+}
@@ -2,6 +2,27 @@
 // Copyright (c) 2025 Adrian Scarlett
 
 //! Module: render::materials
+//!
+//! Renderer materials built from each body's `Material`: base color (or its
+//! group's color override), alpha, metallic, roughness and reflectance, plus
+//! any texture paths, loaded through the asset server as repeating textures.
+//! Bevy reads roughness and metallic from the green and blue channels of one
+//! texture, so a material with both uses its roughness texture for the two.
+//! Ghosted bodies get a see-through version of their material. When a body's
+//! material or ghosting changes its face mesh keeps its mesh and only the
+//! material asset is rewritten.
+
+use bevy::image::{ImageAddressMode, ImageLoaderSettings, ImageSampler, ImageSamplerDescriptor};
+use bevy::prelude::*;
+
+use crate::model::body::BodyId;
+use crate::model::groups::BodyGroups;
+use crate::model::material::Material;
+use crate::model::properties::BodyPropertiesCollection;
+use crate::render::analysis::analysis_material;
+use crate::render::brep_refs::BrepBodyRef;
+use crate::render::display_mode::{BodyFaceMesh, DisplayMode, DisplaySettings, HIDDEN_LINE_FILL};
+use crate::render::ghosting::ghost_material;
 
 /// Materials render struct.
 pub struct Materials;
@@ -12,6 +33,98 @@ impl Materials {
     }
 }
 
+/// Load a texture that repeats across a face; color textures are sRGB, data
+/// textures (normals, roughness, metallic) linear
+pub fn load_texture(asset_server: &AssetServer, path: &str, srgb: bool) -> Handle<Image> {
+    asset_server.load_with_settings(path.to_string(), move |settings: &mut ImageLoaderSettings| {
+        settings.is_srgb = srgb;
+        settings.sampler = ImageSampler::Descriptor(ImageSamplerDescriptor {
+            address_mode_u: ImageAddressMode::Repeat,
+            address_mode_v: ImageAddressMode::Repeat,
+            ..ImageSamplerDescriptor::linear()
+        });
+    })
+}
+
+/// Shaded material for a body with the given material and base color;
+/// `load(path, srgb)` provides the textures
+pub fn standard_material(material: &Material, base_color: [f32; 3], mut load: impl FnMut(&str, bool) -> Handle<Image>) -> StandardMaterial {
+    let [r, g, b] = base_color;
+    let metallic_roughness = material.roughness_texture.as_ref().or(material.metallic_texture.as_ref());
+    StandardMaterial {
+        base_color: Color::srgba(r, g, b, material.alpha),
+        alpha_mode: if material.alpha < 1.0 { AlphaMode::Blend } else { AlphaMode::Opaque },
+        metallic: material.metallic,
+        perceptual_roughness: material.roughness,
+        reflectance: material.reflectance,
+        base_color_texture: material.diffuse_texture.as_deref().map(|path| load(path, true)),
+        normal_map_texture: material.normal_texture.as_deref().map(|path| load(path, false)),
+        metallic_roughness_texture: metallic_roughness.map(|path| load(path, false)),
+        ..default()
+    }
+}
+
+/// Flat, unlit fill hiding what lies behind a hidden-line body
+pub fn hidden_line_material(fill: Color) -> StandardMaterial {
+    StandardMaterial { base_color: fill, unlit: true, ..default() }
+}
+
+/// Shaded material of a body, from its properties and group
+pub fn body_material(body: BodyId, properties: &BodyPropertiesCollection, groups: &BodyGroups, asset_server: &AssetServer) -> StandardMaterial {
+    let color = groups.body_color(body, properties);
+    let load = |path: &str, srgb| load_texture(asset_server, path, srgb);
+    match properties.get(body) {
+        Some(props) => standard_material(&props.material, color, load),
+        None => standard_material(&Material::default(), color, load),
+    }
+}
+
+/// Material of a body's face mesh in the given mode: shaded, the hidden-line
+/// `fill` or its surface analysis colors, see-through when the body is ghosted
+pub fn face_material(
+    body: BodyId,
+    mode: DisplayMode,
+    fill: Color,
+    properties: &BodyPropertiesCollection,
+    groups: &BodyGroups,
+    asset_server: &AssetServer,
+) -> StandardMaterial {
+    let material = if properties.analysis_of(body).is_some() {
+        analysis_material()
+    } else if mode == DisplayMode::HiddenLine {
+        hidden_line_material(fill)
+    } else {
+        body_material(body, properties, groups, asset_server)
+    };
+    if properties.is_ghosted(body) {
+        ghost_material(material)
+    } else {
+        material
+    }
+}
+
+/// Rewrite the materials of face meshes when body materials, ghosting or group colors change
+pub fn update_body_materials(
+    properties: Res<BodyPropertiesCollection>,
+    groups: Res<BodyGroups>,
+    display: Res<DisplaySettings>,
+    meshes: Query<(&BrepBodyRef, &MeshMaterial3d<StandardMaterial>), With<BodyFaceMesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+    clear_color: Option<Res<ClearColor>>,
+) {
+    if !properties.is_changed() && !groups.is_changed() {
+        return;
+    }
+    let fill = clear_color.as_ref().map_or(HIDDEN_LINE_FILL, |c| c.0);
+    for (BrepBodyRef(body), handle) in meshes.iter() {
+        let mode = display.mode_of(*body, &properties);
+        if let Some(material) = materials.get_mut(&handle.0) {
+            *material = face_material(*body, mode, fill, &properties, &groups, &asset_server);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -20,4 +133,41 @@ mod tests {
         let m = Materials::new();
         let _ = m;
     }
+
+    #[test]
+    fn test_standard_material_from_body_material() {
+        let mut glass = Material::new("Glass", [0.2, 0.4, 0.6]);
+        glass.alpha = 0.3;
+        glass.roughness = 0.1;
+        let standard = standard_material(&glass, glass.base_color, |_, _| Handle::default());
+        assert_eq!(standard.alpha_mode, AlphaMode::Blend);
+        assert_eq!(standard.base_color, Color::srgba(0.2, 0.4, 0.6, 0.3));
+        assert_eq!(standard.perceptual_roughness, 0.1);
+        assert!(standard.base_color_texture.is_none());
+        let plain = standard_material(&Material::default(), [1.0, 0.0, 0.0], |_, _| Handle::default());
+        assert_eq!(plain.alpha_mode, AlphaMode::Opaque);
+    }
+
+    #[test]
+    fn test_standard_material_textures() {
+        let mut wood = Material::new("Wood", [1.0, 1.0, 1.0]);
+        wood.diffuse_texture = Some("textures/wood.png".to_string());
+        wood.normal_texture = Some("textures/wood_normal.png".to_string());
+        wood.metallic_texture = Some("textures/wood_metal.png".to_string());
+        let mut loaded = Vec::new();
+        let standard = standard_material(&wood, wood.base_color, |path, srgb| {
+            loaded.push((path.to_string(), srgb));
+            Handle::default()
+        });
+        assert!(standard.base_color_texture.is_some() && standard.normal_map_texture.is_some());
+        assert!(standard.metallic_roughness_texture.is_some());
+        assert_eq!(
+            loaded,
+            [
+                ("textures/wood.png".to_string(), true),
+                ("textures/wood_normal.png".to_string(), false),
+                ("textures/wood_metal.png".to_string(), false),
+            ]
+        );
+    }
 }
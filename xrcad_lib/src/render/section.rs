@@ -0,0 +1,312 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: render::section
+//!
+//! Section view: a construction plane cuts the model in the viewport. Geometry
+//! on the normal side of the plane is not drawn, and the cut itself is capped
+//! with its outline and hatching, both computed from the BREP faces crossing
+//! the plane. Hatching fills by even-odd crossings, so the cavities of hollow
+//! bodies are left open. H toggles the section, Shift+H takes the plane from
+//! the selected face or cycles through XY, YZ and ZX, Ctrl+H toggles the caps
+//! and Shift+PageUp/Shift+PageDown move the plane along its normal.
+
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+use nalgebra::{Point3, Vector3};
+
+use crate::color::{CYAN, RED};
+use crate::input::keyboard::KeyBindings;
+use crate::interaction::selection::Selection;
+use crate::model::brep::topology::plane::{Plane, PlaneOrigin, PlaneRenderMode};
+use crate::model::brep_model::{na_vec3_to_bevy, BrepModel};
+use crate::telemetry::crash::journal;
+use crate::telemetry::usage::UsageStats;
+
+/// Plane movement per PageUp/PageDown press (model units)
+const SECTION_STEP: f64 = 5.0;
+/// Upper bound on hatch lines per cap, so a fine spacing on a large cut stays cheap
+const MAX_HATCH_LINES: usize = 200;
+
+/// Section plane state; geometry where `plane.distance` is positive is clipped away
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct SectionView {
+    pub enabled: bool,
+    pub plane: Plane,
+    /// Draw the cut outline and hatching
+    pub caps: bool,
+    /// Distance between hatch lines (model units)
+    pub hatch_spacing: f64,
+    /// Hatch direction, measured from the plane's first in-plane axis (radians)
+    pub hatch_angle: f64,
+}
+
+impl Default for SectionView {
+    fn default() -> Self {
+        Self { enabled: false, plane: section_plane(Plane::zx()), caps: true, hatch_spacing: 2.0, hatch_angle: std::f64::consts::FRAC_PI_4 }
+    }
+}
+
+impl SectionView {
+    /// True if the point is on the clipped side of an enabled section
+    pub fn clips(&self, p: &Vector3<f64>) -> bool {
+        self.enabled && self.plane.distance(&Point3::from(*p)) > 0.0
+    }
+
+    /// The part of segment `a`-`b` that stays visible, if any
+    pub fn clip_segment(&self, a: &Vector3<f64>, b: &Vector3<f64>) -> Option<(Vector3<f64>, Vector3<f64>)> {
+        if !self.enabled {
+            return Some((*a, *b));
+        }
+        let (da, db) = (self.plane.distance(&Point3::from(*a)), self.plane.distance(&Point3::from(*b)));
+        match (da > 0.0, db > 0.0) {
+            (true, true) => None,
+            (false, false) => Some((*a, *b)),
+            _ => {
+                let hit = a + (b - a) * (da / (da - db));
+                Some(if da > 0.0 { (hit, *b) } else { (*a, hit) })
+            }
+        }
+    }
+}
+
+/// A plane as shown while sectioning
+fn section_plane(mut plane: Plane) -> Plane {
+    plane.set_render_mode(PlaneRenderMode::Ghosted);
+    plane
+}
+
+/// Point of the plane its quad is drawn around
+fn plane_center(plane: &Plane) -> Point3<f64> {
+    match plane.origin {
+        PlaneOrigin::PointNormal { point, .. } => point,
+        _ => Point3::origin() - plane.normal * plane.d,
+    }
+}
+
+/// Segments where the plane cuts the model's faces. Each face contributes the
+/// stretches of the cut line lying inside it; on a closed shell they join up
+/// into the outline of the section.
+pub fn section_segments(model: &BrepModel, plane: &Plane) -> Vec<(Vector3<f64>, Vector3<f64>)> {
+    let mut segments = Vec::new();
+    for face in &model.faces {
+        let Some(normal) = model.face_normal(face) else { continue };
+        if model.tolerance.parallel(&normal, &plane.normal) {
+            continue;
+        }
+        let dir = plane.normal.cross(&normal);
+        // A vertex on the plane counts as behind it, so each crossing is found once
+        let mut crossings: Vec<(f64, Vector3<f64>)> = Vec::new();
+        for l in model.face_loops(face) {
+            let points = model.loop_positions(l);
+            for (i, a) in points.iter().enumerate() {
+                let b = &points[(i + 1) % points.len()];
+                let (da, db) = (plane.distance(&Point3::from(*a)), plane.distance(&Point3::from(*b)));
+                if (da > 0.0) != (db > 0.0) {
+                    let p = a + (b - a) * (da / (da - db));
+                    crossings.push((p.dot(&dir), p));
+                }
+            }
+        }
+        crossings.sort_by(|x, y| x.0.total_cmp(&y.0));
+        segments.extend(
+            crossings
+                .chunks_exact(2)
+                .map(|pair| (pair[0].1, pair[1].1))
+                .filter(|(a, b)| !model.tolerance.is_zero_length((b - a).norm())),
+        );
+    }
+    segments
+}
+
+/// Hatch lines filling the regions enclosed by `outline` (even-odd), `spacing`
+/// apart at `angle` to the plane's first in-plane axis
+pub fn hatch_lines(outline: &[(Vector3<f64>, Vector3<f64>)], plane: &Plane, spacing: f64, angle: f64) -> Vec<(Vector3<f64>, Vector3<f64>)> {
+    let (u, v) = plane.in_plane_axes();
+    let (along, across) = (u * angle.cos() + v * angle.sin(), v * angle.cos() - u * angle.sin());
+    let origin = plane_center(plane).coords;
+    let to_2d = |p: &Vector3<f64>| ((p - origin).dot(&along), (p - origin).dot(&across));
+    let edges: Vec<((f64, f64), (f64, f64))> = outline.iter().map(|(a, b)| (to_2d(a), to_2d(b))).collect();
+    if edges.is_empty() || spacing <= 0.0 {
+        return Vec::new();
+    }
+    let (min, max) = edges.iter().flat_map(|(a, b)| [a.1, b.1]).fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), y| (lo.min(y), hi.max(y)));
+    let spacing = spacing.max((max - min) / MAX_HATCH_LINES as f64);
+    let mut lines = Vec::new();
+    let mut y = (min / spacing).ceil() * spacing;
+    while y <= max {
+        let mut xs: Vec<f64> = edges
+            .iter()
+            .filter(|(a, b)| (a.1 > y) != (b.1 > y))
+            .map(|(a, b)| a.0 + (b.0 - a.0) * (y - a.1) / (b.1 - a.1))
+            .collect();
+        xs.sort_by(f64::total_cmp);
+        for pair in xs.chunks_exact(2) {
+            lines.push((origin + along * pair[0] + across * y, origin + along * pair[1] + across * y));
+        }
+        y += spacing;
+    }
+    lines
+}
+
+/// Request to turn the section on or off
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ToggleSection;
+
+/// Request to turn the section caps on or off
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ToggleSectionCaps;
+
+/// Request to cut along a new plane (enables the section)
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct SetSectionPlane(pub Plane);
+
+/// Request to move the plane along its normal (model units)
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct OffsetSection(pub f64);
+
+/// The toggle_section binding (H) toggles, section_plane (Shift+H) sets the plane, section_caps
+/// (Ctrl+H) toggles caps, section_forward / section_back (Shift+PageUp / Shift+PageDown) move it
+pub fn section_keys(
+    (keys, bindings): (Res<ButtonInput<KeyCode>>, Res<KeyBindings>),
+    (section, selection, model): (Res<SectionView>, Res<Selection>, Res<BrepModel>),
+    mut toggles: EventWriter<ToggleSection>,
+    mut caps: EventWriter<ToggleSectionCaps>,
+    mut planes: EventWriter<SetSectionPlane>,
+    mut offsets: EventWriter<OffsetSection>,
+) {
+    if bindings.just_pressed("section_caps", &keys) {
+        caps.write(ToggleSectionCaps);
+    } else if bindings.just_pressed("section_plane", &keys) {
+        let from_face = selection.faces().first().and_then(|id| model.face(*id)).and_then(|f| model.face_plane(f));
+        planes.write(SetSectionPlane(from_face.unwrap_or_else(|| next_standard_plane(&section.plane))));
+    } else if bindings.just_pressed("toggle_section", &keys) {
+        toggles.write(ToggleSection);
+    }
+    if section.enabled {
+        if bindings.just_pressed("section_forward", &keys) {
+            offsets.write(OffsetSection(SECTION_STEP));
+        }
+        if bindings.just_pressed("section_back", &keys) {
+            offsets.write(OffsetSection(-SECTION_STEP));
+        }
+    }
+}
+
+/// XY, YZ and ZX in turn, by the direction of the current plane
+fn next_standard_plane(plane: &Plane) -> Plane {
+    if plane.normal.z.abs() > 0.9 {
+        Plane::yz()
+    } else if plane.normal.x.abs() > 0.9 {
+        Plane::zx()
+    } else {
+        Plane::xy()
+    }
+}
+
+/// Update the section from requests
+pub fn apply_section_requests(
+    mut toggles: EventReader<ToggleSection>,
+    mut caps: EventReader<ToggleSectionCaps>,
+    mut planes: EventReader<SetSectionPlane>,
+    mut offsets: EventReader<OffsetSection>,
+    mut section: ResMut<SectionView>,
+    mut usage: Option<ResMut<UsageStats>>,
+) {
+    for _ in toggles.read() {
+        let start = Instant::now();
+        section.enabled = !section.enabled;
+        journal(format!("section {}", if section.enabled { "on" } else { "off" }));
+        if let Some(usage) = usage.as_mut() {
+            usage.record("section_view", start.elapsed());
+        }
+    }
+    for _ in caps.read() {
+        section.caps = !section.caps;
+        journal(format!("section_caps {}", section.caps));
+    }
+    for SetSectionPlane(plane) in planes.read() {
+        journal(format!("section_plane {:?} {}", plane.normal, plane.d));
+        section.plane = section_plane(plane.clone());
+        section.enabled = true;
+    }
+    for OffsetSection(delta) in offsets.read() {
+        journal(format!("section_offset {}", delta));
+        let normal = section.plane.normal.normalize();
+        let center = plane_center(&section.plane) + normal * *delta;
+        section.plane = section_plane(Plane::from_point_normal(center, normal, None));
+    }
+}
+
+/// Draw the section plane and, with caps on, the cut outline and its hatching
+pub fn render_section(mut gizmos: Gizmos, model: Res<BrepModel>, section: Res<SectionView>) {
+    if !section.enabled {
+        return;
+    }
+    section.plane.render(&mut gizmos);
+    if !section.caps {
+        return;
+    }
+    let outline = section_segments(&model, &section.plane);
+    for (a, b) in &outline {
+        gizmos.line(na_vec3_to_bevy(a), na_vec3_to_bevy(b), RED);
+    }
+    for (a, b) in hatch_lines(&outline, &section.plane, section.hatch_spacing, section.hatch_angle) {
+        gizmos.line(na_vec3_to_bevy(&a), na_vec3_to_bevy(&b), CYAN);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::cube;
+
+    #[test]
+    fn test_clip_segment() {
+        let section = SectionView { enabled: true, plane: Plane::xy(), ..default() };
+        let (a, b) = (Vector3::new(0.0, 0.0, -2.0), Vector3::new(0.0, 0.0, 2.0));
+        assert_eq!(section.clip_segment(&a, &b), Some((a, Vector3::zeros())));
+        assert_eq!(section.clip_segment(&b, &(b * 2.0)), None);
+        assert!(section.clips(&b) && !section.clips(&a));
+        assert_eq!(SectionView::default().clip_segment(&b, &(b * 2.0)), Some((b, b * 2.0)));
+    }
+
+    #[test]
+    fn test_section_of_hollow_cube() {
+        // A 10 mm cube with a 4 mm cube cavity, cut through the middle
+        let mut model = cube(10.0);
+        model.append(&cube(4.0));
+        let outline = section_segments(&model, &Plane::xy());
+        assert_eq!(outline.len(), 8);
+        let length: f64 = outline.iter().map(|(a, b)| (b - a).norm()).sum();
+        assert!((length - 56.0).abs() < 1e-9);
+
+        // Hatching covers the wall but not the cavity
+        let hatch = hatch_lines(&outline, &Plane::xy(), 1.0, 0.0);
+        assert!(!hatch.is_empty());
+        for (a, b) in &hatch {
+            let mid = (a + b) / 2.0;
+            assert!(mid.x.abs().max(mid.y.abs()) > 2.0 - 1e-9);
+        }
+        let through_cavity: Vec<_> = hatch.iter().filter(|(a, _)| a.x.abs() < 2.0).collect();
+        assert!(through_cavity.iter().all(|(a, b)| (b - a).norm() < 3.0 + 1e-9));
+    }
+
+    #[test]
+    fn test_section_requests() {
+        let mut app = App::new();
+        app.init_resource::<SectionView>()
+            .add_event::<ToggleSection>()
+            .add_event::<ToggleSectionCaps>()
+            .add_event::<SetSectionPlane>()
+            .add_event::<OffsetSection>()
+            .add_systems(Update, apply_section_requests);
+        app.world_mut().send_event(SetSectionPlane(Plane::xy()));
+        app.world_mut().send_event(OffsetSection(3.0));
+        app.update();
+        let section = app.world().resource::<SectionView>();
+        assert!(section.enabled);
+        assert!((section.plane.distance(&Point3::new(0.0, 0.0, 3.0))).abs() < 1e-12);
+        assert_eq!(section.plane.render_mode, PlaneRenderMode::Ghosted);
+    }
+}
@@ -0,0 +1,390 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: render::settings
+//!
+//! Camera rendering settings: tone mapping, exposure (fixed or automatic),
+//! bloom, screen-space ambient occlusion and the lighting environment. Bevy's
+//! default filmic tone mapping shifts saturated colors, so the default "CAD"
+//! profile turns tone mapping, bloom and auto exposure off and shows material
+//! colors as authored. Automatic exposure needs the app to add
+//! `AutoExposurePlugin` and compute shader support.
+//!
+//! An environment adds image-based lighting from a prefiltered HDRI (a diffuse
+//! and a specular KTX2 cubemap under `assets/environments`) on top of the
+//! directional lights of `render::lighting`. The HDRIs are not bundled; when
+//! an environment's cubemaps fail to load the settings fall back to no
+//! environment, so the realistic profile still renders without them. SSAO
+//! needs MSAA off, so the cameras lose multisampling while it is on. Both are
+//! chosen in the lighting panel.
+
+use bevy::asset::LoadState;
+use bevy::core_pipeline::auto_exposure::AutoExposure;
+use bevy::core_pipeline::bloom::Bloom;
+use bevy::core_pipeline::tonemapping::Tonemapping;
+use bevy::pbr::environment_map::EnvironmentMapLight;
+use bevy::pbr::ScreenSpaceAmbientOcclusion;
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+use bevy::render::camera::Exposure;
+
+use crate::input::keyboard::KeyBindings;
+use crate::render::ui_style::{BUTTON_ACTIVE, BUTTON_IDLE};
+use crate::telemetry::crash::journal;
+use crate::telemetry::usage::UsageStats;
+
+/// Tone mapping curves offered in the settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TonemappingMode {
+    /// Colors pass through unchanged (clamped)
+    None,
+    Reinhard,
+    AcesFitted,
+    AgX,
+    TonyMcMapface,
+}
+
+impl TonemappingMode {
+    pub fn to_bevy(self) -> Tonemapping {
+        match self {
+            TonemappingMode::None => Tonemapping::None,
+            TonemappingMode::Reinhard => Tonemapping::Reinhard,
+            TonemappingMode::AcesFitted => Tonemapping::AcesFitted,
+            TonemappingMode::AgX => Tonemapping::AgX,
+            TonemappingMode::TonyMcMapface => Tonemapping::TonyMcMapface,
+        }
+    }
+}
+
+/// Image-based lighting around the model
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LightingEnvironment {
+    /// Directional lights only
+    None,
+    /// Soft, even light from large overhead softboxes
+    Studio,
+    /// Sky and sun
+    Outdoor,
+    /// Mixed window and lamp light in a workshop interior
+    Workshop,
+}
+
+impl LightingEnvironment {
+    pub const ALL: [LightingEnvironment; 4] =
+        [LightingEnvironment::None, LightingEnvironment::Studio, LightingEnvironment::Outdoor, LightingEnvironment::Workshop];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LightingEnvironment::None => "No environment",
+            LightingEnvironment::Studio => "Studio",
+            LightingEnvironment::Outdoor => "Outdoor",
+            LightingEnvironment::Workshop => "Workshop",
+        }
+    }
+
+    /// Diffuse and specular cubemaps of the HDRI, relative to the assets folder
+    pub fn cubemaps(&self) -> Option<(String, String)> {
+        let name = match self {
+            LightingEnvironment::None => return None,
+            LightingEnvironment::Studio => "studio",
+            LightingEnvironment::Outdoor => "outdoor",
+            LightingEnvironment::Workshop => "workshop",
+        };
+        Some((format!("environments/{}_diffuse.ktx2", name), format!("environments/{}_specular.ktx2", name)))
+    }
+
+    /// Brightness of the environment map (cd/m²)
+    pub fn intensity(&self) -> f32 {
+        match self {
+            LightingEnvironment::None => 0.0,
+            LightingEnvironment::Studio => 1500.0,
+            LightingEnvironment::Outdoor => 2500.0,
+            LightingEnvironment::Workshop => 1000.0,
+        }
+    }
+}
+
+/// Named combinations of the settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderProfile {
+    /// Flat, faithful colors: no tone mapping, bloom or auto exposure
+    Cad,
+    /// Filmic look with bloom and automatic exposure
+    Realistic,
+    /// Edited away from a named profile
+    Custom,
+}
+
+/// Post-processing settings applied to every 3D camera
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct RenderSettings {
+    pub profile: RenderProfile,
+    pub tonemapping: TonemappingMode,
+    /// Exposure value at ISO 100; the starting point when exposure is automatic
+    pub exposure_ev100: f32,
+    pub auto_exposure: bool,
+    pub bloom: bool,
+    /// Screen-space ambient occlusion
+    pub ssao: bool,
+    pub environment: LightingEnvironment,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self::cad()
+    }
+}
+
+impl RenderSettings {
+    pub fn cad() -> Self {
+        Self {
+            profile: RenderProfile::Cad,
+            tonemapping: TonemappingMode::None,
+            exposure_ev100: Exposure::EV100_BLENDER,
+            auto_exposure: false,
+            bloom: false,
+            ssao: false,
+            environment: LightingEnvironment::None,
+        }
+    }
+
+    pub fn realistic() -> Self {
+        Self {
+            profile: RenderProfile::Realistic,
+            tonemapping: TonemappingMode::TonyMcMapface,
+            exposure_ev100: Exposure::EV100_BLENDER,
+            auto_exposure: true,
+            bloom: true,
+            ssao: true,
+            environment: LightingEnvironment::Studio,
+        }
+    }
+
+    /// Bloom and auto exposure need an HDR target
+    pub fn needs_hdr(&self) -> bool {
+        self.bloom || self.auto_exposure
+    }
+}
+
+/// Request to switch render profile
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetRenderProfile(pub RenderProfile);
+
+/// Request to switch the lighting environment
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetEnvironment(pub LightingEnvironment);
+
+/// Request to turn ambient occlusion on or off
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetAmbientOcclusion(pub bool);
+
+/// The render_profile binding (F8) toggles between the CAD and realistic profiles
+pub fn render_settings_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    settings: Res<RenderSettings>,
+    mut requests: EventWriter<SetRenderProfile>,
+) {
+    if bindings.just_pressed("render_profile", &keys) {
+        let next = if settings.profile == RenderProfile::Cad { RenderProfile::Realistic } else { RenderProfile::Cad };
+        requests.write(SetRenderProfile(next));
+    }
+}
+
+/// Replace the settings with a named profile
+pub fn apply_render_profile(
+    mut requests: EventReader<SetRenderProfile>,
+    mut settings: ResMut<RenderSettings>,
+    mut usage: Option<ResMut<UsageStats>>,
+) {
+    for SetRenderProfile(profile) in requests.read() {
+        let start = Instant::now();
+        journal(format!("render_profile {:?}", profile));
+        match profile {
+            RenderProfile::Cad => *settings = RenderSettings::cad(),
+            RenderProfile::Realistic => *settings = RenderSettings::realistic(),
+            RenderProfile::Custom => settings.profile = RenderProfile::Custom,
+        }
+        if let Some(usage) = usage.as_mut() {
+            usage.record("render_profile", start.elapsed());
+        }
+    }
+}
+
+/// Change the environment or ambient occlusion, leaving the named profile
+pub fn apply_environment_requests(
+    mut environments: EventReader<SetEnvironment>,
+    mut occlusion: EventReader<SetAmbientOcclusion>,
+    mut settings: ResMut<RenderSettings>,
+    mut usage: Option<ResMut<UsageStats>>,
+) {
+    for SetEnvironment(environment) in environments.read() {
+        let start = Instant::now();
+        journal(format!("environment {:?}", environment));
+        settings.environment = *environment;
+        settings.profile = RenderProfile::Custom;
+        if let Some(usage) = usage.as_mut() {
+            usage.record("environment", start.elapsed());
+        }
+    }
+    for SetAmbientOcclusion(on) in occlusion.read() {
+        journal(format!("ssao {}", on));
+        settings.ssao = *on;
+        settings.profile = RenderProfile::Custom;
+    }
+}
+
+/// Push changed settings onto the 3D cameras (and cameras spawned since)
+pub fn apply_render_settings(
+    mut commands: Commands,
+    settings: Res<RenderSettings>,
+    mut cameras: Query<(Entity, &mut Camera, Ref<Camera3d>)>,
+    asset_server: Option<Res<AssetServer>>,
+) {
+    for (entity, mut camera, added) in cameras.iter_mut() {
+        if !settings.is_changed() && !added.is_added() {
+            continue;
+        }
+        camera.hdr = settings.needs_hdr();
+        let mut entity = commands.entity(entity);
+        entity.insert((settings.tonemapping.to_bevy(), Exposure { ev100: settings.exposure_ev100 }));
+        if settings.bloom {
+            entity.insert(Bloom::NATURAL);
+        } else {
+            entity.remove::<Bloom>();
+        }
+        if settings.auto_exposure {
+            entity.insert(AutoExposure::default());
+        } else {
+            entity.remove::<AutoExposure>();
+        }
+        if settings.ssao {
+            entity.insert((ScreenSpaceAmbientOcclusion::default(), Msaa::Off));
+        } else {
+            entity.remove::<ScreenSpaceAmbientOcclusion>().insert(Msaa::default());
+        }
+        match (settings.environment.cubemaps(), asset_server.as_ref()) {
+            (Some((diffuse, specular)), Some(assets)) => {
+                entity.insert(EnvironmentMapLight {
+                    diffuse_map: assets.load(diffuse),
+                    specular_map: assets.load(specular),
+                    intensity: settings.environment.intensity(),
+                    ..default()
+                });
+            }
+            _ => {
+                entity.remove::<EnvironmentMapLight>();
+            }
+        }
+    }
+}
+
+/// Drop back to no environment when the cubemaps of the chosen one fail to
+/// load, as when its HDRI is not installed under `assets/environments`
+pub fn environment_fallback(
+    mut settings: ResMut<RenderSettings>,
+    cameras: Query<&EnvironmentMapLight, With<Camera3d>>,
+    asset_server: Option<Res<AssetServer>>,
+) {
+    let Some(assets) = asset_server else { return };
+    if settings.environment == LightingEnvironment::None {
+        return;
+    }
+    let failed = |map: &Handle<Image>| matches!(assets.load_state(map.id()), LoadState::Failed(_));
+    if cameras.iter().any(|light| failed(&light.diffuse_map) || failed(&light.specular_map)) {
+        warn!("{} environment maps are missing; lighting without an environment", settings.environment.label());
+        journal(format!("environment {:?} missing", settings.environment));
+        settings.environment = LightingEnvironment::None;
+    }
+}
+
+/// Lighting panel button choosing an environment
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnvironmentButton(pub LightingEnvironment);
+
+/// Lighting panel button toggling ambient occlusion
+#[derive(Component, Debug)]
+pub struct AmbientOcclusionButton;
+
+/// Label of the ambient occlusion button
+#[derive(Component, Debug)]
+pub struct AmbientOcclusionLabel;
+
+/// Environment and ambient occlusion buttons switch the settings and show the current ones
+pub fn environment_panel_system(
+    settings: Res<RenderSettings>,
+    environments: Query<(&Interaction, &EnvironmentButton), Changed<Interaction>>,
+    occlusion: Query<&Interaction, (Changed<Interaction>, With<AmbientOcclusionButton>)>,
+    mut buttons: Query<(&EnvironmentButton, &mut BackgroundColor)>,
+    mut labels: Query<&mut Text, With<AmbientOcclusionLabel>>,
+    (mut set_environment, mut set_occlusion): (EventWriter<SetEnvironment>, EventWriter<SetAmbientOcclusion>),
+) {
+    for (interaction, button) in environments.iter() {
+        if *interaction == Interaction::Pressed {
+            set_environment.write(SetEnvironment(button.0));
+        }
+    }
+    if occlusion.iter().any(|i| *i == Interaction::Pressed) {
+        set_occlusion.write(SetAmbientOcclusion(!settings.ssao));
+    }
+    if !settings.is_changed() {
+        return;
+    }
+    for (button, mut color) in buttons.iter_mut() {
+        color.0 = if button.0 == settings.environment { BUTTON_ACTIVE } else { BUTTON_IDLE };
+    }
+    for mut text in labels.iter_mut() {
+        text.0 = format!("Ambient occlusion: {}", if settings.ssao { "on" } else { "off" });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profiles_configure_cameras() {
+        let mut app = App::new();
+        app.init_resource::<RenderSettings>()
+            .add_event::<SetRenderProfile>()
+            .add_systems(Update, (apply_render_profile, apply_render_settings).chain());
+        let camera = app.world_mut().spawn(Camera3d::default()).id();
+        app.update();
+        let world = app.world();
+        assert_eq!(*world.get::<Tonemapping>(camera).unwrap(), Tonemapping::None);
+        assert!(world.get::<Bloom>(camera).is_none() && !world.get::<Camera>(camera).unwrap().hdr);
+
+        app.world_mut().send_event(SetRenderProfile(RenderProfile::Realistic));
+        app.update();
+        let world = app.world();
+        assert_eq!(*world.get::<Tonemapping>(camera).unwrap(), Tonemapping::TonyMcMapface);
+        assert!(world.get::<Bloom>(camera).is_some() && world.get::<AutoExposure>(camera).is_some());
+        assert!(world.get::<Camera>(camera).unwrap().hdr);
+        assert!(world.get::<ScreenSpaceAmbientOcclusion>(camera).is_some());
+        assert_eq!(*world.get::<Msaa>(camera).unwrap(), Msaa::Off);
+    }
+
+    #[test]
+    fn test_environment_and_occlusion_requests() {
+        let mut app = App::new();
+        app.init_resource::<RenderSettings>()
+            .add_event::<SetEnvironment>()
+            .add_event::<SetAmbientOcclusion>()
+            .add_systems(Update, (apply_environment_requests, apply_render_settings).chain());
+        let camera = app.world_mut().spawn(Camera3d::default()).id();
+        app.world_mut().send_event(SetEnvironment(LightingEnvironment::Workshop));
+        app.world_mut().send_event(SetAmbientOcclusion(true));
+        app.update();
+        let settings = app.world().resource::<RenderSettings>();
+        assert_eq!(settings.environment, LightingEnvironment::Workshop);
+        assert_eq!(settings.profile, RenderProfile::Custom);
+        assert!(app.world().get::<ScreenSpaceAmbientOcclusion>(camera).is_some());
+
+        app.world_mut().send_event(SetAmbientOcclusion(false));
+        app.update();
+        assert!(app.world().get::<ScreenSpaceAmbientOcclusion>(camera).is_none());
+        assert_ne!(*app.world().get::<Msaa>(camera).unwrap(), Msaa::Off);
+        assert_eq!(LightingEnvironment::Studio.cubemaps().unwrap().0, "environments/studio_diffuse.ktx2");
+        assert!(LightingEnvironment::None.cubemaps().is_none());
+    }
+}
@@ -0,0 +1,18 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: render::ui_style
+//!
+//! Colors shared by the panels, toolbars and their buttons, so every panel
+//! looks alike.
+
+use bevy::prelude::Color;
+
+/// Background of panels and toolbars
+pub const PANEL_COLOR: Color = Color::srgb(0.1, 0.1, 0.15);
+/// Background of panels opened over the middle of the view
+pub const PANEL_COLOR_TRANSLUCENT: Color = Color::srgba(0.1, 0.1, 0.15, 0.9);
+/// Button at rest
+pub const BUTTON_IDLE: Color = Color::srgb(0.2, 0.2, 0.25);
+/// Button of the active mode, tool or item
+pub const BUTTON_ACTIVE: Color = Color::srgb(0.35, 0.35, 0.6);
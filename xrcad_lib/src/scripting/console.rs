@@ -0,0 +1,277 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: scripting::console
+//!
+//! Running scripts in the application. Ctrl+` (the script_console binding)
+//! opens the script console: typed characters edit a line of Rhai, Enter runs
+//! it and Escape closes the console. Scripts also run from `.rhai` files, including the ones listed in
+//! `StartupScripts` when the app starts. A script's modeling commands are sent
+//! to the command bus only if the whole script succeeds.
+
+use std::fs;
+use std::path::PathBuf;
+
+#[cfg(feature = "render")]
+use bevy::input::keyboard::{Key, KeyboardInput};
+#[cfg(feature = "render")]
+use bevy::input::ButtonState;
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+
+#[cfg(feature = "render")]
+use crate::input::keyboard::KeyBindings;
+#[cfg(feature = "render")]
+use crate::interaction::state::UiPanel;
+use crate::model::brep_model::BrepModel;
+use crate::model::command::ModelCommand;
+use crate::model::feature_tree::FeatureTree;
+use crate::model::properties::BodyPropertiesCollection;
+use crate::scripting::engine::{run_script, ScriptDocument};
+use crate::telemetry::crash::journal;
+use crate::telemetry::usage::UsageStats;
+
+/// Transcript lines kept by the console
+pub const CONSOLE_LINES: usize = 200;
+/// Transcript lines shown in the panel
+#[cfg(feature = "render")]
+const VISIBLE_LINES: usize = 12;
+
+/// Request to run Rhai source
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct RunScript(pub String);
+
+/// Request to run a `.rhai` file
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct RunScriptFile(pub PathBuf);
+
+/// Script files run once when the app starts, in order
+#[derive(Resource, Debug, Default, Clone)]
+pub struct StartupScripts(pub Vec<PathBuf>);
+
+/// The console's line being typed and its transcript
+#[derive(Resource, Debug, Default, Clone)]
+pub struct ScriptConsole {
+    pub open: bool,
+    pub buffer: String,
+    /// Entered lines, printed output and errors, oldest first
+    pub transcript: Vec<String>,
+}
+
+impl ScriptConsole {
+    /// Append a transcript line, dropping the oldest past the limit
+    pub fn log(&mut self, line: impl Into<String>) {
+        self.transcript.push(line.into());
+        if self.transcript.len() > CONSOLE_LINES {
+            self.transcript.remove(0);
+        }
+    }
+
+    /// Finish the typed line and return it as a run request
+    pub fn commit(&mut self) -> Option<RunScript> {
+        let line = std::mem::take(&mut self.buffer);
+        if line.trim().is_empty() {
+            return None;
+        }
+        self.log(format!("> {}", line));
+        Some(RunScript(line))
+    }
+}
+
+/// Run condition: true unless the script console is capturing the keyboard
+pub fn not_typing_script(console: Option<Res<ScriptConsole>>) -> bool {
+    !console.is_some_and(|c| c.open)
+}
+
+/// Queue the startup scripts
+pub fn run_startup_scripts(scripts: Option<Res<StartupScripts>>, mut requests: EventWriter<RunScriptFile>) {
+    for path in scripts.iter().flat_map(|s| s.0.iter()) {
+        requests.write(RunScriptFile(path.clone()));
+    }
+}
+
+/// Run requested scripts and send the commands of those that succeed. Scripts
+/// of the same frame run in order, each seeing the ones before it.
+pub fn apply_script_requests(
+    mut scripts: EventReader<RunScript>,
+    mut files: EventReader<RunScriptFile>,
+    (model, features, properties): (Res<BrepModel>, Res<FeatureTree>, Option<Res<BodyPropertiesCollection>>),
+    mut commands: EventWriter<ModelCommand>,
+    mut console: Option<ResMut<ScriptConsole>>,
+    mut usage: Option<ResMut<UsageStats>>,
+) {
+    let mut sources: Vec<(String, String)> = scripts.read().map(|RunScript(source)| ("console".to_string(), source.clone())).collect();
+    for RunScriptFile(path) in files.read() {
+        match fs::read_to_string(path) {
+            Ok(source) => sources.push((path.display().to_string(), source)),
+            Err(err) => {
+                warn!("Could not read script {}: {}", path.display(), err);
+                if let Some(console) = console.as_mut() {
+                    console.log(format!("{}: {}", path.display(), err));
+                }
+            }
+        }
+    }
+    if sources.is_empty() {
+        return;
+    }
+    let mut doc = ScriptDocument {
+        model: model.clone(),
+        features: features.clone(),
+        properties: properties.as_deref().cloned().unwrap_or_default(),
+    };
+    for (name, source) in sources {
+        let start = Instant::now();
+        journal(format!("run_script {}", name));
+        let mut attempt = doc.clone();
+        let mut lines = Vec::new();
+        match run_script(&source, &mut attempt) {
+            Ok(outcome) => {
+                doc = attempt;
+                for command in outcome.commands {
+                    commands.write(command);
+                }
+                lines.extend(outcome.output);
+                lines.extend(outcome.value);
+            }
+            Err(err) => {
+                warn!("Script {} failed: {}", name, err);
+                lines.push(format!("error: {}", err));
+            }
+        }
+        if let Some(console) = console.as_mut() {
+            for line in lines {
+                console.log(line);
+            }
+        }
+        if let Some(usage) = usage.as_mut() {
+            usage.record("run_script", start.elapsed());
+        }
+    }
+}
+
+/// Text of the console panel
+#[cfg(feature = "render")]
+#[derive(Component, Debug)]
+pub struct ScriptConsoleText;
+
+/// Spawn the script console (bottom left, shown while open)
+#[cfg(feature = "render")]
+pub fn spawn_script_console(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(8.0),
+                bottom: Val::Px(8.0),
+                width: Val::Px(480.0),
+                padding: UiRect::all(Val::Px(6.0)),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.05, 0.05, 0.08, 0.9)),
+            UiPanel("console"),
+        ))
+        .with_child((Text::new(""), TextFont { font_size: 13.0, ..default() }, ScriptConsoleText));
+}
+
+/// Keyboard-driven console: the script_console binding opens it, Enter runs the line, Escape closes it
+#[cfg(feature = "render")]
+pub fn script_console_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut key_events: EventReader<KeyboardInput>,
+    mut console: ResMut<ScriptConsole>,
+    mut requests: EventWriter<RunScript>,
+) {
+    if !console.open {
+        key_events.clear();
+        if bindings.just_pressed("script_console", &keys) {
+            console.open = true;
+        }
+        return;
+    }
+    for ev in key_events.read() {
+        if ev.state != ButtonState::Pressed {
+            continue;
+        }
+        match &ev.logical_key {
+            Key::Enter => {
+                if let Some(request) = console.commit() {
+                    requests.write(request);
+                }
+            }
+            Key::Escape => {
+                console.open = false;
+                return;
+            }
+            Key::Backspace => {
+                console.buffer.pop();
+            }
+            Key::Space => console.buffer.push(' '),
+            Key::Character(text) => console.buffer.extend(text.chars().filter(|c| !c.is_control())),
+            _ => {}
+        }
+    }
+}
+
+/// Show the console while it is open, with the end of its transcript and the typed line
+#[cfg(feature = "render")]
+pub fn script_console_panel(
+    console: Res<ScriptConsole>,
+    mut panels: Query<&mut Node, With<UiPanel>>,
+    mut texts: Query<(&mut Text, &ChildOf), With<ScriptConsoleText>>,
+) {
+    if !console.is_changed() {
+        return;
+    }
+    let Ok((mut text, parent)) = texts.single_mut() else { return };
+    if let Ok(mut node) = panels.get_mut(parent.parent()) {
+        node.display = if console.open { Display::Flex } else { Display::None };
+    }
+    let skip = console.transcript.len().saturating_sub(VISIBLE_LINES);
+    let mut content: String = console.transcript[skip..].iter().map(|line| format!("{}\n", line)).collect();
+    content.push_str(&format!("> {}_", console.buffer));
+    text.0 = content;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::body::BodyId;
+    use crate::model::command::execute_model_commands;
+
+    #[test]
+    fn test_scripts_drive_the_model_through_the_command_bus() {
+        let mut app = App::new();
+        app.init_resource::<BrepModel>()
+            .init_resource::<FeatureTree>()
+            .init_resource::<BodyPropertiesCollection>()
+            .init_resource::<ScriptConsole>()
+            .add_event::<RunScript>()
+            .add_event::<RunScriptFile>()
+            .add_event::<ModelCommand>()
+            .add_systems(Update, (apply_script_requests, execute_model_commands).chain());
+        app.world_mut().send_event(RunScript("let a = box(10, 10, 10); rename(a, \"Base\");".into()));
+        app.world_mut().send_event(RunScript("box(5, 5, 5, 20, 0, 0); body_count()".into()));
+        app.world_mut().send_event(RunScript("box(1, 1, 1); union(0, 7)".into()));
+        app.update();
+
+        assert_eq!(app.world().resource::<BrepModel>().shells().len(), 2);
+        let props = app.world().resource::<BodyPropertiesCollection>();
+        assert_eq!(props.get(BodyId(0)).unwrap().name, "Base");
+        let console = app.world().resource::<ScriptConsole>();
+        assert_eq!(console.transcript[0], "2");
+        assert!(console.transcript[1].starts_with("error: "), "{:?}", console.transcript);
+    }
+
+    #[test]
+    fn test_console_commits_typed_lines() {
+        let mut console = ScriptConsole { open: true, buffer: "  ".into(), ..Default::default() };
+        assert_eq!(console.commit(), None);
+        console.buffer = "box(1, 2, 3)".into();
+        assert_eq!(console.commit(), Some(RunScript("box(1, 2, 3)".into())));
+        assert_eq!(console.transcript, ["> box(1, 2, 3)"]);
+        assert!(console.buffer.is_empty());
+    }
+}
@@ -0,0 +1,291 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: scripting::engine
+//!
+//! Rhai scripting for model generation. Scripts call primitives, transforms,
+//! booleans and document queries:
+//!
+//! ```text
+//! let plate = box(80, 40, 10);
+//! for x in [-40, 40] {
+//!     let notch = box(10, 10, 20, x, 0, -10);
+//!     plate = subtract(plate, notch);
+//! }
+//! translate(plate, 0, 0, 5);
+//! rename(plate, "Plate");
+//! print(`volume ${volume(plate)}`);
+//! ```
+//!
+//! A script runs against a copy of the document and every modeling call is
+//! executed as a `ModelCommand`, so later calls see its result. The commands
+//! are returned only if the whole script succeeds; sending them to the command
+//! bus then repeats them on the live document. A body keeps the id it was
+//! created with; a boolean's result keeps the id of its target.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+use nalgebra::{Point3, Vector3};
+use rhai::{Array, Dynamic, Engine, EvalAltResult, INT};
+
+use crate::measure::mass_properties::mass_properties;
+use crate::model::body::BodyId;
+use crate::model::brep::operations::boolean::BooleanOp;
+use crate::model::brep::placement::PlacementFrame;
+use crate::model::brep_model::BrepModel;
+use crate::model::command::{execute, ModelCommand};
+use crate::model::feature_tree::{FeatureKind, FeatureTree};
+use crate::model::material::Material;
+use crate::model::properties::BodyPropertiesCollection;
+
+/// Operations a script may run before it is stopped, so a runaway loop
+/// cannot hang the application
+pub const MAX_OPERATIONS: u64 = 10_000_000;
+/// Side count of scripted cylinders
+pub const CYLINDER_SEGMENTS: usize = 32;
+
+/// The parts of the document a script works on
+#[derive(Debug, Default, Clone)]
+pub struct ScriptDocument {
+    pub model: BrepModel,
+    pub features: FeatureTree,
+    pub properties: BodyPropertiesCollection,
+}
+
+/// What a successful script did
+#[derive(Debug, Default, Clone)]
+pub struct ScriptOutcome {
+    /// Modeling commands, in the order they ran
+    pub commands: Vec<ModelCommand>,
+    /// Lines printed by the script
+    pub output: Vec<String>,
+    /// Value of the last statement, unless it was unit
+    pub value: Option<String>,
+}
+
+/// A script error, with its line and column
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptError(pub String);
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+/// State shared by the registered functions while a script runs
+#[derive(Default)]
+struct Session {
+    doc: ScriptDocument,
+    commands: Vec<ModelCommand>,
+    output: Vec<String>,
+}
+
+type Shared = Rc<RefCell<Session>>;
+type FnResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// A number argument; scripts may pass integers or floats
+fn number(value: &Dynamic) -> FnResult<f64> {
+    value
+        .as_float()
+        .or_else(|_| value.as_int().map(|i| i as f64))
+        .map_err(|found| format!("expected a number, found {}", found).into())
+}
+
+fn numbers<const N: usize>(values: [&Dynamic; N]) -> FnResult<[f64; N]> {
+    let mut out = [0.0; N];
+    for (out, value) in out.iter_mut().zip(values) {
+        *out = number(value)?;
+    }
+    Ok(out)
+}
+
+fn body_id(id: INT) -> FnResult<BodyId> {
+    usize::try_from(id).map(BodyId).map_err(|_| format!("no body with id {}", id).into())
+}
+
+/// Base frame of a primitive at a point, growing along +Z
+fn base_at(x: &Dynamic, y: &Dynamic, z: &Dynamic) -> FnResult<Option<PlacementFrame>> {
+    let [x, y, z] = numbers([x, y, z])?;
+    Ok(Some(PlacementFrame::new(Point3::new(x, y, z), Vector3::z())))
+}
+
+impl Session {
+    /// Execute a command on the script's document and keep it for the bus
+    fn run(&mut self, command: ModelCommand) -> FnResult<()> {
+        let doc = &mut self.doc;
+        execute(&command, &mut doc.model, &mut doc.features, &mut doc.properties).map_err(|err| err.to_string())?;
+        self.commands.push(command);
+        Ok(())
+    }
+
+    /// Id of the most recently added body; ids are handed out in increasing order
+    fn last_body(&self) -> INT {
+        self.doc.model.body_ids().into_iter().max().map_or(-1, |id| id.0 as INT)
+    }
+
+    fn create(&mut self, kind: FeatureKind) -> FnResult<INT> {
+        self.run(ModelCommand::CreatePrimitive(kind))?;
+        Ok(self.last_body())
+    }
+
+    fn boolean(&mut self, op: BooleanOp, target: INT, tool: INT) -> FnResult<INT> {
+        self.run(ModelCommand::Boolean { op, target: body_id(target)?, tool: body_id(tool)? })?;
+        Ok(target)
+    }
+
+    /// Faces of a body, checked against the model
+    fn body(&self, id: INT) -> FnResult<BrepModel> {
+        self.doc.model.body(body_id(id)?).ok_or_else(|| format!("no body with id {}", id).into())
+    }
+}
+
+fn cuboid(size: [f64; 3], placement: Option<PlacementFrame>) -> FeatureKind {
+    FeatureKind::Box { size: Vector3::from(size), placement }
+}
+
+fn cylinder(radius: f64, height: f64, placement: Option<PlacementFrame>) -> FeatureKind {
+    FeatureKind::Cylinder { bottom_radius: radius, top_radius: radius, height, segments: CYLINDER_SEGMENTS, placement }
+}
+
+/// Register the modeling functions
+fn register_modeling(engine: &mut Engine, session: &Shared) {
+    let s = session.clone();
+    engine.register_fn("box", move |x: Dynamic, y: Dynamic, z: Dynamic| -> FnResult<INT> {
+        s.borrow_mut().create(cuboid(numbers([&x, &y, &z])?, None))
+    });
+    let s = session.clone();
+    engine.register_fn(
+        "box",
+        move |x: Dynamic, y: Dynamic, z: Dynamic, px: Dynamic, py: Dynamic, pz: Dynamic| -> FnResult<INT> {
+            s.borrow_mut().create(cuboid(numbers([&x, &y, &z])?, base_at(&px, &py, &pz)?))
+        },
+    );
+    let s = session.clone();
+    engine.register_fn("cylinder", move |r: Dynamic, h: Dynamic| -> FnResult<INT> {
+        let [r, h] = numbers([&r, &h])?;
+        s.borrow_mut().create(cylinder(r, h, None))
+    });
+    let s = session.clone();
+    engine.register_fn(
+        "cylinder",
+        move |r: Dynamic, h: Dynamic, px: Dynamic, py: Dynamic, pz: Dynamic| -> FnResult<INT> {
+            let [r, h] = numbers([&r, &h])?;
+            s.borrow_mut().create(cylinder(r, h, base_at(&px, &py, &pz)?))
+        },
+    );
+    let s = session.clone();
+    engine.register_fn("translate", move |body: INT, x: Dynamic, y: Dynamic, z: Dynamic| -> FnResult<()> {
+        let offset = Vector3::from(numbers([&x, &y, &z])?);
+        s.borrow_mut().run(ModelCommand::TranslateBody { body: body_id(body)?, offset })
+    });
+    for (name, op) in [("union", BooleanOp::Union), ("subtract", BooleanOp::Subtract), ("intersect", BooleanOp::Intersect)] {
+        let s = session.clone();
+        engine.register_fn(name, move |target: INT, tool: INT| -> FnResult<INT> { s.borrow_mut().boolean(op, target, tool) });
+    }
+    let s = session.clone();
+    engine.register_fn("delete", move |body: INT| -> FnResult<()> { s.borrow_mut().run(ModelCommand::DeleteBody(body_id(body)?)) });
+    let s = session.clone();
+    engine.register_fn("rename", move |body: INT, name: &str| -> FnResult<()> {
+        s.borrow_mut().run(ModelCommand::RenameBody { body: body_id(body)?, name: name.to_string() })
+    });
+    let s = session.clone();
+    engine.register_fn("material", move |body: INT, name: &str, r: Dynamic, g: Dynamic, b: Dynamic| -> FnResult<()> {
+        let [r, g, b] = numbers([&r, &g, &b])?;
+        let material = Material::new(name, [r as f32, g as f32, b as f32]);
+        s.borrow_mut().run(ModelCommand::SetMaterial { body: body_id(body)?, material })
+    });
+}
+
+/// Register the document queries
+fn register_queries(engine: &mut Engine, session: &Shared) {
+    let s = session.clone();
+    engine.register_fn("body_count", move || -> INT { s.borrow().doc.model.shells().len() as INT });
+    let s = session.clone();
+    engine.register_fn("feature_count", move || -> INT { s.borrow().doc.features.features.len() as INT });
+    let s = session.clone();
+    engine.register_fn("body_name", move |body: INT| -> FnResult<String> {
+        let session = s.borrow();
+        session.body(body)?;
+        Ok(session.doc.properties.get(body_id(body)?).map_or_else(|| format!("Body {}", body), |p| p.name.clone()))
+    });
+    let s = session.clone();
+    engine.register_fn("find_body", move |name: &str| -> Dynamic {
+        let session = s.borrow();
+        let found = session.doc.properties.iter().find(|(_, p)| p.name == name).map(|(id, _)| id.0 as INT);
+        found.map_or(Dynamic::UNIT, Dynamic::from)
+    });
+    let s = session.clone();
+    engine.register_fn("volume", move |body: INT| -> FnResult<f64> { Ok(mass_properties(&s.borrow().body(body)?).volume) });
+    let s = session.clone();
+    engine.register_fn("area", move |body: INT| -> FnResult<f64> { Ok(mass_properties(&s.borrow().body(body)?).surface_area) });
+    let s = session.clone();
+    engine.register_fn("center", move |body: INT| -> FnResult<Array> {
+        let c = mass_properties(&s.borrow().body(body)?).center_of_mass;
+        Ok(vec![c.x.into(), c.y.into(), c.z.into()])
+    });
+    let s = session.clone();
+    engine.register_fn("bounds", move |body: INT| -> FnResult<Array> {
+        let (min, max) = s.borrow().body(body)?.bounds().ok_or("body has no vertices")?;
+        Ok(min.iter().chain(max.iter()).map(|v| Dynamic::from(*v)).collect())
+    });
+}
+
+/// Run a script on `doc`. On success `doc` holds the result; on failure it is
+/// left part way, so callers pass a copy when the original matters.
+pub fn run_script(source: &str, doc: &mut ScriptDocument) -> Result<ScriptOutcome, ScriptError> {
+    let session: Shared = Rc::new(RefCell::new(Session { doc: std::mem::take(doc), ..Default::default() }));
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    let s = session.clone();
+    engine.on_print(move |line| s.borrow_mut().output.push(line.to_string()));
+    let s = session.clone();
+    engine.on_debug(move |line, _, _| s.borrow_mut().output.push(line.to_string()));
+    register_modeling(&mut engine, &session);
+    register_queries(&mut engine, &session);
+
+    let result = engine.eval::<Dynamic>(source);
+    drop(engine);
+    let Session { doc: done, commands, output } = std::mem::take(&mut *session.borrow_mut());
+    *doc = done;
+    let value = result.map_err(|err| ScriptError(err.to_string()))?;
+    Ok(ScriptOutcome { commands, output, value: (!value.is_unit()).then(|| value.to_string()) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script_builds_a_notched_plate() {
+        let mut doc = ScriptDocument::default();
+        let script = r#"
+            let plate = box(80, 40, 10);
+            for x in [-40, 40] {
+                plate = subtract(plate, box(10, 10, 20, x, 0, -10));
+            }
+            rename(plate, "Plate");
+            print(body_count());
+            find_body("Plate")
+        "#;
+        let outcome = run_script(script, &mut doc).unwrap();
+        assert_eq!(outcome.commands.len(), 6);
+        assert_eq!(outcome.output, ["1"]);
+        assert_eq!(outcome.value.as_deref(), Some("0"));
+        assert!((mass_properties(&doc.model).volume - 31000.0).abs() < 1e-6);
+        assert_eq!(doc.properties.get(BodyId(0)).unwrap().name, "Plate");
+    }
+
+    #[test]
+    fn test_errors_report_the_failing_call() {
+        let mut doc = ScriptDocument::default();
+        let err = run_script("box(10, 10, 10);\ntranslate(4, 1, 0, 0);", &mut doc).unwrap_err();
+        assert!(err.0.contains("no body with id 4"), "{}", err);
+        assert!(err.0.contains("line 2"), "{}", err);
+        assert!(run_script("loop {}", &mut ScriptDocument::default()).is_err());
+    }
+}
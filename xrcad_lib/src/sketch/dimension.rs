@@ -0,0 +1,182 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: sketch::dimension
+//!
+//! Driving dimensions: each is a constraint for the sketch solver and an
+//! annotation drawn on the sketch plane.
+
+use nalgebra::Vector2;
+use serde::{Deserialize, Serialize};
+
+use crate::model::tolerance::Tolerance;
+use crate::model::units::UnitSystem;
+use crate::sketch::sketch::{arc_polyline, Sketch, SketchEntity};
+
+/// Length of the arrowheads on dimension lines (sketch units)
+pub const ARROW_SIZE: f64 = 2.0;
+
+/// What a dimension measures
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DimensionKind {
+    /// Distance between two points
+    Linear { a: usize, b: usize },
+    /// Angle between two lines (radians, in [0, π])
+    Angular { a: usize, b: usize },
+    /// Radius of a circle or arc
+    Radial { entity: usize },
+}
+
+impl DimensionKind {
+    /// Current value of the measured quantity
+    pub fn measure(&self, sketch: &Sketch) -> Option<f64> {
+        match self {
+            DimensionKind::Linear { a, b } => Some((sketch.point_position(*b)? - sketch.point_position(*a)?).norm()),
+            DimensionKind::Angular { a, b } => {
+                let (da, db) = (line_direction(sketch, *a)?, line_direction(sketch, *b)?);
+                Some(da.perp(&db).abs().atan2(da.dot(&db)))
+            }
+            DimensionKind::Radial { entity } => match sketch.entity(*entity)? {
+                SketchEntity::Circle { radius, .. } => Some(*radius),
+                SketchEntity::Arc { center, start, .. } => {
+                    Some((sketch.point_position(*start)? - sketch.point_position(*center)?).norm())
+                }
+                SketchEntity::Line { .. } => None,
+            },
+        }
+    }
+}
+
+/// Direction vector (end - start) of a line entity
+fn line_direction(sketch: &Sketch, line: usize) -> Option<Vector2<f64>> {
+    match sketch.entity(line)? {
+        SketchEntity::Line { start, end, .. } => Some(sketch.point_position(*end)? - sketch.point_position(*start)?),
+        _ => None,
+    }
+}
+
+/// A driving dimension: the solver moves geometry until `kind` measures `value`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Dimension {
+    pub id: usize,
+    pub kind: DimensionKind,
+    pub value: f64,
+    /// Distance of the annotation from the measured geometry
+    pub offset: f64,
+}
+
+impl Dimension {
+    pub fn new(id: usize, kind: DimensionKind, value: f64) -> Self {
+        Self { id, kind, value, offset: 10.0 }
+    }
+
+    /// Constraint error: measured minus driving value
+    pub fn residual(&self, sketch: &Sketch) -> Option<f64> {
+        Some(self.kind.measure(sketch)? - self.value)
+    }
+
+    /// Annotation text: lengths as-is, angles in degrees, radii prefixed with R
+    pub fn label(&self) -> String {
+        match self.kind {
+            DimensionKind::Linear { .. } => format!("{:.2}", self.value),
+            DimensionKind::Angular { .. } => format!("{:.2}°", self.value.to_degrees()),
+            DimensionKind::Radial { .. } => format!("R{:.2}", self.value),
+        }
+    }
+
+    /// Annotation text in the document's units
+    pub fn label_in(&self, units: &UnitSystem) -> String {
+        match self.kind {
+            DimensionKind::Linear { .. } => units.format_length(self.value),
+            DimensionKind::Angular { .. } => units.format_angle(self.value),
+            DimensionKind::Radial { .. } => format!("R{}", units.format_length(self.value)),
+        }
+    }
+
+    /// Polylines (plane coordinates) making up the annotation graphics
+    pub fn annotation(&self, sketch: &Sketch) -> Vec<Vec<Vector2<f64>>> {
+        self.annotation_lines(sketch).unwrap_or_default()
+    }
+
+    fn annotation_lines(&self, sketch: &Sketch) -> Option<Vec<Vec<Vector2<f64>>>> {
+        match &self.kind {
+            DimensionKind::Linear { a, b } => {
+                let (pa, pb) = (sketch.point_position(*a)?, sketch.point_position(*b)?);
+                let dir = (pb - pa).try_normalize(sketch.tolerance.linear)?;
+                let off = Vector2::new(-dir.y, dir.x) * self.offset;
+                let (da, db) = (pa + off, pb + off);
+                Some(vec![vec![pa, da], vec![pb, db], vec![da, db], arrow(&da, &dir), arrow(&db, &-dir)])
+            }
+            DimensionKind::Angular { a, b } => {
+                let (sa, da) = line_of(sketch, *a)?;
+                let (sb, db) = line_of(sketch, *b)?;
+                let vertex = intersect(&sa, &da, &sb, &db, &sketch.tolerance)?;
+                let a0 = da.y.atan2(da.x);
+                let sweep = da.perp(&db).atan2(da.dot(&db));
+                Some(vec![arc_polyline(&vertex, self.offset, a0, sweep, 24)])
+            }
+            DimensionKind::Radial { entity } => {
+                let (center, radius) = match sketch.entity(*entity)? {
+                    SketchEntity::Circle { center, radius, .. } => (sketch.point_position(*center)?, *radius),
+                    SketchEntity::Arc { center, start, .. } => {
+                        let c = sketch.point_position(*center)?;
+                        (c, (sketch.point_position(*start)? - c).norm())
+                    }
+                    SketchEntity::Line { .. } => return None,
+                };
+                let dir = Vector2::new(1.0, 1.0).normalize();
+                let tip = center + dir * radius;
+                Some(vec![vec![center, tip + dir * self.offset], arrow(&tip, &-dir)])
+            }
+        }
+    }
+}
+
+/// Start point and unit direction of a line entity
+fn line_of(sketch: &Sketch, line: usize) -> Option<(Vector2<f64>, Vector2<f64>)> {
+    let SketchEntity::Line { start, .. } = sketch.entity(line)? else { return None; };
+    Some((sketch.point_position(*start)?, line_direction(sketch, line)?.try_normalize(sketch.tolerance.linear)?))
+}
+
+/// Intersection of two infinite lines given unit directions, if not parallel
+/// within the sketch's tolerance
+fn intersect(pa: &Vector2<f64>, da: &Vector2<f64>, pb: &Vector2<f64>, db: &Vector2<f64>, tolerance: &Tolerance) -> Option<Vector2<f64>> {
+    let denom = da.perp(db);
+    if denom.abs() <= tolerance.linear {
+        return None;
+    }
+    Some(pa + da * ((pb - pa).perp(db) / denom))
+}
+
+/// Open arrowhead at `tip` pointing along `-dir`
+fn arrow(tip: &Vector2<f64>, dir: &Vector2<f64>) -> Vec<Vector2<f64>> {
+    let side = Vector2::new(-dir.y, dir.x) * (ARROW_SIZE * 0.4);
+    let back = tip + dir * ARROW_SIZE;
+    vec![back + side, *tip, back - side]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::topology::plane::Plane;
+
+    #[test]
+    fn test_measure_angle_and_radius() {
+        let mut s = Sketch::new("S", Plane::xy());
+        let o = s.add_point(Vector2::new(0.0, 0.0));
+        let x = s.add_point(Vector2::new(1.0, 0.0));
+        let y = s.add_point(Vector2::new(1.0, 1.0));
+        let l1 = s.add_line(o, x);
+        let l2 = s.add_line(o, y);
+        let c = s.add_circle(o, 2.5);
+        let angle = s.add_dimension(DimensionKind::Angular { a: l1, b: l2 }, None).unwrap();
+        let radius = s.add_dimension(DimensionKind::Radial { entity: c }, None).unwrap();
+        assert_eq!(s.dimension(angle).unwrap().label(), "45.00°");
+        assert_eq!(s.dimension(radius).unwrap().label(), "R2.50");
+        let inches = UnitSystem::new(crate::model::units::LengthUnit::Inch);
+        assert_eq!(s.dimension(radius).unwrap().label_in(&inches), "R0.10 in");
+        assert!(s.dimension(angle).unwrap().annotation(&s).len() == 1);
+        // Lines have no radius
+        assert!(s.add_dimension(DimensionKind::Radial { entity: l1 }, None).is_none());
+    }
+}
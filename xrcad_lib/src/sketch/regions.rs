@@ -0,0 +1,292 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: sketch::regions
+//!
+//! Closed profile finding: the sketch curves are split at their intersections
+//! into a planar graph, dangling edges are pruned, and the faces of the graph
+//! are traced. Bounded faces become regions; the outer boundary of a separate
+//! component inside a region becomes one of its holes. Arcs and circles are
+//! approximated by polylines.
+
+use std::collections::HashSet;
+
+use nalgebra::Vector2;
+
+use crate::model::brep::geometry::polygon::{contains_point_2d, signed_area_2d};
+use crate::sketch::sketch::{Sketch, SketchEntity};
+
+/// Polyline segments used for a full circle (arcs use the same count)
+pub const CURVE_SEGMENTS: usize = 48;
+
+/// A closed loop of a region; `entities[i]` is the sketch entity the segment
+/// from `points[i]` to `points[i + 1]` (wrapping) came from
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegionLoop {
+    pub points: Vec<Vector2<f64>>,
+    pub entities: Vec<usize>,
+}
+
+impl RegionLoop {
+    pub fn signed_area(&self) -> f64 {
+        signed_area_2d(&self.points)
+    }
+}
+
+/// A pickable closed region: counter-clockwise outer loop and clockwise holes
+#[derive(Debug, Clone, PartialEq)]
+pub struct SketchRegion {
+    pub outer: RegionLoop,
+    pub holes: Vec<RegionLoop>,
+}
+
+impl SketchRegion {
+    /// Area enclosed by the outer loop minus the holes
+    pub fn area(&self) -> f64 {
+        self.outer.signed_area() + self.holes.iter().map(|h| h.signed_area()).sum::<f64>()
+    }
+
+    /// True if the point is inside the outer loop and outside every hole
+    pub fn contains_point(&self, p: &Vector2<f64>) -> bool {
+        contains_point_2d(&self.outer.points, p) && !self.holes.iter().any(|h| contains_point_2d(&h.points, p))
+    }
+}
+
+/// Planar graph of the sketch curves
+struct Arrangement {
+    nodes: Vec<Vector2<f64>>,
+    /// Undirected edges (a, b, entity)
+    edges: Vec<(usize, usize, usize)>,
+}
+
+impl Arrangement {
+    fn node_at(&mut self, p: Vector2<f64>, tol: f64) -> usize {
+        if let Some(i) = self.nodes.iter().position(|n| (n - p).norm() <= tol) {
+            return i;
+        }
+        self.nodes.push(p);
+        self.nodes.len() - 1
+    }
+}
+
+/// Curve segments of the sketch tagged with their entity id
+fn segments(sketch: &Sketch) -> Vec<(Vector2<f64>, Vector2<f64>, usize)> {
+    let mut out = Vec::new();
+    for entity in &sketch.entities {
+        let segments = match entity {
+            SketchEntity::Line { .. } => 1,
+            _ => CURVE_SEGMENTS,
+        };
+        let points = sketch.entity_polyline(entity, segments);
+        for w in points.windows(2) {
+            out.push((w[0], w[1], entity.id()));
+        }
+    }
+    out
+}
+
+/// Parameters along segment a-b where it meets segment c-d (including overlaps and touching ends)
+fn split_params(a: &Vector2<f64>, b: &Vector2<f64>, c: &Vector2<f64>, d: &Vector2<f64>, tol: f64) -> Vec<f64> {
+    let r = b - a;
+    let s = d - c;
+    let len2 = r.norm_squared();
+    if len2 <= tol * tol {
+        return Vec::new();
+    }
+    let denom = r.perp(&s);
+    let mut out = Vec::new();
+    if denom.abs() > tol * r.norm() * s.norm() {
+        let t = (c - a).perp(&s) / denom;
+        let u = (c - a).perp(&r) / denom;
+        let (et, eu) = (tol / r.norm(), tol / s.norm().max(tol));
+        if t >= -et && t <= 1.0 + et && u >= -eu && u <= 1.0 + eu {
+            out.push(t.clamp(0.0, 1.0));
+        }
+    } else {
+        // Parallel: endpoints of c-d lying on a-b split it
+        for p in [c, d] {
+            let t = (p - a).dot(&r) / len2;
+            if (0.0..=1.0).contains(&t) && (a + r * t - p).norm() <= tol {
+                out.push(t);
+            }
+        }
+    }
+    out
+}
+
+fn build_arrangement(sketch: &Sketch) -> Arrangement {
+    let tol = sketch.tolerance.linear;
+    let segs = segments(sketch);
+    let mut graph = Arrangement { nodes: Vec::new(), edges: Vec::new() };
+    for (i, (a, b, entity)) in segs.iter().enumerate() {
+        let mut params = vec![0.0, 1.0];
+        for (j, (c, d, _)) in segs.iter().enumerate() {
+            if i != j {
+                params.extend(split_params(a, b, c, d, tol));
+            }
+        }
+        params.sort_by(f64::total_cmp);
+        let ids: Vec<usize> = params.iter().map(|t| graph.node_at(a + (b - a) * *t, tol)).collect();
+        for w in ids.windows(2) {
+            let (u, v) = (w[0].min(w[1]), w[0].max(w[1]));
+            if u != v && !graph.edges.iter().any(|(x, y, _)| *x == u && *y == v) {
+                graph.edges.push((u, v, *entity));
+            }
+        }
+    }
+    // Dangling edges cannot bound a region
+    loop {
+        let mut degree = vec![0usize; graph.nodes.len()];
+        for (u, v, _) in &graph.edges {
+            degree[*u] += 1;
+            degree[*v] += 1;
+        }
+        let before = graph.edges.len();
+        graph.edges.retain(|(u, v, _)| degree[*u] > 1 && degree[*v] > 1);
+        if graph.edges.len() == before {
+            break;
+        }
+    }
+    graph
+}
+
+fn find_root(parent: &mut [usize], i: usize) -> usize {
+    let mut i = i;
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+/// Detect all closed regions of the sketch, with holes nested into their smallest enclosing region
+pub fn find_regions(sketch: &Sketch) -> Vec<SketchRegion> {
+    let graph = build_arrangement(sketch);
+    let n = graph.nodes.len();
+
+    // Neighbours of each node sorted counter-clockwise, with the entity of the connecting edge
+    let mut adjacency: Vec<Vec<(usize, usize)>> = vec![Vec::new(); n];
+    let mut parent: Vec<usize> = (0..n).collect();
+    for (u, v, entity) in &graph.edges {
+        adjacency[*u].push((*v, *entity));
+        adjacency[*v].push((*u, *entity));
+        let (ru, rv) = (find_root(&mut parent, *u), find_root(&mut parent, *v));
+        parent[ru] = rv;
+    }
+    for (i, list) in adjacency.iter_mut().enumerate() {
+        let origin = graph.nodes[i];
+        list.sort_by(|a, b| {
+            let (da, db) = (graph.nodes[a.0] - origin, graph.nodes[b.0] - origin);
+            da.y.atan2(da.x).total_cmp(&db.y.atan2(db.x))
+        });
+    }
+
+    // Trace every half-edge once, keeping the face on its left
+    let mut visited = HashSet::new();
+    let mut faces: Vec<(RegionLoop, usize)> = Vec::new();
+    let mut boundaries: Vec<(RegionLoop, usize)> = Vec::new();
+    for start_node in 0..n {
+        for &(start_next, _) in &adjacency[start_node] {
+            if visited.contains(&(start_node, start_next)) {
+                continue;
+            }
+            let mut cycle = RegionLoop { points: Vec::new(), entities: Vec::new() };
+            let (mut u, mut v) = (start_node, start_next);
+            while visited.insert((u, v)) {
+                let entity = adjacency[u].iter().find(|(w, _)| *w == v).map_or(0, |(_, e)| *e);
+                cycle.points.push(graph.nodes[u]);
+                cycle.entities.push(entity);
+                let around = &adjacency[v];
+                let Some(i) = around.iter().position(|(w, _)| *w == u) else { break; };
+                let next = around[(i + around.len() - 1) % around.len()].0;
+                (u, v) = (v, next);
+            }
+            let component = find_root(&mut parent, start_node);
+            let area = cycle.signed_area();
+            if area > sketch.tolerance.linear * sketch.tolerance.linear {
+                faces.push((cycle, component));
+            } else if area < 0.0 {
+                boundaries.push((cycle, component));
+            }
+        }
+    }
+
+    let mut regions: Vec<SketchRegion> =
+        faces.iter().map(|(outer, _)| SketchRegion { outer: outer.clone(), holes: Vec::new() }).collect();
+    for (boundary, component) in boundaries {
+        let probe = boundary.points[0];
+        let host = faces
+            .iter()
+            .enumerate()
+            .filter(|(_, (f, c))| *c != component && contains_point_2d(&f.points, &probe))
+            .min_by(|(_, (a, _)), (_, (b, _))| a.signed_area().total_cmp(&b.signed_area()))
+            .map(|(i, _)| i);
+        if let Some(i) = host {
+            regions[i].holes.push(boundary);
+        }
+    }
+    regions
+}
+
+/// The region containing a picked point, if any
+pub fn region_at<'a>(regions: &'a [SketchRegion], p: &Vector2<f64>) -> Option<&'a SketchRegion> {
+    regions.iter().find(|r| r.contains_point(p))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::topology::plane::Plane;
+
+    fn rectangle(s: &mut Sketch, x0: f64, y0: f64, x1: f64, y1: f64) {
+        let p: Vec<usize> = [(x0, y0), (x1, y0), (x1, y1), (x0, y1)]
+            .iter()
+            .map(|(x, y)| s.add_point(Vector2::new(*x, *y)))
+            .collect();
+        for i in 0..4 {
+            s.add_line(p[i], p[(i + 1) % 4]);
+        }
+    }
+
+    #[test]
+    fn test_overlapping_rectangles() {
+        let mut s = Sketch::new("S", Plane::xy());
+        rectangle(&mut s, 0.0, 0.0, 2.0, 2.0);
+        rectangle(&mut s, 1.0, 1.0, 3.0, 3.0);
+        let regions = find_regions(&s);
+        assert_eq!(regions.len(), 3);
+        let mut areas: Vec<f64> = regions.iter().map(|r| r.area()).collect();
+        areas.sort_by(f64::total_cmp);
+        assert!((areas[0] - 1.0).abs() < 1e-9 && (areas[1] - 3.0).abs() < 1e-9 && (areas[2] - 3.0).abs() < 1e-9);
+        assert!(regions.iter().all(|r| r.holes.is_empty()));
+    }
+
+    #[test]
+    fn test_circle_inside_square_is_a_hole() {
+        let mut s = Sketch::new("S", Plane::xy());
+        rectangle(&mut s, -5.0, -5.0, 5.0, 5.0);
+        let c = s.add_point(Vector2::zeros());
+        s.add_circle(c, 2.0);
+        let regions = find_regions(&s);
+        assert_eq!(regions.len(), 2);
+        let square = regions.iter().find(|r| r.area() > 50.0).unwrap();
+        assert_eq!(square.holes.len(), 1);
+        assert!(!square.contains_point(&Vector2::new(0.5, 0.5)));
+        let disc = region_at(&regions, &Vector2::new(0.5, 0.5)).unwrap();
+        assert!(disc.holes.is_empty());
+        assert!(disc.outer.entities.iter().all(|e| *e == 4));
+    }
+
+    #[test]
+    fn test_line_across_square_and_dangling_ends() {
+        let mut s = Sketch::new("S", Plane::xy());
+        rectangle(&mut s, 0.0, 0.0, 4.0, 4.0);
+        let a = s.add_point(Vector2::new(1.0, -1.0));
+        let b = s.add_point(Vector2::new(1.0, 5.0));
+        s.add_line(a, b);
+        let regions = find_regions(&s);
+        assert_eq!(regions.len(), 2);
+        assert!(regions.iter().any(|r| (r.area() - 4.0).abs() < 1e-9));
+        assert!(regions.iter().any(|r| (r.area() - 12.0).abs() < 1e-9));
+    }
+}
@@ -0,0 +1,262 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: sketch::sketch
+//!
+//! 2D sketches on a plane: points, curve entities and driving dimensions.
+//! Coordinates are in the plane's 2D frame (see `Plane::project_2d`).
+
+use bevy::prelude::*;
+use nalgebra::Vector2;
+use serde::{Deserialize, Serialize};
+
+use crate::model::brep::topology::plane::Plane;
+use crate::model::tolerance::Tolerance;
+use crate::sketch::dimension::{Dimension, DimensionKind};
+#[cfg(feature = "render")]
+use crate::{
+    color::{CYAN, WHITE, YELLOW},
+    model::brep_model::na_vec3_to_bevy,
+    render::gizmo_scale::{GizmoScale, SKETCH_POINT_PIXELS},
+};
+
+/// A point of a sketch; fixed points are never moved by the solver
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SketchPoint {
+    pub id: usize,
+    pub position: Vector2<f64>,
+    pub fixed: bool,
+}
+
+/// A curve of a sketch, referencing sketch points by id
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SketchEntity {
+    Line { id: usize, start: usize, end: usize },
+    Circle { id: usize, center: usize, radius: f64 },
+    /// Counter-clockwise arc from `start` to `end`; both lie on the same circle
+    Arc { id: usize, center: usize, start: usize, end: usize },
+}
+
+impl SketchEntity {
+    pub fn id(&self) -> usize {
+        match self {
+            SketchEntity::Line { id, .. } | SketchEntity::Circle { id, .. } | SketchEntity::Arc { id, .. } => *id,
+        }
+    }
+}
+
+/// A sketch on a plane
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sketch {
+    pub name: String,
+    pub plane: Plane,
+    pub points: Vec<SketchPoint>,
+    pub entities: Vec<SketchEntity>,
+    pub dimensions: Vec<Dimension>,
+    pub tolerance: Tolerance,
+    /// Hidden from the viewport (toggled in the outliner)
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+impl Sketch {
+    pub fn new(name: impl Into<String>, plane: Plane) -> Self {
+        Self {
+            name: name.into(),
+            plane,
+            points: Vec::new(),
+            entities: Vec::new(),
+            dimensions: Vec::new(),
+            tolerance: Tolerance::default(),
+            hidden: false,
+        }
+    }
+
+    pub fn point(&self, id: usize) -> Option<&SketchPoint> {
+        self.points.iter().find(|p| p.id == id)
+    }
+
+    pub fn point_mut(&mut self, id: usize) -> Option<&mut SketchPoint> {
+        self.points.iter_mut().find(|p| p.id == id)
+    }
+
+    pub fn point_position(&self, id: usize) -> Option<Vector2<f64>> {
+        self.point(id).map(|p| p.position)
+    }
+
+    pub fn entity(&self, id: usize) -> Option<&SketchEntity> {
+        self.entities.iter().find(|e| e.id() == id)
+    }
+
+    pub fn dimension(&self, id: usize) -> Option<&Dimension> {
+        self.dimensions.iter().find(|d| d.id == id)
+    }
+
+    pub fn dimension_mut(&mut self, id: usize) -> Option<&mut Dimension> {
+        self.dimensions.iter_mut().find(|d| d.id == id)
+    }
+
+    fn next_entity_id(&self) -> usize {
+        self.entities.iter().map(|e| e.id() + 1).max().unwrap_or(0)
+    }
+
+    pub fn add_point(&mut self, position: Vector2<f64>) -> usize {
+        let id = self.points.iter().map(|p| p.id + 1).max().unwrap_or(0);
+        self.points.push(SketchPoint { id, position, fixed: false });
+        id
+    }
+
+    pub fn add_fixed_point(&mut self, position: Vector2<f64>) -> usize {
+        let id = self.add_point(position);
+        if let Some(p) = self.point_mut(id) {
+            p.fixed = true;
+        }
+        id
+    }
+
+    pub fn add_line(&mut self, start: usize, end: usize) -> usize {
+        let id = self.next_entity_id();
+        self.entities.push(SketchEntity::Line { id, start, end });
+        id
+    }
+
+    pub fn add_circle(&mut self, center: usize, radius: f64) -> usize {
+        let id = self.next_entity_id();
+        self.entities.push(SketchEntity::Circle { id, center, radius });
+        id
+    }
+
+    pub fn add_arc(&mut self, center: usize, start: usize, end: usize) -> usize {
+        let id = self.next_entity_id();
+        self.entities.push(SketchEntity::Arc { id, center, start, end });
+        id
+    }
+
+    /// Add a driving dimension; its value is the current measurement unless given
+    pub fn add_dimension(&mut self, kind: DimensionKind, value: Option<f64>) -> Option<usize> {
+        let id = self.dimensions.iter().map(|d| d.id + 1).max().unwrap_or(0);
+        let value = match value {
+            Some(v) => v,
+            None => kind.measure(self)?,
+        };
+        self.dimensions.push(Dimension::new(id, kind, value));
+        Some(id)
+    }
+
+    /// Sampled polyline of an entity in plane coordinates
+    pub fn entity_polyline(&self, entity: &SketchEntity, segments: usize) -> Vec<Vector2<f64>> {
+        match entity {
+            SketchEntity::Line { start, end, .. } => {
+                [*start, *end].iter().filter_map(|id| self.point_position(*id)).collect()
+            }
+            SketchEntity::Circle { center, radius, .. } => {
+                let Some(c) = self.point_position(*center) else { return Vec::new(); };
+                arc_polyline(&c, *radius, 0.0, std::f64::consts::TAU, segments)
+            }
+            SketchEntity::Arc { center, start, end, .. } => {
+                let (Some(c), Some(s), Some(e)) =
+                    (self.point_position(*center), self.point_position(*start), self.point_position(*end))
+                else {
+                    return Vec::new();
+                };
+                let a0 = (s - c).y.atan2((s - c).x);
+                let mut sweep = (e - c).y.atan2((e - c).x) - a0;
+                if sweep <= 0.0 {
+                    sweep += std::f64::consts::TAU;
+                }
+                arc_polyline(&c, (s - c).norm(), a0, sweep, segments)
+            }
+        }
+    }
+
+    /// Draw the sketch and its dimension annotations on the sketch plane
+    #[cfg(feature = "render")]
+    pub fn draw(&self, gizmos: &mut Gizmos, scale: &GizmoScale) {
+        let to_world = |p: &Vector2<f64>| na_vec3_to_bevy(&self.plane.point_at_2d(p).coords);
+        let mut polyline = |points: &[Vector2<f64>], color: Color| {
+            for w in points.windows(2) {
+                gizmos.line(to_world(&w[0]), to_world(&w[1]), color);
+            }
+        };
+        for entity in &self.entities {
+            polyline(&self.entity_polyline(entity, 48), WHITE);
+        }
+        for dim in &self.dimensions {
+            for segment in dim.annotation(self) {
+                polyline(&segment, CYAN);
+            }
+        }
+        for p in &self.points {
+            let color = if p.fixed { YELLOW } else { WHITE };
+            let position = to_world(&p.position);
+            gizmos.circle(position, scale.world_size(position, SKETCH_POINT_PIXELS), color);
+        }
+    }
+}
+
+/// Points along a circular arc starting at angle `a0` and sweeping `sweep` radians
+pub fn arc_polyline(center: &Vector2<f64>, radius: f64, a0: f64, sweep: f64, segments: usize) -> Vec<Vector2<f64>> {
+    let segments = segments.max(1);
+    (0..=segments)
+        .map(|i| {
+            let a = a0 + sweep * i as f64 / segments as f64;
+            center + Vector2::new(a.cos(), a.sin()) * radius
+        })
+        .collect()
+}
+
+/// All sketches of the document
+#[derive(Resource, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Sketches {
+    pub sketches: Vec<Sketch>,
+    /// Index of the sketch being edited
+    pub active: Option<usize>,
+}
+
+impl Sketches {
+    pub fn add(&mut self, sketch: Sketch) -> usize {
+        self.sketches.push(sketch);
+        self.sketches.len() - 1
+    }
+
+    pub fn active(&self) -> Option<&Sketch> {
+        self.sketches.get(self.active?)
+    }
+
+    pub fn active_mut(&mut self) -> Option<&mut Sketch> {
+        self.sketches.get_mut(self.active?)
+    }
+
+    #[cfg(feature = "render")]
+    pub fn render(mut gizmos: Gizmos, sketches: Res<Sketches>, scale: Option<Res<GizmoScale>>) {
+        let scale = scale.as_deref().copied().unwrap_or_default();
+        for sketch in sketches.sketches.iter().filter(|s| !s.hidden) {
+            sketch.draw(&mut gizmos, &scale);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_sketch_and_measure() {
+        let mut s = Sketch::new("Sketch.001", Plane::xy());
+        let a = s.add_fixed_point(Vector2::new(0.0, 0.0));
+        let b = s.add_point(Vector2::new(3.0, 4.0));
+        s.add_line(a, b);
+        let d = s.add_dimension(DimensionKind::Linear { a, b }, None).unwrap();
+        assert!((s.dimension(d).unwrap().value - 5.0).abs() < 1e-12);
+        assert!(s.point(a).unwrap().fixed);
+    }
+
+    #[test]
+    fn test_plane_round_trip() {
+        let s = Sketch::new("S", Plane::from_point_normal(nalgebra::Point3::new(0.0, 0.0, 5.0), nalgebra::Vector3::z(), None));
+        let p = Vector2::new(1.5, -2.0);
+        let world = s.plane.point_at_2d(&p);
+        assert!((world.z - 5.0).abs() < 1e-12);
+        assert!((s.plane.project_2d(&world) - p).norm() < 1e-12);
+    }
+}
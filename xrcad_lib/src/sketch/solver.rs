@@ -0,0 +1,211 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: sketch::solver
+//!
+//! Numeric sketch solver. Free point coordinates and circle radii are adjusted
+//! by Gauss-Newton steps (minimum-norm, so under-constrained geometry moves as
+//! little as possible) until every dimension holds.
+
+use std::fmt;
+
+use nalgebra::{DMatrix, DVector};
+
+use crate::sketch::sketch::{Sketch, SketchEntity};
+
+/// Iteration limit for a single solve
+pub const MAX_ITERATIONS: usize = 50;
+
+/// Why a sketch could not be solved
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolveError {
+    /// A dimension references missing or unsuitable geometry
+    InvalidDimension(usize),
+    /// No configuration satisfying all dimensions was found; the sketch is unchanged
+    NotConverged { residual: f64 },
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::InvalidDimension(id) => write!(f, "dimension {} references invalid geometry", id),
+            SolveError::NotConverged { residual } => write!(f, "sketch did not converge (residual {:.3e})", residual),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+/// A solver unknown
+#[derive(Debug, Clone, Copy)]
+enum Variable {
+    X(usize),
+    Y(usize),
+    Radius(usize),
+}
+
+fn variables(sketch: &Sketch) -> Vec<Variable> {
+    let points = sketch.points.iter().filter(|p| !p.fixed).flat_map(|p| [Variable::X(p.id), Variable::Y(p.id)]);
+    let radii = sketch.entities.iter().filter_map(|e| match e {
+        SketchEntity::Circle { id, .. } => Some(Variable::Radius(*id)),
+        _ => None,
+    });
+    points.chain(radii).collect()
+}
+
+fn get(sketch: &Sketch, var: Variable) -> f64 {
+    match var {
+        Variable::X(id) => sketch.point_position(id).map_or(0.0, |p| p.x),
+        Variable::Y(id) => sketch.point_position(id).map_or(0.0, |p| p.y),
+        Variable::Radius(id) => match sketch.entity(id) {
+            Some(SketchEntity::Circle { radius, .. }) => *radius,
+            _ => 0.0,
+        },
+    }
+}
+
+fn set(sketch: &mut Sketch, var: Variable, value: f64) {
+    match var {
+        Variable::X(id) => {
+            if let Some(p) = sketch.point_mut(id) {
+                p.position.x = value;
+            }
+        }
+        Variable::Y(id) => {
+            if let Some(p) = sketch.point_mut(id) {
+                p.position.y = value;
+            }
+        }
+        Variable::Radius(id) => {
+            if let Some(SketchEntity::Circle { radius, .. }) = sketch.entities.iter_mut().find(|e| e.id() == id) {
+                *radius = value;
+            }
+        }
+    }
+}
+
+/// All constraint errors: one per dimension, plus one per arc keeping its end on its circle
+fn residuals(sketch: &Sketch) -> Result<DVector<f64>, SolveError> {
+    let mut r = Vec::with_capacity(sketch.dimensions.len());
+    for dim in &sketch.dimensions {
+        r.push(dim.residual(sketch).ok_or(SolveError::InvalidDimension(dim.id))?);
+    }
+    for entity in &sketch.entities {
+        if let SketchEntity::Arc { center, start, end, .. } = entity {
+            let (Some(c), Some(s), Some(e)) =
+                (sketch.point_position(*center), sketch.point_position(*start), sketch.point_position(*end))
+            else {
+                continue;
+            };
+            r.push((e - c).norm() - (s - c).norm());
+        }
+    }
+    Ok(DVector::from_vec(r))
+}
+
+/// Solve the sketch in place. Returns the number of iterations taken.
+pub fn solve(sketch: &mut Sketch) -> Result<usize, SolveError> {
+    let vars = variables(sketch);
+    let tol = sketch.tolerance.linear;
+    let original: Vec<f64> = vars.iter().map(|v| get(sketch, *v)).collect();
+
+    let mut r = residuals(sketch)?;
+    for iteration in 0..MAX_ITERATIONS {
+        if r.amax() <= tol {
+            return Ok(iteration);
+        }
+        if vars.is_empty() {
+            break;
+        }
+        // Central-difference Jacobian
+        let mut jacobian = DMatrix::zeros(r.len(), vars.len());
+        for (j, var) in vars.iter().enumerate() {
+            let x = get(sketch, *var);
+            let h = 1e-7 * x.abs().max(1.0);
+            set(sketch, *var, x + h);
+            let plus = residuals(sketch)?;
+            set(sketch, *var, x - h);
+            let minus = residuals(sketch)?;
+            set(sketch, *var, x);
+            jacobian.set_column(j, &((plus - minus) / (2.0 * h)));
+        }
+        let Ok(step) = jacobian.svd(true, true).solve(&-&r, 1e-12) else { break; };
+        for (var, dx) in vars.iter().zip(step.iter()) {
+            set(sketch, *var, get(sketch, *var) + dx);
+        }
+        r = residuals(sketch)?;
+    }
+    if r.amax() <= tol {
+        return Ok(MAX_ITERATIONS);
+    }
+    for (var, x) in vars.iter().zip(original) {
+        set(sketch, *var, x);
+    }
+    Err(SolveError::NotConverged { residual: r.amax() })
+}
+
+/// Change a dimension's driving value and re-solve; the old value is restored on failure
+pub fn set_dimension_value(sketch: &mut Sketch, dimension: usize, value: f64) -> Result<usize, SolveError> {
+    let dim = sketch.dimension_mut(dimension).ok_or(SolveError::InvalidDimension(dimension))?;
+    let previous = std::mem::replace(&mut dim.value, value);
+    let result = solve(sketch);
+    if result.is_err() {
+        if let Some(dim) = sketch.dimension_mut(dimension) {
+            dim.value = previous;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::topology::plane::Plane;
+    use crate::sketch::dimension::DimensionKind;
+    use nalgebra::Vector2;
+
+    fn triangle() -> (Sketch, usize, usize, usize) {
+        let mut s = Sketch::new("S", Plane::xy());
+        let o = s.add_fixed_point(Vector2::new(0.0, 0.0));
+        let x = s.add_point(Vector2::new(10.0, 0.0));
+        let y = s.add_point(Vector2::new(0.0, 10.0));
+        let l1 = s.add_line(o, x);
+        let l2 = s.add_line(o, y);
+        s.add_line(x, y);
+        let len = s.add_dimension(DimensionKind::Linear { a: o, b: x }, None).unwrap();
+        let ang = s.add_dimension(DimensionKind::Angular { a: l1, b: l2 }, None).unwrap();
+        (s, o, len, ang)
+    }
+
+    #[test]
+    fn test_changing_length_resolves() {
+        let (mut s, o, len, _) = triangle();
+        set_dimension_value(&mut s, len, 25.0).unwrap();
+        let DimensionKind::Linear { b, .. } = s.dimension(len).unwrap().kind else { unreachable!() };
+        assert!(((s.point_position(b).unwrap() - s.point_position(o).unwrap()).norm() - 25.0).abs() < 1e-6);
+        // The fixed point stays put
+        assert_eq!(s.point_position(o).unwrap(), Vector2::zeros());
+    }
+
+    #[test]
+    fn test_changing_angle_and_radius() {
+        let (mut s, o, _, ang) = triangle();
+        set_dimension_value(&mut s, ang, 60f64.to_radians()).unwrap();
+        assert!((s.dimension(ang).unwrap().kind.measure(&s).unwrap() - 60f64.to_radians()).abs() < 1e-6);
+
+        let c = s.add_circle(o, 3.0);
+        let r = s.add_dimension(DimensionKind::Radial { entity: c }, None).unwrap();
+        set_dimension_value(&mut s, r, 4.5).unwrap();
+        assert!(matches!(s.entity(c), Some(SketchEntity::Circle { radius, .. }) if (radius - 4.5).abs() < 1e-9));
+    }
+
+    #[test]
+    fn test_infeasible_value_is_rejected() {
+        let mut s = Sketch::new("S", Plane::xy());
+        let a = s.add_fixed_point(Vector2::new(0.0, 0.0));
+        let b = s.add_fixed_point(Vector2::new(1.0, 0.0));
+        let d = s.add_dimension(DimensionKind::Linear { a, b }, None).unwrap();
+        assert!(matches!(set_dimension_value(&mut s, d, 2.0), Err(SolveError::NotConverged { .. })));
+        assert_eq!(s.dimension(d).unwrap().value, 1.0);
+    }
+}
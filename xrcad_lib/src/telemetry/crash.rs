@@ -0,0 +1,328 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: telemetry::crash
+//!
+//! Crash safety: a panic hook that writes the last known document state to a
+//! recovery file, and a diagnostic report with the recent operation journal,
+//! before the process goes down. The snapshot is a project document, refreshed
+//! by a system once the document has been quiet for `RECOVERY_DEBOUNCE` after
+//! a change, so the hook never has to touch the ECS world and the file opens
+//! like any project. Both files live in the per-user data directory. On the
+//! next start `PendingRecovery` holds the file until it is restored
+//! (Ctrl+Shift+R) or discarded (Ctrl+Shift+Delete).
+
+use std::backtrace::Backtrace;
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::panic::{self, PanicHookInfo};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::input::keyboard::KeyBindings;
+use crate::io::project::{in_shared_session, ProjectDocument, ProjectFile};
+use crate::model::document::DocumentChanged;
+
+/// Recovery file name inside the crash directory; a project document
+pub const RECOVERY_FILE: &str = "xrcad_recovery.xrcad";
+/// Diagnostic report file name inside the crash directory
+pub const CRASH_REPORT_FILE: &str = "xrcad_crash_report.txt";
+/// Number of journal entries kept for the crash report
+pub const JOURNAL_CAPACITY: usize = 200;
+/// Quiet time after a document change before the snapshot is refreshed
+pub const RECOVERY_DEBOUNCE: Duration = Duration::from_secs(2);
+
+/// Per-user data directory of the application: `%APPDATA%\xrcad` on Windows,
+/// `~/Library/Application Support/xrcad` on macOS and `$XDG_DATA_HOME/xrcad`
+/// (by default `~/.local/share/xrcad`) elsewhere. Falls back to the temp
+/// directory where none is set, as on Android.
+pub fn app_data_dir() -> PathBuf {
+    let var = |name: &str| std::env::var_os(name).map(PathBuf::from).filter(|p| p.is_absolute());
+    let base = if cfg!(windows) {
+        var("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        var("HOME").map(|home| home.join("Library").join("Application Support"))
+    } else {
+        var("XDG_DATA_HOME").or_else(|| var("HOME").map(|home| home.join(".local").join("share")))
+    };
+    base.unwrap_or_else(std::env::temp_dir).join("xrcad")
+}
+
+/// Last known document state, shared with the panic hook
+#[derive(Debug, Default)]
+struct RecoveryState {
+    document: String,
+    journal: VecDeque<String>,
+}
+
+static RECOVERY: Mutex<RecoveryState> = Mutex::new(RecoveryState { document: String::new(), journal: VecDeque::new() });
+
+/// Append an entry to the operation journal
+pub fn journal(entry: impl Into<String>) {
+    if let Ok(mut state) = RECOVERY.lock() {
+        if state.journal.len() == JOURNAL_CAPACITY {
+            state.journal.pop_front();
+        }
+        state.journal.push_back(entry.into());
+    }
+}
+
+/// Replace the document snapshot written on a crash
+pub fn set_document_snapshot(document: String) {
+    if let Ok(mut state) = RECOVERY.lock() {
+        state.document = document;
+    }
+}
+
+/// When the document last changed without the snapshot being refreshed
+#[derive(Resource, Debug, Default, Clone, PartialEq)]
+pub struct RecoverySnapshot {
+    /// Elapsed app time of the change
+    pub changed_at: Option<Duration>,
+}
+
+/// Recovery file left by a previous crash, waiting to be restored or discarded
+#[derive(Resource, Debug, Default, Clone, PartialEq)]
+pub struct PendingRecovery {
+    pub path: Option<PathBuf>,
+}
+
+/// Replace the open document with the pending recovery file
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RestoreRecovery;
+
+/// Delete the pending recovery file
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiscardRecovery;
+
+/// Note document changes; the snapshot is taken once they stop
+pub fn schedule_recovery_snapshot(mut changes: EventReader<DocumentChanged>, time: Res<Time>, mut snapshot: ResMut<RecoverySnapshot>) {
+    if changes.read().count() > 0 {
+        snapshot.changed_at = Some(time.elapsed());
+    }
+}
+
+/// Refresh the crash snapshot `RECOVERY_DEBOUNCE` after the last document change
+pub fn update_recovery_snapshot(world: &mut World) {
+    let (Some(changed_at), Some(time)) = (world.get_resource::<RecoverySnapshot>().and_then(|s| s.changed_at), world.get_resource::<Time>())
+    else {
+        return;
+    };
+    if time.elapsed().saturating_sub(changed_at) < RECOVERY_DEBOUNCE {
+        return;
+    }
+    world.resource_mut::<RecoverySnapshot>().changed_at = None;
+    match ProjectDocument::capture(world).to_ron() {
+        Ok(document) => set_document_snapshot(document),
+        Err(err) => warn!("Could not snapshot the document for crash recovery: {}", err),
+    }
+}
+
+/// Tell the user about a recovery file found at startup
+pub fn announce_recovery(pending: Res<PendingRecovery>, bindings: Res<KeyBindings>) {
+    if let Some(path) = &pending.path {
+        warn!(
+            "A recovery file from a previous crash is available at {}; press {} to restore it or {} to discard it",
+            path.display(),
+            bindings.label("restore_recovery"),
+            bindings.label("discard_recovery")
+        );
+    }
+}
+
+/// The restore_recovery and discard_recovery bindings settle a pending recovery file
+pub fn recovery_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    pending: Res<PendingRecovery>,
+    mut restore: EventWriter<RestoreRecovery>,
+    mut discard: EventWriter<DiscardRecovery>,
+) {
+    if pending.path.is_none() {
+        return;
+    }
+    if bindings.just_pressed("restore_recovery", &keys) {
+        restore.write(RestoreRecovery);
+    } else if bindings.just_pressed("discard_recovery", &keys) {
+        discard.write(DiscardRecovery);
+    }
+}
+
+/// Carry out restore and discard requests. A restored document is unsaved:
+/// it has no project file, so saving asks for a path.
+pub fn handle_recovery_requests(world: &mut World) {
+    let restore = world.get_resource_mut::<Events<RestoreRecovery>>().is_some_and(|mut events| events.drain().count() > 0);
+    let discard = world.get_resource_mut::<Events<DiscardRecovery>>().is_some_and(|mut events| events.drain().count() > 0);
+    if !restore && !discard {
+        return;
+    }
+    if restore && in_shared_session(world) {
+        warn!("Leave the shared session before restoring the recovered document");
+        return;
+    }
+    let Some(path) = world.get_resource_mut::<PendingRecovery>().and_then(|mut pending| pending.path.take()) else { return };
+    if restore {
+        journal(format!("restore_recovery {:?}", path));
+        match ProjectDocument::load(&path) {
+            Ok(doc) => {
+                doc.apply(world);
+                world.insert_resource(ProjectFile::default());
+                info!("Restored the document from {}", path.display());
+            }
+            Err(err) => warn!("Could not restore {}: {}", path.display(), err),
+        }
+    } else {
+        journal(format!("discard_recovery {:?}", path));
+    }
+    if let Err(err) = fs::remove_file(&path) {
+        warn!("Could not remove {}: {}", path.display(), err);
+    }
+}
+
+/// Diagnostic report: panic message, location, backtrace, system information
+/// and the operation journal
+pub fn crash_report(message: &str, backtrace: &Backtrace, entries: &[String]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "xrcad {} crashed", env!("CARGO_PKG_VERSION"));
+    let _ = writeln!(out, "os: {} ({}), arch: {}", std::env::consts::OS, std::env::consts::FAMILY, std::env::consts::ARCH);
+    let _ = writeln!(out, "threads available: {}", std::thread::available_parallelism().map_or(0, |n| n.get()));
+    let _ = writeln!(out, "thread: {}", std::thread::current().name().unwrap_or("<unnamed>"));
+    let _ = writeln!(out, "\n{}\n\nbacktrace:\n{}", message, backtrace);
+    let _ = writeln!(out, "\njournal:");
+    for entry in entries {
+        let _ = writeln!(out, "{}", entry);
+    }
+    out
+}
+
+/// Write the crash report, and the recovery file if there is a snapshot, into
+/// `dir`; without a snapshot an older recovery file is left in place
+pub fn write_crash_files(dir: &Path, message: &str, backtrace: &Backtrace) -> io::Result<(Option<PathBuf>, PathBuf)> {
+    fs::create_dir_all(dir)?;
+    let report = dir.join(CRASH_REPORT_FILE);
+    // Never block inside a panic: a poisoned or held lock just skips the snapshot
+    let (document, entries): (String, Vec<String>) = RECOVERY
+        .try_lock()
+        .ok()
+        .map(|state| (state.document.clone(), state.journal.iter().cloned().collect()))
+        .unwrap_or_default();
+    let recovery = if document.is_empty() {
+        None
+    } else {
+        let path = dir.join(RECOVERY_FILE);
+        fs::write(&path, document)?;
+        Some(path)
+    };
+    fs::write(&report, crash_report(message, backtrace, &entries))?;
+    Ok((recovery, report))
+}
+
+fn panic_message(info: &PanicHookInfo) -> String {
+    let payload = info
+        .payload()
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "<non-string panic payload>".to_string());
+    match info.location() {
+        Some(loc) => format!("panic at {}:{}: {}", loc.file(), loc.line(), payload),
+        None => format!("panic: {}", payload),
+    }
+}
+
+/// Install the crash handler; recovery data goes to `dir`. The previous hook still runs afterwards.
+pub fn install_panic_hook(dir: impl Into<PathBuf>) {
+    let dir = dir.into();
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let message = panic_message(info);
+        match write_crash_files(&dir, &message, &Backtrace::force_capture()) {
+            Ok((Some(recovery), report)) => eprintln!(
+                "xrcad crashed; document saved to {} and report to {}",
+                recovery.display(),
+                report.display()
+            ),
+            Ok((None, report)) => eprintln!("xrcad crashed; report saved to {}", report.display()),
+            Err(err) => eprintln!("xrcad crashed and the recovery file could not be written: {}", err),
+        }
+        previous(info);
+    }));
+}
+
+/// Recovery file left by a previous crash, if any
+pub fn pending_recovery(dir: &Path) -> Option<PathBuf> {
+    let path = dir.join(RECOVERY_FILE);
+    path.exists().then_some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::cube;
+    use crate::model::brep_model::BrepModel;
+
+    #[test]
+    fn test_write_crash_files() {
+        let dir = std::env::temp_dir().join(format!("xrcad_crash_test_{}", std::process::id()));
+        set_document_snapshot(ProjectDocument { model: cube(1.0), ..Default::default() }.to_ron().unwrap());
+        journal("rename_body Body -> Bracket");
+        let (recovery, report) = write_crash_files(&dir, "panic at test", &Backtrace::disabled()).unwrap();
+        let recovery = recovery.unwrap();
+        assert_eq!(ProjectDocument::load(&recovery).unwrap().model.faces.len(), 6);
+        let report = fs::read_to_string(&report).unwrap();
+        assert!(report.contains("panic at test") && report.contains("rename_body Body -> Bracket"));
+        assert_eq!(pending_recovery(&dir), Some(recovery));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    fn recovery_app() -> App {
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .init_resource::<BrepModel>()
+            .init_resource::<RecoverySnapshot>()
+            .init_resource::<PendingRecovery>()
+            .add_event::<DocumentChanged>()
+            .add_event::<RestoreRecovery>()
+            .add_event::<DiscardRecovery>()
+            .add_systems(Update, (schedule_recovery_snapshot, update_recovery_snapshot, handle_recovery_requests).chain());
+        app
+    }
+
+    #[test]
+    fn test_snapshot_waits_for_quiet() {
+        let mut app = recovery_app();
+        // The snapshot is process-wide; keep it the same document the crash file test writes
+        app.insert_resource(cube(1.0));
+        app.world_mut().send_event(DocumentChanged::Bodies);
+        app.update();
+        assert!(app.world().resource::<RecoverySnapshot>().changed_at.is_some());
+        app.world_mut().resource_mut::<Time>().advance_by(RECOVERY_DEBOUNCE);
+        app.update();
+        assert_eq!(app.world().resource::<RecoverySnapshot>().changed_at, None);
+    }
+
+    #[test]
+    fn test_restore_and_discard() {
+        let path = std::env::temp_dir().join(format!("xrcad_recovery_test_{}.xrcad", std::process::id()));
+        ProjectDocument { model: cube(1.0), ..Default::default() }.save(&path).unwrap();
+        let mut app = recovery_app();
+        app.insert_resource(PendingRecovery { path: Some(path.clone()) });
+        app.world_mut().send_event(RestoreRecovery);
+        app.update();
+        assert_eq!(app.world().resource::<BrepModel>().faces.len(), 6);
+        assert!(!path.exists());
+        assert_eq!(app.world().resource::<PendingRecovery>().path, None);
+
+        fs::write(&path, "left over").unwrap();
+        app.insert_resource(PendingRecovery { path: Some(path.clone()) });
+        app.world_mut().send_event(DiscardRecovery);
+        app.update();
+        assert!(!path.exists());
+        assert_eq!(app.world().resource::<BrepModel>().faces.len(), 6);
+    }
+}
@@ -0,0 +1,220 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: telemetry::usage
+//!
+//! Opt-in, local-only command usage statistics: how often each command runs and
+//! how long it takes. Statistics are kept in a tab-separated file next to the
+//! user's data and are never uploaded anywhere. Recording is off by default.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::input::keyboard::KeyBindings;
+
+/// Default statistics file, relative to the working directory
+pub const DEFAULT_USAGE_FILE: &str = "xrcad_usage.tsv";
+
+/// Aggregated usage of one command
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct CommandStats {
+    pub count: u64,
+    pub total: Duration,
+    pub longest: Duration,
+}
+
+impl CommandStats {
+    pub fn mean(&self) -> Duration {
+        if self.count == 0 {
+            Duration::ZERO
+        } else {
+            self.total / self.count as u32
+        }
+    }
+}
+
+/// Notification that a command ran, for systems that do not hold the stats resource
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct CommandExecuted {
+    pub name: String,
+    pub duration: Duration,
+}
+
+/// Command usage statistics of this and previous sessions
+#[derive(Resource, Debug, Clone)]
+pub struct UsageStats {
+    /// Recording only happens when the user opted in
+    pub enabled: bool,
+    pub path: PathBuf,
+    pub commands: BTreeMap<String, CommandStats>,
+}
+
+impl Default for UsageStats {
+    fn default() -> Self {
+        Self::new(DEFAULT_USAGE_FILE)
+    }
+}
+
+impl UsageStats {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { enabled: false, path: path.into(), commands: BTreeMap::new() }
+    }
+
+    /// Record one execution of a command (ignored unless enabled)
+    pub fn record(&mut self, name: &str, duration: Duration) {
+        if !self.enabled {
+            return;
+        }
+        let stats = self.commands.entry(name.to_string()).or_default();
+        stats.count += 1;
+        stats.total += duration;
+        stats.longest = stats.longest.max(duration);
+    }
+
+    /// Commands ordered by execution count, most used first
+    pub fn most_used(&self, limit: usize) -> Vec<(&str, CommandStats)> {
+        let mut all: Vec<(&str, CommandStats)> = self.commands.iter().map(|(k, v)| (k.as_str(), *v)).collect();
+        all.sort_by(|a, b| b.1.count.cmp(&a.1.count).then(a.0.cmp(b.0)));
+        all.truncate(limit);
+        all
+    }
+
+    /// Commands ordered by total time spent, slowest first
+    pub fn most_time(&self, limit: usize) -> Vec<(&str, CommandStats)> {
+        let mut all: Vec<(&str, CommandStats)> = self.commands.iter().map(|(k, v)| (k.as_str(), *v)).collect();
+        all.sort_by(|a, b| b.1.total.cmp(&a.1.total).then(a.0.cmp(b.0)));
+        all.truncate(limit);
+        all
+    }
+
+    /// Serialize as `name<TAB>count<TAB>total_us<TAB>longest_us` lines
+    pub fn to_tsv(&self) -> String {
+        self.commands
+            .iter()
+            .map(|(name, s)| format!("{}\t{}\t{}\t{}\n", name, s.count, s.total.as_micros(), s.longest.as_micros()))
+            .collect()
+    }
+
+    /// Merge statistics from TSV text; malformed lines are skipped
+    pub fn merge_tsv(&mut self, text: &str) {
+        for line in text.lines() {
+            let fields: Vec<&str> = line.split('\t').collect();
+            let [name, count, total, longest] = fields[..] else { continue; };
+            let (Ok(count), Ok(total), Ok(longest)) = (count.parse::<u64>(), total.parse::<u64>(), longest.parse::<u64>())
+            else {
+                continue;
+            };
+            let stats = self.commands.entry(name.to_string()).or_default();
+            stats.count += count;
+            stats.total += Duration::from_micros(total);
+            stats.longest = stats.longest.max(Duration::from_micros(longest));
+        }
+    }
+
+    /// Load statistics from previous sessions; a missing file is not an error
+    pub fn load(&mut self) -> io::Result<()> {
+        match fs::read_to_string(&self.path) {
+            Ok(text) => {
+                self.merge_tsv(&text);
+                Ok(())
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Write statistics to the local file (only when enabled)
+    pub fn save(&self) -> io::Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        write_atomic(&self.path, &self.to_tsv())
+    }
+
+    /// Forget all statistics and delete the local file
+    pub fn clear(&mut self) -> io::Result<()> {
+        self.commands.clear();
+        match fs::remove_file(&self.path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn write_atomic(path: &Path, contents: &str) -> io::Result<()> {
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, contents)?;
+    fs::rename(tmp, path)
+}
+
+/// Fold `CommandExecuted` events into the statistics
+pub fn record_command_usage(mut events: EventReader<CommandExecuted>, mut stats: ResMut<UsageStats>) {
+    for ev in events.read() {
+        stats.record(&ev.name, ev.duration);
+    }
+}
+
+/// The usage_stats binding (F4) toggles recording; statistics are flushed when recording stops
+pub fn usage_stats_keys(keys: Res<ButtonInput<KeyCode>>, bindings: Res<KeyBindings>, mut stats: ResMut<UsageStats>) {
+    if bindings.just_pressed("usage_stats", &keys) {
+        if let Err(err) = stats.save() {
+            warn!("Could not save usage statistics: {}", err);
+        }
+        stats.enabled = !stats.enabled;
+    }
+}
+
+/// Flush statistics when the app exits
+pub fn save_usage_on_exit(exit: EventReader<AppExit>, stats: Res<UsageStats>) {
+    if !exit.is_empty() {
+        if let Err(err) = stats.save() {
+            warn!("Could not save usage statistics: {}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_by_default() {
+        let mut stats = UsageStats::default();
+        stats.record("extrude", Duration::from_millis(5));
+        assert!(stats.commands.is_empty());
+    }
+
+    #[test]
+    fn test_record_and_rank() {
+        let mut stats = UsageStats::new("unused.tsv");
+        stats.enabled = true;
+        stats.record("rename", Duration::from_millis(1));
+        stats.record("rename", Duration::from_millis(3));
+        stats.record("solve", Duration::from_millis(40));
+        let used = stats.most_used(1);
+        assert_eq!(used[0].0, "rename");
+        assert_eq!(used[0].1.mean(), Duration::from_millis(2));
+        assert_eq!(stats.most_time(1)[0].0, "solve");
+    }
+
+    #[test]
+    fn test_tsv_round_trip_and_file() {
+        let path = std::env::temp_dir().join(format!("xrcad_usage_test_{}.tsv", std::process::id()));
+        let mut stats = UsageStats::new(&path);
+        stats.enabled = true;
+        stats.record("rename", Duration::from_micros(1500));
+        stats.save().unwrap();
+
+        let mut loaded = UsageStats::new(&path);
+        loaded.load().unwrap();
+        loaded.merge_tsv("garbage line\n");
+        assert_eq!(loaded.commands, stats.commands);
+        loaded.clear().unwrap();
+        assert!(!path.exists());
+    }
+}
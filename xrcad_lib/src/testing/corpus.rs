@@ -0,0 +1,224 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: testing::corpus
+//!
+//! Regression runner over a directory of fixture models. Each fixture is loaded
+//! by the loader registered for its file extension, then regenerated (written
+//! and re-read), validated and tessellated. Every stage is timed and the first
+//! failure is reported. A fixture starting with a `# expect: sheet` line is
+//! validated as an open sheet rather than a closed solid.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::model::brep::tessellate::tessellate;
+use crate::model::brep::text_format::{from_text, to_text};
+use crate::model::brep::validate::{validate, validate_solid};
+use crate::model::brep_model::{area_vector, BrepModel};
+
+/// Parses fixture file contents into a model
+pub type FixtureLoader = fn(&str) -> Result<BrepModel, String>;
+
+/// Timing of one stage of a fixture run
+#[derive(Debug, Clone, PartialEq)]
+pub struct StageTiming {
+    pub stage: &'static str,
+    pub duration: Duration,
+}
+
+/// Outcome of one fixture
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixtureReport {
+    pub path: PathBuf,
+    pub stages: Vec<StageTiming>,
+    /// First failure, if any
+    pub error: Option<String>,
+}
+
+impl FixtureReport {
+    pub fn passed(&self) -> bool {
+        self.error.is_none()
+    }
+
+    pub fn total_time(&self) -> Duration {
+        self.stages.iter().map(|s| s.duration).sum()
+    }
+}
+
+/// Outcome of a whole corpus
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CorpusReport {
+    pub fixtures: Vec<FixtureReport>,
+}
+
+impl CorpusReport {
+    pub fn passed(&self) -> usize {
+        self.fixtures.iter().filter(|f| f.passed()).count()
+    }
+
+    pub fn failed(&self) -> usize {
+        self.fixtures.len() - self.passed()
+    }
+
+    /// Human-readable table of results
+    pub fn summary(&self) -> String {
+        let mut out = String::new();
+        for f in &self.fixtures {
+            let status = if f.passed() { "PASS" } else { "FAIL" };
+            let stages: Vec<String> =
+                f.stages.iter().map(|s| format!("{} {:.2}ms", s.stage, s.duration.as_secs_f64() * 1000.0)).collect();
+            let _ = writeln!(out, "{} {} [{}]", status, f.path.display(), stages.join(", "));
+            if let Some(err) = &f.error {
+                let _ = writeln!(out, "     {}", err);
+            }
+        }
+        let _ = writeln!(out, "{} passed, {} failed", self.passed(), self.failed());
+        out
+    }
+}
+
+/// Fixture runner with per-extension loaders
+#[derive(Debug, Clone)]
+pub struct CorpusRunner {
+    loaders: BTreeMap<String, FixtureLoader>,
+    /// Allowed relative difference between tessellated and exact face areas
+    pub area_tolerance: f64,
+}
+
+impl Default for CorpusRunner {
+    fn default() -> Self {
+        let mut runner = Self { loaders: BTreeMap::new(), area_tolerance: 1e-9 };
+        runner.register_loader("brep", |text| from_text(text).map_err(|e| e.to_string()));
+        runner
+    }
+}
+
+impl CorpusRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register (or replace) the loader for a file extension
+    pub fn register_loader(&mut self, extension: &str, loader: FixtureLoader) {
+        self.loaders.insert(extension.to_ascii_lowercase(), loader);
+    }
+
+    pub fn supports(&self, path: &Path) -> bool {
+        self.loader_for(path).is_some()
+    }
+
+    fn loader_for(&self, path: &Path) -> Option<FixtureLoader> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        self.loaders.get(&ext).copied()
+    }
+
+    /// Run every supported fixture in `dir` (not recursive), in file name order
+    pub fn run_dir(&self, dir: &Path) -> io::Result<CorpusReport> {
+        let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| p.is_file() && self.supports(p))
+            .collect();
+        paths.sort();
+        Ok(CorpusReport { fixtures: paths.iter().map(|p| self.run_file(p)).collect() })
+    }
+
+    /// Run all stages on one fixture
+    pub fn run_file(&self, path: &Path) -> FixtureReport {
+        let mut report = FixtureReport { path: path.to_path_buf(), stages: Vec::new(), error: None };
+        if let Err(err) = self.run_stages(path, &mut report.stages) {
+            report.error = Some(err);
+        }
+        report
+    }
+
+    fn run_stages(&self, path: &Path, stages: &mut Vec<StageTiming>) -> Result<(), String> {
+        let loader = self.loader_for(path).ok_or("no loader for this file type")?;
+        let model = timed(stages, "load", || {
+            let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+            let sheet = text.lines().next().is_some_and(|l| l.trim() == "# expect: sheet");
+            loader(&text).map(|m| (m, sheet)).map_err(|e| format!("load: {}", e))
+        });
+        let (model, sheet) = model?;
+
+        timed(stages, "regenerate", || {
+            let text = to_text(&model);
+            let again = from_text(&text).map_err(|e| format!("regenerate: {}", e))?;
+            if to_text(&again) != text {
+                return Err("regenerate: model changed on round trip".to_string());
+            }
+            Ok(())
+        })?;
+
+        timed(stages, "validate", || {
+            let issues = if sheet { validate(&model) } else { validate_solid(&model) };
+            match issues.first() {
+                None => Ok(()),
+                Some(first) => Err(format!("validate: {} ({} issues)", first, issues.len())),
+            }
+        })?;
+
+        timed(stages, "tessellate", || {
+            let meshes = tessellate(&model);
+            if meshes.len() != model.faces.len() {
+                return Err(format!("tessellate: {} of {} faces meshed", meshes.len(), model.faces.len()));
+            }
+            for mesh in meshes {
+                let face = model.face(mesh.face_id).ok_or("tessellate: unknown face")?;
+                let exact: f64 = model
+                    .face_loops(face)
+                    .iter()
+                    .enumerate()
+                    .map(|(i, l)| {
+                        let area = area_vector(&model.loop_positions(l)).norm();
+                        if i == 0 { area } else { -area }
+                    })
+                    .sum();
+                let meshed: f64 =
+                    mesh.triangle_positions().map(|[a, b, c]| (b - a).cross(&(c - a)).norm() * 0.5).sum();
+                if (meshed - exact).abs() > self.area_tolerance * exact.abs().max(1.0) {
+                    return Err(format!("tessellate: face {} area {} != {}", mesh.face_id, meshed, exact));
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+fn timed<T>(stages: &mut Vec<StageTiming>, stage: &'static str, f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let out = f();
+    stages.push(StageTiming { stage, duration: start.elapsed() });
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_fixtures_pass() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures");
+        let report = CorpusRunner::new().run_dir(&dir).unwrap();
+        assert!(report.fixtures.len() >= 3);
+        assert_eq!(report.failed(), 0, "{}", report.summary());
+    }
+
+    #[test]
+    fn test_failures_are_reported() {
+        let dir = std::env::temp_dir().join(format!("xrcad_corpus_test_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("broken.brep"), "vertex 0 0 0 0\nedge 0 0 7\n").unwrap();
+        fs::write(dir.join("ignored.txt"), "not a fixture").unwrap();
+        let report = CorpusRunner::new().run_dir(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(report.fixtures.len(), 1);
+        assert_eq!(report.failed(), 1);
+        assert!(report.fixtures[0].error.as_deref().unwrap().starts_with("validate"));
+        assert!(report.summary().contains("FAIL"));
+    }
+}
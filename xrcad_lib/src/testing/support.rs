@@ -0,0 +1,18 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: testing::support
+//!
+//! Helpers shared by the unit tests of several modules.
+
+use nalgebra::Vector3;
+
+use crate::model::brep_model::BrepModel;
+
+/// The model with every vertex moved by `offset`
+pub fn shifted(mut model: BrepModel, offset: Vector3<f64>) -> BrepModel {
+    for v in &mut model.vertices {
+        v.position += offset;
+    }
+    model
+}
@@ -2,22 +2,118 @@
 // Copyright (c) 2025 Adrian Scarlett
 
 //! Module: viewport::camera
+//!
+//! What drives a view camera. Every view camera carries a `ViewRig`: a
+//! desktop rig is a flat-screen camera moved by `CustomCameraController`, an
+//! XR rig is a headset that the XR runtime moves by the tracked head motion
+//! each frame and renders, per eye when stereo, with its own projections.
+//! Scene logic targets the rig rather than a device: XR scale, comfort,
+//! locomotion, passthrough and XR panels act on XR rigs, and the desktop
+//! controls leave XR rigs alone so they don't fight the headset. The XR
+//! runtime switches the rig with `SetViewRig` as a session starts and ends,
+//! and keeps `XrSession` current with what the running session offers.
 
-/// Camera viewport struct.
-pub struct Camera;
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
 
-impl Camera {
-    pub fn new() -> Self {
-        Camera
+use crate::telemetry::crash::journal;
+use crate::telemetry::usage::UsageStats;
+
+/// How a view camera is driven
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ViewRig {
+    /// Flat screen, moved by the desktop camera controls
+    #[default]
+    Desktop,
+    /// Head-mounted display; `stereo` when it renders one view per eye
+    Xr { stereo: bool },
+}
+
+impl ViewRig {
+    /// The usual headset: XR in stereo
+    pub const HEADSET: ViewRig = ViewRig::Xr { stereo: true };
+
+    pub fn is_xr(&self) -> bool {
+        matches!(self, ViewRig::Xr { .. })
+    }
+
+    pub fn is_stereo(&self) -> bool {
+        matches!(self, ViewRig::Xr { stereo: true })
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ViewRig::Desktop => "Desktop",
+            ViewRig::Xr { stereo: false } => "XR",
+            ViewRig::Xr { stereo: true } => "XR stereo",
+        }
+    }
+}
+
+/// The XR session as reported by the XR backend. Without a backend no session
+/// ever runs, and everything that needs a headset stays off.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct XrSession {
+    pub running: bool,
+    /// The runtime offers an alpha-blend environment mode: the camera feed shows behind the scene
+    pub passthrough: bool,
+}
+
+/// Request to drive the view cameras with another rig
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetViewRig(pub ViewRig);
+
+/// Switch the view cameras' rig; back on the desktop the XR view scale is undone
+pub fn apply_view_rig_requests(
+    mut requests: EventReader<SetViewRig>,
+    mut cameras: Query<(&mut ViewRig, &mut Transform)>,
+    mut usage: Option<ResMut<UsageStats>>,
+) {
+    for SetViewRig(next) in requests.read() {
+        let start = Instant::now();
+        journal(format!("view_rig {}", next.label()));
+        for (mut rig, mut transform) in cameras.iter_mut() {
+            if *rig == *next {
+                continue;
+            }
+            if !next.is_xr() {
+                transform.scale = Vec3::ONE;
+            }
+            *rig = *next;
+        }
+        if let Some(usage) = usage.as_mut() {
+            usage.record("view_rig", start.elapsed());
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+
     #[test]
-    fn test_camera_new() {
-        let c = Camera::new();
-        let _ = c;
+    fn test_rig_kinds() {
+        assert!(!ViewRig::Desktop.is_xr() && !ViewRig::Desktop.is_stereo());
+        assert!(ViewRig::HEADSET.is_xr() && ViewRig::HEADSET.is_stereo());
+        let mono = ViewRig::Xr { stereo: false };
+        assert!(mono.is_xr() && !mono.is_stereo());
+        assert_eq!(mono.label(), "XR");
+    }
+
+    #[test]
+    fn test_switching_rigs() {
+        let mut app = App::new();
+        app.add_event::<SetViewRig>().add_systems(Update, apply_view_rig_requests);
+        let camera = app.world_mut().spawn((ViewRig::default(), Transform::default())).id();
+        app.world_mut().send_event(SetViewRig(ViewRig::HEADSET));
+        app.update();
+        assert_eq!(*app.world().get::<ViewRig>(camera).unwrap(), ViewRig::HEADSET);
+
+        // XR scale shrinks the camera; the desktop view has no use for that
+        app.world_mut().get_mut::<Transform>(camera).unwrap().scale = Vec3::splat(4.0);
+        app.world_mut().send_event(SetViewRig(ViewRig::Desktop));
+        app.update();
+        assert_eq!(*app.world().get::<ViewRig>(camera).unwrap(), ViewRig::Desktop);
+        assert_eq!(app.world().get::<Transform>(camera).unwrap().scale, Vec3::ONE);
     }
 }
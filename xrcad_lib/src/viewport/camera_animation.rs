@@ -0,0 +1,263 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: viewport::camera_animation
+//!
+//! Presentation camera for design reviews: a turntable that circles the pivot
+//! at a steady rate, or a fly-through that glides between saved views in
+//! order and loops back to the first. Space plays or pauses and Shift+Space
+//! switches between the two modes; the camera panel has a play/pause button
+//! for use in XR. Views are saved through `viewport::saved_views`.
+
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+
+use crate::input::keyboard::KeyBindings;
+use crate::telemetry::crash::journal;
+use crate::telemetry::usage::UsageStats;
+use crate::viewport::camera_control::CustomCameraController;
+use crate::viewport::saved_views::{SavedView, SavedViews};
+
+/// What the presentation camera does while playing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CameraAnimationMode {
+    #[default]
+    Turntable,
+    FlyThrough,
+}
+
+impl CameraAnimationMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CameraAnimationMode::Turntable => "Turntable",
+            CameraAnimationMode::FlyThrough => "Fly-through",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            CameraAnimationMode::Turntable => CameraAnimationMode::FlyThrough,
+            CameraAnimationMode::FlyThrough => CameraAnimationMode::Turntable,
+        }
+    }
+}
+
+/// Playback state of the presentation camera
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct CameraAnimation {
+    pub mode: CameraAnimationMode,
+    pub playing: bool,
+    /// Turntable rate (radians per second)
+    pub turntable_speed: f32,
+    /// Seconds the fly-through takes from one saved view to the next
+    pub view_seconds: f32,
+    /// Seconds played of the fly-through
+    pub clock: f32,
+}
+
+impl Default for CameraAnimation {
+    fn default() -> Self {
+        Self {
+            mode: CameraAnimationMode::default(),
+            playing: false,
+            turntable_speed: 0.5,
+            view_seconds: 3.0,
+            clock: 0.0,
+        }
+    }
+}
+
+impl CameraAnimation {
+    /// Whether playback can start in the current mode
+    pub fn can_play(&self, views: &SavedViews) -> bool {
+        self.mode == CameraAnimationMode::Turntable || !views.is_empty()
+    }
+}
+
+/// Camera placement and pivot `t` seconds into a fly-through of `views`,
+/// spending `seconds` on each leg and looping back to the first view
+pub fn fly_through_pose(views: &[SavedView], t: f32, seconds: f32) -> Option<(Transform, Vec3)> {
+    let first = views.first()?;
+    if views.len() == 1 || seconds <= 0.0 {
+        return Some((first.transform(), first.pivot()));
+    }
+    let legs = t.max(0.0) / seconds;
+    let leg = legs.floor() as usize % views.len();
+    let (from, to) = (&views[leg], &views[(leg + 1) % views.len()]);
+    let u = legs.fract();
+    let eased = u * u * (3.0 - 2.0 * u);
+    let (from_transform, to_transform) = (from.transform(), to.transform());
+    let transform = Transform {
+        translation: from_transform.translation.lerp(to_transform.translation, eased),
+        rotation: from_transform.rotation.slerp(to_transform.rotation, eased),
+        scale: from_transform.scale,
+    };
+    Some((transform, from.pivot().lerp(to.pivot(), eased)))
+}
+
+/// Request to play or pause the presentation camera
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ToggleCameraAnimation;
+
+/// Request to switch the presentation camera mode
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetCameraAnimationMode(pub CameraAnimationMode);
+
+/// The play_animation binding (Space) plays or pauses, animation_mode (Shift+Space) switches mode
+pub fn camera_animation_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    animation: Res<CameraAnimation>,
+    mut toggles: EventWriter<ToggleCameraAnimation>,
+    mut modes: EventWriter<SetCameraAnimationMode>,
+) {
+    if bindings.just_pressed("animation_mode", &keys) {
+        modes.write(SetCameraAnimationMode(animation.mode.next()));
+    } else if bindings.just_pressed("play_animation", &keys) {
+        toggles.write(ToggleCameraAnimation);
+    }
+}
+
+/// Switch modes and start or stop playback
+pub fn apply_camera_animation_requests(
+    mut toggles: EventReader<ToggleCameraAnimation>,
+    mut modes: EventReader<SetCameraAnimationMode>,
+    views: Res<SavedViews>,
+    mut animation: ResMut<CameraAnimation>,
+    mut usage: Option<ResMut<UsageStats>>,
+) {
+    let mut record = |command: &str, start: Instant| {
+        if let Some(usage) = usage.as_mut() {
+            usage.record(command, start.elapsed());
+        }
+    };
+    for SetCameraAnimationMode(mode) in modes.read() {
+        let start = Instant::now();
+        journal(format!("camera animation mode {}", mode.label()));
+        animation.mode = *mode;
+        animation.clock = 0.0;
+        record("camera_animation_mode", start);
+    }
+    for _ in toggles.read() {
+        let start = Instant::now();
+        if !animation.playing && !animation.can_play(&views) {
+            warn!("Save at least one view (Insert) before playing a fly-through");
+            continue;
+        }
+        animation.playing = !animation.playing;
+        journal(format!("camera animation {}", if animation.playing { "play" } else { "pause" }));
+        record("camera_animation", start);
+    }
+}
+
+/// Move the camera while the presentation is playing
+pub fn play_camera_animation(
+    time: Res<Time>,
+    views: Res<SavedViews>,
+    mut animation: ResMut<CameraAnimation>,
+    mut cameras: Query<(&mut Transform, &mut CustomCameraController)>,
+) {
+    if !animation.playing {
+        return;
+    }
+    let dt = time.delta_secs();
+    match animation.mode {
+        CameraAnimationMode::Turntable => {
+            let angle = animation.turntable_speed * dt;
+            for (mut transform, controller) in cameras.iter_mut() {
+                transform.rotate_around(controller.pivot, Quat::from_rotation_y(angle));
+            }
+        }
+        CameraAnimationMode::FlyThrough => {
+            animation.clock += dt;
+            let Some((pose, pivot)) = fly_through_pose(&views.views, animation.clock, animation.view_seconds) else {
+                animation.playing = false;
+                return;
+            };
+            for (mut transform, mut controller) in cameras.iter_mut() {
+                *transform = pose;
+                controller.pivot = pivot;
+            }
+        }
+    }
+}
+
+/// Camera panel button playing or pausing the presentation
+#[derive(Component, Debug)]
+pub struct CameraAnimationButton;
+
+/// Label of the play/pause button
+#[derive(Component, Debug)]
+pub struct CameraAnimationLabel;
+
+/// The play/pause button toggles playback and shows what it will do
+pub fn camera_animation_button_system(
+    pressed: Query<&Interaction, (Changed<Interaction>, With<CameraAnimationButton>)>,
+    animation: Res<CameraAnimation>,
+    mut labels: Query<&mut Text, With<CameraAnimationLabel>>,
+    mut toggles: EventWriter<ToggleCameraAnimation>,
+) {
+    if pressed.iter().any(|i| *i == Interaction::Pressed) {
+        toggles.write(ToggleCameraAnimation);
+    }
+    let label = format!("{} {}", if animation.playing { "Pause" } else { "Play" }, animation.mode.label());
+    for mut text in labels.iter_mut() {
+        if text.0 != label {
+            text.0 = label.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view(x: f32) -> SavedView {
+        SavedView::new(&format!("{}", x), &Transform::from_xyz(x, 0.0, 10.0), Vec3::new(x, 0.0, 0.0))
+    }
+
+    #[test]
+    fn test_fly_through_pose_loops_over_views() {
+        let views = vec![view(0.0), view(10.0), view(20.0)];
+        assert!(fly_through_pose(&[], 1.0, 2.0).is_none());
+        assert_eq!(fly_through_pose(&views, 0.0, 2.0).unwrap().0.translation.x, 0.0);
+        let (mid, pivot) = fly_through_pose(&views, 3.0, 2.0).unwrap();
+        assert!((mid.translation.x - 15.0).abs() < 1e-4 && (pivot.x - 15.0).abs() < 1e-4);
+        // The last leg heads back to the first view
+        let (back, _) = fly_through_pose(&views, 5.0, 2.0).unwrap();
+        assert!((back.translation.x - 10.0).abs() < 1e-4);
+        assert!((fly_through_pose(&views, 6.0, 2.0).unwrap().0.translation.x).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_play_needs_views_and_turntable_orbits() {
+        let mut app = App::new();
+        app.init_resource::<CameraAnimation>()
+            .init_resource::<SavedViews>()
+            .init_resource::<Time>()
+            .add_event::<ToggleCameraAnimation>()
+            .add_event::<SetCameraAnimationMode>()
+            .add_systems(Update, (apply_camera_animation_requests, play_camera_animation).chain());
+        let camera = app.world_mut().spawn((Transform::from_xyz(0.0, 0.0, 10.0), CustomCameraController::default())).id();
+
+        // A fly-through needs saved views
+        app.world_mut().send_event(SetCameraAnimationMode(CameraAnimationMode::FlyThrough));
+        app.world_mut().send_event(ToggleCameraAnimation);
+        app.update();
+        assert!(!app.world().resource::<CameraAnimation>().playing);
+        let saved = SavedView::new("Front", &Transform::from_xyz(0.0, 0.0, 10.0), Vec3::ZERO);
+        app.world_mut().resource_mut::<SavedViews>().save(saved).unwrap();
+        app.world_mut().send_event(ToggleCameraAnimation);
+        app.update();
+        assert!(app.world().resource::<CameraAnimation>().playing);
+        app.world_mut().send_event(ToggleCameraAnimation);
+
+        app.world_mut().send_event(SetCameraAnimationMode(CameraAnimationMode::Turntable));
+        app.world_mut().send_event(ToggleCameraAnimation);
+        app.world_mut().resource_mut::<Time>().advance_by(std::time::Duration::from_secs(1));
+        app.update();
+        let moved = app.world().get::<Transform>(camera).unwrap().translation;
+        let angle = app.world().resource::<CameraAnimation>().turntable_speed;
+        assert!((moved - Vec3::new(10.0 * angle.sin(), 0.0, 10.0 * angle.cos())).length() < 1e-4);
+    }
+}
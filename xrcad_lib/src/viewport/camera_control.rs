@@ -1,13 +1,126 @@
-// Moved from xrcad_app/src/camera_control.rs
-use bevy::{input::mouse::{MouseMotion, MouseWheel}, prelude::*};
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
 
+//! Module: viewport::camera_control
+//!
+//! Camera controller driven by the pan, orbit and zoom axes of `ActionState`:
+//! orbit (LMB, one finger or the left stick) about an explicit pivot, pan (MMB,
+//! Shift+LMB, two fingers or the left stick with the pan modifier) and smooth
+//! zoom towards the cursor (scroll, pinch or the right stick). When an orbit starts
+//! the pivot moves to the point under the cursor or the centre of the selection,
+//! depending on the pivot mode; the view elevation is clamped so the camera
+//! never flips over the poles. The controller also owns the projection mode:
+//! in orthographic views zooming changes the projection scale instead of
+//! moving the camera. Numpad 5 or the camera panel button toggles it. None
+//! of this applies to cameras whose `ViewRig` is XR: the headset drives those.
+
+use bevy::platform::time::Instant;
+use bevy::render::camera::ScalingMode;
+use bevy::prelude::*;
+
+use crate::input::actions::{ActionState, InputAction};
+use crate::input::keyboard::KeyBindings;
+use crate::interaction::picking::PickState;
+use crate::interaction::selection::Selection;
+use crate::interaction::transform_gizmo::TransformGizmo;
+use crate::model::brep_model::{na_vec3_to_bevy, BrepModel};
+use crate::telemetry::crash::journal;
+use crate::telemetry::usage::UsageStats;
+use crate::viewport::camera::ViewRig;
+use crate::viewport::framing::selection_bounds;
+
+/// Share of the distance to the pivot one scroll notch zooms by
+const ZOOM_STEP: f32 = 0.15;
+/// Orbit turn per pixel of orbit motion (radians)
+pub const ORBIT_RADIANS_PER_PIXEL: f32 = 0.01;
+/// Pan per pixel of pan motion (world units)
+const PAN_PER_PIXEL: f32 = 0.5;
+/// How quickly pending zoom is applied (per second)
+const ZOOM_RATE: f32 = 12.0;
+/// Zoom never moves the camera more than this share of the way to the pivot in one step
+const MAX_ZOOM_FRACTION: f32 = 0.9;
+/// Orthographic views see this far in front of and behind the camera
+const ORTHO_DEPTH: f32 = 100_000.0;
+/// Smallest orthographic view height (model units)
+const MIN_ORTHO_HEIGHT: f32 = 1e-3;
+
+/// Where an orbit pivots
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PivotMode {
+    /// The model point under the cursor, else the previous pivot
+    #[default]
+    Cursor,
+    /// The centre of the selection, else the previous pivot
+    Selection,
+    /// Keep the current pivot
+    Fixed,
+}
+
+impl PivotMode {
+    pub const ALL: [PivotMode; 3] = [PivotMode::Cursor, PivotMode::Selection, PivotMode::Fixed];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PivotMode::Cursor => "Cursor",
+            PivotMode::Selection => "Selection",
+            PivotMode::Fixed => "Fixed",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|m| *m == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+}
+
+/// How the camera projects the model
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProjectionMode {
+    #[default]
+    Perspective,
+    Orthographic,
+}
+
+impl ProjectionMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProjectionMode::Perspective => "Perspective",
+            ProjectionMode::Orthographic => "Orthographic",
+        }
+    }
+
+    pub fn toggled(self) -> Self {
+        match self {
+            ProjectionMode::Perspective => ProjectionMode::Orthographic,
+            ProjectionMode::Orthographic => ProjectionMode::Perspective,
+        }
+    }
+
+    /// Mode a camera projection is in
+    pub fn of(projection: &Projection) -> Self {
+        match projection {
+            Projection::Orthographic(_) => ProjectionMode::Orthographic,
+            _ => ProjectionMode::Perspective,
+        }
+    }
+}
+
+/// Desktop camera controls; cameras with an XR rig are left to the headset
 #[derive(Component)]
+#[require(ViewRig)]
 pub struct CustomCameraController {
     pub pan_sensitivity: f32,
     pub rotate_sensitivity: f32,
     pub zoom_sensitivity: f32,
-    pub is_xr: bool,
-    pub is_stereo: bool,
+    /// Point the camera orbits about
+    pub pivot: Vec3,
+    pub pivot_mode: PivotMode,
+    /// Lowest and highest view elevation (radians, negative looks down)
+    pub min_elevation: f32,
+    pub max_elevation: f32,
+    /// Zoom still to be applied, as a share of the distance to the pivot
+    pub zoom_pending: f32,
+    pub projection: ProjectionMode,
 }
 
 impl Default for CustomCameraController {
@@ -16,66 +129,254 @@ impl Default for CustomCameraController {
             pan_sensitivity: 1.0,
             rotate_sensitivity: 1.0,
             zoom_sensitivity: 1.0,
-            is_xr: false,
-            is_stereo: false,
+            pivot: Vec3::ZERO,
+            pivot_mode: PivotMode::default(),
+            min_elevation: -89f32.to_radians(),
+            max_elevation: 89f32.to_radians(),
+            zoom_pending: 0.0,
+            projection: ProjectionMode::default(),
+        }
+    }
+}
+
+/// Camera placement after turning `yaw` about the world up axis and `pitch`
+/// about the camera's right axis, both around `pivot`, with the resulting view
+/// elevation kept within `min_elevation..=max_elevation`
+pub fn orbit_about(transform: &Transform, pivot: Vec3, yaw: f32, pitch: f32, min_elevation: f32, max_elevation: f32) -> Transform {
+    let elevation = transform.forward().y.clamp(-1.0, 1.0).asin();
+    let pitch = (elevation + pitch).clamp(min_elevation, max_elevation) - elevation;
+    let rotation = Quat::from_rotation_y(yaw) * Quat::from_axis_angle(transform.right().as_vec3(), pitch);
+    let mut orbited = *transform;
+    orbited.rotate_around(pivot, rotation);
+    orbited
+}
+
+/// Switch a camera between projections, keeping the pivot's plane the same size
+/// on screen: an orthographic view is as tall as the perspective view at the
+/// pivot's depth, and going back to perspective moves the camera to that depth
+pub fn convert_projection(transform: &mut Transform, projection: &mut Projection, pivot: Vec3, mode: ProjectionMode) {
+    let forward = transform.forward().as_vec3();
+    let depth = (pivot - transform.translation).dot(forward).max(1.0);
+    match (mode, &*projection) {
+        (ProjectionMode::Orthographic, Projection::Perspective(p)) => {
+            let height = 2.0 * depth * (p.fov / 2.0).tan();
+            *projection = Projection::Orthographic(OrthographicProjection {
+                scaling_mode: ScalingMode::FixedVertical { viewport_height: 1.0 },
+                scale: height,
+                near: -ORTHO_DEPTH,
+                far: ORTHO_DEPTH,
+                ..OrthographicProjection::default_3d()
+            });
+        }
+        (ProjectionMode::Perspective, Projection::Orthographic(o)) => {
+            let perspective = PerspectiveProjection::default();
+            let distance = o.scale / (2.0 * (perspective.fov / 2.0).tan());
+            transform.translation += forward * (depth - distance);
+            *projection = Projection::Perspective(perspective);
         }
+        _ => {}
+    }
+}
+
+/// Request to switch between perspective and orthographic projection
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ToggleProjection;
+
+/// The toggle_projection binding (Numpad 5) toggles the projection
+pub fn projection_keys(keys: Res<ButtonInput<KeyCode>>, bindings: Res<KeyBindings>, mut toggles: EventWriter<ToggleProjection>) {
+    if bindings.just_pressed("toggle_projection", &keys) {
+        toggles.write(ToggleProjection);
     }
 }
 
+/// Flip the projection mode of the camera controllers
+pub fn apply_projection_requests(
+    mut toggles: EventReader<ToggleProjection>,
+    mut controllers: Query<&mut CustomCameraController>,
+    mut usage: Option<ResMut<UsageStats>>,
+) {
+    for _ in toggles.read() {
+        let start = Instant::now();
+        for mut controller in controllers.iter_mut() {
+            controller.projection = controller.projection.toggled();
+            journal(format!("projection {}", controller.projection.label()));
+        }
+        if let Some(usage) = usage.as_mut() {
+            usage.record("projection", start.elapsed());
+        }
+    }
+}
+
+/// Bring each camera's projection in line with its controller's mode
+pub fn sync_camera_projection(mut cameras: Query<(&mut Transform, &mut Projection, &CustomCameraController, &ViewRig)>) {
+    for (mut transform, mut projection, controller, rig) in cameras.iter_mut() {
+        // Headsets project each eye themselves
+        if !rig.is_xr() && ProjectionMode::of(&projection) != controller.projection {
+            convert_projection(&mut transform, &mut projection, controller.pivot, controller.projection);
+        }
+    }
+}
+
+/// Camera panel button toggling the projection
+#[derive(Component, Debug)]
+pub struct ProjectionButton;
+
+/// Label of the projection button
+#[derive(Component, Debug)]
+pub struct ProjectionLabel;
+
+/// The projection button toggles the projection and shows the current mode
+pub fn projection_button_system(
+    pressed: Query<&Interaction, (Changed<Interaction>, With<ProjectionButton>)>,
+    controllers: Query<&CustomCameraController>,
+    mut labels: Query<&mut Text, With<ProjectionLabel>>,
+    mut toggles: EventWriter<ToggleProjection>,
+) {
+    if pressed.iter().any(|i| *i == Interaction::Pressed) {
+        toggles.write(ToggleProjection);
+    }
+    let Some(controller) = controllers.iter().next() else { return };
+    for mut text in labels.iter_mut() {
+        if text.0 != controller.projection.label() {
+            text.0 = controller.projection.label().to_string();
+        }
+    }
+}
+
+/// Pan, orbit and zoom the desktop cameras by the action axes
 pub fn camera_control_system(
-    mut query: Query<(&mut Transform, &mut CustomCameraController, &Camera, &GlobalTransform)>,
-    mut mouse_motion_events: EventReader<MouseMotion>,
-    mouse_button: Res<ButtonInput<MouseButton>>,
-    keys: Res<ButtonInput<KeyCode>>,
-    mut scroll_evr: EventReader<MouseWheel>,
+    mut query: Query<(&mut Transform, &mut CustomCameraController, &Camera, &GlobalTransform, Option<&mut Projection>, &ViewRig)>,
+    actions: Res<ActionState>,
     windows: Query<&Window>,
+    (time, gizmo): (Res<Time>, Option<Res<TransformGizmo>>),
+    (pick, selection, model): (Option<Res<PickState>>, Option<Res<Selection>>, Option<Res<BrepModel>>),
 ) {
-    let window = match windows.single() {
-        Ok(w) => w,
-        Err(_) => return,
-    };
-    let mouse_pos = window.cursor_position();
-    let mut delta = Vec2::ZERO;
-    for ev in mouse_motion_events.read() {
-        delta += ev.delta;
-    }
-    for (mut transform, controller, camera, cam_transform) in query.iter_mut() {
-        // Pan (MMB or Shift+LMB)
-        if mouse_button.pressed(MouseButton::Middle)
-            || (mouse_button.pressed(MouseButton::Left) && keys.pressed(KeyCode::ShiftLeft))
-        {
+    let mouse_pos = windows.single().ok().and_then(|w| w.cursor_position());
+    let (pan, orbit) = (actions.axis(InputAction::Pan), actions.axis(InputAction::Orbit));
+    let zoom = actions.axis(InputAction::Zoom).y;
+    let gizmo_dragging = gizmo.as_ref().is_some_and(|g| g.drag.is_some());
+    for (mut transform, mut controller, camera, cam_transform, mut projection, rig) in query.iter_mut() {
+        if rig.is_xr() {
+            continue;
+        }
+        // Pan; the pivot moves with the view
+        if pan != Vec2::ZERO {
             let right = transform.rotation * Vec3::X;
             let up = transform.rotation * Vec3::Y;
-            transform.translation -= right * delta.x * 0.5 * controller.pan_sensitivity;
-            transform.translation += up * delta.y * 0.5 * controller.pan_sensitivity;
-        }
-        // Orbit (LMB)
-        else if mouse_button.pressed(MouseButton::Left) {
-            let yaw = -delta.x * 0.01 * controller.rotate_sensitivity;
-            let pitch = -delta.y * 0.01 * controller.rotate_sensitivity;
-            transform.rotate_y(yaw);
-            transform.rotate_local_x(pitch);
-        }
-        // Zoom (scroll)
-        for ev in scroll_evr.read() {
-            let zoom_dir = if let Some(mouse_pos) = mouse_pos {
-                if let Ok(ray) = camera.viewport_to_world(cam_transform, mouse_pos) {
-                    ray.direction
-                } else {
-                    transform.forward()
+            let shift = (-right * pan.x + up * pan.y) * PAN_PER_PIXEL * controller.pan_sensitivity;
+            transform.translation += shift;
+            controller.pivot += shift;
+        }
+        // Orbit about the pivot, unless a transform gizmo handle is being dragged
+        if actions.pressed(InputAction::Orbit) && !gizmo_dragging {
+            if actions.just_pressed(InputAction::Orbit) {
+                let picked = match controller.pivot_mode {
+                    PivotMode::Cursor => pick.as_ref().and_then(|p| p.hover.as_ref()).map(|h| na_vec3_to_bevy(&h.point)),
+                    PivotMode::Selection => model
+                        .as_ref()
+                        .zip(selection.as_ref())
+                        .and_then(|(m, s)| selection_bounds(m, s))
+                        .map(|(min, max)| na_vec3_to_bevy(&((min + max) / 2.0))),
+                    PivotMode::Fixed => None,
+                };
+                if let Some(pivot) = picked {
+                    controller.pivot = pivot;
                 }
+            }
+            let yaw = -orbit.x * ORBIT_RADIANS_PER_PIXEL * controller.rotate_sensitivity;
+            let pitch = -orbit.y * ORBIT_RADIANS_PER_PIXEL * controller.rotate_sensitivity;
+            *transform = orbit_about(&transform, controller.pivot, yaw, pitch, controller.min_elevation, controller.max_elevation);
+        }
+        // Zoom towards the cursor, eased over a few frames
+        controller.zoom_pending += zoom * controller.zoom_sensitivity * ZOOM_STEP;
+        if controller.zoom_pending != 0.0 {
+            let step = if controller.zoom_pending.abs() < 1e-4 {
+                controller.zoom_pending
             } else {
-                transform.forward()
+                controller.zoom_pending * (1.0 - (-ZOOM_RATE * time.delta_secs()).exp())
             };
-            transform.translation += zoom_dir * ev.y * controller.zoom_sensitivity * 5.0;
-        }
-        // XR stub: if is_xr, you could override transform with XR pose here
-        if controller.is_xr {
-            // XR device pose integration stub
+            controller.zoom_pending -= step;
+            let step = step.min(MAX_ZOOM_FRACTION);
+            let ray = mouse_pos.and_then(|p| camera.viewport_to_world(cam_transform, p).ok());
+            if let Some(Projection::Orthographic(ortho)) = projection.as_deref_mut() {
+                // Orthographic zoom scales the view and slides it towards the cursor
+                ortho.scale = (ortho.scale * (1.0 - step)).max(MIN_ORTHO_HEIGHT);
+                if let Some(ray) = ray {
+                    let (offset, forward) = (ray.origin - transform.translation, transform.forward().as_vec3());
+                    transform.translation += (offset - forward * offset.dot(forward)) * step;
+                }
+            } else {
+                let zoom_dir = ray.map_or(transform.forward(), |ray| ray.direction);
+                let distance = (controller.pivot - transform.translation).length().max(1.0);
+                transform.translation += zoom_dir * step * distance;
+            }
         }
-        // Stereo stub: if is_stereo, you could adjust camera projection here
-        if controller.is_stereo {
-            // Stereo rendering integration stub
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::actions::{begin_actions, binding_actions, finish_actions};
+    use crate::input::gamepad::GamepadSettings;
+    use std::f32::consts::FRAC_PI_2;
+
+    #[test]
+    fn test_projection_round_trip() {
+        let mut transform = Transform::from_xyz(0.0, 0.0, 100.0).looking_at(Vec3::ZERO, Vec3::Y);
+        let mut projection = Projection::Perspective(PerspectiveProjection::default());
+        convert_projection(&mut transform, &mut projection, Vec3::ZERO, ProjectionMode::Orthographic);
+        let Projection::Orthographic(ortho) = &projection else { panic!("expected an orthographic projection") };
+        let fov = PerspectiveProjection::default().fov;
+        assert!((ortho.scale - 200.0 * (fov / 2.0).tan()).abs() < 1e-3);
+        assert_eq!(ProjectionMode::of(&projection), ProjectionMode::Orthographic);
+
+        // Zooming in ortho halves the view height; back in perspective the camera is twice as close
+        if let Projection::Orthographic(ortho) = &mut projection {
+            ortho.scale /= 2.0;
         }
+        convert_projection(&mut transform, &mut projection, Vec3::ZERO, ProjectionMode::Perspective);
+        assert!(matches!(projection, Projection::Perspective(_)));
+        assert!((transform.translation - Vec3::new(0.0, 0.0, 50.0)).length() < 1e-3);
+    }
+
+    #[test]
+    fn test_orbit_about_pivot() {
+        let pivot = Vec3::new(0.0, 0.0, -5.0);
+        let camera = Transform::from_xyz(0.0, 0.0, 5.0).looking_at(pivot, Vec3::Y);
+        let orbited = orbit_about(&camera, pivot, FRAC_PI_2, 0.0, -1.0, 1.0);
+        assert!((orbited.translation - Vec3::new(10.0, 0.0, -5.0)).length() < 1e-4);
+        assert!((orbited.forward().as_vec3() - Vec3::NEG_X).length() < 1e-5);
+
+        // Pitching far past the limit stops at the maximum elevation, still facing the pivot
+        let tilted = orbit_about(&camera, pivot, 0.0, 3.0, -1.0, 1.0);
+        assert!((tilted.forward().y.asin() - 1.0).abs() < 1e-4);
+        assert!(((tilted.translation - pivot).length() - 10.0).abs() < 1e-3);
+        assert!((tilted.forward().as_vec3() - (pivot - tilted.translation).normalize()).length() < 1e-4);
+    }
+
+    #[test]
+    fn test_sticks_drive_the_camera_through_actions() {
+        let mut app = App::new();
+        app.init_resource::<ActionState>()
+            .init_resource::<KeyBindings>()
+            .init_resource::<GamepadSettings>()
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<Time>()
+            .add_systems(Update, (begin_actions, binding_actions, finish_actions, camera_control_system).chain());
+        let start = Transform::from_xyz(0.0, 0.0, 100.0).looking_at(Vec3::ZERO, Vec3::Y);
+        let camera = app.world_mut().spawn((start, CustomCameraController::default(), Camera::default(), GlobalTransform::from(start))).id();
+        let mut gamepad = Gamepad::default();
+        gamepad.analog_mut().set(GamepadAxis::LeftStickX, 1.0);
+        gamepad.analog_mut().set(GamepadAxis::RightStickY, 1.0);
+        app.world_mut().spawn(gamepad);
+        app.world_mut().resource_mut::<Time>().advance_by(std::time::Duration::from_millis(500));
+        app.update();
+
+        // Half a second at full deflection orbits 45 degrees about the pivot and zooms 5 notches in
+        let transform = app.world().get::<Transform>(camera).unwrap();
+        assert!((transform.translation.x.atan2(transform.translation.z).abs() - 45f32.to_radians()).abs() < 1e-4);
+        let eased = 5.0 * ZOOM_STEP * (1.0 - (-ZOOM_RATE * 0.5f32).exp());
+        assert!((transform.translation.length() - 100.0 * (1.0 - eased)).abs() < 1e-2, "{}", transform.translation);
     }
 }
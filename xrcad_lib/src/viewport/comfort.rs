@@ -0,0 +1,284 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: viewport::comfort
+//!
+//! Comfort options for moving through the model in XR: a vignette that narrows
+//! the view during artificial locomotion, snap turning in fixed steps, and
+//! keeping the camera from passing through faces of the model. Thumbstick
+//! walking and teleporting are in `viewport::locomotion`. All of it only
+//! applies to cameras in XR mode and is configured through `ComfortSettings`.
+
+use bevy::prelude::*;
+use nalgebra::Point3;
+
+use crate::input::keyboard::KeyBindings;
+use crate::model::brep::placement::raycast_faces;
+use crate::model::brep_model::{bevy_vec3_to_na, na_vec3_to_bevy, BrepModel};
+use crate::viewport::camera::ViewRig;
+
+/// Largest share of the screen each vignette edge covers at full intensity (percent)
+const VIGNETTE_MAX_PERCENT: f32 = 30.0;
+/// Collision passes per frame: the first stops at a face, later ones slide along it
+const COLLISION_PASSES: usize = 3;
+/// Moves closer to parallel with a face than this (cosine) never collide with it
+const MIN_APPROACH: f32 = 1e-3;
+
+/// User-configurable XR comfort options
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct ComfortSettings {
+    pub vignette: bool,
+    /// Vignette intensity at or above `vignette_full_speed`, 0..=1
+    pub vignette_strength: f32,
+    /// Locomotion speed (model units per second) at which the vignette is fully on
+    pub vignette_full_speed: f32,
+    /// How fast the vignette fades in and out (intensity per second)
+    pub vignette_fade: f32,
+    pub snap_turn: bool,
+    pub snap_turn_degrees: f32,
+    /// Turning speed with the thumbstick when snap turning is off (degrees per second)
+    pub smooth_turn_speed: f32,
+    /// Walk with the left thumbstick
+    pub smooth_locomotion: bool,
+    /// Thumbstick walking speed (physical millimetres per second)
+    pub locomotion_speed: f32,
+    /// Teleport along an arc aimed with the right thumbstick
+    pub teleport: bool,
+    /// Thumbstick deflection ignored as drift, 0..1
+    pub thumbstick_deadzone: f32,
+    /// Keep the camera from passing through faces of the model
+    pub collision: bool,
+    /// Distance kept between the camera and any face (model units)
+    pub collision_radius: f32,
+}
+
+impl Default for ComfortSettings {
+    fn default() -> Self {
+        Self {
+            vignette: true,
+            vignette_strength: 0.7,
+            vignette_full_speed: 1000.0,
+            vignette_fade: 4.0,
+            snap_turn: true,
+            snap_turn_degrees: 30.0,
+            smooth_turn_speed: 90.0,
+            smooth_locomotion: true,
+            locomotion_speed: 1400.0,
+            teleport: true,
+            thumbstick_deadzone: 0.15,
+            collision: true,
+            collision_radius: 50.0,
+        }
+    }
+}
+
+impl ComfortSettings {
+    /// Vignette intensity wanted while moving at `speed`
+    pub fn vignette_target(&self, speed: f32) -> f32 {
+        if !self.vignette || self.vignette_full_speed <= 0.0 {
+            return 0.0;
+        }
+        self.vignette_strength.clamp(0.0, 1.0) * (speed / self.vignette_full_speed).clamp(0.0, 1.0)
+    }
+}
+
+/// Camera movement tracked between frames
+#[derive(Resource, Debug, Default, Clone, PartialEq)]
+pub struct LocomotionState {
+    pub last_position: Option<Vec3>,
+    /// Current vignette intensity, 0..=1
+    pub vignette: f32,
+}
+
+/// Request to turn the XR camera by one snap step
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnapTurn {
+    pub clockwise: bool,
+}
+
+/// Where a camera moving from `from` to `to` ends up without coming closer than
+/// `radius` to any face: it stops short of the first face hit and slides along it
+pub fn resolve_camera_collision(model: &BrepModel, from: Vec3, to: Vec3, radius: f32) -> Vec3 {
+    let mut position = from;
+    let mut remaining = to - from;
+    for _ in 0..COLLISION_PASSES {
+        let length = remaining.length();
+        if length <= f32::EPSILON {
+            break;
+        }
+        let direction = remaining / length;
+        let origin = Point3::from(bevy_vec3_to_na(&position));
+        // Distance along the move at which the camera would be `radius` from the hit face
+        let stop = raycast_faces(model, &origin, &bevy_vec3_to_na(&remaining)).and_then(|hit| {
+            let normal = na_vec3_to_bevy(&hit.normal);
+            let approach = -direction.dot(normal);
+            (approach > MIN_APPROACH).then(|| (hit.distance as f32 - radius / approach, normal))
+        });
+        let Some((stop, normal)) = stop.filter(|(stop, _)| *stop < length) else {
+            position += remaining;
+            break;
+        };
+        let travel = stop.max(0.0);
+        position += direction * travel;
+        // Keep only the part of the rest of the move that runs along the face
+        let rest = direction * (length - travel);
+        remaining = rest - normal * rest.dot(normal);
+    }
+    position
+}
+
+/// The snap turn keys (left / right arrow by default) snap-turn the camera
+pub fn snap_turn_keys(keys: Res<ButtonInput<KeyCode>>, bindings: Res<KeyBindings>, mut turns: EventWriter<SnapTurn>) {
+    if bindings.just_pressed("snap_turn_left", &keys) {
+        turns.write(SnapTurn { clockwise: false });
+    }
+    if bindings.just_pressed("snap_turn_right", &keys) {
+        turns.write(SnapTurn { clockwise: true });
+    }
+}
+
+/// Rotate XR cameras about the vertical axis through their position
+pub fn apply_snap_turn(
+    mut events: EventReader<SnapTurn>,
+    settings: Res<ComfortSettings>,
+    mut cameras: Query<(&mut Transform, &ViewRig)>,
+) {
+    for ev in events.read() {
+        if !settings.snap_turn {
+            continue;
+        }
+        let angle = settings.snap_turn_degrees.to_radians() * if ev.clockwise { -1.0 } else { 1.0 };
+        for (mut transform, rig) in cameras.iter_mut() {
+            if rig.is_xr() {
+                transform.rotate_y(angle);
+            }
+        }
+    }
+}
+
+/// After camera movement: undo moves into faces and drive the vignette from speed
+pub fn comfort_locomotion_system(
+    time: Res<Time>,
+    settings: Res<ComfortSettings>,
+    model: Option<Res<BrepModel>>,
+    mut state: ResMut<LocomotionState>,
+    mut cameras: Query<(&mut Transform, &ViewRig)>,
+) {
+    let Some((mut transform, _)) = cameras.iter_mut().find(|(_, rig)| rig.is_xr()) else {
+        state.last_position = None;
+        state.vignette = 0.0;
+        return;
+    };
+    let dt = time.delta_secs();
+    let mut speed = 0.0;
+    if let Some(last) = state.last_position {
+        if let Some(model) = model.as_deref().filter(|_| settings.collision) {
+            transform.translation = resolve_camera_collision(model, last, transform.translation, settings.collision_radius);
+        }
+        if dt > 0.0 {
+            speed = (transform.translation - last).length() / dt;
+        }
+    }
+    state.last_position = Some(transform.translation);
+    let target = settings.vignette_target(speed);
+    let step = settings.vignette_fade.max(0.0) * dt;
+    state.vignette += (target - state.vignette).clamp(-step, step);
+}
+
+/// Screen edge covered by one vignette panel
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VignetteEdge {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+/// Spawn the (initially invisible) vignette panels along the screen edges
+pub fn spawn_comfort_vignette(mut commands: Commands) {
+    for edge in [VignetteEdge::Top, VignetteEdge::Bottom, VignetteEdge::Left, VignetteEdge::Right] {
+        let mut node = Node { position_type: PositionType::Absolute, ..default() };
+        match edge {
+            VignetteEdge::Top | VignetteEdge::Bottom => node.width = Val::Percent(100.0),
+            VignetteEdge::Left | VignetteEdge::Right => node.height = Val::Percent(100.0),
+        }
+        match edge {
+            VignetteEdge::Top => node.top = Val::Px(0.0),
+            VignetteEdge::Bottom => node.bottom = Val::Px(0.0),
+            VignetteEdge::Left => node.left = Val::Px(0.0),
+            VignetteEdge::Right => node.right = Val::Px(0.0),
+        }
+        commands.spawn((node, BackgroundColor(Color::NONE), edge));
+    }
+}
+
+/// Size and fade the vignette panels to the current intensity
+pub fn update_comfort_vignette(
+    state: Res<LocomotionState>,
+    mut panels: Query<(&VignetteEdge, &mut Node, &mut BackgroundColor)>,
+) {
+    if !state.is_changed() {
+        return;
+    }
+    let size = Val::Percent(VIGNETTE_MAX_PERCENT * state.vignette);
+    for (edge, mut node, mut color) in panels.iter_mut() {
+        match edge {
+            VignetteEdge::Top | VignetteEdge::Bottom => node.height = size,
+            VignetteEdge::Left | VignetteEdge::Right => node.width = size,
+        }
+        color.0 = Color::BLACK.with_alpha(state.vignette);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::cube;
+    use std::time::Duration;
+
+    #[test]
+    fn test_collision_stops_and_slides() {
+        let model = cube(100.0);
+        // Straight at the +X face: stop one radius short of it
+        let p = resolve_camera_collision(&model, Vec3::new(200.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 0.0), 10.0);
+        assert!((p - Vec3::new(60.0, 0.0, 0.0)).length() < 1e-3);
+        // Diagonally into it: keep the sideways part of the move
+        let p = resolve_camera_collision(&model, Vec3::new(200.0, 0.0, 0.0), Vec3::new(0.0, 30.0, 0.0), 10.0);
+        assert!((p.x - 60.0).abs() < 1e-3 && (p.y - 30.0).abs() < 1e-3);
+        // Moving away is unaffected
+        let p = resolve_camera_collision(&model, Vec3::new(200.0, 0.0, 0.0), Vec3::new(300.0, 0.0, 0.0), 10.0);
+        assert_eq!(p, Vec3::new(300.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_vignette_and_snap_turn() {
+        let settings = ComfortSettings::default();
+        assert_eq!(settings.vignette_target(0.0), 0.0);
+        assert!((settings.vignette_target(500.0) - 0.35).abs() < 1e-6);
+        assert!((settings.vignette_target(5000.0) - 0.7).abs() < 1e-6);
+        assert_eq!(ComfortSettings { vignette: false, ..settings.clone() }.vignette_target(5000.0), 0.0);
+
+        let mut app = App::new();
+        app.insert_resource(settings)
+            .init_resource::<LocomotionState>()
+            .init_resource::<Time>()
+            .add_event::<SnapTurn>()
+            .add_systems(Update, (apply_snap_turn, comfort_locomotion_system).chain());
+        let camera = app
+            .world_mut()
+            .spawn((Transform::default(), ViewRig::HEADSET))
+            .id();
+        app.update();
+        app.world_mut().send_event(SnapTurn { clockwise: true });
+        app.world_mut().resource_mut::<Time>().advance_by(Duration::from_millis(100));
+        app.world_mut().get_mut::<Transform>(camera).unwrap().translation = Vec3::new(200.0, 0.0, 0.0);
+        app.update();
+
+        let transform = app.world().get::<Transform>(camera).unwrap();
+        let (yaw, _, _) = transform.rotation.to_euler(EulerRot::YXZ);
+        assert!((yaw + 30f32.to_radians()).abs() < 1e-5);
+        // 2000 units/s wants full vignette, but it fades in at 4/s
+        let state = app.world().resource::<LocomotionState>();
+        assert!((state.vignette - 0.4).abs() < 1e-5);
+    }
+}
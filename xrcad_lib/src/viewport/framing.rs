@@ -0,0 +1,301 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: viewport::framing
+//!
+//! Camera framing commands: fit the whole model or the selection into view
+//! along the current view direction, or jump to a named view (front, top,
+//! right, isometric) framing the model. The camera glides to its new placement
+//! over a short animation. F fits all, Shift+F fits the selection, numpad
+//! 1/3/7/0 pick the named views, and in XR a double tap fits all.
+
+use bevy::input::touch::Touches;
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+use nalgebra::Vector3;
+
+use crate::input::keyboard::KeyBindings;
+use crate::interaction::selection::{Selection, SelectionItem};
+use crate::model::brep_model::{na_vec3_to_bevy, BrepModel};
+use crate::telemetry::crash::journal;
+use crate::telemetry::usage::UsageStats;
+use crate::viewport::camera::ViewRig;
+use crate::viewport::camera_control::CustomCameraController;
+
+/// Field of view assumed for cameras without a perspective projection (radians)
+const DEFAULT_FOV: f32 = std::f32::consts::FRAC_PI_4;
+/// Longest gap between the taps of a double tap (seconds)
+const DOUBLE_TAP_SECONDS: f64 = 0.3;
+/// Furthest a finger may move during a tap (pixels)
+const TAP_SLOP: f32 = 20.0;
+
+/// Standard views, named from where the camera looks at the model (Y up)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NamedView {
+    Front,
+    Top,
+    Right,
+    Iso,
+}
+
+impl NamedView {
+    pub fn label(&self) -> &'static str {
+        match self {
+            NamedView::Front => "Front",
+            NamedView::Top => "Top",
+            NamedView::Right => "Right",
+            NamedView::Iso => "Isometric",
+        }
+    }
+
+    /// Direction the camera looks along
+    pub fn direction(&self) -> Vec3 {
+        match self {
+            NamedView::Front => Vec3::NEG_Z,
+            NamedView::Top => Vec3::NEG_Y,
+            NamedView::Right => Vec3::NEG_X,
+            NamedView::Iso => Vec3::new(-1.0, -1.0, -1.0).normalize(),
+        }
+    }
+
+    /// Screen-up direction of the view
+    pub fn up(&self) -> Vec3 {
+        match self {
+            NamedView::Top => Vec3::NEG_Z,
+            _ => Vec3::Y,
+        }
+    }
+}
+
+/// A camera move in progress
+#[derive(Debug, Clone, PartialEq)]
+pub struct FramingAnimation {
+    pub from: Transform,
+    pub to: Transform,
+    /// Seconds since the move started
+    pub elapsed: f32,
+}
+
+/// Framing settings and the current camera move
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct CameraFraming {
+    /// Seconds a framing move takes
+    pub duration: f32,
+    /// Extra room around the framed bounds (1 = touching the view edges)
+    pub margin: f32,
+    pub animation: Option<FramingAnimation>,
+}
+
+impl Default for CameraFraming {
+    fn default() -> Self {
+        Self { duration: 0.4, margin: 1.1, animation: None }
+    }
+}
+
+/// Axis-aligned bounds (min, max) of the selected elements' vertices
+pub fn selection_bounds(model: &BrepModel, selection: &Selection) -> Option<(Vector3<f64>, Vector3<f64>)> {
+    let vertices: Vec<usize> = selection
+        .items
+        .iter()
+        .flat_map(|item| match *item {
+            SelectionItem::Vertex(id) => vec![id],
+            SelectionItem::Edge(id) => model.edge(id).map_or(Vec::new(), |e| vec![e.vertices.0, e.vertices.1]),
+            SelectionItem::Face(id) => model.shell_vertex_ids(&[id]),
+            SelectionItem::Body(body) => model.body_faces(body).map_or(Vec::new(), |faces| model.shell_vertex_ids(&faces)),
+        })
+        .collect();
+    let mut positions = vertices.iter().filter_map(|v| model.vertex_position(*v));
+    let first = positions.next()?;
+    Some(positions.fold((first, first), |(lo, hi), p| (lo.inf(&p), hi.sup(&p))))
+}
+
+/// Camera placement looking along `direction` that fits the sphere around the
+/// bounds into a view with vertical field of view `fov`
+pub fn framing_transform(min: &Vector3<f64>, max: &Vector3<f64>, direction: Vec3, up: Vec3, fov: f32, margin: f32) -> Transform {
+    let center = na_vec3_to_bevy(&((min + max) / 2.0));
+    // A point-sized target still gets a little room around it
+    let radius = (((max - min).norm() / 2.0) as f32).max(1.0);
+    let distance = radius * margin / (fov / 2.0).sin();
+    let direction = direction.normalize_or(Vec3::NEG_Z);
+    Transform::from_translation(center - direction * distance).looking_to(direction, up)
+}
+
+/// Request to frame the whole model
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FitAll;
+
+/// Request to frame the selection
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FitSelection;
+
+/// Request to look at the model from a named view
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetNamedView(pub NamedView);
+
+/// The fit_all binding (F) fits all, fit_selection (Shift+F) the selection; the view_* bindings
+/// (numpad 1/3/7/0) pick front/right/top/iso
+pub fn framing_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut fit_all: EventWriter<FitAll>,
+    mut fit_selection: EventWriter<FitSelection>,
+    mut views: EventWriter<SetNamedView>,
+) {
+    if bindings.just_pressed("fit_all", &keys) {
+        fit_all.write(FitAll);
+    } else if bindings.just_pressed("fit_selection", &keys) {
+        fit_selection.write(FitSelection);
+    }
+    for (action, view) in [
+        ("view_front", NamedView::Front),
+        ("view_right", NamedView::Right),
+        ("view_top", NamedView::Top),
+        ("view_iso", NamedView::Iso),
+    ] {
+        if bindings.just_pressed(action, &keys) {
+            views.write(SetNamedView(view));
+        }
+    }
+}
+
+/// In XR, a quick double tap fits the whole model
+pub fn double_tap_fit(
+    touches: Option<Res<Touches>>,
+    time: Res<Time>,
+    cameras: Query<&ViewRig>,
+    mut last_tap: Local<Option<f64>>,
+    mut fit_all: EventWriter<FitAll>,
+) {
+    let Some(touches) = touches else { return };
+    if !cameras.iter().any(|rig| rig.is_xr()) {
+        return;
+    }
+    let now = time.elapsed_secs_f64();
+    for _ in touches.iter_just_released().filter(|t| t.distance().length() <= TAP_SLOP) {
+        match *last_tap {
+            Some(previous) if now - previous <= DOUBLE_TAP_SECONDS => {
+                fit_all.write(FitAll);
+                *last_tap = None;
+            }
+            _ => *last_tap = Some(now),
+        }
+    }
+}
+
+/// Start a camera move for framing requests
+pub fn apply_framing_requests(
+    (mut fit_all, mut fit_selection, mut views): (EventReader<FitAll>, EventReader<FitSelection>, EventReader<SetNamedView>),
+    model: Res<BrepModel>,
+    selection: Res<Selection>,
+    mut cameras: Query<(&Transform, Option<&mut Projection>, &mut CustomCameraController)>,
+    mut framing: ResMut<CameraFraming>,
+    mut usage: Option<ResMut<UsageStats>>,
+) {
+    let mut requests: Vec<(&'static str, Option<NamedView>)> = Vec::new();
+    requests.extend(fit_all.read().map(|_| ("fit_all", None)));
+    requests.extend(fit_selection.read().map(|_| ("fit_selection", None)));
+    requests.extend(views.read().map(|SetNamedView(view)| ("named_view", Some(*view))));
+    let Ok((transform, mut projection, mut controller)) = cameras.single_mut() else { return };
+    let fov = match projection.as_deref() {
+        Some(Projection::Perspective(p)) => p.fov,
+        _ => DEFAULT_FOV,
+    };
+    for (command, view) in requests {
+        let start = Instant::now();
+        journal(view.map_or(command.to_string(), |v| format!("{} {}", command, v.label())));
+        let bounds = if command == "fit_selection" { selection_bounds(&model, &selection) } else { model.bounds() };
+        let Some((min, max)) = bounds else {
+            info!("Nothing to frame");
+            continue;
+        };
+        let (direction, up) = view.map_or((transform.forward().as_vec3(), transform.up().as_vec3()), |v| (v.direction(), v.up()));
+        let to = framing_transform(&min, &max, direction, up, fov, framing.margin);
+        if let Some(Projection::Orthographic(ortho)) = projection.as_deref_mut() {
+            // Orthographic views fit the same sphere by their height
+            let center = na_vec3_to_bevy(&((min + max) / 2.0));
+            ortho.scale = 2.0 * to.translation.distance(center) * (fov / 2.0).sin();
+        }
+        framing.animation = Some(FramingAnimation { from: *transform, to, elapsed: 0.0 });
+        // Later orbits turn about the framed centre
+        controller.pivot = na_vec3_to_bevy(&((min + max) / 2.0));
+        controller.zoom_pending = 0.0;
+        if let Some(usage) = usage.as_mut() {
+            usage.record(command, start.elapsed());
+        }
+    }
+}
+
+/// Glide the camera towards the framed placement
+pub fn animate_camera_framing(
+    time: Res<Time>,
+    mut framing: ResMut<CameraFraming>,
+    mut cameras: Query<&mut Transform, With<CustomCameraController>>,
+) {
+    let duration = framing.duration;
+    let Some(animation) = framing.animation.as_mut() else { return };
+    animation.elapsed += time.delta_secs();
+    let t = if duration > 0.0 { (animation.elapsed / duration).clamp(0.0, 1.0) } else { 1.0 };
+    let eased = t * t * (3.0 - 2.0 * t);
+    let placed = Transform {
+        translation: animation.from.translation.lerp(animation.to.translation, eased),
+        rotation: animation.from.rotation.slerp(animation.to.rotation, eased),
+        scale: animation.from.scale,
+    };
+    for mut transform in cameras.iter_mut() {
+        *transform = placed;
+    }
+    if t >= 1.0 {
+        framing.animation = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::body::BodyId;
+    use crate::model::brep::primitives::cube;
+    use std::time::Duration;
+
+    #[test]
+    fn test_framing_transform_and_bounds() {
+        let model = cube(10.0);
+        let (min, max) = model.bounds().unwrap();
+        let fov = std::f32::consts::FRAC_PI_2;
+        let t = framing_transform(&min, &max, NamedView::Front.direction(), NamedView::Front.up(), fov, 1.0);
+        // The bounding sphere (radius 5√3) just fits a 90° view
+        let distance = 5.0 * 3f32.sqrt() / (fov / 2.0).sin();
+        assert!((t.translation - Vec3::new(0.0, 0.0, distance)).length() < 1e-3);
+        assert!((t.forward().as_vec3() - Vec3::NEG_Z).length() < 1e-5);
+
+        let mut selection = Selection::default();
+        assert!(selection_bounds(&model, &selection).is_none());
+        let top = model.find_vertex_at(&Vector3::new(5.0, 5.0, 5.0)).unwrap();
+        selection.add(SelectionItem::Vertex(top));
+        assert_eq!(selection_bounds(&model, &selection), Some((Vector3::new(5.0, 5.0, 5.0), Vector3::new(5.0, 5.0, 5.0))));
+        selection.add(SelectionItem::Body(BodyId(0)));
+        assert_eq!(selection_bounds(&model, &selection), Some((min, max)));
+    }
+
+    #[test]
+    fn test_named_view_animates_camera() {
+        let mut app = App::new();
+        app.insert_resource(cube(10.0))
+            .init_resource::<Selection>()
+            .init_resource::<CameraFraming>()
+            .init_resource::<Time>()
+            .add_event::<FitAll>()
+            .add_event::<FitSelection>()
+            .add_event::<SetNamedView>()
+            .add_systems(Update, (apply_framing_requests, animate_camera_framing).chain());
+        let controller = CustomCameraController { pivot: Vec3::splat(100.0), ..default() };
+        let camera = app.world_mut().spawn((Transform::from_xyz(0.0, 0.0, 500.0), controller)).id();
+        app.world_mut().send_event(SetNamedView(NamedView::Top));
+        app.world_mut().resource_mut::<Time>().advance_by(Duration::from_millis(500));
+        app.update();
+        let transform = *app.world().get::<Transform>(camera).unwrap();
+        assert!(transform.translation.x.abs() < 1e-3 && transform.translation.z.abs() < 1e-3 && transform.translation.y > 5.0);
+        assert!((transform.forward().as_vec3() - Vec3::NEG_Y).length() < 1e-4);
+        assert!(app.world().resource::<CameraFraming>().animation.is_none());
+        assert_eq!(app.world().get::<CustomCameraController>(camera).unwrap().pivot, Vec3::ZERO);
+    }
+}
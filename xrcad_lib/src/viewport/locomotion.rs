@@ -0,0 +1,222 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: viewport::locomotion
+//!
+//! Walking around the model in XR with the controller thumbsticks. The left
+//! stick walks in the direction the head faces, level with the floor. The
+//! right stick turns, in snap steps or smoothly as `ComfortSettings` says;
+//! pushing it forward aims a teleport arc from the right controller, and
+//! releasing it moves the user to where the arc meets the floor. The floor
+//! is the physical plane y = 0 of `XrViewScale`, so at room scale the user
+//! walks around a 1:1 model standing on it. Collision and the vignette of
+//! `viewport::comfort` apply to walking; teleports jump straight there.
+
+use bevy::prelude::*;
+
+use crate::interaction::xr_controller::{XrController, XrHand};
+use crate::telemetry::crash::journal;
+use crate::viewport::camera::ViewRig;
+use crate::viewport::comfort::{ComfortSettings, LocomotionState, SnapTurn};
+use crate::viewport::xr_scale::XrViewScale;
+
+/// Sideways deflection of the right stick that snap turns (0..1)
+const SNAP_TURN_THRESHOLD: f32 = 0.7;
+/// The stick must come back within this before the next snap turn
+const SNAP_TURN_RELEASE: f32 = 0.3;
+/// Forward deflection of the right stick that aims a teleport
+const TELEPORT_AIM_THRESHOLD: f32 = 0.6;
+/// Launch speed of the teleport arc (physical millimetres per second)
+const TELEPORT_SPEED: f32 = 6000.0;
+/// Gravity bending the teleport arc (physical millimetres per second squared)
+const GRAVITY: f32 = 9810.0;
+/// Time step and number of steps the arc is traced over (seconds)
+const ARC_STEP: f32 = 0.02;
+const ARC_STEPS: usize = 100;
+/// Ring drawn where the arc lands (physical millimetres)
+const LANDING_RADIUS: f32 = 250.0;
+const ARC_COLOR: Color = Color::srgb(0.3, 0.8, 1.0);
+
+/// Thumbstick locomotion between frames
+#[derive(Resource, Debug, Default, Clone, PartialEq)]
+pub struct XrLocomotion {
+    /// The right stick is still over to the side after a snap turn
+    turn_held: bool,
+    /// Teleport arc being aimed (model space), empty when not aiming
+    pub arc: Vec<Vec3>,
+    /// Where the arc meets the floor (physical space)
+    pub target: Option<Vec3>,
+}
+
+/// Points of a parabola thrown from `origin` along `direction` under gravity,
+/// up to where it comes down onto the floor, and that point (physical space)
+pub fn teleport_arc(origin: Vec3, direction: Vec3) -> (Vec<Vec3>, Option<Vec3>) {
+    let mut points = vec![origin];
+    if origin.y <= 0.0 {
+        return (points, None);
+    }
+    let velocity = direction.normalize_or_zero() * TELEPORT_SPEED;
+    let mut last = origin;
+    for step in 1..=ARC_STEPS {
+        let t = step as f32 * ARC_STEP;
+        let p = origin + velocity * t + Vec3::NEG_Y * (0.5 * GRAVITY * t * t);
+        if p.y <= 0.0 {
+            let landing = last.lerp(p, last.y / (last.y - p.y)).with_y(0.0);
+            points.push(landing);
+            return (points, Some(landing));
+        }
+        points.push(p);
+        last = p;
+    }
+    (points, None)
+}
+
+/// Stick deflection with the dead zone cut out
+fn deflection(stick: Vec2, deadzone: f32) -> Vec2 {
+    if stick.length() <= deadzone {
+        Vec2::ZERO
+    } else {
+        stick.clamp_length_max(1.0)
+    }
+}
+
+/// Left stick walks level with the floor; right stick turns
+pub fn xr_thumbstick_locomotion(
+    time: Res<Time>,
+    settings: Res<ComfortSettings>,
+    view: Option<Res<XrViewScale>>,
+    controllers: Query<&XrController>,
+    mut cameras: Query<(&mut Transform, &ViewRig)>,
+    mut state: ResMut<XrLocomotion>,
+    mut turns: EventWriter<SnapTurn>,
+) {
+    let scale = view.map_or(1.0, |v| v.scale);
+    let dt = time.delta_secs();
+    for controller in controllers.iter() {
+        let stick = deflection(controller.thumbstick, settings.thumbstick_deadzone);
+        match controller.hand {
+            XrHand::Left if settings.smooth_locomotion && stick != Vec2::ZERO => {
+                for (mut transform, _) in cameras.iter_mut().filter(|(_, rig)| rig.is_xr()) {
+                    let forward = transform.forward().as_vec3().with_y(0.0).normalize_or_zero();
+                    let right = transform.right().as_vec3().with_y(0.0).normalize_or_zero();
+                    transform.translation += (right * stick.x + forward * stick.y) * settings.locomotion_speed * dt / scale;
+                }
+            }
+            XrHand::Left => {}
+            XrHand::Right if settings.snap_turn => {
+                if stick.x.abs() >= SNAP_TURN_THRESHOLD && !state.turn_held {
+                    turns.write(SnapTurn { clockwise: stick.x > 0.0 });
+                    state.turn_held = true;
+                } else if stick.x.abs() < SNAP_TURN_RELEASE && state.turn_held {
+                    state.turn_held = false;
+                }
+            }
+            XrHand::Right => {
+                let angle = -stick.x * settings.smooth_turn_speed.to_radians() * dt;
+                if angle != 0.0 {
+                    for (mut transform, _) in cameras.iter_mut().filter(|(_, rig)| rig.is_xr()) {
+                        transform.rotate_y(angle);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Right stick forward aims the teleport arc; letting go moves the user to where it lands
+pub fn xr_teleport(
+    settings: Res<ComfortSettings>,
+    view: Option<Res<XrViewScale>>,
+    controllers: Query<(&GlobalTransform, &XrController)>,
+    mut cameras: Query<(&mut Transform, &ViewRig)>,
+    mut state: ResMut<XrLocomotion>,
+    mut locomotion: ResMut<LocomotionState>,
+) {
+    let view = view.as_deref().copied().unwrap_or_default();
+    let aim = controllers.iter().find(|(_, c)| c.hand == XrHand::Right).filter(|(_, c)| c.thumbstick.y >= TELEPORT_AIM_THRESHOLD);
+    if let Some((pose, _)) = aim.filter(|_| settings.teleport) {
+        let (arc, target) = teleport_arc(view.to_physical(pose.translation()), pose.forward().as_vec3());
+        state.arc = arc.into_iter().map(|p| view.to_model(p)).collect();
+        state.target = target;
+        return;
+    }
+    if state.arc.is_empty() {
+        return;
+    }
+    state.arc.clear();
+    let Some(target) = state.target.take() else { return };
+    for (mut transform, _) in cameras.iter_mut().filter(|(_, rig)| rig.is_xr()) {
+        // Keep the head's height over the floor; move it over the landing point
+        let eye = view.to_physical(transform.translation);
+        transform.translation = view.to_model(Vec3::new(target.x, eye.y, target.z));
+    }
+    // A jump, not a walk: no collision or vignette for it
+    locomotion.last_position = None;
+    journal(format!("xr_teleport {:.0} {:.0}", target.x, target.z));
+}
+
+/// Draw the teleport arc while aiming, with a ring where it lands
+pub fn render_teleport_arc(mut gizmos: Gizmos, state: Res<XrLocomotion>, view: Option<Res<XrViewScale>>) {
+    if state.arc.is_empty() {
+        return;
+    }
+    let color = if state.target.is_some() { ARC_COLOR } else { ARC_COLOR.with_alpha(0.3) };
+    gizmos.linestrip(state.arc.iter().copied(), color);
+    if let Some(landing) = state.arc.last().filter(|_| state.target.is_some()) {
+        let radius = LANDING_RADIUS / view.map_or(1.0, |v| v.scale);
+        gizmos.circle(Isometry3d::new(*landing, Quat::from_rotation_x(std::f32::consts::FRAC_PI_2)), radius, color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_arc_lands_on_floor() {
+        // Thrown level from 1.5 m: falls 1.5 m in sqrt(2 * 1500 / 9810) s
+        let (points, landing) = teleport_arc(Vec3::new(0.0, 1500.0, 0.0), Vec3::X);
+        let landing = landing.unwrap();
+        let expected = TELEPORT_SPEED * (2.0 * 1500.0 / GRAVITY).sqrt();
+        assert!((landing.x - expected).abs() < 20.0 && landing.y == 0.0 && landing.z == 0.0, "{landing}");
+        assert_eq!(points.last(), Some(&landing));
+        // Already on the floor: nowhere to land
+        assert_eq!(teleport_arc(Vec3::ZERO, Vec3::X).1, None);
+    }
+
+    #[test]
+    fn test_walk_and_teleport() {
+        let mut app = App::new();
+        // One model unit is half a millimetre
+        app.insert_resource(XrViewScale { scale: 0.5, ..default() })
+            .init_resource::<ComfortSettings>()
+            .init_resource::<LocomotionState>()
+            .init_resource::<XrLocomotion>()
+            .init_resource::<Time>()
+            .add_event::<SnapTurn>()
+            .add_systems(Update, (xr_thumbstick_locomotion, xr_teleport).chain());
+        let camera = app.world_mut().spawn((Transform::from_xyz(0.0, 3000.0, 0.0), ViewRig::HEADSET)).id();
+        let left = app.world_mut().spawn((XrController::new(XrHand::Left), GlobalTransform::default())).id();
+        let right = app.world_mut().spawn((XrController::new(XrHand::Right), GlobalTransform::from_xyz(0.0, 2000.0, 0.0))).id();
+
+        // Half a second at full stick walks 700 mm forward (-Z)
+        app.world_mut().get_mut::<XrController>(left).unwrap().thumbstick = Vec2::Y;
+        app.world_mut().resource_mut::<Time>().advance_by(Duration::from_millis(500));
+        app.update();
+        let at = app.world().get::<Transform>(camera).unwrap().translation;
+        assert!((at - Vec3::new(0.0, 3000.0, -1400.0)).length() < 1e-2, "{at}");
+        app.world_mut().get_mut::<XrController>(left).unwrap().thumbstick = Vec2::ZERO;
+
+        // Aim level from 1 m up, then let go
+        app.world_mut().get_mut::<XrController>(right).unwrap().thumbstick = Vec2::Y;
+        app.update();
+        let target = app.world().resource::<XrLocomotion>().target.unwrap();
+        app.world_mut().get_mut::<XrController>(right).unwrap().thumbstick = Vec2::ZERO;
+        app.update();
+        let at = app.world().get::<Transform>(camera).unwrap().translation;
+        assert!((at - Vec3::new(0.0, 3000.0, target.z * 2.0)).length() < 1e-2, "{at}");
+        assert!((target.z + TELEPORT_SPEED * (2.0 * 1000.0 / GRAVITY).sqrt()).abs() < 20.0);
+        assert!(app.world().resource::<XrLocomotion>().arc.is_empty());
+    }
+}
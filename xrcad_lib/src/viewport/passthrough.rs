@@ -0,0 +1,345 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: viewport::passthrough
+//!
+//! AR mode for headsets with passthrough: XR cameras clear to transparent so
+//! the model and a minimal set of helpers are drawn over the camera feed, and
+//! the model can be anchored to a real surface. Passthrough is supported while
+//! the running `XrSession` offers it, and AR mode ends with the session. In AR
+//! mode a press of a controller's primary button (A or X) places the anchor at
+//! the controller, so the model stands where the hand rests on the table; the
+//! anchor moves the `XrViewScale` anchor, keeping the current scale preset.
+//! While AR mode is on a small scale panel reads out the model's scale and
+//! steps it up and down or onto the tabletop and life-size presets, for
+//! reviewing a model on a real table; in the headset it shows as an XR panel.
+
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+use bevy::render::camera::ClearColorConfig;
+
+use crate::input::keyboard::KeyBindings;
+use crate::interaction::state::UiPanel;
+use crate::interaction::xr_controller::XrController;
+use crate::model::brep_model::BrepModel;
+use crate::render::ui_style::{BUTTON_IDLE, PANEL_COLOR};
+use crate::telemetry::crash::journal;
+use crate::telemetry::usage::UsageStats;
+use crate::viewport::camera::{ViewRig, XrSession};
+use crate::viewport::xr_scale::{model_bounds, ScaleWorld, SetXrScalePreset, XrScalePreset, XrScaleSettings, XrViewScale};
+
+/// Scale change per press of the panel's - and + buttons
+const BUTTON_SCALE_STEP: f32 = 1.25;
+
+/// Passthrough state of the XR session
+#[derive(Resource, Debug, Default, Clone, PartialEq)]
+pub struct PassthroughMode {
+    /// The running XR session offers passthrough, kept by `sync_passthrough_support`
+    pub supported: bool,
+    pub enabled: bool,
+    /// Physical point the model is anchored to
+    pub anchor: Option<Vec3>,
+}
+
+impl PassthroughMode {
+    pub fn is_active(&self) -> bool {
+        self.supported && self.enabled
+    }
+}
+
+/// Request to switch AR mode on or off
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TogglePassthrough;
+
+/// Anchor pose placed by the user on a real surface (physical space)
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct AnchorPlaced {
+    pub pose: Transform,
+}
+
+/// The passthrough binding (A) toggles AR mode
+pub fn passthrough_keys(keys: Res<ButtonInput<KeyCode>>, bindings: Res<KeyBindings>, mut toggles: EventWriter<TogglePassthrough>) {
+    if bindings.just_pressed("passthrough", &keys) {
+        toggles.write(TogglePassthrough);
+    }
+}
+
+/// Follow the XR session: passthrough is supported while a running session
+/// offers it, and AR mode ends when it stops doing so
+pub fn sync_passthrough_support(session: Res<XrSession>, mut mode: ResMut<PassthroughMode>, mut cameras: Query<&mut Camera, With<ViewRig>>) {
+    let supported = session.running && session.passthrough;
+    if mode.supported == supported {
+        return;
+    }
+    journal(format!("passthrough supported {}", supported));
+    if mode.enabled {
+        for mut camera in cameras.iter_mut() {
+            camera.clear_color = ClearColorConfig::Default;
+        }
+    }
+    *mode = PassthroughMode { supported, ..default() };
+}
+
+/// Switch AR mode and make XR cameras clear to transparent while it is on
+pub fn apply_passthrough(
+    mut toggles: EventReader<TogglePassthrough>,
+    mut mode: ResMut<PassthroughMode>,
+    mut cameras: Query<(&mut Camera, &ViewRig)>,
+    mut usage: Option<ResMut<UsageStats>>,
+) {
+    for _ in toggles.read() {
+        if !mode.supported {
+            info!("This headset does not offer passthrough");
+            continue;
+        }
+        let start = Instant::now();
+        mode.enabled = !mode.enabled;
+        journal(format!("passthrough {}", mode.enabled));
+        for (mut camera, rig) in cameras.iter_mut() {
+            if rig.is_xr() {
+                camera.clear_color = if mode.enabled { ClearColorConfig::Custom(Color::NONE) } else { ClearColorConfig::Default };
+            }
+        }
+        if let Some(usage) = usage.as_mut() {
+            usage.record("passthrough", start.elapsed());
+        }
+    }
+}
+
+/// In AR mode a controller's primary button places an anchor at the controller
+pub fn place_anchor(
+    mode: Res<PassthroughMode>,
+    view: Res<XrViewScale>,
+    controllers: Query<(&GlobalTransform, &XrController)>,
+    mut anchors: EventWriter<AnchorPlaced>,
+) {
+    if !mode.is_active() {
+        return;
+    }
+    for (transform, _) in controllers.iter().filter(|(_, c)| c.primary_pressed()) {
+        let translation = view.to_physical(transform.translation());
+        anchors.write(AnchorPlaced { pose: Transform { translation, rotation: transform.rotation(), ..default() } });
+    }
+}
+
+/// Stand the model on placed anchors while in AR mode
+pub fn anchor_model(
+    mut anchors: EventReader<AnchorPlaced>,
+    settings: Res<XrScaleSettings>,
+    model: Option<Res<BrepModel>>,
+    mut mode: ResMut<PassthroughMode>,
+    mut view: ResMut<XrViewScale>,
+    mut cameras: Query<(&mut Transform, &ViewRig)>,
+    mut usage: Option<ResMut<UsageStats>>,
+) {
+    for ev in anchors.read() {
+        if !mode.is_active() {
+            continue;
+        }
+        let start = Instant::now();
+        let anchor = ev.pose.translation;
+        journal(format!("anchor_model {:?}", anchor));
+        let preset = view.preset.unwrap_or(XrScalePreset::LifeSize);
+        let next = XrViewScale { anchor, ..settings.preset_view(preset, model.as_deref().and_then(model_bounds)) };
+        for (mut transform, rig) in cameras.iter_mut() {
+            if rig.is_xr() {
+                *transform = view.retarget(&next, &transform);
+            }
+        }
+        *view = next;
+        mode.anchor = Some(anchor);
+        if let Some(usage) = usage.as_mut() {
+            usage.record("anchor_model", start.elapsed());
+        }
+    }
+}
+
+/// Model scale as a ratio, e.g. "1:3.3" for a model shown at 0.3 of its size
+pub fn scale_label(scale: f32) -> String {
+    if scale >= 1.0 {
+        format!("{:.1}:1", scale)
+    } else {
+        format!("1:{:.1}", 1.0 / scale)
+    }
+}
+
+/// Buttons of the AR scale panel
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArScaleButton {
+    Smaller,
+    Larger,
+    Preset(XrScalePreset),
+}
+
+/// Scale readout of the AR scale panel
+#[derive(Component, Debug)]
+pub struct ArScaleLabel;
+
+/// Spawn the AR scale panel (bottom right, shown in AR mode)
+pub fn spawn_ar_scale_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(8.0),
+                bottom: Val::Px(8.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                padding: UiRect::all(Val::Px(6.0)),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(PANEL_COLOR),
+            UiPanel("ar_scale"),
+        ))
+        .with_children(|panel| {
+            panel.spawn((Text::new(""), ArScaleLabel));
+            panel.spawn(Node { column_gap: Val::Px(4.0), ..default() }).with_children(|row| {
+                let buttons = [
+                    (ArScaleButton::Smaller, "-"),
+                    (ArScaleButton::Larger, "+"),
+                    (ArScaleButton::Preset(XrScalePreset::Tabletop), XrScalePreset::Tabletop.label()),
+                    (ArScaleButton::Preset(XrScalePreset::LifeSize), XrScalePreset::LifeSize.label()),
+                ];
+                for (button, label) in buttons {
+                    row.spawn((Button, Node { padding: UiRect::axes(Val::Px(6.0), Val::Px(2.0)), ..default() }, BackgroundColor(BUTTON_IDLE), button))
+                        .with_child(Text::new(label));
+                }
+            });
+        });
+}
+
+/// Show the scale panel in AR mode, send scale requests for its buttons and keep its readout current
+pub fn ar_scale_panel_system(
+    mode: Res<PassthroughMode>,
+    view: Res<XrViewScale>,
+    pressed: Query<(&Interaction, &ArScaleButton), Changed<Interaction>>,
+    mut panels: Query<(&UiPanel, &mut Node)>,
+    mut labels: Query<&mut Text, With<ArScaleLabel>>,
+    (mut presets, mut scales): (EventWriter<SetXrScalePreset>, EventWriter<ScaleWorld>),
+) {
+    for (_, button) in pressed.iter().filter(|(i, _)| **i == Interaction::Pressed) {
+        match *button {
+            ArScaleButton::Smaller => {
+                scales.write(ScaleWorld { factor: 1.0 / BUTTON_SCALE_STEP });
+            }
+            ArScaleButton::Larger => {
+                scales.write(ScaleWorld { factor: BUTTON_SCALE_STEP });
+            }
+            ArScaleButton::Preset(preset) => {
+                presets.write(SetXrScalePreset(preset));
+            }
+        }
+    }
+    if mode.is_changed() {
+        let display = if mode.is_active() { Display::Flex } else { Display::None };
+        for (_, mut node) in panels.iter_mut().filter(|(p, _)| p.0 == "ar_scale") {
+            node.display = display;
+        }
+    }
+    if mode.is_changed() || view.is_changed() {
+        let preset = view.preset.map_or(String::new(), |p| format!(" ({})", p.label()));
+        for mut text in labels.iter_mut() {
+            text.0 = format!("Scale {}{}", scale_label(view.scale), preset);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::cube;
+
+    #[test]
+    fn test_ar_mode_and_anchoring() {
+        let mut app = App::new();
+        app.insert_resource(cube(100.0))
+            .init_resource::<XrSession>()
+            .init_resource::<PassthroughMode>()
+            .init_resource::<XrScaleSettings>()
+            .init_resource::<XrViewScale>()
+            .add_event::<TogglePassthrough>()
+            .add_event::<AnchorPlaced>()
+            .add_systems(Update, (sync_passthrough_support, apply_passthrough, anchor_model).chain());
+        let camera = app
+            .world_mut()
+            .spawn((Camera::default(), Transform::from_xyz(0.0, 1600.0, 500.0), ViewRig::HEADSET))
+            .id();
+
+        // Without a session offering passthrough AR mode stays off
+        app.world_mut().send_event(TogglePassthrough);
+        app.update();
+        assert!(!app.world().resource::<PassthroughMode>().is_active());
+        *app.world_mut().resource_mut::<XrSession>() = XrSession { running: true, passthrough: true };
+
+        // Anchors are ignored until AR mode is on
+        let pose = Transform::from_xyz(300.0, 720.0, -200.0);
+        app.world_mut().send_event(AnchorPlaced { pose });
+        app.update();
+        assert_eq!(app.world().resource::<PassthroughMode>().anchor, None);
+
+        app.world_mut().send_event(TogglePassthrough);
+        app.world_mut().send_event(AnchorPlaced { pose });
+        app.update();
+        assert!(matches!(app.world().get::<Camera>(camera).unwrap().clear_color, ClearColorConfig::Custom(c) if c == Color::NONE));
+        let view = *app.world().resource::<XrViewScale>();
+        assert_eq!(app.world().resource::<PassthroughMode>().anchor, Some(pose.translation));
+        // Bottom centre of the cube stands on the anchor; the viewer has not moved
+        assert!((view.to_physical(Vec3::new(0.0, -50.0, 0.0)) - pose.translation).length() < 1e-3);
+        let head = view.to_physical(app.world().get::<Transform>(camera).unwrap().translation);
+        assert!((head - Vec3::new(0.0, 1600.0, 500.0)).length() < 1e-3);
+
+        // AR mode ends with the session
+        app.world_mut().resource_mut::<XrSession>().running = false;
+        app.update();
+        assert_eq!(*app.world().resource::<PassthroughMode>(), PassthroughMode::default());
+        assert!(matches!(app.world().get::<Camera>(camera).unwrap().clear_color, ClearColorConfig::Default));
+    }
+
+    #[test]
+    fn test_primary_button_places_anchor() {
+        use crate::interaction::xr_controller::{latch_xr_buttons, XrHand};
+
+        let mut app = App::new();
+        let view = XrViewScale { scale: 0.5, ..default() };
+        app.insert_resource(PassthroughMode { supported: true, enabled: true, anchor: None })
+            .insert_resource(view)
+            .add_event::<AnchorPlaced>()
+            .add_systems(Update, (place_anchor, latch_xr_buttons).chain());
+        let mut controller = XrController::new(XrHand::Right);
+        controller.primary = true;
+        app.world_mut().spawn((controller, GlobalTransform::from_xyz(100.0, 40.0, 0.0)));
+        app.update();
+        app.update();
+        let events = app.world().resource::<Events<AnchorPlaced>>();
+        let placed: Vec<Vec3> = events.get_cursor().read(events).map(|e| e.pose.translation).collect();
+        // One anchor per press, at the controller's physical position
+        assert_eq!(placed, vec![view.to_physical(Vec3::new(100.0, 40.0, 0.0))]);
+    }
+
+    #[test]
+    fn test_ar_scale_panel() {
+        assert_eq!(scale_label(0.3), "1:3.3");
+        assert_eq!(scale_label(2.0), "2.0:1");
+
+        let mut app = App::new();
+        app.insert_resource(PassthroughMode { supported: true, enabled: true, anchor: None })
+            .insert_resource(XrViewScale { scale: 0.3, preset: Some(XrScalePreset::Tabletop), ..default() })
+            .add_event::<SetXrScalePreset>()
+            .add_event::<ScaleWorld>()
+            .add_systems(Startup, spawn_ar_scale_panel)
+            .add_systems(Update, ar_scale_panel_system);
+        app.update();
+        let node = app.world_mut().query_filtered::<&Node, With<UiPanel>>().single(app.world()).unwrap();
+        assert_eq!(node.display, Display::Flex);
+        let text = app.world_mut().query_filtered::<&Text, With<ArScaleLabel>>().single(app.world()).unwrap();
+        assert_eq!(text.0, "Scale 1:3.3 (Tabletop)");
+
+        let larger = app.world_mut().query::<(Entity, &ArScaleButton)>().iter(app.world()).find(|(_, b)| **b == ArScaleButton::Larger).unwrap().0;
+        app.world_mut().entity_mut(larger).insert(Interaction::Pressed);
+        app.update();
+        let events = app.world().resource::<Events<ScaleWorld>>();
+        let factors: Vec<f32> = events.get_cursor().read(events).map(|e| e.factor).collect();
+        assert_eq!(factors, vec![BUTTON_SCALE_STEP]);
+    }
+}
@@ -0,0 +1,380 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: viewport::saved_views
+//!
+//! Named camera views saved with the document. Insert saves the current view
+//! under a generated name (saving under a name already in use replaces that
+//! view), keys 1-9 recall the first nine views and the views panel (F12) lists
+//! them all. Recalling a view glides the camera there and restores its orbit
+//! pivot. The presentation fly-through plays the views in order.
+
+#[cfg(feature = "render")]
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::input::keyboard::KeyBindings;
+use crate::io::project::CameraState;
+#[cfg(feature = "render")]
+use crate::render::ui_style::{BUTTON_IDLE, PANEL_COLOR};
+#[cfg(feature = "render")]
+use crate::{
+    interaction::state::UiPanel,
+    telemetry::crash::journal,
+    telemetry::usage::UsageStats,
+    viewport::camera_control::CustomCameraController,
+    viewport::framing::{CameraFraming, FramingAnimation},
+};
+
+/// Views with a recall_view_N binding, N counting from 1
+const RECALLED_VIEWS: usize = 9;
+
+/// A named camera placement
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedView {
+    pub name: String,
+    pub camera: CameraState,
+    /// Orbit pivot restored with the view
+    pub pivot: [f32; 3],
+}
+
+impl SavedView {
+    pub fn new(name: &str, transform: &Transform, pivot: Vec3) -> Self {
+        Self { name: name.to_string(), camera: CameraState::from_transform(transform), pivot: pivot.to_array() }
+    }
+
+    pub fn transform(&self) -> Transform {
+        self.camera.to_transform()
+    }
+
+    pub fn pivot(&self) -> Vec3 {
+        Vec3::from_array(self.pivot)
+    }
+}
+
+/// Why a saved view could not be changed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SavedViewError {
+    UnknownView(usize),
+    EmptyName,
+    NameTaken(String),
+}
+
+impl fmt::Display for SavedViewError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SavedViewError::UnknownView(index) => write!(f, "no saved view {}", index + 1),
+            SavedViewError::EmptyName => write!(f, "view names cannot be empty"),
+            SavedViewError::NameTaken(name) => write!(f, "a view named '{}' already exists", name),
+        }
+    }
+}
+
+impl std::error::Error for SavedViewError {}
+
+/// The document's saved views, in recall order
+#[derive(Resource, Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedViews {
+    pub views: Vec<SavedView>,
+}
+
+impl SavedViews {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&SavedView> {
+        self.views.get(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.views.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.views.is_empty()
+    }
+
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.views.iter().position(|v| v.name == name)
+    }
+
+    /// First free "View N" name
+    pub fn generate_name(&self) -> String {
+        (1..).map(|n| format!("View {}", n)).find(|name| self.find(name).is_none()).unwrap_or_default()
+    }
+
+    /// Save a view, replacing any view with the same name; returns its index
+    pub fn save(&mut self, view: SavedView) -> Result<usize, SavedViewError> {
+        if view.name.trim().is_empty() {
+            return Err(SavedViewError::EmptyName);
+        }
+        match self.find(&view.name) {
+            Some(index) => {
+                self.views[index] = view;
+                Ok(index)
+            }
+            None => {
+                self.views.push(view);
+                Ok(self.views.len() - 1)
+            }
+        }
+    }
+
+    pub fn rename(&mut self, index: usize, name: &str) -> Result<(), SavedViewError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(SavedViewError::EmptyName);
+        }
+        if self.find(name).is_some_and(|other| other != index) {
+            return Err(SavedViewError::NameTaken(name.to_string()));
+        }
+        let view = self.views.get_mut(index).ok_or(SavedViewError::UnknownView(index))?;
+        view.name = name.to_string();
+        Ok(())
+    }
+
+    pub fn delete(&mut self, index: usize) -> Result<SavedView, SavedViewError> {
+        if index >= self.views.len() {
+            return Err(SavedViewError::UnknownView(index));
+        }
+        Ok(self.views.remove(index))
+    }
+}
+
+/// Request to save the current camera placement, under a generated name when `name` is `None`
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct SaveView {
+    pub name: Option<String>,
+}
+
+/// Request to move the camera to a saved view
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecallView(pub usize);
+
+/// Request to rename a saved view
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct RenameView {
+    pub index: usize,
+    pub name: String,
+}
+
+/// Request to delete a saved view
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeleteView(pub usize);
+
+/// The save_view binding (Insert) saves the current view, recall_view_1-9 (1-9) recall saved views
+pub fn saved_view_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut saves: EventWriter<SaveView>,
+    mut recalls: EventWriter<RecallView>,
+) {
+    if bindings.just_pressed("save_view", &keys) {
+        saves.write(SaveView { name: None });
+    }
+    for index in 0..RECALLED_VIEWS {
+        if bindings.just_pressed(&format!("recall_view_{}", index + 1), &keys) {
+            recalls.write(RecallView(index));
+        }
+    }
+}
+
+/// Save, recall, rename and delete views
+#[cfg(feature = "render")]
+pub fn apply_saved_view_requests(
+    (mut saves, mut recalls, mut renames, mut deletes): (EventReader<SaveView>, EventReader<RecallView>, EventReader<RenameView>, EventReader<DeleteView>),
+    mut cameras: Query<(&Transform, &mut CustomCameraController)>,
+    mut views: ResMut<SavedViews>,
+    mut framing: ResMut<CameraFraming>,
+    mut usage: Option<ResMut<UsageStats>>,
+) {
+    let mut record = |command: &str, start: Instant| {
+        if let Some(usage) = usage.as_mut() {
+            usage.record(command, start.elapsed());
+        }
+    };
+    for SaveView { name } in saves.read() {
+        let start = Instant::now();
+        let Ok((transform, controller)) = cameras.single() else { continue };
+        let name = name.clone().unwrap_or_else(|| views.generate_name());
+        journal(format!("save view {}", name));
+        if let Err(err) = views.save(SavedView::new(&name, transform, controller.pivot)) {
+            warn!("Could not save view: {}", err);
+        }
+        record("save_view", start);
+    }
+    for RenameView { index, name } in renames.read() {
+        let start = Instant::now();
+        journal(format!("rename view {} {}", index + 1, name));
+        if let Err(err) = views.rename(*index, name) {
+            warn!("Could not rename view: {}", err);
+        }
+        record("rename_view", start);
+    }
+    for DeleteView(index) in deletes.read() {
+        let start = Instant::now();
+        journal(format!("delete view {}", index + 1));
+        if let Err(err) = views.delete(*index) {
+            warn!("Could not delete view: {}", err);
+        }
+        record("delete_view", start);
+    }
+    for RecallView(index) in recalls.read() {
+        let start = Instant::now();
+        let Some(view) = views.get(*index) else {
+            info!("No saved view {}", index + 1);
+            continue;
+        };
+        journal(format!("recall view {}", view.name));
+        let Ok((transform, mut controller)) = cameras.single_mut() else { continue };
+        framing.animation = Some(FramingAnimation { from: *transform, to: view.transform(), elapsed: 0.0 });
+        controller.pivot = view.pivot();
+        controller.zoom_pending = 0.0;
+        record("recall_view", start);
+    }
+}
+
+/// Content of the views panel
+#[cfg(feature = "render")]
+#[derive(Component, Debug)]
+pub struct SavedViewList;
+
+/// A row of the views panel, rebuilt when the views change
+#[cfg(feature = "render")]
+#[derive(Component, Debug)]
+pub struct SavedViewRow;
+
+/// What a views panel button does
+#[cfg(feature = "render")]
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SavedViewButton {
+    Save,
+    Recall(usize),
+    Delete(usize),
+}
+
+/// Spawn the views panel (bottom left)
+#[cfg(feature = "render")]
+pub fn spawn_saved_views_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(8.0),
+                bottom: Val::Px(8.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                padding: UiRect::all(Val::Px(6.0)),
+                ..default()
+            },
+            BackgroundColor(PANEL_COLOR),
+            UiPanel("views"),
+        ))
+        .with_children(|panel| {
+            panel.spawn(Text::new("Views (F12 hides)"));
+            panel.spawn((Node { flex_direction: FlexDirection::Column, row_gap: Val::Px(2.0), ..default() }, SavedViewList));
+            panel
+                .spawn((Button, Node { padding: UiRect::axes(Val::Px(4.0), Val::Px(1.0)), ..default() }, BackgroundColor(BUTTON_IDLE), SavedViewButton::Save))
+                .with_child(Text::new("+ Save view (Insert)"));
+        });
+}
+
+/// Send requests for clicked buttons and rebuild the list when the views change
+#[cfg(feature = "render")]
+pub fn saved_views_panel_system(
+    mut commands: Commands,
+    views: Res<SavedViews>,
+    pressed: Query<(&Interaction, &SavedViewButton), Changed<Interaction>>,
+    list: Query<Entity, With<SavedViewList>>,
+    old_rows: Query<Entity, With<SavedViewRow>>,
+    (mut saves, mut recalls, mut deletes): (EventWriter<SaveView>, EventWriter<RecallView>, EventWriter<DeleteView>),
+) {
+    for (interaction, button) in pressed.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match *button {
+            SavedViewButton::Save => {
+                saves.write(SaveView { name: None });
+            }
+            SavedViewButton::Recall(index) => {
+                recalls.write(RecallView(index));
+            }
+            SavedViewButton::Delete(index) => {
+                deletes.write(DeleteView(index));
+            }
+        }
+    }
+    if !views.is_changed() {
+        return;
+    }
+    let Ok(list) = list.single() else { return };
+    for entity in old_rows.iter() {
+        commands.entity(entity).despawn();
+    }
+    commands.entity(list).with_children(|list| {
+        for (index, view) in views.views.iter().enumerate() {
+            let label = if index < RECALLED_VIEWS { format!("{}: {}", index + 1, view.name) } else { view.name.clone() };
+            let button = |action| (Button, Node { padding: UiRect::axes(Val::Px(4.0), Val::Px(1.0)), ..default() }, BackgroundColor(BUTTON_IDLE), action);
+            list.spawn((Node { column_gap: Val::Px(4.0), ..default() }, SavedViewRow)).with_children(|line| {
+                line.spawn(button(SavedViewButton::Recall(index))).with_child(Text::new(label));
+                line.spawn(button(SavedViewButton::Delete(index))).with_child(Text::new("x"));
+            });
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_saved_views_save_rename_delete() {
+        let mut views = SavedViews::new();
+        let transform = Transform::from_xyz(1.0, 2.0, 3.0);
+        assert_eq!(views.save(SavedView::new(&views.generate_name(), &transform, Vec3::ZERO)), Ok(0));
+        assert_eq!(views.save(SavedView::new(&views.generate_name(), &transform, Vec3::ONE)), Ok(1));
+        assert_eq!(views.get(1).unwrap().name, "View 2");
+        // Saving under a used name replaces that view
+        assert_eq!(views.save(SavedView::new("View 1", &Transform::from_xyz(5.0, 0.0, 0.0), Vec3::ZERO)), Ok(0));
+        assert_eq!(views.get(0).unwrap().transform().translation, Vec3::new(5.0, 0.0, 0.0));
+
+        assert_eq!(views.rename(1, "View 1"), Err(SavedViewError::NameTaken("View 1".to_string())));
+        assert_eq!(views.rename(1, " "), Err(SavedViewError::EmptyName));
+        views.rename(1, "Detail").unwrap();
+        assert_eq!(views.find("Detail"), Some(1));
+        assert_eq!(views.delete(0).unwrap().name, "View 1");
+        assert_eq!(views.delete(3), Err(SavedViewError::UnknownView(3)));
+        assert_eq!(views.generate_name(), "View 1");
+    }
+
+    #[cfg(feature = "render")]
+    #[test]
+    fn test_recall_view_moves_camera_and_pivot() {
+        let mut app = App::new();
+        app.init_resource::<SavedViews>()
+            .init_resource::<CameraFraming>()
+            .add_event::<SaveView>()
+            .add_event::<RecallView>()
+            .add_event::<RenameView>()
+            .add_event::<DeleteView>()
+            .add_systems(Update, apply_saved_view_requests);
+        let camera = app.world_mut().spawn((Transform::from_xyz(0.0, 0.0, 10.0), CustomCameraController::default())).id();
+        app.world_mut().send_event(SaveView { name: Some("Front".to_string()) });
+        app.update();
+
+        let mut moved = app.world_mut().get_mut::<Transform>(camera).unwrap();
+        moved.translation = Vec3::new(50.0, 0.0, 0.0);
+        app.world_mut().get_mut::<CustomCameraController>(camera).unwrap().pivot = Vec3::ONE;
+        app.world_mut().send_event(RecallView(0));
+        app.update();
+        let framing = app.world().resource::<CameraFraming>();
+        let animation = framing.animation.as_ref().unwrap();
+        assert_eq!(animation.from.translation, Vec3::new(50.0, 0.0, 0.0));
+        assert_eq!(animation.to.translation, Vec3::new(0.0, 0.0, 10.0));
+        assert_eq!(app.world().get::<CustomCameraController>(camera).unwrap().pivot, Vec3::ZERO);
+    }
+}
@@ -0,0 +1,208 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: viewport::view_cube
+//!
+//! Navigation cube in the bottom-right corner of the viewport. The cube is
+//! drawn with gizmos just in front of the camera, aligned with the model axes,
+//! so it turns with the view. Each face, edge and corner is a click target:
+//! clicking it glides the camera about the pivot to look at the model from
+//! that side (26 views in all). The click is consumed so it neither selects
+//! nor starts an orbit.
+
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::telemetry::crash::journal;
+use crate::telemetry::usage::UsageStats;
+use crate::viewport::camera_control::CustomCameraController;
+use crate::viewport::framing::{CameraFraming, FramingAnimation};
+
+/// Distance in front of the camera the cube is drawn at (model units)
+const CUBE_DEPTH: f32 = 1.0;
+/// Share of a face's width, from its edges, that picks the edge or corner views
+const EDGE_BAND: f32 = 1.0 / 3.0;
+
+/// View cube placement and the region under the cursor
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct ViewCube {
+    pub enabled: bool,
+    /// Screen distance of the cube centre from the bottom-right corner (pixels)
+    pub margin: f32,
+    /// Screen width of the cube (pixels)
+    pub size: f32,
+    /// Face, edge or corner under the cursor, as the outward direction with
+    /// components in -1..=1
+    pub hover: Option<IVec3>,
+}
+
+impl Default for ViewCube {
+    fn default() -> Self {
+        Self { enabled: true, margin: 80.0, size: 60.0, hover: None }
+    }
+}
+
+impl ViewCube {
+    /// Cube centre and half-width in the world for a camera and window size
+    pub fn placement(&self, camera: &Camera, transform: &GlobalTransform, window_size: Vec2) -> Option<(Vec3, f32)> {
+        let screen = window_size - Vec2::splat(self.margin);
+        let forward = transform.forward().as_vec3();
+        let at_depth = |point: Vec2| {
+            let ray = camera.viewport_to_world(transform, point).ok()?;
+            let along = ray.direction.dot(forward);
+            if along <= f32::EPSILON {
+                return None;
+            }
+            let t = (CUBE_DEPTH - (ray.origin - transform.translation()).dot(forward)) / along;
+            Some(ray.origin + ray.direction * t)
+        };
+        let center = at_depth(screen)?;
+        let side = at_depth(screen + Vec2::new(self.size / 2.0, 0.0))?;
+        Some((center, center.distance(side)))
+    }
+}
+
+/// Face, edge or corner of the cube at `center` with half-width `half` that
+/// a ray hits first
+pub fn view_cube_hit(center: Vec3, half: f32, origin: Vec3, dir: Vec3) -> Option<IVec3> {
+    let (lo, hi) = (center - Vec3::splat(half), center + Vec3::splat(half));
+    let (mut near, mut far) = (f32::NEG_INFINITY, f32::INFINITY);
+    for axis in 0..3 {
+        if dir[axis].abs() < f32::EPSILON {
+            if origin[axis] < lo[axis] || origin[axis] > hi[axis] {
+                return None;
+            }
+            continue;
+        }
+        let (a, b) = ((lo[axis] - origin[axis]) / dir[axis], (hi[axis] - origin[axis]) / dir[axis]);
+        near = near.max(a.min(b));
+        far = far.min(a.max(b));
+    }
+    if near > far || far < 0.0 {
+        return None;
+    }
+    let local = (origin + dir * near.max(0.0) - center) / half;
+    let cell = |v: f32| {
+        if v > 1.0 - 2.0 * EDGE_BAND {
+            1
+        } else if v < 2.0 * EDGE_BAND - 1.0 {
+            -1
+        } else {
+            0
+        }
+    };
+    let region = IVec3::new(cell(local.x), cell(local.y), cell(local.z));
+    (region != IVec3::ZERO).then_some(region)
+}
+
+/// Camera direction and screen up for looking at the model from a cube region
+pub fn view_for(region: IVec3) -> (Vec3, Vec3) {
+    let direction = -region.as_vec3().normalize();
+    // Straight down or up keeps -Z or +Z at the top of the screen
+    let up = if region.x == 0 && region.z == 0 { Vec3::Z * direction.y } else { Vec3::Y };
+    (direction, up)
+}
+
+/// Request to look at the model from a view cube region
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnapToView(pub IVec3);
+
+/// Track the region under the cursor and turn clicks on it into view snaps
+pub fn view_cube_input(
+    mut mouse: ResMut<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<CustomCameraController>>,
+    mut cube: ResMut<ViewCube>,
+    mut snaps: EventWriter<SnapToView>,
+) {
+    let hover = if cube.enabled {
+        windows.single().ok().zip(cameras.single().ok()).and_then(|(window, (camera, transform))| {
+            let (center, half) = cube.placement(camera, transform, window.size())?;
+            let ray = camera.viewport_to_world(transform, window.cursor_position()?).ok()?;
+            view_cube_hit(center, half, ray.origin, ray.direction.as_vec3())
+        })
+    } else {
+        None
+    };
+    if cube.hover != hover {
+        cube.hover = hover;
+    }
+    if let (Some(region), true) = (hover, mouse.just_pressed(MouseButton::Left)) {
+        snaps.write(SnapToView(region));
+        // The cube owns this click
+        mouse.reset(MouseButton::Left);
+    }
+}
+
+/// Glide the camera about its pivot to the requested view
+pub fn apply_view_snaps(
+    mut snaps: EventReader<SnapToView>,
+    cameras: Query<(&Transform, &CustomCameraController)>,
+    mut framing: ResMut<CameraFraming>,
+    mut usage: Option<ResMut<UsageStats>>,
+) {
+    for SnapToView(region) in snaps.read() {
+        let start = Instant::now();
+        journal(format!("view cube {} {} {}", region.x, region.y, region.z));
+        let Ok((transform, controller)) = cameras.single() else { continue };
+        let (direction, up) = view_for(*region);
+        let distance = controller.pivot.distance(transform.translation).max(1.0);
+        let to = Transform::from_translation(controller.pivot - direction * distance).looking_to(direction, up);
+        framing.animation = Some(FramingAnimation { from: *transform, to, elapsed: 0.0 });
+        if let Some(usage) = usage.as_mut() {
+            usage.record("view_cube", start.elapsed());
+        }
+    }
+}
+
+/// Draw the cube, its axes and the highlighted region
+pub fn draw_view_cube(
+    mut gizmos: Gizmos,
+    cube: Res<ViewCube>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<CustomCameraController>>,
+) {
+    if !cube.enabled {
+        return;
+    }
+    let (Ok(window), Ok((camera, transform))) = (windows.single(), cameras.single()) else { return };
+    let Some((center, half)) = cube.placement(camera, transform, window.size()) else { return };
+    gizmos.cuboid(Transform::from_translation(center).with_scale(Vec3::splat(2.0 * half)), Color::srgb(0.8, 0.8, 0.8));
+    // Axes from the back corner show which way the model is turned
+    let corner = center - Vec3::splat(half);
+    for (axis, color) in [(Vec3::X, Color::srgb(1.0, 0.2, 0.2)), (Vec3::Y, Color::srgb(0.2, 1.0, 0.2)), (Vec3::Z, Color::srgb(0.2, 0.4, 1.0))] {
+        gizmos.line(corner, corner + axis * 2.6 * half, color);
+    }
+    if let Some(region) = cube.hover {
+        let spot = center + region.as_vec3() * half;
+        gizmos.sphere(Isometry3d::from_translation(spot), half * 0.2, Color::srgb(1.0, 0.8, 0.2));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_view_cube_hit_regions() {
+        let down = Vec3::NEG_Z;
+        // Middle of the front (+Z) face, near its top edge, and at its top-right corner
+        assert_eq!(view_cube_hit(Vec3::ZERO, 1.0, Vec3::new(0.0, 0.0, 5.0), down), Some(IVec3::Z));
+        assert_eq!(view_cube_hit(Vec3::ZERO, 1.0, Vec3::new(0.1, 0.9, 5.0), down), Some(IVec3::new(0, 1, 1)));
+        assert_eq!(view_cube_hit(Vec3::ZERO, 1.0, Vec3::new(0.9, 0.9, 5.0), down), Some(IVec3::new(1, 1, 1)));
+        // Beside the cube, and with the cube behind the ray
+        assert_eq!(view_cube_hit(Vec3::ZERO, 1.0, Vec3::new(1.5, 0.0, 5.0), down), None);
+        assert_eq!(view_cube_hit(Vec3::ZERO, 1.0, Vec3::new(0.0, 0.0, -5.0), down), None);
+    }
+
+    #[test]
+    fn test_view_for_regions() {
+        assert_eq!(view_for(IVec3::Z), (Vec3::NEG_Z, Vec3::Y));
+        assert_eq!(view_for(IVec3::Y), (Vec3::NEG_Y, Vec3::NEG_Z));
+        assert_eq!(view_for(IVec3::NEG_Y), (Vec3::Y, Vec3::Z));
+        let (direction, up) = view_for(IVec3::ONE);
+        assert!((direction - Vec3::new(-1.0, -1.0, -1.0).normalize()).length() < 1e-6);
+        assert_eq!(up, Vec3::Y);
+    }
+}
@@ -0,0 +1,323 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: viewport::xr_panels
+//!
+//! UI panels in XR. Screen-space panels do not show in a headset, so while an
+//! XR camera is active the panels named in `XrPanelSettings` (by default the
+//! outliner, saved views, lighting and AR scale panels) are rendered to
+//! textures by UI cameras of their own and shown on quads in the scene. The
+//! quads either float in a row in front of the user, where they were when XR
+//! started or was last recentered, or ride above a wrist facing the head.
+//! Panel sizes are physical (millimetres), so they keep their size as the
+//! world scales. Leaving XR puts the panels back on the screen. Ctrl+F10
+//! switches between wrist and floating, Ctrl+Shift+F10 recenters floating
+//! panels.
+
+use bevy::prelude::*;
+use bevy::render::camera::{ClearColorConfig, RenderTarget};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages};
+use bevy::ui::UiTargetCamera;
+
+use crate::input::keyboard::KeyBindings;
+use crate::interaction::state::UiPanel;
+use crate::interaction::xr_controller::{XrController, XrHand};
+use crate::telemetry::crash::journal;
+use crate::viewport::camera::ViewRig;
+use crate::viewport::xr_scale::XrViewScale;
+
+/// Space between panels side by side (millimetres)
+const PANEL_GAP: f32 = 20.0;
+/// Height of wrist panels above the controller (millimetres)
+const WRIST_LIFT: f32 = 120.0;
+/// Size of wrist panels relative to floating ones
+const WRIST_SCALE: f32 = 0.5;
+
+/// Where XR panels are shown
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum XrPanelAnchor {
+    /// In front of the user, staying put as they move
+    #[default]
+    Floating,
+    /// Above the wrist of a controller, following it
+    Wrist(XrHand),
+}
+
+/// Which panels are shown in XR and how
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct XrPanelSettings {
+    /// `UiPanel` names, in order from left to right
+    pub panels: Vec<&'static str>,
+    pub anchor: XrPanelAnchor,
+    /// Physical width of a floating panel (millimetres)
+    pub width: f32,
+    /// Size of the panel textures (pixels)
+    pub resolution: UVec2,
+    /// Distance of floating panels in front of the user (millimetres)
+    pub distance: f32,
+}
+
+impl Default for XrPanelSettings {
+    fn default() -> Self {
+        Self {
+            panels: vec!["outliner", "views", "lighting", "ar_scale"],
+            anchor: XrPanelAnchor::Floating,
+            width: 400.0,
+            resolution: UVec2::new(512, 640),
+            distance: 1000.0,
+        }
+    }
+}
+
+impl XrPanelSettings {
+    /// Physical height of a floating panel, keeping the texture's aspect
+    pub fn height(&self) -> f32 {
+        self.width * self.resolution.y as f32 / self.resolution.x.max(1) as f32
+    }
+}
+
+/// Placement of floating panels (physical space), set when they first appear
+#[derive(Resource, Debug, Default, Clone, PartialEq)]
+pub struct XrPanelState {
+    pub floating: Option<Transform>,
+}
+
+/// Request to move the XR panels to a wrist or in front of the user
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetXrPanelAnchor(pub XrPanelAnchor);
+
+/// Request to place floating panels in front of the user again
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecenterXrPanels;
+
+/// Quad showing a UI panel in XR
+#[derive(Component, Debug, Clone)]
+pub struct XrPanel {
+    /// The `UiPanel` entity drawn on this quad
+    pub panel: Entity,
+    /// UI camera rendering the panel to the quad's texture
+    camera: Entity,
+    /// Place in the row of panels
+    slot: usize,
+    /// Layout of the panel on the screen, restored when XR ends
+    screen: Node,
+}
+
+/// The bound chords switch panels between the left wrist and floating
+/// (Ctrl+F10) and recenter them (Ctrl+Shift+F10)
+pub fn xr_panel_keys(
+    (keys, bindings): (Res<ButtonInput<KeyCode>>, Res<KeyBindings>),
+    settings: Res<XrPanelSettings>,
+    mut anchors: EventWriter<SetXrPanelAnchor>,
+    mut recenters: EventWriter<RecenterXrPanels>,
+) {
+    if bindings.just_pressed("recenter_xr_panels", &keys) {
+        recenters.write(RecenterXrPanels);
+    }
+    if bindings.just_pressed("xr_panel_anchor", &keys) {
+        let anchor = if settings.anchor == XrPanelAnchor::Floating { XrPanelAnchor::Wrist(XrHand::Left) } else { XrPanelAnchor::Floating };
+        anchors.write(SetXrPanelAnchor(anchor));
+    }
+}
+
+/// Apply anchor changes and recenter requests
+pub fn apply_xr_panel_requests(
+    mut anchors: EventReader<SetXrPanelAnchor>,
+    mut recenters: EventReader<RecenterXrPanels>,
+    mut settings: ResMut<XrPanelSettings>,
+    mut state: ResMut<XrPanelState>,
+) {
+    for SetXrPanelAnchor(anchor) in anchors.read() {
+        journal(format!("xr_panels anchor {:?}", anchor));
+        settings.anchor = *anchor;
+    }
+    if recenters.read().count() > 0 {
+        journal("xr_panels recenter");
+        state.floating = None;
+    }
+}
+
+/// Move the listed panels onto textured quads when XR starts, and back onto the screen when it ends
+pub fn sync_xr_panels(
+    mut commands: Commands,
+    settings: Res<XrPanelSettings>,
+    cameras: Query<&ViewRig>,
+    mut panels: Query<(Entity, &UiPanel, &mut Node)>,
+    quads: Query<(Entity, &XrPanel)>,
+    (mut images, mut meshes, mut materials): (ResMut<Assets<Image>>, ResMut<Assets<Mesh>>, ResMut<Assets<StandardMaterial>>),
+) {
+    if !cameras.iter().any(|rig| rig.is_xr()) {
+        for (entity, quad) in quads.iter() {
+            if let Ok((_, _, mut node)) = panels.get_mut(quad.panel) {
+                *node = quad.screen.clone();
+            }
+            if let Ok(mut panel) = commands.get_entity(quad.panel) {
+                panel.remove::<UiTargetCamera>();
+            }
+            commands.entity(quad.camera).despawn();
+            commands.entity(entity).despawn();
+        }
+        return;
+    }
+    if !quads.is_empty() {
+        return;
+    }
+    let mut shown: Vec<_> = panels
+        .iter_mut()
+        .filter_map(|(entity, panel, node)| Some((settings.panels.iter().position(|name| *name == panel.0)?, entity, node)))
+        .collect();
+    if shown.is_empty() {
+        return;
+    }
+    shown.sort_by_key(|(order, ..)| *order);
+    let mesh = meshes.add(Rectangle::new(settings.width, settings.height()));
+    let size = Extent3d { width: settings.resolution.x, height: settings.resolution.y, depth_or_array_layers: 1 };
+    for (slot, (_, panel, mut node)) in shown.into_iter().enumerate() {
+        let mut image = Image::new_fill(size, TextureDimension::D2, &[0, 0, 0, 0], TextureFormat::Bgra8UnormSrgb, RenderAssetUsages::default());
+        image.texture_descriptor.usage = TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::RENDER_ATTACHMENT;
+        let image = images.add(image);
+        let camera = commands
+            .spawn((
+                Camera2d,
+                Camera { target: RenderTarget::Image(image.clone().into()), clear_color: ClearColorConfig::Custom(Color::NONE), order: -1, ..default() },
+            ))
+            .id();
+        let screen = node.clone();
+        // Draw the panel from the texture's top left corner
+        node.left = Val::Px(0.0);
+        node.top = Val::Px(0.0);
+        node.right = Val::Auto;
+        node.bottom = Val::Auto;
+        commands.entity(panel).insert(UiTargetCamera(camera));
+        let material = materials.add(StandardMaterial {
+            base_color_texture: Some(image),
+            unlit: true,
+            alpha_mode: AlphaMode::Blend,
+            cull_mode: None,
+            ..default()
+        });
+        commands.spawn((Mesh3d(mesh.clone()), MeshMaterial3d(material), Visibility::Hidden, XrPanel { panel, camera, slot, screen }));
+    }
+    journal(format!("xr_panels {:?}", settings.anchor));
+}
+
+/// Lay the panel quads out at their anchor and show those whose panel is shown
+pub fn place_xr_panels(
+    settings: Res<XrPanelSettings>,
+    mut state: ResMut<XrPanelState>,
+    view: Option<Res<XrViewScale>>,
+    cameras: Query<(&Transform, &ViewRig), Without<XrPanel>>,
+    controllers: Query<(&GlobalTransform, &XrController)>,
+    panels: Query<(&Node, &Visibility), Without<XrPanel>>,
+    mut quads: Query<(&XrPanel, &mut Transform, &mut Visibility)>,
+) {
+    let Some((head, _)) = cameras.iter().find(|(_, rig)| rig.is_xr()) else {
+        if state.floating.is_some() {
+            state.floating = None;
+        }
+        return;
+    };
+    let view = view.as_deref().copied().unwrap_or_default();
+    let eye = view.to_physical(head.translation);
+    // Physical frame the row of panels is centred on, and the panels' size in it
+    let anchor = match settings.anchor {
+        XrPanelAnchor::Floating => {
+            let frame = *state.floating.get_or_insert_with(|| {
+                let ahead = head.forward().as_vec3().with_y(0.0).normalize_or(Vec3::NEG_Z);
+                Transform::from_translation(eye + ahead * settings.distance).looking_to(ahead, Vec3::Y)
+            });
+            Some((frame, 1.0))
+        }
+        XrPanelAnchor::Wrist(hand) => controllers.iter().find(|(_, c)| c.hand == hand).map(|(wrist, _)| {
+            let at = view.to_physical(wrist.translation()) + Vec3::Y * WRIST_LIFT;
+            (Transform::from_translation(at).looking_to(at - eye, Vec3::Y), WRIST_SCALE)
+        }),
+    };
+    let count = quads.iter().count() as f32;
+    for (quad, mut transform, mut visibility) in quads.iter_mut() {
+        let shown = anchor.is_some() && panels.get(quad.panel).is_ok_and(|(node, v)| node.display != Display::None && *v != Visibility::Hidden);
+        let wanted = if shown { Visibility::Inherited } else { Visibility::Hidden };
+        if *visibility != wanted {
+            *visibility = wanted;
+        }
+        let Some((frame, size)) = anchor else { continue };
+        // The quad faces the user along +Z; the row runs along the frame's X axis
+        let offset = (quad.slot as f32 - (count - 1.0) * 0.5) * (settings.width + PANEL_GAP) * size;
+        let at = frame.translation + frame.rotation * Vec3::X * offset;
+        *transform = Transform { translation: view.to_model(at), rotation: frame.rotation, scale: Vec3::splat(size / view.scale) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn panel_app() -> App {
+        let mut app = App::new();
+        app.init_resource::<XrPanelSettings>()
+            .init_resource::<XrPanelState>()
+            .init_resource::<Assets<Image>>()
+            .init_resource::<Assets<Mesh>>()
+            .init_resource::<Assets<StandardMaterial>>()
+            .add_systems(Update, (sync_xr_panels, place_xr_panels).chain());
+        app
+    }
+
+    #[test]
+    fn test_panels_move_to_quads_in_xr_and_back() {
+        let mut app = panel_app();
+        let screen = Node { position_type: PositionType::Absolute, right: Val::Px(8.0), top: Val::Px(40.0), ..default() };
+        let lighting = app.world_mut().spawn((screen.clone(), UiPanel("lighting"))).id();
+        let jobs = app.world_mut().spawn((Node::default(), UiPanel("jobs"))).id();
+        let camera = app.world_mut().spawn((Transform::default(), ViewRig::Desktop)).id();
+        app.update();
+        assert!(app.world_mut().query::<&XrPanel>().iter(app.world()).next().is_none());
+
+        *app.world_mut().get_mut::<ViewRig>(camera).unwrap() = ViewRig::HEADSET;
+        app.update();
+        let quads: Vec<XrPanel> = app.world_mut().query::<&XrPanel>().iter(app.world()).cloned().collect();
+        assert_eq!(quads.len(), 1);
+        assert_eq!(quads[0].panel, lighting);
+        assert!(app.world().get::<UiTargetCamera>(lighting).is_some() && app.world().get::<UiTargetCamera>(jobs).is_none());
+        assert_eq!(app.world().get::<Node>(lighting).unwrap().left, Val::Px(0.0));
+
+        *app.world_mut().get_mut::<ViewRig>(camera).unwrap() = ViewRig::Desktop;
+        app.update();
+        assert!(app.world_mut().query::<&XrPanel>().iter(app.world()).next().is_none());
+        assert!(app.world().get::<UiTargetCamera>(lighting).is_none());
+        assert_eq!(*app.world().get::<Node>(lighting).unwrap(), screen);
+    }
+
+    #[test]
+    fn test_floating_and_wrist_placement() {
+        let mut app = panel_app();
+        // One model unit is a tenth of a millimetre
+        app.insert_resource(XrViewScale { scale: 0.1, ..default() });
+        for name in ["outliner", "views"] {
+            app.world_mut().spawn((Node::default(), UiPanel(name)));
+        }
+        let head = Transform::from_xyz(0.0, 0.0, 0.0).looking_to(Vec3::new(1.0, -0.5, 0.0), Vec3::Y);
+        app.world_mut().spawn((head, ViewRig::HEADSET));
+        app.update();
+        app.update();
+        let mut placed: Vec<(usize, Transform, Visibility)> =
+            app.world_mut().query::<(&XrPanel, &Transform, &Visibility)>().iter(app.world()).map(|(p, t, v)| (p.slot, *t, *v)).collect();
+        placed.sort_by_key(|(slot, ..)| *slot);
+        // A metre ahead at eye level, ignoring the downward look, side by side across the view
+        let gap = (400.0 + PANEL_GAP) * 0.5 * 10.0;
+        assert!((placed[0].1.translation - Vec3::new(10000.0, 0.0, -gap)).length() < 1e-2, "{}", placed[0].1.translation);
+        assert!((placed[1].1.translation - Vec3::new(10000.0, 0.0, gap)).length() < 1e-2);
+        assert!((placed[0].1.scale - Vec3::splat(10.0)).length() < 1e-4);
+        assert!(placed.iter().all(|(_, _, v)| *v == Visibility::Inherited));
+
+        // On a wrist without a controller the panels are hidden
+        app.world_mut().resource_mut::<XrPanelSettings>().anchor = XrPanelAnchor::Wrist(XrHand::Left);
+        app.update();
+        assert!(app.world_mut().query::<(&XrPanel, &Visibility)>().iter(app.world()).all(|(_, v)| *v == Visibility::Hidden));
+        app.world_mut().spawn((XrController::new(XrHand::Left), GlobalTransform::from_xyz(3000.0, -2000.0, 0.0)));
+        app.update();
+        let centre = app.world_mut().query::<(&XrPanel, &Transform)>().iter(app.world()).map(|(_, t)| t.translation).sum::<Vec3>() / 2.0;
+        assert!((centre - Vec3::new(3000.0, -2000.0 + WRIST_LIFT * 10.0, 0.0)).length() < 1e-2, "{centre}");
+    }
+}
@@ -0,0 +1,281 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: viewport::xr_scale
+//!
+//! Viewing scale of the model in XR. `XrViewScale` maps model space onto the
+//! physical room: the model point `pivot` sits at the physical point `anchor`,
+//! scaled by `scale`. The view is realised by moving and scaling the XR camera,
+//! so the model itself never changes.
+//!
+//! Presets: life size on a desk, scaled down to fit a tabletop, or scaled to a
+//! room-sized footprint on the floor to walk around. Scaling by world grab is
+//! clamped to the range spanned by the presets (and snaps onto them) when
+//! `XrScaleSettings::clamp_to_presets` is set.
+
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+
+use crate::input::keyboard::KeyBindings;
+use crate::model::brep_model::{na_vec3_to_bevy, BrepModel};
+use crate::telemetry::crash::journal;
+use crate::telemetry::usage::UsageStats;
+use crate::viewport::camera::ViewRig;
+
+/// Smallest and largest scale reachable without preset clamping
+const SCALE_LIMITS: (f32, f32) = (1e-4, 1e4);
+/// A clamped grab scale within this ratio of a preset snaps onto it
+const SNAP_RATIO: f32 = 1.1;
+/// Scale change per key press
+const KEY_SCALE_STEP: f32 = 1.25;
+
+/// Quick XR viewing scales
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XrScalePreset {
+    /// 1:1, standing on the desk
+    LifeSize,
+    /// Scaled so the footprint fits the tabletop
+    Tabletop,
+    /// Scaled so the footprint fills the room, standing on the floor
+    RoomScale,
+}
+
+impl XrScalePreset {
+    pub const ALL: [XrScalePreset; 3] = [XrScalePreset::LifeSize, XrScalePreset::Tabletop, XrScalePreset::RoomScale];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            XrScalePreset::LifeSize => "1:1 on desk",
+            XrScalePreset::Tabletop => "Tabletop",
+            XrScalePreset::RoomScale => "Room scale",
+        }
+    }
+}
+
+/// Physical layout the presets are fitted to (millimetres)
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct XrScaleSettings {
+    /// Height of the desk anchor above the floor
+    pub desk_height: f32,
+    /// Footprint a tabletop model is fitted into
+    pub tabletop_size: f32,
+    /// Footprint a room-scale model is fitted into
+    pub room_size: f32,
+    /// Keep world-grab scaling within the presets
+    pub clamp_to_presets: bool,
+}
+
+impl Default for XrScaleSettings {
+    fn default() -> Self {
+        Self { desk_height: 750.0, tabletop_size: 600.0, room_size: 4000.0, clamp_to_presets: true }
+    }
+}
+
+/// Model bounds in viewport coordinates
+pub fn model_bounds(model: &BrepModel) -> Option<(Vec3, Vec3)> {
+    model.bounds().map(|(lo, hi)| (na_vec3_to_bevy(&lo), na_vec3_to_bevy(&hi)))
+}
+
+impl XrScaleSettings {
+    /// View for a preset; the model's footprint centre rests on the anchor
+    pub fn preset_view(&self, preset: XrScalePreset, bounds: Option<(Vec3, Vec3)>) -> XrViewScale {
+        let (lo, hi) = bounds.unwrap_or((Vec3::ZERO, Vec3::ZERO));
+        let footprint = (hi.x - lo.x).max(hi.z - lo.z);
+        let fit = |size: f32| if footprint > f32::EPSILON { size / footprint } else { 1.0 };
+        let desk = Vec3::new(0.0, self.desk_height, 0.0);
+        let (scale, anchor) = match preset {
+            XrScalePreset::LifeSize => (1.0, desk),
+            XrScalePreset::Tabletop => (fit(self.tabletop_size), desk),
+            XrScalePreset::RoomScale => (fit(self.room_size), Vec3::ZERO),
+        };
+        let pivot = Vec3::new((lo.x + hi.x) * 0.5, lo.y, (lo.z + hi.z) * 0.5);
+        XrViewScale { scale, pivot, anchor, preset: Some(preset) }
+    }
+
+    /// Scale a world grab may reach, and the preset it snapped onto
+    pub fn clamp_grab_scale(&self, bounds: Option<(Vec3, Vec3)>, requested: f32) -> (f32, Option<XrScalePreset>) {
+        let requested = requested.clamp(SCALE_LIMITS.0, SCALE_LIMITS.1);
+        if !self.clamp_to_presets {
+            return (requested, None);
+        }
+        let presets: Vec<(XrScalePreset, f32)> =
+            XrScalePreset::ALL.iter().map(|p| (*p, self.preset_view(*p, bounds).scale)).collect();
+        let min = presets.iter().map(|(_, s)| *s).fold(f32::INFINITY, f32::min);
+        let max = presets.iter().map(|(_, s)| *s).fold(f32::NEG_INFINITY, f32::max);
+        let scale = requested.clamp(min, max);
+        presets
+            .into_iter()
+            .find(|(_, s)| (scale / s).max(s / scale) < SNAP_RATIO)
+            .map_or((scale, None), |(p, s)| (s, Some(p)))
+    }
+}
+
+/// Mapping from model space to the physical XR space
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct XrViewScale {
+    /// Physical size of one model unit
+    pub scale: f32,
+    /// Model point placed at the anchor
+    pub pivot: Vec3,
+    /// Physical point the pivot is placed at
+    pub anchor: Vec3,
+    /// Preset this view matches, if any
+    pub preset: Option<XrScalePreset>,
+}
+
+impl Default for XrViewScale {
+    fn default() -> Self {
+        Self { scale: 1.0, pivot: Vec3::ZERO, anchor: Vec3::ZERO, preset: None }
+    }
+}
+
+impl XrViewScale {
+    pub fn to_physical(&self, p: Vec3) -> Vec3 {
+        self.anchor + (p - self.pivot) * self.scale
+    }
+
+    pub fn to_model(&self, p: Vec3) -> Vec3 {
+        self.pivot + (p - self.anchor) / self.scale
+    }
+
+    /// Camera transform under `next` that leaves the viewer where they physically are
+    pub fn retarget(&self, next: &XrViewScale, camera: &Transform) -> Transform {
+        let physical = self.to_physical(camera.translation);
+        Transform { translation: next.to_model(physical), rotation: camera.rotation, scale: Vec3::splat(1.0 / next.scale) }
+    }
+}
+
+/// Request to switch the XR view to a preset
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetXrScalePreset(pub XrScalePreset);
+
+/// Request to scale the XR world by a factor (world grab); above 1 enlarges the model
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct ScaleWorld {
+    pub factor: f32,
+}
+
+/// The xr_scale_* bindings (F5 / F6 / F7) pick a preset; scale_world_up / down (= and -) scale the world
+pub fn xr_scale_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut presets: EventWriter<SetXrScalePreset>,
+    mut scales: EventWriter<ScaleWorld>,
+) {
+    for (action, preset) in [
+        ("xr_scale_life_size", XrScalePreset::LifeSize),
+        ("xr_scale_tabletop", XrScalePreset::Tabletop),
+        ("xr_scale_room", XrScalePreset::RoomScale),
+    ] {
+        if bindings.just_pressed(action, &keys) {
+            presets.write(SetXrScalePreset(preset));
+        }
+    }
+    if bindings.just_pressed("scale_world_up", &keys) {
+        scales.write(ScaleWorld { factor: KEY_SCALE_STEP });
+    }
+    if bindings.just_pressed("scale_world_down", &keys) {
+        scales.write(ScaleWorld { factor: 1.0 / KEY_SCALE_STEP });
+    }
+}
+
+/// Change the XR viewing scale and move XR cameras to match
+pub fn apply_xr_scale(
+    mut presets: EventReader<SetXrScalePreset>,
+    mut scales: EventReader<ScaleWorld>,
+    settings: Res<XrScaleSettings>,
+    model: Option<Res<BrepModel>>,
+    mut view: ResMut<XrViewScale>,
+    mut cameras: Query<(&mut Transform, &ViewRig)>,
+    mut usage: Option<ResMut<UsageStats>>,
+) {
+    let bounds = model.as_deref().and_then(model_bounds);
+    let mut requests: Vec<(&'static str, XrViewScale)> = presets
+        .read()
+        .map(|SetXrScalePreset(preset)| ("xr_scale_preset", settings.preset_view(*preset, bounds)))
+        .collect();
+    for ev in scales.read() {
+        let current = requests.last().map_or(*view, |(_, v)| *v);
+        let (scale, preset) = settings.clamp_grab_scale(bounds, current.scale * ev.factor);
+        // Snapping onto a preset takes its placement too
+        let next = match preset {
+            Some(preset) => settings.preset_view(preset, bounds),
+            None => XrViewScale { scale, preset: None, ..current },
+        };
+        requests.push(("xr_scale_world", next));
+    }
+    if requests.is_empty() {
+        return;
+    }
+    if !cameras.iter().any(|(_, rig)| rig.is_xr()) {
+        info!("XR scale presets apply in XR mode only");
+        return;
+    }
+    for (command, next) in requests {
+        let start = Instant::now();
+        journal(format!("{} {:?} scale {}", command, next.preset, next.scale));
+        for (mut transform, rig) in cameras.iter_mut() {
+            if rig.is_xr() {
+                *transform = view.retarget(&next, &transform);
+            }
+        }
+        *view = next;
+        if let Some(usage) = usage.as_mut() {
+            usage.record(command, start.elapsed());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::brep::primitives::cube;
+
+    #[test]
+    fn test_presets_and_clamping() {
+        let settings = XrScaleSettings::default();
+        let bounds = model_bounds(&cube(2000.0));
+        let life = settings.preset_view(XrScalePreset::LifeSize, bounds);
+        assert_eq!(life.scale, 1.0);
+        // Bottom centre of the cube sits on the desk
+        assert!((life.to_physical(Vec3::new(0.0, -1000.0, 0.0)) - Vec3::new(0.0, 750.0, 0.0)).length() < 1e-3);
+        assert!((settings.preset_view(XrScalePreset::Tabletop, bounds).scale - 0.3).abs() < 1e-6);
+        let room = settings.preset_view(XrScalePreset::RoomScale, bounds);
+        assert!((room.scale - 2.0).abs() < 1e-6 && room.anchor == Vec3::ZERO);
+
+        assert_eq!(settings.clamp_grab_scale(bounds, 0.01), (0.3, Some(XrScalePreset::Tabletop)));
+        assert_eq!(settings.clamp_grab_scale(bounds, 1.05), (1.0, Some(XrScalePreset::LifeSize)));
+        assert_eq!(settings.clamp_grab_scale(bounds, 0.6), (0.6, None));
+        let free = XrScaleSettings { clamp_to_presets: false, ..settings };
+        assert_eq!(free.clamp_grab_scale(bounds, 0.01), (0.01, None));
+    }
+
+    #[test]
+    fn test_preset_keeps_viewer_in_place() {
+        let mut app = App::new();
+        app.insert_resource(cube(2000.0))
+            .init_resource::<XrScaleSettings>()
+            .init_resource::<XrViewScale>()
+            .add_event::<SetXrScalePreset>()
+            .add_event::<ScaleWorld>()
+            .add_systems(Update, apply_xr_scale);
+        let head = Vec3::new(100.0, 1600.0, 900.0);
+        let camera = app
+            .world_mut()
+            .spawn((Transform::from_translation(head), ViewRig::HEADSET))
+            .id();
+        app.world_mut().send_event(SetXrScalePreset(XrScalePreset::Tabletop));
+        app.update();
+
+        let view = *app.world().resource::<XrViewScale>();
+        assert_eq!(view.preset, Some(XrScalePreset::Tabletop));
+        let transform = *app.world().get::<Transform>(camera).unwrap();
+        assert!((view.to_physical(transform.translation) - head).length() < 1e-2);
+        assert!((transform.scale.x - 1.0 / 0.3).abs() < 1e-4);
+
+        app.world_mut().send_event(ScaleWorld { factor: 0.5 });
+        app.update();
+        // Already at the smallest preset: clamped, so nothing moves
+        assert_eq!(*app.world().resource::<XrViewScale>(), view);
+    }
+}
@@ -4,18 +4,17 @@
 //! Module: workspace::helpers::axes
 
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 use crate::color::{RED, GREEN, BLUE};
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Axes;
 
 impl Axes {
-    pub fn render(&self, gizmos: &mut Gizmos) {
-        let origin = Vec3::ZERO;
-        let length = 100.0;
-        gizmos.line(origin, origin + Vec3::X * length, RED);
-        gizmos.line(origin, origin + Vec3::Y * length, GREEN);
-        gizmos.line(origin, origin + Vec3::Z * length, BLUE);
+    /// Unit axis directions from the origin with their colors; `render::lines`
+    /// scales them to a constant on-screen length
+    pub fn directions(&self) -> [(Vec3, Color); 3] {
+        [(Vec3::X, RED), (Vec3::Y, GREEN), (Vec3::Z, BLUE)]
     }
 }
 
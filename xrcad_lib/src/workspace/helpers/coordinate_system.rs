@@ -2,9 +2,10 @@
 
 use nalgebra::Point3;
 use nalgebra::Vector3;
+use serde::{Deserialize, Serialize};
 
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CoordinateSystem {
     pub origin: Point3<f64>,
     pub x_axis: Vector3<f64>,
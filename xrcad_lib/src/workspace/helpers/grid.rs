@@ -2,9 +2,74 @@
 // Copyright (c) 2025 Adrian Scarlett
 
 //! Module: workspace::helpers::grid
+//!
+//! The workspace grid: an infinite grid on one of the principal planes whose
+//! spacing follows the camera's distance from it, stepping through 1, 10 and
+//! 100 mm (and on in powers of ten). Drawn by `render::grid`.
 
-#[derive(Debug, Default, Clone)]
-pub struct Grid;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::color::{BLUE, GREEN, RED};
+
+/// Smallest grid spacing (mm)
+pub const MIN_GRID_SPACING: f32 = 1.0;
+/// Camera distance per minor grid line spacing at which a level is fully shown
+const DISTANCE_PER_SPACING: f32 = 100.0;
+
+/// Principal plane a grid lies in. The default is the ground plane, Y being up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum GridPlane {
+    XY,
+    YZ,
+    #[default]
+    ZX,
+}
+
+impl GridPlane {
+    /// In-plane axes with their colors; the first axis line runs along the first axis
+    pub fn axes(&self) -> [(Vec3, Color); 2] {
+        match self {
+            GridPlane::XY => [(Vec3::X, RED), (Vec3::Y, GREEN)],
+            GridPlane::YZ => [(Vec3::Y, GREEN), (Vec3::Z, BLUE)],
+            GridPlane::ZX => [(Vec3::Z, BLUE), (Vec3::X, RED)],
+        }
+    }
+
+    pub fn normal(&self) -> Vec3 {
+        let [(u, _), (v, _)] = self.axes();
+        u.cross(v)
+    }
+}
+
+/// Infinite grid settings. Projects saved before the grid had settings store
+/// it as `()`, which reads as the defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Grid {
+    pub plane: GridPlane,
+    /// Distance at which the grid has faded out, in multiples of the camera's height above it
+    pub fade: f32,
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Self { plane: GridPlane::default(), fade: 40.0 }
+    }
+}
+
+impl Grid {
+    /// Minor line spacing for a camera `distance` from the grid, and how far
+    /// those lines have faded towards the next level (0 to 1). Major lines are
+    /// ten times the minor spacing.
+    pub fn level(distance: f32) -> (f32, f32) {
+        let exponent = (distance.max(f32::EPSILON) / DISTANCE_PER_SPACING).log10();
+        if exponent <= MIN_GRID_SPACING.log10() {
+            return (MIN_GRID_SPACING, 0.0);
+        }
+        (10f32.powf(exponent.floor()), exponent.fract())
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -12,6 +77,26 @@ mod tests {
     #[test]
     fn test_grid_default() {
         let grid = Grid::default();
-        let _ = grid;
-    }    
-}    
+        assert_eq!(grid.plane.normal(), Vec3::Y);
+    }
+
+    #[test]
+    fn test_grid_level_steps_by_tens() {
+        assert_eq!(Grid::level(10.0), (1.0, 0.0));
+        assert_eq!(Grid::level(100.0).0, 1.0);
+        let (spacing, blend) = Grid::level(500.0);
+        assert_eq!(spacing, 1.0);
+        assert!((blend - 5f32.log10()).abs() < 1e-5);
+        assert_eq!(Grid::level(1000.0).0, 10.0);
+        assert_eq!(Grid::level(25_000.0).0, 100.0);
+        assert_eq!(GridPlane::ZX.normal(), Vec3::Y);
+    }
+
+    #[test]
+    fn test_grid_reads_settingless_grids() {
+        let old: Grid = ron::from_str("()").unwrap();
+        assert_eq!(old, Grid::default());
+        let grid = Grid { plane: GridPlane::YZ, fade: 10.0 };
+        assert_eq!(ron::from_str::<Grid>(&ron::to_string(&grid).unwrap()).unwrap(), grid);
+    }
+}
@@ -3,7 +3,9 @@
 
 //! Module: workspace::helpers::origin
 
-#[derive(Debug, Default, Clone)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Origin;
 
 #[cfg(test)]
@@ -0,0 +1,260 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025 Adrian Scarlett
+
+//! Module: workspace::workbenches
+//!
+//! Named modeling contexts ("Part", "Assembly", "Sketch"), each with its own
+//! helpers and its own set of hidden panels. The active workbench's helpers are
+//! the `Workspace` resource and its panels are the `UiLayout`'s. Switching
+//! stores both back into the workbench being left and loads the other one's.
+//! The workbench bar lists them all, and the workbenches are saved with the
+//! document.
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::interaction::state::UiLayout;
+#[cfg(feature = "render")]
+use crate::interaction::state::UiPanel;
+use crate::model::brep::topology::plane::{Plane, PlaneRenderMode};
+#[cfg(feature = "render")]
+use crate::render::ui_style::{BUTTON_ACTIVE, BUTTON_IDLE, PANEL_COLOR};
+use crate::telemetry::crash::journal;
+use crate::telemetry::usage::UsageStats;
+use crate::workspace::helpers::axes::Axes;
+use crate::workspace::helpers::grid::Grid;
+use crate::workspace::workspace::{HelperKind, Workspace};
+
+/// Panel of the workbench bar, which no workbench hides
+const BAR_PANEL: &str = "workbenches";
+
+/// A named modeling context
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Workbench {
+    pub name: String,
+    /// Helpers shown while the workbench is active
+    pub workspace: Workspace,
+    /// Panels hidden while the workbench is active
+    pub hidden_panels: BTreeSet<String>,
+}
+
+impl Workbench {
+    pub fn new(name: impl Into<String>, workspace: Workspace, hidden_panels: &[&str]) -> Self {
+        Self { name: name.into(), workspace, hidden_panels: hidden_panels.iter().map(|p| p.to_string()).collect() }
+    }
+}
+
+/// Why a workbench request failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorkbenchError {
+    UnknownWorkbench(String),
+    NameTaken(String),
+}
+
+impl fmt::Display for WorkbenchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorkbenchError::UnknownWorkbench(name) => write!(f, "no workbench named {}", name),
+            WorkbenchError::NameTaken(name) => write!(f, "a workbench named {} already exists", name),
+        }
+    }
+}
+
+impl std::error::Error for WorkbenchError {}
+
+/// All workbenches of the document. The entry of the active one is only
+/// brought up to date when switching away from it or saving.
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+pub struct Workbenches {
+    pub benches: Vec<Workbench>,
+    pub active: usize,
+}
+
+impl Default for Workbenches {
+    fn default() -> Self {
+        let mut assembly = Workspace::new();
+        assembly.add_helper("axes", HelperKind::Axes(Axes));
+        assembly.add_helper("grid", HelperKind::Grid(Grid::default()));
+        let mut sketch = Workspace::new();
+        sketch.add_helper("axes", HelperKind::Axes(Axes));
+        let mut top = Plane::xy();
+        top.set_render_mode(PlaneRenderMode::Grid);
+        sketch.add_helper("top", HelperKind::Plane(top));
+        Self {
+            benches: vec![
+                Workbench::new("Part", Workspace::default(), &["analysis"]),
+                Workbench::new("Assembly", assembly, &["analysis", "helpers", "measurements"]),
+                Workbench::new("Sketch", sketch, &["analysis", "lighting", "views"]),
+            ],
+            active: 0,
+        }
+    }
+}
+
+impl Workbenches {
+    pub fn active(&self) -> Option<&Workbench> {
+        self.benches.get(self.active)
+    }
+
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.benches.iter().position(|b| b.name == name)
+    }
+
+    /// Add a workbench; it is not made active
+    pub fn add(&mut self, bench: Workbench) -> Result<usize, WorkbenchError> {
+        if self.index_of(&bench.name).is_some() {
+            return Err(WorkbenchError::NameTaken(bench.name));
+        }
+        self.benches.push(bench);
+        Ok(self.benches.len() - 1)
+    }
+
+    /// Store the live helpers and panels into the active workbench
+    pub fn store_active(&mut self, workspace: &Workspace, layout: &UiLayout) {
+        if let Some(bench) = self.benches.get_mut(self.active) {
+            bench.workspace = workspace.clone();
+            bench.hidden_panels = layout.hidden_panels.iter().filter(|p| *p != BAR_PANEL).cloned().collect();
+        }
+    }
+
+    /// Make a workbench active: store the live state into the current one and
+    /// load the named one's helpers and panels in its place
+    pub fn switch(&mut self, name: &str, workspace: &mut Workspace, layout: &mut UiLayout) -> Result<(), WorkbenchError> {
+        let index = self.index_of(name).ok_or_else(|| WorkbenchError::UnknownWorkbench(name.to_string()))?;
+        self.store_active(workspace, layout);
+        self.active = index;
+        let bench = &self.benches[index];
+        *workspace = bench.workspace.clone();
+        layout.hidden_panels = bench.hidden_panels.clone();
+        Ok(())
+    }
+}
+
+/// Request to make a workbench active, by name
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct SwitchWorkbench(pub String);
+
+/// Switch workbenches on request
+pub fn apply_workbench_requests(
+    mut requests: EventReader<SwitchWorkbench>,
+    mut workbenches: ResMut<Workbenches>,
+    mut workspace: ResMut<Workspace>,
+    mut layout: ResMut<UiLayout>,
+    mut usage: Option<ResMut<UsageStats>>,
+) {
+    for SwitchWorkbench(name) in requests.read() {
+        let start = Instant::now();
+        journal(format!("switch_workbench {}", name));
+        if let Err(err) = workbenches.switch(name, &mut workspace, &mut layout) {
+            warn!("Could not switch workbench: {}", err);
+        }
+        if let Some(usage) = usage.as_mut() {
+            usage.record("switch_workbench", start.elapsed());
+        }
+    }
+}
+
+/// Container of the workbench buttons
+#[cfg(feature = "render")]
+#[derive(Component, Debug)]
+pub struct WorkbenchList;
+
+/// Button switching to a workbench, by index
+#[cfg(feature = "render")]
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorkbenchButton(pub usize);
+
+/// Spawn the workbench bar (top center)
+#[cfg(feature = "render")]
+pub fn spawn_workbench_bar(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Percent(40.0),
+            top: Val::Px(8.0),
+            column_gap: Val::Px(4.0),
+            padding: UiRect::all(Val::Px(4.0)),
+            ..default()
+        },
+        BackgroundColor(PANEL_COLOR),
+        UiPanel(BAR_PANEL),
+        WorkbenchList,
+    ));
+}
+
+/// Send switch requests for clicked buttons and rebuild the bar when the workbenches change
+#[cfg(feature = "render")]
+pub fn workbench_bar_system(
+    mut commands: Commands,
+    workbenches: Res<Workbenches>,
+    pressed: Query<(&Interaction, &WorkbenchButton), Changed<Interaction>>,
+    list: Query<Entity, With<WorkbenchList>>,
+    old_buttons: Query<Entity, With<WorkbenchButton>>,
+    mut requests: EventWriter<SwitchWorkbench>,
+) {
+    for (interaction, WorkbenchButton(index)) in pressed.iter() {
+        if *interaction == Interaction::Pressed {
+            if let Some(bench) = workbenches.benches.get(*index) {
+                requests.write(SwitchWorkbench(bench.name.clone()));
+            }
+        }
+    }
+    if !workbenches.is_changed() {
+        return;
+    }
+    let Ok(list) = list.single() else { return };
+    for entity in old_buttons.iter() {
+        commands.entity(entity).despawn();
+    }
+    commands.entity(list).with_children(|list| {
+        for (index, bench) in workbenches.benches.iter().enumerate() {
+            let color = if index == workbenches.active { BUTTON_ACTIVE } else { BUTTON_IDLE };
+            list.spawn((Button, Node { padding: UiRect::axes(Val::Px(6.0), Val::Px(2.0)), ..default() }, BackgroundColor(color), WorkbenchButton(index)))
+                .with_child(Text::new(bench.name.clone()));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_switching_keeps_each_workbench_state() {
+        let mut app = App::new();
+        app.init_resource::<Workbenches>()
+            .insert_resource(Workspace::default())
+            .init_resource::<UiLayout>()
+            .add_event::<SwitchWorkbench>()
+            .add_systems(Update, apply_workbench_requests);
+        // Edit the Part workbench, then leave it
+        app.world_mut().resource_mut::<Workspace>().add_helper("Plane.001", HelperKind::Plane(Plane::yz()));
+        app.world_mut().resource_mut::<UiLayout>().toggle_panel("lighting");
+        app.world_mut().send_event(SwitchWorkbench("Assembly".into()));
+        app.update();
+        assert_eq!(app.world().resource::<Workspace>().helpers.len(), 2);
+        assert!(!app.world().resource::<UiLayout>().is_panel_visible("helpers"));
+        assert!(app.world().resource::<UiLayout>().is_panel_visible("lighting"));
+
+        app.world_mut().send_event(SwitchWorkbench("Part".into()));
+        app.update();
+        assert!(app.world().resource::<Workspace>().get_helper("Plane.001").is_some());
+        assert!(!app.world().resource::<UiLayout>().is_panel_visible("lighting"));
+
+        app.world_mut().send_event(SwitchWorkbench("Drawing".into()));
+        app.update();
+        assert_eq!(app.world().resource::<Workbenches>().active().unwrap().name, "Part");
+    }
+
+    #[test]
+    fn test_workbench_names_are_unique() {
+        let mut workbenches = Workbenches::default();
+        let taken = workbenches.add(Workbench::new("Sketch", Workspace::new(), &[]));
+        assert_eq!(taken, Err(WorkbenchError::NameTaken("Sketch".into())));
+        assert_eq!(workbenches.add(Workbench::new("Render", Workspace::new(), &["helpers"])), Ok(3));
+    }
+}
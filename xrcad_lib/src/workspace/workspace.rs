@@ -2,12 +2,15 @@
 // Copyright (c) 2025 Adrian Scarlett
 
 //! Module: workspace
+//!
+//! Construction helpers shown in the viewport (axes, planes, grid, markers).
+//! They are drawn as retained line meshes by `render::lines`.
 
      
 
 use bevy::ecs::resource::Resource;
-use bevy::ecs::system::Res;
-use bevy::gizmos::gizmos::Gizmos;
+use serde::{Deserialize, Serialize};
+use std::fmt;
 use super::helpers::axes::Axes;
 use super::helpers::coordinate_system::CoordinateSystem;
 use super::helpers::grid::Grid;
@@ -16,7 +19,7 @@ use super::helpers::origin::Origin;
 use crate::model::brep::topology::plane::Plane;
 
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum HelperKind {
     Axes(Axes),
     CoordinateSystem(CoordinateSystem),
@@ -26,13 +29,55 @@ pub enum HelperKind {
     Plane(Plane),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkspaceHelper {
     pub id: String,
     pub kind: HelperKind,
+    #[serde(default = "default_visible")]
+    pub visible: bool,
+    /// sRGB color drawn instead of the helper's own colors
+    #[serde(default)]
+    pub color: Option<[f32; 3]>,
+    /// Drawn size, as a multiple of the helper's own size
+    #[serde(default = "default_size")]
+    pub size: f32,
 }
 
-#[derive(Resource)]
+fn default_visible() -> bool {
+    true
+}
+
+fn default_size() -> f32 {
+    1.0
+}
+
+impl WorkspaceHelper {
+    /// Shown in the viewport; planes can also be hidden through their own flag
+    pub fn is_visible(&self) -> bool {
+        match &self.kind {
+            HelperKind::Plane(plane) => self.visible && plane.visible,
+            _ => self.visible,
+        }
+    }
+}
+
+/// Why a workspace edit failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorkspaceError {
+    UnknownHelper(String),
+}
+
+impl fmt::Display for WorkspaceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorkspaceError::UnknownHelper(id) => write!(f, "no workspace helper with id {}", id),
+        }
+    }
+}
+
+impl std::error::Error for WorkspaceError {}
+
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
 pub struct Workspace {
     pub helpers: Vec<WorkspaceHelper>,
 }
@@ -61,21 +106,53 @@ impl Workspace {
         self.helpers.push(WorkspaceHelper {
             id: id.into(),
             kind,
+            visible: true,
+            color: None,
+            size: 1.0,
         });
     }
 
-    pub fn workspace_render_system(
-        mut gizmos: Gizmos,
-        workspace: Res<Workspace>,
-    ) {
-        for helper in &workspace.helpers {
-            match &helper.kind {
-                HelperKind::Axes(axes) => axes.render(&mut gizmos),
-                HelperKind::Plane(plane) => plane.render(&mut gizmos),
-                _ => {}
-            }
+    pub fn get_helper(&self, id: &str) -> Option<&WorkspaceHelper> {
+        self.helpers.iter().find(|h| h.id == id)
+    }
+
+    pub fn get_helper_mut(&mut self, id: &str) -> Option<&mut WorkspaceHelper> {
+        self.helpers.iter_mut().find(|h| h.id == id)
+    }
+
+    /// Remove a helper by id, returning it
+    pub fn remove_helper(&mut self, id: &str) -> Result<WorkspaceHelper, WorkspaceError> {
+        let index = self.helpers.iter().position(|h| h.id == id).ok_or_else(|| WorkspaceError::UnknownHelper(id.to_string()))?;
+        Ok(self.helpers.remove(index))
+    }
+
+    /// Show or hide a helper by id
+    pub fn set_helper_visible(&mut self, id: &str, visible: bool) -> Result<(), WorkspaceError> {
+        let helper = self.get_helper_mut(id).ok_or_else(|| WorkspaceError::UnknownHelper(id.to_string()))?;
+        helper.visible = visible;
+        // Plane placement and snapping read the plane's own flag
+        if let HelperKind::Plane(plane) = &mut helper.kind {
+            plane.visible = visible;
         }
+        Ok(())
     }
+
+    /// First unused id of the form `{prefix}.001`, `{prefix}.002`, ...
+    pub fn next_helper_id(&self, prefix: &str) -> String {
+        (1..)
+            .map(|n| format!("{}.{:03}", prefix, n))
+            .find(|id| self.helpers.iter().all(|h| &h.id != id))
+            .unwrap_or_default()
+    }
+
+    /// Helper plane by id
+    pub fn plane(&self, id: &str) -> Option<&Plane> {
+        self.helpers.iter().find_map(|h| match &h.kind {
+            HelperKind::Plane(plane) if h.id == id => Some(plane),
+            _ => None,
+        })
+    }
+
     /// Set the render mode of a helper plane by id
     pub fn set_plane_render_mode(&mut self, id: &str, mode: crate::model::brep::topology::plane::PlaneRenderMode) {
         for helper in &mut self.helpers {
@@ -86,6 +163,17 @@ impl Workspace {
             }
         }
     }
+
+    /// Set how large a helper plane is drawn, by id
+    pub fn set_plane_extent(&mut self, id: &str, extent: crate::model::brep::topology::plane::PlaneExtent) {
+        for helper in &mut self.helpers {
+            if helper.id == id {
+                if let HelperKind::Plane(plane) = &mut helper.kind {
+                    plane.set_extent(extent);
+                }
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -96,4 +184,33 @@ mod tests {
         let w = Workspace::new();
         let _ = w;
     }
+
+    #[test]
+    fn test_next_helper_id_skips_used_ids() {
+        let mut ws = Workspace::new();
+        assert_eq!(ws.next_helper_id("Plane"), "Plane.001");
+        ws.add_helper("Plane.001", HelperKind::Plane(Plane::xy()));
+        ws.add_helper("Plane.003", HelperKind::Plane(Plane::yz()));
+        assert_eq!(ws.next_helper_id("Plane"), "Plane.002");
+        assert!(ws.plane("Plane.003").is_some() && ws.plane("Plane.002").is_none());
+    }
+
+    #[test]
+    fn test_helper_visibility_and_removal() {
+        let mut ws = Workspace::default();
+        ws.set_helper_visible("top", false).unwrap();
+        let top = ws.get_helper("top").unwrap();
+        assert!(!top.is_visible());
+        assert!(matches!(&top.kind, HelperKind::Plane(plane) if !plane.visible));
+        ws.get_helper_mut("axes").unwrap().size = 2.0;
+        assert_eq!(ws.remove_helper("axes").unwrap().size, 2.0);
+        assert_eq!(ws.remove_helper("axes").unwrap_err(), WorkspaceError::UnknownHelper("axes".into()));
+        assert_eq!(ws.set_helper_visible("axes", true), Err(WorkspaceError::UnknownHelper("axes".into())));
+    }
+
+    #[test]
+    fn test_helpers_saved_without_display_settings_read_as_shown() {
+        let helper: WorkspaceHelper = ron::from_str("(id: \"grid\", kind: Grid(()))").unwrap();
+        assert!(helper.visible && helper.color.is_none() && helper.size == 1.0);
+    }
 }